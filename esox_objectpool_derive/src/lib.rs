@@ -0,0 +1,168 @@
+//! Derive macros for [`esox_objectpool`](https://docs.rs/esox_objectpool)
+//!
+//! Use them through the `derive` feature of `esox_objectpool`, which
+//! re-exports them next to the traits they implement.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Expr, Index, Member, Path};
+
+/// Derive `Recycle` for a struct from `#[pool(...)]` field attributes
+///
+/// Field attributes say how `recycle` resets each field; fields without
+/// one are left as they are:
+///
+/// - `#[pool(clear)]`: recycle the field itself, keeping its allocation
+///   (the field's type implements `Recycle`, like `Vec`, `String` or
+///   another derived struct). `cap_capacity` is passed on to these fields.
+/// - `#[pool(reset)]`: set the field to `Default::default()`
+/// - `#[pool(reset = expr)]`: set the field to `expr`
+///
+/// A struct attribute `#[pool(validate = path)]` implements `is_reusable`
+/// by calling `path(&self)`; pass `MyType::is_reusable` to
+/// `PoolConfiguration::with_validation` to destroy returned objects that
+/// fail it.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{DynamicObjectPool, PoolConfiguration, Recycle};
+///
+/// #[derive(Default, Recycle)]
+/// #[pool(validate = Request::is_intact)]
+/// struct Request {
+///     #[pool(clear)]
+///     headers: Vec<(String, String)>,
+///     #[pool(clear)]
+///     body: String,
+///     #[pool(reset)]
+///     retries: u32,
+///     #[pool(reset = 200)]
+///     status: u16,
+///     corrupted: bool,
+/// }
+///
+/// impl Request {
+///     fn is_intact(&self) -> bool {
+///         !self.corrupted
+///     }
+/// }
+///
+/// let pool = DynamicObjectPool::new(
+///     Request::default,
+///     PoolConfiguration::new()
+///         .with_recycling(4096)
+///         .with_validation(Request::is_reusable),
+/// );
+///
+/// let mut request = pool.get_object().unwrap();
+/// request.body.push_str("payload");
+/// request.retries = 3;
+/// request.status = 500;
+/// drop(request);
+///
+/// let request = pool.get_object().unwrap();
+/// assert!(request.body.is_empty());
+/// assert_eq!((request.retries, request.status), (0, 200));
+/// ```
+#[proc_macro_derive(Recycle, attributes(pool))]
+pub fn derive_recycle(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_recycle(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// What `recycle` does with one field.
+enum Reset {
+    Clear,
+    Default,
+    To(Expr),
+}
+
+fn expand_recycle(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new(input.ident.span(), "Recycle can only be derived for structs"));
+    };
+
+    let mut validate: Option<Path> = None;
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("pool")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("validate") {
+                validate = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `validate = path`"))
+            }
+        })?;
+    }
+
+    let mut resets = Vec::new();
+    let mut clears = Vec::new();
+    for (index, field) in data.fields.iter().enumerate() {
+        let member = match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(index)),
+        };
+        let mut reset = None;
+        for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("pool")) {
+            attr.parse_nested_meta(|meta| {
+                if reset.is_some() {
+                    return Err(meta.error("only one of `clear` and `reset` per field"));
+                }
+                if meta.path.is_ident("clear") {
+                    reset = Some(Reset::Clear);
+                } else if meta.path.is_ident("reset") {
+                    reset = Some(match meta.value() {
+                        Ok(value) => Reset::To(value.parse()?),
+                        Err(_) => Reset::Default,
+                    });
+                } else {
+                    return Err(meta.error("expected `clear`, `reset` or `reset = expr`"));
+                }
+                Ok(())
+            })?;
+        }
+        match reset {
+            Some(Reset::Clear) => {
+                resets.push(quote! { ::esox_objectpool::Recycle::recycle(&mut self.#member); });
+                clears.push(member);
+            }
+            Some(Reset::Default) => {
+                resets.push(quote! { self.#member = ::core::default::Default::default(); });
+            }
+            Some(Reset::To(expr)) => {
+                resets.push(quote! { self.#member = #expr; });
+            }
+            None => {}
+        }
+    }
+
+    let max_capacity = if clears.is_empty() {
+        format_ident!("_max_capacity")
+    } else {
+        format_ident!("max_capacity")
+    };
+    let is_reusable = validate.map(|path| {
+        quote! {
+            fn is_reusable(&self) -> bool {
+                #path(self)
+            }
+        }
+    });
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::esox_objectpool::Recycle for #name #ty_generics #where_clause {
+            fn recycle(&mut self) {
+                #(#resets)*
+            }
+
+            fn cap_capacity(&mut self, #max_capacity: usize) {
+                #(::esox_objectpool::Recycle::cap_capacity(&mut self.#clears, #max_capacity);)*
+            }
+
+            #is_reusable
+        }
+    })
+}
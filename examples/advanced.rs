@@ -55,7 +55,7 @@ fn queryable_pool() {
     // Find specific connection
     {
         let conn = pool.get_object(|c| c.id == 2).unwrap();
-        println!("   Found: {:?}", *conn);
+        println!("   Found: {:?} ({})", *conn, conn.data);
     }
     
     // Try to find non-existent
@@ -42,8 +42,8 @@ fn configured_pool() {
     let pool = ObjectPool::new(vec![1, 2, 3, 4, 5], config);
     
     {
-        let obj1 = pool.get_object().unwrap();
-        let obj2 = pool.get_object().unwrap();
+        let _obj1 = pool.get_object().unwrap();
+        let _obj2 = pool.get_object().unwrap();
         println!("   Active objects: {}", pool.active_count());
         println!("   Available objects: {}", pool.available_count());
     }
@@ -0,0 +1,26 @@
+//! Compare pool configurations against a naive Mutex<Vec<T>> pool
+//!
+//! Run with `cargo run --release --example compare --features bench-internals`.
+
+use esox_objectpool::bench::{compare, Workload};
+use esox_objectpool::PoolConfiguration;
+use std::time::Duration;
+
+fn main() {
+    println!("=== EsoxSolutions.ObjectPool - Pool vs Mutex<Vec> ===\n");
+
+    let threads = std::thread::available_parallelism().map_or(4, |n| n.get());
+    let buffer = || Vec::<u8>::with_capacity(4096);
+
+    for workload in [
+        Workload::new(threads, threads * 2),
+        Workload::new(threads, threads / 2 + 1).with_hold(200),
+    ] {
+        println!("Default configuration:");
+        println!("{}", compare(buffer, PoolConfiguration::default(), &workload));
+
+        println!("With a circuit breaker (no fast path):");
+        let config = PoolConfiguration::new().with_circuit_breaker(5, Duration::from_secs(60));
+        println!("{}", compare(buffer, config, &workload));
+    }
+}
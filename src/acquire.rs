@@ -0,0 +1,155 @@
+//! Named future returned by async acquisition
+
+use crate::errors::PoolResult;
+use crate::pool::PooledObject;
+
+use std::future::Future;
+use std::panic::Location;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+type Inner<'a, T> = Pin<Box<dyn Future<Output = PoolResult<PooledObject<T>>> + Send + 'a>>;
+
+/// Future of a pooled object, returned by `get_object_async` and the other
+/// async acquisition methods
+///
+/// Unlike the anonymous future of an `async fn`, an `Acquire` can be named:
+/// stored in a struct field, kept in a hand-written state machine, or
+/// polled from a `poll_*` method such as
+/// `tower::Service::poll_ready`. It is
+/// `Unpin` and `Send`, so it can be polled through `Pin::new(&mut acquire)`
+/// without boxing or pinning it again.
+///
+/// Cancel safe, like every async acquisition: dropping it before it
+/// completes never takes an object from the pool.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{Acquire, ObjectPool, PoolConfiguration, PoolResult};
+/// use std::future::Future;
+/// use std::pin::Pin;
+/// use std::task::{Context, Poll};
+///
+/// struct Checkout<'p> {
+///     acquire: Acquire<'p, u32>,
+/// }
+///
+/// impl Checkout<'_> {
+///     fn poll_value(&mut self, cx: &mut Context<'_>) -> Poll<PoolResult<u32>> {
+///         Pin::new(&mut self.acquire).poll(cx).map(|obj| obj.map(|obj| *obj))
+///     }
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let pool = ObjectPool::new(vec![7], PoolConfiguration::default());
+/// let mut checkout = Checkout { acquire: pool.get_object_async() };
+///
+/// let value = std::future::poll_fn(|cx| checkout.poll_value(cx)).await;
+/// assert_eq!(value.unwrap(), 7);
+/// # }
+/// ```
+#[must_use = "futures do nothing unless polled"]
+pub struct Acquire<'a, T> {
+    inner: Inner<'a, T>,
+    /// Recorded as the acquired object's acquisition site.
+    location: &'static Location<'static>,
+}
+
+impl<'a, T> Acquire<'a, T> {
+    #[track_caller]
+    pub(crate) fn new<F>(future: F) -> Self
+    where
+        F: Future<Output = PoolResult<PooledObject<T>>> + Send + 'a,
+    {
+        Self {
+            inner: Box::pin(future),
+            location: Location::caller(),
+        }
+    }
+
+    /// Where the acquisition was started
+    #[must_use]
+    pub fn location(&self) -> &'static Location<'static> {
+        self.location
+    }
+}
+
+impl<T> Future for Acquire<'_, T> {
+    type Output = PoolResult<PooledObject<T>>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let location = self.location;
+        self.inner.as_mut().poll(cx).map(|result| result.map(|obj| obj.located(location)))
+    }
+}
+
+impl<T> std::fmt::Debug for Acquire<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Acquire")
+            .field("location", &self.location)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Acquire, DynamicObjectPool, ObjectPool, PoolConfiguration, WaitPolicy};
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll, Waker};
+    use std::time::Duration;
+
+    fn assert_send_unpin<F: Send + Unpin>(_: &F) {}
+
+    #[test]
+    fn manual_polling_waits_then_completes() {
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new().with_wait_on_empty(WaitPolicy::Wait(Duration::from_secs(5))),
+        );
+        let held = pool.get_object().unwrap();
+
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        runtime.block_on(async {
+            let mut acquire = pool.get_object_async();
+            assert_send_unpin(&acquire);
+            let mut cx = Context::from_waker(Waker::noop());
+            assert!(Pin::new(&mut acquire).poll(&mut cx).is_pending());
+            assert_eq!(pool.waiting_count(), 1);
+
+            drop(held);
+            let obj = acquire.await.unwrap();
+            assert_eq!(*obj, 1);
+        });
+    }
+
+    #[tokio::test]
+    async fn stored_futures_record_where_they_started() {
+        struct Pending<'p> {
+            acquire: Acquire<'p, u32>,
+        }
+
+        let pool = DynamicObjectPool::new(|| 3, PoolConfiguration::default());
+        let (pending, line) = (Pending { acquire: pool.get_object_async() }, line!());
+        assert_eq!(pending.acquire.location().line(), line);
+        assert!(format!("{:?}", pending.acquire).starts_with("Acquire"));
+
+        let obj = pending.acquire.await.unwrap();
+        assert_eq!(obj.acquired_at().unwrap().line(), line);
+        assert_eq!(pool.active_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn dropping_before_completion_takes_nothing() {
+        let pool = ObjectPool::new(vec![1], PoolConfiguration::default());
+        let held = pool.get_object().unwrap();
+        let mut acquire = pool.get_object_async();
+        let mut cx = Context::from_waker(Waker::noop());
+        assert!(matches!(Pin::new(&mut acquire).poll(&mut cx), Poll::Pending));
+        drop(acquire);
+        drop(held);
+        assert_eq!((pool.available_count(), pool.waiting_count()), (1, 0));
+    }
+}
@@ -0,0 +1,139 @@
+//! Ring buffer of a pool's recent activity, for post-mortem debugging
+
+use crate::errors::PoolError;
+use crate::ids::PoolObjectId;
+
+use std::collections::VecDeque;
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, SystemTime};
+
+/// What happened in an [`ActivityRecord`]
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum ActivityOutcome {
+    /// An object was handed out
+    Acquired,
+
+    /// An acquisition gave up with this error
+    AcquireFailed(PoolError),
+
+    /// A checked-out object came back and was kept
+    Returned,
+
+    /// A checked-out object came back and was destroyed: discarded,
+    /// retired, invalid, or returned to a closed or full pool
+    Discarded,
+
+    /// An idle object expired and was destroyed
+    Evicted,
+}
+
+/// One entry of a pool's recent activity
+///
+/// Returned by [`ObjectPool::recent_activity`](crate::ObjectPool::recent_activity)
+/// when enabled with
+/// [`with_activity_log`](crate::PoolConfiguration::with_activity_log).
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{AcquireContext, ActivityOutcome, ObjectPool, PoolConfiguration};
+///
+/// let pool = ObjectPool::new(vec![1], PoolConfiguration::new().with_activity_log(16));
+///
+/// let obj = pool.get_object_with(AcquireContext::new().with_label("import")).unwrap();
+/// assert!(pool.try_get_object().unwrap().is_none());
+/// drop(obj);
+///
+/// let activity = pool.recent_activity();
+/// assert_eq!(activity.len(), 2);
+/// assert!(matches!(activity[0].outcome, ActivityOutcome::Acquired));
+/// assert_eq!(activity[0].label.as_deref(), Some("import"));
+/// assert!(matches!(activity[1].outcome, ActivityOutcome::Returned));
+/// assert_eq!(activity[1].object_id, activity[0].object_id);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ActivityRecord {
+    /// When it happened
+    pub at: SystemTime,
+
+    /// What happened
+    pub outcome: ActivityOutcome,
+
+    /// Id of the object involved; `None` for failed acquisitions
+    pub object_id: Option<PoolObjectId>,
+
+    /// Label of the [`AcquireContext`](crate::AcquireContext) an object was
+    /// acquired with, if any; only known for successful acquisitions
+    pub label: Option<String>,
+
+    /// How long the acquisition waited; `None` for other records
+    pub wait: Option<Duration>,
+}
+
+impl ActivityRecord {
+    pub(crate) fn new(outcome: ActivityOutcome, object_id: Option<usize>) -> Self {
+        Self {
+            at: SystemTime::now(),
+            outcome,
+            object_id: object_id.map(PoolObjectId::new),
+            label: None,
+            wait: None,
+        }
+    }
+}
+
+/// The last `capacity` records of one pool, oldest first.
+pub(crate) struct ActivityLog {
+    records: Mutex<VecDeque<ActivityRecord>>,
+    capacity: usize,
+}
+
+impl ActivityLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            records: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+        }
+    }
+
+    /// Append `record`, dropping the oldest once full.
+    pub fn record(&self, record: ActivityRecord) {
+        if self.capacity == 0 {
+            return;
+        }
+        let mut records = self.records.lock().unwrap_or_else(PoisonError::into_inner);
+        if records.len() == self.capacity {
+            records.pop_front();
+        }
+        records.push_back(record);
+    }
+
+    pub fn snapshot(&self) -> Vec<ActivityRecord> {
+        self.records
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_most_recent_records() {
+        let log = ActivityLog::new(2);
+        for id in 0..3 {
+            log.record(ActivityRecord::new(ActivityOutcome::Evicted, Some(id)));
+        }
+        let ids: Vec<_> = log.snapshot().iter().map(|r| r.object_id.unwrap().raw()).collect();
+        assert_eq!(ids, [1, 2]);
+
+        let empty = ActivityLog::new(0);
+        empty.record(ActivityRecord::new(ActivityOutcome::Returned, Some(0)));
+        assert!(empty.snapshot().is_empty());
+    }
+}
@@ -0,0 +1,137 @@
+//! Grouped handling of pooled objects
+
+use crate::pool::{ObjectPool, PooledObject};
+
+use std::ops::{Deref, DerefMut};
+
+/// A group of pooled objects that is returned to the pool as one unit
+///
+/// Created by [`ObjectPool::get_batch`] or
+/// [`DynamicObjectPool::get_batch`](crate::DynamicObjectPool::get_batch).
+/// Dereferences to a slice of [`PooledObject`]s. On drop, every object still
+/// in the batch is returned through [`ObjectPool::return_many`], so the pool's
+/// bookkeeping is updated once for the whole group.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{ObjectPool, PoolConfiguration};
+///
+/// let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+///
+/// let mut batch = pool.get_batch(3).unwrap();
+/// for obj in batch.iter_mut() {
+///     **obj *= 10;
+/// }
+/// let sum: i32 = batch.iter().map(|obj| **obj).sum();
+/// assert_eq!(sum, 60);
+///
+/// drop(batch);
+/// assert_eq!(pool.available_count(), 3);
+/// ```
+pub struct PooledBatch<'a, T: Send + Sync + 'static> {
+    pool: &'a ObjectPool<T>,
+    objects: Vec<PooledObject<T>>,
+}
+
+impl<'a, T: Send + Sync + 'static> PooledBatch<'a, T> {
+    pub(crate) fn new(pool: &'a ObjectPool<T>, objects: Vec<PooledObject<T>>) -> Self {
+        Self { pool, objects }
+    }
+
+    /// Remove the last object from the batch so it can be handled on its own.
+    pub fn pop(&mut self) -> Option<PooledObject<T>> {
+        self.objects.pop()
+    }
+
+    /// Break the batch up into individually managed guards.
+    ///
+    /// Each guard is returned to the pool on its own drop, as usual.
+    pub fn into_vec(mut self) -> Vec<PooledObject<T>> {
+        std::mem::take(&mut self.objects)
+    }
+
+    /// Return every object in the batch now. Returns the number of objects
+    /// put back into the available queue.
+    pub fn return_all(mut self) -> usize {
+        let objects = std::mem::take(&mut self.objects);
+        self.pool.return_many(objects)
+    }
+}
+
+impl<T: Send + Sync + 'static> Deref for PooledBatch<'_, T> {
+    type Target = [PooledObject<T>];
+
+    fn deref(&self) -> &Self::Target {
+        &self.objects
+    }
+}
+
+impl<T: Send + Sync + 'static> DerefMut for PooledBatch<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.objects
+    }
+}
+
+impl<T: Send + Sync + 'static> Drop for PooledBatch<'_, T> {
+    fn drop(&mut self) {
+        if !self.objects.is_empty() {
+            let objects = std::mem::take(&mut self.objects);
+            self.pool.return_many(objects);
+        }
+    }
+}
+
+impl<T: Send + Sync + std::fmt::Debug + 'static> std::fmt::Debug for PooledBatch<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PooledBatch")
+            .field("objects", &self.objects)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ObjectPool, PoolConfiguration};
+
+    #[test]
+    fn drop_returns_whole_batch() {
+        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+        {
+            let batch = pool.get_batch(3).unwrap();
+            assert_eq!(pool.active_count(), 3);
+            assert_eq!(batch.len(), 3);
+        }
+        assert_eq!(pool.active_count(), 0);
+        assert_eq!(pool.available_count(), 3);
+        assert_eq!(pool.get_metrics().total_returned, 3);
+    }
+
+    #[test]
+    fn popped_guard_is_managed_independently() {
+        let pool = ObjectPool::new(vec![1, 2], PoolConfiguration::default());
+        let mut batch = pool.get_batch(2).unwrap();
+
+        let single = batch.pop().unwrap();
+        drop(batch);
+        assert_eq!(pool.active_count(), 1);
+
+        drop(single);
+        assert_eq!(pool.active_count(), 0);
+        assert_eq!(pool.available_count(), 2);
+    }
+
+    #[test]
+    fn into_vec_and_return_all() {
+        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+
+        let guards = pool.get_batch(2).unwrap().into_vec();
+        assert_eq!(pool.active_count(), 2);
+        drop(guards);
+        assert_eq!(pool.active_count(), 0);
+
+        let batch = pool.get_batch(3).unwrap();
+        assert_eq!(batch.return_all(), 3);
+        assert_eq!(pool.available_count(), 3);
+    }
+}
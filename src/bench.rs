@@ -0,0 +1,335 @@
+//! Benchmark harness comparing the pool with a naive `Mutex<Vec<T>>` pool
+//!
+//! Enabled with the `bench-internals` feature. [`compare`] runs the same
+//! [`Workload`] — threads repeatedly taking an object, using it for a
+//! while and giving it back — against an [`ObjectPool`] built from the
+//! given configuration and against a baseline that keeps idle objects in a
+//! `Mutex<Vec<T>>`. Run it once per candidate configuration, for example
+//! with and without [partitions](PoolConfiguration::with_partitions) or an
+//! eviction policy, to see what each costs under your thread count and
+//! hold times.
+//!
+//! Numbers depend on the machine and on what else it is doing; build in
+//! release mode and compare runs made back to back.
+//!
+//! # Examples
+//!
+//! ```
+//! use esox_objectpool::bench::{compare, Contender, Workload};
+//! use esox_objectpool::PoolConfiguration;
+//!
+//! let workload = Workload::new(4, 8).with_operations(1_000).with_hold(50);
+//! let comparison = compare(|| vec![0u8; 256], PoolConfiguration::default(), &workload);
+//!
+//! let pool = comparison.get(Contender::Pool).unwrap();
+//! assert_eq!(pool.operations, 4_000);
+//! assert!(comparison.get(Contender::MutexVec).is_some());
+//! println!("{comparison}");
+//! ```
+
+use crate::config::PoolConfiguration;
+use crate::pool::ObjectPool;
+
+use std::fmt;
+use std::hint::black_box;
+use std::sync::{Mutex, PoisonError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// What each benchmark thread does
+///
+/// `threads` threads each perform `operations` acquire, use, release
+/// cycles against a pool of `objects` objects. Using an object spins
+/// `hold` times over it; with more threads than objects, acquisitions also
+/// miss and retry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Workload {
+    /// Concurrent threads
+    pub threads: usize,
+
+    /// Objects in the pool
+    pub objects: usize,
+
+    /// Acquire, use, release cycles per thread
+    pub operations: usize,
+
+    /// Spin iterations an object is held for
+    pub hold: u32,
+}
+
+impl Workload {
+    /// `threads` threads sharing `objects` objects, each doing 10 000
+    /// cycles without holding the objects
+    ///
+    /// # Panics
+    ///
+    /// Panics if `threads` or `objects` is zero.
+    pub fn new(threads: usize, objects: usize) -> Self {
+        assert!(threads > 0 && objects > 0, "a workload needs threads and objects");
+        Self {
+            threads,
+            objects,
+            operations: 10_000,
+            hold: 0,
+        }
+    }
+
+    /// Set the cycles each thread performs
+    pub fn with_operations(mut self, operations: usize) -> Self {
+        self.operations = operations;
+        self
+    }
+
+    /// Set the spin iterations each object is held for
+    pub fn with_hold(mut self, hold: u32) -> Self {
+        self.hold = hold;
+        self
+    }
+}
+
+/// A way of pooling objects measured by [`compare`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Contender {
+    /// [`ObjectPool::try_get_object`], with every feature the configuration
+    /// enables
+    Pool,
+
+    /// [`ObjectPool::try_get_object_fast`]; measured only when the
+    /// configuration [supports it](ObjectPool::supports_fast_path)
+    FastPath,
+
+    /// Idle objects in a `Mutex<Vec<T>>`, taken from and pushed to the end
+    MutexVec,
+}
+
+impl Contender {
+    /// Short name used in reports
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Pool => "pool",
+            Self::FastPath => "fast-path",
+            Self::MutexVec => "mutex-vec",
+        }
+    }
+}
+
+impl fmt::Display for Contender {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// How one [`Contender`] did on a workload
+#[derive(Debug, Clone, PartialEq)]
+pub struct Measurement {
+    /// What was measured
+    pub contender: Contender,
+
+    /// Wall time from the first thread starting to the last finishing
+    pub elapsed: Duration,
+
+    /// Completed acquire, use, release cycles across all threads
+    pub operations: usize,
+
+    /// Acquisitions that found no idle object and were retried
+    pub misses: usize,
+}
+
+impl Measurement {
+    /// Completed cycles per second
+    #[must_use]
+    pub fn ops_per_sec(&self) -> f64 {
+        self.operations as f64 / self.elapsed.as_secs_f64().max(f64::MIN_POSITIVE)
+    }
+
+    /// Average wall time per completed cycle, in nanoseconds
+    #[must_use]
+    pub fn nanos_per_op(&self) -> f64 {
+        self.elapsed.as_nanos() as f64 / self.operations.max(1) as f64
+    }
+}
+
+/// Measurements of every contender on one workload
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    /// The workload measured
+    pub workload: Workload,
+
+    /// One measurement per contender, in the order they ran
+    pub measurements: Vec<Measurement>,
+}
+
+impl Comparison {
+    /// The measurement of `contender`, if it ran
+    #[must_use]
+    pub fn get(&self, contender: Contender) -> Option<&Measurement> {
+        self.measurements.iter().find(|m| m.contender == contender)
+    }
+
+    /// The contender with the highest throughput
+    #[must_use]
+    pub fn fastest(&self) -> Option<&Measurement> {
+        self.measurements
+            .iter()
+            .max_by(|a, b| a.ops_per_sec().total_cmp(&b.ops_per_sec()))
+    }
+
+    /// Throughput of `contender` relative to the `Mutex<Vec<T>>` baseline;
+    /// above 1 is faster
+    #[must_use]
+    pub fn speedup(&self, contender: Contender) -> Option<f64> {
+        let baseline = self.get(Contender::MutexVec)?.ops_per_sec();
+        Some(self.get(contender)?.ops_per_sec() / baseline)
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Workload { threads, objects, operations, hold } = self.workload;
+        writeln!(f, "{threads} threads, {objects} objects, {operations} ops/thread, hold {hold}")?;
+        for m in &self.measurements {
+            write!(
+                f,
+                "{:>10}: {:>12.0} ops/s {:>9.1} ns/op {:>9} misses",
+                m.contender,
+                m.ops_per_sec(),
+                m.nanos_per_op(),
+                m.misses
+            )?;
+            if let Some(speedup) = self.speedup(m.contender).filter(|_| m.contender != Contender::MutexVec) {
+                write!(f, " ({speedup:.2}x)")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Run `workload` against a pool built from `config` and against the
+/// `Mutex<Vec<T>>` baseline, both filled with `workload.objects` objects
+/// from `factory`
+///
+/// Hooks in the configuration run as usual, so the pool pays for the
+/// features it is given while the baseline does none of that work.
+pub fn compare<T, F>(factory: F, config: PoolConfiguration<T>, workload: &Workload) -> Comparison
+where
+    T: Send + Sync + 'static,
+    F: Fn() -> T,
+{
+    let pool = ObjectPool::new((0..workload.objects).map(|_| factory()).collect(), config);
+    let mut measurements = vec![measure(Contender::Pool, workload, || pool.try_get_object().ok().flatten())];
+    if pool.supports_fast_path() {
+        measurements.push(measure(Contender::FastPath, workload, || pool.try_get_object_fast()));
+    }
+
+    let baseline = MutexVecPool::new((0..workload.objects).map(|_| factory()).collect());
+    measurements.push(measure(Contender::MutexVec, workload, || baseline.acquire()));
+
+    Comparison {
+        workload: *workload,
+        measurements,
+    }
+}
+
+/// Time `workload` with objects taken by `acquire` and released on drop.
+fn measure<G, A>(contender: Contender, workload: &Workload, acquire: A) -> Measurement
+where
+    A: Fn() -> Option<G> + Sync,
+{
+    let started = Instant::now();
+    let misses: usize = thread::scope(|scope| {
+        let threads: Vec<_> = (0..workload.threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut misses = 0;
+                    for _ in 0..workload.operations {
+                        let held = loop {
+                            match acquire() {
+                                Some(held) => break held,
+                                None => {
+                                    misses += 1;
+                                    thread::yield_now();
+                                }
+                            }
+                        };
+                        for spin in 0..workload.hold {
+                            black_box((&held, spin));
+                        }
+                        drop(held);
+                    }
+                    misses
+                })
+            })
+            .collect();
+        threads.into_iter().map(|t| t.join().expect("benchmark thread panicked")).sum()
+    });
+    Measurement {
+        contender,
+        elapsed: started.elapsed(),
+        operations: workload.threads * workload.operations,
+        misses,
+    }
+}
+
+/// The baseline: idle objects in a `Vec` behind a `Mutex`.
+struct MutexVecPool<T> {
+    idle: Mutex<Vec<T>>,
+}
+
+impl<T> MutexVecPool<T> {
+    fn new(objects: Vec<T>) -> Self {
+        Self { idle: Mutex::new(objects) }
+    }
+
+    fn acquire(&self) -> Option<MutexVecGuard<'_, T>> {
+        let obj = self.lock().pop()?;
+        Some(MutexVecGuard { pool: self, obj: Some(obj) })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<T>> {
+        self.idle.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+struct MutexVecGuard<'a, T> {
+    pool: &'a MutexVecPool<T>,
+    obj: Option<T>,
+}
+
+impl<T> Drop for MutexVecGuard<'_, T> {
+    fn drop(&mut self) {
+        if let Some(obj) = self.obj.take() {
+            self.pool.lock().push(obj);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_contender_completes_the_workload() {
+        let workload = Workload::new(4, 2).with_operations(200).with_hold(10);
+        let comparison = compare(|| 0u64, PoolConfiguration::default(), &workload);
+
+        let contenders: Vec<_> = comparison.measurements.iter().map(|m| m.contender).collect();
+        assert_eq!(contenders, [Contender::Pool, Contender::FastPath, Contender::MutexVec]);
+        for m in &comparison.measurements {
+            assert_eq!(m.operations, 800);
+            assert!(m.ops_per_sec() > 0.0);
+        }
+        assert!(comparison.fastest().is_some());
+        assert_eq!(comparison.speedup(Contender::MutexVec), Some(1.0));
+        assert_eq!(comparison.to_string().lines().count(), 4);
+    }
+
+    #[test]
+    fn fast_path_is_skipped_when_the_configuration_rules_it_out() {
+        let config = PoolConfiguration::new().with_circuit_breaker(5, Duration::from_secs(60));
+        let comparison = compare(|| 0u64, config, &Workload::new(1, 1).with_operations(10));
+        assert!(comparison.get(Contender::FastPath).is_none());
+        assert_eq!(comparison.get(Contender::Pool).unwrap().misses, 0);
+    }
+}
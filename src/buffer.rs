@@ -0,0 +1,225 @@
+//! Byte buffer pools with size classes
+
+use crate::acquire::Acquire;
+use crate::config::PoolConfiguration;
+use crate::errors::{PoolError, PoolResult};
+use crate::metrics::PoolMetrics;
+use crate::pool::{DynamicObjectPool, PooledObject};
+
+/// Pools of `Vec<u8>` buffers in a few capacity classes
+///
+/// [`get_buffer`](Self::get_buffer) picks the smallest class that holds
+/// the requested capacity and hands out an empty buffer of at least that
+/// size, allocating one if none is idle. Each class is its own
+/// [`DynamicObjectPool`] built from the given configuration, so
+/// `max_pool_size` applies per class.
+///
+/// Returned buffers are cleared, and a buffer that grew past its class is
+/// shrunk back to the class capacity so one oversized message does not
+/// stay resident. Any [`on_return`](PoolConfiguration::with_on_return) hook
+/// in the configuration runs first.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{BufferPool, PoolConfiguration};
+///
+/// let buffers = BufferPool::new(PoolConfiguration::preset_buffers());
+///
+/// let mut buf = buffers.get_buffer(10_000).unwrap();
+/// assert!(buf.is_empty());
+/// assert_eq!(buf.capacity(), 64 * 1024);
+///
+/// buf.resize(200_000, 0); // outgrows its class
+/// drop(buf);
+///
+/// let buf = buffers.get_buffer(10_000).unwrap();
+/// assert!(buf.is_empty());
+/// assert_eq!(buf.capacity(), 64 * 1024);
+/// ```
+pub struct BufferPool {
+    /// One pool per class, ordered by capacity.
+    classes: Vec<(usize, DynamicObjectPool<Vec<u8>>)>,
+}
+
+impl BufferPool {
+    /// The classes used by [`new`](Self::new): 4 KiB, 64 KiB and 1 MiB
+    pub const DEFAULT_CLASSES: [usize; 3] = [4 * 1024, 64 * 1024, 1024 * 1024];
+
+    /// Create a pool with the [default classes](Self::DEFAULT_CLASSES)
+    pub fn new(config: PoolConfiguration<Vec<u8>>) -> Self {
+        Self::with_classes(Self::DEFAULT_CLASSES, config)
+    }
+
+    /// Create a pool with the given class capacities, in bytes
+    ///
+    /// # Panics
+    ///
+    /// Panics if `classes` is empty or contains 0.
+    pub fn with_classes(classes: impl IntoIterator<Item = usize>, config: PoolConfiguration<Vec<u8>>) -> Self {
+        let mut capacities: Vec<usize> = classes.into_iter().collect();
+        capacities.sort_unstable();
+        capacities.dedup();
+        assert!(
+            capacities.first().is_some_and(|&smallest| smallest > 0),
+            "BufferPool needs at least one non-zero size class"
+        );
+
+        let classes = capacities
+            .into_iter()
+            .map(|class| {
+                let user_hook = config.on_return.clone();
+                let config = config.clone().with_on_return(move |buf: &mut Vec<u8>| {
+                    if let Some(ref hook) = user_hook {
+                        hook(buf);
+                    }
+                    buf.clear();
+                    if buf.capacity() > class {
+                        buf.shrink_to(class);
+                    } else if buf.capacity() < class {
+                        buf.reserve_exact(class);
+                    }
+                });
+                (class, DynamicObjectPool::new(move || Vec::with_capacity(class), config))
+            })
+            .collect();
+        Self { classes }
+    }
+
+    /// Get an empty buffer with room for at least `min_capacity` bytes
+    ///
+    /// Fails with `PoolError::NoMatchFound` if `min_capacity` exceeds the
+    /// largest class, and otherwise like
+    /// [`DynamicObjectPool::get_object`].
+    #[track_caller]
+    pub fn get_buffer(&self, min_capacity: usize) -> PoolResult<PooledObject<Vec<u8>>> {
+        self.pool_for(min_capacity)?.get_object()
+    }
+
+    /// Async counterpart of [`get_buffer`](Self::get_buffer)
+    #[track_caller]
+    pub fn get_buffer_async(&self, min_capacity: usize) -> Acquire<'_, Vec<u8>> {
+        match self.pool_for(min_capacity) {
+            Ok(pool) => pool.get_object_async(),
+            Err(err) => Acquire::new(std::future::ready(Err(err))),
+        }
+    }
+
+    /// Capacity of the class serving requests for `min_capacity` bytes
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{BufferPool, PoolConfiguration};
+    ///
+    /// let buffers = BufferPool::with_classes([512, 8192], PoolConfiguration::default());
+    /// assert_eq!(buffers.class_for(100), Some(512));
+    /// assert_eq!(buffers.class_for(513), Some(8192));
+    /// assert_eq!(buffers.class_for(10_000), None);
+    /// ```
+    #[must_use]
+    pub fn class_for(&self, min_capacity: usize) -> Option<usize> {
+        self.class_index(min_capacity).map(|index| self.classes[index].0)
+    }
+
+    /// The class capacities, smallest first
+    #[must_use]
+    pub fn classes(&self) -> Vec<usize> {
+        self.classes.iter().map(|(class, _)| *class).collect()
+    }
+
+    /// The pool backing the class of exactly `class` bytes
+    #[must_use]
+    pub fn class_pool(&self, class: usize) -> Option<&DynamicObjectPool<Vec<u8>>> {
+        self.classes
+            .iter()
+            .find(|(capacity, _)| *capacity == class)
+            .map(|(_, pool)| pool)
+    }
+
+    /// Metrics of every class, smallest first
+    #[must_use]
+    pub fn class_metrics(&self) -> Vec<(usize, PoolMetrics)> {
+        self.classes
+            .iter()
+            .map(|(class, pool)| (*class, pool.get_metrics()))
+            .collect()
+    }
+
+    fn class_index(&self, min_capacity: usize) -> Option<usize> {
+        let index = self.classes.partition_point(|(class, _)| *class < min_capacity);
+        (index < self.classes.len()).then_some(index)
+    }
+
+    fn pool_for(&self, min_capacity: usize) -> PoolResult<&DynamicObjectPool<Vec<u8>>> {
+        let index = self.class_index(min_capacity).ok_or(PoolError::NoMatchFound)?;
+        Ok(&self.classes[index].1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_go_to_the_smallest_fitting_class() {
+        let buffers = BufferPool::with_classes([4096, 16, 256, 16], PoolConfiguration::default());
+        assert_eq!(buffers.classes(), [16, 256, 4096]);
+
+        let exact = buffers.get_buffer(256).unwrap();
+        assert_eq!(exact.capacity(), 256);
+        assert_eq!(buffers.get_buffer(0).unwrap().capacity(), 16);
+        assert!(matches!(buffers.get_buffer(4097), Err(PoolError::NoMatchFound)));
+
+        drop(exact);
+        let metrics = buffers.class_metrics();
+        assert_eq!(metrics[1].0, 256);
+        assert_eq!(metrics[1].1.total_returned, 1);
+        assert_eq!(buffers.class_pool(256).unwrap().available_count(), 1);
+    }
+
+    #[test]
+    fn returned_buffers_are_reused_cleared() {
+        let buffers = BufferPool::with_classes([64], PoolConfiguration::default());
+        let mut buf = buffers.get_buffer(10).unwrap();
+        buf.extend_from_slice(b"hello");
+        let ptr = buf.as_ptr();
+        drop(buf);
+
+        let buf = buffers.get_buffer(10).unwrap();
+        assert!(buf.is_empty());
+        assert_eq!(buf.as_ptr(), ptr, "the same allocation is reused");
+    }
+
+    #[test]
+    fn shrunk_buffers_regain_their_class_capacity() {
+        let buffers = BufferPool::with_classes([64], PoolConfiguration::default());
+        let mut buf = buffers.get_buffer(64).unwrap();
+        buf.shrink_to_fit();
+        drop(buf);
+
+        assert!(buffers.get_buffer(64).unwrap().capacity() >= 64);
+    }
+
+    #[test]
+    fn user_return_hook_runs_before_clearing() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+
+        let seen = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&seen);
+        let config = PoolConfiguration::new().with_on_return(move |buf: &mut Vec<u8>| {
+            counter.store(buf.len(), Ordering::SeqCst);
+        });
+        let buffers = BufferPool::with_classes([64], config);
+
+        buffers.get_buffer(1).unwrap().extend_from_slice(b"abc");
+        assert_eq!(seen.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "non-zero size class")]
+    fn zero_sized_class_is_rejected() {
+        let _ = BufferPool::with_classes([0, 64], PoolConfiguration::default());
+    }
+}
@@ -0,0 +1,254 @@
+//! Typestate builder catching incomplete pool setups at compile time
+
+use crate::config::{CapacityLimits, PoolConfiguration};
+use crate::pool::{DynamicObjectPool, Factory, ObjectPool, QueryableObjectPool};
+
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// Builder state: neither objects nor a factory given yet, so there is
+/// nothing to build
+#[derive(Debug)]
+pub struct NeedsSource;
+
+/// Builder state: builds an [`ObjectPool`] over a fixed set of objects
+#[derive(Debug)]
+pub struct Fixed<T> {
+    objects: Vec<T>,
+}
+
+/// Builder state: builds a [`DynamicObjectPool`] around a factory
+pub struct Dynamic<T> {
+    factory: Factory<T>,
+    initial: Vec<T>,
+}
+
+/// Builder state: no validation function given
+#[derive(Debug)]
+pub struct NoValidator;
+
+/// Builder state: a validation function was given
+#[derive(Debug)]
+pub struct HasValidator;
+
+/// Builds a pool, rejecting incomplete setups at compile time
+///
+/// [`PoolConfiguration`] accepts any combination of settings, including
+/// some that silently do nothing. The builder tracks in its type what has
+/// been provided:
+///
+/// - `build` only exists once the pool has a source of objects: a fixed
+///   set through [`objects`](Self::objects), or a factory through
+///   [`factory`](Self::factory) or [`try_factory`](Self::try_factory).
+/// - [`validate_on_return`](PoolBuilder::validate_on_return) only exists
+///   once a validation function was given with
+///   [`validator`](Self::validator).
+///
+/// Capacity is set as a [`CapacityLimits`]. Every other setting is reached
+/// through [`configure`](Self::configure); settings changed there are not
+/// checked.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{CapacityLimits, PoolBuilder, WaitPolicy};
+///
+/// let pool = PoolBuilder::new()
+///     .factory(|| Vec::<u8>::with_capacity(1024))
+///     .limits(CapacityLimits::new(8).with_max_active_objects(4))
+///     .validator(|buf: &Vec<u8>| buf.capacity() >= 1024)
+///     .validate_on_return()
+///     .configure(|config| config.with_wait_on_empty(WaitPolicy::FailFast))
+///     .build();
+///
+/// assert!(pool.get_object().is_ok());
+/// assert_eq!(pool.capacity(), 8);
+/// ```
+///
+/// A dynamic pool without a factory does not compile:
+///
+/// ```compile_fail
+/// use esox_objectpool::{CapacityLimits, PoolBuilder};
+///
+/// let pool = PoolBuilder::<u32>::new().limits(CapacityLimits::new(8)).build();
+/// ```
+///
+/// Neither does validation on return without a validation function:
+///
+/// ```compile_fail
+/// use esox_objectpool::PoolBuilder;
+///
+/// let pool = PoolBuilder::new().objects(vec![1, 2]).validate_on_return().build();
+/// ```
+pub struct PoolBuilder<T, S = NeedsSource, V = NoValidator> {
+    config: PoolConfiguration<T>,
+    source: S,
+    validation: PhantomData<V>,
+}
+
+impl<T> PoolBuilder<T> {
+    /// Start from the default configuration
+    pub fn new() -> Self {
+        Self {
+            config: PoolConfiguration::default(),
+            source: NeedsSource,
+            validation: PhantomData,
+        }
+    }
+}
+
+impl<T> Default for PoolBuilder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, S, V> PoolBuilder<T, S, V> {
+    /// Set how many objects the pool may own and hand out at once
+    pub fn limits(mut self, limits: CapacityLimits) -> Self {
+        self.config = self.config.with_capacity_limits(limits);
+        self
+    }
+
+    /// Change any other setting of the underlying [`PoolConfiguration`]
+    pub fn configure(mut self, f: impl FnOnce(PoolConfiguration<T>) -> PoolConfiguration<T>) -> Self {
+        self.config = f(self.config);
+        self
+    }
+
+    fn with_state<S2, V2>(self, source: S2) -> PoolBuilder<T, S2, V2> {
+        PoolBuilder {
+            config: self.config,
+            source,
+            validation: PhantomData,
+        }
+    }
+}
+
+impl<T, V> PoolBuilder<T, NeedsSource, V> {
+    /// Pool exactly these objects
+    pub fn objects(self, objects: Vec<T>) -> PoolBuilder<T, Fixed<T>, V> {
+        self.with_state(Fixed { objects })
+    }
+
+    /// Create objects with `factory` as they are needed
+    pub fn factory<F>(self, factory: F) -> PoolBuilder<T, Dynamic<T>, V>
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+    {
+        self.with_state(Dynamic {
+            factory: Arc::new(move || Ok(factory())),
+            initial: Vec::new(),
+        })
+    }
+
+    /// Create objects with a factory that can fail. See
+    /// [`DynamicObjectPool::try_new`].
+    pub fn try_factory<F, E>(self, factory: F) -> PoolBuilder<T, Dynamic<T>, V>
+    where
+        F: Fn() -> Result<T, E> + Send + Sync + 'static,
+        E: std::fmt::Display,
+    {
+        self.with_state(Dynamic {
+            factory: Arc::new(move || factory().map_err(|err| err.to_string())),
+            initial: Vec::new(),
+        })
+    }
+}
+
+impl<T, S> PoolBuilder<T, S, NoValidator> {
+    /// Check objects with `func` when the pool verifies them; add
+    /// [`validate_on_return`](PoolBuilder::validate_on_return) to also
+    /// check every returned object
+    pub fn validator(mut self, func: fn(&T) -> bool) -> PoolBuilder<T, S, HasValidator> {
+        self.config.validation_function = Some(func);
+        let source = self.source;
+        PoolBuilder {
+            config: self.config,
+            source,
+            validation: PhantomData,
+        }
+    }
+}
+
+impl<T, S> PoolBuilder<T, S, HasValidator> {
+    /// Run the validation function on every returned object, destroying
+    /// the ones that fail
+    pub fn validate_on_return(mut self) -> Self {
+        self.config.validate_on_return = true;
+        self
+    }
+}
+
+impl<T: Send + Sync + 'static, V> PoolBuilder<T, Fixed<T>, V> {
+    /// Build an [`ObjectPool`] over the given objects
+    pub fn build(self) -> ObjectPool<T> {
+        ObjectPool::new(self.source.objects, self.config)
+    }
+
+    /// Build a [`QueryableObjectPool`] over the given objects
+    pub fn build_queryable(self) -> QueryableObjectPool<T> {
+        QueryableObjectPool::new(self.source.objects, self.config)
+    }
+}
+
+impl<T: Send + Sync + 'static, V> PoolBuilder<T, Dynamic<T>, V> {
+    /// Start with these objects before creating any
+    pub fn initial_objects(mut self, objects: Vec<T>) -> Self {
+        self.source.initial = objects;
+        self
+    }
+
+    /// Build a [`DynamicObjectPool`] around the factory
+    pub fn build(self) -> DynamicObjectPool<T> {
+        DynamicObjectPool::from_factory(self.source.factory, self.source.initial, self.config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PoolError, WaitPolicy};
+
+    #[test]
+    fn fixed_builder_uses_objects_and_limits() {
+        let pool = PoolBuilder::new()
+            .objects(vec![1, 2, 3])
+            .limits(CapacityLimits::new(3).with_max_active_objects(1))
+            .build();
+        assert_eq!(pool.available_count(), 3);
+        let _held = pool.get_object().unwrap();
+        assert!(matches!(pool.get_object(), Err(PoolError::MaxActiveObjectsReached)));
+    }
+
+    #[test]
+    fn dynamic_builder_creates_after_initial_objects() {
+        let pool = PoolBuilder::new()
+            .try_factory(|| Ok::<_, String>(7))
+            .initial_objects(vec![1])
+            .limits(CapacityLimits::new(2))
+            .configure(|config| config.with_wait_on_empty(WaitPolicy::FailFast))
+            .build();
+        let first = pool.get_object().unwrap();
+        let second = pool.get_object().unwrap();
+        assert_eq!((*first, *second), (1, 7));
+        assert!(matches!(pool.get_object(), Err(PoolError::PoolFull)));
+    }
+
+    #[test]
+    fn validator_alone_does_not_validate_returns() {
+        let lenient = PoolBuilder::new().objects(vec![1]).validator(|x: &i32| *x > 0).build();
+        let mut obj = lenient.get_object().unwrap();
+        *obj = -1;
+        assert!(obj.release().is_ok());
+
+        let strict = PoolBuilder::new()
+            .objects(vec![1])
+            .validator(|x: &i32| *x > 0)
+            .validate_on_return()
+            .build();
+        let mut obj = strict.get_object().unwrap();
+        *obj = -1;
+        assert!(matches!(obj.release(), Err(PoolError::ValidationFailed)));
+    }
+}
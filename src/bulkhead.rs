@@ -0,0 +1,137 @@
+//! Per-category concurrency limits within one pool
+
+use crate::errors::{PoolError, PoolResult};
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// One category's share of the pool.
+struct Bulkhead {
+    limit: usize,
+    active: AtomicUsize,
+}
+
+/// The bulkheads configured for a pool, keyed by category.
+///
+/// Built once from the configuration; the set of categories is fixed for the
+/// lifetime of the pool.
+pub(crate) struct Bulkheads {
+    categories: HashMap<String, Arc<Bulkhead>>,
+}
+
+impl Bulkheads {
+    pub fn new(limits: &HashMap<String, usize>) -> Self {
+        Self {
+            categories: limits
+                .iter()
+                .map(|(category, &limit)| {
+                    let bulkhead = Bulkhead {
+                        limit,
+                        active: AtomicUsize::new(0),
+                    };
+                    (category.clone(), Arc::new(bulkhead))
+                })
+                .collect(),
+        }
+    }
+
+    /// Fail as [`enter`](Self::enter) would, without taking a slot.
+    ///
+    /// Lets a caller that is about to wait for an object fail fast on a full
+    /// category, then take the slot only once the object is in hand.
+    pub fn check(&self, category: &str) -> PoolResult<()> {
+        let bulkhead = self.get(category)?;
+        if bulkhead.active.load(Ordering::Acquire) >= bulkhead.limit {
+            return Err(PoolError::BulkheadFull(category.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Take a slot in `category`, failing if the category is at its limit.
+    pub fn enter(&self, category: &str) -> PoolResult<BulkheadPermit> {
+        let bulkhead = self.get(category)?;
+
+        bulkhead
+            .active
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |active| {
+                (active < bulkhead.limit).then_some(active + 1)
+            })
+            .map_err(|_| PoolError::BulkheadFull(category.to_string()))?;
+
+        Ok(BulkheadPermit {
+            bulkhead: Arc::clone(bulkhead),
+        })
+    }
+
+    fn get(&self, category: &str) -> PoolResult<&Arc<Bulkhead>> {
+        self.categories
+            .get(category)
+            .ok_or_else(|| PoolError::UnknownBulkhead(category.to_string()))
+    }
+
+    /// Objects currently checked out under `category`.
+    pub fn active(&self, category: &str) -> Option<usize> {
+        self.categories
+            .get(category)
+            .map(|b| b.active.load(Ordering::Acquire))
+    }
+}
+
+/// A held slot in a bulkhead, released on drop.
+pub(crate) struct BulkheadPermit {
+    bulkhead: Arc<Bulkhead>,
+}
+
+impl Drop for BulkheadPermit {
+    fn drop(&mut self) {
+        self.bulkhead.active.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bulkheads() -> Bulkheads {
+        Bulkheads::new(&HashMap::from([("reads".to_string(), 2), ("writes".to_string(), 1)]))
+    }
+
+    #[test]
+    fn enter_enforces_category_limit() {
+        let bulkheads = bulkheads();
+        let _a = bulkheads.enter("writes").unwrap();
+        assert!(matches!(bulkheads.enter("writes"), Err(PoolError::BulkheadFull(c)) if c == "writes"));
+
+        // Other categories are unaffected.
+        let _b = bulkheads.enter("reads").unwrap();
+        assert_eq!(bulkheads.active("reads"), Some(1));
+    }
+
+    #[test]
+    fn dropping_permit_frees_slot() {
+        let bulkheads = bulkheads();
+        let permit = bulkheads.enter("writes").unwrap();
+        drop(permit);
+        assert_eq!(bulkheads.active("writes"), Some(0));
+        assert!(bulkheads.enter("writes").is_ok());
+    }
+
+    #[test]
+    fn check_fails_like_enter_without_taking_a_slot() {
+        let bulkheads = bulkheads();
+        assert!(bulkheads.check("writes").is_ok());
+        assert_eq!(bulkheads.active("writes"), Some(0));
+
+        let _a = bulkheads.enter("writes").unwrap();
+        assert!(matches!(bulkheads.check("writes"), Err(PoolError::BulkheadFull(c)) if c == "writes"));
+    }
+
+    #[test]
+    fn unknown_category_is_rejected() {
+        let bulkheads = bulkheads();
+        assert!(matches!(bulkheads.check("admin"), Err(PoolError::UnknownBulkhead(_))));
+        assert!(matches!(bulkheads.enter("admin"), Err(PoolError::UnknownBulkhead(c)) if c == "admin"));
+        assert_eq!(bulkheads.active("admin"), None);
+    }
+}
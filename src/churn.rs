@@ -0,0 +1,155 @@
+//! Detection of objects being created and destroyed at a high rate
+
+use crate::events::PoolWarning;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::Instant;
+
+/// Seconds covered by the rolling window, one bucket per second.
+const WINDOW_SECS: usize = 60;
+
+#[derive(Clone, Copy, Default)]
+struct Bucket {
+    /// Second since the monitor started that the counts belong to.
+    second: u64,
+    created: u64,
+    destroyed: u64,
+}
+
+/// Objects created and destroyed in the last minute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Churn {
+    pub created: u64,
+    pub destroyed: u64,
+}
+
+impl Churn {
+    /// Objects replaced: each destruction matched by a creation. Warming up
+    /// or losing objects alone is not churn.
+    pub fn replaced(&self) -> u64 {
+        self.created.min(self.destroyed)
+    }
+}
+
+/// Counts creations and destructions over a rolling minute and warns once
+/// replacements exceed a threshold.
+pub(crate) struct ChurnMonitor {
+    threshold: u64,
+    started: Instant,
+    buckets: Mutex<[Bucket; WINDOW_SECS]>,
+    /// Set once high churn has been reported; cleared when churn drops back
+    /// to the threshold so each episode is reported once.
+    reported: AtomicBool,
+}
+
+impl ChurnMonitor {
+    pub fn new(threshold_per_minute: u64) -> Self {
+        Self {
+            threshold: threshold_per_minute,
+            started: Instant::now(),
+            buckets: Mutex::new([Bucket::default(); WINDOW_SECS]),
+            reported: AtomicBool::new(false),
+        }
+    }
+
+    pub fn threshold(&self) -> u64 {
+        self.threshold
+    }
+
+    /// An object was created; the warning to publish, if churn is now high.
+    pub fn record_creation(&self) -> Option<PoolWarning> {
+        self.record_at(Instant::now(), |bucket| bucket.created += 1)
+    }
+
+    /// An object was destroyed; the warning to publish, if churn is now high.
+    pub fn record_destruction(&self) -> Option<PoolWarning> {
+        self.record_at(Instant::now(), |bucket| bucket.destroyed += 1)
+    }
+
+    /// Churn over the minute up to now.
+    pub fn churn(&self) -> Churn {
+        let buckets = self.buckets.lock().unwrap_or_else(PoisonError::into_inner);
+        Self::sum(&buckets, self.second(Instant::now()))
+    }
+
+    fn record_at(&self, now: Instant, count: impl FnOnce(&mut Bucket)) -> Option<PoolWarning> {
+        let second = self.second(now);
+        let churn = {
+            let mut buckets = self.buckets.lock().unwrap_or_else(PoisonError::into_inner);
+            let bucket = &mut buckets[second as usize % WINDOW_SECS];
+            if bucket.second != second {
+                *bucket = Bucket { second, ..Bucket::default() };
+            }
+            count(bucket);
+            Self::sum(&buckets, second)
+        };
+        if churn.replaced() <= self.threshold {
+            self.reported.store(false, Ordering::Relaxed);
+            return None;
+        }
+        if self.reported.swap(true, Ordering::Relaxed) {
+            return None;
+        }
+        Some(PoolWarning::HighChurn {
+            created: churn.created,
+            destroyed: churn.destroyed,
+            threshold: self.threshold,
+        })
+    }
+
+    fn second(&self, now: Instant) -> u64 {
+        now.saturating_duration_since(self.started).as_secs()
+    }
+
+    /// Totals of the buckets within the minute ending at `second`.
+    fn sum(buckets: &[Bucket; WINDOW_SECS], second: u64) -> Churn {
+        buckets
+            .iter()
+            .filter(|bucket| bucket.second + (WINDOW_SECS as u64) > second && bucket.second <= second)
+            .fold(Churn { created: 0, destroyed: 0 }, |total, bucket| Churn {
+                created: total.created + bucket.created,
+                destroyed: total.destroyed + bucket.destroyed,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn replace(monitor: &ChurnMonitor, at: Instant) -> Option<PoolWarning> {
+        monitor.record_at(at, |bucket| bucket.destroyed += 1);
+        monitor.record_at(at, |bucket| bucket.created += 1)
+    }
+
+    #[test]
+    fn warns_once_per_episode_of_high_churn() {
+        let monitor = ChurnMonitor::new(2);
+        let start = monitor.started;
+        assert!(replace(&monitor, start).is_none());
+        assert!(replace(&monitor, start).is_none());
+        assert_eq!(
+            replace(&monitor, start + Duration::from_secs(1)),
+            Some(PoolWarning::HighChurn { created: 3, destroyed: 3, threshold: 2 })
+        );
+        assert!(replace(&monitor, start + Duration::from_secs(2)).is_none(), "already reported");
+
+        // A minute later the earlier replacements have left the window.
+        assert!(replace(&monitor, start + Duration::from_secs(61)).is_none());
+        assert!(replace(&monitor, start + Duration::from_secs(200)).is_none());
+        assert!(replace(&monitor, start + Duration::from_secs(200)).is_none());
+        assert!(replace(&monitor, start + Duration::from_secs(200)).is_some(), "a new episode");
+    }
+
+    #[test]
+    fn creations_alone_are_not_churn() {
+        let monitor = ChurnMonitor::new(1);
+        for _ in 0..10 {
+            assert!(monitor.record_creation().is_none());
+        }
+        assert_eq!(monitor.churn(), Churn { created: 10, destroyed: 0 });
+        assert_eq!(monitor.churn().replaced(), 0);
+    }
+}
@@ -27,6 +27,51 @@ pub enum CircuitBreakerState {
     HalfOpen,
 }
 
+/// Which signals a pool feeds into its circuit breaker
+///
+/// *Acquisition* signals are whether an idle object was available when one
+/// was requested; they trip the breaker when the pool is undersized.
+/// *Operation* signals are outcomes reported by the caller — through
+/// [`ObjectPool::report_success`](crate::ObjectPool::report_success) /
+/// [`ObjectPool::report_failure`](crate::ObjectPool::report_failure) or
+/// [`ObjectPool::run_guarded`](crate::ObjectPool::run_guarded) — and trip it
+/// when the backend behind the objects is failing.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{BreakerSignals, PoolConfiguration};
+/// use std::time::Duration;
+///
+/// let config = PoolConfiguration::<i32>::new()
+///     .with_circuit_breaker(5, Duration::from_secs(30))
+///     .with_breaker_signals(BreakerSignals::Operations);
+///
+/// assert_eq!(config.breaker_signals, BreakerSignals::Operations);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BreakerSignals {
+    /// Only pool emptiness on acquisition
+    Acquisition,
+
+    /// Only reported operation outcomes
+    Operations,
+
+    /// Both kinds of signal
+    #[default]
+    Both,
+}
+
+impl BreakerSignals {
+    pub(crate) fn includes_acquisition(self) -> bool {
+        self != BreakerSignals::Operations
+    }
+
+    pub(crate) fn includes_operations(self) -> bool {
+        self != BreakerSignals::Acquisition
+    }
+}
+
 /// Circuit breaker for protecting against cascading failures
 ///
 /// # Examples
@@ -52,6 +97,8 @@ pub struct CircuitBreaker {
     failure_threshold: usize,
     timeout: Duration,
     last_failure_time: Arc<Mutex<Option<Instant>>>,
+    /// Told about every change of state.
+    observer: Option<Box<dyn Fn(CircuitBreakerState) + Send + Sync>>,
 }
 
 impl CircuitBreaker {
@@ -64,8 +111,18 @@ impl CircuitBreaker {
             failure_threshold,
             timeout,
             last_failure_time: Arc::new(Mutex::new(None)),
+            observer: None,
         }
     }
+
+    /// Call `observer` with the new state whenever the state changes.
+    pub(crate) fn with_observer<F>(mut self, observer: F) -> Self
+    where
+        F: Fn(CircuitBreakerState) + Send + Sync + 'static,
+    {
+        self.observer = Some(Box::new(observer));
+        self
+    }
     
     /// Get the current state
     pub fn state(&self) -> CircuitBreakerState {
@@ -94,6 +151,30 @@ impl CircuitBreaker {
         }
     }
     
+    /// How long until an open breaker lets a probe request through, or
+    /// `None` if it is not open
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::CircuitBreaker;
+    /// use std::time::Duration;
+    ///
+    /// let breaker = CircuitBreaker::new(1, Duration::from_secs(60));
+    /// assert_eq!(breaker.retry_after(), None);
+    ///
+    /// breaker.record_failure();
+    /// assert!(breaker.retry_after().unwrap() > Duration::from_secs(59));
+    /// ```
+    pub fn retry_after(&self) -> Option<Duration> {
+        if self.state() != CircuitBreakerState::Open {
+            return None;
+        }
+        let elapsed = (*self.last_failure_time.lock().unwrap())?.elapsed();
+        // `allow_request` needs strictly more than `timeout` to have passed.
+        Some(self.timeout.saturating_sub(elapsed) + Duration::from_millis(1))
+    }
+
     /// Record a successful operation
     pub fn record_success(&self) {
         let current_state = self.state();
@@ -135,19 +216,28 @@ impl CircuitBreaker {
     }
     
     fn transition_to_open(&self) {
-        *self.state.lock().unwrap() = CircuitBreakerState::Open;
+        self.transition(CircuitBreakerState::Open);
     }
     
     fn transition_to_half_open(&self) {
-        *self.state.lock().unwrap() = CircuitBreakerState::HalfOpen;
+        self.transition(CircuitBreakerState::HalfOpen);
         self.success_count.store(0, Ordering::Relaxed);
     }
     
     fn transition_to_closed(&self) {
-        *self.state.lock().unwrap() = CircuitBreakerState::Closed;
+        self.transition(CircuitBreakerState::Closed);
         self.failure_count.store(0, Ordering::Relaxed);
         self.success_count.store(0, Ordering::Relaxed);
     }
+
+    fn transition(&self, state: CircuitBreakerState) {
+        let previous = std::mem::replace(&mut *self.state.lock().unwrap(), state);
+        if previous != state
+            && let Some(ref observer) = self.observer
+        {
+            observer(state);
+        }
+    }
     
     /// Reset the circuit breaker
     pub fn reset(&self) {
@@ -155,10 +245,24 @@ impl CircuitBreaker {
     }
 }
 
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new(5, Duration::from_secs(60))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn breaker_signals_selection() {
+        assert!(BreakerSignals::default().includes_acquisition());
+        assert!(BreakerSignals::default().includes_operations());
+        assert!(!BreakerSignals::Acquisition.includes_operations());
+        assert!(!BreakerSignals::Operations.includes_acquisition());
+    }
+
     #[test]
     fn does_not_open_on_non_consecutive_failures() {
         let breaker = CircuitBreaker::new(3, Duration::from_secs(60));
@@ -293,9 +397,3 @@ mod tests {
         assert_eq!(breaker.state(), CircuitBreakerState::Open);
     }
 }
-
-impl Default for CircuitBreaker {
-    fn default() -> Self {
-        Self::new(5, Duration::from_secs(60))
-    }
-}
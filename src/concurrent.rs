@@ -0,0 +1,230 @@
+//! Concurrent maps and queues behind the pool's internals
+//!
+//! Normally these are DashMap and crossbeam's `ArrayQueue`. Under Miri, or
+//! with the `mutex-internals` feature, they are a `Mutex` around a std
+//! collection instead: slower under contention, but quick to interpret, so
+//! crates embedding a pool can run their own tests under Miri.
+//!
+//! Map entries are only reached through closures, which must not touch the
+//! same map again: with the mutex fallback that would deadlock.
+
+pub(crate) use imp::{Map, Queue};
+
+#[cfg(not(any(miri, feature = "mutex-internals")))]
+mod imp {
+    use dashmap::DashMap;
+    use std::borrow::Borrow;
+    use std::hash::Hash;
+
+    pub(crate) use crossbeam::queue::ArrayQueue as Queue;
+
+    pub(crate) struct Map<K, V> {
+        inner: DashMap<K, V>,
+    }
+
+    impl<K: Eq + Hash, V> Map<K, V> {
+        pub fn new() -> Self {
+            Self { inner: DashMap::new() }
+        }
+
+        pub fn insert(&self, key: K, value: V) {
+            self.inner.insert(key, value);
+        }
+
+        pub fn remove<Q: Eq + Hash + ?Sized>(&self, key: &Q)
+        where
+            K: Borrow<Q>,
+        {
+            self.inner.remove(key);
+        }
+
+        /// Remove `key` if `condition` holds for its value.
+        pub fn remove_if<Q: Eq + Hash + ?Sized>(&self, key: &Q, condition: impl FnOnce(&V) -> bool)
+        where
+            K: Borrow<Q>,
+        {
+            self.inner.remove_if(key, |_, value| condition(value));
+        }
+
+        pub fn read<Q: Eq + Hash + ?Sized, R>(&self, key: &Q, f: impl FnOnce(&V) -> R) -> Option<R>
+        where
+            K: Borrow<Q>,
+        {
+            self.inner.get(key).map(|value| f(&value))
+        }
+
+        pub fn update<Q: Eq + Hash + ?Sized, R>(&self, key: &Q, f: impl FnOnce(&mut V) -> R) -> Option<R>
+        where
+            K: Borrow<Q>,
+        {
+            self.inner.get_mut(key).map(|mut value| f(&mut value))
+        }
+
+        /// Run `f` on the value of `key`, inserting the default first if
+        /// there is none.
+        pub fn update_or_default<R>(&self, key: K, f: impl FnOnce(&mut V) -> R) -> R
+        where
+            V: Default,
+        {
+            f(&mut self.inner.entry(key).or_default())
+        }
+
+        /// `f` applied to every entry, in no particular order.
+        pub fn collect<R>(&self, mut f: impl FnMut(&K, &V) -> R) -> Vec<R> {
+            self.inner.iter().map(|entry| f(entry.key(), entry.value())).collect()
+        }
+
+        pub fn len(&self) -> usize {
+            self.inner.len()
+        }
+    }
+}
+
+#[cfg(any(miri, feature = "mutex-internals"))]
+mod imp {
+    use std::borrow::Borrow;
+    use std::collections::{HashMap, VecDeque};
+    use std::hash::Hash;
+    use std::sync::{Mutex, MutexGuard, PoisonError};
+
+    /// Bounded FIFO queue with the `ArrayQueue` operations the pool uses.
+    pub(crate) struct Queue<T> {
+        items: Mutex<VecDeque<T>>,
+        capacity: usize,
+    }
+
+    impl<T> Queue<T> {
+        pub fn new(capacity: usize) -> Self {
+            Self {
+                items: Mutex::new(VecDeque::new()),
+                capacity,
+            }
+        }
+
+        pub fn push(&self, item: T) -> Result<(), T> {
+            let mut items = self.items.lock().unwrap_or_else(PoisonError::into_inner);
+            if items.len() >= self.capacity {
+                return Err(item);
+            }
+            items.push_back(item);
+            Ok(())
+        }
+
+        pub fn pop(&self) -> Option<T> {
+            self.items.lock().unwrap_or_else(PoisonError::into_inner).pop_front()
+        }
+
+        pub fn len(&self) -> usize {
+            self.items.lock().unwrap_or_else(PoisonError::into_inner).len()
+        }
+    }
+
+    pub(crate) struct Map<K, V> {
+        inner: Mutex<HashMap<K, V>>,
+    }
+
+    impl<K: Eq + Hash, V> Map<K, V> {
+        pub fn new() -> Self {
+            Self { inner: Mutex::new(HashMap::new()) }
+        }
+
+        fn lock(&self) -> MutexGuard<'_, HashMap<K, V>> {
+            self.inner.lock().unwrap_or_else(PoisonError::into_inner)
+        }
+
+        pub fn insert(&self, key: K, value: V) {
+            self.lock().insert(key, value);
+        }
+
+        pub fn remove<Q: Eq + Hash + ?Sized>(&self, key: &Q)
+        where
+            K: Borrow<Q>,
+        {
+            self.lock().remove(key);
+        }
+
+        /// Remove `key` if `condition` holds for its value.
+        pub fn remove_if<Q: Eq + Hash + ?Sized>(&self, key: &Q, condition: impl FnOnce(&V) -> bool)
+        where
+            K: Borrow<Q>,
+        {
+            let mut map = self.lock();
+            if map.get(key).is_some_and(condition) {
+                map.remove(key);
+            }
+        }
+
+        pub fn read<Q: Eq + Hash + ?Sized, R>(&self, key: &Q, f: impl FnOnce(&V) -> R) -> Option<R>
+        where
+            K: Borrow<Q>,
+        {
+            self.lock().get(key).map(f)
+        }
+
+        pub fn update<Q: Eq + Hash + ?Sized, R>(&self, key: &Q, f: impl FnOnce(&mut V) -> R) -> Option<R>
+        where
+            K: Borrow<Q>,
+        {
+            self.lock().get_mut(key).map(f)
+        }
+
+        /// Run `f` on the value of `key`, inserting the default first if
+        /// there is none.
+        pub fn update_or_default<R>(&self, key: K, f: impl FnOnce(&mut V) -> R) -> R
+        where
+            V: Default,
+        {
+            f(self.lock().entry(key).or_default())
+        }
+
+        /// `f` applied to every entry, in no particular order.
+        pub fn collect<R>(&self, mut f: impl FnMut(&K, &V) -> R) -> Vec<R> {
+            self.lock().iter().map(|(key, value)| f(key, value)).collect()
+        }
+
+        pub fn len(&self) -> usize {
+            self.lock().len()
+        }
+    }
+}
+
+impl<K: Eq + std::hash::Hash, V> Default for Map<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_reads_updates_and_removes_through_closures() {
+        let map: Map<String, u32> = Map::new();
+        map.insert("a".to_owned(), 1);
+        assert_eq!(map.update("a", |n| std::mem::replace(n, 5)), Some(1));
+        assert_eq!(map.read("a", |n| *n), Some(5));
+        assert_eq!(map.read("b", |n| *n), None);
+
+        assert_eq!(map.update_or_default("b".to_owned(), |n| { *n += 2; *n }), 2);
+        let mut all = map.collect(|key, n| (key.clone(), *n));
+        all.sort();
+        assert_eq!(all, [("a".to_owned(), 5), ("b".to_owned(), 2)]);
+
+        map.remove_if("a", |n| *n == 1);
+        assert_eq!(map.len(), 2, "condition did not hold");
+        map.remove_if("a", |n| *n == 5);
+        map.remove("b");
+        assert_eq!(map.len(), 0);
+    }
+
+    #[test]
+    fn queue_is_bounded_and_fifo() {
+        let queue = Queue::new(2);
+        assert!(queue.push(1).is_ok());
+        assert!(queue.push(2).is_ok());
+        assert_eq!(queue.push(3), Err(3));
+        assert_eq!(queue.len(), 2);
+        assert_eq!((queue.pop(), queue.pop(), queue.pop()), (Some(1), Some(2), None));
+    }
+}
@@ -1,7 +1,132 @@
 //! Pool configuration options
 
+use crate::circuit_breaker::BreakerSignals;
+use crate::context::AcquireContext;
+use crate::duration::parse_duration;
+use crate::errors::PoolResult;
+use crate::eviction::{EvictionPredicate, ExpiredReturn, ObjectStats, TtlRefresh};
+use crate::failure::FailurePolicy;
+use crate::group::LimitGroup;
+use crate::hooks::{AsyncHooks, HookPanicPolicy, HookTiming, SyncHooks};
+use crate::slo::SloTargets;
+use crate::throttle::CreationPolicy;
+use crate::wait::{Backoff, WaitPolicy};
+
+use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
 
+/// Hook run on an object just before it is handed out to a caller.
+pub type BorrowHook<T> = Arc<dyn Fn(&mut T) + Send + Sync>;
+
+/// Hook run on an object just before it is handed out, with the context of
+/// the acquisition.
+pub type AcquireHook<T> = Arc<dyn Fn(&mut T, &AcquireContext) + Send + Sync>;
+
+/// Hook run on an object as it is given back, before validation.
+pub type ReturnHook<T> = Arc<dyn Fn(&mut T) + Send + Sync>;
+
+/// Hook run with the durations the pool measured for an object's
+/// acquisition, hold or creation.
+pub type TimedHook<T> = Arc<dyn Fn(&mut T, &HookTiming) + Send + Sync>;
+
+/// Hook receiving objects that leave the pool for good (evicted, rejected by
+/// validation, or discarded because the queue was full).
+pub type DestroyHook<T> = Arc<dyn Fn(T) + Send + Sync>;
+
+/// Maps an object to the partition it is stored under in a queryable pool.
+pub type PartitionFn<T> = Arc<dyn Fn(&T) -> u64 + Send + Sync>;
+
+/// Scores an idle object from the object and its
+/// [health](crate::PooledObject::health); higher scores are handed out first.
+pub type SelectionFn<T> = Arc<dyn Fn(&T, f64) -> f64 + Send + Sync>;
+
+/// Hook run at acquisition time that yields a correlation id for the guard.
+pub type ContextHook = Arc<dyn Fn() -> Option<String> + Send + Sync>;
+
+/// Creates degraded objects while a dynamic pool's circuit breaker is open.
+pub type FallbackFactory<T> = Arc<dyn Fn() -> Result<T, String> + Send + Sync>;
+
+/// Estimates the bytes an object holds, for memory accounting.
+pub type SizeFn<T> = Arc<dyn Fn(&T) -> usize + Send + Sync>;
+
+/// How many objects a pool may own and how many it may hand out at once
+///
+/// The two limits apply in a fixed order:
+///
+/// 1. `max_pool_size` bounds the objects the pool owns, idle or checked
+///    out. A fixed pool given more initial objects owns all of them, so its
+///    size is the larger of the two.
+/// 2. `max_active_objects` bounds the objects checked out at once. It only
+///    has an effect below the pool size: at or above it, every object is
+///    checked out before the limit is reached, so it is ignored.
+///
+/// An acquisition checks the active limit first. If the limit is reached it
+/// fails with `PoolError::MaxActiveObjectsReached`, which is never waited
+/// on. Otherwise it takes an idle object, and a dynamic pool creates one
+/// only when none is idle and it owns fewer than `max_pool_size`. When
+/// every object is checked out and no more may be created, a fixed pool
+/// fails with `PoolError::PoolEmpty` and a dynamic one with
+/// `PoolError::PoolFull`; both are waited on according to the pool's
+/// [`WaitPolicy`](crate::WaitPolicy).
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{CapacityLimits, DynamicObjectPool, PoolConfiguration, PoolError};
+///
+/// let limits = CapacityLimits::new(4).with_max_active_objects(2);
+/// assert_eq!(limits.max_active(), 2);
+/// assert_eq!(limits.active_limit(), Some(2));
+///
+/// let pool = DynamicObjectPool::new(|| 0, PoolConfiguration::new().with_capacity_limits(limits));
+/// let _a = pool.get_object().unwrap();
+/// let _b = pool.get_object().unwrap();
+/// assert!(matches!(pool.get_object(), Err(PoolError::MaxActiveObjectsReached)));
+///
+/// // A limit at or above the pool size never applies.
+/// assert_eq!(CapacityLimits::new(2).with_max_active_objects(5).active_limit(), None);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CapacityLimits {
+    /// Most objects the pool owns, idle or checked out
+    pub max_pool_size: usize,
+
+    /// Most objects checked out at once, if fewer than `max_pool_size`
+    pub max_active_objects: Option<usize>,
+}
+
+impl CapacityLimits {
+    /// Limits allowing `max_pool_size` objects, all of which may be checked
+    /// out at once
+    pub fn new(max_pool_size: usize) -> Self {
+        Self {
+            max_pool_size,
+            max_active_objects: None,
+        }
+    }
+
+    /// Allow at most `count` objects to be checked out at once
+    pub fn with_max_active_objects(mut self, count: usize) -> Self {
+        self.max_active_objects = Some(count);
+        self
+    }
+
+    /// Most objects that can be checked out at once under both limits
+    #[must_use]
+    pub fn max_active(&self) -> usize {
+        self.max_active_objects
+            .map_or(self.max_pool_size, |max| max.min(self.max_pool_size))
+    }
+
+    /// The active limit that is enforced on its own: `max_active_objects`
+    /// when it is below `max_pool_size`, otherwise `None`
+    #[must_use]
+    pub fn active_limit(&self) -> Option<usize> {
+        self.max_active_objects.filter(|&max| max < self.max_pool_size)
+    }
+}
+
 /// Configuration for object pool behavior
 ///
 /// # Examples
@@ -19,12 +144,14 @@ use std::time::Duration;
 /// assert_eq!(config.max_pool_size, 100);
 /// assert_eq!(config.max_active_objects, Some(50));
 /// ```
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct PoolConfiguration<T> {
-    /// Maximum number of objects that can exist in the pool
+    /// Maximum number of objects that can exist in the pool; see
+    /// [`CapacityLimits`] for how it combines with `max_active_objects`
     pub max_pool_size: usize,
     
-    /// Maximum number of objects that can be active (checked out) simultaneously
+    /// Maximum number of objects that can be active (checked out)
+    /// simultaneously; ignored unless below `max_pool_size`
     pub max_active_objects: Option<usize>,
     
     /// Whether to validate objects when they are returned to the pool
@@ -33,14 +160,40 @@ pub struct PoolConfiguration<T> {
     /// Custom validation function
     pub validation_function: Option<fn(&T) -> bool>,
     
-    /// Timeout for async operations
+    /// Timeout for async operations; `None` waits as long as it takes
     pub operation_timeout: Option<Duration>,
+
+    /// Largest share of the wait budget by which each acquisition's
+    /// timeout is randomly lengthened or shortened
+    pub timeout_jitter: f64,
+
+    /// What acquisition does when the pool has no object to hand out
+    /// (`None` keeps the per-method defaults, see [`with_wait_on_empty`](Self::with_wait_on_empty))
+    pub wait_on_empty: Option<WaitPolicy>,
+
+    /// Most acquisitions that may wait at once
+    pub max_waiters: Option<usize>,
+
+    /// Initial delay between acquisition retries while waiting
+    pub retry_interval: Duration,
+
+    /// Upper bound for the exponentially growing retry delay
+    pub max_retry_interval: Duration,
     
     /// Time-to-live for objects (eviction policy)
     pub time_to_live: Option<Duration>,
     
     /// Idle timeout for objects (eviction policy)
     pub idle_timeout: Option<Duration>,
+
+    /// What a successful validation does to an object's time to live
+    pub ttl_refresh: TtlRefresh,
+
+    /// What happens to an object that is already expired when given back
+    pub expired_return: ExpiredReturn,
+
+    /// Custom expiry predicate, replacing the TTL and idle timeout
+    pub custom_eviction: Option<EvictionPredicate>,
     
     /// Whether to pre-populate the pool on creation
     pub warmup_size: Option<usize>,
@@ -53,6 +206,178 @@ pub struct PoolConfiguration<T> {
     
     /// Circuit breaker reset timeout
     pub circuit_breaker_timeout: Duration,
+
+    /// Which signals drive the circuit breaker
+    pub breaker_signals: BreakerSignals,
+
+    /// Factory a dynamic pool serves degraded objects from while its
+    /// circuit breaker is open
+    pub degraded_factory: Option<FallbackFactory<T>>,
+
+    /// What happens to an object whose guard was completed with an error
+    pub failure_policy: FailurePolicy,
+
+    /// Hook run on every object just before its guard is handed out
+    pub on_borrow: Option<BorrowHook<T>>,
+
+    /// Hook run on every object just before its guard is handed out, given
+    /// the caller's acquisition context
+    pub on_acquire: Option<AcquireHook<T>>,
+
+    /// Hook run after `on_acquire`, given how long the acquisition took
+    pub on_acquire_timed: Option<TimedHook<T>>,
+
+    /// Hook run on every object given back to the pool, before validation
+    pub on_return: Option<ReturnHook<T>>,
+
+    /// Hook run after `on_return`, given how long the object was held
+    pub on_return_timed: Option<TimedHook<T>>,
+
+    /// Hook run on every object a dynamic pool creates, given how long the
+    /// factory took
+    pub on_create: Option<TimedHook<T>>,
+
+    /// Hook capturing the caller's tracing context at acquisition
+    pub context_hook: Option<ContextHook>,
+
+    /// Hook receiving objects the pool discards
+    pub on_destroy: Option<DestroyHook<T>>,
+
+    /// Partition function used to bucket idle objects
+    pub partition_fn: Option<PartitionFn<T>>,
+
+    /// Number of buckets idle objects are spread over when partitioned
+    pub partition_buckets: usize,
+
+    /// Scores idle objects so acquisition prefers the best of several
+    pub selection: Option<SelectionFn<T>>,
+
+    /// Idle objects compared per acquisition when `selection` is set
+    pub selection_window: usize,
+
+    /// Maximum active objects per bulkhead category
+    pub bulkheads: HashMap<String, usize>,
+
+    /// Active-object limit shared with other pools
+    pub limit_group: Option<LimitGroup>,
+
+    /// Whether to keep per-caller-label acquisition metrics
+    pub caller_metrics: bool,
+
+    /// Record one in every this-many acquisitions in the latency histograms
+    /// and one in every this-many evictions and empty-pool hits as events
+    pub sample_every: u32,
+
+    /// Maximum factory calls per second in dynamic pools
+    pub max_creations_per_second: Option<u32>,
+
+    /// Initial pause in object creation after a factory failure
+    pub creation_backoff: Option<Duration>,
+
+    /// Upper bound of the pause after consecutive factory failures
+    pub max_creation_backoff: Duration,
+
+    /// Maximum concurrent factory calls in dynamic pools
+    pub max_pending_creations: Option<usize>,
+
+    /// Whether an empty dynamic pool creates or first waits for a return
+    pub creation_policy: CreationPolicy,
+
+    /// Whether to record who holds each checked-out object
+    pub track_holders: bool,
+
+    /// Capture a backtrace for every this-many-th acquisition of a tracked
+    /// pool
+    pub holder_backtrace_every: Option<u32>,
+
+    /// Number of recent acquisitions, returns and evictions to keep
+    pub activity_log: Option<usize>,
+
+    /// How long waiters may be starved before a warning is raised
+    pub starvation_threshold: Option<Duration>,
+
+    /// Acquisition objectives tracked as error budgets
+    pub slo: Option<SloTargets>,
+
+    /// Share of its original objects a fixed pool may fall below before its
+    /// health status warns
+    pub capacity_loss_warning: f64,
+
+    /// Objects replaced per minute before a high churn warning is raised
+    pub churn_warning: Option<u64>,
+
+    /// Estimates the bytes each object holds, enabling memory accounting
+    pub size_estimator: Option<SizeFn<T>>,
+
+    /// Bytes idle objects may retain before the health status warns
+    pub retained_memory_cap: Option<usize>,
+
+    /// What to do when the validation function or a hook panics
+    pub hook_panic_policy: HookPanicPolicy,
+
+    /// Hooks run on a background worker thread
+    pub async_hooks: Option<Arc<dyn AsyncHooks<T>>>,
+}
+
+impl<T> std::fmt::Debug for PoolConfiguration<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PoolConfiguration")
+            .field("max_pool_size", &self.max_pool_size)
+            .field("max_active_objects", &self.max_active_objects)
+            .field("validate_on_return", &self.validate_on_return)
+            .field("validation_function", &self.validation_function.is_some())
+            .field("operation_timeout", &self.operation_timeout)
+            .field("timeout_jitter", &self.timeout_jitter)
+            .field("wait_on_empty", &self.wait_on_empty)
+            .field("max_waiters", &self.max_waiters)
+            .field("retry_interval", &self.retry_interval)
+            .field("max_retry_interval", &self.max_retry_interval)
+            .field("time_to_live", &self.time_to_live)
+            .field("idle_timeout", &self.idle_timeout)
+            .field("ttl_refresh", &self.ttl_refresh)
+            .field("expired_return", &self.expired_return)
+            .field("custom_eviction", &self.custom_eviction.is_some())
+            .field("warmup_size", &self.warmup_size)
+            .field("enable_circuit_breaker", &self.enable_circuit_breaker)
+            .field("circuit_breaker_threshold", &self.circuit_breaker_threshold)
+            .field("circuit_breaker_timeout", &self.circuit_breaker_timeout)
+            .field("breaker_signals", &self.breaker_signals)
+            .field("degraded_factory", &self.degraded_factory.is_some())
+            .field("failure_policy", &self.failure_policy)
+            .field("on_borrow", &self.on_borrow.is_some())
+            .field("on_acquire", &self.on_acquire.is_some())
+            .field("on_acquire_timed", &self.on_acquire_timed.is_some())
+            .field("on_return", &self.on_return.is_some())
+            .field("on_return_timed", &self.on_return_timed.is_some())
+            .field("on_create", &self.on_create.is_some())
+            .field("context_hook", &self.context_hook.is_some())
+            .field("on_destroy", &self.on_destroy.is_some())
+            .field("partition_fn", &self.partition_fn.is_some())
+            .field("partition_buckets", &self.partition_buckets)
+            .field("selection", &self.selection.is_some())
+            .field("selection_window", &self.selection_window)
+            .field("bulkheads", &self.bulkheads)
+            .field("limit_group", &self.limit_group)
+            .field("caller_metrics", &self.caller_metrics)
+            .field("sample_every", &self.sample_every)
+            .field("max_creations_per_second", &self.max_creations_per_second)
+            .field("creation_backoff", &self.creation_backoff)
+            .field("max_creation_backoff", &self.max_creation_backoff)
+            .field("max_pending_creations", &self.max_pending_creations)
+            .field("creation_policy", &self.creation_policy)
+            .field("track_holders", &self.track_holders)
+            .field("holder_backtrace_every", &self.holder_backtrace_every)
+            .field("activity_log", &self.activity_log)
+            .field("starvation_threshold", &self.starvation_threshold)
+            .field("slo", &self.slo)
+            .field("capacity_loss_warning", &self.capacity_loss_warning)
+            .field("churn_warning", &self.churn_warning)
+            .field("size_estimator", &self.size_estimator.is_some())
+            .field("retained_memory_cap", &self.retained_memory_cap)
+            .field("hook_panic_policy", &self.hook_panic_policy)
+            .field("async_hooks", &self.async_hooks.is_some())
+            .finish()
+    }
 }
 
 impl<T> Default for PoolConfiguration<T> {
@@ -63,12 +388,55 @@ impl<T> Default for PoolConfiguration<T> {
             validate_on_return: false,
             validation_function: None,
             operation_timeout: Some(Duration::from_secs(30)),
+            timeout_jitter: 0.0,
+            wait_on_empty: None,
+            max_waiters: None,
+            retry_interval: Duration::from_millis(5),
+            max_retry_interval: Duration::from_millis(20),
             time_to_live: None,
             idle_timeout: None,
+            ttl_refresh: TtlRefresh::Never,
+            expired_return: ExpiredReturn::Evict,
+            custom_eviction: None,
             warmup_size: None,
             enable_circuit_breaker: false,
             circuit_breaker_threshold: 5,
             circuit_breaker_timeout: Duration::from_secs(60),
+            breaker_signals: BreakerSignals::Both,
+            degraded_factory: None,
+            failure_policy: FailurePolicy::Validate,
+            on_borrow: None,
+            on_acquire: None,
+            on_acquire_timed: None,
+            on_return: None,
+            on_return_timed: None,
+            on_create: None,
+            context_hook: None,
+            on_destroy: None,
+            partition_fn: None,
+            partition_buckets: 1,
+            selection: None,
+            selection_window: 1,
+            bulkheads: HashMap::new(),
+            limit_group: None,
+            caller_metrics: false,
+            sample_every: 1,
+            max_creations_per_second: None,
+            creation_backoff: None,
+            max_creation_backoff: Duration::from_secs(30),
+            max_pending_creations: None,
+            creation_policy: CreationPolicy::CreateFirst,
+            track_holders: false,
+            holder_backtrace_every: None,
+            activity_log: None,
+            starvation_threshold: None,
+            slo: None,
+            capacity_loss_warning: 0.5,
+            churn_warning: None,
+            size_estimator: None,
+            retained_memory_cap: None,
+            hook_panic_policy: HookPanicPolicy::Contain,
+            async_hooks: None,
         }
     }
 }
@@ -78,6 +446,99 @@ impl<T> PoolConfiguration<T> {
     pub fn new() -> Self {
         Self::default()
     }
+
+    /// Starting point for pools of database (or other network) connections
+    ///
+    /// Twenty connections that are recycled after 30 minutes and dropped
+    /// after 5 idle minutes, waiting up to 5 seconds for a free connection
+    /// and tripping a circuit breaker after 5 consecutive failures (30 s
+    /// cool-down). `warmup_size` is set to 2, the number of connections
+    /// worth opening up front.
+    ///
+    /// Connection liveness depends on the driver, so no validator is set;
+    /// chain [`with_validation`](Self::with_validation) to add one. Every
+    /// value can be overridden by the usual builder methods.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{PoolConfiguration, WaitPolicy};
+    /// use std::time::Duration;
+    ///
+    /// struct Conn { alive: bool }
+    ///
+    /// let config = PoolConfiguration::<Conn>::preset_db_connections()
+    ///     .with_validation(|c| c.alive)
+    ///     .with_max_pool_size(50);
+    ///
+    /// assert_eq!(config.max_pool_size, 50);
+    /// assert!(config.enable_circuit_breaker);
+    /// assert_eq!(config.wait_on_empty, Some(WaitPolicy::Wait(Duration::from_secs(5))));
+    /// ```
+    pub fn preset_db_connections() -> Self {
+        Self::new()
+            .with_max_pool_size(20)
+            .with_timeout(Duration::from_secs(5))
+            .with_wait_on_empty(WaitPolicy::Wait(Duration::from_secs(5)))
+            .with_ttl(Duration::from_secs(30 * 60))
+            .with_idle_timeout(Duration::from_secs(5 * 60))
+            .with_warmup(2)
+            .with_circuit_breaker(5, Duration::from_secs(30))
+    }
+
+    /// Starting point for pools of reusable buffers
+    ///
+    /// A large pool (256) that never waits: when no buffer is free the
+    /// caller is expected to allocate a fresh one rather than block, so
+    /// acquisition fails fast. Buffers never go stale, so there is no TTL,
+    /// idle timeout or circuit breaker.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    ///
+    /// let pool = ObjectPool::new(
+    ///     (0..4).map(|_| Vec::<u8>::with_capacity(4096)).collect(),
+    ///     PoolConfiguration::preset_buffers(),
+    /// );
+    /// let buf = pool.try_get_object().unwrap().map(|b| b.into_detached())
+    ///     .unwrap_or_else(|| Vec::with_capacity(4096));
+    /// assert!(buf.capacity() >= 4096);
+    /// ```
+    pub fn preset_buffers() -> Self {
+        Self::new()
+            .with_max_pool_size(256)
+            .with_timeout(Duration::from_secs(1))
+            .with_wait_on_empty(WaitPolicy::FailFast)
+    }
+
+    /// Starting point for pools of expensive, CPU-bound worker objects
+    /// (parsers, compressors, interpreters)
+    ///
+    /// Sized to the machine's available parallelism, since more workers than
+    /// cores only adds contention. Callers wait up to 30 seconds for a
+    /// worker; workers idle for 10 minutes are released. All of them are
+    /// worth pre-creating, so `warmup_size` equals the pool size.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::PoolConfiguration;
+    ///
+    /// let config = PoolConfiguration::<String>::preset_worker_objects();
+    /// assert!(config.max_pool_size >= 1);
+    /// assert_eq!(config.warmup_size, Some(config.max_pool_size));
+    /// ```
+    pub fn preset_worker_objects() -> Self {
+        let workers = std::thread::available_parallelism().map_or(4, |n| n.get());
+        Self::new()
+            .with_max_pool_size(workers)
+            .with_timeout(Duration::from_secs(30))
+            .with_wait_on_empty(WaitPolicy::Wait(Duration::from_secs(30)))
+            .with_idle_timeout(Duration::from_secs(10 * 60))
+            .with_warmup(workers)
+    }
     
     /// Set the maximum pool size
     ///
@@ -97,10 +558,40 @@ impl<T> PoolConfiguration<T> {
     }
     
     /// Set the maximum active objects
+    ///
+    /// Has no effect unless below the pool size; see [`CapacityLimits`].
     pub fn with_max_active_objects(mut self, count: usize) -> Self {
         self.max_active_objects = Some(count);
         self
     }
+
+    /// Set both `max_pool_size` and `max_active_objects` at once
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{CapacityLimits, PoolConfiguration};
+    ///
+    /// let config = PoolConfiguration::<i32>::new()
+    ///     .with_capacity_limits(CapacityLimits::new(10).with_max_active_objects(4));
+    ///
+    /// assert_eq!(config.max_pool_size, 10);
+    /// assert_eq!(config.max_active_objects, Some(4));
+    /// ```
+    pub fn with_capacity_limits(mut self, limits: CapacityLimits) -> Self {
+        self.max_pool_size = limits.max_pool_size;
+        self.max_active_objects = limits.max_active_objects;
+        self
+    }
+
+    /// The configured `max_pool_size` and `max_active_objects`
+    #[must_use]
+    pub fn capacity_limits(&self) -> CapacityLimits {
+        CapacityLimits {
+            max_pool_size: self.max_pool_size,
+            max_active_objects: self.max_active_objects,
+        }
+    }
     
     /// Enable validation on return
     pub fn with_validation(mut self, func: fn(&T) -> bool) -> Self {
@@ -114,19 +605,279 @@ impl<T> PoolConfiguration<T> {
         self.operation_timeout = Some(timeout);
         self
     }
+
+    /// Let async acquisitions wait as long as it takes for an object
+    ///
+    /// Clears the operation timeout, so `get_object_async` and the other
+    /// async methods never fail with `PoolError::Timeout`. Cancel the
+    /// future, or bound a single call with an
+    /// [`AcquireContext`](crate::AcquireContext) deadline, to stop waiting
+    /// earlier. A [`with_wait_on_empty`](Self::with_wait_on_empty) policy
+    /// still takes precedence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let pool = ObjectPool::new(vec![1], PoolConfiguration::new().with_no_timeout());
+    /// let held = pool.get_object().unwrap();
+    ///
+    /// let waiting = tokio::time::timeout(Duration::from_millis(50), pool.get_object_async());
+    /// assert!(waiting.await.is_err(), "still waiting, not timed out");
+    ///
+    /// drop(held);
+    /// assert!(pool.get_object_async().await.is_ok());
+    /// # }
+    /// ```
+    pub fn with_no_timeout(mut self) -> Self {
+        self.operation_timeout = None;
+        self
+    }
+
+    /// Randomise each acquisition's wait budget by up to `fraction` of it
+    /// either way
+    ///
+    /// Callers sharing one timeout that start waiting together also time
+    /// out and retry together. With a jitter of `0.1`, a 1 s wait budget
+    /// becomes anything from 900 ms to 1.1 s, drawn anew for every
+    /// acquisition, which spreads those waves out. Applies to
+    /// [`WaitPolicy::Wait`](crate::WaitPolicy::Wait) budgets, whether set
+    /// with [`with_wait_on_empty`](Self::with_wait_on_empty) or taken from
+    /// the operation timeout. `fraction` is clamped to `0.0..=1.0`; `0.0`,
+    /// the default, turns jitter off.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration, PoolError, WaitPolicy};
+    /// use std::time::Duration;
+    ///
+    /// let pool = ObjectPool::new(
+    ///     vec![1],
+    ///     PoolConfiguration::new()
+    ///         .with_wait_on_empty(WaitPolicy::Wait(Duration::from_millis(20)))
+    ///         .with_timeout_jitter(0.5),
+    /// );
+    /// let _held = pool.get_object().unwrap();
+    ///
+    /// match pool.get_object() {
+    ///     Err(PoolError::Timeout(budget, _)) => {
+    ///         assert!(budget >= Duration::from_millis(10) && budget <= Duration::from_millis(30));
+    ///     }
+    ///     other => panic!("expected a timeout, got {other:?}"),
+    /// }
+    /// ```
+    pub fn with_timeout_jitter(mut self, fraction: f64) -> Self {
+        self.timeout_jitter = fraction.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set operation timeout from a human-readable duration such as
+    /// `"250ms"` or `"1m 30s"`
+    ///
+    /// Handy for values read from configuration files. Accepts one or more
+    /// `<integer><unit>` terms, which are summed; units are `ns`, `us`,
+    /// `ms`, `s`, `m`, `h` and `d` (plus long forms like `min` or `hours`).
+    /// Fails with `PoolError::InvalidConfiguration` on malformed input. With
+    /// the `serde` feature, `deserialize_duration` reads the same format into
+    /// `Duration` fields of a deserialized configuration.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{PoolConfiguration, PoolError};
+    /// use std::time::Duration;
+    ///
+    /// let config = PoolConfiguration::<i32>::new()
+    ///     .with_timeout_str("250ms")?
+    ///     .with_ttl_str("1h 30m")?
+    ///     .with_idle_timeout_str("5min")?;
+    ///
+    /// assert_eq!(config.operation_timeout, Some(Duration::from_millis(250)));
+    /// assert_eq!(config.time_to_live, Some(Duration::from_secs(5400)));
+    /// assert!(matches!(
+    ///     PoolConfiguration::<i32>::new().with_ttl_str("soon"),
+    ///     Err(PoolError::InvalidConfiguration(_))
+    /// ));
+    /// # Ok::<(), PoolError>(())
+    /// ```
+    pub fn with_timeout_str(self, timeout: &str) -> PoolResult<Self> {
+        Ok(self.with_timeout(parse_duration(timeout)?))
+    }
     
+    /// Choose what acquisition does when no object is available
+    ///
+    /// Without a policy, `get_object` fails fast and `get_object_async` waits
+    /// for up to the operation timeout. With a policy, both follow it — so a
+    /// pool either always fails fast or always waits, regardless of which
+    /// acquisition method a call site happens to use.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration, PoolError, WaitPolicy};
+    ///
+    /// let pool = ObjectPool::new(
+    ///     vec![1],
+    ///     PoolConfiguration::new().with_wait_on_empty(WaitPolicy::FailFast),
+    /// );
+    /// let _held = pool.get_object().unwrap();
+    ///
+    /// # tokio::runtime::Runtime::new().unwrap().block_on(async {
+    /// assert!(matches!(pool.get_object_async().await, Err(PoolError::PoolEmpty)));
+    /// # });
+    /// ```
+    pub fn with_wait_on_empty(mut self, policy: WaitPolicy) -> Self {
+        self.wait_on_empty = Some(policy);
+        self
+    }
+
+    /// Set the delay between acquisition retries while waiting
+    ///
+    /// The delay starts at `initial` and doubles on every retry up to `max`.
+    /// Async waiters additionally retry as soon as an object is returned, so
+    /// for them the delay only bounds how long a change that is not signalled
+    /// (for example the circuit breaker closing) can go unnoticed. Raising
+    /// `max` keeps large queryable pools from being rescanned needlessly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::PoolConfiguration;
+    /// use std::time::Duration;
+    ///
+    /// let config = PoolConfiguration::<i32>::new()
+    ///     .with_retry_backoff(Duration::from_millis(10), Duration::from_secs(1));
+    ///
+    /// assert_eq!(config.retry_interval, Duration::from_millis(10));
+    /// assert_eq!(config.max_retry_interval, Duration::from_secs(1));
+    /// ```
+    pub fn with_retry_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.retry_interval = initial;
+        self.max_retry_interval = max.max(initial);
+        self
+    }
+
+    pub(crate) fn retry_backoff(&self) -> Backoff {
+        Backoff {
+            initial: self.retry_interval,
+            max: self.max_retry_interval,
+        }
+    }
+
+    /// Policy for one blocking acquisition, jittered.
+    pub(crate) fn blocking_wait_policy(&self) -> WaitPolicy {
+        self.wait_on_empty
+            .unwrap_or(WaitPolicy::FailFast)
+            .jittered(self.timeout_jitter)
+    }
+
+    /// Policy for one async acquisition, jittered.
+    pub(crate) fn async_wait_policy(&self) -> WaitPolicy {
+        self.wait_on_empty
+            .unwrap_or_else(|| self.operation_timeout.map_or(WaitPolicy::WaitForever, WaitPolicy::Wait))
+            .jittered(self.timeout_jitter)
+    }
+
+    /// Limit how many acquisitions may wait for an object at once
+    ///
+    /// An acquisition that would have to wait while `max` others already
+    /// are fails immediately with `PoolError::QueueFull` instead, so
+    /// overload is shed at the edge rather than surfacing later as
+    /// timeouts. Rejections are counted in
+    /// [`PoolMetrics::waiter_rejections`](crate::PoolMetrics::waiter_rejections).
+    /// Acquisitions that do not wait, under
+    /// [`WaitPolicy::FailFast`](crate::WaitPolicy::FailFast), are never
+    /// rejected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration, PoolError, WaitPolicy};
+    /// use std::time::Duration;
+    ///
+    /// let pool = ObjectPool::new(
+    ///     vec![1],
+    ///     PoolConfiguration::new()
+    ///         .with_wait_on_empty(WaitPolicy::Wait(Duration::from_secs(5)))
+    ///         .with_max_waiters(0),
+    /// );
+    /// let _held = pool.get_object().unwrap();
+    ///
+    /// assert!(matches!(pool.get_object(), Err(PoolError::QueueFull)));
+    /// assert_eq!(pool.get_metrics().waiter_rejections, 1);
+    /// ```
+    pub fn with_max_waiters(mut self, max: usize) -> Self {
+        self.max_waiters = Some(max);
+        self
+    }
+
     /// Set time-to-live for objects
     pub fn with_ttl(mut self, ttl: Duration) -> Self {
         self.time_to_live = Some(ttl);
         self
     }
+
+    /// Set time-to-live from a human-readable duration. See
+    /// [`with_timeout_str`](Self::with_timeout_str) for the format.
+    pub fn with_ttl_str(self, ttl: &str) -> PoolResult<Self> {
+        Ok(self.with_ttl(parse_duration(ttl)?))
+    }
     
     /// Set idle timeout for objects
     pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
         self.idle_timeout = Some(timeout);
         self
     }
+
+    /// Set idle timeout from a human-readable duration. See
+    /// [`with_timeout_str`](Self::with_timeout_str) for the format.
+    pub fn with_idle_timeout_str(self, timeout: &str) -> PoolResult<Self> {
+        Ok(self.with_idle_timeout(parse_duration(timeout)?))
+    }
     
+    /// Have successful validations refresh objects' time to live (default:
+    /// [`TtlRefresh::Never`])
+    ///
+    /// Keeps healthy long-lived objects pooled while ones that stop
+    /// validating still expire. See [`TtlRefresh`].
+    pub fn with_ttl_refresh(mut self, refresh: TtlRefresh) -> Self {
+        self.ttl_refresh = refresh;
+        self
+    }
+
+    /// Decide what happens to objects that are already expired when given
+    /// back (default: [`ExpiredReturn::Evict`])
+    ///
+    /// Makes the outcome of a return racing with an eviction sweep
+    /// deterministic: the object is either destroyed on the spot or pooled
+    /// with a fresh lifetime. See [`ExpiredReturn`].
+    pub fn with_expired_return(mut self, expired_return: ExpiredReturn) -> Self {
+        self.expired_return = expired_return;
+        self
+    }
+
+    /// Expire objects for which `predicate` returns `true`
+    ///
+    /// The predicate sees each object's age, idle time and use count (see
+    /// [`ObjectStats`]) and is checked wherever expiry is, that is when an
+    /// idle object is about to be handed out and in
+    /// [`evict_expired`](crate::ObjectPool::evict_expired). It replaces
+    /// [`with_ttl`](Self::with_ttl) and
+    /// [`with_idle_timeout`](Self::with_idle_timeout), whose limits it can
+    /// express itself.
+    pub fn with_custom_eviction<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&ObjectStats) -> bool + Send + Sync + 'static,
+    {
+        self.custom_eviction = Some(Arc::new(predicate));
+        self
+    }
+
     /// Set warm-up size
     pub fn with_warmup(mut self, size: usize) -> Self {
         self.warmup_size = Some(size);
@@ -153,25 +904,949 @@ impl<T> PoolConfiguration<T> {
         self.circuit_breaker_timeout = timeout;
         self
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    #[test]
-    fn default_values_are_sensible() {
-        let cfg = PoolConfiguration::<i32>::default();
-        assert_eq!(cfg.max_pool_size, 100);
-        assert_eq!(cfg.max_active_objects, None);
-        assert!(!cfg.validate_on_return);
-        assert!(cfg.validation_function.is_none());
-        assert!(cfg.operation_timeout.is_some());
-        assert!(cfg.time_to_live.is_none());
-        assert!(cfg.idle_timeout.is_none());
-        assert!(cfg.warmup_size.is_none());
-        assert!(!cfg.enable_circuit_breaker);
-        assert_eq!(cfg.circuit_breaker_threshold, 5);
+    /// Choose which signals drive the circuit breaker
+    ///
+    /// Defaults to [`BreakerSignals::Both`]. Use
+    /// [`BreakerSignals::Operations`] to make the breaker reflect backend
+    /// health only, so a busy pool never trips it.
+    pub fn with_breaker_signals(mut self, signals: BreakerSignals) -> Self {
+        self.breaker_signals = signals;
+        self
+    }
+
+    /// Keep a dynamic pool serving while its circuit breaker is open, with
+    /// objects from a fallback `factory`
+    ///
+    /// Instead of failing with `PoolError::CircuitBreakerOpen`, acquisitions
+    /// get an object made by `factory` — a read-only replica connection, a
+    /// cache-only client — whose guard reports
+    /// [`ObjectOrigin::Degraded`](crate::ObjectOrigin::Degraded) through
+    /// [`origin`](crate::PooledObject::origin), so the service keeps partial
+    /// functionality. Degraded objects are never pooled: each is dropped
+    /// when given back, so primary objects take over as soon as the breaker
+    /// closes. At most `max_pool_size` are out at once; beyond that, or if
+    /// `factory` fails, acquisitions fail with `CircuitBreakerOpen` as
+    /// before. They are counted in
+    /// [`PoolMetrics::degraded_objects`](crate::PoolMetrics::degraded_objects).
+    /// Needs [`with_circuit_breaker`](Self::with_circuit_breaker); fixed
+    /// pools ignore it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{DynamicObjectPool, ObjectOrigin, PoolConfiguration};
+    /// use std::time::Duration;
+    ///
+    /// let pool = DynamicObjectPool::try_new(
+    ///     || Err::<&str, _>("primary is down"),
+    ///     PoolConfiguration::new()
+    ///         .with_circuit_breaker(2, Duration::from_secs(30))
+    ///         .with_degraded_factory(|| Ok::<_, String>("replica")),
+    /// );
+    ///
+    /// // The primary factory fails until the breaker opens...
+    /// assert!(pool.get_object().is_err());
+    /// assert!(pool.get_object().is_err());
+    ///
+    /// // ...then the replica takes over.
+    /// let conn = pool.get_object().unwrap();
+    /// assert_eq!((*conn, conn.origin()), ("replica", ObjectOrigin::Degraded));
+    /// ```
+    pub fn with_degraded_factory<F, E>(mut self, factory: F) -> Self
+    where
+        F: Fn() -> Result<T, E> + Send + Sync + 'static,
+        E: std::fmt::Display,
+    {
+        self.degraded_factory = Some(Arc::new(move || factory().map_err(|err| err.to_string())));
+        self
+    }
+
+    /// Choose what happens to an object whose guard was
+    /// [completed](crate::PooledObject::complete) with an error
+    ///
+    /// Defaults to [`FailurePolicy::Validate`].
+    pub fn with_failure_policy(mut self, policy: FailurePolicy) -> Self {
+        self.failure_policy = policy;
+        self
+    }
+
+    /// Run `hook` on every object just before it is handed out
+    ///
+    /// Unlike validation, which only accepts or rejects an object, the hook
+    /// can prepare it for the caller — refresh an auth token, reset a
+    /// per-request deadline, bump a counter. It runs on every acquisition
+    /// path, after the object has been selected and before the guard is
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    ///
+    /// let config = PoolConfiguration::new().with_on_borrow(|uses: &mut u32| *uses += 1);
+    /// let pool = ObjectPool::new(vec![0u32], config);
+    ///
+    /// drop(pool.get_object().unwrap());
+    /// let obj = pool.get_object().unwrap();
+    /// assert_eq!(*obj, 2);
+    /// ```
+    pub fn with_on_borrow<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&mut T) + Send + Sync + 'static,
+    {
+        self.on_borrow = Some(Arc::new(hook));
+        self
+    }
+
+    /// Run `hook` on every object just before it is handed out, together
+    /// with the caller's [`AcquireContext`]
+    ///
+    /// Runs after the [`on_borrow`](Self::with_on_borrow) hook. Acquisitions
+    /// made with [`ObjectPool::get_object_with`](crate::ObjectPool::get_object_with)
+    /// pass the caller's context; all others pass an empty one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{AcquireContext, ObjectPool, PoolConfiguration};
+    ///
+    /// struct Session { tenant: String }
+    ///
+    /// let config = PoolConfiguration::new().with_on_acquire(|s: &mut Session, ctx: &AcquireContext| {
+    ///     s.tenant = ctx.extension("tenant").unwrap_or("default").to_string();
+    /// });
+    /// let pool = ObjectPool::new(vec![Session { tenant: String::new() }], config);
+    ///
+    /// let session = pool
+    ///     .get_object_with(AcquireContext::new().with_extension("tenant", "acme"))
+    ///     .unwrap();
+    /// assert_eq!(session.tenant, "acme");
+    /// ```
+    pub fn with_on_acquire<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&mut T, &AcquireContext) + Send + Sync + 'static,
+    {
+        self.on_acquire = Some(Arc::new(hook));
+        self
+    }
+
+    /// Run `hook` on every object given back to the pool
+    ///
+    /// The place to reset an object for its next user while it is still
+    /// owned by the caller's thread: clear a buffer, roll back an open
+    /// transaction. Runs before [validation](Self::with_validation), on
+    /// returns that put the object back; objects being destroyed instead
+    /// (the pool is closed or was invalidated) skip it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    ///
+    /// let config = PoolConfiguration::new().with_on_return(|buf: &mut Vec<u8>| buf.clear());
+    /// let pool = ObjectPool::new(vec![Vec::with_capacity(64)], config);
+    ///
+    /// pool.get_object().unwrap().extend_from_slice(b"request");
+    /// let buf = pool.get_object().unwrap();
+    /// assert!(buf.is_empty());
+    /// assert!(buf.capacity() >= 64);
+    /// ```
+    pub fn with_on_return<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&mut T) + Send + Sync + 'static,
+    {
+        self.on_return = Some(Arc::new(hook));
+        self
+    }
+
+    /// Run `hook` on every object just before it is handed out, with how
+    /// long the acquisition took
+    ///
+    /// Runs after the [`on_acquire`](Self::with_on_acquire) hook.
+    /// [`HookTiming::wait`] covers the whole acquisition: waiting for an
+    /// object, and creating one in a dynamic pool.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{HookTiming, ObjectPool, PoolConfiguration};
+    /// use std::time::Duration;
+    ///
+    /// let config = PoolConfiguration::new().with_on_acquire_timed(|_: &mut u32, timing: &HookTiming| {
+    ///     assert!(timing.wait.unwrap() < Duration::from_secs(1));
+    /// });
+    /// let pool = ObjectPool::new(vec![1u32], config);
+    /// let _obj = pool.get_object().unwrap();
+    /// ```
+    pub fn with_on_acquire_timed<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&mut T, &HookTiming) + Send + Sync + 'static,
+    {
+        self.on_acquire_timed = Some(Arc::new(hook));
+        self
+    }
+
+    /// Run `hook` on every object given back to the pool, with how long it
+    /// was checked out
+    ///
+    /// Runs after the [`on_return`](Self::with_on_return) hook, on the same
+    /// returns. See [`HookTiming`] for an example.
+    pub fn with_on_return_timed<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&mut T, &HookTiming) + Send + Sync + 'static,
+    {
+        self.on_return_timed = Some(Arc::new(hook));
+        self
+    }
+
+    /// Run `hook` on every object a dynamic pool's factory creates, with how
+    /// long the factory took
+    ///
+    /// Covers creation on acquisition and during warm-up. A panic in the
+    /// hook destroys the object and fails the creation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{DynamicObjectPool, HookTiming, PoolConfiguration};
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// let created = Arc::new(AtomicUsize::new(0));
+    /// let counter = Arc::clone(&created);
+    /// let pool = DynamicObjectPool::new(
+    ///     || 0u8,
+    ///     PoolConfiguration::new().with_on_create(move |_: &mut u8, timing: &HookTiming| {
+    ///         assert!(timing.creation.is_some());
+    ///         counter.fetch_add(1, Ordering::Relaxed);
+    ///     }),
+    /// );
+    ///
+    /// pool.warmup(2).unwrap();
+    /// assert_eq!(created.load(Ordering::Relaxed), 2);
+    /// ```
+    pub fn with_on_create<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&mut T, &HookTiming) + Send + Sync + 'static,
+    {
+        self.on_create = Some(Arc::new(hook));
+        self
+    }
+
+    /// Bucket idle objects by `partition` so hinted queries scan less
+    ///
+    /// Each idle object is stored in bucket `partition(obj) % buckets`.
+    /// [`QueryableObjectPool::get_object_with_hint`](crate::QueryableObjectPool::get_object_with_hint)
+    /// then only scans the bucket for the given partition, cutting the cost
+    /// of a query from O(n) to roughly O(n / buckets). The function is called
+    /// whenever an object enters the idle queue, so it should be cheap.
+    ///
+    /// Every bucket is sized to hold the whole pool, so memory for the idle
+    /// queue grows with `buckets × max_pool_size`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{PoolConfiguration, QueryableObjectPool};
+    ///
+    /// struct Connection { shard: u64 }
+    ///
+    /// let config = PoolConfiguration::new().with_partitions(8, |c: &Connection| c.shard);
+    /// let pool = QueryableObjectPool::new(
+    ///     (0..32).map(|shard| Connection { shard }).collect(),
+    ///     config,
+    /// );
+    ///
+    /// let conn = pool.get_object_with_hint(|c| c.shard == 12, 12).unwrap();
+    /// assert_eq!(conn.shard, 12);
+    /// ```
+    pub fn with_partitions<F>(mut self, buckets: usize, partition: F) -> Self
+    where
+        F: Fn(&T) -> u64 + Send + Sync + 'static,
+    {
+        self.partition_buckets = buckets.max(1);
+        self.partition_fn = Some(Arc::new(partition));
+        self
+    }
+
+    /// Hand out the best-scoring of up to `window` idle objects instead of
+    /// the longest idle one
+    ///
+    /// `score` sees each candidate and its
+    /// [health](crate::PooledObject::health), as reported through
+    /// [`PooledObject::set_health`](crate::PooledObject::set_health), and
+    /// ties go to the longer idle object. Candidates are taken out of the
+    /// idle queue one at a time and the losers put back, so at most two are
+    /// out at once. Each acquisition then costs up to `window` queue
+    /// operations and `score` calls; keep both small. Applies to
+    /// `get_object` and its variants, not to queries of a
+    /// [`QueryableObjectPool`](crate::QueryableObjectPool) or
+    /// [`try_get_object_fast`](crate::ObjectPool::try_get_object_fast).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    ///
+    /// struct Connection {
+    ///     recent_errors: u32,
+    /// }
+    ///
+    /// let pool = ObjectPool::new(
+    ///     vec![Connection { recent_errors: 5 }, Connection { recent_errors: 0 }],
+    ///     PoolConfiguration::new().with_selection(4, |c: &Connection, _| -f64::from(c.recent_errors)),
+    /// );
+    ///
+    /// assert_eq!(pool.get_object().unwrap().recent_errors, 0);
+    /// ```
+    pub fn with_selection<F>(mut self, window: usize, score: F) -> Self
+    where
+        F: Fn(&T, f64) -> f64 + Send + Sync + 'static,
+    {
+        self.selection_window = window.max(1);
+        self.selection = Some(Arc::new(score));
+        self
+    }
+
+    /// Hand out the healthiest of up to `window` idle objects, by the
+    /// scores reported through
+    /// [`PooledObject::set_health`](crate::PooledObject::set_health)
+    ///
+    /// Shorthand for [`with_selection`](Self::with_selection) scoring by
+    /// health alone.
+    pub fn with_health_selection(self, window: usize) -> Self {
+        self.with_selection(window, |_, health| health)
+    }
+
+    /// Reserve at most `max_active` objects for acquisitions in `category`
+    ///
+    /// A bulkhead stops one class of traffic from exhausting the pool for
+    /// everyone else. Objects acquired through
+    /// [`ObjectPool::get_object_in`](crate::ObjectPool::get_object_in) count
+    /// against their category's limit until they are returned; once the limit
+    /// is reached, further acquisitions in that category fail with
+    /// [`PoolError::BulkheadFull`](crate::PoolError::BulkheadFull) while other
+    /// categories are unaffected. Plain `get_object` is not subject to any
+    /// bulkhead. Calling this again for the same category replaces its limit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration, PoolError};
+    ///
+    /// let config = PoolConfiguration::new()
+    ///     .with_bulkhead("reads", 3)
+    ///     .with_bulkhead("writes", 1);
+    /// let pool = ObjectPool::new(vec![1, 2, 3, 4], config);
+    ///
+    /// let _write = pool.get_object_in("writes").unwrap();
+    /// assert!(matches!(pool.get_object_in("writes"), Err(PoolError::BulkheadFull(_))));
+    /// assert!(pool.get_object_in("reads").is_ok());
+    /// ```
+    pub fn with_bulkhead(mut self, category: impl Into<String>, max_active: usize) -> Self {
+        self.bulkheads.insert(category.into(), max_active);
+        self
+    }
+
+    /// Count this pool's checked-out objects against a [`LimitGroup`]
+    /// shared with other pools
+    ///
+    /// Applies on top of [`max_active_objects`](Self::with_max_active_objects).
+    /// See [`LimitGroup`] for an example.
+    pub fn with_limit_group(mut self, group: LimitGroup) -> Self {
+        self.limit_group = Some(group);
+        self
+    }
+
+    /// Break metrics down by caller
+    ///
+    /// Acquisitions made with
+    /// [`ObjectPool::get_object_with`](crate::ObjectPool::get_object_with)
+    /// using a labelled [`AcquireContext`] are counted per label —
+    /// acquisitions, timeouts, and how long objects were held — in
+    /// [`PoolMetrics::by_caller`](crate::PoolMetrics::by_caller) and as
+    /// Prometheus series with a `caller` label. Use a small, fixed set of
+    /// labels: each distinct label is kept for the lifetime of the pool.
+    ///
+    /// See [`CallerMetrics`](crate::CallerMetrics) for an example.
+    pub fn with_caller_metrics(mut self) -> Self {
+        self.caller_metrics = true;
+        self
+    }
+
+    /// Sample the expensive parts of observability, keeping one in every
+    /// `every` occurrences
+    ///
+    /// Applies to the wait and hold time histograms behind
+    /// [`tuning_advice`](crate::ObjectPool::tuning_advice) and to
+    /// [`PoolEvent::Evicted`](crate::PoolEvent::Evicted) and
+    /// [`PoolEvent::Empty`](crate::PoolEvent::Empty) on
+    /// [`events`](crate::ObjectPool::events), and so to anything logging
+    /// them. Counters in [`PoolMetrics`](crate::PoolMetrics) stay exact, as
+    /// do circuit breaker events, warnings and return errors. `0` and `1`
+    /// keep everything.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    ///
+    /// let pool = ObjectPool::new(vec![1], PoolConfiguration::new().with_sampling(10));
+    /// let mut events = pool.events();
+    /// let _held = pool.get_object().unwrap();
+    /// for _ in 0..20 {
+    ///     assert!(pool.try_get_object().unwrap().is_none());
+    /// }
+    ///
+    /// assert_eq!(pool.get_metrics().pool_empty_events, 20);
+    /// assert_eq!(std::iter::from_fn(|| events.try_recv().ok()).count(), 2);
+    /// ```
+    pub fn with_sampling(mut self, every: u32) -> Self {
+        self.sample_every = every.max(1);
+        self
+    }
+
+    /// Record who holds each checked-out object
+    ///
+    /// Makes [`ObjectPool::current_holders`](crate::ObjectPool::current_holders)
+    /// list every checked-out object with its hold time and the label of
+    /// the [`AcquireContext`] it was acquired with — the first thing to look
+    /// at when a pool runs dry. Costs one map insert and removal per
+    /// acquisition.
+    ///
+    /// See [`Holder`](crate::Holder) for an example.
+    pub fn with_holder_tracking(mut self) -> Self {
+        self.track_holders = true;
+        self
+    }
+
+    /// Record who holds each checked-out object, with a backtrace of the
+    /// acquiring code for one in every `every` acquisitions
+    ///
+    /// Capturing a backtrace is expensive, so sample sparingly in
+    /// production. Implies [`with_holder_tracking`](Self::with_holder_tracking).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    ///
+    /// let pool = ObjectPool::new(vec![1], PoolConfiguration::new().with_holder_backtraces(1));
+    /// let _obj = pool.get_object().unwrap();
+    ///
+    /// assert!(pool.current_holders()[0].backtrace.is_some());
+    /// ```
+    pub fn with_holder_backtraces(mut self, every: u32) -> Self {
+        self.track_holders = true;
+        self.holder_backtrace_every = Some(every);
+        self
+    }
+
+    /// Keep the last `capacity` acquisitions, returns and evictions in
+    /// memory
+    ///
+    /// [`recent_activity`](crate::ObjectPool::recent_activity) lists them
+    /// with their time, object, caller label, outcome and wait, for looking
+    /// back at an incident without tracing infrastructure. Recording takes a
+    /// short lock per event. See [`ActivityRecord`](crate::ActivityRecord)
+    /// for an example.
+    pub fn with_activity_log(mut self, capacity: usize) -> Self {
+        self.activity_log = Some(capacity);
+        self
+    }
+
+    /// Warn when acquisitions starve
+    ///
+    /// Publishes a [`PoolWarning::Starvation`](crate::PoolWarning::Starvation)
+    /// on [`ObjectPool::warnings`](crate::ObjectPool::warnings) when an
+    /// acquisition has waited longer than `threshold` while every object is
+    /// checked out and none has been returned for at least as long. That
+    /// pattern usually means application code holds one object while waiting
+    /// for another — a deadlock the pool cannot break on its own. Each stall
+    /// is reported once; the next return re-arms the detector.
+    ///
+    /// Only waiting acquisitions are watched, so this is useful together
+    /// with a [`WaitPolicy`] other than `FailFast`. Checks run when a waiter
+    /// retries; no background thread is used. See
+    /// [`PoolWarning`](crate::PoolWarning) for an example.
+    pub fn with_starvation_detection(mut self, threshold: Duration) -> Self {
+        self.starvation_threshold = Some(threshold);
+        self
+    }
+
+    /// Track acquisitions against latency and timeout objectives
+    ///
+    /// The pool keeps a rolling window of its acquisitions, reports how
+    /// fast each objective's error budget burns through
+    /// [`slo_status`](crate::ObjectPool::slo_status), and publishes
+    /// [`PoolEvent::SloBudgetExhausted`](crate::PoolEvent::SloBudgetExhausted)
+    /// when one runs out. See [`SloTargets`] for an example.
+    pub fn with_slo(mut self, targets: SloTargets) -> Self {
+        self.slo = Some(targets);
+        self
+    }
+
+    /// Warn when a fixed pool has lost objects
+    ///
+    /// A fixed [`ObjectPool`](crate::ObjectPool) cannot replace objects it
+    /// destroys — because they expired, failed validation or were
+    /// discarded — or that were detached, so it shrinks for good.
+    /// [`get_health_status`](crate::ObjectPool::get_health_status) warns
+    /// once fewer than `fraction` of the original objects are left (by
+    /// default half); `0.0` turns the warning off. The loss is always
+    /// reported in [`PoolMetrics::capacity_lost`](crate::PoolMetrics::capacity_lost).
+    /// Dynamic pools replace lost objects and never warn.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    ///
+    /// let pool = ObjectPool::new(
+    ///     vec![1, 2, 3, 4],
+    ///     PoolConfiguration::new().with_capacity_loss_warning(0.9),
+    /// );
+    /// pool.get_object().unwrap().discard();
+    ///
+    /// assert_eq!(pool.get_metrics().capacity_lost, 1);
+    /// assert!(pool.get_health_status().warnings.iter().any(|w| w.contains("3 of 4")));
+    /// ```
+    pub fn with_capacity_loss_warning(mut self, fraction: f64) -> Self {
+        self.capacity_loss_warning = fraction;
+        self
+    }
+
+    /// Warn when objects are constantly replaced
+    ///
+    /// Counts the objects destroyed and created over a rolling minute. Once
+    /// more than `per_minute` have been replaced — a destruction matched by
+    /// a creation, so warming up or shrinking alone does not count —
+    /// [`get_health_status`](crate::ObjectPool::get_health_status) warns and
+    /// a [`PoolWarning::HighChurn`](crate::PoolWarning::HighChurn) is
+    /// published on [`ObjectPool::warnings`](crate::ObjectPool::warnings),
+    /// once per episode. High churn usually means a TTL that is too short or
+    /// validation that keeps failing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{DynamicObjectPool, PoolConfiguration, PoolWarning};
+    ///
+    /// let pool = DynamicObjectPool::new(
+    ///     || 0,
+    ///     PoolConfiguration::new().with_max_pool_size(1).with_churn_warning(2),
+    /// );
+    /// let mut warnings = pool.warnings();
+    ///
+    /// for _ in 0..3 {
+    ///     pool.get_object().unwrap().discard();
+    /// }
+    ///
+    /// assert!(matches!(warnings.try_recv(), Ok(PoolWarning::HighChurn { threshold: 2, .. })));
+    /// assert!(pool.get_health_status().warnings.iter().any(|w| w.contains("churn")));
+    /// ```
+    pub fn with_churn_warning(mut self, per_minute: u64) -> Self {
+        self.churn_warning = Some(per_minute);
+        self
+    }
+
+    /// Account for the memory held by the pool's objects, as estimated by
+    /// `estimate`
+    ///
+    /// Objects are measured as they go idle and as they are handed out, and
+    /// the sums are reported as
+    /// [`PoolMetrics::idle_bytes`](crate::PoolMetrics::idle_bytes) and
+    /// [`PoolMetrics::active_bytes`](crate::PoolMetrics::active_bytes), so
+    /// growth while an object is checked out shows once it comes back.
+    /// Keep `estimate` cheap: it runs on every acquisition and return.
+    /// Accounting takes a lock, which rules out
+    /// [`try_get_object_fast`](crate::ObjectPool::try_get_object_fast). For
+    /// types implementing [`SizedPoolable`](crate::SizedPoolable), see
+    /// [`with_memory_accounting`](Self::with_memory_accounting).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    ///
+    /// struct Image { pixels: Vec<u8> }
+    ///
+    /// let pool = ObjectPool::new(
+    ///     vec![Image { pixels: vec![0; 1000] }, Image { pixels: vec![0; 500] }],
+    ///     PoolConfiguration::new().with_size_estimator(|image: &Image| image.pixels.len()),
+    /// );
+    /// assert_eq!(pool.get_metrics().idle_bytes, 1500);
+    ///
+    /// let image = pool.get_object().unwrap();
+    /// let metrics = pool.get_metrics();
+    /// assert_eq!((metrics.idle_bytes, metrics.active_bytes), (500, 1000));
+    /// # drop(image);
+    /// ```
+    pub fn with_size_estimator<F>(mut self, estimate: F) -> Self
+    where
+        F: Fn(&T) -> usize + Send + Sync + 'static,
+    {
+        self.size_estimator = Some(Arc::new(estimate));
+        self
+    }
+
+    /// Warn when idle objects retain more than `bytes`
+    ///
+    /// [`get_health_status`](crate::ObjectPool::get_health_status) warns
+    /// while the idle objects' estimated size exceeds the cap, a sign that
+    /// a few oversized buffers are pinning memory in the pool. Needs memory
+    /// accounting, enabled with
+    /// [`with_size_estimator`](Self::with_size_estimator) or
+    /// [`with_memory_accounting`](Self::with_memory_accounting).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{BytesPool, PoolConfiguration};
+    ///
+    /// let buffers = BytesPool::new(
+    ///     Vec::new,
+    ///     PoolConfiguration::new()
+    ///         .with_max_pool_size(4)
+    ///         .with_memory_accounting()
+    ///         .with_retained_memory_cap(64 * 1024),
+    /// );
+    ///
+    /// buffers.get_object().unwrap().reserve(1024 * 1024);
+    ///
+    /// let health = buffers.get_health_status();
+    /// assert!(health.warnings.iter().any(|w| w.contains("Retained memory")));
+    /// ```
+    pub fn with_retained_memory_cap(mut self, bytes: usize) -> Self {
+        self.retained_memory_cap = Some(bytes);
+        self
+    }
+
+    /// Choose what happens when the validation function or a hook panics
+    ///
+    /// See [`HookPanicPolicy`] for the default and an example.
+    pub fn with_hook_panic_policy(mut self, policy: HookPanicPolicy) -> Self {
+        self.hook_panic_policy = policy;
+        self
+    }
+
+    /// Let dynamic pools call their factory at most `per_second` times a
+    /// second
+    ///
+    /// Smooths out bursts of creation, for example a thundering herd of
+    /// connection attempts after a restart. A creation refused by the limit
+    /// fails with
+    /// [`PoolError::CreationThrottled`](crate::PoolError::CreationThrottled);
+    /// waiting acquisitions keep retrying until their timeout. Warmup is not
+    /// limited. Zero disables the limit.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{DynamicObjectPool, PoolConfiguration, PoolError};
+    ///
+    /// let pool = DynamicObjectPool::new(|| 0, PoolConfiguration::new().with_creation_rate_limit(1));
+    ///
+    /// let _first = pool.get_object().unwrap();
+    /// assert!(matches!(pool.get_object(), Err(PoolError::CreationThrottled)));
+    /// assert_eq!(pool.get_metrics().creations_throttled, 1);
+    /// ```
+    pub fn with_creation_rate_limit(mut self, per_second: u32) -> Self {
+        self.max_creations_per_second = Some(per_second);
+        self
+    }
+
+    /// Pause object creation in dynamic pools after the factory fails
+    ///
+    /// After a failure no creation is attempted for `initial`; each further
+    /// consecutive failure doubles the pause, up to `max`. A successful
+    /// creation resets it. Acquisitions during the pause fail with
+    /// [`PoolError::CreationThrottled`](crate::PoolError::CreationThrottled)
+    /// instead of calling a factory that is likely to fail again, while idle
+    /// objects are still handed out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{DynamicObjectPool, PoolConfiguration, PoolError};
+    /// use std::time::Duration;
+    ///
+    /// let pool = DynamicObjectPool::try_new(
+    ///     || Err::<u32, _>("backend down"),
+    ///     PoolConfiguration::new()
+    ///         .with_creation_backoff(Duration::from_secs(1), Duration::from_secs(30)),
+    /// );
+    ///
+    /// assert!(matches!(pool.get_object(), Err(PoolError::CreationFailed(_))));
+    /// assert!(matches!(pool.get_object(), Err(PoolError::CreationThrottled)));
+    /// ```
+    pub fn with_creation_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.creation_backoff = Some(initial);
+        self.max_creation_backoff = max.max(initial);
+        self
+    }
+
+    /// Let at most `max` factory calls run at once in dynamic pools
+    ///
+    /// Without a cap, fifty callers hitting an empty pool together make
+    /// fifty factory calls. With one, only `max` of them create objects; the
+    /// rest do not call the factory but wait, under the pool's
+    /// [`WaitPolicy`], for an object to be returned or for a creation to
+    /// finish so they can start the next one. Under
+    /// [`WaitPolicy::FailFast`] they fail with
+    /// [`PoolError::CreationPending`](crate::PoolError::CreationPending)
+    /// instead. Values below 1 are treated as 1.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{DynamicObjectPool, PoolConfiguration};
+    ///
+    /// let pool = DynamicObjectPool::new(|| 0, PoolConfiguration::new().with_max_pending_creations(4));
+    ///
+    /// let _obj = pool.get_object().unwrap();
+    /// assert_eq!(pool.pending_creations(), 0);
+    /// ```
+    pub fn with_max_pending_creations(mut self, max: usize) -> Self {
+        self.max_pending_creations = Some(max.max(1));
+        self
+    }
+
+    /// Choose between creating and waiting for a returned object when a
+    /// dynamic pool is empty
+    ///
+    /// See [`CreationPolicy`] for an example.
+    pub fn with_creation_policy(mut self, policy: CreationPolicy) -> Self {
+        self.creation_policy = policy;
+        self
+    }
+
+    pub(crate) fn creation_backoff(&self) -> Option<Backoff> {
+        self.creation_backoff.map(|initial| Backoff {
+            initial,
+            max: self.max_creation_backoff,
+        })
+    }
+
+    /// Capture a correlation id for every acquisition
+    ///
+    /// The hook runs on the acquiring thread or task, so it can read the
+    /// current tracing span, task-local request id, or similar context. The
+    /// returned id is attached to the guard and readable via
+    /// [`PooledObject::context`](crate::PooledObject::context), letting pooled
+    /// resource usage be stitched into distributed traces.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    ///
+    /// thread_local! {
+    ///     static REQUEST_ID: std::cell::RefCell<Option<String>> = const { std::cell::RefCell::new(None) };
+    /// }
+    ///
+    /// let config = PoolConfiguration::new()
+    ///     .with_context_hook(|| REQUEST_ID.with(|id| id.borrow().clone()));
+    /// let pool = ObjectPool::new(vec![1], config);
+    ///
+    /// REQUEST_ID.with(|id| *id.borrow_mut() = Some("req-42".to_string()));
+    /// let obj = pool.get_object().unwrap();
+    /// assert_eq!(obj.context(), Some("req-42"));
+    /// ```
+    pub fn with_context_hook<F>(mut self, hook: F) -> Self
+    where
+        F: Fn() -> Option<String> + Send + Sync + 'static,
+    {
+        self.context_hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// Hand objects that the pool discards to `hook` instead of dropping them
+    ///
+    /// Called for objects that are evicted, fail validation on return, or
+    /// cannot be re-queued because the queue is full — for example to close a
+    /// connection gracefully. Objects removed via `drain()` or
+    /// `into_detached()` are handed to the caller instead and never reach the
+    /// hook.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use std::sync::Arc;
+    ///
+    /// let destroyed = Arc::new(AtomicUsize::new(0));
+    /// let counter = Arc::clone(&destroyed);
+    /// let config = PoolConfiguration::new()
+    ///     .with_validation(|x: &i32| *x > 0)
+    ///     .with_on_destroy(move |_obj: i32| {
+    ///         counter.fetch_add(1, Ordering::Relaxed);
+    ///     });
+    /// let pool = ObjectPool::new(vec![1], config);
+    ///
+    /// let mut obj = pool.get_object().unwrap();
+    /// *obj = -1;
+    /// drop(obj);
+    /// assert_eq!(destroyed.load(Ordering::Relaxed), 1);
+    /// ```
+    pub fn with_on_destroy<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(T) + Send + Sync + 'static,
+    {
+        self.on_destroy = Some(Arc::new(hook));
+        self
+    }
+
+    /// Install `hooks` as the pool's inline borrow, acquire, return, create
+    /// and destroy hooks, timed ones included, replacing any set with the
+    /// individual builders
+    ///
+    /// See [`SyncHooks`] for what may run inline and an example.
+    pub fn with_sync_hooks<H>(mut self, hooks: H) -> Self
+    where
+        H: SyncHooks<T> + 'static,
+    {
+        let hooks = Arc::new(hooks);
+        let borrow = Arc::clone(&hooks);
+        let acquire = Arc::clone(&hooks);
+        let acquire_timed = Arc::clone(&hooks);
+        let give_back = Arc::clone(&hooks);
+        let give_back_timed = Arc::clone(&hooks);
+        let create = Arc::clone(&hooks);
+        self.on_borrow = Some(Arc::new(move |obj: &mut T| borrow.on_borrow(obj)));
+        self.on_acquire = Some(Arc::new(move |obj: &mut T, ctx: &AcquireContext| acquire.on_acquire(obj, ctx)));
+        self.on_acquire_timed = Some(Arc::new(move |obj: &mut T, timing: &HookTiming| {
+            acquire_timed.on_acquire_timed(obj, timing);
+        }));
+        self.on_return = Some(Arc::new(move |obj: &mut T| give_back.on_return(obj)));
+        self.on_return_timed = Some(Arc::new(move |obj: &mut T, timing: &HookTiming| {
+            give_back_timed.on_return_timed(obj, timing);
+        }));
+        self.on_create = Some(Arc::new(move |obj: &mut T, timing: &HookTiming| create.on_create(obj, timing)));
+        self.on_destroy = Some(Arc::new(move |obj: T| hooks.on_destroy(obj)));
+        self
+    }
+
+    /// Run `hooks` on a background worker thread, so that slow work such as
+    /// closing a connection stays out of the return path
+    ///
+    /// See [`AsyncHooks`] for which hooks may run there and an example.
+    pub fn with_async_hooks<H>(mut self, hooks: H) -> Self
+    where
+        H: AsyncHooks<T> + 'static,
+    {
+        self.async_hooks = Some(Arc::new(hooks));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_values_are_sensible() {
+        let cfg = PoolConfiguration::<i32>::default();
+        assert_eq!(cfg.max_pool_size, 100);
+        assert_eq!(cfg.max_active_objects, None);
+        assert!(!cfg.validate_on_return);
+        assert!(cfg.validation_function.is_none());
+        assert!(cfg.operation_timeout.is_some());
+        assert!(cfg.time_to_live.is_none());
+        assert!(cfg.idle_timeout.is_none());
+        assert_eq!(cfg.ttl_refresh, TtlRefresh::Never);
+        assert_eq!(cfg.expired_return, ExpiredReturn::Evict);
+        assert!(cfg.custom_eviction.is_none());
+        assert!(cfg.warmup_size.is_none());
+        assert!(!cfg.enable_circuit_breaker);
+        assert_eq!(cfg.circuit_breaker_threshold, 5);
+        assert_eq!(cfg.breaker_signals, BreakerSignals::Both);
+        assert_eq!(cfg.failure_policy, FailurePolicy::Validate);
+        assert!(cfg.bulkheads.is_empty());
+        assert!(cfg.limit_group.is_none());
+        assert!(!cfg.caller_metrics);
+        assert!(cfg.max_creations_per_second.is_none());
+        assert!(cfg.creation_backoff.is_none());
+        assert!(cfg.max_pending_creations.is_none());
+        assert_eq!(cfg.creation_policy, CreationPolicy::CreateFirst);
+        assert!(!cfg.track_holders);
+        assert!(cfg.holder_backtrace_every.is_none());
+        assert_eq!(cfg.sample_every, 1);
+        assert_eq!(cfg.capacity_loss_warning, 0.5);
+        assert!(cfg.churn_warning.is_none());
+        assert!(cfg.size_estimator.is_none());
+        assert!(cfg.degraded_factory.is_none());
+        assert!(cfg.retained_memory_cap.is_none());
+        assert!(cfg.activity_log.is_none());
+        assert!(cfg.starvation_threshold.is_none());
+        assert!(cfg.slo.is_none());
+        assert_eq!(cfg.hook_panic_policy, HookPanicPolicy::Contain);
+        assert!(cfg.async_hooks.is_none());
+        assert!(cfg.on_borrow.is_none());
+        assert!(cfg.on_return.is_none());
+        assert!(cfg.on_acquire_timed.is_none());
+        assert!(cfg.on_return_timed.is_none());
+        assert!(cfg.on_create.is_none());
+        assert!(cfg.on_destroy.is_none());
+        assert!(cfg.wait_on_empty.is_none());
+        assert!(cfg.max_waiters.is_none());
+        assert_eq!(cfg.timeout_jitter, 0.0);
+        assert!(cfg.partition_fn.is_none());
+        assert!(cfg.selection.is_none());
+        assert_eq!(cfg.selection_window, 1);
+    }
+
+    #[test]
+    fn selection_window_is_at_least_one() {
+        let cfg = PoolConfiguration::<u64>::new().with_health_selection(0);
+        assert_eq!(cfg.selection_window, 1);
+        assert_eq!(cfg.selection.as_ref().map(|score| score(&7, 0.25)), Some(0.25));
+    }
+
+    #[test]
+    fn with_partitions_sets_function_and_buckets() {
+        let cfg = PoolConfiguration::<u64>::new().with_partitions(0, |x| *x);
+        assert_eq!(cfg.partition_buckets, 1);
+        assert_eq!(cfg.partition_fn.as_ref().map(|f| f(&7)), Some(7));
+    }
+
+    #[test]
+    fn wait_policy_defaults_per_method() {
+        let cfg = PoolConfiguration::<i32>::new().with_timeout(Duration::from_secs(5));
+        assert_eq!(cfg.blocking_wait_policy(), WaitPolicy::FailFast);
+        assert_eq!(cfg.async_wait_policy(), WaitPolicy::Wait(Duration::from_secs(5)));
+
+        let cfg = cfg.with_wait_on_empty(WaitPolicy::WaitForever);
+        assert_eq!(cfg.blocking_wait_policy(), WaitPolicy::WaitForever);
+        assert_eq!(cfg.async_wait_policy(), WaitPolicy::WaitForever);
+    }
+
+    #[test]
+    fn presets_differ_where_workloads_differ() {
+        let db = PoolConfiguration::<i32>::preset_db_connections();
+        let buffers = PoolConfiguration::<i32>::preset_buffers();
+        let workers = PoolConfiguration::<i32>::preset_worker_objects();
+
+        assert!(db.enable_circuit_breaker && db.time_to_live.is_some());
+        assert!(!buffers.enable_circuit_breaker && buffers.time_to_live.is_none());
+        assert_eq!(buffers.blocking_wait_policy(), WaitPolicy::FailFast);
+        assert!(matches!(workers.async_wait_policy(), WaitPolicy::Wait(_)));
+        assert!(workers.max_pool_size >= 1);
+        assert!(db.validation_function.is_none());
+    }
+
+    #[test]
+    fn duration_str_builders_parse_or_fail() {
+        let cfg = PoolConfiguration::<i32>::new()
+            .with_timeout_str("2s")
+            .unwrap()
+            .with_idle_timeout_str("10m")
+            .unwrap();
+        assert_eq!(cfg.operation_timeout, Some(Duration::from_secs(2)));
+        assert_eq!(cfg.idle_timeout, Some(Duration::from_secs(600)));
+        assert!(PoolConfiguration::<i32>::new().with_timeout_str("2").is_err());
     }
 
     #[test]
@@ -202,6 +1877,17 @@ mod tests {
         assert_eq!(cfg.operation_timeout, Some(Duration::from_secs(5)));
     }
 
+    #[test]
+    fn no_timeout_waits_forever_unless_a_policy_is_set() {
+        let cfg = PoolConfiguration::<i32>::new().with_timeout_jitter(0.5).with_no_timeout();
+        assert_eq!(cfg.operation_timeout, None);
+        assert_eq!(cfg.async_wait_policy(), WaitPolicy::WaitForever);
+        assert_eq!(cfg.blocking_wait_policy(), WaitPolicy::FailFast);
+
+        let cfg = cfg.with_wait_on_empty(WaitPolicy::FailFast);
+        assert_eq!(cfg.async_wait_policy(), WaitPolicy::FailFast);
+    }
+
     #[test]
     fn with_ttl() {
         let cfg = PoolConfiguration::<i32>::new().with_ttl(Duration::from_secs(60));
@@ -229,6 +1915,33 @@ mod tests {
         assert_eq!(cfg.circuit_breaker_timeout, Duration::from_secs(45));
     }
 
+    #[test]
+    fn with_on_borrow() {
+        let cfg = PoolConfiguration::<i32>::new().with_on_borrow(|x| *x += 1);
+        let hook = cfg.on_borrow.clone().expect("hook should be set");
+        let mut value = 1;
+        hook(&mut value);
+        assert_eq!(value, 2);
+        assert!(format!("{cfg:?}").contains("on_borrow: true"));
+    }
+
+    #[test]
+    fn with_on_return() {
+        let cfg = PoolConfiguration::<i32>::new().with_on_return(|x| *x = 0);
+        let hook = cfg.on_return.clone().expect("hook should be set");
+        let mut value = 5;
+        hook(&mut value);
+        assert_eq!(value, 0);
+        assert!(format!("{cfg:?}").contains("on_return: true"));
+    }
+
+    #[test]
+    fn with_context_hook() {
+        let cfg = PoolConfiguration::<i32>::new().with_context_hook(|| Some("trace-1".into()));
+        let hook = cfg.context_hook.clone().expect("hook should be set");
+        assert_eq!(hook().as_deref(), Some("trace-1"));
+    }
+
     #[test]
     fn builder_is_chainable() {
         let cfg = PoolConfiguration::<i32>::new()
@@ -249,5 +1962,30 @@ mod tests {
         assert!(cfg.enable_circuit_breaker);
         assert_eq!(cfg.circuit_breaker_threshold, 2);
     }
+
+    #[test]
+    fn capacity_limits_precedence() {
+        let unlimited = CapacityLimits::new(10);
+        assert_eq!(unlimited.max_active(), 10);
+        assert_eq!(unlimited.active_limit(), None);
+
+        let below = unlimited.with_max_active_objects(3);
+        assert_eq!(below.max_active(), 3);
+        assert_eq!(below.active_limit(), Some(3));
+
+        for redundant in [10, 50] {
+            let limits = unlimited.with_max_active_objects(redundant);
+            assert_eq!(limits.max_active(), 10);
+            assert_eq!(limits.active_limit(), None);
+        }
+    }
+
+    #[test]
+    fn capacity_limits_round_trip_through_config() {
+        let limits = CapacityLimits::new(8).with_max_active_objects(2);
+        let cfg = PoolConfiguration::<i32>::new().with_capacity_limits(limits);
+        assert_eq!(cfg.capacity_limits(), limits);
+        assert_eq!(PoolConfiguration::<i32>::new().capacity_limits(), CapacityLimits::new(100));
+    }
 }
 
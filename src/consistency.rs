@@ -0,0 +1,121 @@
+//! Self-check of a pool's internal accounting
+
+use std::fmt;
+
+/// What [`ObjectPool::check_consistency`](crate::ObjectPool::check_consistency)
+/// found
+///
+/// The counts are read one after another, so checks are only meaningful
+/// while nothing else uses the pool: an acquisition or return racing with
+/// the check shows up as a transient mismatch.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{ObjectPool, PoolConfiguration};
+///
+/// let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+/// let held = pool.get_object().unwrap();
+/// held.discard();
+/// let _held = pool.get_object().unwrap();
+///
+/// let report = pool.check_consistency();
+/// assert!(report.is_consistent(), "{report}");
+/// assert_eq!((report.population, report.available, report.active), (2, 1, 1));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConsistencyReport {
+    /// Objects the pool owns, idle or checked out
+    pub population: usize,
+
+    /// Idle objects
+    pub available: usize,
+
+    /// Checked-out objects
+    pub active: usize,
+
+    /// Factory calls in progress, whose objects already count towards the
+    /// population
+    pub pending: usize,
+
+    /// Most objects the pool may own
+    pub capacity: usize,
+
+    /// Every mismatch found; empty if the accounting is consistent
+    pub issues: Vec<String>,
+}
+
+impl ConsistencyReport {
+    /// Whether no mismatch was found
+    #[must_use]
+    pub fn is_consistent(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Record `issue` unless `holds`.
+    pub(crate) fn check(&mut self, holds: bool, issue: impl FnOnce() -> String) {
+        if !holds {
+            self.issues.push(issue());
+        }
+    }
+
+    /// Check the counts against each other: every owned object is idle,
+    /// checked out or still being created.
+    pub(crate) fn check_counts(&mut self) {
+        let (population, available, active, pending) = (self.population, self.available, self.active, self.pending);
+        let accounted = available + active;
+        self.check(accounted <= population && population <= accounted + pending, || {
+            format!("population {population} != {available} available + {active} active + {pending} pending")
+        });
+        let capacity = self.capacity;
+        self.check(population <= capacity, || format!("population {population} exceeds capacity {capacity}"));
+    }
+}
+
+impl fmt::Display for ConsistencyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "population {} ({} available, {} active, {} pending) of {}",
+            self.population, self.available, self.active, self.pending, self.capacity
+        )?;
+        if self.is_consistent() {
+            f.write_str(": consistent")
+        } else {
+            write!(f, ": {}", self.issues.join("; "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(population: usize, available: usize, active: usize, pending: usize) -> ConsistencyReport {
+        let mut report = ConsistencyReport {
+            population,
+            available,
+            active,
+            pending,
+            capacity: 4,
+            issues: Vec::new(),
+        };
+        report.check_counts();
+        report
+    }
+
+    #[test]
+    fn pending_creations_may_hold_population() {
+        assert!(report(3, 1, 2, 0).is_consistent());
+        assert!(report(3, 1, 1, 1).is_consistent(), "a warmup in flight");
+        assert!(report(3, 1, 2, 1).is_consistent(), "a creation holding an active slot");
+
+        let leaked = report(3, 1, 1, 0);
+        assert_eq!(leaked.issues, ["population 3 != 1 available + 1 active + 0 pending"]);
+        assert_eq!(
+            leaked.to_string(),
+            "population 3 (1 available, 1 active, 0 pending) of 4: population 3 != 1 available + 1 active + 0 pending"
+        );
+        assert_eq!(report(5, 5, 0, 0).issues, ["population 5 exceeds capacity 4"]);
+    }
+}
@@ -0,0 +1,160 @@
+//! Caller-supplied context for a single acquisition
+
+use crate::wait::WaitPolicy;
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// Who is acquiring an object, and under what constraints
+///
+/// Passed to [`ObjectPool::get_object_with`](crate::ObjectPool::get_object_with)
+/// and friends. The context is handed to the
+/// [`on_acquire` hook](crate::PoolConfiguration::with_on_acquire) and stays
+/// attached to the guard, readable via
+/// [`PooledObject::acquire_context`](crate::PooledObject::acquire_context).
+///
+/// A deadline caps how long the acquisition may wait, tightening (never
+/// loosening) the pool's [`WaitPolicy`].
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{AcquireContext, ObjectPool, PoolConfiguration};
+/// use std::time::Duration;
+///
+/// let pool = ObjectPool::new(vec![1], PoolConfiguration::default());
+/// let ctx = AcquireContext::new()
+///     .with_label("checkout-service")
+///     .with_priority(10)
+///     .with_timeout(Duration::from_millis(50))
+///     .with_extension("tenant", "acme");
+///
+/// let obj = pool.get_object_with(ctx).unwrap();
+/// let ctx = obj.acquire_context().unwrap();
+/// assert_eq!(ctx.label(), Some("checkout-service"));
+/// assert_eq!(ctx.extension("tenant"), Some("acme"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct AcquireContext {
+    label: Option<String>,
+    priority: i32,
+    deadline: Option<Instant>,
+    extensions: HashMap<String, String>,
+}
+
+impl AcquireContext {
+    /// An empty context: no label, priority 0, no deadline
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Name the calling code path, for example for per-caller metrics
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Set the caller's priority; higher values are more urgent
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Give up waiting for an object at `deadline`
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// Give up waiting for an object after `timeout` from now
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        self.with_deadline(Instant::now() + timeout)
+    }
+
+    /// Attach an arbitrary key/value pair for hooks to inspect
+    pub fn with_extension(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.extensions.insert(key.into(), value.into());
+        self
+    }
+
+    /// The caller label, if set
+    #[must_use]
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// The caller priority (0 unless set)
+    #[must_use]
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    /// The deadline for acquiring an object, if set
+    #[must_use]
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    /// The value of extension `key`, if set
+    #[must_use]
+    pub fn extension(&self, key: &str) -> Option<&str> {
+        self.extensions.get(key).map(String::as_str)
+    }
+
+    /// All extensions
+    #[must_use]
+    pub fn extensions(&self) -> &HashMap<String, String> {
+        &self.extensions
+    }
+
+    /// `policy`, shortened so that waiting ends by the deadline.
+    pub(crate) fn limit_wait(&self, policy: WaitPolicy) -> WaitPolicy {
+        let Some(deadline) = self.deadline else {
+            return policy;
+        };
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match policy {
+            WaitPolicy::FailFast => WaitPolicy::FailFast,
+            WaitPolicy::Wait(budget) => WaitPolicy::Wait(budget.min(remaining)),
+            WaitPolicy::WaitForever => WaitPolicy::Wait(remaining),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_context_is_empty() {
+        let ctx = AcquireContext::new();
+        assert_eq!(ctx.label(), None);
+        assert_eq!(ctx.priority(), 0);
+        assert_eq!(ctx.deadline(), None);
+        assert!(ctx.extensions().is_empty());
+    }
+
+    #[test]
+    fn deadline_only_tightens_wait_policy() {
+        let ctx = AcquireContext::new().with_timeout(Duration::from_secs(1));
+        assert_eq!(ctx.limit_wait(WaitPolicy::FailFast), WaitPolicy::FailFast);
+        assert!(matches!(
+            ctx.limit_wait(WaitPolicy::WaitForever),
+            WaitPolicy::Wait(d) if d <= Duration::from_secs(1)
+        ));
+        assert_eq!(
+            ctx.limit_wait(WaitPolicy::Wait(Duration::from_millis(10))),
+            WaitPolicy::Wait(Duration::from_millis(10))
+        );
+        assert_eq!(
+            AcquireContext::new().limit_wait(WaitPolicy::WaitForever),
+            WaitPolicy::WaitForever
+        );
+    }
+
+    #[test]
+    fn passed_deadline_leaves_no_wait() {
+        let ctx = AcquireContext::new().with_deadline(Instant::now() - Duration::from_secs(1));
+        assert_eq!(ctx.limit_wait(WaitPolicy::WaitForever), WaitPolicy::Wait(Duration::ZERO));
+    }
+}
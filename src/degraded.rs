@@ -0,0 +1,88 @@
+//! Degraded-tier objects served while a dynamic pool's circuit breaker is open
+
+use crate::config::FallbackFactory;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Which factory a pooled object came from
+///
+/// Returned by [`PooledObject::origin`](crate::PooledObject::origin).
+/// Degraded objects are made by the factory set with
+/// [`with_degraded_factory`](crate::PoolConfiguration::with_degraded_factory)
+/// while a dynamic pool's circuit breaker is open, so callers can fall back
+/// to partial functionality, such as read-only queries on a replica.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ObjectOrigin {
+    /// Made by the pool's factory, or one of its initial objects
+    #[default]
+    Primary,
+
+    /// Made by the degraded-tier factory; destroyed rather than pooled when
+    /// given back
+    Degraded,
+}
+
+/// The degraded-tier factory of a dynamic pool and how many of its objects
+/// are out.
+pub(crate) struct DegradedTier<T> {
+    factory: FallbackFactory<T>,
+    in_use: AtomicUsize,
+    /// Most degraded objects out at once: the pool's capacity.
+    limit: usize,
+}
+
+impl<T> DegradedTier<T> {
+    pub fn new(factory: FallbackFactory<T>, limit: usize) -> Self {
+        Self {
+            factory,
+            in_use: AtomicUsize::new(0),
+            limit,
+        }
+    }
+
+    /// Make a degraded object, unless `limit` are already out or the
+    /// factory fails. Every object made must be [`release`](Self::release)d.
+    pub fn create(&self) -> Option<T> {
+        self.in_use
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| (n < self.limit).then_some(n + 1))
+            .ok()?;
+        let created = (self.factory)().ok();
+        if created.is_none() {
+            self.release();
+        }
+        created
+    }
+
+    /// A degraded object was given back, discarded or detached.
+    pub fn release(&self) {
+        self.in_use.fetch_sub(1, Ordering::AcqRel);
+    }
+
+    pub fn in_use(&self) -> usize {
+        self.in_use.load(Ordering::Acquire)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn limits_objects_out_and_frees_slots_of_failed_creations() {
+        let fail = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let failing = Arc::clone(&fail);
+        let tier = DegradedTier::new(
+            Arc::new(move || if failing.load(Ordering::Relaxed) { Err("down".to_string()) } else { Ok(1) }),
+            2,
+        );
+        assert_eq!(tier.create(), Some(1));
+        assert_eq!(tier.create(), Some(1));
+        assert_eq!(tier.create(), None, "limit reached");
+
+        tier.release();
+        fail.store(true, Ordering::Relaxed);
+        assert_eq!(tier.create(), None);
+        assert_eq!(tier.in_use(), 1);
+    }
+}
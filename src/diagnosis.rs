@@ -0,0 +1,176 @@
+//! Diagnosis of failed acquisitions
+
+use crate::circuit_breaker::CircuitBreakerState;
+use crate::errors::PoolError;
+
+use std::fmt;
+use std::time::Instant;
+
+/// A check that turned an acquisition away
+///
+/// Listed by [`FailureDiagnosis::gates`]. An acquisition can be stopped by
+/// several at once, for example a dynamic pool that is both out of idle
+/// objects and at capacity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Gate {
+    /// The pool was shut down
+    Closed,
+
+    /// The circuit breaker refused the acquisition
+    CircuitBreakerOpen,
+
+    /// `max_active_objects` objects were already checked out
+    MaxActiveObjects,
+
+    /// The named limit group was full
+    LimitGroup(String),
+
+    /// No idle object was available
+    Empty,
+
+    /// A dynamic pool already owned as many objects as it may
+    AtCapacity,
+
+    /// Idle objects found on the way had expired and were destroyed, so
+    /// eviction is removing objects faster than they are reused
+    EvictionChurn,
+
+    /// Object creation was refused by the rate limit or failure backoff
+    CreationThrottled,
+
+    /// Too many factory calls were already in progress
+    CreationPending,
+
+    /// The factory returned an error
+    CreationFailed,
+
+    /// The object failed validation or a borrow hook
+    ValidationFailed,
+
+    /// No idle object matched the query
+    NoMatch,
+
+    /// `max_waiters` acquisitions were already waiting
+    WaitQueueFull,
+
+    /// The pool was paused for maintenance
+    Paused,
+}
+
+impl fmt::Display for Gate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Closed => f.write_str("pool closed"),
+            Self::CircuitBreakerOpen => f.write_str("circuit breaker open"),
+            Self::MaxActiveObjects => f.write_str("max active objects reached"),
+            Self::LimitGroup(name) => write!(f, "limit group '{name}' full"),
+            Self::Empty => f.write_str("no idle object"),
+            Self::AtCapacity => f.write_str("at capacity"),
+            Self::EvictionChurn => f.write_str("idle objects expired"),
+            Self::CreationThrottled => f.write_str("creation throttled"),
+            Self::CreationPending => f.write_str("too many creations pending"),
+            Self::CreationFailed => f.write_str("factory failed"),
+            Self::ValidationFailed => f.write_str("validation failed"),
+            Self::NoMatch => f.write_str("no matching object"),
+            Self::WaitQueueFull => f.write_str("wait queue full"),
+            Self::Paused => f.write_str("pool paused"),
+        }
+    }
+}
+
+/// Why the most recent acquisition attempt of a pool failed, with the
+/// pool's state at that moment
+///
+/// Returned by [`ObjectPool::explain_last_failure`](crate::ObjectPool::explain_last_failure).
+/// A waiting acquisition retries until it succeeds or times out, so after a
+/// `PoolError::Timeout` this describes the last attempt before giving up.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{Gate, ObjectPool, PoolConfiguration, WaitPolicy};
+///
+/// let pool = ObjectPool::new(
+///     vec![1, 2, 3],
+///     PoolConfiguration::new()
+///         .with_max_active_objects(1)
+///         .with_wait_on_empty(WaitPolicy::FailFast),
+/// );
+/// let _held = pool.get_object().unwrap();
+/// assert!(pool.get_object().is_err());
+///
+/// let diagnosis = pool.explain_last_failure().unwrap();
+/// assert_eq!(diagnosis.gates, [Gate::MaxActiveObjects]);
+/// assert_eq!((diagnosis.active, diagnosis.available), (1, 2));
+/// println!("{diagnosis}");
+/// ```
+#[derive(Debug, Clone)]
+pub struct FailureDiagnosis {
+    /// The error the attempt failed with
+    pub error: PoolError,
+
+    /// The checks that turned the attempt away
+    pub gates: Vec<Gate>,
+
+    /// Idle objects in the pool
+    pub available: usize,
+
+    /// Objects checked out
+    pub active: usize,
+
+    /// The `max_active_objects` limit, if any
+    pub max_active: Option<usize>,
+
+    /// Objects owned by the pool, idle or checked out
+    pub population: usize,
+
+    /// Most objects the pool may own
+    pub capacity: usize,
+
+    /// Other acquisitions waiting for an object
+    pub waiting: usize,
+
+    /// Factory calls in progress
+    pub pending_creations: usize,
+
+    /// State of the circuit breaker, if enabled
+    pub breaker: Option<CircuitBreakerState>,
+
+    /// Idle objects destroyed as expired since an object was last handed
+    /// out
+    pub expired_since_last_acquisition: usize,
+
+    /// When the attempt failed
+    pub at: Instant,
+}
+
+impl fmt::Display for FailureDiagnosis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.error)?;
+        if !self.gates.is_empty() {
+            let gates: Vec<String> = self.gates.iter().map(Gate::to_string).collect();
+            write!(f, " ({})", gates.join(", "))?;
+        }
+        write!(f, ": {} available, {} active", self.available, self.active)?;
+        if let Some(max) = self.max_active {
+            write!(f, " of at most {max}")?;
+        }
+        write!(
+            f,
+            ", {}/{} owned, {} waiting, {} creations pending",
+            self.population, self.capacity, self.waiting, self.pending_creations
+        )?;
+        if let Some(state) = self.breaker {
+            write!(f, ", breaker {state:?}")?;
+        }
+        if self.expired_since_last_acquisition > 0 {
+            write!(
+                f,
+                ", {} expired since last acquisition",
+                self.expired_since_last_acquisition
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+//! Parsing of human-readable durations such as `"250ms"` or `"1h 30m"`
+
+use crate::errors::{PoolError, PoolResult};
+
+use std::time::Duration;
+
+/// Parse a duration written as one or more `<integer><unit>` terms.
+///
+/// Whitespace is allowed around units, and terms are summed, so `"1h30m"` and
+/// `"1h 30m"` are both ninety minutes. Recognised units are `ns`, `us`/`µs`,
+/// `ms`, `s`/`sec`/`secs`, `m`/`min`/`mins`, `h`/`hr`/`hrs`/`hour`/`hours`
+/// and `d`/`day`/`days`.
+pub(crate) fn parse_duration(text: &str) -> PoolResult<Duration> {
+    let invalid = |reason: &str| {
+        PoolError::InvalidConfiguration(format!("invalid duration {text:?}: {reason}"))
+    };
+
+    let mut rest = text.trim();
+    if rest.is_empty() {
+        return Err(invalid("empty"));
+    }
+
+    let mut total = Duration::ZERO;
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len());
+        if digits == 0 {
+            return Err(invalid("expected a number"));
+        }
+        let value: u64 = rest[..digits].parse().map_err(|_| invalid("number too large"))?;
+        rest = rest[digits..].trim_start();
+
+        let unit_len = rest
+            .find(|c: char| c.is_ascii_digit() || c.is_whitespace())
+            .unwrap_or(rest.len());
+        let unit = &rest[..unit_len];
+        rest = rest[unit_len..].trim_start();
+
+        let term = match unit {
+            "ns" => Some(Duration::from_nanos(value)),
+            "us" | "µs" => Some(Duration::from_micros(value)),
+            "ms" => Some(Duration::from_millis(value)),
+            "s" | "sec" | "secs" => Some(Duration::from_secs(value)),
+            "m" | "min" | "mins" => minutes(value, 1),
+            "h" | "hr" | "hrs" | "hour" | "hours" => minutes(value, 60),
+            "d" | "day" | "days" => minutes(value, 24 * 60),
+            "" => return Err(invalid("missing unit")),
+            _ => return Err(invalid(&format!("unknown unit {unit:?}"))),
+        }
+        .ok_or_else(|| invalid("too large"))?;
+
+        total = total.checked_add(term).ok_or_else(|| invalid("too large"))?;
+    }
+    Ok(total)
+}
+
+/// Deserialize a [`Duration`] from a human-readable string such as
+/// `"250ms"` or `"1h 30m"`, in the format accepted by
+/// [`PoolConfiguration::with_timeout_str`](crate::PoolConfiguration::with_timeout_str).
+///
+/// Meant for `#[serde(deserialize_with = "esox_objectpool::deserialize_duration")]`
+/// on the `Duration` fields of an application's own configuration struct.
+/// Available with the `serde` feature.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::deserialize_duration;
+/// use serde_core::de::value::{Error, StrDeserializer};
+/// use std::time::Duration;
+///
+/// let ttl = deserialize_duration(StrDeserializer::<Error>::new("1h 30m")).unwrap();
+/// assert_eq!(ttl, Duration::from_secs(5400));
+/// assert!(deserialize_duration(StrDeserializer::<Error>::new("soon")).is_err());
+/// ```
+#[cfg(feature = "serde")]
+pub fn deserialize_duration<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+where
+    D: serde_core::Deserializer<'de>,
+{
+    use serde_core::de::{self, Visitor};
+    use std::fmt;
+
+    struct HumanDuration;
+
+    impl Visitor<'_> for HumanDuration {
+        type Value = Duration;
+
+        fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("a duration such as \"250ms\" or \"1h 30m\"")
+        }
+
+        fn visit_str<E: de::Error>(self, text: &str) -> Result<Duration, E> {
+            parse_duration(text).map_err(E::custom)
+        }
+    }
+
+    deserializer.deserialize_str(HumanDuration)
+}
+
+fn minutes(value: u64, per_unit: u64) -> Option<Duration> {
+    value
+        .checked_mul(per_unit * 60)
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_single_terms() {
+        assert_eq!(parse_duration("250ms").unwrap(), Duration::from_millis(250));
+        assert_eq!(parse_duration("30m").unwrap(), Duration::from_secs(1800));
+        assert_eq!(parse_duration("2 days").unwrap(), Duration::from_secs(2 * 86_400));
+        assert_eq!(parse_duration(" 15s ").unwrap(), Duration::from_secs(15));
+    }
+
+    #[test]
+    fn sums_compound_durations() {
+        assert_eq!(parse_duration("1h30m").unwrap(), Duration::from_secs(5400));
+        assert_eq!(parse_duration("1h 30m 5s").unwrap(), Duration::from_secs(5405));
+        assert_eq!(parse_duration("1s500ms").unwrap(), Duration::from_millis(1500));
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        for bad in ["", "ms", "30", "30x", "1.5s", "-1s", "99999999999999999999999s"] {
+            let err = parse_duration(bad).unwrap_err();
+            assert!(matches!(err, PoolError::InvalidConfiguration(_)), "{bad:?} gave {err:?}");
+        }
+    }
+
+    #[test]
+    fn error_names_the_input() {
+        let msg = parse_duration("30x").unwrap_err().to_string();
+        assert!(msg.contains("\"30x\""));
+        assert!(msg.contains("unknown unit"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserializes_with_the_same_grammar() {
+        use serde_core::de::value::{Error, StrDeserializer};
+
+        let parse = |text| deserialize_duration(StrDeserializer::<Error>::new(text));
+        assert_eq!(parse("1s500ms").unwrap(), Duration::from_millis(1500));
+        let err = parse("5 fortnights").unwrap_err().to_string();
+        assert!(err.contains("unknown unit"), "{err}");
+    }
+}
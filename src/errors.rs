@@ -13,8 +13,12 @@
 //! assert!(matches!(result, Err(PoolError::PoolEmpty)));
 //! ```
 
+use crate::pool::ObjectPool;
+
 use thiserror::Error;
 
+use std::time::Duration;
+
 #[derive(Error, Debug, Clone)]
 pub enum PoolError {
     #[error("Pool is empty - no objects available")]
@@ -23,8 +27,8 @@ pub enum PoolError {
     #[error("Pool is at maximum capacity")]
     PoolFull,
     
-    #[error("Operation timed out after {0:?}")]
-    Timeout(std::time::Duration),
+    #[error("Operation timed out after {0:?} ({1})")]
+    Timeout(Duration, WaitBreakdown),
     
     #[error("No object matching the query was found")]
     NoMatchFound,
@@ -40,14 +44,148 @@ pub enum PoolError {
     
     #[error("Operation was cancelled")]
     Cancelled,
+
+    #[error("Bulkhead '{0}' is at its concurrency limit")]
+    BulkheadFull(String),
+
+    #[error("No bulkhead is configured for category '{0}'")]
+    UnknownBulkhead(String),
+
+    #[error("Limit group '{0}' has reached its shared active limit")]
+    GroupLimitReached(String),
+
+    #[error("Invalid configuration: {0}")]
+    InvalidConfiguration(String),
+
+    #[error("Object creation is throttled")]
+    CreationThrottled,
+
+    #[error("Object creation failed: {0}")]
+    CreationFailed(String),
+
+    #[error("Maximum pending object creations reached")]
+    CreationPending,
+
+    #[error("Pool is closed")]
+    PoolClosed,
+
+    #[error("Too many acquisitions are already waiting")]
+    QueueFull,
+
+    #[error("Pool is paused")]
+    Paused,
 }
 
 pub type PoolResult<T> = Result<T, PoolError>;
 
+/// How a timed-out acquisition spent its wait
+///
+/// Carried by [`PoolError::Timeout`] next to the wait budget.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{ObjectPool, PoolConfiguration, PoolError, WaitPolicy};
+/// use std::time::Duration;
+///
+/// let pool = ObjectPool::new(
+///     vec![1],
+///     PoolConfiguration::new().with_wait_on_empty(WaitPolicy::Wait(Duration::from_millis(20))),
+/// );
+/// let _held = pool.get_object().unwrap();
+///
+/// match pool.get_object() {
+///     Err(PoolError::Timeout(budget, waited)) => {
+///         assert_eq!(budget, Duration::from_millis(20));
+///         assert!(waited.empty >= budget);
+///         assert_eq!(waited.breaker, Duration::ZERO);
+///     }
+///     other => panic!("expected a timeout, got {other:?}"),
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct WaitBreakdown {
+    /// Time spent waiting for an object to become available
+    pub empty: Duration,
+
+    /// Time spent waiting for an open circuit breaker to allow a probe
+    pub breaker: Duration,
+}
+
+impl std::fmt::Display for WaitBreakdown {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:?} waiting for an object, {:?} waiting for the circuit breaker",
+            self.empty, self.breaker
+        )
+    }
+}
+
+/// Error from running an operation on a pooled object
+///
+/// Returned by [`ObjectPool::run_guarded`](crate::ObjectPool::run_guarded)
+/// and friends, separating failure to obtain an object from failure of the
+/// operation itself.
+#[derive(Error, Debug, Clone)]
+pub enum GuardedError<E> {
+    /// No object could be acquired
+    #[error(transparent)]
+    Pool(#[from] PoolError),
+
+    /// The operation ran and returned an error
+    #[error("Operation failed: {0}")]
+    Operation(E),
+}
+
+/// Error from [`ObjectPool::into_inner`] while objects are checked out
+///
+/// Carries the pool back untouched, so no idle object is lost; retry once
+/// the checked-out objects have been returned.
+#[derive(Error)]
+#[error("Pool still has {active} checked-out objects ({available} idle)")]
+pub struct IntoInnerError<T: Send> {
+    pool: Box<ObjectPool<T>>,
+    active: usize,
+    available: usize,
+}
+
+impl<T: Send> IntoInnerError<T> {
+    pub(crate) fn new(pool: ObjectPool<T>, active: usize, available: usize) -> Self {
+        Self { pool: Box::new(pool), active, available }
+    }
+
+    /// Objects that were checked out
+    #[must_use]
+    pub fn active(&self) -> usize {
+        self.active
+    }
+
+    /// Objects that were idle in the pool
+    #[must_use]
+    pub fn available(&self) -> usize {
+        self.available
+    }
+
+    /// The pool, unchanged
+    #[must_use]
+    pub fn into_pool(self) -> ObjectPool<T> {
+        *self.pool
+    }
+}
+
+impl<T: Send> std::fmt::Debug for IntoInnerError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IntoInnerError")
+            .field("active", &self.active)
+            .field("available", &self.available)
+            .finish_non_exhaustive()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::time::Duration;
 
     #[test]
     fn error_display_messages() {
@@ -58,14 +196,63 @@ mod tests {
         assert_eq!(PoolError::CircuitBreakerOpen.to_string(), "Circuit breaker is open - too many failures");
         assert_eq!(PoolError::MaxActiveObjectsReached.to_string(), "Maximum active objects limit reached");
         assert_eq!(PoolError::Cancelled.to_string(), "Operation was cancelled");
+        assert_eq!(PoolError::PoolClosed.to_string(), "Pool is closed");
+        assert_eq!(PoolError::QueueFull.to_string(), "Too many acquisitions are already waiting");
+        assert_eq!(PoolError::Paused.to_string(), "Pool is paused");
+        assert_eq!(
+            PoolError::BulkheadFull("writes".into()).to_string(),
+            "Bulkhead 'writes' is at its concurrency limit"
+        );
+        assert_eq!(
+            PoolError::UnknownBulkhead("admin".into()).to_string(),
+            "No bulkhead is configured for category 'admin'"
+        );
+        assert_eq!(
+            PoolError::GroupLimitReached("db".into()).to_string(),
+            "Limit group 'db' has reached its shared active limit"
+        );
+        assert_eq!(
+            PoolError::InvalidConfiguration("bad".into()).to_string(),
+            "Invalid configuration: bad"
+        );
+        assert_eq!(PoolError::CreationThrottled.to_string(), "Object creation is throttled");
+        assert_eq!(
+            PoolError::CreationFailed("refused".into()).to_string(),
+            "Object creation failed: refused"
+        );
+        assert_eq!(
+            PoolError::CreationPending.to_string(),
+            "Maximum pending object creations reached"
+        );
     }
 
     #[test]
     fn timeout_display_includes_duration() {
-        let msg = PoolError::Timeout(Duration::from_secs(30)).to_string();
+        let msg = PoolError::Timeout(Duration::from_secs(30), WaitBreakdown::default()).to_string();
         assert!(msg.contains("30s") || msg.contains("30"), "expected duration in: {msg}");
     }
 
+    #[test]
+    fn timeout_display_includes_breakdown() {
+        let waited = WaitBreakdown {
+            empty: Duration::from_millis(40),
+            breaker: Duration::from_millis(60),
+        };
+        assert_eq!(
+            PoolError::Timeout(Duration::from_millis(100), waited).to_string(),
+            "Operation timed out after 100ms (40ms waiting for an object, 60ms waiting for the circuit breaker)"
+        );
+    }
+
+    #[test]
+    fn guarded_error_display() {
+        let pool: GuardedError<String> = PoolError::PoolEmpty.into();
+        assert_eq!(pool.to_string(), "Pool is empty - no objects available");
+
+        let op = GuardedError::Operation("connection reset".to_string());
+        assert_eq!(op.to_string(), "Operation failed: connection reset");
+    }
+
     #[test]
     fn errors_are_clone() {
         let e = PoolError::PoolEmpty;
@@ -78,7 +265,7 @@ mod tests {
         let cases: &[PoolError] = &[
             PoolError::PoolEmpty,
             PoolError::PoolFull,
-            PoolError::Timeout(Duration::from_millis(100)),
+            PoolError::Timeout(Duration::from_millis(100), WaitBreakdown::default()),
             PoolError::NoMatchFound,
             PoolError::ValidationFailed,
             PoolError::CircuitBreakerOpen,
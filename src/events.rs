@@ -0,0 +1,326 @@
+//! Event reporting for object pools
+
+use crate::ids::PoolObjectId;
+use crate::slo::SloObjective;
+
+use std::panic::Location;
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::sync::broadcast;
+
+/// Number of undelivered reports buffered per receiver before it starts
+/// lagging (see [`broadcast::error::RecvError::Lagged`]).
+const REPORT_CHANNEL_CAPACITY: usize = 256;
+
+/// Why an object on its way back into the pool was discarded
+///
+/// `Drop` cannot return errors, so these are published on the channel
+/// returned by [`ObjectPool::return_errors`](crate::ObjectPool::return_errors).
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{ObjectPool, PoolConfiguration, ReturnError};
+///
+/// let pool = ObjectPool::new(
+///     vec![1],
+///     PoolConfiguration::new().with_validation(|x: &i32| *x > 0),
+/// );
+/// let mut errors = pool.return_errors();
+///
+/// let mut obj = pool.get_object().unwrap();
+/// *obj = -1;
+/// drop(obj);
+///
+/// assert!(matches!(errors.try_recv(), Ok(ReturnError::ValidationFailed { .. })));
+/// ```
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ReturnError {
+    #[error("object {object_id} failed validation on return and was discarded")]
+    ValidationFailed { object_id: PoolObjectId },
+
+    #[error("object {object_id} could not be re-queued (queue full) and was discarded")]
+    QueueFull { object_id: PoolObjectId },
+}
+
+/// Something about a pool that deserves an operator's attention
+///
+/// Published on the channel returned by
+/// [`ObjectPool::warnings`](crate::ObjectPool::warnings).
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{ObjectPool, PoolConfiguration, PoolWarning, WaitPolicy};
+/// use std::time::Duration;
+///
+/// let pool = ObjectPool::new(
+///     vec![1],
+///     PoolConfiguration::new()
+///         .with_wait_on_empty(WaitPolicy::Wait(Duration::from_millis(100)))
+///         .with_starvation_detection(Duration::from_millis(20)),
+/// );
+/// let mut warnings = pool.warnings();
+///
+/// // Holding an object while acquiring another from the same pool.
+/// let _held = pool.get_object().unwrap();
+/// assert!(pool.get_object().is_err());
+///
+/// assert!(matches!(warnings.try_recv(), Ok(PoolWarning::Starvation { waiters: 1, .. })));
+/// ```
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PoolWarning {
+    /// Acquisitions have been waiting longer than the configured threshold
+    /// while every object was checked out and none was returned — typically
+    /// code holding one object while waiting for another
+    #[error(
+        "{waiters} acquisition(s) starved: longest wait {longest_wait:?}, \
+         every object checked out, last return {since_last_return:?} ago"
+    )]
+    Starvation {
+        /// Acquisitions waiting when the warning fired
+        waiters: usize,
+        /// How long the oldest of them had been waiting
+        longest_wait: Duration,
+        /// Time since an object was last returned (or since the pool was
+        /// created, if none has been)
+        since_last_return: Duration,
+    },
+
+    /// The validation function or a hook panicked; the panic was contained
+    /// and the object involved discarded (see
+    /// [`HookPanicPolicy`](crate::HookPanicPolicy))
+    #[error("{hook} panicked: {message}")]
+    HookPanicked {
+        /// Which closure panicked: `validation`, `on_borrow`, `on_acquire`,
+        /// `on_destroy` or `context_hook`
+        hook: &'static str,
+        /// The panic message
+        message: String,
+    },
+
+    /// A leased object was still checked out when its lease elapsed —
+    /// often a leak — reported with where it was acquired
+    #[error("object {object_id} held past its {max_hold:?} lease{}", site(.label, .location))]
+    LeaseExpired {
+        /// Id of the object
+        object_id: PoolObjectId,
+        /// Length of the lease
+        max_hold: Duration,
+        /// Label of the [`AcquireContext`](crate::AcquireContext) it was
+        /// acquired with, if any
+        label: Option<String>,
+        /// Source location of the acquisition (see
+        /// [`PooledObject::acquired_at`](crate::PooledObject::acquired_at))
+        location: Option<&'static Location<'static>>,
+    },
+
+    /// More objects were replaced — destroyed and created anew — over the
+    /// last minute than the threshold set with
+    /// [`with_churn_warning`](crate::PoolConfiguration::with_churn_warning);
+    /// usually a TTL that is too short or validation that keeps failing.
+    /// Published again only after churn has dropped back to the threshold
+    #[error(
+        "high churn: {created} object(s) created and {destroyed} destroyed \
+         in the last minute (threshold {threshold})"
+    )]
+    HighChurn {
+        /// Objects created in the last minute
+        created: u64,
+        /// Objects destroyed in the last minute
+        destroyed: u64,
+        /// Replacements per minute allowed before warning
+        threshold: u64,
+    },
+}
+
+/// `" (label at file:line:col)"`, or as much of it as is known.
+fn site(label: &Option<String>, location: &Option<&'static Location<'static>>) -> String {
+    match (label, location) {
+        (Some(label), Some(location)) => format!(" ({label} at {location})"),
+        (Some(label), None) => format!(" ({label})"),
+        (None, Some(location)) => format!(" (at {location})"),
+        (None, None) => String::new(),
+    }
+}
+
+/// Something that happened in a pool
+///
+/// Published on the channel returned by
+/// [`ObjectPool::events`](crate::ObjectPool::events), for dashboards and
+/// logs (see also the `tracing` feature's `EventLogger`).
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{ObjectPool, PoolConfiguration, PoolEvent};
+///
+/// let pool = ObjectPool::new(vec![1], PoolConfiguration::default());
+/// let mut events = pool.events();
+///
+/// let _held = pool.get_object().unwrap();
+/// assert!(pool.try_get_object().unwrap().is_none());
+///
+/// assert_eq!(events.try_recv(), Ok(PoolEvent::Empty));
+/// ```
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PoolEvent {
+    /// An idle object outlived the eviction policy and was destroyed
+    #[error("object {object_id} expired and was evicted")]
+    Evicted {
+        /// Id of the evicted object
+        object_id: PoolObjectId,
+    },
+
+    /// An acquisition found no idle object
+    #[error("pool empty")]
+    Empty,
+
+    /// The circuit breaker opened and refuses acquisitions
+    #[error("circuit breaker opened")]
+    CircuitOpened,
+
+    /// The circuit breaker lets trial acquisitions through
+    #[error("circuit breaker half-open")]
+    CircuitHalfOpen,
+
+    /// The circuit breaker closed again
+    #[error("circuit breaker closed")]
+    CircuitClosed,
+
+    /// An objective set with
+    /// [`with_slo`](crate::PoolConfiguration::with_slo) used up its error
+    /// budget; published again only after its burn rate has dropped below 1
+    #[error("{objective} error budget exhausted")]
+    SloBudgetExhausted {
+        /// The objective that ran out
+        objective: SloObjective,
+    },
+}
+
+/// Observer called synchronously with every report.
+pub(crate) type Tap<E> = Arc<dyn Fn(&E) + Send + Sync>;
+
+/// Publishes events such as [`ReturnError`]s to any interested receivers.
+pub(crate) struct Reporter<E> {
+    sender: broadcast::Sender<E>,
+    tap: RwLock<Option<Tap<E>>>,
+}
+
+pub(crate) type ReturnErrorReporter = Reporter<ReturnError>;
+
+impl<E: Clone> Reporter<E> {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(REPORT_CHANNEL_CAPACITY);
+        Self {
+            sender,
+            tap: RwLock::new(None),
+        }
+    }
+
+    /// Have `tap` see every report from now on, replacing any earlier tap.
+    #[cfg_attr(not(feature = "tracing"), allow(dead_code))]
+    pub fn set_tap(&self, tap: Tap<E>) {
+        *self.tap.write().unwrap_or_else(PoisonError::into_inner) = Some(tap);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<E> {
+        self.sender.subscribe()
+    }
+
+    pub fn report(&self, event: E) {
+        if let Some(ref tap) = *self.tap.read().unwrap_or_else(PoisonError::into_inner) {
+            tap(&event);
+        }
+        // No receivers is the common case and not an error.
+        let _ = self.sender.send(event);
+    }
+}
+
+impl<E: Clone> Default for Reporter<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_without_receivers_is_silent() {
+        let reporter = ReturnErrorReporter::new();
+        reporter.report(ReturnError::QueueFull { object_id: PoolObjectId::new(1) });
+    }
+
+    #[test]
+    fn every_receiver_sees_reports() {
+        let reporter = ReturnErrorReporter::new();
+        let mut a = reporter.subscribe();
+        let mut b = reporter.subscribe();
+
+        reporter.report(ReturnError::ValidationFailed { object_id: PoolObjectId::new(3) });
+
+        assert_eq!(a.try_recv().unwrap(), ReturnError::ValidationFailed { object_id: PoolObjectId::new(3) });
+        assert_eq!(b.try_recv().unwrap(), ReturnError::ValidationFailed { object_id: PoolObjectId::new(3) });
+    }
+
+    #[test]
+    fn display_mentions_object_id() {
+        let msg = ReturnError::QueueFull { object_id: PoolObjectId::new(17) }.to_string();
+        assert!(msg.contains("17"));
+        assert!(msg.contains("queue full"));
+    }
+
+    #[test]
+    fn starvation_display_mentions_waiters() {
+        let msg = PoolWarning::Starvation {
+            waiters: 3,
+            longest_wait: Duration::from_secs(2),
+            since_last_return: Duration::from_secs(5),
+        }
+        .to_string();
+        assert!(msg.starts_with("3 acquisition(s) starved"));
+        assert!(msg.contains("2s"));
+    }
+
+    #[test]
+    fn hook_panic_display_names_the_hook() {
+        let msg = PoolWarning::HookPanicked {
+            hook: "on_borrow",
+            message: "boom".into(),
+        }
+        .to_string();
+        assert_eq!(msg, "on_borrow panicked: boom");
+    }
+
+    #[test]
+    fn high_churn_display_mentions_counts() {
+        let msg = PoolWarning::HighChurn { created: 12, destroyed: 11, threshold: 10 }.to_string();
+        assert_eq!(
+            msg,
+            "high churn: 12 object(s) created and 11 destroyed in the last minute (threshold 10)"
+        );
+    }
+
+    #[test]
+    fn lease_expired_display_names_the_site() {
+        let location = Location::caller();
+        let warning = |label: Option<&str>, location| PoolWarning::LeaseExpired {
+            object_id: PoolObjectId::new(4),
+            max_hold: Duration::from_secs(1),
+            label: label.map(str::to_owned),
+            location,
+        };
+        assert_eq!(warning(None, None).to_string(), "object 4 held past its 1s lease");
+        assert_eq!(
+            warning(Some("import"), Some(location)).to_string(),
+            format!("object 4 held past its 1s lease (import at {location})"),
+        );
+        assert!(warning(None, Some(location)).to_string().ends_with(&format!("(at {location})")));
+    }
+}
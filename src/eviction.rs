@@ -1,6 +1,11 @@
 //! Eviction policies for automatic object removal
 
-use dashmap::DashMap;
+use crate::concurrent::Map;
+use crate::consistency::ConsistencyReport;
+use crate::memory::MemoryAccount;
+use crate::metadata::MetaTable;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::{Duration, Instant};
 
 /// Eviction policy for pool objects
@@ -18,7 +23,7 @@ use std::time::{Duration, Instant};
 /// let pool = ObjectPool::new(vec![1, 2, 3], config);
 /// // Objects will be evicted after 1 hour
 /// ```
-#[derive(Debug, Clone, Default)]
+#[derive(Clone, Default)]
 pub enum EvictionPolicy {
     /// No eviction
     #[default]
@@ -35,6 +40,147 @@ pub enum EvictionPolicy {
         ttl: Duration,
         idle_timeout: Duration,
     },
+
+    /// Custom: objects expire when the predicate returns `true`
+    ///
+    /// Set with
+    /// [`PoolConfiguration::with_custom_eviction`](crate::PoolConfiguration::with_custom_eviction).
+    Custom(EvictionPredicate),
+}
+
+impl std::fmt::Debug for EvictionPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => f.write_str("None"),
+            Self::TimeToLive(ttl) => f.debug_tuple("TimeToLive").field(ttl).finish(),
+            Self::IdleTimeout(timeout) => f.debug_tuple("IdleTimeout").field(timeout).finish(),
+            Self::Combined { ttl, idle_timeout } => f
+                .debug_struct("Combined")
+                .field("ttl", ttl)
+                .field("idle_timeout", idle_timeout)
+                .finish(),
+            Self::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+/// Decides whether an object has expired, for [`EvictionPolicy::Custom`]
+pub type EvictionPredicate = Arc<dyn Fn(&ObjectStats) -> bool + Send + Sync>;
+
+/// What a custom eviction predicate knows about an object
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{ObjectPool, PoolConfiguration};
+/// use std::time::Duration;
+///
+/// // Evict objects older than an hour that were used fewer than 5 times.
+/// let pool = ObjectPool::new(
+///     vec![1, 2],
+///     PoolConfiguration::new().with_custom_eviction(|stats| {
+///         stats.age > Duration::from_secs(3600) && stats.uses < 5
+///     }),
+/// );
+/// assert_eq!(pool.evict_expired(), 0);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ObjectStats {
+    /// Time since the object was created, or since its time to live was
+    /// last [refreshed](TtlRefresh)
+    pub age: Duration,
+
+    /// Time since the object was last handed out or returned
+    pub idle: Duration,
+
+    /// Number of times the object has been handed out
+    pub uses: u64,
+}
+
+/// What a successful validation does to an object's time to live
+///
+/// Set with
+/// [`PoolConfiguration::with_ttl_refresh`](crate::PoolConfiguration::with_ttl_refresh).
+/// Applies whenever the validation function passes: on return with
+/// [`with_validation`](crate::PoolConfiguration::with_validation), after a
+/// failed completion under [`FailurePolicy::Validate`](crate::FailurePolicy::Validate)
+/// and in [`verify`](crate::ObjectPool::verify). Objects that have already
+/// expired are not revived, so connections that stop validating still age
+/// out.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{ObjectPool, PoolConfiguration, TtlRefresh};
+/// use std::time::Duration;
+///
+/// let pool = ObjectPool::new(
+///     vec![1],
+///     PoolConfiguration::new()
+///         .with_ttl(Duration::from_millis(50))
+///         .with_validation(|_: &i32| true)
+///         .with_ttl_refresh(TtlRefresh::Reset),
+/// );
+///
+/// for _ in 0..4 {
+///     std::thread::sleep(Duration::from_millis(20));
+///     assert_eq!(pool.verify().passed, 1);
+/// }
+/// assert!(pool.get_object().is_ok(), "kept alive past its original TTL");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TtlRefresh {
+    /// Validation leaves the time to live alone
+    #[default]
+    Never,
+
+    /// Restart the time to live, as if the object had just been created
+    Reset,
+
+    /// Push expiry back by the given duration, up to a full time to live
+    /// from now
+    Extend(Duration),
+}
+
+/// What happens to an object that is already expired when it is given back
+///
+/// Set with
+/// [`PoolConfiguration::with_expired_return`](crate::PoolConfiguration::with_expired_return).
+/// Expiry is checked as the object comes back, after its idle clock is
+/// restarted, so the outcome does not depend on whether an
+/// [`evict_expired`](crate::ObjectPool::evict_expired) sweep happens to run
+/// before or after the return. Only the time to live and custom predicates
+/// can expire an object at this point.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{ExpiredReturn, ObjectPool, PoolConfiguration};
+/// use std::time::Duration;
+///
+/// let pool = ObjectPool::new(
+///     vec![1],
+///     PoolConfiguration::new()
+///         .with_ttl(Duration::from_millis(20))
+///         .with_expired_return(ExpiredReturn::Refresh),
+/// );
+///
+/// let obj = pool.get_object().unwrap();
+/// std::thread::sleep(Duration::from_millis(40));
+/// drop(obj);
+/// assert_eq!(pool.evict_expired(), 0, "the return restarted its lifetime");
+/// assert_eq!(pool.available_count(), 1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExpiredReturn {
+    /// Eviction wins: the object is destroyed on return, as a sweep would
+    #[default]
+    Evict,
+
+    /// The return wins: the object is pooled with a fresh lifetime, its
+    /// age, idle time and use count restarted
+    Refresh,
 }
 
 /// Metadata for tracking object lifecycle
@@ -42,6 +188,7 @@ pub enum EvictionPolicy {
 pub(crate) struct ObjectMetadata {
     pub created_at: Instant,
     pub last_used: Instant,
+    pub uses: u64,
 }
 
 impl ObjectMetadata {
@@ -50,12 +197,32 @@ impl ObjectMetadata {
         Self {
             created_at: now,
             last_used: now,
+            uses: 0,
         }
     }
     
     pub fn touch(&mut self) {
         self.last_used = Instant::now();
     }
+
+    pub fn stats(&self) -> ObjectStats {
+        ObjectStats {
+            age: self.created_at.elapsed(),
+            idle: self.last_used.elapsed(),
+            uses: self.uses,
+        }
+    }
+
+    pub fn refresh(&mut self, refresh: TtlRefresh) {
+        let now = Instant::now();
+        match refresh {
+            TtlRefresh::Never => {}
+            TtlRefresh::Reset => self.created_at = now,
+            TtlRefresh::Extend(by) => {
+                self.created_at = self.created_at.checked_add(by).map_or(now, |moved| moved.min(now));
+            }
+        }
+    }
     
     pub fn is_expired(&self, policy: &EvictionPolicy) -> bool {
         match policy {
@@ -69,50 +236,239 @@ impl ObjectMetadata {
             EvictionPolicy::Combined { ttl, idle_timeout } => {
                 self.created_at.elapsed() > *ttl || self.last_used.elapsed() > *idle_timeout
             }
+            EvictionPolicy::Custom(predicate) => predicate(&self.stats()),
         }
     }
 }
 
 /// Tracker for object metadata
 pub(crate) struct EvictionTracker<T> {
-    metadata: DashMap<usize, ObjectMetadata>,
+    metadata: Map<usize, ObjectMetadata>,
     policy: EvictionPolicy,
-    _phantom: std::marker::PhantomData<T>,
+    refresh: TtlRefresh,
+    expired_return: ExpiredReturn,
+    /// Bumped by `invalidate`; objects from older epochs count as expired.
+    epoch: AtomicU64,
+    /// Epoch each object was created in. Only objects created after the
+    /// first `invalidate` are recorded; a missing entry means epoch 0.
+    epochs: Map<usize, u64>,
+    /// Application metadata attached through guards.
+    user_meta: MetaTable,
+    /// Size estimates of the objects, when memory accounting is enabled.
+    memory: Option<MemoryAccount<T>>,
+    _phantom: std::marker::PhantomData<fn() -> T>,
 }
 
 impl<T> EvictionTracker<T> {
     pub fn new(policy: EvictionPolicy) -> Self {
+        // The `minimal` feature keeps no per-object timestamps, which every
+        // policy but `None` needs.
+        #[cfg(feature = "minimal")]
+        let policy = {
+            drop(policy);
+            EvictionPolicy::None
+        };
         Self {
-            metadata: DashMap::new(),
+            metadata: Map::new(),
             policy,
+            refresh: TtlRefresh::Never,
+            expired_return: ExpiredReturn::Evict,
+            epoch: AtomicU64::new(0),
+            epochs: Map::new(),
+            user_meta: MetaTable::default(),
+            memory: None,
             _phantom: std::marker::PhantomData,
         }
     }
 
+    pub fn with_refresh(mut self, refresh: TtlRefresh) -> Self {
+        self.refresh = refresh;
+        self
+    }
+
+    pub fn with_expired_return(mut self, expired_return: ExpiredReturn) -> Self {
+        self.expired_return = expired_return;
+        self
+    }
+
+    pub fn with_memory(mut self, memory: Option<MemoryAccount<T>>) -> Self {
+        self.memory = memory;
+        self
+    }
+
+    pub fn memory(&self) -> Option<&MemoryAccount<T>> {
+        self.memory.as_ref()
+    }
+
     pub fn track_object(&self, id: usize) {
+        self.track_object_from(id, self.epoch());
+    }
+
+    /// Track `id` as created in `epoch`, which may already have ended if
+    /// `invalidate` ran while the object was being built.
+    pub fn track_object_from(&self, id: usize, epoch: u64) {
+        if epoch > 0 {
+            self.epochs.insert(id, epoch);
+        }
         if !matches!(self.policy, EvictionPolicy::None) {
             self.metadata.insert(id, ObjectMetadata::new());
         }
     }
 
-    pub fn touch_object(&self, id: usize) {
-        if !matches!(self.policy, EvictionPolicy::None)
-            && let Some(mut meta) = self.metadata.get_mut(&id) {
+    /// Touch `id` and count a use, as it is handed out.
+    pub fn checked_out(&self, id: usize) {
+        if !matches!(self.policy, EvictionPolicy::None) {
+            self.metadata.update(&id, |meta| {
                 meta.touch();
+                meta.uses += 1;
+            });
+        }
+    }
+
+    pub fn touch_object(&self, id: usize) {
+        if !matches!(self.policy, EvictionPolicy::None) {
+            self.metadata.update(&id, ObjectMetadata::touch);
+        }
+    }
+
+    /// Touch `id` as it is given back. Returns `false` if it has expired
+    /// and is to be evicted rather than pooled; under
+    /// [`ExpiredReturn::Refresh`] it starts a fresh lifetime instead.
+    pub fn returned(&self, id: usize) -> bool {
+        if matches!(self.policy, EvictionPolicy::None) {
+            return true;
+        }
+        self.touch_object(id);
+        if !self.is_expired(id) {
+            return true;
+        }
+        match self.expired_return {
+            ExpiredReturn::Evict => false,
+            ExpiredReturn::Refresh => {
+                self.metadata.update(&id, |meta| *meta = ObjectMetadata::new());
+                true
             }
+        }
     }
 
+    /// Record that `id` passed validation, refreshing its time to live as
+    /// configured unless it has already expired.
+    pub fn validated(&self, id: usize) {
+        if self.refresh == TtlRefresh::Never || self.is_expired(id) {
+            return;
+        }
+        self.metadata.update(&id, |meta| meta.refresh(self.refresh));
+    }
+
+    /// Whether `id` has outlived the eviction policy or was created before
+    /// the last `invalidate`.
     pub fn is_expired(&self, id: usize) -> bool {
+        if self.is_stale(id) {
+            return true;
+        }
         if matches!(self.policy, EvictionPolicy::None) {
             return false;
         }
         self.metadata
-            .get(&id)
-            .is_some_and(|meta| meta.is_expired(&self.policy))
+            .read(&id, |meta| meta.is_expired(&self.policy))
+            .unwrap_or(false)
     }
 
     pub fn remove_object(&self, id: usize) {
         self.metadata.remove(&id);
+        self.epochs.remove(&id);
+        self.user_meta.remove(id);
+        if let Some(ref memory) = self.memory {
+            memory.remove(id);
+        }
+    }
+
+    /// Check that the per-object tables match the pool's population:
+    /// every object has eviction metadata and a size estimate when those
+    /// are kept, size estimates are idle or active as the objects are, and
+    /// no table holds entries for more objects than exist.
+    pub fn check_tables(&self, report: &mut ConsistencyReport) {
+        let population = report.population;
+        if !matches!(self.policy, EvictionPolicy::None) {
+            let tracked = self.metadata.len();
+            report.check(tracked == population, || {
+                format!("{tracked} objects tracked for eviction, population {population}")
+            });
+        }
+        if let Some(ref memory) = self.memory {
+            let (idle, active) = memory.tracked();
+            let measured = idle + active;
+            report.check(measured == population, || {
+                format!("{measured} objects with a size estimate, population {population}")
+            });
+            let (available, checked_out) = (report.available, report.active);
+            report.check(idle == available, || {
+                format!("{idle} objects measured as idle, {available} available")
+            });
+            report.check(active == checked_out, || {
+                format!("{active} objects measured as checked out, {checked_out} active")
+            });
+        }
+        let epochs = self.epochs.len();
+        report.check(epochs <= population, || {
+            format!("{epochs} objects with an epoch, population {population}")
+        });
+        let with_meta = self.user_meta.len();
+        report.check(with_meta <= population, || {
+            format!("{with_meta} objects with metadata, population {population}")
+        });
+    }
+
+    pub fn user_meta(&self) -> &MetaTable {
+        &self.user_meta
+    }
+
+    pub fn policy(&self) -> &EvictionPolicy {
+        &self.policy
+    }
+
+    /// Time left before `id` outlives the policy's time to live, or `None`
+    /// if the policy has none. Zero once `id` is stale.
+    pub fn remaining_ttl(&self, id: usize) -> Option<Duration> {
+        let (EvictionPolicy::TimeToLive(ttl) | EvictionPolicy::Combined { ttl, .. }) = self.policy else {
+            return None;
+        };
+        self.remaining(id, ttl, |meta| meta.created_at)
+    }
+
+    /// Time left before `id` exceeds the policy's idle timeout, counted
+    /// from when it was last handed out or returned, or `None` if the
+    /// policy has none. Zero once `id` is stale.
+    pub fn idle_remaining(&self, id: usize) -> Option<Duration> {
+        let (EvictionPolicy::IdleTimeout(timeout) | EvictionPolicy::Combined { idle_timeout: timeout, .. }) =
+            self.policy
+        else {
+            return None;
+        };
+        self.remaining(id, timeout, |meta| meta.last_used)
+    }
+
+    fn remaining(&self, id: usize, limit: Duration, since: fn(&ObjectMetadata) -> Instant) -> Option<Duration> {
+        if self.is_stale(id) {
+            return Some(Duration::ZERO);
+        }
+        self.metadata.read(&id, |meta| limit.saturating_sub(since(meta).elapsed()))
+    }
+
+    /// Whether `id` was created before the last `invalidate`.
+    pub fn is_stale(&self, id: usize) -> bool {
+        let epoch = self.epoch();
+        epoch > 0 && self.epochs.read(&id, |born| *born).unwrap_or(0) < epoch
+    }
+
+    /// Start a new epoch, making every object tracked so far stale.
+    /// Returns the new epoch.
+    pub fn invalidate(&self) -> u64 {
+        self.epoch.fetch_add(1, Ordering::AcqRel) + 1
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch.load(Ordering::Acquire)
     }
 
     /// Returns the IDs of all currently expired objects. Useful for inspection;
@@ -123,9 +479,9 @@ impl<T> EvictionTracker<T> {
             return Vec::new();
         }
         self.metadata
-            .iter()
-            .filter(|entry| entry.value().is_expired(&self.policy))
-            .map(|entry| *entry.key())
+            .collect(|id, meta| meta.is_expired(&self.policy).then_some(*id))
+            .into_iter()
+            .flatten()
             .collect()
     }
 }
@@ -274,11 +630,98 @@ mod tests {
         assert!(tracker.get_expired_objects().is_empty());
     }
 
+    #[test]
+    fn invalidate_makes_earlier_objects_stale() {
+        let tracker = EvictionTracker::<i32>::new(EvictionPolicy::None);
+        tracker.track_object(1);
+        assert!(!tracker.is_expired(1));
+
+        assert_eq!(tracker.invalidate(), 1);
+        tracker.track_object(2);
+        assert!(tracker.is_stale(1) && tracker.is_expired(1));
+        assert!(!tracker.is_stale(2));
+
+        assert_eq!(tracker.invalidate(), 2);
+        assert!(tracker.is_stale(2));
+    }
+
     #[test]
     fn tracker_unknown_id_is_not_expired() {
         let tracker = EvictionTracker::<i32>::new(EvictionPolicy::TimeToLive(Duration::from_millis(1)));
         // id 99 was never tracked
         assert!(!tracker.is_expired(99));
     }
-}
 
+    #[test]
+    fn validation_refreshes_ttl_only_when_configured() {
+        let policy = EvictionPolicy::TimeToLive(Duration::from_millis(30));
+        let plain = EvictionTracker::<i32>::new(policy.clone());
+        let reset = EvictionTracker::<i32>::new(policy).with_refresh(TtlRefresh::Reset);
+        plain.track_object(1);
+        reset.track_object(1);
+
+        std::thread::sleep(Duration::from_millis(20));
+        plain.validated(1);
+        reset.validated(1);
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(plain.is_expired(1));
+        assert!(!reset.is_expired(1));
+    }
+
+    #[test]
+    fn expired_returns_are_evicted_or_restarted() {
+        let policy = EvictionPolicy::TimeToLive(Duration::from_millis(5));
+        let evict = EvictionTracker::<i32>::new(policy.clone());
+        let refresh = EvictionTracker::<i32>::new(policy).with_expired_return(ExpiredReturn::Refresh);
+        evict.track_object(1);
+        refresh.track_object(1);
+        assert!(evict.returned(1) && refresh.returned(1));
+
+        std::thread::sleep(Duration::from_millis(10));
+        assert!(!evict.returned(1));
+        assert!(refresh.returned(1));
+        assert!(!refresh.is_expired(1));
+    }
+
+    #[test]
+    fn validation_does_not_revive_expired_objects() {
+        let tracker = EvictionTracker::<i32>::new(EvictionPolicy::TimeToLive(Duration::from_millis(5)))
+            .with_refresh(TtlRefresh::Reset);
+        tracker.track_object(1);
+        std::thread::sleep(Duration::from_millis(10));
+        tracker.validated(1);
+        assert!(tracker.is_expired(1));
+    }
+
+    #[test]
+    fn extend_pushes_expiry_back_at_most_a_full_ttl() {
+        let mut meta = ObjectMetadata::new();
+        let created = meta.created_at;
+        meta.refresh(TtlRefresh::Extend(Duration::from_secs(3600)));
+        assert!(meta.created_at >= created && meta.created_at <= Instant::now());
+
+        meta.created_at = Instant::now() - Duration::from_secs(60);
+        let before = meta.created_at;
+        meta.refresh(TtlRefresh::Extend(Duration::from_secs(10)));
+        assert_eq!(meta.created_at, before + Duration::from_secs(10));
+    }
+
+    #[test]
+    fn custom_policy_sees_uses_and_idle_time() {
+        let policy = EvictionPolicy::Custom(Arc::new(|stats: &ObjectStats| {
+            stats.uses < 2 && stats.idle > Duration::from_millis(10)
+        }));
+        let tracker = EvictionTracker::<i32>::new(policy);
+        tracker.track_object(1);
+        tracker.track_object(2);
+        tracker.checked_out(2);
+        tracker.checked_out(2);
+        assert!(!tracker.is_expired(1));
+
+        std::thread::sleep(Duration::from_millis(20));
+        assert!(tracker.is_expired(1));
+        assert!(!tracker.is_expired(2), "used often enough");
+        assert_eq!(tracker.remaining_ttl(1), None);
+        assert_eq!(format!("{:?}", tracker.policy()), "Custom(..)");
+    }
+}
@@ -0,0 +1,342 @@
+//! Primary/standby pool pairs with switchover
+
+use crate::errors::{PoolError, PoolResult};
+use crate::events::Reporter;
+use crate::health::HealthStatus;
+use crate::metrics::PoolMetrics;
+use crate::observable::ObservablePool;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU8, Ordering};
+use tokio::sync::broadcast;
+
+/// Which pool of a [`FailoverPool`] serves acquisitions
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailoverRole {
+    /// The preferred pool
+    Primary,
+
+    /// The warm standby
+    Standby,
+}
+
+impl FailoverRole {
+    fn other(self) -> Self {
+        match self {
+            Self::Primary => Self::Standby,
+            Self::Standby => Self::Primary,
+        }
+    }
+}
+
+/// Why a [`FailoverPool`] switched pools
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SwitchReason {
+    /// The serving pool's circuit breaker opened
+    CircuitBreakerOpen,
+
+    /// The serving pool reported itself unhealthy
+    Unhealthy,
+
+    /// [`FailoverPool::failover`] or [`FailoverPool::failback`] was called
+    Manual,
+}
+
+/// A change of serving pool
+///
+/// Published on the channel returned by [`FailoverPool::switchovers`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Switchover {
+    /// The pool that was serving
+    pub from: FailoverRole,
+
+    /// The pool serving from now on
+    pub to: FailoverRole,
+
+    /// What triggered the switch
+    pub reason: SwitchReason,
+}
+
+/// A primary pool with a warm standby, for pools backed by redundant
+/// endpoints
+///
+/// Acquisitions go to the serving pool returned by [`pool`](Self::pool).
+/// Each call checks the serving pool's health first: if its circuit breaker
+/// is open or it reports itself unhealthy (see [`HealthStatus::is_healthy`],
+/// which also covers a pool more than 90% checked out) while the other pool
+/// is healthy, the pair switches over. Switching is symmetric, so a failing
+/// standby hands back to a recovered primary. [`failover`](Self::failover)
+/// and [`failback`](Self::failback) switch by hand.
+///
+/// Objects already checked out stay with the pool they came from. Every
+/// switch is published on [`switchovers`](Self::switchovers).
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{FailoverPool, FailoverRole, ObjectPool, PoolConfiguration, SwitchReason};
+/// use std::time::Duration;
+///
+/// let config = || PoolConfiguration::new().with_circuit_breaker(1, Duration::from_secs(60));
+/// let pair = FailoverPool::new(
+///     ObjectPool::new(vec!["primary"], config()),
+///     ObjectPool::new(vec!["standby"], config()),
+/// );
+/// let mut switchovers = pair.switchovers();
+///
+/// // Work against the primary's endpoint fails and opens its breaker.
+/// pair.pool().report_failure();
+///
+/// assert_eq!(*pair.pool().get_object().unwrap(), "standby");
+/// assert_eq!(pair.serving(), FailoverRole::Standby);
+/// assert_eq!(switchovers.try_recv().unwrap().reason, SwitchReason::CircuitBreakerOpen);
+/// ```
+pub struct FailoverPool<P> {
+    primary: P,
+    standby: P,
+    /// 0 while the primary serves, 1 while the standby does.
+    serving: AtomicU8,
+    switchovers: Reporter<Switchover>,
+}
+
+impl<P: ObservablePool> FailoverPool<P> {
+    /// Pair `primary` with `standby`; the primary serves first
+    pub fn new(primary: P, standby: P) -> Self {
+        Self {
+            primary,
+            standby,
+            serving: AtomicU8::new(0),
+            switchovers: Reporter::new(),
+        }
+    }
+
+    /// The pool currently serving acquisitions, after switching over if it
+    /// has become unhealthy
+    pub fn pool(&self) -> &P {
+        let role = self.serving();
+        if let Some(reason) = unhealthy(&self.get(role).get_health_status())
+            && self.get(role.other()).get_health_status().is_healthy
+        {
+            self.switch(role, reason);
+        }
+        self.get(self.serving())
+    }
+
+    /// Run `acquire` against the serving pool, retrying once on the other
+    /// pool if the attempt found the serving pool's breaker open and the
+    /// pair switched over
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{FailoverPool, ObjectPool, PoolConfiguration};
+    ///
+    /// let pair = FailoverPool::new(
+    ///     ObjectPool::new(vec![1], PoolConfiguration::default()),
+    ///     ObjectPool::new(vec![2], PoolConfiguration::default()),
+    /// );
+    /// let obj = pair.acquire(|pool| pool.get_object()).unwrap();
+    /// assert_eq!(*obj, 1);
+    /// ```
+    pub fn acquire<R>(&self, acquire: impl Fn(&P) -> PoolResult<R>) -> PoolResult<R> {
+        let before = self.serving();
+        match acquire(self.pool()) {
+            Err(PoolError::CircuitBreakerOpen) => {
+                let retry = self.pool();
+                if self.serving() == before {
+                    return Err(PoolError::CircuitBreakerOpen);
+                }
+                acquire(retry)
+            }
+            other => other,
+        }
+    }
+
+    /// Which pool is serving, without checking health
+    #[must_use]
+    pub fn serving(&self) -> FailoverRole {
+        match self.serving.load(Ordering::Acquire) {
+            0 => FailoverRole::Primary,
+            _ => FailoverRole::Standby,
+        }
+    }
+
+    /// The pool in `role`
+    #[must_use]
+    pub fn get(&self, role: FailoverRole) -> &P {
+        match role {
+            FailoverRole::Primary => &self.primary,
+            FailoverRole::Standby => &self.standby,
+        }
+    }
+
+    /// Switch to the standby; `false` if it was already serving
+    pub fn failover(&self) -> bool {
+        self.switch(FailoverRole::Primary, SwitchReason::Manual)
+    }
+
+    /// Switch back to the primary; `false` if it was already serving
+    pub fn failback(&self) -> bool {
+        self.switch(FailoverRole::Standby, SwitchReason::Manual)
+    }
+
+    /// Subscribe to switchovers
+    #[must_use]
+    pub fn switchovers(&self) -> broadcast::Receiver<Switchover> {
+        self.switchovers.subscribe()
+    }
+
+    /// Switch away from `from`, if it is still serving.
+    fn switch(&self, from: FailoverRole, reason: SwitchReason) -> bool {
+        let to = from.other();
+        let switched = self
+            .serving
+            .compare_exchange(role_index(from), role_index(to), Ordering::AcqRel, Ordering::Acquire)
+            .is_ok();
+        if switched {
+            self.switchovers.report(Switchover { from, to, reason });
+        }
+        switched
+    }
+}
+
+fn role_index(role: FailoverRole) -> u8 {
+    match role {
+        FailoverRole::Primary => 0,
+        FailoverRole::Standby => 1,
+    }
+}
+
+/// Why a pool with `health` should not serve, if it should not.
+fn unhealthy(health: &HealthStatus) -> Option<SwitchReason> {
+    if health.circuit_breaker_open {
+        Some(SwitchReason::CircuitBreakerOpen)
+    } else if !health.is_healthy {
+        Some(SwitchReason::Unhealthy)
+    } else {
+        None
+    }
+}
+
+/// Observes the serving pool.
+impl<P: ObservablePool> ObservablePool for FailoverPool<P> {
+    fn available_count(&self) -> usize {
+        self.get(self.serving()).available_count()
+    }
+
+    fn active_count(&self) -> usize {
+        self.get(self.serving()).active_count()
+    }
+
+    fn capacity(&self) -> usize {
+        self.get(self.serving()).capacity()
+    }
+
+    fn get_metrics(&self) -> PoolMetrics {
+        self.get(self.serving()).get_metrics()
+    }
+
+    fn get_health_status(&self) -> HealthStatus {
+        self.get(self.serving()).get_health_status()
+    }
+
+    fn export_metrics(&self) -> HashMap<String, String> {
+        self.get(self.serving()).export_metrics()
+    }
+
+    fn export_metrics_prometheus(
+        &self,
+        pool_name: &str,
+        tags: Option<&HashMap<String, String>>,
+    ) -> String {
+        self.get(self.serving()).export_metrics_prometheus(pool_name, tags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ObjectPool, PoolConfiguration};
+    use std::time::Duration;
+
+    fn pair() -> FailoverPool<ObjectPool<&'static str>> {
+        let config = || {
+            PoolConfiguration::new()
+                .with_max_pool_size(1)
+                .with_circuit_breaker(1, Duration::from_secs(60))
+        };
+        FailoverPool::new(
+            ObjectPool::new(vec!["primary"], config()),
+            ObjectPool::new(vec!["standby"], config()),
+        )
+    }
+
+    #[test]
+    fn acquire_retries_on_the_standby_when_the_breaker_opens() {
+        let pair = pair();
+        let first = std::cell::Cell::new(true);
+        let obj = pair
+            .acquire(|pool| {
+                // The breaker opens between the health check and the attempt.
+                if first.replace(false) {
+                    pool.report_failure();
+                }
+                pool.get_object()
+            })
+            .unwrap();
+        assert_eq!(*obj, "standby");
+    }
+
+    #[test]
+    fn fully_checked_out_primary_counts_as_unhealthy() {
+        let pair = pair();
+        let mut switchovers = pair.switchovers();
+        let _held = pair.pool().get_object().unwrap();
+
+        assert_eq!(*pair.pool().get_object().unwrap(), "standby");
+        assert_eq!(switchovers.try_recv().unwrap().reason, SwitchReason::Unhealthy);
+    }
+
+    #[test]
+    fn manual_switches_are_reported_once() {
+        let pair = pair();
+        let mut switchovers = pair.switchovers();
+
+        assert!(pair.failover());
+        assert!(!pair.failover(), "standby already serving");
+        assert_eq!(*pair.pool().get_object().unwrap(), "standby");
+        assert!(pair.failback());
+        assert_eq!(pair.serving(), FailoverRole::Primary);
+
+        let expected = Switchover {
+            from: FailoverRole::Primary,
+            to: FailoverRole::Standby,
+            reason: SwitchReason::Manual,
+        };
+        assert_eq!(switchovers.try_recv().unwrap(), expected);
+        assert_eq!(switchovers.try_recv().unwrap().to, FailoverRole::Primary);
+        assert!(switchovers.try_recv().is_err());
+    }
+
+    #[test]
+    fn no_switch_to_an_unhealthy_standby() {
+        let pair = pair();
+        let _primary = pair.get(FailoverRole::Primary).get_object().unwrap();
+        let _standby = pair.get(FailoverRole::Standby).get_object().unwrap();
+
+        // Both pools are fully checked out, hence unhealthy.
+        assert!(!pair.get(FailoverRole::Standby).get_health_status().is_healthy);
+        pair.pool();
+        assert_eq!(pair.serving(), FailoverRole::Primary);
+    }
+
+    #[test]
+    fn observes_the_serving_pool() {
+        let pair = pair();
+        pair.failover();
+        let _held = pair.get(FailoverRole::Standby).get_object().unwrap();
+        assert_eq!(ObservablePool::active_count(&pair), 1);
+        assert_eq!(pair.get(FailoverRole::Primary).active_count(), 0);
+    }
+}
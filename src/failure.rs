@@ -0,0 +1,39 @@
+//! Handling of objects whose use failed
+
+/// What happens to an object whose guard was
+/// [completed](crate::PooledObject::complete) with an error
+///
+/// Set with
+/// [`PoolConfiguration::with_failure_policy`](crate::PoolConfiguration::with_failure_policy).
+/// Objects completed with `Ok`, released or simply dropped are returned as
+/// usual whatever the policy.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{FailurePolicy, ObjectPool, PoolConfiguration};
+///
+/// let pool = ObjectPool::new(
+///     vec![1, 2],
+///     PoolConfiguration::new().with_failure_policy(FailurePolicy::Discard),
+/// );
+///
+/// let obj = pool.get_object().unwrap();
+/// let _ = obj.complete(Err::<(), _>("query failed"));
+///
+/// assert_eq!(pool.available_count(), 1);
+/// assert_eq!(pool.get_metrics().discarded_objects, 1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FailurePolicy {
+    /// Return the object as usual
+    Return,
+
+    /// Run the validation function before taking the object back, even if
+    /// objects are not otherwise validated on return
+    #[default]
+    Validate,
+
+    /// Destroy the object, as [`discard`](crate::PooledObject::discard) does
+    Discard,
+}
@@ -0,0 +1,118 @@
+//! Process-wide pools declared with [`pooled!`](crate::pooled)
+
+/// Declare a process-wide pool for a type
+///
+/// `pooled!(MyType, factory)` adds three associated functions to `MyType`:
+///
+/// - `MyType::global_pool()`: the pool, a [`DynamicObjectPool`](crate::DynamicObjectPool)
+///   created with `factory` on first use and kept for the life of the
+///   process
+/// - `MyType::pooled()`: take an object from it, like
+///   [`DynamicObjectPool::get_object`](crate::DynamicObjectPool::get_object)
+/// - `MyType::pooled_async()`: the same, like
+///   [`DynamicObjectPool::get_object_async`](crate::DynamicObjectPool::get_object_async)
+///
+/// A third argument gives the pool's [`PoolConfiguration`](crate::PoolConfiguration);
+/// it is evaluated once, when the pool is created. As the functions are an
+/// inherent `impl`, `MyType` must be defined in the invoking crate.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{pooled, PoolConfiguration};
+///
+/// struct Scratch {
+///     buf: Vec<u8>,
+/// }
+///
+/// pooled!(
+///     Scratch,
+///     || Scratch { buf: Vec::with_capacity(4096) },
+///     PoolConfiguration::new().with_max_pool_size(16)
+/// );
+///
+/// let mut scratch = Scratch::pooled().unwrap();
+/// scratch.buf.extend_from_slice(b"hello");
+/// assert_eq!(Scratch::global_pool().active_count(), 1);
+/// drop(scratch);
+/// assert_eq!(Scratch::global_pool().capacity(), 16);
+/// ```
+#[macro_export]
+macro_rules! pooled {
+    ($ty:ty, $factory:expr $(,)?) => {
+        $crate::pooled!($ty, $factory, $crate::PoolConfiguration::default());
+    };
+    ($ty:ty, $factory:expr, $config:expr $(,)?) => {
+        // Callers typically use only some of these.
+        #[allow(dead_code)]
+        impl $ty {
+            /// The process-wide pool of this type, created on first use
+            pub fn global_pool() -> &'static $crate::DynamicObjectPool<$ty> {
+                static POOL: ::std::sync::OnceLock<$crate::DynamicObjectPool<$ty>> = ::std::sync::OnceLock::new();
+                POOL.get_or_init(|| $crate::DynamicObjectPool::new($factory, $config))
+            }
+
+            /// Take an object from the process-wide pool of this type
+            #[track_caller]
+            pub fn pooled() -> $crate::PoolResult<$crate::PooledObject<$ty>> {
+                Self::global_pool().get_object()
+            }
+
+            /// Take an object from the process-wide pool of this type,
+            /// waiting asynchronously
+            #[track_caller]
+            pub fn pooled_async() -> $crate::Acquire<'static, $ty> {
+                Self::global_pool().get_object_async()
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{PoolConfiguration, WaitPolicy};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static CREATED: AtomicUsize = AtomicUsize::new(0);
+
+    struct Conn(usize);
+
+    crate::pooled!(Conn, || Conn(CREATED.fetch_add(1, Ordering::Relaxed)));
+
+    struct Limited;
+
+    crate::pooled!(
+        Limited,
+        || Limited,
+        PoolConfiguration::new()
+            .with_max_pool_size(1)
+            .with_wait_on_empty(WaitPolicy::FailFast),
+    );
+
+    #[test]
+    fn one_pool_per_type_reused_across_calls() {
+        let first = Conn::pooled().unwrap();
+        let id = first.0;
+        drop(first);
+        assert_eq!(Conn::pooled().unwrap().0, id, "the returned object is reused");
+        assert!(std::ptr::eq(Conn::global_pool(), Conn::global_pool()));
+        assert_eq!(CREATED.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn configuration_applies() {
+        let _held = Limited::pooled().unwrap();
+        assert!(Limited::pooled().is_err());
+        assert_eq!(Limited::global_pool().capacity(), 1);
+    }
+
+    struct Session;
+
+    crate::pooled!(Session, || Session);
+
+    #[tokio::test]
+    async fn async_accessor_records_the_caller() {
+        let (obj, line) = (Session::pooled_async(), line!());
+        assert_eq!(obj.await.unwrap().acquired_at().unwrap().line(), line);
+    }
+}
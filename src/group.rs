@@ -0,0 +1,239 @@
+//! Active-object limits shared between pools
+
+use crate::errors::{PoolError, PoolResult};
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A cap on the combined number of checked-out objects across several pools
+///
+/// Useful when pools draw from one external quota — for example a read pool
+/// and a write pool that together may hold at most 100 database connections.
+/// Every pool configured with
+/// [`with_limit_group`](crate::PoolConfiguration::with_limit_group) counts
+/// its checked-out objects against the group as well as against its own
+/// limits; an acquisition that would push the group over its limit fails
+/// with [`PoolError::GroupLimitReached`] and is counted in that pool's
+/// [`PoolMetrics::group_limit_rejections`](crate::PoolMetrics::group_limit_rejections).
+///
+/// Cloning a `LimitGroup` yields another handle to the same group.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{LimitGroup, ObjectPool, PoolConfiguration, PoolError};
+///
+/// let quota = LimitGroup::new("db", 2);
+/// let reads = ObjectPool::new(vec![1, 2], PoolConfiguration::new().with_limit_group(quota.clone()));
+/// let writes = ObjectPool::new(vec![3, 4], PoolConfiguration::new().with_limit_group(quota.clone()));
+///
+/// let _r = reads.get_object().unwrap();
+/// let _w = writes.get_object().unwrap();
+/// assert!(matches!(reads.get_object(), Err(PoolError::GroupLimitReached(_))));
+///
+/// assert_eq!(quota.active(), 2);
+/// assert_eq!(quota.rejections(), 1);
+/// assert_eq!(reads.get_metrics().group_limit_rejections, 1);
+/// ```
+#[derive(Clone)]
+pub struct LimitGroup {
+    state: Arc<GroupState>,
+}
+
+struct GroupState {
+    name: String,
+    limit: usize,
+    active: AtomicUsize,
+    rejections: AtomicUsize,
+}
+
+impl LimitGroup {
+    /// Create a group allowing at most `limit` objects checked out across
+    /// all member pools
+    pub fn new(name: impl Into<String>, limit: usize) -> Self {
+        Self {
+            state: Arc::new(GroupState {
+                name: name.into(),
+                limit,
+                active: AtomicUsize::new(0),
+                rejections: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    /// Name the group was created with
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.state.name
+    }
+
+    /// Maximum number of objects checked out across all member pools
+    #[must_use]
+    pub fn limit(&self) -> usize {
+        self.state.limit
+    }
+
+    /// Objects currently checked out across all member pools
+    #[must_use]
+    pub fn active(&self) -> usize {
+        self.state.active.load(Ordering::Relaxed)
+    }
+
+    /// Acquisitions in any member pool refused because the group was full
+    #[must_use]
+    pub fn rejections(&self) -> usize {
+        self.state.rejections.load(Ordering::Relaxed)
+    }
+
+    /// Count one more checked-out object; `false`, counted as a rejection,
+    /// if the group is full.
+    fn try_enter(&self) -> bool {
+        let state = &self.state;
+        let entered = state
+            .active
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |active| {
+                (active < state.limit).then_some(active + 1)
+            })
+            .is_ok();
+        if !entered {
+            state.rejections.fetch_add(1, Ordering::Relaxed);
+        }
+        entered
+    }
+
+    fn leave(&self, n: usize) {
+        self.state.active.fetch_sub(n, Ordering::AcqRel);
+    }
+}
+
+impl std::fmt::Debug for LimitGroup {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LimitGroup")
+            .field("name", &self.state.name)
+            .field("limit", &self.state.limit)
+            .field("active", &self.active())
+            .finish()
+    }
+}
+
+/// Which limit refused an active slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Refusal {
+    MaxActive,
+    Group,
+}
+
+/// A pool's count of checked-out objects, enforcing `max_active_objects`
+/// and the pool's limit group (if any) together.
+pub(crate) struct ActiveSlots {
+    count: AtomicUsize,
+    max: Option<usize>,
+    group: Option<LimitGroup>,
+}
+
+impl ActiveSlots {
+    pub fn new(max: Option<usize>, group: Option<LimitGroup>) -> Self {
+        Self {
+            count: AtomicUsize::new(0),
+            max,
+            group,
+        }
+    }
+
+    /// Reserve one slot. The check and increment are a single atomic
+    /// operation, so concurrent callers cannot overshoot either limit.
+    pub fn try_acquire(&self) -> PoolResult<()> {
+        self.reserve().map_err(|refusal| match (refusal, &self.group) {
+            (Refusal::Group, Some(group)) => PoolError::GroupLimitReached(group.name().to_string()),
+            _ => PoolError::MaxActiveObjectsReached,
+        })
+    }
+
+    /// [`try_acquire`](Self::try_acquire) without building an error, so it
+    /// never allocates.
+    pub fn reserve(&self) -> Result<(), Refusal> {
+        match self.max {
+            Some(max) => {
+                self.count
+                    .fetch_update(Ordering::AcqRel, Ordering::Acquire, |current| {
+                        (current < max).then_some(current + 1)
+                    })
+                    .map_err(|_| Refusal::MaxActive)?;
+            }
+            None => {
+                self.count.fetch_add(1, Ordering::AcqRel);
+            }
+        }
+
+        if let Some(ref group) = self.group
+            && !group.try_enter()
+        {
+            self.count.fetch_sub(1, Ordering::AcqRel);
+            return Err(Refusal::Group);
+        }
+        Ok(())
+    }
+
+    /// Give back `n` slots.
+    pub fn release(&self, n: usize) {
+        self.count.fetch_sub(n, Ordering::AcqRel);
+        if let Some(ref group) = self.group {
+            group.leave(n);
+        }
+    }
+
+    pub fn load(&self) -> usize {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// Whether `try_acquire` would currently succeed.
+    pub fn has_room(&self) -> bool {
+        self.max.is_none_or(|max| self.load() < max)
+            && self.group.as_ref().is_none_or(|group| group.active() < group.limit())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn group_is_shared_between_slots() {
+        let group = LimitGroup::new("g", 2);
+        let a = ActiveSlots::new(None, Some(group.clone()));
+        let b = ActiveSlots::new(None, Some(group.clone()));
+
+        a.try_acquire().unwrap();
+        b.try_acquire().unwrap();
+        assert!(matches!(a.try_acquire(), Err(PoolError::GroupLimitReached(name)) if name == "g"));
+        assert_eq!(a.load(), 1);
+        assert_eq!(group.rejections(), 1);
+
+        b.release(1);
+        assert!(a.try_acquire().is_ok());
+        assert_eq!(group.active(), 2);
+    }
+
+    #[test]
+    fn local_limit_is_checked_before_group() {
+        let group = LimitGroup::new("g", 10);
+        let slots = ActiveSlots::new(Some(1), Some(group.clone()));
+
+        slots.try_acquire().unwrap();
+        assert!(matches!(slots.try_acquire(), Err(PoolError::MaxActiveObjectsReached)));
+        assert_eq!(group.active(), 1);
+        assert_eq!(group.rejections(), 0);
+    }
+
+    #[test]
+    fn release_returns_slots_to_group() {
+        let group = LimitGroup::new("g", 3);
+        let slots = ActiveSlots::new(None, Some(group.clone()));
+        for _ in 0..3 {
+            slots.try_acquire().unwrap();
+        }
+        slots.release(3);
+        assert_eq!(slots.load(), 0);
+        assert_eq!(group.active(), 0);
+    }
+}
@@ -21,6 +21,9 @@ pub struct HealthStatus {
     /// Whether the circuit breaker is currently open
     pub circuit_breaker_open: bool,
 
+    /// Whether the pool is paused for maintenance
+    pub paused: bool,
+
     /// Number of warnings detected
     pub warning_count: usize,
 
@@ -77,6 +80,7 @@ impl HealthStatus {
         Self {
             is_healthy,
             circuit_breaker_open,
+            paused: false,
             warning_count: warnings.len(),
             utilization,
             available_objects: available,
@@ -90,6 +94,54 @@ impl HealthStatus {
     pub fn is_healthy(&self) -> bool {
         self.is_healthy
     }
+
+    /// Warn if fewer than `fraction` of a fixed pool's `original` objects
+    /// are left.
+    pub(crate) fn warn_on_capacity_loss(&mut self, population: usize, original: usize, fraction: f64) {
+        if (population as f64) < original as f64 * fraction {
+            self.warnings.push(format!(
+                "Capacity lost: {population} of {original} objects left"
+            ));
+            self.warning_count = self.warnings.len();
+        }
+    }
+
+    /// Warn if more than `threshold` objects were `replaced` in the last
+    /// minute.
+    pub(crate) fn warn_on_churn(&mut self, replaced: u64, threshold: u64) {
+        if replaced > threshold {
+            self.warnings.push(format!(
+                "High churn: {replaced} objects replaced in the last minute (threshold {threshold})"
+            ));
+            self.warning_count = self.warnings.len();
+        }
+    }
+
+    /// Warn if idle objects hold more than `cap` estimated bytes.
+    pub(crate) fn warn_on_retained_memory(&mut self, retained: usize, cap: usize) {
+        if retained > cap {
+            self.warnings.push(format!(
+                "Retained memory: idle objects hold {retained} bytes (cap {cap})"
+            ));
+            self.warning_count = self.warnings.len();
+        }
+    }
+
+    /// Warn that `in_use` degraded objects are out.
+    pub(crate) fn warn_on_degraded(&mut self, in_use: usize) {
+        if in_use > 0 {
+            self.warnings.push(format!("Serving degraded: {in_use} fallback objects in use"));
+            self.warning_count = self.warnings.len();
+        }
+    }
+
+    /// Report the pool as paused, which makes it unhealthy until resumed.
+    pub(crate) fn mark_paused(&mut self) {
+        self.paused = true;
+        self.is_healthy = false;
+        self.warnings.push("Pool is paused".to_string());
+        self.warning_count = self.warnings.len();
+    }
 }
 
 #[cfg(test)]
@@ -162,4 +214,66 @@ mod tests {
         let h = HealthStatus::new(0, 10, 10, true);
         assert_eq!(h.warning_count, h.warnings.len());
     }
+
+    #[test]
+    fn capacity_loss_warns_below_fraction() {
+        let mut h = HealthStatus::new(5, 0, 10, false);
+        h.warn_on_capacity_loss(5, 10, 0.5);
+        assert!(h.warnings.is_empty(), "exactly half left is not below half");
+
+        h.warn_on_capacity_loss(4, 10, 0.5);
+        assert_eq!(h.warnings, ["Capacity lost: 4 of 10 objects left"]);
+        assert_eq!(h.warning_count, 1);
+        assert!(h.is_healthy);
+
+        let mut off = HealthStatus::new(0, 0, 10, false);
+        off.warn_on_capacity_loss(0, 10, 0.0);
+        assert_eq!(off.warning_count, 1, "only the empty-pool warning");
+    }
+
+    #[test]
+    fn churn_warns_above_threshold() {
+        let mut h = HealthStatus::new(5, 0, 10, false);
+        h.warn_on_churn(10, 10);
+        assert!(h.warnings.is_empty(), "reaching the threshold is not above it");
+
+        h.warn_on_churn(11, 10);
+        assert_eq!(h.warnings, ["High churn: 11 objects replaced in the last minute (threshold 10)"]);
+        assert_eq!(h.warning_count, 1);
+        assert!(h.is_healthy);
+    }
+
+    #[test]
+    fn retained_memory_warns_above_cap() {
+        let mut h = HealthStatus::new(5, 0, 10, false);
+        h.warn_on_retained_memory(1024, 1024);
+        assert!(h.warnings.is_empty());
+
+        h.warn_on_retained_memory(4096, 1024);
+        assert_eq!(h.warnings, ["Retained memory: idle objects hold 4096 bytes (cap 1024)"]);
+        assert_eq!(h.warning_count, 1);
+        assert!(h.is_healthy);
+    }
+
+    #[test]
+    fn degraded_objects_in_use_warn() {
+        let mut h = HealthStatus::new(5, 0, 10, false);
+        h.warn_on_degraded(0);
+        assert!(h.warnings.is_empty());
+
+        h.warn_on_degraded(3);
+        assert_eq!(h.warnings, ["Serving degraded: 3 fallback objects in use"]);
+        assert_eq!(h.warning_count, 1);
+    }
+
+    #[test]
+    fn paused_pool_is_unhealthy() {
+        let mut h = HealthStatus::new(5, 0, 5, false);
+        assert!(!h.paused);
+        h.mark_paused();
+        assert!(h.paused);
+        assert!(!h.is_healthy());
+        assert_eq!(h.warnings, ["Pool is paused"]);
+        assert_eq!(h.warning_count, 1);
+    }
 }
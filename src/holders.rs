@@ -0,0 +1,191 @@
+//! Tracking of who currently holds a pool's objects
+
+use crate::concurrent::Map;
+use crate::ids::PoolObjectId;
+
+use std::backtrace::Backtrace;
+use std::panic::Location;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// A checked-out object and who has held it for how long
+///
+/// Returned by [`ObjectPool::current_holders`](crate::ObjectPool::current_holders)
+/// when holder tracking is enabled with
+/// [`with_holder_tracking`](crate::PoolConfiguration::with_holder_tracking).
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{AcquireContext, ObjectPool, PoolConfiguration};
+///
+/// let pool = ObjectPool::new(vec![1, 2], PoolConfiguration::new().with_holder_tracking());
+/// let _report = pool.get_object_with(AcquireContext::new().with_label("report-job")).unwrap();
+/// # std::thread::sleep(std::time::Duration::from_millis(1));
+/// let _other = pool.get_object().unwrap();
+///
+/// let holders = pool.current_holders();
+/// assert_eq!(holders.len(), 2);
+/// assert_eq!(holders[0].label.as_deref(), Some("report-job"), "longest hold first");
+/// assert_eq!(holders[1].label, None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Holder {
+    /// Id of the checked-out object
+    pub object_id: PoolObjectId,
+
+    /// How long the object has been checked out
+    pub held_for: Duration,
+
+    /// Label of the [`AcquireContext`](crate::AcquireContext) it was
+    /// acquired with, if any
+    pub label: Option<String>,
+
+    /// Source location of the acquisition, such as `src/jobs/import.rs:120:23`
+    /// (see [`PooledObject::acquired_at`](crate::PooledObject::acquired_at))
+    pub location: Option<&'static Location<'static>>,
+
+    /// Where the object was acquired, for sampled acquisitions (see
+    /// [`with_holder_backtraces`](crate::PoolConfiguration::with_holder_backtraces))
+    pub backtrace: Option<Arc<Backtrace>>,
+}
+
+struct HolderEntry {
+    object_id: usize,
+    since: Instant,
+    label: Option<String>,
+    location: Option<&'static Location<'static>>,
+    backtrace: Option<Arc<Backtrace>>,
+}
+
+/// Registry of the checked-out objects of one pool.
+pub(crate) struct Holders {
+    // Keyed by a per-checkout token rather than the object id: a returned
+    // object may be checked out again before the old guard is gone.
+    entries: Map<u64, HolderEntry>,
+    next_token: AtomicU64,
+    backtrace_every: Option<u64>,
+}
+
+/// Removes its holder entry when the guard is dropped.
+pub(crate) struct HolderTicket {
+    holders: Arc<Holders>,
+    token: u64,
+}
+
+impl Holders {
+    pub fn new(backtrace_every: Option<u32>) -> Self {
+        Self {
+            entries: Map::new(),
+            next_token: AtomicU64::new(0),
+            backtrace_every: backtrace_every.filter(|&n| n > 0).map(u64::from),
+        }
+    }
+
+    /// Record that `object_id` was just checked out by `label`.
+    pub fn register(self: &Arc<Self>, object_id: usize, label: Option<&str>) -> HolderTicket {
+        let token = self.next_token.fetch_add(1, Ordering::Relaxed);
+        let backtrace = self
+            .backtrace_every
+            .filter(|&every| token.is_multiple_of(every))
+            .map(|_| Arc::new(Backtrace::force_capture()));
+        self.entries.insert(
+            token,
+            HolderEntry {
+                object_id,
+                since: Instant::now(),
+                label: label.map(str::to_owned),
+                location: None,
+                backtrace,
+            },
+        );
+        HolderTicket {
+            holders: Arc::clone(self),
+            token,
+        }
+    }
+
+    /// Current holders, longest hold first.
+    pub fn snapshot(&self) -> Vec<Holder> {
+        let now = Instant::now();
+        let mut holders = self.entries.collect(|_, entry| Holder {
+            object_id: PoolObjectId::new(entry.object_id),
+            held_for: now.saturating_duration_since(entry.since),
+            label: entry.label.clone(),
+            location: entry.location,
+            backtrace: entry.backtrace.clone(),
+        });
+        holders.sort_by_key(|holder| std::cmp::Reverse(holder.held_for));
+        holders
+    }
+}
+
+impl HolderTicket {
+    /// Record where the checkout happened.
+    pub fn locate(&self, location: &'static Location<'static>) {
+        self.holders.entries.update(&self.token, |entry| entry.location = Some(location));
+    }
+}
+
+impl Drop for HolderTicket {
+    fn drop(&mut self) {
+        self.holders.entries.remove(&self.token);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tickets_register_and_remove_holders() {
+        let holders = Arc::new(Holders::new(None));
+        let first = holders.register(7, Some("job"));
+        std::thread::sleep(Duration::from_millis(2));
+        let second = holders.register(8, None);
+
+        let snapshot = holders.snapshot();
+        assert_eq!(snapshot.iter().map(|h| h.object_id.raw()).collect::<Vec<_>>(), vec![7, 8]);
+        assert_eq!(snapshot[0].label.as_deref(), Some("job"));
+        assert!(snapshot[0].backtrace.is_none());
+
+        drop(first);
+        assert_eq!(holders.snapshot().len(), 1);
+        drop(second);
+        assert!(holders.snapshot().is_empty());
+    }
+
+    #[test]
+    fn same_object_checked_out_again_keeps_its_new_entry() {
+        let holders = Arc::new(Holders::new(None));
+        let old = holders.register(1, None);
+        let new = holders.register(1, Some("next"));
+        drop(old);
+
+        let snapshot = holders.snapshot();
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot[0].label.as_deref(), Some("next"));
+        drop(new);
+    }
+
+    #[test]
+    fn tickets_record_their_location() {
+        let holders = Arc::new(Holders::new(None));
+        let ticket = holders.register(3, None);
+        assert_eq!(holders.snapshot()[0].location, None);
+
+        let here = Location::caller();
+        ticket.locate(here);
+        assert_eq!(holders.snapshot()[0].location, Some(here));
+    }
+
+    #[test]
+    fn backtraces_are_sampled() {
+        let holders = Arc::new(Holders::new(Some(2)));
+        let tickets: Vec<_> = (0..4).map(|id| holders.register(id, None)).collect();
+        let sampled = holders.snapshot().iter().filter(|h| h.backtrace.is_some()).count();
+        assert_eq!(sampled, 2);
+        drop(tickets);
+    }
+}
@@ -0,0 +1,578 @@
+//! User-supplied hooks: where they run, and without letting their panics
+//! escape
+
+use crate::config::PoolConfiguration;
+use crate::churn::{Churn, ChurnMonitor};
+use crate::context::AcquireContext;
+use crate::events::{PoolWarning, Reporter};
+use crate::metrics::Counter;
+
+use crossbeam::channel::{self, Sender};
+
+use std::any::Any;
+use std::cell::Cell;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Arc;
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Durations measured by the pool, handed to the timed hooks
+///
+/// Each hook gets the durations of its own phase and `None` for the rest:
+///
+/// - [`on_acquire_timed`](crate::PoolConfiguration::with_on_acquire_timed):
+///   `wait`, from the start of the acquisition to the object being handed
+///   out (near zero when an object was idle)
+/// - [`on_return_timed`](crate::PoolConfiguration::with_on_return_timed):
+///   `hold`, from the object being handed out to its return
+/// - [`on_create`](crate::PoolConfiguration::with_on_create): `creation`,
+///   the duration of the factory call
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{DynamicObjectPool, HookTiming, PoolConfiguration};
+/// use std::sync::{Arc, Mutex};
+/// use std::time::Duration;
+///
+/// let holds = Arc::new(Mutex::new(Vec::new()));
+/// let recorded = Arc::clone(&holds);
+/// let pool = DynamicObjectPool::new(
+///     || 0,
+///     PoolConfiguration::new().with_on_return_timed(move |_: &mut i32, timing: &HookTiming| {
+///         recorded.lock().unwrap().push(timing.hold.unwrap());
+///     }),
+/// );
+///
+/// let obj = pool.get_object().unwrap();
+/// std::thread::sleep(Duration::from_millis(5));
+/// drop(obj);
+/// assert!(holds.lock().unwrap()[0] >= Duration::from_millis(5));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct HookTiming {
+    /// How long the acquisition took, waiting included
+    pub wait: Option<Duration>,
+
+    /// How long the object was checked out
+    pub hold: Option<Duration>,
+
+    /// How long the factory took to create the object
+    pub creation: Option<Duration>,
+}
+
+/// Hooks that run inline, on the thread acquiring or returning the object
+///
+/// These run while the caller waits — `on_destroy` typically inside the
+/// [`Drop`] of a [`PooledObject`](crate::PooledObject) — so they must be fast
+/// and must not block. Anything slow, such as closing a connection over the
+/// network, belongs in [`AsyncHooks`].
+///
+/// Every method defaults to doing nothing. Register with
+/// [`PoolConfiguration::with_sync_hooks`](crate::PoolConfiguration::with_sync_hooks).
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{ObjectPool, PoolConfiguration, SyncHooks};
+///
+/// struct ResetOnBorrow;
+///
+/// impl SyncHooks<Vec<u8>> for ResetOnBorrow {
+///     fn on_borrow(&self, buf: &mut Vec<u8>) {
+///         buf.clear();
+///     }
+/// }
+///
+/// let pool = ObjectPool::new(
+///     vec![vec![1, 2, 3]],
+///     PoolConfiguration::new().with_sync_hooks(ResetOnBorrow),
+/// );
+/// assert!(pool.get_object().unwrap().is_empty());
+/// ```
+pub trait SyncHooks<T>: Send + Sync {
+    /// Called on every object just before it is handed out
+    fn on_borrow(&self, _obj: &mut T) {}
+
+    /// Called after `on_borrow`, with the caller's
+    /// [`AcquireContext`] (empty if none was given)
+    fn on_acquire(&self, _obj: &mut T, _ctx: &AcquireContext) {}
+
+    /// Called after `on_acquire`, with how long the acquisition took
+    fn on_acquire_timed(&self, _obj: &mut T, _timing: &HookTiming) {}
+
+    /// Called on every object given back to the pool, before validation
+    fn on_return(&self, _obj: &mut T) {}
+
+    /// Called after `on_return`, with how long the object was checked out
+    fn on_return_timed(&self, _obj: &mut T, _timing: &HookTiming) {}
+
+    /// Called on every object a dynamic pool's factory creates, with how
+    /// long the factory took
+    fn on_create(&self, _obj: &mut T, _timing: &HookTiming) {}
+
+    /// Called with every object that leaves the pool for good, unless
+    /// [`AsyncHooks`] are registered
+    fn on_destroy(&self, _obj: T) {}
+}
+
+/// Hooks that run on a background worker thread, off the caller's path
+///
+/// The pool hands the object to a dedicated worker over a channel and
+/// carries on, so these hooks may block or do slow I/O without delaying a
+/// return or an acquisition. Only hooks that consume the object can run
+/// here; hooks that must finish before an object is handed out are
+/// [`SyncHooks`].
+///
+/// Registering `AsyncHooks` moves destruction to the worker: the inline
+/// destroy hook is no longer called. Panics on the worker are always
+/// contained and reported as
+/// [`PoolWarning::HookPanicked`], whatever the [`HookPanicPolicy`], since
+/// there is no caller to propagate them to. Objects still queued when the
+/// pool is dropped are destroyed before the worker exits.
+///
+/// Register with
+/// [`PoolConfiguration::with_async_hooks`](crate::PoolConfiguration::with_async_hooks).
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{AsyncHooks, DynamicObjectPool, PoolConfiguration};
+/// use std::sync::mpsc;
+/// use std::sync::Mutex;
+///
+/// struct Disconnect(Mutex<mpsc::Sender<u32>>);
+///
+/// impl AsyncHooks<u32> for Disconnect {
+///     fn on_destroy(&self, conn: u32) {
+///         // A slow, blocking close is fine here.
+///         self.0.lock().unwrap().send(conn).unwrap();
+///     }
+/// }
+///
+/// let (tx, closed) = mpsc::channel();
+/// let pool = DynamicObjectPool::new(
+///     || 7u32,
+///     PoolConfiguration::new()
+///         .with_validation(|_: &u32| false)
+///         .with_async_hooks(Disconnect(Mutex::new(tx))),
+/// );
+///
+/// drop(pool.get_object().unwrap()); // fails validation and is destroyed
+/// assert_eq!(closed.recv().unwrap(), 7);
+/// ```
+pub trait AsyncHooks<T>: Send + Sync {
+    /// Called on the worker with every object that leaves the pool for good
+    fn on_destroy(&self, _obj: T) {}
+}
+
+/// What the pool does when a validation function or hook panics
+///
+/// Validation often runs while a [`PooledObject`](crate::PooledObject) is
+/// dropped, and a panic escaping `Drop` during another unwind aborts the
+/// process. By default the pool therefore contains such panics: the object
+/// is treated as having failed validation and is discarded, the panic is
+/// counted in [`PoolMetrics::hook_panics`](crate::PoolMetrics::hook_panics)
+/// and published as a [`PoolWarning::HookPanicked`].
+///
+/// Covers the validation function and the `on_borrow`, `on_acquire`,
+/// `on_return`, `on_create`, `on_destroy` and context hooks, timed ones
+/// included. A panic in an acquire hook fails the acquisition with
+/// `PoolError::ValidationFailed`; a panic in a return hook discards the
+/// object as if it had failed validation; a panic in `on_create` fails the
+/// creation; a panicking context hook leaves the guard without a context.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{ObjectPool, PoolConfiguration, PoolWarning};
+///
+/// fn flaky(x: &i32) -> bool {
+///     assert!(*x >= 0, "negative object");
+///     true
+/// }
+///
+/// let pool = ObjectPool::new(
+///     vec![1],
+///     PoolConfiguration::new().with_validation(flaky),
+/// );
+/// let mut warnings = pool.warnings();
+/// # std::panic::set_hook(Box::new(|_| {}));
+///
+/// let mut obj = pool.get_object().unwrap();
+/// *obj = -1;
+/// drop(obj); // the panic is contained and the object discarded
+///
+/// assert_eq!(pool.available_count(), 0);
+/// assert_eq!(pool.get_metrics().hook_panics, 1);
+/// assert!(matches!(warnings.try_recv(), Ok(PoolWarning::HookPanicked { hook: "validation", .. })));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum HookPanicPolicy {
+    /// Catch the panic and treat it as a failed validation
+    #[default]
+    Contain,
+
+    /// Let the panic unwind into the pool and the caller
+    Propagate,
+}
+
+thread_local! {
+    /// Start of the acquisition whose attempt is running on this thread.
+    static ACQUIRE_STARTED: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+/// Run one attempt of an acquisition that started at `started`, letting the
+/// timed acquire hook measure the wait. Nested acquisitions keep the
+/// outermost start.
+pub(crate) fn attempt_started_at<R>(started: Instant, attempt: impl FnOnce() -> R) -> R {
+    struct Reset;
+
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            ACQUIRE_STARTED.set(None);
+        }
+    }
+
+    if ACQUIRE_STARTED.get().is_some() {
+        return attempt();
+    }
+    ACQUIRE_STARTED.set(Some(started));
+    let _reset = Reset;
+    attempt()
+}
+
+/// How long the acquisition running on this thread has waited, if it is
+/// being timed.
+pub(crate) fn acquisition_wait() -> Option<Duration> {
+    ACQUIRE_STARTED.get().map(|started| started.elapsed())
+}
+
+/// The pool's user-supplied closures, each run under the configured
+/// [`HookPanicPolicy`].
+pub(crate) struct Hooks<T> {
+    config: Arc<PoolConfiguration<T>>,
+    panics: Arc<Counter>,
+    warnings: Arc<Reporter<PoolWarning>>,
+    /// Feeds the [`AsyncHooks`] worker; dropping it lets the worker exit.
+    background: Option<Sender<T>>,
+    /// Counts creations and destructions, when a churn warning is set.
+    churn: Option<ChurnMonitor>,
+}
+
+impl<T: Send + 'static> Hooks<T> {
+    pub fn new(
+        config: Arc<PoolConfiguration<T>>,
+        panics: Arc<Counter>,
+        warnings: Arc<Reporter<PoolWarning>>,
+    ) -> Self {
+        let background = config.async_hooks.clone().map(|hooks| {
+            let (tx, rx) = channel::unbounded::<T>();
+            let panics = Arc::clone(&panics);
+            let warnings = Arc::clone(&warnings);
+            thread::Builder::new()
+                .name("objectpool-hooks".into())
+                .spawn(move || {
+                    for obj in rx {
+                        contain(&panics, &warnings, "on_destroy", || hooks.on_destroy(obj));
+                    }
+                })
+                .expect("failed to spawn the background hook worker");
+            tx
+        });
+        // The `minimal` feature counts no churn on the create/destroy path.
+        let churn = config
+            .churn_warning
+            .filter(|_| !cfg!(feature = "minimal"))
+            .map(ChurnMonitor::new);
+        Self { config, panics, warnings, background, churn }
+    }
+}
+
+impl<T> Hooks<T> {
+    /// Run `f`, returning `None` if it panicked and the panic was contained.
+    fn run<R>(&self, hook: &'static str, f: impl FnOnce() -> R) -> Option<R> {
+        if self.config.hook_panic_policy == HookPanicPolicy::Propagate {
+            return Some(f());
+        }
+        contain(&self.panics, &self.warnings, hook, f)
+    }
+
+    /// Whether a validation function is configured.
+    pub fn validates(&self) -> bool {
+        self.config.validation_function.is_some()
+    }
+
+    /// Whether `obj` passes the validation function; a contained panic
+    /// counts as a failure.
+    pub fn is_valid(&self, obj: &T) -> bool {
+        match self.config.validation_function {
+            Some(validate) => self.run("validation", || validate(obj)).unwrap_or(false),
+            None => true,
+        }
+    }
+
+    /// Run `on_borrow` and `on_acquire`; `false` if one of them panicked.
+    pub fn prepare(&self, obj: &mut T, ctx: Option<&AcquireContext>) -> bool {
+        if let Some(ref on_borrow) = self.config.on_borrow
+            && self.run("on_borrow", || on_borrow(obj)).is_none()
+        {
+            return false;
+        }
+        if let Some(ref on_acquire) = self.config.on_acquire {
+            let default = AcquireContext::default();
+            let ctx = ctx.unwrap_or(&default);
+            if self.run("on_acquire", || on_acquire(obj, ctx)).is_none() {
+                return false;
+            }
+        }
+        if let Some(ref on_acquire_timed) = self.config.on_acquire_timed {
+            let timing = HookTiming {
+                wait: Some(ACQUIRE_STARTED.get().map_or(Duration::ZERO, |started| started.elapsed())),
+                ..HookTiming::default()
+            };
+            return self.run("on_acquire_timed", || on_acquire_timed(obj, &timing)).is_some();
+        }
+        true
+    }
+
+    /// Whether acquisitions should be timed for the timed acquire hook.
+    pub fn times_acquisitions(&self) -> bool {
+        self.config.on_acquire_timed.is_some()
+    }
+
+    /// Whether guards should note when they were handed out, for the timed
+    /// return hook.
+    pub fn times_holds(&self) -> bool {
+        self.config.on_return_timed.is_some()
+    }
+
+    /// Run `on_return` and `on_return_timed`, the latter with the time since
+    /// `checked_out`; `false` if one of them panicked.
+    pub fn recycle(&self, obj: &mut T, checked_out: Option<Instant>) -> bool {
+        if let Some(ref on_return) = self.config.on_return
+            && self.run("on_return", || on_return(obj)).is_none()
+        {
+            return false;
+        }
+        if let Some(ref on_return_timed) = self.config.on_return_timed {
+            let timing = HookTiming {
+                hold: checked_out.map(|at| at.elapsed()),
+                ..HookTiming::default()
+            };
+            return self.run("on_return_timed", || on_return_timed(obj, &timing)).is_some();
+        }
+        true
+    }
+
+    /// Call `factory`, timing it for `on_create`. An object whose hook
+    /// panicked is destroyed and reported as a failed creation.
+    pub fn create(&self, factory: impl FnOnce() -> Result<T, String>) -> Result<T, String> {
+        let started = Instant::now();
+        let mut obj = factory()?;
+        let creation = started.elapsed();
+        self.count(ChurnMonitor::record_creation);
+        let Some(ref on_create) = self.config.on_create else {
+            return Ok(obj);
+        };
+        let timing = HookTiming {
+            creation: Some(creation),
+            ..HookTiming::default()
+        };
+        if self.run("on_create", || on_create(&mut obj, &timing)).is_none() {
+            self.destroy(obj);
+            return Err("on_create hook panicked".to_owned());
+        }
+        Ok(obj)
+    }
+
+    /// Objects created and destroyed in the last minute and the churn
+    /// threshold, when a churn warning is set.
+    pub fn churn(&self) -> Option<(Churn, u64)> {
+        self.churn.as_ref().map(|monitor| (monitor.churn(), monitor.threshold()))
+    }
+
+    fn count(&self, record: impl FnOnce(&ChurnMonitor) -> Option<PoolWarning>) {
+        if let Some(warning) = self.churn.as_ref().and_then(record) {
+            self.warnings.report(warning);
+        }
+    }
+
+    /// Correlation id from the context hook, if any.
+    pub fn context(&self) -> Option<String> {
+        let hook = self.config.context_hook.as_ref()?;
+        self.run("context_hook", || hook()).flatten()
+    }
+
+    /// Hand `obj` to the background worker or the destroy hook, or drop it.
+    pub fn destroy(&self, obj: T) {
+        self.count(ChurnMonitor::record_destruction);
+        if let Some(ref background) = self.background {
+            // The worker outlives its sender, so the send cannot fail.
+            let _ = background.send(obj);
+        } else if let Some(ref on_destroy) = self.config.on_destroy {
+            self.run("on_destroy", || on_destroy(obj));
+        }
+    }
+}
+
+/// Run `f`, catching a panic and counting and reporting it as `hook`.
+fn contain<R>(
+    panics: &Counter,
+    warnings: &Reporter<PoolWarning>,
+    hook: &'static str,
+    f: impl FnOnce() -> R,
+) -> Option<R> {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(result) => Some(result),
+        Err(payload) => {
+            panics.fetch_add(1, Ordering::Relaxed);
+            warnings.report(PoolWarning::HookPanicked {
+                hook,
+                message: panic_message(payload.as_ref()),
+            });
+            None
+        }
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        (*message).to_owned()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "non-string panic payload".to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hooks(config: PoolConfiguration<i32>) -> (Hooks<i32>, Arc<Counter>) {
+        let panics = Arc::new(Counter::new(0));
+        let hooks = Hooks::new(Arc::new(config), Arc::clone(&panics), Arc::new(Reporter::new()));
+        (hooks, panics)
+    }
+
+    fn panicking_validator(_: &i32) -> bool {
+        panic!("validator exploded")
+    }
+
+    #[test]
+    fn contained_validation_panic_is_a_failure() {
+        let (hooks, panics) = hooks(PoolConfiguration::new().with_validation(panicking_validator));
+        let mut warnings = hooks.warnings.subscribe();
+
+        assert!(!hooks.is_valid(&1));
+        assert_eq!(panics.load(Ordering::Relaxed), 1);
+        assert_eq!(
+            warnings.try_recv().unwrap(),
+            PoolWarning::HookPanicked { hook: "validation", message: "validator exploded".into() }
+        );
+    }
+
+    #[test]
+    fn propagate_policy_lets_panics_through() {
+        let (hooks, _) = hooks(
+            PoolConfiguration::new()
+                .with_validation(panicking_validator)
+                .with_hook_panic_policy(HookPanicPolicy::Propagate),
+        );
+        assert!(catch_unwind(AssertUnwindSafe(|| hooks.is_valid(&1))).is_err());
+    }
+
+    #[test]
+    fn panicking_borrow_hook_fails_preparation() {
+        let (hooks, panics) = hooks(PoolConfiguration::new().with_on_borrow(|_: &mut i32| panic!("no")));
+        assert!(!hooks.prepare(&mut 1, None));
+        assert_eq!(panics.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn timed_hooks_see_their_own_phase() {
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let (acquired, returned, created) = (Arc::clone(&seen), Arc::clone(&seen), Arc::clone(&seen));
+        let (hooks, _) = hooks(
+            PoolConfiguration::new()
+                .with_on_acquire_timed(move |_: &mut i32, timing: &HookTiming| acquired.lock().unwrap().push(*timing))
+                .with_on_return_timed(move |_: &mut i32, timing: &HookTiming| returned.lock().unwrap().push(*timing))
+                .with_on_create(move |obj: &mut i32, timing: &HookTiming| {
+                    *obj += 1;
+                    created.lock().unwrap().push(*timing);
+                }),
+        );
+
+        let started = Instant::now() - Duration::from_secs(1);
+        assert!(attempt_started_at(started, || hooks.prepare(&mut 1, None)));
+        assert!(hooks.prepare(&mut 1, None));
+        assert!(hooks.recycle(&mut 1, Some(started)));
+        assert_eq!(hooks.create(|| Ok(1)), Ok(2));
+
+        let seen = seen.lock().unwrap();
+        assert!(seen[0].wait.unwrap() >= Duration::from_secs(1));
+        assert!(seen[1].wait.unwrap() < Duration::from_secs(1), "no wait outside an attempt");
+        assert_eq!((seen[2].wait, seen[2].creation), (None, None));
+        assert!(seen[2].hold.unwrap() >= Duration::from_secs(1));
+        assert!(seen[3].creation.is_some() && seen[3].hold.is_none());
+    }
+
+    #[test]
+    fn panicking_create_hook_fails_the_creation() {
+        let (hooks, panics) = hooks(PoolConfiguration::new().with_on_create(|_: &mut i32, _: &HookTiming| panic!("no")));
+        assert!(hooks.create(|| Ok(1)).is_err());
+        assert_eq!(hooks.create(|| Err("down".into())), Err("down".to_owned()));
+        assert_eq!(panics.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn panicking_context_hook_yields_no_context() {
+        let (hooks, _) = hooks(PoolConfiguration::new().with_context_hook(|| panic!("no context")));
+        assert_eq!(hooks.context(), None);
+    }
+
+    struct Forward(std::sync::Mutex<std::sync::mpsc::Sender<(i32, Option<String>)>>);
+
+    impl AsyncHooks<i32> for Forward {
+        fn on_destroy(&self, obj: i32) {
+            assert!(obj >= 0, "negative");
+            let worker = thread::current().name().map(str::to_owned);
+            self.0.lock().unwrap().send((obj, worker)).unwrap();
+        }
+    }
+
+    #[test]
+    fn async_destroy_runs_on_the_worker_instead_of_inline() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let (hooks, panics) = hooks(
+            PoolConfiguration::new()
+                .with_on_destroy(|_: i32| panic!("inline hook must not run"))
+                .with_async_hooks(Forward(std::sync::Mutex::new(tx))),
+        );
+
+        hooks.destroy(-1);
+        hooks.destroy(5);
+        assert_eq!(rx.recv().unwrap(), (5, Some("objectpool-hooks".to_owned())));
+        assert_eq!(panics.load(Ordering::Relaxed), 1, "worker panic contained");
+    }
+
+    #[test]
+    fn queued_objects_are_destroyed_after_the_pool_is_gone() {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let (hooks, _) = hooks(PoolConfiguration::new().with_async_hooks(Forward(std::sync::Mutex::new(tx))));
+        for obj in 0..3 {
+            hooks.destroy(obj);
+        }
+        drop(hooks);
+        assert_eq!(rx.iter().map(|(obj, _)| obj).collect::<Vec<_>>(), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn panic_message_handles_formatted_payloads() {
+        let payload = catch_unwind(|| panic!("object {}", 7)).unwrap_err();
+        assert_eq!(panic_message(payload.as_ref()), "object 7");
+    }
+}
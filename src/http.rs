@@ -0,0 +1,392 @@
+//! Axum integration: an object extractor, health and metrics endpoints and
+//! graceful shutdown
+//!
+//! Enabled with the `axum` feature. Put a [`PoolService`] in the router
+//! state (directly or through [`FromRef`]) and handlers can take a
+//! [`Pooled`] argument; [`PoolEndpoints`] serves `/health` and `/metrics`
+//! for any number of pools, and [`serve`] runs the application until a
+//! shutdown signal, then shuts the pools down.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use axum::routing::get;
+//! use axum::Router;
+//! use esox_objectpool::http::{self, PoolEndpoints, Pooled};
+//! use esox_objectpool::{DynamicObjectPool, PoolConfiguration, PoolService};
+//! use std::sync::Arc;
+//!
+//! async fn greet(Pooled(mut buf): Pooled<String>) -> String {
+//!     buf.push_str("hello");
+//!     buf.clone()
+//! }
+//!
+//! # #[tokio::main]
+//! # async fn main() -> std::io::Result<()> {
+//! let buffers = Arc::new(DynamicObjectPool::new(String::new, PoolConfiguration::default()));
+//! let app = Router::new()
+//!     .route("/", get(greet))
+//!     .with_state(PoolService::from(Arc::clone(&buffers)));
+//! let endpoints = PoolEndpoints::new().with_pool("buffers", buffers);
+//!
+//! let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await?;
+//! http::serve(listener, app, endpoints, http::shutdown_signal()).await
+//! # }
+//! ```
+
+use crate::errors::PoolError;
+use crate::metrics::merge_families;
+use crate::observable::ObservablePool;
+use crate::permit::PermitPool;
+use crate::pool::{DynamicObjectPool, DynamicQueryablePool, ObjectPool, PooledObject, QueryableObjectPool};
+use crate::service::PoolService;
+
+use axum::extract::{FromRef, FromRequestParts};
+use axum::http::request::Parts;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use std::future::Future;
+use std::sync::Arc;
+use tokio::net::TcpListener;
+
+/// Extractor handing a handler an object from the [`PoolService`] in the
+/// router state
+///
+/// The object goes back to the pool when the handler drops it. Acquisition
+/// waits as configured for the pool; a failure rejects the request with a
+/// [`PoolRejection`].
+pub struct Pooled<T>(pub PooledObject<T>);
+
+impl<S, T> FromRequestParts<S> for Pooled<T>
+where
+    S: Send + Sync,
+    T: Send + Sync + 'static,
+    PoolService<T>: FromRef<S>,
+{
+    type Rejection = PoolRejection;
+
+    async fn from_request_parts(_parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        PoolService::from_ref(state)
+            .acquire()
+            .await
+            .map(Pooled)
+            .map_err(PoolRejection)
+    }
+}
+
+/// Why [`Pooled`] could not hand out an object
+///
+/// Responds with `503 Service Unavailable` when the pool is saturated,
+/// throttled, behind an open circuit breaker or shut down, and with
+/// `500 Internal Server Error` otherwise, such as when the factory fails.
+#[derive(Debug)]
+pub struct PoolRejection(pub PoolError);
+
+impl PoolRejection {
+    /// The status code this rejection responds with
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use axum::http::StatusCode;
+    /// use esox_objectpool::http::PoolRejection;
+    /// use esox_objectpool::PoolError;
+    ///
+    /// assert_eq!(PoolRejection(PoolError::PoolEmpty).status(), StatusCode::SERVICE_UNAVAILABLE);
+    /// assert_eq!(
+    ///     PoolRejection(PoolError::CreationFailed("refused".into())).status(),
+    ///     StatusCode::INTERNAL_SERVER_ERROR,
+    /// );
+    /// ```
+    #[must_use]
+    pub fn status(&self) -> StatusCode {
+        match self.0 {
+            PoolError::PoolEmpty
+            | PoolError::PoolFull
+            | PoolError::Timeout(..)
+            | PoolError::CircuitBreakerOpen
+            | PoolError::MaxActiveObjectsReached
+            | PoolError::BulkheadFull(_)
+            | PoolError::GroupLimitReached(_)
+            | PoolError::CreationThrottled
+            | PoolError::CreationPending
+            | PoolError::PoolClosed
+            | PoolError::QueueFull
+            | PoolError::Paused => StatusCode::SERVICE_UNAVAILABLE,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+impl IntoResponse for PoolRejection {
+    fn into_response(self) -> Response {
+        (self.status(), self.0.to_string()).into_response()
+    }
+}
+
+/// A pool that [`PoolEndpoints`] can report on and shut down
+pub trait ServedPool: ObservablePool + Send + Sync + 'static {
+    /// Stop handing out objects; see [`ObjectPool::shutdown`]
+    fn shutdown(&self) -> usize;
+}
+
+impl<T: Send + Sync + 'static> ServedPool for ObjectPool<T> {
+    fn shutdown(&self) -> usize {
+        ObjectPool::shutdown(self)
+    }
+}
+
+impl<T: Send + Sync + 'static> ServedPool for QueryableObjectPool<T> {
+    fn shutdown(&self) -> usize {
+        ObjectPool::shutdown(self)
+    }
+}
+
+impl<T: Send + Sync + 'static> ServedPool for DynamicObjectPool<T> {
+    fn shutdown(&self) -> usize {
+        DynamicObjectPool::shutdown(self)
+    }
+}
+
+impl<K: Send + Sync + 'static, T: Send + Sync + 'static> ServedPool for DynamicQueryablePool<K, T> {
+    fn shutdown(&self) -> usize {
+        DynamicQueryablePool::shutdown(self)
+    }
+}
+
+impl ServedPool for PermitPool {
+    fn shutdown(&self) -> usize {
+        ObjectPool::shutdown(self)
+    }
+}
+
+/// Health and Prometheus endpoints for a set of named pools
+///
+/// [`router`](Self::router) serves:
+///
+/// - `GET /health`: one line per pool, with status `200 OK` when every pool
+///   is healthy and `503 Service Unavailable` otherwise
+/// - `GET /metrics`: every pool's metrics in Prometheus text format,
+///   labelled with the pool's name
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::http::PoolEndpoints;
+/// use esox_objectpool::{ObjectPool, PoolConfiguration};
+/// use std::sync::Arc;
+///
+/// let pool = Arc::new(ObjectPool::new(vec![1, 2], PoolConfiguration::default()));
+/// let endpoints = PoolEndpoints::new().with_pool("numbers", pool);
+///
+/// assert!(endpoints.health_report().0);
+/// assert!(endpoints.prometheus().contains(r#"pool="numbers""#));
+/// ```
+#[derive(Clone, Default)]
+pub struct PoolEndpoints {
+    pools: Vec<(String, Arc<dyn ServedPool>)>,
+}
+
+impl PoolEndpoints {
+    /// Endpoints for no pools yet
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Report on `pool` under `name`
+    pub fn with_pool<P: ServedPool>(mut self, name: impl Into<String>, pool: Arc<P>) -> Self {
+        self.pools.push((name.into(), pool));
+        self
+    }
+
+    /// Whether every pool is healthy, and one line per pool describing it
+    #[must_use]
+    pub fn health_report(&self) -> (bool, String) {
+        let mut healthy = true;
+        let mut report = String::new();
+        for (name, pool) in &self.pools {
+            let health = pool.get_health_status();
+            healthy &= health.is_healthy;
+            let state = if health.is_healthy { "healthy" } else { "unhealthy" };
+            report.push_str(&format!(
+                "{name}: {state}, {}/{} in use",
+                health.active_objects, health.total_capacity
+            ));
+            if !health.warnings.is_empty() {
+                report.push_str(&format!(" ({})", health.warnings.join("; ")));
+            }
+            report.push('\n');
+        }
+        (healthy, report)
+    }
+
+    /// Every pool's metrics in Prometheus text format
+    ///
+    /// Each family lists its samples in the order the pools were added, so
+    /// the output is as deterministic as
+    /// [`MetricsExporter::export_prometheus`](crate::MetricsExporter::export_prometheus).
+    #[must_use]
+    pub fn prometheus(&self) -> String {
+        let outputs: Vec<String> = self
+            .pools
+            .iter()
+            .map(|(name, pool)| pool.export_metrics_prometheus(name, None))
+            .collect();
+        merge_families(&outputs.iter().map(String::as_str).collect::<Vec<_>>())
+    }
+
+    /// Shut every pool down; returns the number of idle objects destroyed
+    pub fn shutdown(&self) -> usize {
+        self.pools.iter().map(|(_, pool)| pool.shutdown()).sum()
+    }
+
+    /// A router serving `/health` and `/metrics`, to be merged into the
+    /// application's router
+    pub fn router<S: Clone + Send + Sync + 'static>(&self) -> Router<S> {
+        let health = self.clone();
+        let metrics = self.clone();
+        Router::new()
+            .route(
+                "/health",
+                get(move || async move {
+                    let (healthy, report) = health.health_report();
+                    let status = if healthy { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+                    (status, report)
+                }),
+            )
+            .route(
+                "/metrics",
+                get(move || async move {
+                    (
+                        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+                        metrics.prometheus(),
+                    )
+                }),
+            )
+    }
+}
+
+/// Serve `app` together with the routes of `endpoints` until `signal`
+/// completes, then shut the pools down
+///
+/// In-flight requests finish before the pools are shut down, so objects
+/// they hold are returned normally rather than stranded.
+pub async fn serve(
+    listener: TcpListener,
+    app: Router,
+    endpoints: PoolEndpoints,
+    signal: impl Future<Output = ()> + Send + 'static,
+) -> std::io::Result<()> {
+    let app = app.merge(endpoints.router());
+    axum::serve(listener, app).with_graceful_shutdown(signal).await?;
+    endpoints.shutdown();
+    Ok(())
+}
+
+/// Completes on Ctrl-C, or on SIGTERM on Unix
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        // Without a handler there is nothing to wait for.
+        if tokio::signal::ctrl_c().await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        () = ctrl_c => {}
+        () = terminate => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PoolConfiguration, WaitPolicy};
+    use axum::body::{to_bytes, Body};
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    async fn get_text(app: Router, uri: &str) -> (StatusCode, String) {
+        let response = app
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    #[tokio::test]
+    async fn extractor_hands_out_and_returns_objects() {
+        let pool = Arc::new(ObjectPool::new(
+            vec![String::from("conn-1")],
+            PoolConfiguration::new().with_wait_on_empty(WaitPolicy::FailFast),
+        ));
+        let app = Router::new()
+            .route("/", get(|Pooled(conn): Pooled<String>| async move { conn.clone() }))
+            .with_state(PoolService::from(Arc::clone(&pool)));
+
+        assert_eq!(get_text(app.clone(), "/").await, (StatusCode::OK, "conn-1".into()));
+        assert_eq!(pool.available_count(), 1);
+
+        let _held = pool.get_object().unwrap();
+        let (status, _) = get_text(app, "/").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[tokio::test]
+    async fn health_reflects_every_pool() {
+        let sized = |size| PoolConfiguration::new().with_max_pool_size(size);
+        let idle = Arc::new(ObjectPool::new(vec![1, 2], sized(2)));
+        let busy = Arc::new(ObjectPool::new(vec![1], sized(1)));
+        let endpoints = PoolEndpoints::new()
+            .with_pool("idle", Arc::clone(&idle))
+            .with_pool("busy", Arc::clone(&busy));
+
+        let (status, body) = get_text(endpoints.router(), "/health").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.contains("idle: healthy, 0/2 in use"));
+
+        let _held = busy.get_object().unwrap();
+        let (status, body) = get_text(endpoints.router(), "/health").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(body.contains("busy: unhealthy, 1/1 in use"));
+    }
+
+    #[tokio::test]
+    async fn metrics_merge_pools_into_one_exposition() {
+        let endpoints = PoolEndpoints::new()
+            .with_pool("a", Arc::new(ObjectPool::new(vec![1], PoolConfiguration::default())))
+            .with_pool("b", Arc::new(DynamicObjectPool::new(|| 0, PoolConfiguration::default())));
+
+        let (status, body) = get_text(endpoints.router(), "/metrics").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body.matches("# TYPE objectpool_objects_retrieved_total").count(), 1);
+        assert!(body.contains(r#"objectpool_objects_retrieved_total{pool="a"}"#));
+        assert!(body.contains(r#"objectpool_objects_retrieved_total{pool="b"}"#));
+    }
+
+    #[tokio::test]
+    async fn serve_shuts_pools_down_after_the_signal() {
+        let pool = Arc::new(ObjectPool::new(vec![1], PoolConfiguration::default()));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoints = PoolEndpoints::new().with_pool("p", Arc::clone(&pool));
+
+        serve(listener, Router::new(), endpoints, async {}).await.unwrap();
+        assert!(pool.is_closed());
+    }
+}
@@ -0,0 +1,143 @@
+//! Storage for idle (available) objects
+
+use crate::config::PartitionFn;
+
+use crate::concurrent::Queue;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+type Entry<T> = (T, usize);
+
+/// Lock-free queue of idle objects, optionally split into partitions.
+///
+/// Without a partition function this is a single queue. With one,
+/// every object is routed to the bucket `partition(obj) % buckets`, so a
+/// search that knows the partition only has to look at that bucket. Each
+/// bucket is sized for the whole pool (objects may all land in one bucket);
+/// `len` tracks the total so capacity is still enforced pool-wide.
+pub(crate) struct IdleQueue<T> {
+    buckets: Box<[Queue<Entry<T>>]>,
+    partition: Option<PartitionFn<T>>,
+    len: AtomicUsize,
+    capacity: usize,
+    /// Bucket an unhinted `pop` starts from, rotated to spread the load.
+    cursor: AtomicUsize,
+}
+
+impl<T> IdleQueue<T> {
+    pub fn new(capacity: usize, buckets: usize, partition: Option<PartitionFn<T>>) -> Self {
+        let buckets = if partition.is_some() { buckets.max(1) } else { 1 };
+        Self {
+            buckets: (0..buckets).map(|_| Queue::new(capacity)).collect(),
+            partition,
+            len: AtomicUsize::new(0),
+            capacity,
+            cursor: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn push(&self, item: Entry<T>) -> Result<(), Entry<T>> {
+        let reserved = self
+            .len
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |len| {
+                (len < self.capacity).then_some(len + 1)
+            });
+        if reserved.is_err() {
+            return Err(item);
+        }
+
+        let bucket = match self.partition {
+            Some(ref partition) => self.bucket_of(partition(&item.0)),
+            None => 0,
+        };
+        // Every bucket can hold the whole pool and the total was reserved
+        // above, so the bucket cannot be full.
+        self.buckets[bucket].push(item).inspect_err(|_| {
+            self.len.fetch_sub(1, Ordering::AcqRel);
+        })
+    }
+
+    pub fn pop(&self) -> Option<Entry<T>> {
+        let n = self.buckets.len();
+        let start = if n == 1 { 0 } else { self.cursor.fetch_add(1, Ordering::Relaxed) };
+        (0..n).find_map(|i| self.pop_bucket((start + i) % n))
+    }
+
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Acquire)
+    }
+
+    pub fn bucket_count(&self) -> usize {
+        self.buckets.len()
+    }
+
+    /// Bucket holding objects of partition `hint`.
+    pub fn bucket_of(&self, hint: u64) -> usize {
+        (hint % self.buckets.len() as u64) as usize
+    }
+
+    pub fn bucket_len(&self, bucket: usize) -> usize {
+        self.buckets[bucket].len()
+    }
+
+    pub fn pop_bucket(&self, bucket: usize) -> Option<Entry<T>> {
+        let item = self.buckets[bucket].pop()?;
+        self.len.fetch_sub(1, Ordering::AcqRel);
+        Some(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn by_parity() -> Option<PartitionFn<u64>> {
+        Some(Arc::new(|x: &u64| *x % 2))
+    }
+
+    #[test]
+    fn unpartitioned_queue_enforces_capacity() {
+        let queue = IdleQueue::new(2, 8, None);
+        assert!(queue.push((1, 0)).is_ok());
+        assert!(queue.push((2, 1)).is_ok());
+        assert_eq!(queue.push((3, 2)), Err((3, 2)));
+        assert_eq!(queue.len(), 2);
+    }
+
+    #[test]
+    fn partitioned_queue_routes_by_key() {
+        let queue = IdleQueue::new(4, 2, by_parity());
+        for (i, x) in [1u64, 2, 3, 4].into_iter().enumerate() {
+            queue.push((x, i)).unwrap();
+        }
+
+        let mut odd = Vec::new();
+        while let Some((x, _)) = queue.pop_bucket(queue.bucket_of(1)) {
+            odd.push(x);
+        }
+        odd.sort();
+        assert_eq!(odd, vec![1, 3]);
+        assert_eq!(queue.len(), 2);
+    }
+
+    #[test]
+    fn capacity_is_pool_wide_across_buckets() {
+        let queue = IdleQueue::new(2, 2, by_parity());
+        queue.push((2, 0)).unwrap();
+        queue.push((4, 1)).unwrap();
+        assert!(queue.push((1, 2)).is_err());
+    }
+
+    #[test]
+    fn pop_visits_every_bucket() {
+        let queue = IdleQueue::new(4, 4, by_parity());
+        queue.push((1, 0)).unwrap();
+        queue.push((2, 1)).unwrap();
+
+        assert!(queue.pop().is_some());
+        assert!(queue.pop().is_some());
+        assert!(queue.pop().is_none());
+        assert_eq!(queue.len(), 0);
+    }
+}
@@ -0,0 +1,124 @@
+//! Object ids: the pool's own and caller-supplied ones for seeded objects
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// Identifies an object for as long as it belongs to its pool
+///
+/// Carried by [`PooledObject::id`](crate::PooledObject::id), pool events,
+/// return errors and holder reports, so they can be correlated. Ids are
+/// opaque: they can be compared, hashed, displayed and, with the `serde`
+/// feature, serialized, but not built or computed with. They are unique
+/// within one pool, not across pools.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{ObjectPool, PoolConfiguration};
+///
+/// let pool = ObjectPool::new(vec![1, 2], PoolConfiguration::default());
+/// let a = pool.get_object().unwrap();
+/// let b = pool.get_object().unwrap();
+/// assert_ne!(a.id(), b.id());
+///
+/// let id = a.id();
+/// drop(a);
+/// assert_eq!(pool.get_object().unwrap().id(), id, "the same object again");
+/// println!("object {id}");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PoolObjectId(usize);
+
+impl PoolObjectId {
+    pub(crate) fn new(raw: usize) -> Self {
+        Self(raw)
+    }
+
+    pub(crate) fn raw(self) -> usize {
+        self.0
+    }
+}
+
+impl fmt::Display for PoolObjectId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde_core::Serialize for PoolObjectId {
+    fn serialize<S: serde_core::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(self.0 as u64)
+    }
+}
+
+/// Two-way mapping between the pool's internal object ids and the external
+/// ids objects were seeded with.
+///
+/// Fixed at construction: only seeded objects have an external id, and an
+/// id keeps referring to its object for the lifetime of the pool.
+#[derive(Default)]
+pub(crate) struct ExternalIds {
+    by_object: HashMap<usize, Arc<str>>,
+    by_external: HashMap<Arc<str>, usize>,
+}
+
+impl ExternalIds {
+    /// Map the `n`th id to internal object id `n`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an id appears more than once.
+    pub fn new(ids: impl IntoIterator<Item = String>) -> Self {
+        let mut mapping = Self::default();
+        for (object_id, external) in ids.into_iter().enumerate() {
+            let external: Arc<str> = external.into();
+            let previous = mapping.by_external.insert(Arc::clone(&external), object_id);
+            assert!(previous.is_none(), "duplicate external object id {external:?}");
+            mapping.by_object.insert(object_id, external);
+        }
+        mapping
+    }
+
+    pub fn external(&self, object_id: usize) -> Option<&Arc<str>> {
+        self.by_object.get(&object_id)
+    }
+
+    pub fn object(&self, external: &str) -> Option<usize> {
+        self.by_external.get(external).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_map_both_ways_in_seed_order() {
+        let ids = ExternalIds::new(["c-1".to_string(), "c-2".to_string()]);
+        assert_eq!(ids.external(1).map(|id| &**id), Some("c-2"));
+        assert_eq!(ids.object("c-1"), Some(0));
+        assert_eq!(ids.object("c-3"), None);
+        assert_eq!(ids.external(2), None);
+    }
+
+    #[test]
+    fn object_ids_display_as_numbers() {
+        assert_eq!(PoolObjectId::new(42).to_string(), "42");
+        assert!(PoolObjectId::new(1) < PoolObjectId::new(2));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn object_ids_are_serializable() {
+        fn assert_serialize<S: serde_core::Serialize>() {}
+        assert_serialize::<PoolObjectId>();
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate external object id")]
+    fn duplicate_ids_are_rejected() {
+        ExternalIds::new(["a".to_string(), "a".to_string()]);
+    }
+}
@@ -0,0 +1,205 @@
+//! Dynamic pools whose objects are set up for each acquisition
+
+use crate::acquire::Acquire;
+use crate::config::PoolConfiguration;
+use crate::errors::PoolResult;
+use crate::health::HealthStatus;
+use crate::metrics::PoolMetrics;
+use crate::observable::ObservablePool;
+use crate::pool::{DynamicObjectPool, PooledObject};
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Builds an object configured for the given arguments.
+type InitFactory<A, T> = Arc<dyn Fn(&A) -> T + Send + Sync>;
+/// Reconfigures a reused object for the given arguments.
+type ReinitHook<A, T> = Arc<dyn Fn(&mut T, &A) + Send + Sync>;
+
+/// Dynamic pool of objects that are configured for each acquisition
+///
+/// [`get_object_with_init`](Self::get_object_with_init) takes the
+/// arguments of one request. An idle object is reused after `reinit`
+/// reconfigures it for them; otherwise `factory` builds a new one from
+/// them. Suits parsers, encoders or sessions that must be set up per
+/// request but whose allocations are worth keeping.
+///
+/// Objects are only ever created from arguments, so the underlying pool is
+/// not exposed; it can be watched through [`ObservablePool`]. `reinit` runs
+/// after the configuration's borrow hooks, and not for newly created
+/// objects.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{InitPool, PoolConfiguration};
+///
+/// struct Parser {
+///     delimiter: char,
+///     fields: Vec<String>,
+/// }
+///
+/// let parsers = InitPool::new(
+///     |delimiter: &char| Parser { delimiter: *delimiter, fields: Vec::with_capacity(64) },
+///     |parser: &mut Parser, delimiter: &char| {
+///         parser.delimiter = *delimiter;
+///         parser.fields.clear();
+///     },
+///     PoolConfiguration::new().with_max_pool_size(8),
+/// );
+///
+/// let csv = parsers.get_object_with_init(&',').unwrap();
+/// assert_eq!(csv.delimiter, ',');
+/// drop(csv);
+///
+/// // The same parser, set up for the next request.
+/// let tsv = parsers.get_object_with_init(&'\t').unwrap();
+/// assert_eq!(tsv.delimiter, '\t');
+/// assert!(tsv.fields.capacity() >= 64);
+/// assert_eq!(parsers.get_metrics().total_retrieved, 2);
+/// ```
+pub struct InitPool<A: ?Sized, T: Send> {
+    pool: DynamicObjectPool<T>,
+    factory: InitFactory<A, T>,
+    reinit: ReinitHook<A, T>,
+}
+
+impl<A: ?Sized, T: Send + Sync + 'static> InitPool<A, T> {
+    /// Create a pool from a factory and a hook reconfiguring reused objects
+    pub fn new<F, R>(factory: F, reinit: R, config: PoolConfiguration<T>) -> Self
+    where
+        F: Fn(&A) -> T + Send + Sync + 'static,
+        R: Fn(&mut T, &A) + Send + Sync + 'static,
+    {
+        Self {
+            pool: DynamicObjectPool::try_new(
+                || Err::<T, _>("objects of an InitPool are created from arguments"),
+                config,
+            ),
+            factory: Arc::new(factory),
+            reinit: Arc::new(reinit),
+        }
+    }
+
+    /// Get an object set up for `args`, creating one if none is idle
+    ///
+    /// Fails like [`DynamicObjectPool::get_object`].
+    #[must_use = "the pool object must be used or explicitly dropped"]
+    #[track_caller]
+    pub fn get_object_with_init(&self, args: &A) -> PoolResult<PooledObject<T>> {
+        let created = AtomicBool::new(false);
+        let create = || {
+            created.store(true, Ordering::Relaxed);
+            (self.factory)(args)
+        };
+        let obj = self.pool.get_object_creating(&create)?;
+        Ok(self.reinit_reused(obj, args, &created))
+    }
+
+    /// Async counterpart of [`get_object_with_init`](Self::get_object_with_init)
+    #[track_caller]
+    pub fn get_object_with_init_async<'a>(&'a self, args: &'a A) -> Acquire<'a, T>
+    where
+        A: Sync,
+    {
+        Acquire::new(async move {
+            let created = AtomicBool::new(false);
+            let create = || {
+                created.store(true, Ordering::Relaxed);
+                (self.factory)(args)
+            };
+            let obj = self.pool.get_object_creating_async(&create).await?;
+            Ok(self.reinit_reused(obj, args, &created))
+        })
+    }
+
+    /// Metrics of the underlying pool
+    #[must_use]
+    pub fn get_metrics(&self) -> PoolMetrics {
+        self.pool.get_metrics()
+    }
+
+    fn reinit_reused(&self, mut obj: PooledObject<T>, args: &A, created: &AtomicBool) -> PooledObject<T> {
+        if !created.load(Ordering::Relaxed) {
+            (self.reinit)(&mut obj, args);
+        }
+        obj
+    }
+}
+
+impl<A: ?Sized, T: Send + Sync + 'static> ObservablePool for InitPool<A, T> {
+    fn available_count(&self) -> usize {
+        self.pool.available_count()
+    }
+
+    fn active_count(&self) -> usize {
+        self.pool.active_count()
+    }
+
+    fn capacity(&self) -> usize {
+        self.pool.capacity()
+    }
+
+    fn get_metrics(&self) -> PoolMetrics {
+        self.pool.get_metrics()
+    }
+
+    fn get_health_status(&self) -> HealthStatus {
+        self.pool.get_health_status()
+    }
+
+    fn export_metrics(&self) -> HashMap<String, String> {
+        self.pool.export_metrics()
+    }
+
+    fn export_metrics_prometheus(
+        &self,
+        pool_name: &str,
+        tags: Option<&HashMap<String, String>>,
+    ) -> String {
+        self.pool.export_metrics_prometheus(pool_name, tags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::PoolError;
+
+    fn session_pool() -> InitPool<str, String> {
+        InitPool::new(
+            |user: &str| format!("session:{user}"),
+            |session: &mut String, user: &str| {
+                session.clear();
+                session.push_str("reused:");
+                session.push_str(user);
+            },
+            PoolConfiguration::new().with_max_pool_size(2),
+        )
+    }
+
+    #[test]
+    fn creates_from_arguments_and_reinitializes_reused_objects() {
+        let pool = session_pool();
+        let alice = pool.get_object_with_init("alice").unwrap();
+        let bob = pool.get_object_with_init("bob").unwrap();
+        assert_eq!((alice.as_str(), bob.as_str()), ("session:alice", "session:bob"));
+        assert!(matches!(pool.get_object_with_init("carol"), Err(PoolError::PoolFull)));
+
+        drop(alice);
+        assert_eq!(*pool.get_object_with_init("carol").unwrap(), "reused:carol");
+        assert_eq!(pool.get_metrics().total_retrieved, 3);
+        assert_eq!((pool.available_count(), pool.active_count()), (1, 1));
+        assert_eq!(pool.get_metrics().creation_failures, 0);
+    }
+
+    #[tokio::test]
+    async fn async_acquisition_reinitializes_reused_objects() {
+        let pool = session_pool();
+        let obj = pool.get_object_with_init_async("alice").await.unwrap();
+        assert_eq!(*obj, "session:alice");
+        drop(obj);
+        assert_eq!(*pool.get_object_with_init_async("bob").await.unwrap(), "reused:bob");
+    }
+}
@@ -0,0 +1,246 @@
+//! Timed leases for checked-out objects
+
+use crate::metrics::Counter;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+type ExpiryCallback = Box<dyn FnOnce() + Send>;
+
+/// A time limit attached to a checked-out object
+///
+/// Leases are cooperative: the object is **not** taken away from the holder
+/// when the lease elapses. Instead the lease is flagged as expired, an
+/// optional callback fires, the expiry is counted in
+/// [`PoolMetrics::expired_leases`](crate::PoolMetrics::expired_leases) and a
+/// [`PoolWarning::LeaseExpired`](crate::PoolWarning::LeaseExpired) naming
+/// where the object was acquired is published.
+/// Holders doing long-running work should check [`expired`](Lease::expired)
+/// and release the object early.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{ObjectPool, PoolConfiguration};
+/// use std::time::Duration;
+///
+/// let pool = ObjectPool::new(vec![1], PoolConfiguration::default());
+/// let obj = pool.get_object_leased(Duration::from_secs(5)).unwrap();
+///
+/// let lease = obj.lease().unwrap();
+/// assert!(!lease.expired());
+/// assert!(lease.remaining() <= Duration::from_secs(5));
+/// ```
+#[derive(Clone)]
+pub struct Lease {
+    state: Arc<LeaseState>,
+}
+
+struct LeaseState {
+    deadline: Instant,
+    expired: AtomicBool,
+    released: AtomicBool,
+    on_expire: Mutex<Option<ExpiryCallback>>,
+    /// Reports the expiry to the pool; unlike `on_expire` it also runs when
+    /// the expiry is only noticed as the object comes back.
+    notice: Mutex<Option<ExpiryCallback>>,
+    expired_counter: Arc<Counter>,
+}
+
+impl Lease {
+    pub(crate) fn new(
+        max_hold: Duration,
+        on_expire: Option<ExpiryCallback>,
+        notice: Option<ExpiryCallback>,
+        expired_counter: Arc<Counter>,
+    ) -> Self {
+        let deadline = Instant::now() + max_hold;
+        let has_callback = on_expire.is_some();
+        let lease = Self {
+            state: Arc::new(LeaseState {
+                deadline,
+                expired: AtomicBool::new(false),
+                released: AtomicBool::new(false),
+                on_expire: Mutex::new(on_expire),
+                notice: Mutex::new(notice),
+                expired_counter,
+            }),
+        };
+
+        // A callback has to fire even if nobody polls the lease, so arm a timer.
+        if has_callback {
+            match tokio::runtime::Handle::try_current() {
+                Ok(handle) => {
+                    let timer = LeaseTimer(Some(lease.clone()));
+                    handle.spawn(async move {
+                        tokio::time::sleep_until(deadline.into()).await;
+                        timer.fire();
+                    });
+                }
+                Err(_) => lease.clone().arm_on_thread(),
+            }
+        }
+
+        lease
+    }
+
+    /// Whether the lease has elapsed
+    #[must_use]
+    pub fn expired(&self) -> bool {
+        if self.state.expired.load(Ordering::Acquire) {
+            return true;
+        }
+        let elapsed = Instant::now() >= self.state.deadline;
+        if elapsed && !self.state.released.load(Ordering::Acquire) {
+            self.mark_expired();
+        }
+        elapsed
+    }
+
+    /// Time left before the lease elapses (zero once expired)
+    #[must_use]
+    pub fn remaining(&self) -> Duration {
+        self.state.deadline.saturating_duration_since(Instant::now())
+    }
+
+    /// The instant at which the lease elapses
+    #[must_use]
+    pub fn deadline(&self) -> Instant {
+        self.state.deadline
+    }
+
+    /// Called when the guard goes back to the pool (or is detached).
+    pub(crate) fn release(&self) {
+        // Count a lease that ran over even if the holder never checked it.
+        let _ = self.expired();
+        self.state.released.store(true, Ordering::Release);
+        // The callbacks are only relevant while the object is held.
+        take(&self.state.on_expire);
+        take(&self.state.notice);
+    }
+
+    fn arm_on_thread(self) {
+        std::thread::spawn(move || {
+            std::thread::sleep(self.remaining());
+            self.fire_if_held();
+        });
+    }
+
+    fn fire_if_held(&self) {
+        if !self.state.released.load(Ordering::Acquire) {
+            self.mark_expired();
+        }
+    }
+
+    fn mark_expired(&self) {
+        if self.state.expired.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        self.state.expired_counter.fetch_add(1, Ordering::Relaxed);
+        if let Some(notice) = take(&self.state.notice) {
+            notice();
+        }
+        if let Some(callback) = take(&self.state.on_expire) {
+            callback();
+        }
+    }
+}
+
+fn take(slot: &Mutex<Option<ExpiryCallback>>) -> Option<ExpiryCallback> {
+    slot.lock().unwrap_or_else(|p| p.into_inner()).take()
+}
+
+/// A lease's timer task on a tokio runtime.
+///
+/// The runtime the lease was created on may shut down before the deadline
+/// (a pool can outlive the runtime it was first used from); the task is then
+/// dropped unfired and the timer moves to a thread.
+struct LeaseTimer(Option<Lease>);
+
+impl LeaseTimer {
+    fn fire(mut self) {
+        if let Some(lease) = self.0.take() {
+            lease.fire_if_held();
+        }
+    }
+}
+
+impl Drop for LeaseTimer {
+    fn drop(&mut self) {
+        if let Some(lease) = self.0.take()
+            && !lease.state.released.load(Ordering::Acquire)
+        {
+            lease.arm_on_thread();
+        }
+    }
+}
+
+impl std::fmt::Debug for Lease {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Lease")
+            .field("remaining", &self.remaining())
+            .field("expired", &self.state.expired.load(Ordering::Relaxed))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lease(max_hold: Duration, on_expire: Option<ExpiryCallback>) -> (Lease, Arc<Counter>) {
+        let counter = Arc::new(Counter::new(0));
+        (Lease::new(max_hold, on_expire, None, Arc::clone(&counter)), counter)
+    }
+
+    #[test]
+    fn fresh_lease_is_not_expired() {
+        let (lease, counter) = lease(Duration::from_secs(60), None);
+        assert!(!lease.expired());
+        assert!(lease.remaining() > Duration::from_secs(59));
+        assert_eq!(counter.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn elapsed_lease_is_counted_once() {
+        let (lease, counter) = lease(Duration::from_millis(5), None);
+        std::thread::sleep(Duration::from_millis(10));
+
+        assert!(lease.expired());
+        assert!(lease.expired());
+        lease.release();
+        assert_eq!(counter.load(Ordering::Relaxed), 1);
+        assert_eq!(lease.remaining(), Duration::ZERO);
+    }
+
+    #[test]
+    fn callback_fires_without_polling() {
+        let fired = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&fired);
+        let (lease, counter) = lease(
+            Duration::from_millis(5),
+            Some(Box::new(move || flag.store(true, Ordering::SeqCst))),
+        );
+
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(fired.load(Ordering::SeqCst));
+        assert_eq!(counter.load(Ordering::Relaxed), 1);
+        assert!(lease.expired());
+    }
+
+    #[test]
+    fn callback_does_not_fire_after_release() {
+        let fired = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&fired);
+        let (lease, counter) = lease(
+            Duration::from_millis(20),
+            Some(Box::new(move || flag.store(true, Ordering::SeqCst))),
+        );
+
+        lease.release();
+        std::thread::sleep(Duration::from_millis(40));
+        assert!(!fired.load(Ordering::SeqCst));
+        assert_eq!(counter.load(Ordering::Relaxed), 0);
+    }
+}
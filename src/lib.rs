@@ -8,12 +8,141 @@
 //! - Thread-safe object pooling with lock-free operations
 //! - Automatic return of objects via RAII ([`Drop`] trait)
 //! - Async support with timeout and jittered retry
+//! - Unbounded async waits for callers that would rather queue than time out
+//!   ([`PoolConfiguration::with_no_timeout`])
+//! - Pools shareable across tokio runtimes, blocking threads and other executors
+//! - Per-pool fail-fast or wait behavior on empty via [`WaitPolicy`]
+//! - Stream-based acquisition via [`AcquireStream`]
+//! - Nameable, `Unpin` acquisition future ([`Acquire`]) for poll-based code
 //! - Queryable pools for finding objects matching predicates
+//! - Stable caller-supplied object ids for seeded objects
 //! - Dynamic pools with factory methods
+//! - Pools of trait objects (`Box<dyn Trait + Send + Sync>`), with boxed
+//!   factories chosen at runtime ([`DynamicObjectPool::from_boxed_factory`])
+//! - Creation rate limiting and backoff after factory failures in dynamic pools
+//! - Capped concurrent factory calls so bursts on an empty pool coalesce
+//! - Choice between creating and waiting for reuse via [`CreationPolicy`]
+//! - Keyed pools creating matching objects on demand ([`DynamicQueryablePool`])
 //! - Health monitoring and metrics (including Prometheus export)
+//! - Optional per-caller metrics keyed by acquisition label
+//! - Optional report of current holders with hold times and sampled backtraces
+//! - Optional in-memory ring of recent acquisitions, returns and evictions for
+//!   post-mortem debugging ([`ObjectPool::recent_activity`])
 //! - Pool warm-up/pre-population
+//! - Startup self-test via `verify()`
+//! - Configuration presets for common workloads
+//! - Per-acquisition caller context ([`AcquireContext`]) with deadlines and hook access
+//! - Durations configurable from strings such as `"30m"` or `"250ms"`
 //! - Eviction/TTL support
+//! - Graceful shutdown failing further acquisitions with `PoolError::PoolClosed`
+//! - Epoch invalidation retiring every existing object, e.g. after credential rotation
+//! - Factories swappable at runtime, pointing a live pool at a new endpoint
 //! - Circuit breaker pattern
+//! - Primary/standby pool pairs switching over on failure ([`FailoverPool`])
+//! - First-available acquisition across several pools of the same type, e.g.
+//!   local before remote ([`try_acquire_any`])
+//! - Read/write split pairs with optional read fallback ([`ReadWritePool`])
+//! - Blue/green rollouts shifting a percentage of acquisitions to a new pool,
+//!   ramping on health and rolling back on errors ([`MigratingPool`])
+//! - Saturation-aware readiness and load for tower load balancing behind the
+//!   `tower` feature (`PoolService`)
+//! - Pooled worker threads for CPU-bound jobs ([`WorkerPool`])
+//! - Byte buffer pools with 4K/64K/1M-style size classes ([`BufferPool`])
+//! - Clear-on-return pools of strings and vectors with a retained-capacity cap ([`Recycle`], [`StringPool`])
+//! - Object-less permit pools limiting concurrency with the full pool machinery ([`PermitPool`])
+//! - Acquire-and-execute retries that replace broken objects ([`RetryPolicy`])
+//! - Result-aware guards whose completion drives validation, discard and the circuit breaker ([`FailurePolicy`])
+//! - Cancel-safe async acquisition with an accurate count of waiting callers
+//! - Diagnosis of failed acquisitions naming the limits that were hit ([`FailureDiagnosis`])
+//! - Per-object application metadata living as long as the object ([`PooledObject::set_meta`])
+//! - Remaining time-to-live and idle time on checked-out objects ([`PooledObject::remaining_ttl`])
+//! - Time-to-live refreshed by successful validation ([`TtlRefresh`])
+//! - Custom eviction predicates over object age, idle time and use count ([`ObjectStats`])
+//! - Per-object health scores steering acquisition towards the healthiest idle
+//!   objects ([`PoolConfiguration::with_selection`])
+//! - Pool size, warm-up and timeout recommendations from wait and hold percentiles ([`PoolTuningAdvice`])
+//! - Latency and timeout objectives tracked as error budgets with rolling burn
+//!   rates and exhaustion events ([`SloTargets`])
+//! - Periodic metric snapshots appended to size- or age-rotated CSV files
+//!   for offline capacity planning ([`MetricsRecorder`])
+//! - Pool events (evictions, empty pool, breaker transitions) as a broadcast
+//!   channel ([`PoolEvent`]), logged through `tracing` with per-event levels
+//!   and sampling behind the `tracing` feature (`EventLogger`)
+//! - 1-in-N sampling of latency histograms and high-frequency events, with
+//!   exact counters ([`PoolConfiguration::with_sampling`])
+//! - Capacity-lost gauge and health warning for fixed pools that shrink
+//!   ([`PoolConfiguration::with_capacity_loss_warning`])
+//! - Churn alarm: health warning and `PoolWarning::HighChurn` when objects
+//!   are replaced faster than a per-minute threshold
+//!   ([`PoolConfiguration::with_churn_warning`])
+//! - Queryable pool scan-length histogram, match rate and no-match counts ([`QueryMetrics`])
+//! - Bounded wait queue shedding excess load with `PoolError::QueueFull`
+//!   ([`PoolConfiguration::with_max_waiters`])
+//! - Per-acquisition timeout jitter breaking up synchronized timeout waves
+//!   ([`PoolConfiguration::with_timeout_jitter`])
+//! - Pause and resume for backend maintenance windows ([`ObjectPool::pause`])
+//! - Gradual retirement of objects as they become idle ([`ObjectPool::retire`])
+//! - Documented precedence of pool size and active-object limits ([`CapacityLimits`])
+//! - Typestate [`PoolBuilder`] rejecting pools without a factory and return
+//!   validation without a validator at compile time
+//! - Pools built from descriptors such as `redis://host?max_size=16` through
+//!   registered scheme handlers ([`register_scheme`], [`DynamicObjectPool::from_uri`])
+//! - Fleet-wide health and metrics roll-ups over named pools
+//!   ([`PoolRegistry::aggregate_health`], [`PoolRegistry::aggregate_metrics`])
+//! - Memory accounting: idle and active byte gauges from a size estimator
+//!   or [`SizedPoolable`], with a health warning above a retained-memory cap
+//!   ([`PoolConfiguration::with_retained_memory_cap`])
+//! - Graceful degradation: dynamic pools serve objects from a fallback
+//!   factory while the circuit breaker is open, flagged by
+//!   [`PooledObject::origin`] ([`PoolConfiguration::with_degraded_factory`])
+//! - Accounting self-check comparing population, idle, active and pending
+//!   objects and the per-object tables ([`ObjectPool::check_consistency`])
+//! - Deterministic handling of objects that expire while checked out:
+//!   evicted or refreshed as they are returned ([`ExpiredReturn`])
+//! - Dynamic pools whose objects are built from, or reconfigured for, each
+//!   acquisition's arguments ([`InitPool`])
+//! - Connection pools opened by an async factory that replace a broken
+//!   connection with backoff and retry the work once ([`ReconnectingPool`])
+//! - Observers receiving a pool's events, metrics and health changes on a
+//!   fixed cadence, for bespoke monitoring ([`PoolObserver`])
+//! - Opaque [`PoolObjectId`] in guards, events and holder reports,
+//!   serializable behind the `serde` feature
+//! - Human-readable durations like `"1h 30m"` in deserialized configuration
+//!   behind the `serde` feature (`deserialize_duration`)
+//! - Lock-free, non-allocating acquisition for real-time callbacks
+//!   ([`ObjectPool::try_get_object_fast`])
+//! - Process-wide pools per type declared with [`pooled!`], accessed as
+//!   `MyType::pooled()`
+//! - Guards over `Sink`s and `Stream`s usable directly in `futures` combinators
+//!   behind the `futures` feature
+//! - Source locations of acquisitions, captured with `#[track_caller]`, in
+//!   holder reports and lease-expiry warnings ([`PooledObject::acquired_at`])
+//! - Mutex-based internals in place of the lock-free maps and queues under
+//!   Miri or with the `mutex-internals` feature, so embedding crates can run
+//!   their tests under Miri
+//! - `minimal` feature compiling metrics counters, latency histograms,
+//!   eviction timestamps and health tracking out of the acquire/return path.
+//!   Not additive: it applies to every pool in the binary, so most metrics
+//!   read 0 and eviction policies, churn warnings and SLO targets are
+//!   ignored. Only a final binary should enable it
+//! - Axum extractor, health and Prometheus endpoints and graceful shutdown
+//!   behind the `axum` feature (`http` module)
+//! - Per-thread pooled scratch objects for rayon parallel work behind the
+//!   `rayon` feature (`parallel` module)
+//! - Benchmark harness measuring the pool against a naive `Mutex<Vec<T>>`
+//!   pool on a given workload behind the `bench-internals` feature (`bench`
+//!   module)
+//! - `#[derive(Recycle)]` resetting pooled structs from `#[pool(clear)]`,
+//!   `#[pool(reset)]` and `#[pool(validate = ...)]` attributes behind the
+//!   `derive` feature
+//! - Starvation detection warning about hold-while-acquiring deadlocks
+//! - Inline [`SyncHooks`] and background-thread [`AsyncHooks`], keeping slow
+//!   teardown out of the return path
+//! - Wait, hold and creation durations handed to timed acquire, return and
+//!   create hooks ([`HookTiming`])
+//! - Panics in validation and hooks contained as validation failures
+//! - Bulkheads capping how many objects each traffic category may hold
+//! - Limit groups sharing one active-object cap across several pools
 //! - [`#[must_use]`](must_use) on all observability methods
 //!
 //! ## Quick Start
@@ -66,7 +195,8 @@
 //!
 //! ## Observing Pool State
 //!
-//! All three pool types expose the same set of observability methods:
+//! All three pool types expose the same set of observability methods, also
+//! available generically through the [`ObservablePool`] trait:
 //!
 //! ```rust
 //! use esox_objectpool::{ObjectPool, PoolConfiguration};
@@ -94,6 +224,11 @@
 //! - All observability methods are annotated with `#[must_use]`.
 //! - `unwrap()` on `PooledObject` is deprecated, use `into_detached()` instead.
 
+// Lets `#[derive(Recycle)]` name `::esox_objectpool` inside this crate too.
+#[cfg(feature = "derive")]
+extern crate self as esox_objectpool;
+
+mod concurrent;
 mod pool;
 mod config;
 mod metrics;
@@ -101,11 +236,118 @@ mod health;
 mod eviction;
 mod circuit_breaker;
 mod errors;
+mod acquire;
+mod stream;
+mod batch;
+mod lease;
+mod events;
+mod wait;
+mod observable;
+mod observer;
+mod idle;
+mod bulkhead;
+mod ids;
+mod group;
+mod verify;
+mod consistency;
+mod duration;
+mod context;
+mod throttle;
+mod holders;
+mod activity;
+mod watchdog;
+mod churn;
+mod hooks;
+mod failover;
+mod select;
+mod readwrite;
+mod migration;
+#[cfg(feature = "tower")]
+mod service;
+mod worker;
+mod buffer;
+mod init;
+mod reconnect;
+mod recycle;
+mod memory;
+mod degraded;
+mod permit;
+mod retry;
+mod failure;
+mod diagnosis;
+mod metadata;
+mod tuning;
+mod selection;
+mod slo;
+mod recorder;
+mod builder;
+mod scheme;
+mod registry;
+mod global;
+#[cfg(feature = "tracing")]
+mod logging;
+#[cfg(feature = "axum")]
+pub mod http;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+#[cfg(feature = "bench-internals")]
+pub mod bench;
 
-pub use pool::{ObjectPool, QueryableObjectPool, DynamicObjectPool, PooledObject};
-pub use config::PoolConfiguration;
-pub use metrics::{PoolMetrics, MetricsExporter};
+pub use pool::{ObjectPool, QueryableObjectPool, DynamicObjectPool, DynamicQueryablePool, PooledObject};
+pub use config::{
+    AcquireHook, BorrowHook, CapacityLimits, ContextHook, DestroyHook, PartitionFn, PoolConfiguration, ReturnHook,
+    FallbackFactory, SelectionFn, SizeFn, TimedHook,
+};
+pub use metrics::{CallerMetrics, PoolMetrics, MetricsExporter, QueryMetrics};
 pub use health::HealthStatus;
-pub use eviction::EvictionPolicy;
-pub use circuit_breaker::{CircuitBreaker, CircuitBreakerState};
-pub use errors::{PoolError, PoolResult};
+pub use eviction::{EvictionPolicy, EvictionPredicate, ExpiredReturn, ObjectStats, TtlRefresh};
+pub use circuit_breaker::{BreakerSignals, CircuitBreaker, CircuitBreakerState};
+pub use errors::{GuardedError, IntoInnerError, PoolError, PoolResult, WaitBreakdown};
+pub use acquire::Acquire;
+pub use stream::AcquireStream;
+pub use batch::PooledBatch;
+pub use lease::Lease;
+pub use events::{PoolEvent, PoolWarning, ReturnError};
+#[cfg(feature = "tracing")]
+pub use logging::{EventKind, EventLogger};
+pub use wait::WaitPolicy;
+pub use observable::ObservablePool;
+pub use observer::{ObserverHandle, PoolObserver};
+pub use group::LimitGroup;
+pub use verify::{FactoryCheck, VerifyReport};
+pub use consistency::ConsistencyReport;
+pub use context::AcquireContext;
+pub use throttle::CreationPolicy;
+pub use holders::Holder;
+pub use activity::{ActivityOutcome, ActivityRecord};
+pub use hooks::{AsyncHooks, HookPanicPolicy, HookTiming, SyncHooks};
+pub use failover::{FailoverPool, FailoverRole, SwitchReason, Switchover};
+pub use select::{try_acquire_any, try_acquire_any_async, SelectablePool};
+pub use readwrite::{ReadWriteMetrics, ReadWritePool};
+pub use migration::{
+    MigratingPool, MigrationMetrics, MigrationSide, MigrationSideMetrics, RampPolicy, ShiftReason, TrafficShift,
+};
+#[cfg(feature = "tower")]
+pub use service::PoolService;
+pub use worker::{JobHandle, Worker, WorkerPool};
+pub use buffer::BufferPool;
+pub use init::InitPool;
+pub use reconnect::ReconnectingPool;
+pub use recycle::{BytesPool, Recycle, StringPool, VecPool};
+pub use memory::SizedPoolable;
+pub use degraded::ObjectOrigin;
+#[cfg(feature = "derive")]
+pub use esox_objectpool_derive::Recycle;
+pub use permit::{Permit, PermitPool};
+pub use retry::RetryPolicy;
+pub use failure::FailurePolicy;
+pub use diagnosis::{FailureDiagnosis, Gate};
+pub use tuning::PoolTuningAdvice;
+pub use slo::{BudgetStatus, SloObjective, SloStatus, SloTargets};
+pub use recorder::{MetricsRecorder, Recording};
+pub use ids::PoolObjectId;
+pub use scheme::{register_scheme, PoolUri};
+#[cfg(feature = "serde")]
+pub use duration::deserialize_duration;
+pub use registry::{FleetHealth, FleetMetrics, PoolHealth, PoolRegistry, PoolState};
+pub use builder::{Dynamic, Fixed, HasValidator, NeedsSource, NoValidator, PoolBuilder};
@@ -0,0 +1,311 @@
+//! Structured logging of pool events through `tracing`
+
+use crate::events::{PoolEvent, PoolWarning, ReturnError};
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::Level;
+
+/// Target of every record written by an [`EventLogger`].
+const TARGET: &str = "esox_objectpool";
+
+/// The kinds of event an [`EventLogger`] writes, each with its own level
+/// and sampling
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum EventKind {
+    /// [`PoolEvent::Evicted`]
+    Evicted,
+    /// [`PoolEvent::Empty`]
+    Empty,
+    /// [`PoolEvent::CircuitOpened`]
+    CircuitOpened,
+    /// [`PoolEvent::CircuitHalfOpen`]
+    CircuitHalfOpen,
+    /// [`PoolEvent::CircuitClosed`]
+    CircuitClosed,
+    /// [`PoolEvent::SloBudgetExhausted`]
+    SloBudgetExhausted,
+    /// Any [`ReturnError`]
+    ReturnFailed,
+    /// Any [`PoolWarning`]
+    Warning,
+}
+
+impl EventKind {
+    /// Name written in the `event` field of each record
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Evicted => "evicted",
+            Self::Empty => "empty",
+            Self::CircuitOpened => "circuit_opened",
+            Self::CircuitHalfOpen => "circuit_half_open",
+            Self::CircuitClosed => "circuit_closed",
+            Self::SloBudgetExhausted => "slo_budget_exhausted",
+            Self::ReturnFailed => "return_failed",
+            Self::Warning => "warning",
+        }
+    }
+
+    fn of(event: &PoolEvent) -> Self {
+        match event {
+            PoolEvent::Evicted { .. } => Self::Evicted,
+            PoolEvent::Empty => Self::Empty,
+            PoolEvent::CircuitOpened => Self::CircuitOpened,
+            PoolEvent::CircuitHalfOpen => Self::CircuitHalfOpen,
+            PoolEvent::CircuitClosed => Self::CircuitClosed,
+            PoolEvent::SloBudgetExhausted { .. } => Self::SloBudgetExhausted,
+        }
+    }
+}
+
+struct Rule {
+    level: Option<Level>,
+    every: u64,
+    seen: AtomicU64,
+}
+
+impl Rule {
+    fn new(level: Level) -> Self {
+        Self {
+            level: Some(level),
+            every: 1,
+            seen: AtomicU64::new(0),
+        }
+    }
+}
+
+/// Writes a pool's events, return errors and warnings as `tracing` records
+///
+/// Installed with [`ObjectPool::log_events`](crate::ObjectPool::log_events),
+/// after which every [`PoolEvent`], [`ReturnError`] and [`PoolWarning`] the
+/// pool publishes is also logged, synchronously and without subscribing to
+/// any channel. Records have target `esox_objectpool` and carry `pool` and
+/// `event` fields.
+///
+/// Default levels: evictions and empty-pool hits at `DEBUG`, the breaker
+/// half-opening and closing at `INFO`, the breaker opening, exhausted error
+/// budgets, return errors and warnings at `WARN`. Frequent events can be sampled so only every
+/// n-th is written.
+///
+/// Requires the `tracing` feature.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{EventKind, EventLogger, ObjectPool, PoolConfiguration};
+/// use tracing::Level;
+///
+/// let pool = ObjectPool::new(vec![1], PoolConfiguration::default());
+/// pool.log_events(
+///     EventLogger::new("db")
+///         .with_level(EventKind::Empty, Level::INFO)
+///         .with_sampling(EventKind::Empty, 100)
+///         .without(EventKind::Evicted),
+/// );
+/// ```
+pub struct EventLogger {
+    pool: Arc<str>,
+    rules: HashMap<EventKind, Rule>,
+}
+
+impl EventLogger {
+    /// Logger for the pool called `pool`, with the default levels
+    pub fn new(pool: impl Into<Arc<str>>) -> Self {
+        let rules = [
+            (EventKind::Evicted, Level::DEBUG),
+            (EventKind::Empty, Level::DEBUG),
+            (EventKind::CircuitOpened, Level::WARN),
+            (EventKind::CircuitHalfOpen, Level::INFO),
+            (EventKind::CircuitClosed, Level::INFO),
+            (EventKind::SloBudgetExhausted, Level::WARN),
+            (EventKind::ReturnFailed, Level::WARN),
+            (EventKind::Warning, Level::WARN),
+        ]
+        .into_iter()
+        .map(|(kind, level)| (kind, Rule::new(level)))
+        .collect();
+        Self {
+            pool: pool.into(),
+            rules,
+        }
+    }
+
+    /// Write events of `kind` at `level`
+    pub fn with_level(mut self, kind: EventKind, level: Level) -> Self {
+        self.rule(kind).level = Some(level);
+        self
+    }
+
+    /// Do not write events of `kind`
+    pub fn without(mut self, kind: EventKind) -> Self {
+        self.rule(kind).level = None;
+        self
+    }
+
+    /// Write only the first of every `every` events of `kind`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `every` is 0.
+    pub fn with_sampling(mut self, kind: EventKind, every: u64) -> Self {
+        assert!(every > 0, "sampling needs to keep one event in at least 1");
+        self.rule(kind).every = every;
+        self
+    }
+
+    fn rule(&mut self, kind: EventKind) -> &mut Rule {
+        self.rules.entry(kind).or_insert_with(|| Rule::new(Level::INFO))
+    }
+
+    pub(crate) fn event(&self, event: &PoolEvent) {
+        self.write(EventKind::of(event), event);
+    }
+
+    pub(crate) fn return_error(&self, error: &ReturnError) {
+        self.write(EventKind::ReturnFailed, error);
+    }
+
+    pub(crate) fn warning(&self, warning: &PoolWarning) {
+        self.write(EventKind::Warning, warning);
+    }
+
+    fn write(&self, kind: EventKind, message: &dyn fmt::Display) {
+        let Some(rule) = self.rules.get(&kind) else {
+            return;
+        };
+        let Some(level) = rule.level else {
+            return;
+        };
+        if rule.every > 1 && rule.seen.fetch_add(1, Ordering::Relaxed) % rule.every != 0 {
+            return;
+        }
+        let (pool, event) = (&*self.pool, kind.name());
+        if level == Level::ERROR {
+            tracing::error!(target: TARGET, pool, event, "{message}");
+        } else if level == Level::WARN {
+            tracing::warn!(target: TARGET, pool, event, "{message}");
+        } else if level == Level::INFO {
+            tracing::info!(target: TARGET, pool, event, "{message}");
+        } else if level == Level::DEBUG {
+            tracing::debug!(target: TARGET, pool, event, "{message}");
+        } else {
+            tracing::trace!(target: TARGET, pool, event, "{message}");
+        }
+    }
+}
+
+impl fmt::Debug for EventLogger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventLogger")
+            .field("pool", &self.pool)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ObjectPool, PoolConfiguration, PoolObjectId};
+
+    use std::sync::Mutex;
+    use std::time::Duration;
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    /// Collects `(level, event field, message)` of every record.
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<(Level, String, String)>>>);
+
+    #[derive(Default)]
+    struct Fields {
+        event: String,
+        message: String,
+    }
+
+    impl Visit for Fields {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            if field.name() == "event" {
+                self.event = value.to_string();
+            }
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            if field.name() == "message" {
+                self.message = format!("{value:?}");
+            }
+        }
+    }
+
+    impl Subscriber for Capture {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _: &Attributes<'_>) -> Id {
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut fields = Fields::default();
+            event.record(&mut fields);
+            let level = *event.metadata().level();
+            self.0.lock().unwrap().push((level, fields.event, fields.message));
+        }
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn events_are_written_at_their_levels() {
+        let capture = Capture::default();
+        tracing::subscriber::with_default(capture.clone(), || {
+            let pool = ObjectPool::new(
+                vec![1],
+                PoolConfiguration::new()
+                    .with_ttl(Duration::from_millis(1))
+                    .with_validation(|x: &i32| *x > 0),
+            );
+            pool.log_events(EventLogger::new("db").with_level(EventKind::Evicted, Level::INFO));
+
+            let mut obj = pool.get_object().unwrap();
+            *obj = -1;
+            drop(obj);
+            assert!(pool.try_get_object().unwrap().is_none());
+        });
+
+        let records = capture.0.lock().unwrap();
+        let kinds: Vec<_> = records.iter().map(|(level, event, _)| (*level, event.as_str())).collect();
+        assert_eq!(kinds, [(Level::WARN, "return_failed"), (Level::DEBUG, "empty")]);
+        assert!(records[0].2.contains("failed validation"));
+    }
+
+    #[test]
+    fn sampling_and_disabling() {
+        let capture = Capture::default();
+        tracing::subscriber::with_default(capture.clone(), || {
+            let logger = EventLogger::new("db")
+                .with_sampling(EventKind::Empty, 3)
+                .without(EventKind::Evicted);
+            for _ in 0..7 {
+                logger.event(&PoolEvent::Empty);
+            }
+            logger.event(&PoolEvent::Evicted { object_id: PoolObjectId::new(1) });
+            logger.event(&PoolEvent::CircuitOpened);
+        });
+
+        let records = capture.0.lock().unwrap();
+        let events: Vec<_> = records.iter().map(|(_, event, _)| event.as_str()).collect();
+        assert_eq!(events, ["empty", "empty", "empty", "circuit_opened"]);
+    }
+}
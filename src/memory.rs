@@ -0,0 +1,210 @@
+//! Estimated memory held by pooled objects
+
+use crate::config::{PoolConfiguration, SizeFn};
+
+use std::collections::{HashMap, VecDeque};
+use std::mem::size_of;
+use std::sync::{Mutex, PoisonError};
+
+/// Types that can estimate how much memory they hold
+///
+/// Implemented for `Vec<T>`, `String` and `VecDeque<T>`, counting the
+/// value itself and its allocated capacity. Pools configured with
+/// [`with_memory_accounting`](PoolConfiguration::with_memory_accounting)
+/// use it to report retained and active bytes.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::SizedPoolable;
+///
+/// struct Frame {
+///     pixels: Vec<u32>,
+/// }
+///
+/// impl SizedPoolable for Frame {
+///     fn estimated_size(&self) -> usize {
+///         self.pixels.estimated_size()
+///     }
+/// }
+///
+/// let frame = Frame { pixels: Vec::with_capacity(1024) };
+/// assert!(frame.estimated_size() >= 4096);
+/// ```
+pub trait SizedPoolable {
+    /// Approximate bytes held by the value, including the heap memory it
+    /// owns
+    fn estimated_size(&self) -> usize;
+}
+
+impl<T> SizedPoolable for Vec<T> {
+    fn estimated_size(&self) -> usize {
+        size_of::<Self>() + self.capacity() * size_of::<T>()
+    }
+}
+
+impl SizedPoolable for String {
+    fn estimated_size(&self) -> usize {
+        size_of::<Self>() + self.capacity()
+    }
+}
+
+impl<T> SizedPoolable for VecDeque<T> {
+    fn estimated_size(&self) -> usize {
+        size_of::<Self>() + self.capacity() * size_of::<T>()
+    }
+}
+
+impl<T: SizedPoolable + 'static> PoolConfiguration<T> {
+    /// Account for the memory held by the pool's objects, as estimated by
+    /// [`SizedPoolable::estimated_size`]
+    ///
+    /// Shorthand for [`with_size_estimator`](Self::with_size_estimator).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{BytesPool, PoolConfiguration};
+    ///
+    /// let buffers = BytesPool::new(
+    ///     || Vec::with_capacity(4096),
+    ///     PoolConfiguration::new().with_max_pool_size(4).with_memory_accounting(),
+    /// );
+    ///
+    /// let buf = buffers.get_object().unwrap();
+    /// assert!(buffers.get_metrics().active_bytes >= 4096);
+    /// drop(buf);
+    /// assert!(buffers.get_metrics().idle_bytes >= 4096);
+    /// assert_eq!(buffers.get_metrics().active_bytes, 0);
+    /// ```
+    pub fn with_memory_accounting(self) -> Self {
+        self.with_size_estimator(T::estimated_size)
+    }
+}
+
+/// Estimated memory held by a pool, split by object state
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct MemoryUsage {
+    /// Bytes held by idle objects.
+    pub idle: usize,
+    /// Bytes held by checked-out objects.
+    pub active: usize,
+}
+
+#[derive(Default)]
+struct Accounts {
+    /// Last estimate of each object, and whether it is checked out.
+    sizes: HashMap<usize, (usize, bool)>,
+    usage: MemoryUsage,
+}
+
+impl Accounts {
+    /// Replace the estimate of object `id`; `None` forgets it.
+    fn set(&mut self, id: usize, entry: Option<(usize, bool)>) {
+        let old = match entry {
+            Some(entry) => self.sizes.insert(id, entry),
+            None => self.sizes.remove(&id),
+        };
+        if let Some((size, active)) = old {
+            let total = self.total(active);
+            *total = total.saturating_sub(size);
+        }
+        if let Some((size, active)) = entry {
+            *self.total(active) += size;
+        }
+    }
+
+    fn total(&mut self, active: bool) -> &mut usize {
+        if active {
+            &mut self.usage.active
+        } else {
+            &mut self.usage.idle
+        }
+    }
+}
+
+/// Per-object size estimates of one pool, summed into idle and active
+/// bytes. Objects are measured as they go idle and as they are handed out,
+/// so growth while checked out shows once they come back.
+pub(crate) struct MemoryAccount<T> {
+    estimate: SizeFn<T>,
+    accounts: Mutex<Accounts>,
+}
+
+impl<T> MemoryAccount<T> {
+    pub fn new(estimate: SizeFn<T>) -> Self {
+        Self {
+            estimate,
+            accounts: Mutex::new(Accounts::default()),
+        }
+    }
+
+    /// `obj` is idle in the pool.
+    pub fn idle(&self, id: usize, obj: &T) {
+        self.measure(id, obj, false);
+    }
+
+    /// `obj` is being handed out.
+    pub fn active(&self, id: usize, obj: &T) {
+        self.measure(id, obj, true);
+    }
+
+    /// Object `id` left the pool.
+    pub fn remove(&self, id: usize) {
+        self.lock().set(id, None);
+    }
+
+    pub fn usage(&self) -> MemoryUsage {
+        self.lock().usage
+    }
+
+    /// Number of idle and of checked-out objects with an estimate.
+    pub fn tracked(&self) -> (usize, usize) {
+        let accounts = self.lock();
+        let active = accounts.sizes.values().filter(|(_, active)| *active).count();
+        (accounts.sizes.len() - active, active)
+    }
+
+    fn measure(&self, id: usize, obj: &T, active: bool) {
+        let size = (self.estimate)(obj);
+        self.lock().set(id, Some((size, active)));
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Accounts> {
+        self.accounts.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn moves_bytes_between_idle_and_active() {
+        let account = MemoryAccount::new(Arc::new(|buf: &Vec<u8>| buf.capacity()) as SizeFn<Vec<u8>>);
+        let (small, large) = (Vec::with_capacity(10), Vec::with_capacity(100));
+        account.idle(0, &small);
+        account.idle(1, &large);
+        assert_eq!(account.usage(), MemoryUsage { idle: 110, active: 0 });
+
+        account.active(1, &large);
+        assert_eq!(account.usage(), MemoryUsage { idle: 10, active: 100 });
+
+        // Grew while checked out: the new size counts once it is back.
+        account.idle(1, &Vec::<u8>::with_capacity(300));
+        assert_eq!(account.usage(), MemoryUsage { idle: 310, active: 0 });
+
+        account.remove(0);
+        account.remove(0);
+        assert_eq!(account.usage(), MemoryUsage { idle: 300, active: 0 });
+    }
+
+    #[test]
+    fn collections_count_their_capacity() {
+        let base = size_of::<Vec<u32>>();
+        assert_eq!(Vec::<u32>::with_capacity(8).estimated_size(), base + 32);
+        assert!(String::with_capacity(64).estimated_size() >= 64);
+        assert!(VecDeque::<u64>::with_capacity(4).estimated_size() >= 32);
+    }
+}
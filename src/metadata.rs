@@ -0,0 +1,84 @@
+//! Application metadata attached to pooled objects
+
+use crate::concurrent::Map;
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+type UserMeta = HashMap<TypeId, Box<dyn Any + Send + Sync>>;
+
+/// Values applications attach to objects with
+/// [`PooledObject::set_meta`](crate::PooledObject::set_meta), keyed by
+/// object id and value type.
+///
+/// Kept by the `EvictionTracker`, which drops an object's entry along
+/// with the rest of its tracking in `remove_object`.
+#[derive(Default)]
+pub(crate) struct MetaTable {
+    entries: Map<usize, UserMeta>,
+}
+
+impl MetaTable {
+    pub fn get<M: Clone + 'static>(&self, id: usize) -> Option<M> {
+        self.entries
+            .read(&id, |meta| meta.get(&TypeId::of::<M>())?.downcast_ref::<M>().cloned())
+            .flatten()
+    }
+
+    /// Store `value`, returning the value of the same type it replaces.
+    pub fn set<M: Send + Sync + 'static>(&self, id: usize, value: M) -> Option<M> {
+        self.entries
+            .update_or_default(id, |meta| meta.insert(TypeId::of::<M>(), Box::new(value)))
+            .and_then(|previous| previous.downcast().ok())
+            .map(|previous| *previous)
+    }
+
+    pub fn take<M: 'static>(&self, id: usize) -> Option<M> {
+        let (value, emptied) = self
+            .entries
+            .update(&id, |meta| (meta.remove(&TypeId::of::<M>()), meta.is_empty()))?;
+        if emptied {
+            self.entries.remove_if(&id, HashMap::is_empty);
+        }
+        value?.downcast().ok().map(|value| *value)
+    }
+
+    pub fn remove(&self, id: usize) {
+        self.entries.remove(&id);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_are_keyed_by_object_and_type() {
+        let table = MetaTable::default();
+        assert_eq!(table.set(1, 7u32), None);
+        assert_eq!(table.set(1, "tenant-a"), None);
+        assert_eq!(table.set(1, 8u32), Some(7));
+
+        assert_eq!(table.get::<u32>(1), Some(8));
+        assert_eq!(table.get::<&str>(1), Some("tenant-a"));
+        assert_eq!(table.get::<u32>(2), None);
+        assert_eq!(table.get::<u64>(1), None);
+    }
+
+    #[test]
+    fn taking_the_last_value_drops_the_entry() {
+        let table = MetaTable::default();
+        table.set(1, 7u32);
+        assert_eq!(table.take::<u64>(1), None);
+        assert_eq!(table.take::<u32>(1), Some(7));
+        assert_eq!(table.len(), 0);
+
+        table.set(2, 7u32);
+        table.remove(2);
+        assert_eq!(table.get::<u32>(2), None);
+    }
+}
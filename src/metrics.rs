@@ -1,8 +1,11 @@
 //! Metrics collection and export for object pools
 
+use crate::concurrent::Map;
+use crate::tuning::{LatencyHistogram, Log2Histogram};
 use std::collections::HashMap;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Metrics data for a pool
 ///
@@ -46,6 +49,65 @@ pub struct PoolMetrics {
     /// Objects permanently detached from the pool via `into_detached()`
     pub total_detached: usize,
 
+    /// Leases that elapsed while the object was still checked out
+    pub expired_leases: usize,
+
+    /// Acquisitions refused because the pool's limit group was full
+    pub group_limit_rejections: usize,
+
+    /// Factory calls in dynamic pools that returned an error
+    pub creation_failures: usize,
+
+    /// Object creations refused by the creation rate limit or failure backoff
+    pub creations_throttled: usize,
+
+    /// Panics contained in the validation function or hooks
+    pub hook_panics: usize,
+
+    /// Objects destroyed as broken via `discard()` instead of being returned
+    pub discarded_objects: usize,
+
+    /// Objects taken out of circulation by
+    /// [`retire`](crate::ObjectPool::retire)
+    pub retired_objects: usize,
+
+    /// Objects a dynamic pool served from its degraded-tier factory while
+    /// its circuit breaker was open
+    pub degraded_objects: usize,
+
+    /// Acquisitions refused because `max_waiters` others were already
+    /// waiting
+    pub waiter_rejections: usize,
+
+    /// Objects the pool owns, idle or checked out
+    pub total_objects: usize,
+
+    /// Objects a fixed pool has destroyed or had detached and cannot
+    /// replace; always 0 for dynamic pools
+    pub capacity_lost: usize,
+
+    /// Estimated bytes held by idle objects (0 unless memory accounting is
+    /// enabled with
+    /// [`with_size_estimator`](crate::PoolConfiguration::with_size_estimator))
+    pub idle_bytes: usize,
+
+    /// Estimated bytes held by checked-out objects, as measured when they
+    /// were handed out (0 unless memory accounting is enabled)
+    pub active_bytes: usize,
+
+    /// Whether the pool is paused for maintenance
+    pub paused: bool,
+
+    /// Per-caller breakdown, keyed by the label of the acquisition context
+    /// (empty unless enabled with
+    /// [`with_caller_metrics`](crate::PoolConfiguration::with_caller_metrics))
+    pub by_caller: HashMap<String, CallerMetrics>,
+
+    /// Searches of a [`QueryableObjectPool`](crate::QueryableObjectPool) or
+    /// [`DynamicQueryablePool`](crate::DynamicQueryablePool); all zero for
+    /// other pools
+    pub queries: QueryMetrics,
+
     /// Pool utilization ratio (0.0 to 1.0)
     pub utilization: f64,
     
@@ -65,18 +127,55 @@ impl PoolMetrics {
         metrics.insert("validation_failures".to_string(), self.validation_failures.to_string());
         metrics.insert("queue_push_failures".to_string(), self.queue_push_failures.to_string());
         metrics.insert("total_detached".to_string(), self.total_detached.to_string());
+        metrics.insert("expired_leases".to_string(), self.expired_leases.to_string());
+        metrics.insert("group_limit_rejections".to_string(), self.group_limit_rejections.to_string());
+        metrics.insert("creation_failures".to_string(), self.creation_failures.to_string());
+        metrics.insert("creations_throttled".to_string(), self.creations_throttled.to_string());
+        metrics.insert("hook_panics".to_string(), self.hook_panics.to_string());
+        metrics.insert("discarded_objects".to_string(), self.discarded_objects.to_string());
+        metrics.insert("retired_objects".to_string(), self.retired_objects.to_string());
+        metrics.insert("degraded_objects".to_string(), self.degraded_objects.to_string());
+        metrics.insert("waiter_rejections".to_string(), self.waiter_rejections.to_string());
+        metrics.insert("total_objects".to_string(), self.total_objects.to_string());
+        metrics.insert("capacity_lost".to_string(), self.capacity_lost.to_string());
+        metrics.insert("idle_bytes".to_string(), self.idle_bytes.to_string());
+        metrics.insert("active_bytes".to_string(), self.active_bytes.to_string());
+        metrics.insert("paused".to_string(), self.paused.to_string());
+        for (caller, stats) in &self.by_caller {
+            metrics.insert(format!("caller.{caller}.acquisitions"), stats.acquisitions.to_string());
+            metrics.insert(format!("caller.{caller}.timeouts"), stats.timeouts.to_string());
+            metrics.insert(format!("caller.{caller}.releases"), stats.releases.to_string());
+            metrics.insert(
+                format!("caller.{caller}.hold_seconds_total"),
+                format!("{:.6}", stats.total_hold_time.as_secs_f64()),
+            );
+        }
+        if self.queries.queries > 0 {
+            metrics.insert("queries".to_string(), self.queries.queries.to_string());
+            metrics.insert("query_no_match_events".to_string(), self.queries.no_match_events.to_string());
+            metrics.insert("query_match_rate".to_string(), format!("{:.2}", self.queries.match_rate));
+            metrics.insert("query_objects_scanned".to_string(), self.queries.objects_scanned.to_string());
+        }
         metrics.insert("utilization".to_string(), format!("{:.2}", self.utilization));
         metrics.insert("max_capacity".to_string(), self.max_capacity.to_string());
         metrics
     }
 }
 
+/// Name, help text and value of one per-caller Prometheus counter.
+type CallerSeries = (&'static str, &'static str, fn(&CallerMetrics) -> String);
+
 /// Metrics exporter for Prometheus format
 pub struct MetricsExporter;
 
 impl MetricsExporter {
     /// Export metrics in Prometheus exposition format
     ///
+    /// The output is deterministic: the same metrics, name and tags always
+    /// give the same text. Metric families come in a fixed order. Within a
+    /// series, labels are ordered `pool` first, then `tags` sorted by key,
+    /// then `caller`. Per-caller series are sorted by caller label.
+    ///
     /// # Examples
     ///
     /// ```
@@ -112,6 +211,26 @@ impl MetricsExporter {
         output.push_str("# HELP objectpool_utilization Pool utilization ratio\n");
         output.push_str("# TYPE objectpool_utilization gauge\n");
         output.push_str(&format!("objectpool_utilization{{{}}} {:.2}\n", labels, metrics.utilization));
+
+        output.push_str("# HELP objectpool_objects_total Objects owned by the pool, idle or checked out\n");
+        output.push_str("# TYPE objectpool_objects_total gauge\n");
+        output.push_str(&format!("objectpool_objects_total{{{}}} {}\n", labels, metrics.total_objects));
+
+        output.push_str("# HELP objectpool_capacity_lost Objects a fixed pool lost and cannot replace\n");
+        output.push_str("# TYPE objectpool_capacity_lost gauge\n");
+        output.push_str(&format!("objectpool_capacity_lost{{{}}} {}\n", labels, metrics.capacity_lost));
+
+        output.push_str("# HELP objectpool_memory_idle_bytes Estimated bytes held by idle objects\n");
+        output.push_str("# TYPE objectpool_memory_idle_bytes gauge\n");
+        output.push_str(&format!("objectpool_memory_idle_bytes{{{}}} {}\n", labels, metrics.idle_bytes));
+
+        output.push_str("# HELP objectpool_memory_active_bytes Estimated bytes held by checked-out objects\n");
+        output.push_str("# TYPE objectpool_memory_active_bytes gauge\n");
+        output.push_str(&format!("objectpool_memory_active_bytes{{{}}} {}\n", labels, metrics.active_bytes));
+
+        output.push_str("# HELP objectpool_paused Whether the pool is paused for maintenance\n");
+        output.push_str("# TYPE objectpool_paused gauge\n");
+        output.push_str(&format!("objectpool_paused{{{}}} {}\n", labels, u8::from(metrics.paused)));
         
         // Counter metrics
         output.push_str("# HELP objectpool_objects_retrieved_total Total objects retrieved\n");
@@ -138,6 +257,84 @@ impl MetricsExporter {
         output.push_str("# TYPE objectpool_objects_detached_total counter\n");
         output.push_str(&format!("objectpool_objects_detached_total{{{}}} {}\n", labels, metrics.total_detached));
 
+        output.push_str("# HELP objectpool_leases_expired_total Leases that elapsed while the object was checked out\n");
+        output.push_str("# TYPE objectpool_leases_expired_total counter\n");
+        output.push_str(&format!("objectpool_leases_expired_total{{{}}} {}\n", labels, metrics.expired_leases));
+
+        output.push_str("# HELP objectpool_group_limit_rejections_total Acquisitions refused by the shared limit group\n");
+        output.push_str("# TYPE objectpool_group_limit_rejections_total counter\n");
+        output.push_str(&format!("objectpool_group_limit_rejections_total{{{}}} {}\n", labels, metrics.group_limit_rejections));
+
+        output.push_str("# HELP objectpool_creation_failures_total Factory calls that returned an error\n");
+        output.push_str("# TYPE objectpool_creation_failures_total counter\n");
+        output.push_str(&format!("objectpool_creation_failures_total{{{}}} {}\n", labels, metrics.creation_failures));
+
+        output.push_str("# HELP objectpool_creations_throttled_total Object creations refused by the rate limit or failure backoff\n");
+        output.push_str("# TYPE objectpool_creations_throttled_total counter\n");
+        output.push_str(&format!("objectpool_creations_throttled_total{{{}}} {}\n", labels, metrics.creations_throttled));
+
+        output.push_str("# HELP objectpool_hook_panics_total Panics contained in the validation function or hooks\n");
+        output.push_str("# TYPE objectpool_hook_panics_total counter\n");
+        output.push_str(&format!("objectpool_hook_panics_total{{{}}} {}\n", labels, metrics.hook_panics));
+
+        output.push_str("# HELP objectpool_objects_discarded_total Objects destroyed as broken instead of being returned\n");
+        output.push_str("# TYPE objectpool_objects_discarded_total counter\n");
+        output.push_str(&format!("objectpool_objects_discarded_total{{{}}} {}\n", labels, metrics.discarded_objects));
+
+        output.push_str("# HELP objectpool_objects_retired_total Objects taken out of circulation on request\n");
+        output.push_str("# TYPE objectpool_objects_retired_total counter\n");
+        output.push_str(&format!("objectpool_objects_retired_total{{{}}} {}\n", labels, metrics.retired_objects));
+
+        output.push_str("# HELP objectpool_objects_degraded_total Objects served from the degraded-tier factory\n");
+        output.push_str("# TYPE objectpool_objects_degraded_total counter\n");
+        output.push_str(&format!("objectpool_objects_degraded_total{{{}}} {}\n", labels, metrics.degraded_objects));
+
+        output.push_str("# HELP objectpool_waiter_rejections_total Acquisitions refused because the wait queue was full\n");
+        output.push_str("# TYPE objectpool_waiter_rejections_total counter\n");
+        output.push_str(&format!("objectpool_waiter_rejections_total{{{}}} {}\n", labels, metrics.waiter_rejections));
+
+        if !metrics.by_caller.is_empty() {
+            let mut callers: Vec<_> = metrics.by_caller.iter().collect();
+            callers.sort_by(|a, b| a.0.cmp(b.0));
+            let series: [CallerSeries; 4] = [
+                ("objectpool_caller_acquisitions_total", "Objects acquired per caller", |m| m.acquisitions.to_string()),
+                ("objectpool_caller_timeouts_total", "Acquisitions that timed out per caller", |m| m.timeouts.to_string()),
+                ("objectpool_caller_releases_total", "Objects given back per caller", |m| m.releases.to_string()),
+                ("objectpool_caller_hold_seconds_total", "Total time objects were held per caller", |m| format!("{:.6}", m.total_hold_time.as_secs_f64())),
+            ];
+            for (name, help, value) in series {
+                output.push_str(&format!("# HELP {name} {help}\n"));
+                output.push_str(&format!("# TYPE {name} counter\n"));
+                for (caller, stats) in &callers {
+                    output.push_str(&format!("{name}{{{labels},caller=\"{caller}\"}} {}\n", value(stats)));
+                }
+            }
+        }
+
+        let queries = &metrics.queries;
+        if queries.queries > 0 {
+            output.push_str("# HELP objectpool_queries_total Searches of a queryable pool\n");
+            output.push_str("# TYPE objectpool_queries_total counter\n");
+            output.push_str(&format!("objectpool_queries_total{{{}}} {}\n", labels, queries.queries));
+
+            output.push_str("# HELP objectpool_query_no_match_total Searches that found no matching idle object\n");
+            output.push_str("# TYPE objectpool_query_no_match_total counter\n");
+            output.push_str(&format!("objectpool_query_no_match_total{{{}}} {}\n", labels, queries.no_match_events));
+
+            output.push_str("# HELP objectpool_query_match_rate Share of searches that found a match\n");
+            output.push_str("# TYPE objectpool_query_match_rate gauge\n");
+            output.push_str(&format!("objectpool_query_match_rate{{{}}} {:.2}\n", labels, queries.match_rate));
+
+            output.push_str("# HELP objectpool_query_scan_length Idle objects examined per search\n");
+            output.push_str("# TYPE objectpool_query_scan_length histogram\n");
+            for (le, count) in &queries.scan_length_buckets {
+                output.push_str(&format!("objectpool_query_scan_length_bucket{{{labels},le=\"{le}\"}} {count}\n"));
+            }
+            output.push_str(&format!("objectpool_query_scan_length_bucket{{{labels},le=\"+Inf\"}} {}\n", queries.queries));
+            output.push_str(&format!("objectpool_query_scan_length_sum{{{labels}}} {}\n", queries.objects_scanned));
+            output.push_str(&format!("objectpool_query_scan_length_count{{{labels}}} {}\n", queries.queries));
+        }
+
         output
     }
     
@@ -145,6 +342,8 @@ impl MetricsExporter {
         let mut labels = vec![format!("pool=\"{}\"", pool_name)];
         
         if let Some(tags) = tags {
+            let mut tags: Vec<_> = tags.iter().collect();
+            tags.sort_unstable_by_key(|(key, _)| *key);
             for (key, value) in tags {
                 labels.push(format!("{}=\"{}\"", key, value));
             }
@@ -154,29 +353,386 @@ impl MetricsExporter {
     }
 }
 
+/// Concatenate Prometheus expositions, keeping each metric family's
+/// `# HELP`/`# TYPE` header once and its samples together.
+pub(crate) fn merge_families(outputs: &[&str]) -> String {
+    let mut families: Vec<(&str, Vec<&str>, Vec<&str>)> = Vec::new();
+    for line in outputs.iter().flat_map(|output| output.lines()) {
+        let (name, header) = match line.strip_prefix("# ") {
+            Some(comment) => (comment.split(' ').nth(1).unwrap_or_default(), true),
+            None => (line.split(['{', ' ']).next().unwrap_or_default(), false),
+        };
+        let index = match families.iter().position(|(family, ..)| *family == name) {
+            Some(index) => index,
+            None => {
+                families.push((name, Vec::new(), Vec::new()));
+                families.len() - 1
+            }
+        };
+        let (_, headers, samples) = &mut families[index];
+        if !header {
+            samples.push(line);
+        } else if !headers.contains(&line) {
+            headers.push(line);
+        }
+    }
+
+    let mut merged = String::new();
+    for line in families.iter().flat_map(|(_, headers, samples)| headers.iter().chain(samples)) {
+        merged.push_str(line);
+        merged.push('\n');
+    }
+    merged
+}
+
+/// Acquisition statistics for one caller label
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{AcquireContext, ObjectPool, PoolConfiguration};
+///
+/// let pool = ObjectPool::new(vec![1, 2], PoolConfiguration::new().with_caller_metrics());
+///
+/// drop(pool.get_object_with(AcquireContext::new().with_label("reports")).unwrap());
+///
+/// let reports = &pool.get_metrics().by_caller["reports"];
+/// assert_eq!(reports.acquisitions, 1);
+/// assert_eq!(reports.releases, 1);
+/// assert!(reports.average_hold_time().is_some());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CallerMetrics {
+    /// Objects acquired
+    pub acquisitions: usize,
+
+    /// Acquisitions that gave up waiting
+    pub timeouts: usize,
+
+    /// Objects given back (returned or detached)
+    pub releases: usize,
+
+    /// Time between acquisition and release, summed over all releases
+    pub total_hold_time: Duration,
+}
+
+impl CallerMetrics {
+    /// Mean time an object was held, once at least one was given back
+    #[must_use]
+    pub fn average_hold_time(&self) -> Option<Duration> {
+        (self.releases > 0).then(|| self.total_hold_time / self.releases as u32)
+    }
+}
+
+/// How the searches of a queryable pool went
+///
+/// Part of [`PoolMetrics`]. A search that examines most of the idle objects
+/// before matching, or fails to match at all, costs a full scan; watch
+/// `scan_length_p99` against the pool size and `match_rate` for queries
+/// degrading. Scan lengths are kept in power-of-two buckets, so the
+/// percentiles are the largest length of their bucket.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{PoolConfiguration, QueryableObjectPool};
+///
+/// let pool = QueryableObjectPool::new(vec![1, 2, 3, 4], PoolConfiguration::default());
+/// drop(pool.get_object(|x| *x == 1).unwrap());
+/// assert!(pool.get_object(|x| *x == 9).is_err());
+///
+/// let queries = pool.query_metrics();
+/// assert_eq!((queries.queries, queries.matches, queries.no_match_events), (2, 1, 1));
+/// assert_eq!(queries.objects_scanned, 5);
+/// assert_eq!(queries.match_rate, 0.5);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueryMetrics {
+    /// Searches run
+    pub queries: usize,
+
+    /// Searches that found a matching idle object
+    pub matches: usize,
+
+    /// Searches that found no matching idle object
+    pub no_match_events: usize,
+
+    /// Idle objects examined, summed over all searches
+    pub objects_scanned: usize,
+
+    /// Share of searches that found a match (0.0 to 1.0)
+    pub match_rate: f64,
+
+    /// Median number of idle objects examined per search
+    pub scan_length_p50: usize,
+
+    /// 99th percentile of idle objects examined per search
+    pub scan_length_p99: usize,
+
+    /// Cumulative scan-length histogram: for each bucket, its largest scan
+    /// length and the searches examining at most that many objects
+    pub scan_length_buckets: Vec<(usize, usize)>,
+}
+
+impl QueryMetrics {
+    /// Mean number of idle objects examined per search, once there was one
+    #[must_use]
+    pub fn average_scan_length(&self) -> Option<f64> {
+        (self.queries > 0).then(|| self.objects_scanned as f64 / self.queries as f64)
+    }
+}
+
+/// Live counters behind a [`QueryMetrics`] snapshot.
+#[derive(Default)]
+pub(crate) struct QueryStats {
+    queries: AtomicUsize,
+    matches: AtomicUsize,
+    scanned: AtomicUsize,
+    scan_lengths: Log2Histogram,
+}
+
+impl QueryStats {
+    /// Count a search that examined `scanned` idle objects.
+    pub fn record(&self, scanned: usize, matched: bool) {
+        if cfg!(feature = "minimal") {
+            return;
+        }
+        self.queries.fetch_add(1, Ordering::Relaxed);
+        if matched {
+            self.matches.fetch_add(1, Ordering::Relaxed);
+        }
+        self.scanned.fetch_add(scanned, Ordering::Relaxed);
+        self.scan_lengths.record(scanned as u64);
+    }
+
+    fn snapshot(&self) -> QueryMetrics {
+        let queries = self.queries.load(Ordering::Relaxed);
+        let matches = self.matches.load(Ordering::Relaxed);
+        let largest = |bucket: usize| (1usize << bucket) - 1;
+        let counts = self.scan_lengths.counts();
+        let used = counts.iter().rposition(|&count| count > 0).map_or(0, |last| last + 1);
+        let mut cumulative = 0;
+        let scan_length_buckets = counts[..used]
+            .iter()
+            .enumerate()
+            .map(|(bucket, &count)| {
+                cumulative += count as usize;
+                (largest(bucket), cumulative)
+            })
+            .collect();
+        let percentile = |quantile| self.scan_lengths.percentile_bucket(quantile).map_or(0, largest);
+        QueryMetrics {
+            queries,
+            matches,
+            no_match_events: queries.saturating_sub(matches),
+            objects_scanned: self.scanned.load(Ordering::Relaxed),
+            match_rate: if queries > 0 { matches as f64 / queries as f64 } else { 0.0 },
+            scan_length_p50: percentile(0.5),
+            scan_length_p99: percentile(0.99),
+            scan_length_buckets,
+        }
+    }
+}
+
+/// Live counters behind a [`CallerMetrics`] snapshot.
+#[derive(Default)]
+pub(crate) struct CallerStats {
+    pub acquisitions: AtomicUsize,
+    pub timeouts: AtomicUsize,
+    releases: AtomicUsize,
+    hold_nanos: AtomicU64,
+}
+
+impl CallerStats {
+    fn snapshot(&self) -> CallerMetrics {
+        CallerMetrics {
+            acquisitions: self.acquisitions.load(Ordering::Relaxed),
+            timeouts: self.timeouts.load(Ordering::Relaxed),
+            releases: self.releases.load(Ordering::Relaxed),
+            total_hold_time: Duration::from_nanos(self.hold_nanos.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+/// Measures how long a guard is held; records the hold when dropped.
+pub(crate) struct HoldTimer {
+    stats: Arc<CallerStats>,
+    started: Instant,
+}
+
+impl HoldTimer {
+    pub fn start(stats: Arc<CallerStats>) -> Self {
+        Self {
+            stats,
+            started: Instant::now(),
+        }
+    }
+}
+
+impl Drop for HoldTimer {
+    fn drop(&mut self) {
+        let held = u64::try_from(self.started.elapsed().as_nanos()).unwrap_or(u64::MAX);
+        self.stats.hold_nanos.fetch_add(held, Ordering::Relaxed);
+        self.stats.releases.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Keeps one in every `every` occurrences, for
+/// [`with_sampling`](crate::PoolConfiguration::with_sampling).
+pub(crate) struct Sampler {
+    every: u64,
+    seen: AtomicU64,
+}
+
+impl Sampler {
+    pub fn new(every: u32) -> Self {
+        Self {
+            every: u64::from(every.max(1)),
+            seen: AtomicU64::new(0),
+        }
+    }
+
+    /// Whether this occurrence is one to keep.
+    pub fn sample(&self) -> bool {
+        self.every == 1 || self.seen.fetch_add(1, Ordering::Relaxed).is_multiple_of(self.every)
+    }
+}
+
+/// A pool counter. Under the `minimal` feature it is `NoopCounter`, so
+/// counting compiles away.
+#[cfg(not(feature = "minimal"))]
+pub(crate) type Counter = AtomicUsize;
+
+/// A pool counter, compiled away by the `minimal` feature.
+#[cfg(feature = "minimal")]
+pub(crate) type Counter = NoopCounter;
+
+/// Zero-sized stand-in for [`AtomicUsize`] with the same methods, all empty.
+/// Reads always return 0.
+#[cfg(feature = "minimal")]
+#[derive(Debug, Default)]
+pub(crate) struct NoopCounter;
+
+#[cfg(feature = "minimal")]
+impl NoopCounter {
+    #[inline(always)]
+    pub const fn new(_: usize) -> Self {
+        Self
+    }
+
+    #[inline(always)]
+    pub fn fetch_add(&self, _: usize, _: Ordering) -> usize {
+        0
+    }
+
+    #[inline(always)]
+    pub fn fetch_max(&self, _: usize, _: Ordering) -> usize {
+        0
+    }
+
+    #[inline(always)]
+    pub fn load(&self, _: Ordering) -> usize {
+        0
+    }
+}
+
 /// Internal metrics tracker
 pub(crate) struct MetricsTracker {
-    pub total_retrieved: Arc<AtomicUsize>,
-    pub total_returned: Arc<AtomicUsize>,
-    pub pool_empty_events: Arc<AtomicUsize>,
-    pub validation_failures: Arc<AtomicUsize>,
-    pub queue_push_failures: Arc<AtomicUsize>,
-    pub total_detached: Arc<AtomicUsize>,
+    pub total_retrieved: Arc<Counter>,
+    pub total_returned: Arc<Counter>,
+    pub pool_empty_events: Arc<Counter>,
+    pub validation_failures: Arc<Counter>,
+    pub queue_push_failures: Arc<Counter>,
+    pub total_detached: Arc<Counter>,
+    pub expired_leases: Arc<Counter>,
+    pub group_limit_rejections: Arc<Counter>,
+    pub creation_failures: Arc<Counter>,
+    pub creations_throttled: Arc<Counter>,
+    pub hook_panics: Arc<Counter>,
+    pub discarded_objects: Arc<Counter>,
+    pub waiter_rejections: Counter,
+    pub degraded_objects: Counter,
+    /// A real count even under `minimal`: the pool's expected size
+    /// depends on it.
+    pub retired_objects: AtomicUsize,
+    /// Time acquisitions took, for tuning advice.
+    pub wait_times: LatencyHistogram,
+    /// Time objects were held, for tuning advice.
+    pub hold_times: Arc<LatencyHistogram>,
+    /// Most objects checked out at once.
+    pub peak_active: Counter,
+    /// Searches of queryable pools.
+    pub queries: QueryStats,
+    /// Which acquisitions `wait_times` records.
+    pub wait_sampler: Sampler,
+    /// Which acquisitions `hold_times` records.
+    pub hold_sampler: Sampler,
+    /// Which evictions and empty-pool hits are published as events.
+    pub event_sampler: Sampler,
+    callers: Map<String, Arc<CallerStats>>,
 }
 
 impl MetricsTracker {
     pub fn new() -> Self {
         Self {
-            total_retrieved: Arc::new(AtomicUsize::new(0)),
-            total_returned: Arc::new(AtomicUsize::new(0)),
-            pool_empty_events: Arc::new(AtomicUsize::new(0)),
-            validation_failures: Arc::new(AtomicUsize::new(0)),
-            queue_push_failures: Arc::new(AtomicUsize::new(0)),
-            total_detached: Arc::new(AtomicUsize::new(0)),
+            total_retrieved: Arc::new(Counter::new(0)),
+            total_returned: Arc::new(Counter::new(0)),
+            pool_empty_events: Arc::new(Counter::new(0)),
+            validation_failures: Arc::new(Counter::new(0)),
+            queue_push_failures: Arc::new(Counter::new(0)),
+            total_detached: Arc::new(Counter::new(0)),
+            expired_leases: Arc::new(Counter::new(0)),
+            group_limit_rejections: Arc::new(Counter::new(0)),
+            creation_failures: Arc::new(Counter::new(0)),
+            creations_throttled: Arc::new(Counter::new(0)),
+            hook_panics: Arc::new(Counter::new(0)),
+            discarded_objects: Arc::new(Counter::new(0)),
+            waiter_rejections: Counter::new(0),
+            degraded_objects: Counter::new(0),
+            retired_objects: AtomicUsize::new(0),
+            wait_times: LatencyHistogram::default(),
+            hold_times: Arc::new(LatencyHistogram::default()),
+            peak_active: Counter::new(0),
+            queries: QueryStats::default(),
+            wait_sampler: Sampler::new(1),
+            hold_sampler: Sampler::new(1),
+            event_sampler: Sampler::new(1),
+            callers: Map::new(),
         }
     }
+
+    /// Sample histograms and events as configured by
+    /// [`with_sampling`](crate::PoolConfiguration::with_sampling).
+    pub fn sampled(mut self, every: u32) -> Self {
+        self.wait_sampler = Sampler::new(every);
+        self.hold_sampler = Sampler::new(every);
+        self.event_sampler = Sampler::new(every);
+        self
+    }
+
+    /// Whether this acquisition's wait goes into `wait_times`. Never under
+    /// the `minimal` feature.
+    #[inline]
+    pub fn sample_wait(&self) -> bool {
+        !cfg!(feature = "minimal") && self.wait_sampler.sample()
+    }
+
+    /// Whether this acquisition's hold time goes into `hold_times`. Never
+    /// under the `minimal` feature.
+    #[inline]
+    pub fn sample_hold(&self) -> bool {
+        !cfg!(feature = "minimal") && self.hold_sampler.sample()
+    }
     
-    pub fn get_metrics(&self, active: usize, available: usize, capacity: usize) -> PoolMetrics {
+    pub fn get_metrics(
+        &self,
+        active: usize,
+        available: usize,
+        capacity: usize,
+        population: usize,
+        capacity_lost: usize,
+        paused: bool,
+    ) -> PoolMetrics {
         let utilization = if capacity > 0 {
             active as f64 / capacity as f64
         } else {
@@ -192,12 +748,42 @@ impl MetricsTracker {
             validation_failures: self.validation_failures.load(Ordering::Relaxed),
             queue_push_failures: self.queue_push_failures.load(Ordering::Relaxed),
             total_detached: self.total_detached.load(Ordering::Relaxed),
+            expired_leases: self.expired_leases.load(Ordering::Relaxed),
+            group_limit_rejections: self.group_limit_rejections.load(Ordering::Relaxed),
+            creation_failures: self.creation_failures.load(Ordering::Relaxed),
+            creations_throttled: self.creations_throttled.load(Ordering::Relaxed),
+            hook_panics: self.hook_panics.load(Ordering::Relaxed),
+            discarded_objects: self.discarded_objects.load(Ordering::Relaxed),
+            retired_objects: self.retired_objects.load(Ordering::Relaxed),
+            waiter_rejections: self.waiter_rejections.load(Ordering::Relaxed),
+            degraded_objects: self.degraded_objects.load(Ordering::Relaxed),
+            total_objects: population,
+            capacity_lost,
+            idle_bytes: 0,
+            active_bytes: 0,
+            paused,
+            by_caller: self
+                .callers
+                .collect(|label, stats| (label.clone(), stats.snapshot()))
+                .into_iter()
+                .collect(),
+            queries: self.queries.snapshot(),
             utilization,
             max_capacity: capacity,
         }
     }
 }
 
+impl MetricsTracker {
+    /// Counters for caller `label`, created on first use.
+    pub fn caller(&self, label: &str) -> Arc<CallerStats> {
+        if let Some(stats) = self.callers.read(label, Arc::clone) {
+            return stats;
+        }
+        self.callers.update_or_default(label.to_string(), |stats| Arc::clone(stats))
+    }
+}
+
 impl Default for MetricsTracker {
     fn default() -> Self {
         Self::new()
@@ -0,0 +1,560 @@
+//! Blue/green migration between two pools
+
+use crate::errors::{PoolError, PoolResult};
+use crate::events::Reporter;
+use crate::metrics::PoolMetrics;
+use crate::observable::ObservablePool;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Mutex, PoisonError};
+use tokio::sync::broadcast;
+
+/// Which pool of a [`MigratingPool`] served an acquisition
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationSide {
+    /// The current pool, migrated away from
+    Blue,
+
+    /// The new pool, migrated to
+    Green,
+}
+
+/// How a [`MigratingPool`] shifts traffic to the green pool on its own
+///
+/// Traffic is judged in windows of green acquisitions. When a window
+/// completes with the green pool healthy, its share grows by one step. If
+/// more than the maximum error rate of a window's acquisitions fail, or one
+/// fails because the green pool's circuit breaker is open, all traffic goes
+/// back to blue and ramping stops until the share is set by hand.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::RampPolicy;
+///
+/// // +10% after every 500 green acquisitions, rolling back above 0.5% errors.
+/// let policy = RampPolicy::new(10, 500).with_max_error_rate(0.005);
+/// assert_eq!(policy.step_percent(), 10);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RampPolicy {
+    step: u32,
+    window: usize,
+    max_error_rate: f64,
+}
+
+impl RampPolicy {
+    /// Grow the green share by `step_percent` (at least 1) after each
+    /// `window` green acquisitions (at least 1), tolerating 1% errors
+    #[must_use]
+    pub fn new(step_percent: u32, window: usize) -> Self {
+        Self {
+            step: step_percent.clamp(1, 100),
+            window: window.max(1),
+            max_error_rate: 0.01,
+        }
+    }
+
+    /// Roll back when more than `rate` (0.0 to 1.0) of a window's green
+    /// acquisitions fail
+    #[must_use]
+    pub fn with_max_error_rate(mut self, rate: f64) -> Self {
+        self.max_error_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Percentage points added per healthy window
+    #[must_use]
+    pub fn step_percent(&self) -> u32 {
+        self.step
+    }
+
+    /// Green acquisitions per window
+    #[must_use]
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Share of failed acquisitions a window tolerates
+    #[must_use]
+    pub fn max_error_rate(&self) -> f64 {
+        self.max_error_rate
+    }
+}
+
+/// Why a [`MigratingPool`] changed the green share
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ShiftReason {
+    /// A window of green acquisitions completed healthily
+    Ramp,
+
+    /// Green acquisitions failed above the tolerated rate
+    ErrorRate,
+
+    /// A green acquisition found the green pool's circuit breaker open
+    CircuitBreakerOpen,
+
+    /// [`MigratingPool::set_green_percent`], [`MigratingPool::promote`] or
+    /// [`MigratingPool::rollback`] was called
+    Manual,
+}
+
+/// A change of the green pool's share of acquisitions
+///
+/// Published on the channel returned by [`MigratingPool::shifts`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TrafficShift {
+    /// Green percentage before the change
+    pub from: u32,
+
+    /// Green percentage from now on
+    pub to: u32,
+
+    /// What triggered the change
+    pub reason: ShiftReason,
+}
+
+/// A running pool ("blue") and its replacement ("green"), for rolling out a
+/// new backend or resource configuration gradually
+///
+/// [`acquire`](Self::acquire) sends [`green_percent`](Self::green_percent)
+/// of acquisitions to the green pool, spread evenly, and the rest to blue.
+/// The share starts at 0 and is moved by hand with
+/// [`set_green_percent`](Self::set_green_percent),
+/// [`promote`](Self::promote) and [`rollback`](Self::rollback), or
+/// automatically with a [`RampPolicy`], which ramps up while the green pool
+/// stays healthy and rolls back when its acquisitions fail.
+///
+/// Objects already checked out stay with the pool they came from. Each
+/// side's acquisitions and errors are counted in
+/// [`get_metrics`](Self::get_metrics), and every change of share is
+/// published on [`shifts`](Self::shifts).
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{MigratingPool, ObjectPool, PoolConfiguration, RampPolicy};
+///
+/// let rollout = MigratingPool::new(
+///     ObjectPool::new(vec!["old"; 4], PoolConfiguration::default()),
+///     ObjectPool::new(vec!["new"; 4], PoolConfiguration::default()),
+/// )
+/// .with_ramp(RampPolicy::new(50, 1));
+/// rollout.set_green_percent(50);
+///
+/// let first = rollout.acquire(|pool| pool.get_object()).unwrap();
+/// let second = rollout.acquire(|pool| pool.get_object()).unwrap();
+/// assert_eq!((*first, *second), ("old", "new"));
+///
+/// // One healthy green acquisition completes a window: all traffic is green.
+/// assert_eq!(rollout.green_percent(), 100);
+/// assert_eq!(rollout.get_metrics().green.acquisitions, 1);
+/// ```
+pub struct MigratingPool<P> {
+    blue: P,
+    green: P,
+    green_percent: AtomicU32,
+    sequence: AtomicU64,
+    ramp: Option<RampPolicy>,
+    rolled_back: AtomicBool,
+    window: Mutex<Window>,
+    counters: [SideCounters; 2],
+    shifts: Reporter<TrafficShift>,
+}
+
+/// Green acquisitions in the current ramp window.
+#[derive(Default)]
+struct Window {
+    acquisitions: usize,
+    errors: usize,
+}
+
+#[derive(Default)]
+struct SideCounters {
+    acquisitions: AtomicUsize,
+    errors: AtomicUsize,
+}
+
+impl<P: ObservablePool> MigratingPool<P> {
+    /// Migrate from `blue` to `green`; all acquisitions go to blue until the
+    /// green share is raised
+    pub fn new(blue: P, green: P) -> Self {
+        Self {
+            blue,
+            green,
+            green_percent: AtomicU32::new(0),
+            sequence: AtomicU64::new(0),
+            ramp: None,
+            rolled_back: AtomicBool::new(false),
+            window: Mutex::new(Window::default()),
+            counters: Default::default(),
+            shifts: Reporter::new(),
+        }
+    }
+
+    /// Ramp the green share up, and roll it back, according to `policy`
+    ///
+    /// Ramping starts from the current share; with a share of 0 the green
+    /// pool sees no traffic, so raise it to the first step by hand.
+    #[must_use]
+    pub fn with_ramp(mut self, policy: RampPolicy) -> Self {
+        self.ramp = Some(policy);
+        self
+    }
+
+    /// Run `acquire` against the pool chosen for this acquisition, counting
+    /// its outcome for that side
+    pub fn acquire<R>(&self, acquire: impl FnOnce(&P) -> PoolResult<R>) -> PoolResult<R> {
+        let side = self.route();
+        let result = acquire(self.get(side));
+        self.record(side, result.as_ref().err());
+        result
+    }
+
+    /// Run `acquire` against the pool chosen for this acquisition, as
+    /// [`acquire`](Self::acquire) does
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{MigratingPool, ObjectPool, PoolConfiguration};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let rollout = MigratingPool::new(
+    ///     ObjectPool::new(vec![1], PoolConfiguration::default()),
+    ///     ObjectPool::new(vec![2], PoolConfiguration::default()),
+    /// );
+    /// rollout.promote();
+    /// let obj = rollout.acquire_async(|pool| pool.get_object_async()).await.unwrap();
+    /// assert_eq!(*obj, 2);
+    /// # }
+    /// ```
+    pub async fn acquire_async<'a, R, F>(&'a self, acquire: impl FnOnce(&'a P) -> F) -> PoolResult<R>
+    where
+        F: Future<Output = PoolResult<R>>,
+    {
+        let side = self.route();
+        let result = acquire(self.get(side)).await;
+        self.record(side, result.as_ref().err());
+        result
+    }
+
+    /// Percentage of acquisitions going to the green pool
+    #[must_use]
+    pub fn green_percent(&self) -> u32 {
+        self.green_percent.load(Ordering::Acquire)
+    }
+
+    /// Send `percent` (at most 100) of acquisitions to the green pool,
+    /// resuming automatic ramping after a rollback
+    pub fn set_green_percent(&self, percent: u32) {
+        self.rolled_back.store(false, Ordering::Release);
+        self.reset_window();
+        self.shift_to(percent.min(100), ShiftReason::Manual);
+    }
+
+    /// Send every acquisition to the green pool
+    pub fn promote(&self) {
+        self.set_green_percent(100);
+    }
+
+    /// Send every acquisition back to the blue pool and stop automatic
+    /// ramping until the share is set again
+    pub fn rollback(&self) {
+        self.roll_back(ShiftReason::Manual);
+    }
+
+    /// Whether the migration was rolled back and has not been resumed
+    #[must_use]
+    pub fn is_rolled_back(&self) -> bool {
+        self.rolled_back.load(Ordering::Acquire)
+    }
+
+    /// The pool on `side`
+    #[must_use]
+    pub fn get(&self, side: MigrationSide) -> &P {
+        match side {
+            MigrationSide::Blue => &self.blue,
+            MigrationSide::Green => &self.green,
+        }
+    }
+
+    /// The ramp policy, if ramping is automatic
+    #[must_use]
+    pub fn ramp(&self) -> Option<&RampPolicy> {
+        self.ramp.as_ref()
+    }
+
+    /// Subscribe to changes of the green share
+    #[must_use]
+    pub fn shifts(&self) -> broadcast::Receiver<TrafficShift> {
+        self.shifts.subscribe()
+    }
+
+    /// Both pools' metrics with each side's acquisitions and errors
+    #[must_use]
+    pub fn get_metrics(&self) -> MigrationMetrics {
+        let side = |side: MigrationSide| {
+            let counters = &self.counters[side_index(side)];
+            MigrationSideMetrics {
+                pool: self.get(side).get_metrics(),
+                acquisitions: counters.acquisitions.load(Ordering::Relaxed),
+                errors: counters.errors.load(Ordering::Relaxed),
+            }
+        };
+        MigrationMetrics {
+            blue: side(MigrationSide::Blue),
+            green: side(MigrationSide::Green),
+            green_percent: self.green_percent(),
+            rolled_back: self.is_rolled_back(),
+        }
+    }
+
+    /// Both pools' metrics as one key/value map, keyed `blue.<metric>` and
+    /// `green.<metric>`, plus each side's `acquisitions` and `errors` and
+    /// `green_percent`
+    #[must_use]
+    pub fn export_metrics(&self) -> HashMap<String, String> {
+        let mut map = HashMap::new();
+        for (name, side) in [("blue", MigrationSide::Blue), ("green", MigrationSide::Green)] {
+            for (key, value) in self.get(side).export_metrics() {
+                map.insert(format!("{name}.{key}"), value);
+            }
+            let counters = &self.counters[side_index(side)];
+            map.insert(
+                format!("{name}.acquisitions"),
+                counters.acquisitions.load(Ordering::Relaxed).to_string(),
+            );
+            map.insert(format!("{name}.errors"), counters.errors.load(Ordering::Relaxed).to_string());
+        }
+        map.insert("green_percent".to_string(), self.green_percent().to_string());
+        map
+    }
+
+    /// Pick the side of the next acquisition, spreading green ones evenly.
+    fn route(&self) -> MigrationSide {
+        let percent = u64::from(self.green_percent());
+        let n = self.sequence.fetch_add(1, Ordering::Relaxed) % 100;
+        // Green whenever n crosses a multiple of 100 / percent.
+        if (n + 1) * percent / 100 > n * percent / 100 {
+            MigrationSide::Green
+        } else {
+            MigrationSide::Blue
+        }
+    }
+
+    fn record(&self, side: MigrationSide, error: Option<&PoolError>) {
+        let counters = &self.counters[side_index(side)];
+        counters.acquisitions.fetch_add(1, Ordering::Relaxed);
+        if error.is_some() {
+            counters.errors.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let Some(policy) = self.ramp else { return };
+        if side == MigrationSide::Blue || self.is_rolled_back() {
+            return;
+        }
+        if matches!(error, Some(PoolError::CircuitBreakerOpen)) {
+            self.roll_back(ShiftReason::CircuitBreakerOpen);
+            return;
+        }
+
+        let mut window = self.window.lock().unwrap_or_else(PoisonError::into_inner);
+        window.acquisitions += 1;
+        window.errors += usize::from(error.is_some());
+        if window.errors as f64 > policy.max_error_rate * policy.window as f64 {
+            *window = Window::default();
+            drop(window);
+            self.roll_back(ShiftReason::ErrorRate);
+        } else if window.acquisitions >= policy.window {
+            *window = Window::default();
+            drop(window);
+            if self.green.get_health_status().is_healthy {
+                let percent = self.green_percent();
+                self.shift_to((percent + policy.step).min(100), ShiftReason::Ramp);
+            }
+        }
+    }
+
+    fn roll_back(&self, reason: ShiftReason) {
+        if !self.rolled_back.swap(true, Ordering::AcqRel) {
+            self.reset_window();
+            self.shift_to(0, reason);
+        }
+    }
+
+    fn reset_window(&self) {
+        *self.window.lock().unwrap_or_else(PoisonError::into_inner) = Window::default();
+    }
+
+    fn shift_to(&self, to: u32, reason: ShiftReason) {
+        let from = self.green_percent.swap(to, Ordering::AcqRel);
+        if from != to {
+            self.shifts.report(TrafficShift { from, to, reason });
+        }
+    }
+}
+
+fn side_index(side: MigrationSide) -> usize {
+    match side {
+        MigrationSide::Blue => 0,
+        MigrationSide::Green => 1,
+    }
+}
+
+/// Metrics of a [`MigratingPool`]
+#[derive(Debug, Clone)]
+pub struct MigrationMetrics {
+    /// The blue pool and its acquisitions
+    pub blue: MigrationSideMetrics,
+
+    /// The green pool and its acquisitions
+    pub green: MigrationSideMetrics,
+
+    /// Percentage of acquisitions going to the green pool
+    pub green_percent: u32,
+
+    /// Whether the migration was rolled back and has not been resumed
+    pub rolled_back: bool,
+}
+
+/// Metrics of one side of a [`MigratingPool`]
+#[derive(Debug, Clone)]
+pub struct MigrationSideMetrics {
+    /// The side's pool metrics
+    pub pool: PoolMetrics,
+
+    /// Acquisitions routed to this side
+    pub acquisitions: usize,
+
+    /// Acquisitions routed to this side that failed
+    pub errors: usize,
+}
+
+impl MigrationSideMetrics {
+    /// Share of this side's acquisitions that failed, 0.0 before the first
+    #[must_use]
+    pub fn error_rate(&self) -> f64 {
+        if self.acquisitions == 0 {
+            0.0
+        } else {
+            self.errors as f64 / self.acquisitions as f64
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ObjectPool, PoolConfiguration};
+    use std::time::Duration;
+
+    fn rollout(objects: usize) -> MigratingPool<ObjectPool<&'static str>> {
+        let config = || {
+            PoolConfiguration::new()
+                .with_max_pool_size(objects)
+                .with_circuit_breaker(1, Duration::from_secs(60))
+        };
+        MigratingPool::new(
+            ObjectPool::new(vec!["blue"; objects], config()),
+            ObjectPool::new(vec!["green"; objects], config()),
+        )
+    }
+
+    fn sides(pool: &MigratingPool<ObjectPool<&'static str>>, n: usize) -> usize {
+        (0..n)
+            .filter(|_| *pool.acquire(|pool| pool.get_object()).unwrap() == "green")
+            .count()
+    }
+
+    #[test]
+    fn routes_the_configured_share_evenly() {
+        let pool = rollout(1);
+        assert_eq!(sides(&pool, 100), 0);
+
+        pool.set_green_percent(25);
+        let routed: Vec<_> = (0..8).map(|_| pool.route()).collect();
+        let green: Vec<_> = (0..8).filter(|&n| routed[n] == MigrationSide::Green).collect();
+        assert_eq!(green, [3, 7], "green acquisitions are spread out");
+        assert_eq!(sides(&pool, 92), 23);
+
+        pool.set_green_percent(250);
+        assert_eq!(pool.green_percent(), 100);
+        assert_eq!(sides(&pool, 10), 10);
+    }
+
+    #[test]
+    fn ramps_while_healthy_and_rolls_back_on_errors() {
+        let pool = rollout(4).with_ramp(RampPolicy::new(20, 2).with_max_error_rate(0.0));
+        let mut shifts = pool.shifts();
+        pool.set_green_percent(20);
+        assert_eq!(shifts.try_recv().unwrap().reason, ShiftReason::Manual);
+
+        for _ in 0..10 {
+            pool.acquire(|pool| pool.get_object()).unwrap();
+        }
+        assert_eq!(pool.green_percent(), 40);
+        assert_eq!(shifts.try_recv().unwrap(), TrafficShift { from: 20, to: 40, reason: ShiftReason::Ramp });
+
+        // Green acquisitions start failing.
+        pool.get(MigrationSide::Green).shutdown();
+        while !pool.is_rolled_back() {
+            let _ = pool.acquire(|pool| pool.get_object());
+        }
+        assert_eq!(pool.green_percent(), 0);
+        assert_eq!(shifts.try_recv().unwrap().reason, ShiftReason::ErrorRate);
+
+        let metrics = pool.get_metrics();
+        assert!(metrics.rolled_back);
+        assert_eq!(metrics.green.errors, 1);
+        assert_eq!(metrics.blue.error_rate(), 0.0);
+        assert_eq!(sides(&pool, 20), 0, "ramping stays stopped");
+    }
+
+    #[test]
+    fn open_green_breaker_rolls_back_at_once() {
+        let pool = rollout(1).with_ramp(RampPolicy::new(10, 1000).with_max_error_rate(0.5));
+        let mut shifts = pool.shifts();
+        pool.promote();
+        pool.get(MigrationSide::Green).report_failure();
+
+        assert!(pool.acquire(|pool| pool.get_object()).is_err());
+        assert_eq!(shifts.try_recv().unwrap().reason, ShiftReason::Manual);
+        assert_eq!(
+            shifts.try_recv().unwrap(),
+            TrafficShift { from: 100, to: 0, reason: ShiftReason::CircuitBreakerOpen }
+        );
+
+        pool.set_green_percent(10);
+        assert!(!pool.is_rolled_back());
+    }
+
+    #[test]
+    fn unhealthy_green_pool_does_not_ramp() {
+        let pool = rollout(1).with_ramp(RampPolicy::new(50, 1));
+        pool.set_green_percent(50);
+        // Fully checked out, hence unhealthy.
+        let _held = pool.get(MigrationSide::Green).get_object().unwrap();
+
+        for _ in 0..4 {
+            pool.acquire(|pool| Ok(pool.capacity())).unwrap();
+        }
+        assert_eq!(pool.green_percent(), 50);
+        assert_eq!(pool.get_metrics().green.acquisitions, 2);
+    }
+
+    #[test]
+    fn exports_per_side_counters() {
+        let pool = rollout(1);
+        pool.acquire(|pool| pool.get_object()).unwrap();
+
+        let map = pool.export_metrics();
+        assert_eq!(map["blue.acquisitions"], "1");
+        assert_eq!(map["green.errors"], "0");
+        assert_eq!(map["green.total_retrieved"], "0");
+        assert_eq!(map["green_percent"], "0");
+    }
+}
@@ -0,0 +1,133 @@
+//! Common observation surface shared by all pool types
+
+use crate::health::HealthStatus;
+use crate::metrics::PoolMetrics;
+use crate::pool::{DynamicObjectPool, DynamicQueryablePool, ObjectPool, QueryableObjectPool};
+
+use std::collections::HashMap;
+
+/// Read-only introspection implemented by every pool type
+///
+/// Each pool also exposes these as inherent methods; the trait exists so
+/// monitoring code can be written once for [`ObjectPool`],
+/// [`QueryableObjectPool`] and [`DynamicObjectPool`] alike.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{DynamicObjectPool, ObjectPool, ObservablePool, PoolConfiguration};
+///
+/// fn report(name: &str, pool: &dyn ObservablePool) -> String {
+///     format!("{name}: {}/{} in use", pool.active_count(), pool.capacity())
+/// }
+///
+/// let fixed = ObjectPool::new(vec![1, 2], PoolConfiguration::new().with_max_pool_size(2));
+/// let dynamic = DynamicObjectPool::new(|| 0, PoolConfiguration::new().with_max_pool_size(4));
+/// let _obj = dynamic.get_object().unwrap();
+///
+/// assert_eq!(report("fixed", &fixed), "fixed: 0/2 in use");
+/// assert_eq!(report("dynamic", &dynamic), "dynamic: 1/4 in use");
+/// ```
+pub trait ObservablePool {
+    /// Number of objects waiting in the pool
+    fn available_count(&self) -> usize;
+
+    /// Number of objects currently checked out
+    fn active_count(&self) -> usize;
+
+    /// Maximum number of objects the pool can hold
+    fn capacity(&self) -> usize;
+
+    /// Snapshot of the pool's metrics
+    fn get_metrics(&self) -> PoolMetrics;
+
+    /// Current health of the pool
+    fn get_health_status(&self) -> HealthStatus;
+
+    /// Metrics as a key/value map
+    fn export_metrics(&self) -> HashMap<String, String>;
+
+    /// Metrics in Prometheus text exposition format
+    fn export_metrics_prometheus(
+        &self,
+        pool_name: &str,
+        tags: Option<&HashMap<String, String>>,
+    ) -> String;
+}
+
+macro_rules! impl_observable_pool {
+    ($pool:ident $(<$key:ident>)?) => {
+        impl<$($key,)? T: Send + Sync + 'static> ObservablePool for $pool<$($key,)? T> {
+            fn available_count(&self) -> usize {
+                $pool::available_count(self)
+            }
+
+            fn active_count(&self) -> usize {
+                $pool::active_count(self)
+            }
+
+            fn capacity(&self) -> usize {
+                $pool::capacity(self)
+            }
+
+            fn get_metrics(&self) -> PoolMetrics {
+                $pool::get_metrics(self)
+            }
+
+            fn get_health_status(&self) -> HealthStatus {
+                $pool::get_health_status(self)
+            }
+
+            fn export_metrics(&self) -> HashMap<String, String> {
+                $pool::export_metrics(self)
+            }
+
+            fn export_metrics_prometheus(
+                &self,
+                pool_name: &str,
+                tags: Option<&HashMap<String, String>>,
+            ) -> String {
+                $pool::export_metrics_prometheus(self, pool_name, tags)
+            }
+        }
+    };
+}
+
+impl_observable_pool!(ObjectPool);
+impl_observable_pool!(QueryableObjectPool);
+impl_observable_pool!(DynamicObjectPool);
+impl_observable_pool!(DynamicQueryablePool<K>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PoolConfiguration;
+
+    fn snapshot(pool: &dyn ObservablePool) -> (usize, usize, usize, usize) {
+        (
+            pool.available_count(),
+            pool.active_count(),
+            pool.capacity(),
+            pool.get_metrics().total_retrieved,
+        )
+    }
+
+    #[test]
+    fn all_pool_types_report_the_same_surface() {
+        let config = || PoolConfiguration::new().with_max_pool_size(2);
+        let fixed = ObjectPool::new(vec![1, 2], config());
+        let queryable = QueryableObjectPool::new(vec![1, 2], config());
+        let dynamic = DynamicObjectPool::with_initial(|| 0, vec![1, 2], config());
+
+        let _a = fixed.get_object().unwrap();
+        let _b = queryable.get_object(|x| *x == 1).unwrap();
+        let _c = dynamic.get_object().unwrap();
+
+        for pool in [&fixed as &dyn ObservablePool, &queryable, &dynamic] {
+            assert_eq!(snapshot(pool), (1, 1, 2, 1));
+            assert!(pool.get_health_status().is_healthy);
+            assert!(pool.export_metrics().contains_key("total_retrieved"));
+            assert!(pool.export_metrics_prometheus("p", None).contains("objectpool_"));
+        }
+    }
+}
@@ -0,0 +1,274 @@
+//! Observers driven by a pool on a fixed cadence
+
+use crate::events::PoolEvent;
+use crate::health::HealthStatus;
+use crate::metrics::PoolMetrics;
+use crate::observable::ObservablePool;
+use crate::pool::{DynamicObjectPool, DynamicQueryablePool, ObjectPool, QueryableObjectPool};
+use crate::registry::PoolState;
+
+use std::sync::{Arc, Condvar, Mutex, PoisonError, Weak};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use tokio::sync::broadcast::{self, error::TryRecvError};
+
+/// Receives a pool's metrics, health changes and events, for bridging to a
+/// monitoring system
+///
+/// Implement the callbacks of interest and register the observer with
+/// [`ObjectPool::observe`] (or the method of the same name on the other
+/// pool types). Every `every`, on a thread of its own, the pool hands the
+/// observer the [`PoolEvent`]s published since the last round, then a
+/// metrics snapshot, then — if the pool's [`PoolState`] differs from the
+/// last round — its health. An observer falling more than 256 events
+/// behind skips ahead, as other event receivers do.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{HealthStatus, ObjectPool, PoolConfiguration, PoolMetrics, PoolObserver, PoolState};
+/// use std::sync::{Arc, Mutex};
+/// use std::time::Duration;
+///
+/// #[derive(Default)]
+/// struct Gauges {
+///     active: Mutex<usize>,
+///     state: Mutex<Option<PoolState>>,
+/// }
+///
+/// impl PoolObserver for Gauges {
+///     fn on_metrics_snapshot(&self, metrics: &PoolMetrics) {
+///         *self.active.lock().unwrap() = metrics.active_objects;
+///     }
+///
+///     fn on_health_change(&self, _previous: PoolState, health: &HealthStatus) {
+///         *self.state.lock().unwrap() = Some(PoolState::of(health));
+///     }
+/// }
+///
+/// let pool = Arc::new(ObjectPool::new(vec![1, 2], PoolConfiguration::default()));
+/// let gauges = Arc::new(Gauges::default());
+/// // Keep a handle to the observer by registering it in an `Arc`.
+/// let _observing = pool.observe(Arc::clone(&gauges), Duration::from_millis(10));
+///
+/// let _obj = pool.get_object().unwrap();
+/// pool.pause();
+/// std::thread::sleep(Duration::from_millis(100));
+/// assert_eq!(*gauges.active.lock().unwrap(), 1);
+/// assert_eq!(*gauges.state.lock().unwrap(), Some(PoolState::Unhealthy));
+/// ```
+pub trait PoolObserver: Send + Sync + 'static {
+    /// Called every round with the pool's current metrics
+    fn on_metrics_snapshot(&self, metrics: &PoolMetrics) {
+        let _ = metrics;
+    }
+
+    /// Called when the pool's [`PoolState`] changed since the last round;
+    /// the pool is taken to be healthy before the first
+    fn on_health_change(&self, previous: PoolState, health: &HealthStatus) {
+        let _ = (previous, health);
+    }
+
+    /// Called with each event the pool published since the last round
+    fn on_event(&self, event: &PoolEvent) {
+        let _ = event;
+    }
+}
+
+impl<O: PoolObserver + ?Sized> PoolObserver for Arc<O> {
+    fn on_metrics_snapshot(&self, metrics: &PoolMetrics) {
+        (**self).on_metrics_snapshot(metrics);
+    }
+
+    fn on_health_change(&self, previous: PoolState, health: &HealthStatus) {
+        (**self).on_health_change(previous, health);
+    }
+
+    fn on_event(&self, event: &PoolEvent) {
+        (**self).on_event(event);
+    }
+}
+
+/// What the observer thread needs from a pool.
+pub(crate) trait Watched: ObservablePool + Send + Sync {
+    fn events(&self) -> broadcast::Receiver<PoolEvent>;
+}
+
+macro_rules! impl_watched {
+    ($pool:ident $(<$key:ident>)?) => {
+        impl<$($key: Send + Sync,)? T: Send + Sync + 'static> Watched for $pool<$($key,)? T> {
+            fn events(&self) -> broadcast::Receiver<PoolEvent> {
+                $pool::events(self)
+            }
+        }
+    };
+}
+
+impl_watched!(ObjectPool);
+impl_watched!(QueryableObjectPool);
+impl_watched!(DynamicObjectPool);
+impl_watched!(DynamicQueryablePool<K>);
+
+/// Keeps a [`PoolObserver`] registered
+///
+/// Returned by [`ObjectPool::observe`]. Dropping the handle stops the
+/// observer, waiting for a round in progress to finish; so does dropping
+/// the pool.
+#[must_use = "dropping the handle stops the observer"]
+pub struct ObserverHandle {
+    stopped: Arc<(Mutex<bool>, Condvar)>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl ObserverHandle {
+    /// Drive `observer` from `pool` every `every` until stopped or the pool
+    /// is dropped.
+    pub(crate) fn spawn(pool: Weak<dyn Watched>, observer: impl PoolObserver, every: Duration) -> Self {
+        assert!(!every.is_zero(), "observer cadence must be non-zero");
+        let events = pool.upgrade().map(|pool| pool.events());
+        let stopped = Arc::new((Mutex::new(false), Condvar::new()));
+        let signal = Arc::clone(&stopped);
+        let thread = thread::Builder::new()
+            .name("objectpool-observer".into())
+            .spawn(move || {
+                let Some(mut events) = events else {
+                    return;
+                };
+                let mut state = PoolState::Healthy;
+                while !wait_stopped(&signal, every) {
+                    let Some(pool) = pool.upgrade() else {
+                        return;
+                    };
+                    state = observe_round(&*pool, &observer, &mut events, state);
+                }
+            })
+            .expect("failed to spawn the pool observer");
+        Self { stopped, thread: Some(thread) }
+    }
+
+    /// Stop the observer; the same as dropping the handle
+    pub fn stop(self) {}
+}
+
+impl Drop for ObserverHandle {
+    fn drop(&mut self) {
+        let (stopped, wake) = &*self.stopped;
+        *stopped.lock().unwrap_or_else(PoisonError::into_inner) = true;
+        wake.notify_all();
+        if let Some(thread) = self.thread.take() {
+            // A panicking observer has already stopped.
+            let _ = thread.join();
+        }
+    }
+}
+
+impl std::fmt::Debug for ObserverHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ObserverHandle").finish_non_exhaustive()
+    }
+}
+
+/// Sleep for `every` or until stopped; whether stopped.
+fn wait_stopped(signal: &(Mutex<bool>, Condvar), every: Duration) -> bool {
+    let (stopped, wake) = signal;
+    let stopped = stopped.lock().unwrap_or_else(PoisonError::into_inner);
+    let (stopped, _) = wake
+        .wait_timeout_while(stopped, every, |stopped| !*stopped)
+        .unwrap_or_else(PoisonError::into_inner);
+    *stopped
+}
+
+/// Hand `observer` one round of events, metrics and health; returns the
+/// pool's state for the next round.
+fn observe_round(
+    pool: &dyn Watched,
+    observer: &impl PoolObserver,
+    events: &mut broadcast::Receiver<PoolEvent>,
+    previous: PoolState,
+) -> PoolState {
+    loop {
+        match events.try_recv() {
+            Ok(event) => observer.on_event(&event),
+            Err(TryRecvError::Lagged(_)) => {}
+            Err(TryRecvError::Empty | TryRecvError::Closed) => break,
+        }
+    }
+    observer.on_metrics_snapshot(&pool.get_metrics());
+    let health = pool.get_health_status();
+    let state = PoolState::of(&health);
+    if state != previous {
+        observer.on_health_change(previous, &health);
+    }
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PoolConfiguration;
+
+    #[derive(Default)]
+    struct Recorder {
+        snapshots: Mutex<Vec<usize>>,
+        changes: Mutex<Vec<(PoolState, PoolState)>>,
+        events: Mutex<Vec<PoolEvent>>,
+    }
+
+    impl PoolObserver for Recorder {
+        fn on_metrics_snapshot(&self, metrics: &PoolMetrics) {
+            self.snapshots.lock().unwrap().push(metrics.active_objects);
+        }
+
+        fn on_health_change(&self, previous: PoolState, health: &HealthStatus) {
+            self.changes.lock().unwrap().push((previous, PoolState::of(health)));
+        }
+
+        fn on_event(&self, event: &PoolEvent) {
+            self.events.lock().unwrap().push(event.clone());
+        }
+    }
+
+    fn settle() {
+        thread::sleep(Duration::from_millis(60));
+    }
+
+    #[test]
+    fn reports_events_metrics_and_state_changes() {
+        let pool = Arc::new(ObjectPool::new(vec![1, 2, 3, 4], PoolConfiguration::default()));
+        let recorder = Arc::new(Recorder::default());
+        let handle = pool.observe(Arc::clone(&recorder), Duration::from_millis(5));
+
+        let obj = pool.get_object().unwrap();
+        let rest: Vec<_> = (0..3).map(|_| pool.get_object().unwrap()).collect();
+        assert!(pool.try_get_object().unwrap().is_none());
+        drop(rest);
+        settle();
+        assert_eq!(*recorder.events.lock().unwrap(), [PoolEvent::Empty]);
+        assert_eq!(recorder.snapshots.lock().unwrap().last(), Some(&1));
+
+        pool.pause();
+        settle();
+        pool.resume();
+        settle();
+        assert_eq!(
+            *recorder.changes.lock().unwrap(),
+            [(PoolState::Healthy, PoolState::Unhealthy), (PoolState::Unhealthy, PoolState::Healthy)]
+        );
+
+        handle.stop();
+        drop(obj);
+        let rounds = recorder.snapshots.lock().unwrap().len();
+        settle();
+        assert_eq!(recorder.snapshots.lock().unwrap().len(), rounds, "stopped");
+    }
+
+    #[test]
+    fn stops_once_the_pool_is_dropped() {
+        let pool = Arc::new(DynamicObjectPool::new(|| 0, PoolConfiguration::default()));
+        let recorder = Arc::new(Recorder::default());
+        let handle = pool.observe(Arc::clone(&recorder), Duration::from_millis(5));
+        drop(pool);
+        settle();
+        assert!(handle.thread.as_ref().unwrap().is_finished());
+    }
+}
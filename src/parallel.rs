@@ -0,0 +1,226 @@
+//! Rayon integration: per-thread pooled scratch objects for parallel work
+//!
+//! Enabled with the `rayon` feature. [`install_with_pool`] gives parallel
+//! code a [`PerThread`] handle: the first time a rayon worker thread calls
+//! [`PerThread::with`] it takes one object from the pool, and keeps using
+//! that object for the rest of the scope. Every object goes back to the
+//! pool when the scope ends, so a pool of scratch buffers sized to the
+//! thread count serves any amount of parallel work.
+//!
+//! # Examples
+//!
+//! ```
+//! use esox_objectpool::parallel::install_with_pool;
+//! use esox_objectpool::{DynamicObjectPool, PoolConfiguration};
+//! use rayon::prelude::*;
+//!
+//! let buffers = DynamicObjectPool::new(
+//!     || Vec::<u64>::with_capacity(1024),
+//!     PoolConfiguration::new().with_max_pool_size(64),
+//! );
+//!
+//! let total: u64 = install_with_pool(&buffers, |scratch| {
+//!     (0..1_000u64)
+//!         .into_par_iter()
+//!         .map(|n| {
+//!             scratch
+//!                 .with(|buf| {
+//!                     buf.clear();
+//!                     buf.extend(0..n % 10);
+//!                     buf.iter().sum::<u64>()
+//!                 })
+//!                 .unwrap()
+//!         })
+//!         .sum()
+//! });
+//!
+//! assert_eq!(total, 12_000);
+//! assert!(buffers.available_count() <= rayon::current_num_threads());
+//! assert_eq!(buffers.active_count(), 0);
+//! ```
+
+use crate::errors::PoolResult;
+use crate::pool::{DynamicObjectPool, ObjectPool, PooledObject, QueryableObjectPool};
+
+use std::sync::{Mutex, PoisonError, TryLockError};
+
+/// A pool [`install_with_pool`] can take per-thread objects from
+pub trait ScratchPool<T>: Sync {
+    /// Take one object, waiting as configured for the pool
+    fn acquire(&self) -> PoolResult<PooledObject<T>>;
+}
+
+impl<T: Send + Sync + 'static> ScratchPool<T> for ObjectPool<T> {
+    fn acquire(&self) -> PoolResult<PooledObject<T>> {
+        self.get_object()
+    }
+}
+
+impl<T: Send + Sync + 'static> ScratchPool<T> for QueryableObjectPool<T> {
+    fn acquire(&self) -> PoolResult<PooledObject<T>> {
+        ObjectPool::get_object(self)
+    }
+}
+
+impl<T: Send + Sync + 'static> ScratchPool<T> for DynamicObjectPool<T> {
+    fn acquire(&self) -> PoolResult<PooledObject<T>> {
+        self.get_object()
+    }
+}
+
+/// One lazily acquired object per rayon worker thread, returned to the pool
+/// when dropped
+///
+/// Created by [`install_with_pool`] and [`install_in`].
+pub struct PerThread<'p, T> {
+    pool: &'p dyn ScratchPool<T>,
+    slots: Vec<Mutex<Option<PooledObject<T>>>>,
+}
+
+impl<'p, T> PerThread<'p, T> {
+    fn new(pool: &'p dyn ScratchPool<T>, threads: usize) -> Self {
+        Self {
+            pool,
+            slots: (0..threads).map(|_| Mutex::new(None)).collect(),
+        }
+    }
+
+    /// Run `f` with the current worker thread's object, acquiring it first
+    /// if this thread has none yet
+    ///
+    /// Fails only if that acquisition fails. Calls from a thread outside
+    /// the rayon pool, and calls nested inside another `with` on the same
+    /// thread (a task stolen while `f` waits on rayon work), use an object
+    /// acquired for that call alone.
+    pub fn with<R>(&self, f: impl FnOnce(&mut T) -> R) -> PoolResult<R> {
+        let slot = rayon::current_thread_index().and_then(|index| self.slots.get(index));
+        let mut held = match slot.map(Mutex::try_lock) {
+            Some(Ok(held)) => held,
+            Some(Err(TryLockError::Poisoned(poisoned))) => poisoned.into_inner(),
+            Some(Err(TryLockError::WouldBlock)) | None => {
+                let mut obj = self.pool.acquire()?;
+                return Ok(f(obj.get_mut()));
+            }
+        };
+        let obj = match held.as_mut() {
+            Some(obj) => obj,
+            None => held.insert(self.pool.acquire()?),
+        };
+        Ok(f(obj))
+    }
+
+    /// Number of worker threads currently holding an object
+    #[must_use]
+    pub fn held(&self) -> usize {
+        self.slots
+            .iter()
+            .filter(|slot| slot.lock().unwrap_or_else(PoisonError::into_inner).is_some())
+            .count()
+    }
+}
+
+/// Run `op` with per-thread objects from `pool` for the current rayon
+/// thread pool (the global one, outside [`rayon::ThreadPool::install`])
+///
+/// Every object taken during `op` is returned to `pool` before this
+/// returns. See the [module documentation](self) for an example.
+pub fn install_with_pool<T, R>(pool: &impl ScratchPool<T>, op: impl FnOnce(&PerThread<'_, T>) -> R) -> R {
+    let scratch = PerThread::new(pool, rayon::current_num_threads());
+    op(&scratch)
+}
+
+/// [`install_with_pool`] on `threads`: `op` runs inside
+/// [`ThreadPool::install`](rayon::ThreadPool::install), so its parallel
+/// iterators use `threads` and take at most one object per thread
+pub fn install_in<T, R>(
+    threads: &rayon::ThreadPool,
+    pool: &impl ScratchPool<T>,
+    op: impl FnOnce(&PerThread<'_, T>) -> R + Send,
+) -> R
+where
+    T: Send,
+    R: Send,
+{
+    let scratch = PerThread::new(pool, threads.current_num_threads());
+    threads.install(|| op(&scratch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PoolConfiguration, WaitPolicy};
+    use rayon::prelude::*;
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn threads(n: usize) -> rayon::ThreadPool {
+        rayon::ThreadPoolBuilder::new().num_threads(n).build().unwrap()
+    }
+
+    #[test]
+    fn each_worker_acquires_once_and_everything_is_returned() {
+        let created = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&created);
+        let pool = DynamicObjectPool::new(
+            move || {
+                counter.fetch_add(1, Ordering::Relaxed);
+                0u64
+            },
+            PoolConfiguration::new().with_max_pool_size(8),
+        );
+
+        let sum: u64 = install_in(&threads(3), &pool, |scratch| {
+            let sum = (0..500u64)
+                .into_par_iter()
+                .map(|n| {
+                    scratch
+                        .with(|acc| {
+                            *acc += 1;
+                            n
+                        })
+                        .unwrap()
+                })
+                .sum();
+            assert!(scratch.held() <= 3);
+            sum
+        });
+
+        assert_eq!(sum, 124_750);
+        assert!(created.load(Ordering::Relaxed) <= 3);
+        assert_eq!(pool.get_metrics().total_retrieved, created.load(Ordering::Relaxed));
+        assert_eq!(pool.active_count(), 0);
+        assert_eq!(pool.available_count(), created.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn nested_and_outside_calls_use_their_own_object() {
+        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+
+        install_in(&threads(1), &pool, |scratch| {
+            scratch
+                .with(|outer| {
+                    let inner = scratch.with(|inner| *inner).unwrap();
+                    assert_ne!(*outer, inner);
+                })
+                .unwrap();
+            assert_eq!(scratch.held(), 1);
+
+            let outside = std::thread::scope(|s| s.spawn(|| scratch.with(|obj| *obj)).join().unwrap());
+            assert!(outside.is_ok());
+            assert_eq!(scratch.held(), 1);
+        });
+        assert_eq!(pool.available_count(), 3);
+    }
+
+    #[test]
+    fn failed_acquisition_is_reported() {
+        let pool = ObjectPool::new(
+            Vec::<i32>::new(),
+            PoolConfiguration::new().with_wait_on_empty(WaitPolicy::FailFast),
+        );
+        install_in(&threads(1), &pool, |scratch| {
+            assert!(scratch.with(|_| ()).is_err());
+            assert_eq!(scratch.held(), 0);
+        });
+    }
+}
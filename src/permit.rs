@@ -0,0 +1,214 @@
+//! Semaphore-style pools of permits
+
+use crate::config::PoolConfiguration;
+use crate::errors::PoolResult;
+use crate::health::HealthStatus;
+use crate::metrics::PoolMetrics;
+use crate::observable::ObservablePool;
+use crate::pool::{ObjectPool, PooledObject};
+
+use std::collections::HashMap;
+use std::ops::Deref;
+
+/// A permit held from a [`PermitPool`]; dropping it gives the permit back
+pub type Permit = PooledObject<()>;
+
+/// A pool of permits with no objects behind them
+///
+/// Limits how many callers run a section of code at once while keeping
+/// everything an [`ObjectPool`] offers: waiting and fairness policies, the
+/// circuit breaker, bulkheads, limit groups, metrics and health. Permits are
+/// `()`, so they take no memory per slot. Anything not wrapped here is
+/// reached through `Deref` to the underlying `ObjectPool<()>`, as with
+/// [`QueryableObjectPool`](crate::QueryableObjectPool).
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{PermitPool, PoolConfiguration, PoolError, WaitPolicy};
+///
+/// let limiter = PermitPool::new(2, PoolConfiguration::new().with_wait_on_empty(WaitPolicy::FailFast));
+///
+/// let a = limiter.acquire().unwrap();
+/// let _b = limiter.acquire().unwrap();
+/// assert!(matches!(limiter.acquire(), Err(PoolError::PoolEmpty)));
+///
+/// drop(a);
+/// assert_eq!(limiter.available_permits(), 1);
+/// assert_eq!(limiter.get_metrics().total_retrieved, 2);
+/// ```
+pub struct PermitPool {
+    inner: ObjectPool<()>,
+}
+
+impl PermitPool {
+    /// Create a pool of `permits` permits; `config.max_pool_size` is
+    /// overridden by `permits`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `permits` is 0.
+    pub fn new(permits: usize, config: PoolConfiguration<()>) -> Self {
+        Self {
+            inner: ObjectPool::new(vec![(); permits], config.with_max_pool_size(permits)),
+        }
+    }
+
+    /// Take a permit, waiting according to the configured
+    /// [`WaitPolicy`](crate::WaitPolicy)
+    ///
+    /// Fails like [`ObjectPool::get_object`].
+    #[track_caller]
+    pub fn acquire(&self) -> PoolResult<Permit> {
+        self.inner.get_object()
+    }
+
+    /// Take a permit if one is free, without waiting
+    ///
+    /// Returns `Ok(None)` when all permits are held.
+    #[track_caller]
+    pub fn try_acquire(&self) -> PoolResult<Option<Permit>> {
+        self.inner.try_get_object()
+    }
+
+    /// Take a permit, waiting asynchronously for one to be given back
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{PermitPool, PoolConfiguration};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let limiter = PermitPool::new(1, PoolConfiguration::default());
+    /// let permit = limiter.acquire_async().await.unwrap();
+    /// assert_eq!(limiter.held_permits(), 1);
+    /// drop(permit);
+    /// # }
+    /// ```
+    #[track_caller]
+    pub fn acquire_async(&self) -> impl Future<Output = PoolResult<Permit>> {
+        self.inner.get_object_async()
+    }
+
+    /// Number of permits free right now
+    #[must_use]
+    pub fn available_permits(&self) -> usize {
+        self.inner.available_count()
+    }
+
+    /// Number of permits currently held
+    #[must_use]
+    pub fn held_permits(&self) -> usize {
+        self.inner.active_count()
+    }
+
+    /// Total number of permits
+    #[must_use]
+    pub fn permits(&self) -> usize {
+        self.inner.capacity()
+    }
+}
+
+impl Deref for PermitPool {
+    type Target = ObjectPool<()>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+impl ObservablePool for PermitPool {
+    fn available_count(&self) -> usize {
+        self.inner.available_count()
+    }
+
+    fn active_count(&self) -> usize {
+        self.inner.active_count()
+    }
+
+    fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    fn get_metrics(&self) -> PoolMetrics {
+        self.inner.get_metrics()
+    }
+
+    fn get_health_status(&self) -> HealthStatus {
+        self.inner.get_health_status()
+    }
+
+    fn export_metrics(&self) -> HashMap<String, String> {
+        self.inner.export_metrics()
+    }
+
+    fn export_metrics_prometheus(
+        &self,
+        pool_name: &str,
+        tags: Option<&HashMap<String, String>>,
+    ) -> String {
+        self.inner.export_metrics_prometheus(pool_name, tags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::{GuardedError, PoolError};
+    use crate::WaitPolicy;
+    use std::time::Duration;
+
+    #[test]
+    fn permits_override_max_pool_size() {
+        let limiter = PermitPool::new(3, PoolConfiguration::new().with_max_pool_size(100));
+        assert_eq!(limiter.permits(), 3);
+        assert_eq!(limiter.available_permits(), 3);
+
+        let permit = limiter.try_acquire().unwrap().unwrap();
+        assert_eq!(limiter.held_permits(), 1);
+        drop(permit);
+        assert_eq!(limiter.available_permits(), 3);
+    }
+
+    #[test]
+    fn breaker_applies_to_permits() {
+        let limiter = PermitPool::new(
+            2,
+            PoolConfiguration::new()
+                .with_wait_on_empty(WaitPolicy::FailFast)
+                .with_circuit_breaker(1, Duration::from_secs(60)),
+        );
+        let failed: Result<(), _> = limiter.run_guarded(|_| Err::<(), _>("downstream down"));
+        assert!(matches!(failed, Err(GuardedError::Operation("downstream down"))));
+
+        assert!(matches!(limiter.acquire(), Err(PoolError::CircuitBreakerOpen)));
+    }
+
+    #[tokio::test]
+    async fn async_waiters_get_released_permits() {
+        let limiter = std::sync::Arc::new(PermitPool::new(1, PoolConfiguration::default()));
+        let held = limiter.acquire().unwrap();
+
+        let waiter = tokio::spawn({
+            let limiter = std::sync::Arc::clone(&limiter);
+            async move { limiter.acquire_async().await.map(drop) }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+
+        drop(held);
+        tokio::time::timeout(Duration::from_secs(1), waiter).await.unwrap().unwrap().unwrap();
+    }
+
+    #[test]
+    fn observable_like_any_pool() {
+        let limiter = PermitPool::new(2, PoolConfiguration::default());
+        let _permit = limiter.acquire().unwrap();
+        let pool: &dyn ObservablePool = &limiter;
+
+        assert_eq!((pool.available_count(), pool.active_count(), pool.capacity()), (1, 1, 2));
+        assert!(pool.get_health_status().is_healthy);
+        assert!(pool.export_metrics_prometheus("limiter", None).contains("objectpool_"));
+    }
+}
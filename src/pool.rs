@@ -1,18 +1,118 @@
 //! Core object pool implementations
 
-use crate::config::PoolConfiguration;
-use crate::errors::{PoolError, PoolResult};
+use crate::config::{CapacityLimits, PoolConfiguration};
+use crate::errors::{GuardedError, IntoInnerError, PoolError, PoolResult};
 use crate::health::HealthStatus;
-use crate::metrics::{MetricsExporter, MetricsTracker, PoolMetrics};
+use crate::metrics::{HoldTimer, MetricsExporter, MetricsTracker, PoolMetrics, QueryMetrics};
 use crate::eviction::{EvictionPolicy, EvictionTracker};
 use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerState};
+use crate::acquire::Acquire;
+use crate::stream::AcquireStream;
+use crate::batch::PooledBatch;
+use crate::lease::Lease;
+use crate::events::{PoolEvent, PoolWarning, Reporter, ReturnError, ReturnErrorReporter};
+use crate::wait::{run_blocking, wait_async, wait_blocking, wait_in_place, WaitPolicy, Waiters};
+use crate::idle::IdleQueue;
+use crate::bulkhead::{BulkheadPermit, Bulkheads};
+use crate::ids::{ExternalIds, PoolObjectId};
+use crate::group::{ActiveSlots, Refusal};
+use crate::verify::{FactoryCheck, VerifyReport};
+use crate::consistency::ConsistencyReport;
+use crate::context::AcquireContext;
+use crate::throttle::{CreationThrottle, PendingCreation, PendingCreations};
+use crate::holders::{Holder, HolderTicket, Holders};
+use crate::watchdog::StarvationWatchdog;
+use crate::hooks::{acquisition_wait, attempt_started_at, Hooks};
+use crate::retry::{self, RetryPolicy};
+use crate::failure::FailurePolicy;
+use crate::diagnosis::{FailureDiagnosis, Gate};
+use crate::tuning::{self, HoldSample, Observed, PoolTuningAdvice};
+use crate::selection::{Best, Health};
+use crate::slo::{SloStatus, SloTracker};
+use crate::activity::{ActivityLog, ActivityOutcome, ActivityRecord};
+use crate::memory::MemoryAccount;
+use crate::degraded::{DegradedTier, ObjectOrigin};
+use crate::observer::{ObserverHandle, PoolObserver, Watched};
+#[cfg(feature = "tracing")]
+use crate::logging::EventLogger;
 
-use crossbeam::queue::ArrayQueue;
 use std::collections::HashMap;
+use std::future::Future;
 use std::ops::{Deref, DerefMut};
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Arc;
-use std::time::Duration;
+use std::panic::Location;
+#[cfg(feature = "futures")]
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError, RwLock, Weak};
+#[cfg(feature = "futures")]
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+#[cfg(feature = "futures")]
+use futures_core::Stream;
+#[cfg(feature = "futures")]
+use futures_sink::Sink;
+use tokio::sync::Notify;
+
+/// Record the caller of a `#[track_caller]` acquisition method as where the
+/// object in `result` was acquired.
+#[track_caller]
+fn located<T>(result: PoolResult<PooledObject<T>>) -> PoolResult<PooledObject<T>> {
+    let location = Location::caller();
+    result.map(|obj| obj.located(location))
+}
+
+fn is_pool_empty(err: &PoolError) -> bool {
+    matches!(err, PoolError::PoolEmpty | PoolError::Paused)
+}
+
+/// A dynamic pool cannot create an object right now, but may later.
+fn is_creation_blocked(err: &PoolError) -> bool {
+    matches!(
+        err,
+        PoolError::PoolFull | PoolError::CreationThrottled | PoolError::CreationPending | PoolError::Paused
+    )
+}
+
+fn is_no_match(err: &PoolError) -> bool {
+    matches!(err, PoolError::NoMatchFound | PoolError::Paused)
+}
+
+/// Return path of a pool; reports why an object could not be put back.
+/// Takes an object back with its id, how it is given back and, for the
+/// timed return hook, when it was handed out.
+type ReturnFn<T> = Arc<dyn Fn(T, usize, Disposal, Option<Instant>) -> PoolResult<()> + Send + Sync>;
+type DetachFn = Arc<dyn Fn(usize) + Send + Sync>;
+/// Fallible object factory of a dynamic pool; errors are rendered to text.
+pub(crate) type Factory<T> = Arc<dyn Fn() -> Result<T, String> + Send + Sync>;
+/// Makes an object for a single acquisition in place of the pool's factory.
+pub(crate) type Creator<'a, T> = &'a dyn Fn() -> T;
+/// Builds an object for a key in a [`DynamicQueryablePool`].
+type KeyedFactory<K, T> = Arc<dyn Fn(&K) -> T + Send + Sync>;
+/// Decides whether an idle object serves a request for a key.
+type KeyMatcher<K, T> = Arc<dyn Fn(&T, &K) -> bool + Send + Sync>;
+
+/// Whether a pool could hand out an object right now.
+pub(crate) enum Readiness {
+    Ready,
+    /// Every object is checked out, an active-object limit is reached or
+    /// the pool is paused.
+    Busy,
+    /// The circuit breaker refuses acquisitions for about this long; read
+    /// by `PoolService` to know when to poll again.
+    BreakerOpen(#[cfg_attr(not(feature = "tower"), allow(dead_code))] Duration),
+    Closed,
+}
+
+/// How a guard gives its object back.
+#[derive(Clone, Copy)]
+enum Disposal {
+    /// Dropped or released.
+    Return,
+    /// Completed with the outcome of the work done with the object.
+    Completed { success: bool },
+    /// Destroyed with [`PooledObject::discard`].
+    Discard,
+}
 
 /// A pooled object that automatically returns to the pool when dropped
 ///
@@ -33,18 +133,94 @@ use std::time::Duration;
 ///
 /// assert_eq!(pool.available_count(), 3);
 /// ```
+///
+/// # Thread safety
+///
+/// Everything a guard carries besides the object — the pool's return path,
+/// its lease and bulkhead slot — is `Send + Sync`, so `PooledObject<T>` is
+/// `Send` whenever `T: Send` and `Sync` whenever `T: Sync`. Guards can be
+/// moved into spawned tasks, across `.await` points and into scoped
+/// threads, and are returned to their pool from whichever thread drops
+/// them. This is enforced at compile time.
+///
+/// ```
+/// use esox_objectpool::{ObjectPool, PoolConfiguration};
+///
+/// let pool = ObjectPool::new(vec![1, 2], PoolConfiguration::default());
+/// let obj = pool.get_object().unwrap();
+///
+/// std::thread::scope(|s| {
+///     s.spawn(move || assert!(*obj > 0));
+///     s.spawn(|| assert!(*pool.get_object().unwrap() > 0));
+/// });
+/// assert_eq!(pool.available_count(), 2);
+/// ```
+///
+/// # Sinks and streams
+///
+/// With the `futures` feature, a guard over a `Stream` or `Sink` is one
+/// itself, so pooled channels and connections plug straight into `futures`
+/// combinators:
+///
+/// ```
+/// # #[cfg(feature = "futures")]
+/// # fn main() {
+/// use esox_objectpool::{ObjectPool, PoolConfiguration};
+/// use futures::channel::mpsc;
+/// use futures::{SinkExt, StreamExt};
+///
+/// let (tx, rx) = mpsc::channel::<u32>(8);
+/// let senders = ObjectPool::new(vec![tx], PoolConfiguration::default());
+///
+/// futures::executor::block_on(async {
+///     let mut sender = senders.get_object().unwrap();
+///     sender.send(7).await.unwrap();
+///     drop(sender);
+///     drop(senders);
+///     assert_eq!(rx.collect::<Vec<_>>().await, [7]);
+/// });
+/// # }
+/// # #[cfg(not(feature = "futures"))]
+/// # fn main() {}
+/// ```
 pub struct PooledObject<T> {
     value: Option<T>,
     object_id: usize,
-    return_fn: Arc<dyn Fn(T, usize) + Send + Sync>,
-    detach_fn: Arc<dyn Fn(usize) + Send + Sync>,
+    return_fn: ReturnFn<T>,
+    detach_fn: DetachFn,
+    lease: Option<Lease>,
+    context: Option<String>,
+    external_id: Option<Arc<str>>,
+    acquire_context: Option<Arc<AcquireContext>>,
+    acquired_at: Option<&'static Location<'static>>,
+    /// When the object was handed out, if the timed return hook needs it.
+    checked_out: Option<Instant>,
+    /// Records the hold time for per-caller metrics when dropped.
+    hold: Option<HoldTimer>,
+    /// Lists this checkout in the pool's holder report while alive.
+    holder: Option<HolderTicket>,
+    /// Records the hold time for tuning advice when dropped.
+    hold_sample: Option<HoldSample>,
+    /// The pool's eviction tracking, which also holds the metadata set with
+    /// [`PooledObject::set_meta`].
+    eviction: Option<Arc<EvictionTracker<T>>>,
+    /// Which factory made the object.
+    origin: ObjectOrigin,
+    /// Bulkhead slot held while checked out; declared last so it is freed
+    /// only after the object is back in the pool.
+    permit: Option<BulkheadPermit>,
 }
 
 impl<T: std::fmt::Debug> std::fmt::Debug for PooledObject<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("PooledObject")
             .field("value", &self.value)
-            .field("object_id", &self.object_id)
+            .field("id", &PoolObjectId::new(self.object_id))
+            .field("external_id", &self.external_id)
+            .field("lease", &self.lease)
+            .field("context", &self.context)
+            .field("acquired_at", &self.acquired_at)
+            .field("origin", &self.origin)
             .finish()
     }
 }
@@ -53,15 +229,219 @@ impl<T> PooledObject<T> {
     fn new(
         value: T,
         object_id: usize,
-        return_fn: Arc<dyn Fn(T, usize) + Send + Sync>,
-        detach_fn: Arc<dyn Fn(usize) + Send + Sync>,
+        return_fn: ReturnFn<T>,
+        detach_fn: DetachFn,
     ) -> Self {
         Self {
             value: Some(value),
             object_id,
             return_fn,
             detach_fn,
+            lease: None,
+            context: None,
+            external_id: None,
+            acquire_context: None,
+            acquired_at: None,
+            checked_out: None,
+            hold: None,
+            holder: None,
+            hold_sample: None,
+            eviction: None,
+            origin: ObjectOrigin::Primary,
+            permit: None,
+        }
+    }
+
+    /// Correlation id captured at acquisition by the configured
+    /// [`context hook`](crate::PoolConfiguration::with_context_hook), if any.
+    #[must_use]
+    pub fn context(&self) -> Option<&str> {
+        self.context.as_deref()
+    }
+
+    /// Whether the object came from the pool's factory or, while its
+    /// circuit breaker was open, from the degraded-tier factory. See
+    /// [`with_degraded_factory`](crate::PoolConfiguration::with_degraded_factory).
+    #[must_use]
+    pub fn origin(&self) -> ObjectOrigin {
+        self.origin
+    }
+
+    /// The id this object was seeded with, for pools created with
+    /// [`ObjectPool::new_with_ids`] or [`QueryableObjectPool::new_with_ids`]
+    #[must_use]
+    pub fn external_id(&self) -> Option<&str> {
+        self.external_id.as_deref()
+    }
+
+    /// The metadata of type `M` attached to this object, if any
+    ///
+    /// Metadata belongs to the object rather than to this checkout: it is
+    /// still there the next time the object is acquired, and dropped when
+    /// the object is destroyed, discarded or detached. Each type is stored
+    /// separately, so unrelated parts of an application can attach their
+    /// own.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    ///
+    /// #[derive(Clone, Debug, PartialEq)]
+    /// struct SchemaVersion(u32);
+    ///
+    /// let pool = ObjectPool::new(vec!["conn"], PoolConfiguration::default());
+    ///
+    /// let conn = pool.get_object().unwrap();
+    /// assert_eq!(conn.meta::<SchemaVersion>(), None);
+    /// conn.set_meta(SchemaVersion(3));
+    /// drop(conn);
+    ///
+    /// let conn = pool.get_object().unwrap();
+    /// assert_eq!(conn.meta::<SchemaVersion>(), Some(SchemaVersion(3)));
+    /// ```
+    #[must_use]
+    pub fn meta<M: Clone + 'static>(&self) -> Option<M> {
+        self.eviction.as_ref()?.user_meta().get(self.object_id)
+    }
+
+    /// Attach `value` to this object, returning the metadata of the same
+    /// type it replaces. See [`meta`](Self::meta).
+    pub fn set_meta<M: Send + Sync + 'static>(&self, value: M) -> Option<M> {
+        self.eviction.as_ref()?.user_meta().set(self.object_id, value)
+    }
+
+    /// Remove and return the metadata of type `M` attached to this object.
+    /// See [`meta`](Self::meta).
+    pub fn take_meta<M: 'static>(&self) -> Option<M> {
+        self.eviction.as_ref()?.user_meta().take(self.object_id)
+    }
+
+    /// This object's health score, `1.0` until one is reported with
+    /// [`set_health`](Self::set_health)
+    #[must_use]
+    pub fn health(&self) -> f64 {
+        self.meta::<Health>().map_or(Health::DEFAULT, |health| health.0)
+    }
+
+    /// Report how healthy this object is, higher being healthier
+    ///
+    /// The scale is the application's: a probe result, a success rate over
+    /// recent calls, a latency budget left. The score stays with the object
+    /// across checkouts and is what
+    /// [`with_health_selection`](PoolConfiguration::with_health_selection)
+    /// compares, so degraded objects are handed out last.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    ///
+    /// let pool = ObjectPool::new(vec!["a", "b"], PoolConfiguration::new().with_health_selection(2));
+    ///
+    /// let first = pool.get_object().unwrap();
+    /// first.set_health(0.2);
+    /// let degraded = *first;
+    /// drop(first);
+    ///
+    /// let next = pool.get_object().unwrap();
+    /// assert_ne!(*next, degraded);
+    /// assert_eq!(next.health(), 1.0);
+    /// ```
+    pub fn set_health(&self, score: f64) {
+        self.set_meta(Health(score));
+    }
+
+    /// Time left before this object outlives the pool's time to live, or
+    /// `None` if the pool has no TTL
+    ///
+    /// An object that expires while checked out is destroyed on return, so
+    /// a caller close to the limit can finish quickly or refresh whatever
+    /// the object holds. Zero for an object retired by
+    /// [`invalidate_all`](ObjectPool::invalidate_all).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    /// use std::time::Duration;
+    ///
+    /// let pool = ObjectPool::new(
+    ///     vec![1],
+    ///     PoolConfiguration::new().with_ttl(Duration::from_secs(60)),
+    /// );
+    /// let obj = pool.get_object().unwrap();
+    ///
+    /// let left = obj.remaining_ttl().unwrap();
+    /// assert!(left <= Duration::from_secs(60) && left > Duration::from_secs(59));
+    /// assert_eq!(obj.idle_remaining(), None);
+    /// ```
+    #[must_use]
+    pub fn remaining_ttl(&self) -> Option<Duration> {
+        self.eviction.as_ref()?.remaining_ttl(self.object_id)
+    }
+
+    /// Time left before this object exceeds the pool's idle timeout, or
+    /// `None` if the pool has none
+    ///
+    /// Counted from when the object was handed out; returning it restarts
+    /// the idle clock. Zero for an object retired by
+    /// [`invalidate_all`](ObjectPool::invalidate_all).
+    #[must_use]
+    pub fn idle_remaining(&self) -> Option<Duration> {
+        self.eviction.as_ref()?.idle_remaining(self.object_id)
+    }
+
+    /// The context this object was acquired with, for guards obtained via
+    /// [`ObjectPool::get_object_with`] and friends
+    #[must_use]
+    pub fn acquire_context(&self) -> Option<&AcquireContext> {
+        self.acquire_context.as_deref()
+    }
+
+    /// Source location of the call that acquired this object
+    ///
+    /// Captured with `#[track_caller]` by the pools' acquisition methods,
+    /// so it names the application code rather than a line in this crate.
+    /// Also shown in [`current_holders`](ObjectPool::current_holders) and
+    /// in [`PoolWarning::LeaseExpired`](crate::PoolWarning::LeaseExpired).
+    /// Objects from a batch or an [`AcquireStream`] carry the location of
+    /// the `get_batch` or `acquire_stream` call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    ///
+    /// let pool = ObjectPool::new(vec![1], PoolConfiguration::default());
+    /// let obj = pool.get_object().unwrap();
+    /// assert_eq!(obj.acquired_at().unwrap().file(), file!());
+    /// ```
+    #[must_use]
+    pub fn acquired_at(&self) -> Option<&'static Location<'static>> {
+        self.acquired_at
+    }
+
+    /// Record `location` as where this object was acquired.
+    pub(crate) fn located(mut self, location: &'static Location<'static>) -> Self {
+        self.acquired_at = Some(location);
+        if let Some(ref holder) = self.holder {
+            holder.locate(location);
         }
+        self
+    }
+
+    /// Id of this object, stable while it belongs to the pool
+    #[must_use]
+    pub fn id(&self) -> PoolObjectId {
+        PoolObjectId::new(self.object_id)
+    }
+
+    /// The lease attached to this object, if it was acquired with
+    /// [`ObjectPool::get_object_leased`] or a related method.
+    #[must_use]
+    pub fn lease(&self) -> Option<&Lease> {
+        self.lease.as_ref()
     }
     
     /// Permanently remove the inner value from the pool and take ownership.
@@ -84,10 +464,126 @@ impl<T> PooledObject<T> {
     /// assert_eq!(pool.available_count(), 0); // capacity is gone
     /// ```
     pub fn into_detached(mut self) -> T {
+        if let Some(lease) = self.lease.take() {
+            lease.release();
+        }
         (self.detach_fn)(self.object_id);
         self.value.take().expect("Value already taken")
     }
 
+    /// Destroy the object instead of returning it, for example after it
+    /// turned out to be broken.
+    ///
+    /// The object is dropped through the pool's
+    /// [`on_destroy`](crate::PoolConfiguration::with_on_destroy) hook and
+    /// counted in [`PoolMetrics::discarded_objects`](crate::PoolMetrics::discarded_objects).
+    /// It no longer counts against the pool's capacity, so a dynamic pool
+    /// creates a replacement on demand.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{DynamicObjectPool, PoolConfiguration};
+    ///
+    /// let pool = DynamicObjectPool::new(|| String::from("conn"), PoolConfiguration::default());
+    /// let conn = pool.get_object().unwrap();
+    ///
+    /// conn.discard();
+    /// assert_eq!(pool.available_count(), 0);
+    /// assert_eq!(pool.get_metrics().discarded_objects, 1);
+    /// ```
+    pub fn discard(mut self) {
+        // Discarding cannot fail.
+        let _ = self.give_back(Disposal::Discard);
+    }
+
+    /// Give the object back along with the outcome of the work done with it
+    ///
+    /// `result` is passed through unchanged, so a call can wrap the
+    /// function's return value. `Ok` returns the object as usual. `Err`
+    /// returns, validates or discards it according to the pool's
+    /// [`FailurePolicy`](crate::FailurePolicy). Either way the outcome
+    /// counts towards the circuit breaker, as with
+    /// [`ObjectPool::report_success`] and [`ObjectPool::report_failure`].
+    /// An object rejected on return is reported through
+    /// [`ObjectPool::return_errors`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration, PooledObject};
+    /// use std::time::Duration;
+    ///
+    /// fn query(mut conn: PooledObject<String>) -> Result<usize, String> {
+    ///     let result = if conn.is_empty() { Err("not connected".to_string()) } else { Ok(conn.len()) };
+    ///     conn.complete(result)
+    /// }
+    ///
+    /// let pool = ObjectPool::new(
+    ///     vec![String::new()],
+    ///     PoolConfiguration::new()
+    ///         .with_circuit_breaker(1, Duration::from_secs(60))
+    ///         .with_validation(|conn: &String| !conn.is_empty()),
+    /// );
+    ///
+    /// assert!(query(pool.get_object().unwrap()).is_err());
+    /// assert_eq!(pool.available_count(), 0, "failed validation after the error");
+    /// assert!(pool.get_object().is_err(), "the breaker is open");
+    /// ```
+    pub fn complete<R, E>(mut self, result: Result<R, E>) -> Result<R, E> {
+        // Like dropping, completing reports return errors out of band.
+        let _ = self.give_back(Disposal::Completed { success: result.is_ok() });
+        result
+    }
+
+    /// Return the object to the pool now and report the outcome.
+    ///
+    /// Dropping a guard does the same thing silently. Use `release()` when the
+    /// caller needs to know whether the object actually went back: it returns
+    /// `PoolError::ValidationFailed` when the object was rejected by the
+    /// configured validator, and `PoolError::PoolFull` when it could not be
+    /// re-queued. In both cases the object has been discarded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration, PoolError};
+    ///
+    /// let pool = ObjectPool::new(
+    ///     vec![1, 2],
+    ///     PoolConfiguration::new().with_validation(|x: &i32| *x > 0),
+    /// );
+    ///
+    /// let good = pool.get_object().unwrap();
+    /// assert!(good.release().is_ok());
+    ///
+    /// let mut bad = pool.get_object().unwrap();
+    /// *bad = -1;
+    /// assert!(matches!(bad.release(), Err(PoolError::ValidationFailed)));
+    /// ```
+    pub fn release(mut self) -> PoolResult<()> {
+        self.return_to_pool()
+    }
+
+    fn return_to_pool(&mut self) -> PoolResult<()> {
+        self.give_back(Disposal::Return)
+    }
+
+    fn give_back(&mut self, disposal: Disposal) -> PoolResult<()> {
+        match self.take_for_return() {
+            Some(value) => (self.return_fn)(value, self.object_id, disposal, self.checked_out),
+            None => Ok(()),
+        }
+    }
+
+    /// Take the object out to hand it back to the pool, ending its lease.
+    fn take_for_return(&mut self) -> Option<T> {
+        if let Some(lease) = self.lease.take() {
+            lease.release();
+        }
+        self.value.take()
+    }
+
     /// Get the inner value without returning to pool.
     ///
     /// # Deprecation
@@ -152,6 +648,26 @@ impl<T> PooledObject<T> {
     }
 }
 
+impl<T: Send + 'static> PooledObject<T> {
+    /// Return the object to the pool from async code and report the outcome.
+    ///
+    /// Behaves like [`release`](Self::release), but runs the return path
+    /// (including the validation function) on tokio's blocking thread pool so
+    /// a slow validator does not stall the async executor. Outside a tokio
+    /// runtime the return runs inline.
+    pub async fn release_async(mut self) -> PoolResult<()> {
+        if let Some(lease) = self.lease.take() {
+            lease.release();
+        }
+        let Some(value) = self.value.take() else {
+            return Ok(());
+        };
+        let return_fn = Arc::clone(&self.return_fn);
+        let (id, checked_out) = (self.object_id, self.checked_out);
+        run_blocking(move || return_fn(value, id, Disposal::Return, checked_out)).await?
+    }
+}
+
 impl<T> AsRef<T> for PooledObject<T> {
     fn as_ref(&self) -> &T {
         self.get()
@@ -178,10 +694,161 @@ impl<T> DerefMut for PooledObject<T> {
     }
 }
 
+#[cfg(feature = "futures")]
+impl<T: Stream + Unpin> Stream for PooledObject<T> {
+    type Item = T::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(self.get_mut().get_mut()).poll_next(cx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.get().size_hint()
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<T: Sink<Item> + Unpin, Item> Sink<Item> for PooledObject<T> {
+    type Error = T::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(self.get_mut().get_mut()).poll_ready(cx)
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Item) -> Result<(), Self::Error> {
+        Pin::new(self.get_mut().get_mut()).start_send(item)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(self.get_mut().get_mut()).poll_flush(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Pin::new(self.get_mut().get_mut()).poll_close(cx)
+    }
+}
+
 impl<T> Drop for PooledObject<T> {
     fn drop(&mut self) {
-        if let Some(value) = self.value.take() {
-            (self.return_fn)(value, self.object_id);
+        // Drop cannot report errors; `release()` is the explicit alternative.
+        let _ = self.return_to_pool();
+    }
+}
+
+/// Compile-time proof of the auto traits promised in the docs. Never called;
+/// it fails to type-check if a field added to a guard or pool loses
+/// `Send`/`Sync` for some `T`.
+#[allow(dead_code)]
+fn assert_auto_traits<S: Send, Y: Sync, T: Send + Sync + 'static>() {
+    fn send<X: Send>() {}
+    fn sync<X: Sync>() {}
+
+    send::<PooledObject<S>>();
+    sync::<PooledObject<Y>>();
+    send::<ObjectPool<T>>();
+    sync::<ObjectPool<T>>();
+    send::<QueryableObjectPool<T>>();
+    sync::<QueryableObjectPool<T>>();
+    send::<DynamicObjectPool<T>>();
+    sync::<DynamicObjectPool<T>>();
+    send::<PooledBatch<'static, T>>();
+}
+
+/// The parts of a pool that take back a returned object, shared by the
+/// guards' return path and [`ObjectPool::return_many`].
+struct ReturnPath<'a, T> {
+    available: &'a IdleQueue<T>,
+    eviction: &'a EvictionTracker<T>,
+    hooks: &'a Hooks<T>,
+    metrics: &'a MetricsTracker,
+    return_errors: &'a ReturnErrorReporter,
+    population: &'a AtomicUsize,
+    closed: &'a AtomicBool,
+    retiring: &'a AtomicUsize,
+    activity: Option<&'a ActivityLog>,
+    events: &'a Reporter<PoolEvent>,
+}
+
+impl<T: Send + Sync + 'static> ReturnPath<'_, T> {
+    /// Decide what becomes of returned object `id`. It is destroyed if the
+    /// pool is closed or was invalidated since the object was created, a
+    /// retirement is owed, the object expired, or it fails recycling or,
+    /// with `validate`, validation; `ValidationFailed` for the latter two.
+    /// Otherwise it is handed back for [`push`](Self::push). Leaves the
+    /// active count to the caller.
+    fn settle(&self, mut obj: T, id: usize, validate: bool, checked_out: Option<Instant>) -> PoolResult<Option<T>> {
+        // A closed pool, or one invalidated since the object was created,
+        // accepts the object back only to destroy it.
+        if self.closed.load(Ordering::Acquire) || self.eviction.is_stale(id) {
+            self.destroy(obj, id, ActivityOutcome::Discarded);
+            return Ok(None);
+        }
+        if ObjectPool::<T>::take_retirement(self.retiring) {
+            self.metrics.retired_objects.fetch_add(1, Ordering::Relaxed);
+            self.destroy(obj, id, ActivityOutcome::Discarded);
+            return Ok(None);
+        }
+        if !self.eviction.returned(id) {
+            self.destroy(obj, id, ActivityOutcome::Evicted);
+            if self.metrics.event_sampler.sample() {
+                self.events.report(PoolEvent::Evicted { object_id: PoolObjectId::new(id) });
+            }
+            return Ok(None);
+        }
+        // Reset, then validate if configured
+        if !self.hooks.recycle(&mut obj, checked_out) || (validate && !self.hooks.is_valid(&obj)) {
+            self.metrics.validation_failures.fetch_add(1, Ordering::Relaxed);
+            self.return_errors.report(ReturnError::ValidationFailed { object_id: PoolObjectId::new(id) });
+            self.destroy(obj, id, ActivityOutcome::Discarded);
+            return Err(PoolError::ValidationFailed);
+        }
+        if validate && self.hooks.validates() {
+            self.eviction.validated(id);
+        }
+        Ok(Some(obj))
+    }
+
+    /// Put a settled object back in the idle queue, destroying it if the
+    /// queue is full.
+    fn push(&self, obj: T, id: usize) -> PoolResult<()> {
+        match ObjectPool::push_returned(self.available, self.eviction, (obj, id)) {
+            Ok(()) => {
+                self.note(ActivityOutcome::Returned, id);
+                Ok(())
+            }
+            Err((obj, failed_id)) => {
+                ObjectPool::discard_overflow_with(
+                    self.hooks,
+                    self.metrics,
+                    self.eviction,
+                    self.return_errors,
+                    self.population,
+                    obj,
+                    failed_id,
+                );
+                self.note(ActivityOutcome::Discarded, failed_id);
+                Err(PoolError::PoolFull)
+            }
+        }
+    }
+
+    /// Destroy a returned object that leaves the pool, recording `outcome`.
+    fn destroy(&self, obj: T, id: usize, outcome: ActivityOutcome) {
+        self.eviction.remove_object(id);
+        ObjectPool::destroy_with(self.hooks, self.population, obj);
+        self.note(outcome, id);
+    }
+
+    fn note(&self, outcome: ActivityOutcome, id: usize) {
+        if let Some(activity) = self.activity {
+            activity.record(ActivityRecord::new(outcome, Some(id)));
+        }
+    }
+
+    /// Destroy what was pushed back while the pool was closing.
+    fn finish(&self) {
+        if self.closed.load(Ordering::Acquire) {
+            ObjectPool::destroy_idle_with(self.available, self.eviction, self.hooks, self.population);
         }
     }
 }
@@ -198,23 +865,71 @@ impl<T> Drop for PooledObject<T> {
 /// // Get an object - automatically returned when dropped
 /// {
 ///     let obj = pool.get_object().unwrap();
-///     assert!(vec![1, 2, 3].contains(&*obj));
+///     assert!([1, 2, 3].contains(&*obj));
 /// }
 /// 
 /// // Object returned, pool refilled
 /// assert_eq!(pool.available_count(), 3);
 /// ```
 pub struct ObjectPool<T: Send> {
-    available: Arc<ArrayQueue<(T, usize)>>,
+    available: Arc<IdleQueue<T>>,
     /// Number of objects currently checked out. Also acts as a CAS semaphore
-    /// for `max_active_objects` enforcement so the check+increment is atomic.
-    active_count: Arc<AtomicUsize>,
+    /// for `max_active_objects` and limit group enforcement so the
+    /// check+increment is atomic.
+    active_count: Arc<ActiveSlots>,
     config: Arc<PoolConfiguration<T>>,
     metrics: Arc<MetricsTracker>,
     eviction: Arc<EvictionTracker<T>>,
     circuit_breaker: Option<Arc<CircuitBreaker>>,
     next_id: Arc<AtomicUsize>,
     capacity: usize,
+    /// Objects owned by the pool, idle or checked out. Creation reserves a
+    /// unit here before building an object; destruction, detaching and
+    /// draining release it. Never exceeds `capacity`.
+    population: Arc<AtomicUsize>,
+    /// Objects a fixed pool started with; `None` for dynamic pools, which
+    /// replace lost objects.
+    original_size: Option<usize>,
+    /// Shared by every guard handed out by this pool; also used to recognise
+    /// this pool's own guards in [`ObjectPool::return_many`].
+    return_fn: ReturnFn<T>,
+    detach_fn: DetachFn,
+    return_errors: Arc<ReturnErrorReporter>,
+    /// Signalled whenever a checked-out object is returned or leaves the
+    /// pool, waking async waiters.
+    released: Arc<Notify>,
+    bulkheads: Arc<Bulkheads>,
+    external_ids: Arc<ExternalIds>,
+    /// Paces factory calls in the dynamic pools built on this one.
+    creation_throttle: Arc<CreationThrottle>,
+    /// Factory calls in progress in the dynamic pools built on this one.
+    pending_creations: Arc<PendingCreations>,
+    /// Who holds the checked-out objects, when holder tracking is enabled.
+    holders: Option<Arc<Holders>>,
+    /// Recent acquisitions, returns and evictions, when enabled.
+    activity: Option<Arc<ActivityLog>>,
+    warnings: Arc<Reporter<PoolWarning>>,
+    events: Arc<Reporter<PoolEvent>>,
+    /// Watches waiting acquisitions, when starvation detection is enabled.
+    watchdog: Option<Arc<StarvationWatchdog>>,
+    /// Error budgets, when SLO targets are configured.
+    slo: Option<Arc<SloTracker>>,
+    /// Acquisitions currently waiting for an object.
+    waiters: Arc<Waiters>,
+    /// Idle objects destroyed as expired since an object was last handed
+    /// out.
+    expired_streak: Arc<AtomicUsize>,
+    /// Read by [`ObjectPool::explain_last_failure`].
+    last_failure: Arc<Mutex<Option<FailureDiagnosis>>>,
+    /// Runs the configured validation function and hooks.
+    hooks: Arc<Hooks<T>>,
+    /// Set by [`ObjectPool::shutdown`]; never cleared.
+    closed: Arc<AtomicBool>,
+    /// Set by [`ObjectPool::pause`], cleared by [`ObjectPool::resume`].
+    paused: Arc<AtomicBool>,
+    /// Objects [`ObjectPool::retire`] still has to take out of circulation
+    /// as they are returned.
+    retiring: Arc<AtomicUsize>,
 }
 
 impl<T: Send + Sync + 'static> ObjectPool<T> {
@@ -231,12 +946,48 @@ impl<T: Send + Sync + 'static> ObjectPool<T> {
     /// let pool = ObjectPool::new(vec![1, 2, 3], config);
     /// assert_eq!(pool.available_count(), 3);
     /// ```
+    ///
+    /// # Trait objects
+    ///
+    /// Objects of different types behind one trait are pooled as
+    /// `Box<dyn Trait + Send + Sync>`. Naming the pool's type lets each
+    /// `Box::new` coerce:
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    ///
+    /// trait Connection {
+    ///     fn backend(&self) -> &str;
+    /// }
+    ///
+    /// struct Postgres;
+    /// struct Sqlite;
+    ///
+    /// impl Connection for Postgres {
+    ///     fn backend(&self) -> &str { "postgres" }
+    /// }
+    /// impl Connection for Sqlite {
+    ///     fn backend(&self) -> &str { "sqlite" }
+    /// }
+    ///
+    /// let pool: ObjectPool<Box<dyn Connection + Send + Sync>> =
+    ///     ObjectPool::new(vec![Box::new(Postgres), Box::new(Sqlite)], PoolConfiguration::default());
+    ///
+    /// let conn = pool.get_object().unwrap();
+    /// assert!(["postgres", "sqlite"].contains(&conn.backend()));
+    /// ```
     pub fn new(objects: Vec<T>, config: PoolConfiguration<T>) -> Self {
         let capacity = objects.len().max(config.max_pool_size);
         assert!(capacity > 0, "ObjectPool capacity must be at least 1");
-        let available = Arc::new(ArrayQueue::new(capacity));
+        let available = Arc::new(IdleQueue::new(
+            capacity,
+            config.partition_buckets,
+            config.partition_fn.clone(),
+        ));
         
-        let eviction_policy = if let Some(ttl) = config.time_to_live {
+        let eviction_policy = if let Some(ref predicate) = config.custom_eviction {
+            EvictionPolicy::Custom(Arc::clone(predicate))
+        } else if let Some(ttl) = config.time_to_live {
             if let Some(idle) = config.idle_timeout {
                 EvictionPolicy::Combined { ttl, idle_timeout: idle }
             } else {
@@ -248,11 +999,22 @@ impl<T: Send + Sync + 'static> ObjectPool<T> {
             EvictionPolicy::None
         };
         
-        let eviction = Arc::new(EvictionTracker::new(eviction_policy));
+        let eviction = Arc::new(
+            EvictionTracker::new(eviction_policy)
+                .with_refresh(config.ttl_refresh)
+                .with_expired_return(config.expired_return)
+                .with_memory(config.size_estimator.clone().map(MemoryAccount::new)),
+        );
         
+        let original_size = Some(objects.len());
+        let population = Arc::new(AtomicUsize::new(objects.len()));
+
         // Add objects to pool; queue is sized to fit all of them, so push cannot fail.
         for (idx, obj) in objects.into_iter().enumerate() {
             eviction.track_object(idx);
+            if let Some(memory) = eviction.memory() {
+                memory.idle(idx, &obj);
+            }
             // Queue is sized to fit all objects; push can only fail if the queue is full,
             // which is impossible here.
             available.push((obj, idx)).unwrap_or_else(|_| {
@@ -260,31 +1022,167 @@ impl<T: Send + Sync + 'static> ObjectPool<T> {
             });
         }
         
+        let events = Arc::new(Reporter::new());
         let circuit_breaker = if config.enable_circuit_breaker {
-            Some(Arc::new(CircuitBreaker::new(
-                config.circuit_breaker_threshold,
-                config.circuit_breaker_timeout,
-            )))
+            let events = Arc::clone(&events);
+            Some(Arc::new(
+                CircuitBreaker::new(config.circuit_breaker_threshold, config.circuit_breaker_timeout)
+                    .with_observer(move |state| {
+                        events.report(match state {
+                            CircuitBreakerState::Open => PoolEvent::CircuitOpened,
+                            CircuitBreakerState::HalfOpen => PoolEvent::CircuitHalfOpen,
+                            CircuitBreakerState::Closed => PoolEvent::CircuitClosed,
+                        });
+                    }),
+            ))
         } else {
             None
         };
         
-        Self {
+        let bulkheads = Arc::new(Bulkheads::new(&config.bulkheads));
+        let creation_throttle = Arc::new(CreationThrottle::new(
+            config.max_creations_per_second,
+            config.creation_backoff(),
+        ));
+        let pending_creations = Arc::new(PendingCreations::new(config.max_pending_creations));
+        let holders = config
+            .track_holders
+            .then(|| Arc::new(Holders::new(config.holder_backtrace_every)));
+        let activity = config.activity_log.map(|capacity| Arc::new(ActivityLog::new(capacity)));
+        let watchdog = config
+            .starvation_threshold
+            .map(|threshold| Arc::new(StarvationWatchdog::new(threshold)));
+        let limits = CapacityLimits {
+            max_pool_size: capacity,
+            ..config.capacity_limits()
+        };
+        let active_count = Arc::new(ActiveSlots::new(limits.active_limit(), config.limit_group.clone()));
+
+        // The `minimal` feature tracks no SLOs on the acquire path.
+        let slo = config
+            .slo
+            .filter(|_| !cfg!(feature = "minimal"))
+            .map(|targets| Arc::new(SloTracker::new(targets)));
+        let config = Arc::new(config);
+        let metrics = Arc::new(MetricsTracker::new().sampled(config.sample_every));
+        let warnings = Arc::new(Reporter::new());
+        let hooks = Arc::new(Hooks::new(
+            Arc::clone(&config),
+            Arc::clone(&metrics.hook_panics),
+            Arc::clone(&warnings),
+        ));
+
+        let mut pool = Self {
             available,
-            active_count: Arc::new(AtomicUsize::new(0)),
-            config: Arc::new(config),
-            metrics: Arc::new(MetricsTracker::new()),
+            active_count,
+            config,
+            metrics,
             eviction,
             circuit_breaker,
             next_id: Arc::new(AtomicUsize::new(capacity)),
             capacity,
-        }
+            population,
+            original_size,
+            return_fn: Arc::new(|_, _, _, _| Ok(())),
+            detach_fn: Arc::new(|_| {}),
+            return_errors: Arc::new(ReturnErrorReporter::new()),
+            released: Arc::new(Notify::new()),
+            bulkheads,
+            external_ids: Arc::new(ExternalIds::default()),
+            creation_throttle,
+            pending_creations,
+            holders,
+            activity,
+            warnings,
+            events,
+            watchdog,
+            slo,
+            waiters: Arc::new(Waiters::default()),
+            expired_streak: Arc::new(AtomicUsize::new(0)),
+            last_failure: Arc::new(Mutex::new(None)),
+            hooks,
+            closed: Arc::new(AtomicBool::new(false)),
+            paused: Arc::new(AtomicBool::new(false)),
+            retiring: Arc::new(AtomicUsize::new(0)),
+        };
+        pool.return_fn = pool.make_return_fn();
+        pool.detach_fn = pool.make_detach_fn();
+        pool
+    }
+
+    /// Create a pool whose initial objects carry caller-supplied ids
+    ///
+    /// The id is stable for the lifetime of the pool and shows up on every
+    /// guard for that object via [`PooledObject::external_id`], so log lines
+    /// about "connection c-42" can be tied to what the pool is doing with
+    /// it. [`external_id`](Self::external_id) translates the internal object
+    /// ids found in [`ReturnError`]s. Objects created later (for example by a
+    /// dynamic pool's factory) have no external id.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the same id is given twice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    ///
+    /// let pool = ObjectPool::new_with_ids(
+    ///     vec![("c-41", "conn A"), ("c-42", "conn B")],
+    ///     PoolConfiguration::default(),
+    /// );
+    ///
+    /// let a = pool.get_object().unwrap();
+    /// let b = pool.get_object().unwrap();
+    /// let mut ids = [a.external_id().unwrap(), b.external_id().unwrap()];
+    /// ids.sort();
+    /// assert_eq!(ids, ["c-41", "c-42"]);
+    /// ```
+    pub fn new_with_ids<I: Into<String>>(objects: Vec<(I, T)>, config: PoolConfiguration<T>) -> Self {
+        let (ids, objects): (Vec<_>, Vec<_>) = objects
+            .into_iter()
+            .map(|(id, obj)| (id.into(), obj))
+            .unzip();
+        let external_ids = Arc::new(ExternalIds::new(ids));
+        let mut pool = Self::new(objects, config);
+        pool.external_ids = external_ids;
+        pool
+    }
+
+    /// External id of the object with internal id `object_id`, if it was
+    /// seeded with one
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration, ReturnError};
+    ///
+    /// let pool = ObjectPool::new_with_ids(
+    ///     vec![("c-7", 1)],
+    ///     PoolConfiguration::new().with_validation(|x: &i32| *x > 0),
+    /// );
+    /// let mut errors = pool.return_errors();
+    ///
+    /// let mut obj = pool.get_object().unwrap();
+    /// *obj = -1;
+    /// drop(obj);
+    ///
+    /// let Ok(ReturnError::ValidationFailed { object_id }) = errors.try_recv() else { panic!() };
+    /// assert_eq!(pool.external_id(object_id), Some("c-7"));
+    /// ```
+    #[must_use]
+    pub fn external_id(&self, object_id: PoolObjectId) -> Option<&str> {
+        self.external_ids.external(object_id.raw()).map(|id| &**id)
     }
     
-    /// Get an object from the pool (non-blocking)
+    /// Get an object from the pool
     ///
-    /// Returns immediately. If no object is available, this returns
-    /// `PoolError::PoolEmpty`.
+    /// By default this returns immediately: if no object is available, it
+    /// returns `PoolError::PoolEmpty`. A pool configured with
+    /// [`with_wait_on_empty`](PoolConfiguration::with_wait_on_empty) blocks
+    /// the calling thread according to its [`WaitPolicy`](crate::WaitPolicy)
+    /// instead.
     ///
     /// Also returns an error if the circuit breaker is open or max-active
     /// limits are reached.
@@ -299,39 +1197,173 @@ impl<T: Send + Sync + 'static> ObjectPool<T> {
     /// assert_eq!(*obj, 42);
     /// ```
     #[must_use = "the pool object must be used or explicitly dropped"]
+    #[track_caller]
     pub fn get_object(&self) -> PoolResult<PooledObject<T>> {
-        self.check_circuit_breaker()?;
-        // Atomically reserve an active slot (enforces max_active_objects without a TOCTOU race).
+        located(self.wait_for(is_pool_empty, || self.acquire_now()))
+    }
+
+    /// Single, non-waiting acquisition attempt.
+    fn acquire_now(&self) -> PoolResult<PooledObject<T>> {
+        self.acquire_idle(self.feeds_acquisition(), None)
+    }
+
+    /// Blocking acquisition for synchronous code that may run on a tokio
+    /// runtime thread
+    ///
+    /// Behaves like [`get_object`](Self::get_object), including the pool's
+    /// [`WaitPolicy`](crate::WaitPolicy), but is safe to call from a sync
+    /// helper invoked by async code. On a multi-threaded runtime the wait is
+    /// wrapped in [`tokio::task::block_in_place`], so other tasks on the
+    /// worker keep running — including the ones that would return an object.
+    /// On a current-thread runtime waiting could never succeed, so a single
+    /// attempt is made instead. Outside a runtime it is `get_object`.
+    ///
+    /// Async code that can `.await` should prefer
+    /// [`get_object_async`](Self::get_object_async).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration, WaitPolicy};
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main(flavor = "multi_thread", worker_threads = 2)]
+    /// # async fn main() {
+    /// let pool = Arc::new(ObjectPool::new(
+    ///     vec![1],
+    ///     PoolConfiguration::new().with_wait_on_empty(WaitPolicy::Wait(Duration::from_secs(5))),
+    /// ));
+    /// let held = pool.get_object().unwrap();
+    /// tokio::spawn(async move {
+    ///     tokio::time::sleep(Duration::from_millis(10)).await;
+    ///     drop(held);
+    /// });
+    ///
+    /// // Blocks this worker only; the spawned task still runs and returns the object.
+    /// let obj = pool.get_object_blocking_in_place().unwrap();
+    /// assert_eq!(*obj, 1);
+    /// # }
+    /// ```
+    #[must_use = "the pool object must be used or explicitly dropped"]
+    #[track_caller]
+    pub fn get_object_blocking_in_place(&self) -> PoolResult<PooledObject<T>> {
+        located(self.wait_in_place(is_pool_empty, || self.acquire_now()))
+    }
+
+    /// Get an object on behalf of the caller described by `ctx`
+    ///
+    /// Like [`get_object`](Self::get_object), but `ctx` is passed to the
+    /// [`on_acquire`](PoolConfiguration::with_on_acquire) hook and attached
+    /// to the guard. A deadline in `ctx` caps any waiting: once it passes,
+    /// the call fails with `PoolError::Timeout`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{AcquireContext, ObjectPool, PoolConfiguration, PoolError, WaitPolicy};
+    /// use std::time::Duration;
+    ///
+    /// let pool = ObjectPool::new(
+    ///     vec![1],
+    ///     PoolConfiguration::new().with_wait_on_empty(WaitPolicy::WaitForever),
+    /// );
+    /// let _held = pool.get_object().unwrap();
+    ///
+    /// let ctx = AcquireContext::new().with_timeout(Duration::from_millis(20));
+    /// assert!(matches!(pool.get_object_with(ctx), Err(PoolError::Timeout(..))));
+    /// ```
+    #[must_use = "the pool object must be used or explicitly dropped"]
+    #[track_caller]
+    pub fn get_object_with(&self, ctx: AcquireContext) -> PoolResult<PooledObject<T>> {
+        let policy = ctx.limit_wait(self.config.blocking_wait_policy());
+        let ctx = Arc::new(ctx);
+        let result = self.wait_with(policy, is_pool_empty, || {
+            self.acquire_idle(self.feeds_acquisition(), Some(&ctx))
+        });
+        located(self.track_caller(&ctx, result))
+    }
+
+    /// Async counterpart of [`get_object_with`](Self::get_object_with)
+    #[track_caller]
+    pub fn get_object_with_async(&self, ctx: AcquireContext) -> Acquire<'_, T> {
+        Acquire::new(async move {
+            let policy = ctx.limit_wait(self.config.async_wait_policy());
+            let ctx = Arc::new(ctx);
+            let result = self
+                .wait_with_async(policy, is_pool_empty, || {
+                    self.acquire_idle(self.feeds_acquisition(), Some(&ctx))
+                })
+                .await;
+            self.track_caller(&ctx, result)
+        })
+    }
+
+    /// Count a labelled acquisition in the per-caller metrics, if enabled.
+    fn track_caller(
+        &self,
+        ctx: &AcquireContext,
+        result: PoolResult<PooledObject<T>>,
+    ) -> PoolResult<PooledObject<T>> {
+        let Some(label) = ctx.label().filter(|_| self.config.caller_metrics) else {
+            return result;
+        };
+        let stats = self.metrics.caller(label);
+        match result {
+            Ok(mut obj) => {
+                stats.acquisitions.fetch_add(1, Ordering::Relaxed);
+                obj.hold = Some(HoldTimer::start(stats));
+                Ok(obj)
+            }
+            Err(err) => {
+                if matches!(err, PoolError::Timeout(..)) {
+                    stats.timeouts.fetch_add(1, Ordering::Relaxed);
+                }
+                Err(err)
+            }
+        }
+    }
+
+    /// Take an idle object. With `feed_breaker` unset, finding the pool empty
+    /// or not does not count toward the circuit breaker — used when the
+    /// caller reports the outcome of the work done with the object instead.
+    fn acquire_idle(&self, feed_breaker: bool, ctx: Option<&Arc<AcquireContext>>) -> PoolResult<PooledObject<T>> {
+        self.check_open()?;
+        self.check_circuit_breaker()?;
+        // Atomically reserve an active slot (enforces max_active_objects without a TOCTOU race).
         self.try_acquire_active_slot()?;
 
         // Try to get available object
         loop {
-            match self.available.pop() {
+            match self.pop_idle() {
                 Some((obj, id)) => {
                     // Check if expired
                     if self.eviction.is_expired(id) {
-                        self.eviction.remove_object(id);
+                        self.evict(obj, id);
+                        self.expired_streak.fetch_add(1, Ordering::Relaxed);
                         continue;
                     }
                     
-                    self.eviction.touch_object(id);
+                    self.eviction.checked_out(id);
                     self.metrics.total_retrieved.fetch_add(1, Ordering::Relaxed);
+                    self.expired_streak.store(0, Ordering::Relaxed);
 
-                    if let Some(ref cb) = self.circuit_breaker {
-                        cb.record_success();
+                    if feed_breaker {
+                        self.record_outcome(true);
                     }
                     
-                    let return_fn = self.make_return_fn();
-                    let detach_fn = self.make_detach_fn();
-                    return Ok(PooledObject::new(obj, id, return_fn, detach_fn));
+                    return self.wrap(obj, id, ctx);
                 }
                 None => {
                     // Release the slot we reserved — no object was obtained.
-                    self.active_count.fetch_sub(1, Ordering::AcqRel);
+                    self.active_count.release(1);
                     self.metrics.pool_empty_events.fetch_add(1, Ordering::Relaxed);
+                    if self.metrics.event_sampler.sample() {
+                        self.events.report(PoolEvent::Empty);
+                    }
 
-                    if let Some(ref cb) = self.circuit_breaker {
-                        cb.record_failure();
+                    if feed_breaker {
+                        self.record_outcome(false);
                     }
                     
                     return Err(PoolError::PoolEmpty);
@@ -340,6 +1372,101 @@ impl<T: Send + Sync + 'static> ObjectPool<T> {
         }
     }
     
+    /// Next idle object to hand out: the longest idle one, or the
+    /// best-scoring of the next few under a selection function.
+    fn pop_idle(&self) -> Option<(T, usize)> {
+        let Some(score) = &self.config.selection else {
+            return self.available.pop();
+        };
+        let mut best = Best::new();
+        for _ in 0..self.config.selection_window.min(self.available.len()).max(1) {
+            let Some((obj, id)) = self.available.pop() else {
+                break;
+            };
+            if self.eviction.is_expired(id) {
+                self.evict(obj, id);
+                self.expired_streak.fetch_add(1, Ordering::Relaxed);
+                continue;
+            }
+            let health = self.eviction.user_meta().get::<Health>(id).map_or(Health::DEFAULT, |h| h.0);
+            let candidate = score(&obj, health);
+            if let Some(loser) = best.offer((obj, id), candidate)
+                && let Err((obj, id)) = Self::push_available_with_retry(&self.available, loser)
+            {
+                self.discard_overflow(obj, id);
+            }
+        }
+        best.into_inner()
+    }
+
+    /// Get an object on behalf of a bulkhead `category`
+    ///
+    /// Works like [`get_object`](Self::get_object), but the object also
+    /// counts against the limit configured for `category` with
+    /// [`with_bulkhead`](PoolConfiguration::with_bulkhead) until it is
+    /// returned. Fails with `PoolError::BulkheadFull` when the category is
+    /// at its limit and `PoolError::UnknownBulkhead` when no bulkhead was
+    /// configured for it. A full bulkhead is never waited on, and the
+    /// category's slot is only taken once an object is in hand, so waiting
+    /// for the pool does not hold it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration, PoolError};
+    ///
+    /// let pool = ObjectPool::new(
+    ///     vec![1, 2, 3],
+    ///     PoolConfiguration::new().with_bulkhead("reports", 1),
+    /// );
+    ///
+    /// let report = pool.get_object_in("reports").unwrap();
+    /// assert!(matches!(pool.get_object_in("reports"), Err(PoolError::BulkheadFull(_))));
+    /// assert_eq!(pool.bulkhead_active("reports"), Some(1));
+    ///
+    /// drop(report);
+    /// assert!(pool.get_object_in("reports").is_ok());
+    /// ```
+    #[must_use = "the pool object must be used or explicitly dropped"]
+    #[track_caller]
+    pub fn get_object_in(&self, category: &str) -> PoolResult<PooledObject<T>> {
+        self.bulkheads.check(category)?;
+        let mut obj = self.get_object()?;
+        // Dropping `obj` hands it back if the category filled up meanwhile.
+        obj.permit = Some(self.bulkheads.enter(category)?);
+        Ok(obj)
+    }
+
+    /// Async counterpart of [`get_object_in`](Self::get_object_in)
+    #[track_caller]
+    pub fn get_object_in_async<'a>(&'a self, category: &'a str) -> Acquire<'a, T> {
+        let acquire = self.get_object_async();
+        Acquire::new(async move {
+            self.bulkheads.check(category)?;
+            let mut obj = acquire.await?;
+            obj.permit = Some(self.bulkheads.enter(category)?);
+            Ok(obj)
+        })
+    }
+
+    /// Number of objects currently checked out under bulkhead `category`,
+    /// or `None` if no such bulkhead is configured
+    #[must_use]
+    pub fn bulkhead_active(&self, category: &str) -> Option<usize> {
+        self.bulkheads.active(category)
+    }
+
+    /// Every checked-out object with its hold time and holder, longest hold
+    /// first
+    ///
+    /// Empty unless enabled with
+    /// [`with_holder_tracking`](PoolConfiguration::with_holder_tracking).
+    /// See [`Holder`] for an example.
+    #[must_use]
+    pub fn current_holders(&self) -> Vec<Holder> {
+        self.holders.as_ref().map(|holders| holders.snapshot()).unwrap_or_default()
+    }
+
     /// Try to get an object without throwing an error for an empty pool
     ///
     /// Returns `Ok(None)` if pool is empty.
@@ -361,53 +1488,235 @@ impl<T: Send + Sync + 'static> ObjectPool<T> {
     /// assert!(obj2.is_none()); // Pool empty
     /// ```
     #[must_use = "check Ok(None) to detect empty pool"]
+    #[track_caller]
     pub fn try_get_object(&self) -> PoolResult<Option<PooledObject<T>>> {
-        match self.get_object() {
+        match located(self.acquire_now()) {
             Ok(obj) => Ok(Some(obj)),
             Err(PoolError::PoolEmpty) => Ok(None),
             Err(err) => Err(err),
         }
     }
+
+    /// Take an idle object without locking, allocating or waiting
+    ///
+    /// Meant for contexts such as real-time audio callbacks, where the
+    /// regular acquisition path is off limits: it may lock the circuit
+    /// breaker, eviction and holder tables, allocate the context hook's
+    /// correlation id and publish events. This path touches only atomics
+    /// and the lock-free idle queue (a `Mutex`-guarded queue under Miri or
+    /// with the `mutex-internals` feature, where it does take a lock), and
+    /// returns `None` instead of an error whenever the pool cannot hand out
+    /// an object that way:
+    ///
+    /// - the pool is closed, paused, empty or at its active-object or limit
+    ///   group cap (counted in the metrics like any other attempt, but no
+    ///   [`PoolEvent`] is published);
+    /// - the pool is configured with a feature that needs locks, as
+    ///   reported by [`supports_fast_path`](Self::supports_fast_path): a
+    ///   circuit breaker, an eviction policy, holder tracking, an activity
+    ///   log, memory accounting, or an
+    ///   [`invalidate_all`](Self::invalidate_all) having run.
+    ///
+    /// The `on_borrow` and `on_acquire` hooks still run, so they must be
+    /// real-time safe themselves. The context hook is skipped, leaving
+    /// [`PooledObject::context`] empty. Returning the object, by dropping
+    /// the guard, takes the regular return path and may lock; drop it
+    /// outside the real-time context when that matters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    ///
+    /// let pool = ObjectPool::new(vec![[0.0f32; 64]], PoolConfiguration::default());
+    /// assert!(pool.supports_fast_path());
+    ///
+    /// let buffer = pool.try_get_object_fast().unwrap();
+    /// assert!(pool.try_get_object_fast().is_none()); // Pool empty
+    /// drop(buffer);
+    /// ```
+    #[must_use = "the pool object must be used or explicitly dropped"]
+    #[track_caller]
+    pub fn try_get_object_fast(&self) -> Option<PooledObject<T>> {
+        if !self.supports_fast_path() || self.check_open().is_err() {
+            return None;
+        }
+        if let Err(refusal) = self.active_count.reserve() {
+            if refusal == Refusal::Group {
+                self.metrics.group_limit_rejections.fetch_add(1, Ordering::Relaxed);
+            }
+            return None;
+        }
+        let Some((obj, id)) = self.available.pop() else {
+            self.active_count.release(1);
+            self.metrics.pool_empty_events.fetch_add(1, Ordering::Relaxed);
+            return None;
+        };
+        self.metrics.total_retrieved.fetch_add(1, Ordering::Relaxed);
+        self.expired_streak.store(0, Ordering::Relaxed);
+        let location = Location::caller();
+        self.guard(obj, id, None).ok().map(|obj| obj.located(location))
+    }
+
+    /// Whether [`try_get_object_fast`](Self::try_get_object_fast) can hand
+    /// out objects: the pool has no circuit breaker, eviction policy,
+    /// holder tracking, activity log or memory accounting, and has never
+    /// been invalidated
+    #[must_use]
+    pub fn supports_fast_path(&self) -> bool {
+        self.circuit_breaker.is_none()
+            && matches!(self.eviction.policy(), EvictionPolicy::None)
+            && self.eviction.epoch() == 0
+            && self.holders.is_none()
+            && self.activity.is_none()
+            && self.eviction.memory().is_none()
+    }
     
+    /// Get an object with a lease of `max_hold`
+    ///
+    /// The returned guard carries a [`Lease`] (see [`PooledObject::lease`]).
+    /// When the lease elapses the holder is not interrupted; the lease is
+    /// flagged as expired and counted in
+    /// [`PoolMetrics::expired_leases`](crate::PoolMetrics::expired_leases).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    /// use std::time::Duration;
+    ///
+    /// let pool = ObjectPool::new(vec![1], PoolConfiguration::default());
+    /// let obj = pool.get_object_leased(Duration::from_millis(1)).unwrap();
+    ///
+    /// std::thread::sleep(Duration::from_millis(5));
+    /// assert!(obj.lease().unwrap().expired());
+    /// drop(obj);
+    /// assert_eq!(pool.get_metrics().expired_leases, 1);
+    /// ```
+    #[must_use = "the pool object must be used or explicitly dropped"]
+    #[track_caller]
+    pub fn get_object_leased(&self, max_hold: Duration) -> PoolResult<PooledObject<T>> {
+        let obj = self.get_object()?;
+        Ok(self.attach_lease(obj, max_hold, None))
+    }
+
+    /// Get an object with a lease of `max_hold`, calling `on_expire` once if
+    /// the lease elapses while the object is still checked out.
+    ///
+    /// The callback is driven by a timer on the current tokio runtime, or by
+    /// a helper thread when called outside a runtime. It runs at most once
+    /// and never after the object has been returned.
+    #[must_use = "the pool object must be used or explicitly dropped"]
+    #[track_caller]
+    pub fn get_object_leased_with<F>(&self, max_hold: Duration, on_expire: F) -> PoolResult<PooledObject<T>>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let obj = self.get_object()?;
+        Ok(self.attach_lease(obj, max_hold, Some(Box::new(on_expire))))
+    }
+
     /// Get an object asynchronously with timeout
-    pub async fn get_object_async(&self) -> PoolResult<PooledObject<T>> {
-        let timeout = self.config.operation_timeout.unwrap_or(Duration::from_secs(30));
-        
-        tokio::time::timeout(timeout, async {
-            let mut attempt: u64 = 0;
-            loop {
-                match self.try_get_object() {
-                    Ok(Some(obj)) => return Ok(obj),
-                    Ok(None) => {
-                        // Small jitter (5–20 ms) avoids a thundering-herd wake-up.
-                        let delay = 5 + (attempt % 4) * 5;
-                        tokio::time::sleep(Duration::from_millis(delay)).await;
-                        attempt = attempt.wrapping_add(1);
-                    }
-                    Err(err) => return Err(err),
-                }
-            }
-        })
-        .await
-        .map_err(|_| PoolError::Timeout(timeout))?
+    ///
+    /// Waits for up to the operation timeout by default, or as dictated by
+    /// the pool's [`WaitPolicy`](crate::WaitPolicy) when one is configured.
+    ///
+    /// # Cancel safety
+    ///
+    /// The future is cancel safe, as are all async acquisition methods. An
+    /// object is only taken from the pool in the poll that completes the
+    /// future, so dropping it mid-wait (losing a `select!`, a caller-side
+    /// timeout, an aborted task) never strands one. The dropped acquisition
+    /// stops counting towards [`waiting_count`](Self::waiting_count) at once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    /// use std::time::Duration;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let pool = ObjectPool::new(vec![1], PoolConfiguration::default());
+    /// let held = pool.get_object().unwrap();
+    ///
+    /// tokio::select! {
+    ///     _ = pool.get_object_async() => unreachable!(),
+    ///     _ = tokio::time::sleep(Duration::from_millis(10)) => {}
+    /// }
+    /// assert_eq!(pool.waiting_count(), 0);
+    ///
+    /// drop(held);
+    /// assert_eq!(pool.available_count(), 1);
+    /// # }
+    /// ```
+    #[track_caller]
+    pub fn get_object_async(&self) -> Acquire<'_, T> {
+        Acquire::new(self.wait_for_async(is_pool_empty, || self.acquire_now()))
     }
     
     /// Try to get an object asynchronously
     pub async fn try_get_object_async(&self) -> PoolResult<Option<PooledObject<T>>> {
         self.try_get_object()
     }
+
+    /// Acquire objects as a [`Stream`](futures_core::Stream).
+    ///
+    /// Each item is obtained via [`get_object_async`](Self::get_object_async),
+    /// so all pool limits apply. The stream keeps waiting across operation
+    /// timeouts and ends on any other acquisition error (for example
+    /// `MaxActiveObjectsReached` or `CircuitBreakerOpen`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    /// use futures::StreamExt;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+    ///
+    /// let mut stream = pool.acquire_stream();
+    /// while let Some(obj) = stream.next().await {
+    ///     println!("processing with {}", *obj);
+    ///     # break;
+    /// }
+    /// # }
+    /// ```
+    #[track_caller]
+    pub fn acquire_stream(&self) -> AcquireStream<'_, T> {
+        AcquireStream::new(move || self.get_object_async())
+    }
     
     /// Get health status
     #[must_use]
     pub fn get_health_status(&self) -> HealthStatus {
         let available = self.available.len();
-        let active = self.active_count.load(Ordering::Relaxed);
+        let active = self.active_count.load();
         let cb_open = self
             .circuit_breaker
             .as_ref()
             .map(|cb| matches!(cb.state(), CircuitBreakerState::Open))
             .unwrap_or(false);
-        HealthStatus::new(available, active, self.capacity, cb_open)
+        let mut health = HealthStatus::new(available, active, self.capacity, cb_open);
+        if let Some(original) = self.expected_size() {
+            health.warn_on_capacity_loss(
+                self.population.load(Ordering::Acquire),
+                original,
+                self.config.capacity_loss_warning,
+            );
+        }
+        if let Some((churn, threshold)) = self.hooks.churn() {
+            health.warn_on_churn(churn.replaced(), threshold);
+        }
+        if let (Some(memory), Some(cap)) = (self.eviction.memory(), self.config.retained_memory_cap) {
+            health.warn_on_retained_memory(memory.usage().idle, cap);
+        }
+        if self.is_paused() {
+            health.mark_paused();
+        }
+        health
     }
     
     /// Export metrics as a key-value map
@@ -431,11 +1740,20 @@ impl<T: Send + Sync + 'static> ObjectPool<T> {
     /// Get pool metrics
     #[must_use]
     pub fn get_metrics(&self) -> PoolMetrics {
-        self.metrics.get_metrics(
-            self.active_count.load(Ordering::Relaxed),
+        let population = self.population.load(Ordering::Acquire);
+        let mut metrics = self.metrics.get_metrics(
+            self.active_count.load(),
             self.available.len(),
             self.capacity,
-        )
+            population,
+            self.expected_size().map_or(0, |expected| expected.saturating_sub(population)),
+            self.is_paused(),
+        );
+        if let Some(memory) = self.eviction.memory() {
+            let usage = memory.usage();
+            (metrics.idle_bytes, metrics.active_bytes) = (usage.idle, usage.active);
+        }
+        metrics
     }
     
     /// Number of objects currently available in the queue
@@ -447,7 +1765,7 @@ impl<T: Send + Sync + 'static> ObjectPool<T> {
     /// Number of objects currently checked out
     #[must_use]
     pub fn active_count(&self) -> usize {
-        self.active_count.load(Ordering::Relaxed)
+        self.active_count.load()
     }
 
     /// Maximum number of objects this pool can hold (set at construction time)
@@ -456,6 +1774,175 @@ impl<T: Send + Sync + 'static> ObjectPool<T> {
         self.capacity
     }
 
+    /// Number of acquisitions currently waiting for an object
+    ///
+    /// Counts blocking and async acquisitions from their first failed
+    /// attempt until they succeed, give up or are dropped.
+    #[must_use]
+    pub fn waiting_count(&self) -> usize {
+        self.waiters.count()
+    }
+
+    /// Recommended pool size, warm-up and timeout based on what the pool
+    /// has observed so far, or `None` until it has served enough
+    /// acquisitions to judge
+    ///
+    /// See [`PoolTuningAdvice`] for the rules and an example.
+    #[must_use]
+    pub fn tuning_advice(&self) -> Option<PoolTuningAdvice> {
+        self.advise(self.population.load(Ordering::Acquire))
+    }
+
+    /// Tuning advice for a pool that can hand out `size` objects.
+    fn advise(&self, size: usize) -> Option<PoolTuningAdvice> {
+        tuning::advise(&Observed {
+            size,
+            peak_active: self.metrics.peak_active.load(Ordering::Relaxed),
+            acquisitions: self.metrics.total_retrieved.load(Ordering::Relaxed),
+            empty_events: self.metrics.pool_empty_events.load(Ordering::Relaxed),
+            warmup: self.config.warmup_size.unwrap_or(0),
+            wait_times: &self.metrics.wait_times,
+            hold_times: &self.metrics.hold_times,
+        })
+    }
+
+    /// Error budgets of the objectives set with
+    /// [`with_slo`](PoolConfiguration::with_slo), or `None` without them
+    ///
+    /// See [`SloTargets`](crate::SloTargets) for an example.
+    #[must_use]
+    pub fn slo_status(&self) -> Option<SloStatus> {
+        self.slo.as_ref().map(|slo| slo.status())
+    }
+
+    /// Why the most recent failed acquisition attempt failed, or `None` if
+    /// none has
+    ///
+    /// Lists the [`Gate`]s that turned the attempt away — limits, an empty
+    /// pool, the circuit breaker, expired objects being evicted as they were
+    /// found — with the pool's counts at that moment. See
+    /// [`FailureDiagnosis`] for an example.
+    #[must_use]
+    pub fn explain_last_failure(&self) -> Option<FailureDiagnosis> {
+        self.last_failure.lock().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    /// The most recent acquisitions, returns and evictions, oldest first
+    ///
+    /// Empty unless enabled with
+    /// [`with_activity_log`](PoolConfiguration::with_activity_log), which
+    /// also sets how many are kept. See [`ActivityRecord`] for an example.
+    #[must_use]
+    pub fn recent_activity(&self) -> Vec<ActivityRecord> {
+        self.activity.as_ref().map(|activity| activity.snapshot()).unwrap_or_default()
+    }
+
+    /// Whether [`get_object`](Self::get_object) would succeed right now
+    /// without waiting
+    ///
+    /// `false` while the pool is closed, its circuit breaker is open, an
+    /// active-object limit is reached or no object is idle. For
+    /// readiness-driven callers such as load balancers; see also
+    /// `PoolService` behind the `tower` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    ///
+    /// let pool = ObjectPool::new(vec![1], PoolConfiguration::default());
+    /// assert!(pool.is_ready());
+    ///
+    /// let _held = pool.get_object().unwrap();
+    /// assert!(!pool.is_ready());
+    /// ```
+    #[must_use]
+    pub fn is_ready(&self) -> bool {
+        matches!(self.readiness(false), Readiness::Ready)
+    }
+
+    /// How saturated the pool is, for picking the least loaded of several
+    ///
+    /// The share of the pool's objects that are checked out, from 0.0 to
+    /// 1.0, counted against `max_active_objects` when that is lower.
+    /// Infinite while the pool is closed or its circuit breaker is open.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    ///
+    /// let pool = ObjectPool::new(vec![1, 2, 3, 4], PoolConfiguration::default());
+    /// let _held = pool.get_object().unwrap();
+    /// assert_eq!(pool.load(), 0.25);
+    ///
+    /// pool.shutdown();
+    /// assert_eq!(pool.load(), f64::INFINITY);
+    /// ```
+    #[must_use]
+    pub fn load(&self) -> f64 {
+        self.load_over(self.population.load(Ordering::Acquire))
+    }
+
+    /// [`load`](Self::load) with `owned` objects counted as the pool's size.
+    fn load_over(&self, owned: usize) -> f64 {
+        if self.is_closed() || self.is_paused() || self.breaker_blocks().is_some() {
+            return f64::INFINITY;
+        }
+        let limit = self.config.max_active_objects.map_or(owned, |max| max.min(owned));
+        self.active_count.load() as f64 / limit.max(1) as f64
+    }
+
+    /// Whether an acquisition would find an object now; `can_create` says
+    /// whether an empty pool may create one.
+    pub(crate) fn readiness(&self, can_create: bool) -> Readiness {
+        if self.is_closed() {
+            Readiness::Closed
+        } else if let Some(wait) = self.breaker_blocks() {
+            Readiness::BreakerOpen(wait)
+        } else if !self.is_paused() && self.active_count.has_room() && (can_create || self.available.len() > 0) {
+            Readiness::Ready
+        } else {
+            Readiness::Busy
+        }
+    }
+
+    /// Signalled whenever an object is returned or leaves the pool.
+    pub(crate) fn released(&self) -> Arc<Notify> {
+        Arc::clone(&self.released)
+    }
+
+    /// How long until the circuit breaker lets acquisitions through, if it
+    /// currently refuses them.
+    fn breaker_blocks(&self) -> Option<Duration> {
+        let cb = self.circuit_breaker.as_ref()?;
+        if cb.allow_request() {
+            return None;
+        }
+        Some(cb.retry_after().unwrap_or_default())
+    }
+
+    /// The policy deciding when idle objects expire, derived from
+    /// [`with_ttl`](PoolConfiguration::with_ttl) and
+    /// [`with_idle_timeout`](PoolConfiguration::with_idle_timeout)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{EvictionPolicy, ObjectPool, PoolConfiguration};
+    /// use std::time::Duration;
+    ///
+    /// let pool = ObjectPool::new(
+    ///     vec![1],
+    ///     PoolConfiguration::new().with_idle_timeout(Duration::from_secs(30)),
+    /// );
+    /// assert!(matches!(pool.eviction_policy(), EvictionPolicy::IdleTimeout(_)));
+    /// ```
+    #[must_use]
+    pub fn eviction_policy(&self) -> &EvictionPolicy {
+        self.eviction.policy()
+    }
+
     /// Proactively remove all expired objects from the available queue.
     ///
     /// Returns the number of objects evicted. Call this periodically (e.g. from a
@@ -468,7 +1955,7 @@ impl<T: Send + Sync + 'static> ObjectPool<T> {
 
         while let Some((obj, id)) = self.available.pop() {
             if self.eviction.is_expired(id) {
-                self.eviction.remove_object(id);
+                self.evict(obj, id);
                 evicted += 1;
             } else {
                 keep.push((obj, id));
@@ -476,1655 +1963,8490 @@ impl<T: Send + Sync + 'static> ObjectPool<T> {
         }
 
         for item in keep {
-            if Self::push_available_with_retry(&self.available, item).is_err() {
+            if let Err((obj, failed_id)) = Self::push_available_with_retry(&self.available, item) {
                 // Queue unexpectedly full (concurrent returns filled it while we
                 // were scanning). Track this as a push failure — NOT as an eviction.
-                self.metrics.queue_push_failures.fetch_add(1, Ordering::Relaxed);
+                self.discard_overflow(obj, failed_id);
             }
         }
 
         evicted
     }
 
-    /// Drain all *available* (not currently checked-out) objects from the pool
-    /// and return them. Active objects are unaffected.
+    /// Self-test: examine every idle object once
     ///
-    /// Useful for graceful shutdown: drain the pool, then wait for active
-    /// `PooledObject`s to be dropped (which will attempt to return to an empty
-    /// queue; those objects are silently discarded).
-    #[must_use = "returns the drained objects"]
-    pub fn drain(&self) -> Vec<T> {
-        let mut objects = Vec::new();
-        while let Some((obj, id)) = self.available.pop() {
-            self.eviction.remove_object(id);
-            objects.push(obj);
+    /// Meant as a boot-time check before declaring a service ready. Each
+    /// object that is idle when the check starts is taken out, run through
+    /// the configured validator and put back; objects that fail validation
+    /// or have expired are removed from the pool. Acquisitions may proceed
+    /// concurrently. See [`VerifyReport`](crate::VerifyReport) for the
+    /// details reported.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    ///
+    /// let pool = ObjectPool::new(
+    ///     vec![1, 2, 3],
+    ///     PoolConfiguration::new().with_validation(|x: &i32| *x > 0),
+    /// );
+    ///
+    /// let report = pool.verify();
+    /// assert!(report.is_ok());
+    /// assert_eq!(report.passed, 3);
+    /// ```
+    #[must_use = "the report says whether the pool is healthy"]
+    pub fn verify(&self) -> VerifyReport {
+        let mut report = VerifyReport::default();
+        for bucket in 0..self.available.bucket_count() {
+            for _ in 0..self.available.bucket_len(bucket) {
+                if !self.verify_next(bucket, &mut report) {
+                    break;
+                }
+            }
         }
-        objects
+        report
     }
 
-    /// Record a circuit-breaker success from an external caller (used by
-    /// `DynamicObjectPool` to offset the failure recorded when the inner queue
-    /// was empty but the request was ultimately served via dynamic creation).
-    pub(crate) fn record_circuit_breaker_success(&self) {
-        if let Some(ref cb) = self.circuit_breaker {
-            cb.record_success();
+    /// Async counterpart of [`verify`](Self::verify), yielding to the
+    /// runtime between objects.
+    #[must_use = "the report says whether the pool is healthy"]
+    pub async fn verify_async(&self) -> VerifyReport {
+        let mut report = VerifyReport::default();
+        for bucket in 0..self.available.bucket_count() {
+            for _ in 0..self.available.bucket_len(bucket) {
+                if !self.verify_next(bucket, &mut report) {
+                    break;
+                }
+                tokio::task::yield_now().await;
+            }
         }
+        report
     }
 
-    fn check_circuit_breaker(&self) -> PoolResult<()> {
-        if let Some(ref cb) = self.circuit_breaker
-            && !cb.allow_request()
-        {
-            return Err(PoolError::CircuitBreakerOpen);
+    /// Check that the pool's accounting adds up
+    ///
+    /// Verifies that every object the pool owns is idle, checked out or
+    /// being created, that the population stays within capacity, that
+    /// holder tracking lists every checked-out object, and that the
+    /// per-object tables — eviction metadata, epochs, size estimates,
+    /// attached metadata — match the population. Nothing is changed. Meant
+    /// for tests and for assurance after resizing, draining or invalidating
+    /// a pool; run it while the pool is otherwise idle. See
+    /// [`ConsistencyReport`] for an example.
+    #[must_use = "the report says whether the accounting is consistent"]
+    pub fn check_consistency(&self) -> ConsistencyReport {
+        let mut report = ConsistencyReport {
+            population: self.population.load(Ordering::Acquire),
+            available: self.available.len(),
+            active: self.active_count.load(),
+            pending: self.pending_creations.in_flight(),
+            capacity: self.capacity,
+            issues: Vec::new(),
+        };
+        report.check_counts();
+        if let Some(ref holders) = self.holders {
+            let (holding, active) = (holders.snapshot().len(), report.active);
+            report.check(holding == active, || format!("{holding} holders, {active} active"));
         }
-        Ok(())
+        self.eviction.check_tables(&mut report);
+        report
     }
 
-    /// Atomically reserve an active slot.
-    ///
-    /// When `max_active_objects` is set this uses a CAS loop so that the
-    /// check-and-increment is a single atomic operation — eliminating the TOCTOU
-    /// race that existed when `active.len() >= max` was checked separately from
-    /// the subsequent increment.
-    fn try_acquire_active_slot(&self) -> PoolResult<()> {
-        match self.config.max_active_objects {
-            Some(max) => {
-                let mut current = self.active_count.load(Ordering::Acquire);
-                loop {
-                    if current >= max {
-                        return Err(PoolError::MaxActiveObjectsReached);
-                    }
-                    match self.active_count.compare_exchange_weak(
-                        current,
-                        current + 1,
-                        Ordering::AcqRel,
-                        Ordering::Acquire,
-                    ) {
-                        Ok(_) => return Ok(()),
-                        Err(actual) => current = actual,
-                    }
-                }
-            }
-            None => {
-                self.active_count.fetch_add(1, Ordering::AcqRel);
-                Ok(())
-            }
-        }
-    }
-    
-    fn make_return_fn(&self) -> Arc<dyn Fn(T, usize) + Send + Sync> {
-        let available = Arc::clone(&self.available);
-        let active_count = Arc::clone(&self.active_count);
-        let metrics = Arc::clone(&self.metrics);
-        let eviction = Arc::clone(&self.eviction);
-        let config = Arc::clone(&self.config);
-        
-        Arc::new(move |obj, id| {
-            // Validate if configured
-            if config.validate_on_return
-                && let Some(validate) = config.validation_function
-                && !validate(&obj)
-            {
-                metrics.validation_failures.fetch_add(1, Ordering::Relaxed);
-                active_count.fetch_sub(1, Ordering::AcqRel);
-                eviction.remove_object(id);
-                return;
+    /// Check the next idle object of `bucket`; `false` once it is empty.
+    fn verify_next(&self, bucket: usize, report: &mut VerifyReport) -> bool {
+        let Some((obj, id)) = self.available.pop_bucket(bucket) else {
+            return false;
+        };
+        report.checked += 1;
+
+        if self.eviction.is_expired(id) {
+            self.evict(obj, id);
+            report.expired.push(id);
+        } else if !self.hooks.is_valid(&obj) {
+            self.metrics.validation_failures.fetch_add(1, Ordering::Relaxed);
+            self.eviction.remove_object(id);
+            self.destroy(obj);
+            report.failed_validation.push(id);
+        } else {
+            if self.hooks.validates() {
+                self.eviction.validated(id);
             }
-            
-            eviction.touch_object(id);
-            active_count.fetch_sub(1, Ordering::AcqRel);
-            match ObjectPool::<T>::push_available_with_retry(available.as_ref(), (obj, id)) {
-                Ok(()) => {
-                    metrics.total_returned.fetch_add(1, Ordering::Relaxed);
-                }
-                Err((_obj, failed_id)) => {
-                    metrics.queue_push_failures.fetch_add(1, Ordering::Relaxed);
-                    eviction.remove_object(failed_id);
+            match Self::push_available_with_retry(&self.available, (obj, id)) {
+                Ok(()) => report.passed += 1,
+                Err((obj, failed_id)) => {
+                    self.discard_overflow(obj, failed_id);
+                    report.overflowed.push(failed_id);
                 }
             }
-        })
+        }
+        true
     }
 
-    fn make_detach_fn(&self) -> Arc<dyn Fn(usize) + Send + Sync> {
-        let active_count = Arc::clone(&self.active_count);
-        let eviction = Arc::clone(&self.eviction);
-        let metrics = Arc::clone(&self.metrics);
-
-        Arc::new(move |id| {
-            active_count.fetch_sub(1, Ordering::AcqRel);
-            eviction.remove_object(id);
-            metrics.total_detached.fetch_add(1, Ordering::Relaxed);
-        })
+    /// Subscribe to reports of objects discarded on their way back into the
+    /// pool (validation failures and queue overflows).
+    ///
+    /// Each receiver sees every report published after it subscribed. A
+    /// receiver that falls more than 256 reports behind gets
+    /// `RecvError::Lagged` and skips ahead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration, ReturnError};
+    ///
+    /// let pool = ObjectPool::new(vec![1], PoolConfiguration::new().with_max_pool_size(1));
+    /// let mut errors = pool.return_errors();
+    ///
+    /// let obj = pool.get_object().unwrap();
+    /// drop(obj);
+    /// assert!(errors.try_recv().is_err()); // returned normally, nothing to report
+    /// ```
+    #[must_use]
+    pub fn return_errors(&self) -> tokio::sync::broadcast::Receiver<ReturnError> {
+        self.return_errors.subscribe()
     }
 
-    fn push_available_with_retry(
-        available: &ArrayQueue<(T, usize)>,
-        mut item: (T, usize),
-    ) -> Result<(), (T, usize)> {
-        for _ in 0..Self::PUSH_RETRY_LIMIT {
-            match available.push(item) {
-                Ok(()) => return Ok(()),
-                Err(returned_item) => {
-                    item = returned_item;
-                    std::thread::yield_now();
-                }
-            }
-        }
-
-        Err(item)
+    /// Subscribe to warnings about the pool's condition
+    ///
+    /// Warnings are only raised by opt-in detectors such as
+    /// [`with_starvation_detection`](PoolConfiguration::with_starvation_detection).
+    /// See [`PoolWarning`] for an example.
+    #[must_use]
+    pub fn warnings(&self) -> tokio::sync::broadcast::Receiver<PoolWarning> {
+        self.warnings.subscribe()
     }
-}
 
-/// Queryable object pool - find objects matching a predicate
-///
-/// # Examples
-///
-/// ```
-/// use esox_objectpool::{QueryableObjectPool, PoolConfiguration};
-///
-/// #[derive(Clone)]
-/// struct Connection { id: u32 }
-///
-/// let pool = QueryableObjectPool::new(
-///     vec![Connection { id: 1 }, Connection { id: 2 }],
-///     PoolConfiguration::default()
-/// );
-///
-/// let conn = pool.get_object(|c| c.id == 2).unwrap();
-/// assert_eq!(conn.id, 2);
-/// ```
-pub struct QueryableObjectPool<T: Send> {
-    inner: ObjectPool<T>,
-}
+    /// Subscribe to the pool's [`PoolEvent`]s: evictions, acquisitions
+    /// finding the pool empty and circuit breaker transitions
+    ///
+    /// Receivers lag like those of [`return_errors`](Self::return_errors).
+    /// See [`PoolEvent`] for an example.
+    #[must_use]
+    pub fn events(&self) -> tokio::sync::broadcast::Receiver<PoolEvent> {
+        self.events.subscribe()
+    }
 
-impl<T: Send + Sync + Clone + 'static> QueryableObjectPool<T> {
-    /// Create a new queryable pool
-    pub fn new(objects: Vec<T>, config: PoolConfiguration<T>) -> Self {
-        Self {
-            inner: ObjectPool::new(objects, config),
-        }
+    /// Have `observer` receive the pool's events, metrics and health
+    /// changes every `every`, on a thread of its own
+    ///
+    /// The observer runs until the returned handle or the pool is dropped.
+    /// See [`PoolObserver`] for an example.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `every` is zero.
+    pub fn observe(self: &Arc<Self>, observer: impl PoolObserver, every: Duration) -> ObserverHandle {
+        ObserverHandle::spawn(Arc::downgrade(self) as Weak<dyn Watched>, observer, every)
     }
-    
-    #[must_use = "the pool object must be used or explicitly dropped"]
-    pub fn get_object<F>(&self, query: F) -> PoolResult<PooledObject<T>>
-    where
-        F: Fn(&T) -> bool,
-    {
-        self.inner.check_circuit_breaker()?;
-        self.inner.try_acquire_active_slot()?;
 
-        // Collect all available objects temporarily
-        let mut temp_storage = Vec::new();
-        let mut found = None;
-        
-        while let Some((obj, id)) = self.inner.available.pop() {
-            if self.inner.eviction.is_expired(id) {
-                self.inner.eviction.remove_object(id);
-                continue;
-            }
-            
-            if found.is_none() && query(&obj) {
-                found = Some((obj, id));
-            } else {
-                temp_storage.push((obj, id));
-            }
-        }
-        
-        // Return non-matching objects
-        for item in temp_storage {
-            if let Err((_obj, failed_id)) = ObjectPool::<T>::push_available_with_retry(
-                self.inner.available.as_ref(),
-                item,
-            ) {
-                self.inner.metrics.queue_push_failures.fetch_add(1, Ordering::Relaxed);
-                self.inner.eviction.remove_object(failed_id);
-            }
+    /// Write the pool's events, return errors and warnings as `tracing`
+    /// records through `logger`, replacing any logger installed before
+    ///
+    /// Requires the `tracing` feature. See [`EventLogger`] for levels and
+    /// sampling.
+    #[cfg(feature = "tracing")]
+    pub fn log_events(&self, logger: EventLogger) {
+        let logger = Arc::new(logger);
+        let events = Arc::clone(&logger);
+        self.events.set_tap(Arc::new(move |event| events.event(event)));
+        let errors = Arc::clone(&logger);
+        self.return_errors.set_tap(Arc::new(move |error| errors.return_error(error)));
+        self.warnings.set_tap(Arc::new(move |warning| logger.warning(warning)));
+    }
+
+    /// Destroy an idle object that expired.
+    fn evict(&self, obj: T, id: usize) {
+        self.eviction.remove_object(id);
+        self.destroy(obj);
+        self.note(ActivityOutcome::Evicted, id);
+        if self.metrics.event_sampler.sample() {
+            self.events.report(PoolEvent::Evicted { object_id: PoolObjectId::new(id) });
         }
-        
-        if let Some((obj, id)) = found {
-            self.inner.eviction.touch_object(id);
-            self.inner.metrics.total_retrieved.fetch_add(1, Ordering::Relaxed);
+    }
 
-            if let Some(ref cb) = self.inner.circuit_breaker {
-                cb.record_success();
-            }
-            
-            let return_fn = self.inner.make_return_fn();
-            let detach_fn = self.inner.make_detach_fn();
-            Ok(PooledObject::new(obj, id, return_fn, detach_fn))
-        } else {
-            // Release the slot we reserved — no match was found.
-            self.inner.active_count.fetch_sub(1, Ordering::AcqRel);
-            if let Some(ref cb) = self.inner.circuit_breaker {
-                cb.record_failure();
-            }
-            Err(PoolError::NoMatchFound)
+    fn return_path(&self) -> ReturnPath<'_, T> {
+        ReturnPath {
+            available: &self.available,
+            eviction: &self.eviction,
+            hooks: &self.hooks,
+            metrics: &self.metrics,
+            return_errors: &self.return_errors,
+            population: &self.population,
+            closed: &self.closed,
+            retiring: &self.retiring,
+            activity: self.activity.as_deref(),
+            events: &self.events,
         }
     }
-    
-    /// Try to get an object matching query
-    pub fn try_get_object<F>(&self, query: F) -> PoolResult<Option<PooledObject<T>>>
-    where
-        F: Fn(&T) -> bool,
-    {
-        match self.get_object(query) {
-            Ok(obj) => Ok(Some(obj)),
-            Err(PoolError::NoMatchFound) => Ok(None),
-            Err(err) => Err(err),
+
+    /// Record what became of object `id` in the activity log, if enabled.
+    fn note(&self, outcome: ActivityOutcome, id: usize) {
+        if let Some(ref activity) = self.activity {
+            activity.record(ActivityRecord::new(outcome, Some(id)));
         }
     }
-    
-    /// Get an object matching query asynchronously
-    pub async fn get_object_async<F>(&self, query: F) -> PoolResult<PooledObject<T>>
-    where
-        F: Fn(&T) -> bool + Send + Sync + 'static,
-    {
-        let timeout = self.inner.config.operation_timeout.unwrap_or(Duration::from_secs(30));
-        
-        tokio::time::timeout(timeout, async {
-            let mut attempt: u64 = 0;
-            loop {
-                match self.try_get_object(&query) {
-                    Ok(Some(obj)) => return Ok(obj),
-                    Ok(None) => {
-                        let delay = 5 + (attempt % 4) * 5;
-                        tokio::time::sleep(Duration::from_millis(delay)).await;
-                        attempt = attempt.wrapping_add(1);
-                    }
-                    Err(err) => return Err(err),
-                }
-            }
-        })
-        .await
-        .map_err(|_| PoolError::Timeout(timeout))?
+
+    /// Dispose of an object that is leaving the pool for good.
+    fn destroy(&self, obj: T) {
+        Self::destroy_with(&self.hooks, &self.population, obj);
     }
-    
-    // Delegate methods to inner pool
-    #[must_use]
-    pub fn get_health_status(&self) -> HealthStatus {
-        self.inner.get_health_status()
+
+    fn destroy_with(hooks: &Hooks<T>, population: &AtomicUsize, obj: T) {
+        population.fetch_sub(1, Ordering::AcqRel);
+        hooks.destroy(obj);
     }
 
-    #[must_use]
-    pub fn available_count(&self) -> usize {
-        self.inner.available_count()
+    /// Objects a fixed pool should still own: its initial objects less those
+    /// retired on purpose. `None` for pools that replace lost objects.
+    fn expected_size(&self) -> Option<usize> {
+        self.original_size
+            .map(|original| original.saturating_sub(self.metrics.retired_objects.load(Ordering::Relaxed)))
     }
 
-    #[must_use]
-    pub fn active_count(&self) -> usize {
-        self.inner.active_count()
+    /// Take one owed retirement, if any.
+    fn take_retirement(retiring: &AtomicUsize) -> bool {
+        retiring
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |owed| owed.checked_sub(1))
+            .is_ok()
     }
 
-    #[must_use]
-    pub fn capacity(&self) -> usize {
-        self.inner.capacity()
+    /// Mark this pool as one that replaces the objects it loses, so losing
+    /// them is not reported.
+    fn replacing_lost_objects(mut self) -> Self {
+        self.original_size = None;
+        self
     }
 
-    /// Proactively remove expired objects. See [`ObjectPool::evict_expired`].
-    #[must_use = "returns the count of evicted objects"]
-    pub fn evict_expired(&self) -> usize {
-        self.inner.evict_expired()
+    /// Reserve room for one more object. Returns `false` at capacity.
+    fn reserve_population(&self) -> bool {
+        self.population
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                (n < self.capacity).then_some(n + 1)
+            })
+            .is_ok()
     }
 
-    /// Drain all available objects. See [`ObjectPool::drain`].
-    #[must_use = "returns the drained objects"]
-    pub fn drain(&self) -> Vec<T> {
-        self.inner.drain()
+    /// Give back a reservation that did not result in an object.
+    fn release_population(&self) {
+        self.population.fetch_sub(1, Ordering::AcqRel);
     }
 
-    #[must_use]
-    pub fn get_metrics(&self) -> PoolMetrics {
-        self.inner.get_metrics()
+    /// Run `factory` under the creation throttle and the pending-creation
+    /// cap, for a caller holding a population reservation and an active
+    /// slot. Both are given back if no object results.
+    fn create_with(&self, factory: impl FnOnce() -> Result<T, String>) -> PoolResult<T> {
+        let created = self.pending_creations.try_begin().and_then(|pending| {
+            self.creation_throttle.try_begin()?;
+            let created = self.hooks.create(factory);
+            drop(pending);
+            // Callers that coalesced onto this creation may start their own.
+            self.released.notify_waiters();
+            self.creation_throttle.record(created.is_ok());
+            created.map_err(|reason| {
+                self.metrics.creation_failures.fetch_add(1, Ordering::Relaxed);
+                PoolError::CreationFailed(reason)
+            })
+        });
+        if let Err(ref err) = created {
+            if matches!(err, PoolError::CreationThrottled) {
+                self.metrics.creations_throttled.fetch_add(1, Ordering::Relaxed);
+            }
+            self.active_count.release(1);
+            self.release_population();
+        }
+        created
     }
 
-    #[must_use]
-    pub fn export_metrics(&self) -> HashMap<String, String> {
-        self.inner.export_metrics()
+    /// Account for a factory failure outside the acquisition path.
+    fn creation_failed(&self, reason: String) -> PoolError {
+        self.release_population();
+        self.creation_throttle.record(false);
+        self.metrics.creation_failures.fetch_add(1, Ordering::Relaxed);
+        PoolError::CreationFailed(reason)
     }
 
-    #[must_use]
-    pub fn export_metrics_prometheus(
-        &self,
-        pool_name: &str,
-        tags: Option<&HashMap<String, String>>,
-    ) -> String {
-        self.inner.export_metrics_prometheus(pool_name, tags)
+    /// Reserve room for an object created outside the pool, claiming what
+    /// [`create_with`](Self::create_with) does before running the factory.
+    fn reserve_creation(&self) -> PoolResult<CreationRoom<'_, T>> {
+        if !self.reserve_population() {
+            return Err(PoolError::PoolFull);
+        }
+        if let Err(err) = self.try_acquire_active_slot() {
+            self.release_population();
+            return Err(err);
+        }
+        let mut room = CreationRoom {
+            pool: self,
+            epoch: self.epoch(),
+            pending: None,
+            filled: false,
+        };
+        room.pending = Some(self.pending_creations.try_begin()?);
+        self.creation_throttle.try_begin().inspect_err(|_| {
+            self.metrics.creations_throttled.fetch_add(1, Ordering::Relaxed);
+        })?;
+        Ok(room)
+    }
+
+    /// Account for, report and destroy an object that could not be pushed
+    /// back because the queue was full.
+    fn discard_overflow(&self, obj: T, object_id: usize) {
+        Self::discard_overflow_with(
+            &self.hooks,
+            &self.metrics,
+            &self.eviction,
+            &self.return_errors,
+            &self.population,
+            obj,
+            object_id,
+        );
     }
-}
 
-/// Dynamic object pool - creates objects on demand
-///
-/// # Examples
-///
-/// ```
-/// use esox_objectpool::{DynamicObjectPool, PoolConfiguration};
-///
-/// let pool = DynamicObjectPool::new(
-///     || 42,
-///     PoolConfiguration::new().with_max_pool_size(10)
-/// );
-///
-/// let obj = pool.get_object().unwrap();
-/// assert_eq!(*obj, 42);
-/// ```
-pub struct DynamicObjectPool<T: Send> {
-    inner: ObjectPool<T>,
-    factory: Arc<dyn Fn() -> T + Send + Sync>,
-    /// Serialises dynamic object creation to prevent TOCTOU over-creation.
-    create_lock: std::sync::Mutex<()>,
-}
+    fn discard_overflow_with(
+        hooks: &Hooks<T>,
+        metrics: &MetricsTracker,
+        eviction: &EvictionTracker<T>,
+        return_errors: &ReturnErrorReporter,
+        population: &AtomicUsize,
+        obj: T,
+        object_id: usize,
+    ) {
+        metrics.queue_push_failures.fetch_add(1, Ordering::Relaxed);
+        eviction.remove_object(object_id);
+        return_errors.report(ReturnError::QueueFull { object_id: PoolObjectId::new(object_id) });
+        Self::destroy_with(hooks, population, obj);
+    }
 
-impl<T: Send + Sync + 'static> DynamicObjectPool<T> {
-    /// Create a new dynamic pool with factory function
-    pub fn new<F>(factory: F, config: PoolConfiguration<T>) -> Self
-    where
-        F: Fn() -> T + Send + Sync + 'static,
-    {
-        Self {
-            inner: ObjectPool::new(Vec::new(), config),
-            factory: Arc::new(factory),
-            create_lock: std::sync::Mutex::new(()),
+    /// Drain all *available* (not currently checked-out) objects from the pool
+    /// and return them. Active objects are unaffected.
+    ///
+    /// The pool stays open: returned objects go back into it as usual. To
+    /// stop acquisitions as well, use [`shutdown`](Self::shutdown).
+    #[must_use = "returns the drained objects"]
+    pub fn drain(&self) -> Vec<T> {
+        let mut objects = Vec::new();
+        while let Some((obj, id)) = self.available.pop() {
+            self.eviction.remove_object(id);
+            objects.push(obj);
         }
+        self.population.fetch_sub(objects.len(), Ordering::AcqRel);
+        objects
     }
 
-    /// Create a dynamic pool with initial objects and factory
-    pub fn with_initial<F>(factory: F, initial_objects: Vec<T>, config: PoolConfiguration<T>) -> Self
-    where
-        F: Fn() -> T + Send + Sync + 'static,
-    {
-        Self {
-            inner: ObjectPool::new(initial_objects, config),
-            factory: Arc::new(factory),
-            create_lock: std::sync::Mutex::new(()),
+    /// Dismantle the pool, handing back the objects it owns
+    ///
+    /// Meant for moving resources to another subsystem: the objects are
+    /// returned as they are, without running the
+    /// [`on_destroy`](PoolConfiguration::with_on_destroy) hook. Only possible
+    /// once every checked-out object has been returned; otherwise the pool
+    /// comes back unchanged inside the error.
+    ///
+    /// # Errors
+    ///
+    /// [`IntoInnerError`] with the active and idle counts while any object
+    /// is checked out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    ///
+    /// let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+    /// let held = pool.get_object().unwrap();
+    ///
+    /// let err = pool.into_inner().unwrap_err();
+    /// assert_eq!((err.active(), err.available()), (1, 2));
+    ///
+    /// let pool = err.into_pool();
+    /// drop(held);
+    /// let mut objects = pool.into_inner().unwrap();
+    /// objects.sort();
+    /// assert_eq!(objects, [1, 2, 3]);
+    /// ```
+    pub fn into_inner(self) -> Result<Vec<T>, IntoInnerError<T>> {
+        let active = self.active_count();
+        if active > 0 {
+            let available = self.available_count();
+            return Err(IntoInnerError::new(self, active, available));
         }
+        Ok(self.drain())
     }
-    
-    /// Get an object, creating one via the factory if the pool is empty.
+
+    /// Close the pool for good
     ///
-    /// Dynamic creation only proceeds when the pool is empty **and** the total
-    /// live object count (active + available) is below `max_pool_size`.
-    /// `CircuitBreakerOpen` and `MaxActiveObjectsReached` are propagated
-    /// immediately — the factory is **never** called in those cases.
+    /// Every idle object is destroyed (through the
+    /// [`on_destroy`](PoolConfiguration::with_on_destroy) hook, if any) and
+    /// from then on every acquisition fails with `PoolError::PoolClosed`,
+    /// including acquisitions already waiting for an object. Checked-out
+    /// objects stay usable; when they are returned, the return succeeds and
+    /// the object is destroyed instead of going back to the pool. Calling
+    /// `shutdown` again has no effect.
     ///
-    /// A `Mutex` serialises the capacity check + creation step, preventing the
-    /// TOCTOU race where two concurrent callers both see room and both create an
-    /// object, exceeding the configured capacity.
-    #[must_use = "the pool object must be used or explicitly dropped"]
-    pub fn get_object(&self) -> PoolResult<PooledObject<T>> {
-        match self.inner.get_object() {
-            Ok(obj) => Ok(obj),
-            Err(PoolError::PoolEmpty) => {
-                // Serialise capacity check + creation to prevent TOCTOU race.
-                let _guard = self.create_lock.lock().unwrap_or_else(|p| p.into_inner());
-
-                // Re-check under the lock: a concurrent thread may have returned
-                // an object between the PoolEmpty error and here.
-                let total_live = self.inner.active_count.load(Ordering::Acquire)
-                    + self.inner.available.len();
-                if total_live >= self.inner.capacity {
-                    return Err(PoolError::PoolFull);
-                }
-
-                // Also enforce max_active_objects in the dynamic creation path.
-                // Use the same CAS semaphore to remain race-free.
-                self.inner.try_acquire_active_slot()?;
-
-                let obj = (self.factory)();
-                let id = self.inner.next_id.fetch_add(1, Ordering::Relaxed);
-
-                self.inner.eviction.track_object(id);
-                self.inner.metrics.total_retrieved.fetch_add(1, Ordering::Relaxed);
-
-                // The inner `get_object()` recorded a CB failure for the empty
-                // queue. Since we successfully served the request, offset it with
-                // a success so routine dynamic creation doesn't trip the breaker.
-                self.inner.record_circuit_breaker_success();
-
-                let return_fn = self.inner.make_return_fn();
-                let detach_fn = self.inner.make_detach_fn();
-                Ok(PooledObject::new(obj, id, return_fn, detach_fn))
-            }
-            Err(err) => Err(err),
+    /// Returns the number of idle objects destroyed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration, PoolError};
+    ///
+    /// let pool = ObjectPool::new(vec![1, 2], PoolConfiguration::default());
+    /// let held = pool.get_object().unwrap();
+    ///
+    /// assert_eq!(pool.shutdown(), 1);
+    /// assert!(pool.is_closed());
+    /// assert!(matches!(pool.get_object(), Err(PoolError::PoolClosed)));
+    ///
+    /// assert!(held.release().is_ok()); // accepted, then destroyed
+    /// assert_eq!(pool.available_count(), 0);
+    /// ```
+    pub fn shutdown(&self) -> usize {
+        if self.closed.swap(true, Ordering::AcqRel) {
+            return 0;
         }
+        let destroyed = self.destroy_idle();
+        self.released.notify_waiters();
+        destroyed
     }
-    
-    /// Try to get an object
-    pub fn try_get_object(&self) -> PoolResult<Option<PooledObject<T>>> {
-        match self.get_object() {
-            Ok(obj) => Ok(Some(obj)),
-            Err(PoolError::PoolFull) => Ok(None),
-            Err(err) => Err(err),
+
+    /// Whether [`shutdown`](Self::shutdown) has been called
+    #[must_use]
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Acquire)
+    }
+
+    /// Stop handing out objects until [`resume`](Self::resume) is called
+    ///
+    /// Meant for maintenance windows of the backend behind the pooled
+    /// objects. While paused, acquisitions treat the pool as empty: they
+    /// wait according to their [`WaitPolicy`], so a fail-fast acquisition
+    /// (and [`try_get_object`](Self::try_get_object)) fails with
+    /// `PoolError::Paused` and a waiting one times out with
+    /// `PoolError::Timeout` unless the pool is resumed first. Dynamic pools
+    /// create no objects and warm-up is refused. Objects are still
+    /// accepted back, and nothing is destroyed. The pause shows up as
+    /// [`HealthStatus::paused`] and [`PoolMetrics::paused`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration, PoolError};
+    ///
+    /// let pool = ObjectPool::new(vec![1, 2], PoolConfiguration::default());
+    /// let held = pool.get_object().unwrap();
+    ///
+    /// pool.pause();
+    /// assert!(matches!(pool.try_get_object(), Err(PoolError::Paused)));
+    /// assert!(!pool.get_health_status().is_healthy());
+    /// drop(held); // returns are accepted
+    /// assert_eq!(pool.available_count(), 2);
+    ///
+    /// pool.resume();
+    /// assert!(pool.get_object().is_ok());
+    /// ```
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::Release);
+    }
+
+    /// Hand out objects again after [`pause`](Self::pause), waking
+    /// acquisitions that waited through the pause
+    pub fn resume(&self) {
+        if self.paused.swap(false, Ordering::AcqRel) {
+            self.released.notify_waiters();
         }
     }
-    
-    /// Get an object asynchronously
-    pub async fn get_object_async(&self) -> PoolResult<PooledObject<T>> {
-        let timeout = self.inner.config.operation_timeout.unwrap_or(Duration::from_secs(30));
-        
-        tokio::time::timeout(timeout, async {
-            let mut attempt: u64 = 0;
-            loop {
-                match self.try_get_object() {
-                    Ok(Some(obj)) => return Ok(obj),
-                    Ok(None) => {
-                        let delay = 5 + (attempt % 4) * 5;
-                        tokio::time::sleep(Duration::from_millis(delay)).await;
-                        attempt = attempt.wrapping_add(1);
-                    }
-                    Err(err) => return Err(err),
-                }
-            }
-        })
-        .await
-        .map_err(|_| PoolError::Timeout(timeout))?
+
+    /// Whether the pool is paused
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Acquire)
     }
-    
-    /// Warm up the pool by pre-creating objects
+
+    /// Retire every object the pool currently owns
     ///
-    /// Pre-populates the pool to avoid cold-start latency.
+    /// Meant for credential rotation and similar events after which
+    /// existing connections must not be reused. The pool moves to a new
+    /// epoch; idle objects from older epochs are destroyed the next time
+    /// an acquisition, [`verify`](Self::verify) or
+    /// [`evict_expired`](Self::evict_expired) comes across them, and
+    /// checked-out ones are destroyed when returned instead of going back
+    /// into the pool. Dynamic pools create replacements on demand.
+    ///
+    /// Returns the new epoch.
     ///
     /// # Examples
     ///
     /// ```
     /// use esox_objectpool::{DynamicObjectPool, PoolConfiguration};
+    /// use std::sync::atomic::{AtomicU32, Ordering};
     ///
+    /// let generation = AtomicU32::new(1);
     /// let pool = DynamicObjectPool::new(
-    ///     || 42,
-    ///     PoolConfiguration::default()
+    ///     move || generation.fetch_add(1, Ordering::Relaxed),
+    ///     PoolConfiguration::default(),
     /// );
+    /// let held = pool.get_object().unwrap();
+    /// assert_eq!(*held, 1);
     ///
-    /// pool.warmup(5).unwrap();
-    /// 
-    /// let health = pool.get_health_status();
-    /// assert_eq!(health.available_objects, 5);
+    /// assert_eq!(pool.invalidate_all(), 1);
+    /// drop(held); // destroyed, not reused
+    /// assert_eq!(pool.available_count(), 0);
+    /// assert_eq!(*pool.get_object().unwrap(), 2);
     /// ```
-    pub fn warmup(&self, count: usize) -> PoolResult<()> {
-        for _ in 0..count.min(self.inner.capacity) {
-            let obj = (self.factory)();
-            let id = self.inner.next_id.fetch_add(1, Ordering::Relaxed);
-            self.inner.eviction.track_object(id);
-            
-            if self.inner.available.push((obj, id)).is_err() {
-                // Queue is full; remove the eviction entry we just registered
-                // to avoid a leak.
-                self.inner.eviction.remove_object(id);
-                break;
-            }
-        }
-        Ok(())
-    }
-    
-    /// Warm up asynchronously
-    pub async fn warmup_async(&self, count: usize) -> PoolResult<()> {
-        let factory = Arc::clone(&self.factory);
-        let available = Arc::clone(&self.inner.available);
-        let next_id = Arc::clone(&self.inner.next_id);
-        let eviction = Arc::clone(&self.inner.eviction);
-        let capacity = self.inner.capacity;
-        
-        tokio::task::spawn_blocking(move || {
-            for _ in 0..count.min(capacity) {
-                let obj = factory();
-                let id = next_id.fetch_add(1, Ordering::Relaxed);
-                eviction.track_object(id);
-                
-                if available.push((obj, id)).is_err() {
-                    eviction.remove_object(id);
-                    break;
-                }
-            }
-        })
-        .await
-        .map_err(|_| PoolError::Cancelled)?;
-        
-        Ok(())
-    }
-    
-    // Delegate methods
-    #[must_use]
-    pub fn get_health_status(&self) -> HealthStatus {
-        self.inner.get_health_status()
+    pub fn invalidate_all(&self) -> u64 {
+        self.eviction.invalidate()
     }
 
+    /// The current epoch: how many times
+    /// [`invalidate_all`](Self::invalidate_all) has been called
     #[must_use]
-    pub fn available_count(&self) -> usize {
-        self.inner.available_count()
+    pub fn epoch(&self) -> u64 {
+        self.eviction.epoch()
     }
 
-    #[must_use]
-    pub fn active_count(&self) -> usize {
-        self.inner.active_count()
+    /// Take `n` objects out of circulation as they become idle
+    ///
+    /// Meant for gradually moving capacity away from a backend that is being
+    /// scaled down. Idle objects are destroyed right away (through the
+    /// [`on_destroy`](PoolConfiguration::with_on_destroy) hook, if any); the
+    /// rest of the `n` are destroyed as checked-out objects are returned,
+    /// instead of going back into the pool. Nothing blocks and no holder is
+    /// interrupted. At most as many objects as the pool owns are retired.
+    ///
+    /// Retired objects count in
+    /// [`PoolMetrics::retired_objects`](crate::PoolMetrics::retired_objects)
+    /// and not as capacity lost. A dynamic pool creates replacements only
+    /// when demand calls for them.
+    ///
+    /// Returns the number of idle objects destroyed right away.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    ///
+    /// let pool = ObjectPool::new(vec![1, 2, 3, 4], PoolConfiguration::default());
+    /// let held = pool.get_object().unwrap();
+    /// let _kept = pool.get_object().unwrap();
+    ///
+    /// assert_eq!(pool.retire(3), 2); // both idle objects
+    /// assert_eq!(pool.pending_retirements(), 1);
+    ///
+    /// drop(held); // retired instead of returned
+    /// assert_eq!(pool.available_count(), 0);
+    /// assert_eq!(pool.get_metrics().retired_objects, 3);
+    /// ```
+    pub fn retire(&self, n: usize) -> usize {
+        let population = self.population.load(Ordering::Acquire);
+        let _ = self.retiring.fetch_update(Ordering::AcqRel, Ordering::Acquire, |owed| {
+            Some(owed.saturating_add(n).min(population))
+        });
+        let mut retired = 0;
+        while Self::take_retirement(&self.retiring) {
+            let Some((obj, id)) = self.available.pop() else {
+                self.retiring.fetch_add(1, Ordering::AcqRel);
+                break;
+            };
+            self.eviction.remove_object(id);
+            self.destroy(obj);
+            retired += 1;
+        }
+        if retired > 0 {
+            self.metrics.retired_objects.fetch_add(retired, Ordering::Relaxed);
+            // Dynamic pools have room to create again.
+            self.released.notify_waiters();
+        }
+        retired
     }
 
+    /// Objects [`retire`](Self::retire) will still take out of circulation
+    /// as they are returned
     #[must_use]
-    pub fn capacity(&self) -> usize {
-        self.inner.capacity()
+    pub fn pending_retirements(&self) -> usize {
+        self.retiring.load(Ordering::Acquire)
     }
 
-    /// Proactively remove expired objects. See [`ObjectPool::evict_expired`].
-    #[must_use = "returns the count of evicted objects"]
-    pub fn evict_expired(&self) -> usize {
-        self.inner.evict_expired()
+    /// Destroy every idle object; returns how many there were.
+    fn destroy_idle(&self) -> usize {
+        Self::destroy_idle_with(&self.available, &self.eviction, &self.hooks, &self.population)
     }
 
-    /// Drain all available objects. See [`ObjectPool::drain`].
-    #[must_use = "returns the drained objects"]
-    pub fn drain(&self) -> Vec<T> {
-        self.inner.drain()
+    fn destroy_idle_with(
+        available: &IdleQueue<T>,
+        eviction: &EvictionTracker<T>,
+        hooks: &Hooks<T>,
+        population: &AtomicUsize,
+    ) -> usize {
+        let mut destroyed = 0;
+        while let Some((obj, id)) = available.pop() {
+            eviction.remove_object(id);
+            Self::destroy_with(hooks, population, obj);
+            destroyed += 1;
+        }
+        destroyed
     }
 
-    #[must_use]
-    pub fn get_metrics(&self) -> PoolMetrics {
-        self.inner.get_metrics()
+    /// Record a circuit-breaker success from an external caller (used by
+    /// `DynamicObjectPool` to offset the failure recorded when the inner queue
+    /// was empty but the request was ultimately served via dynamic creation).
+    pub(crate) fn record_circuit_breaker_success(&self) {
+        self.record_outcome(true);
     }
 
-    #[must_use]
-    pub fn export_metrics(&self) -> HashMap<String, String> {
-        self.inner.export_metrics()
+    fn feeds_acquisition(&self) -> bool {
+        self.config.breaker_signals.includes_acquisition()
     }
 
-    #[must_use]
-    pub fn export_metrics_prometheus(
-        &self,
-        pool_name: &str,
-        tags: Option<&HashMap<String, String>>,
-    ) -> String {
-        self.inner.export_metrics_prometheus(pool_name, tags)
+    /// Report that an operation performed with a pooled object succeeded
+    ///
+    /// Counts toward the circuit breaker unless the pool is configured with
+    /// [`BreakerSignals::Acquisition`](crate::BreakerSignals::Acquisition).
+    pub fn report_success(&self) {
+        self.report_outcome(true);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    
-    #[test]
-    fn test_object_pool_basic() {
-        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
-        
-        {
-            let obj = pool.get_object().unwrap();
-            assert!(vec![1, 2, 3].contains(&*obj));
-        }
-        
-        assert_eq!(pool.available_count(), 3);
+    /// Report that an operation performed with a pooled object failed
+    ///
+    /// Counts toward the circuit breaker unless the pool is configured with
+    /// [`BreakerSignals::Acquisition`](crate::BreakerSignals::Acquisition).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{BreakerSignals, ObjectPool, PoolConfiguration, PoolError};
+    /// use std::time::Duration;
+    ///
+    /// let pool = ObjectPool::new(
+    ///     vec![1, 2],
+    ///     PoolConfiguration::new()
+    ///         .with_circuit_breaker(2, Duration::from_secs(60))
+    ///         .with_breaker_signals(BreakerSignals::Operations),
+    /// );
+    ///
+    /// for _ in 0..2 {
+    ///     let _conn = pool.get_object().unwrap();
+    ///     // ... the request made with the connection fails ...
+    ///     pool.report_failure();
+    /// }
+    /// assert!(matches!(pool.get_object(), Err(PoolError::CircuitBreakerOpen)));
+    /// ```
+    pub fn report_failure(&self) {
+        self.report_outcome(false);
     }
-    
-    #[test]
-    fn test_queryable_pool() {
-        let pool = QueryableObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
-        
-        {
-            let obj = pool.get_object(|x| *x == 2).unwrap();
-            assert_eq!(*obj, 2);
+
+    /// Feed an operation outcome to the breaker if operations drive it.
+    fn report_outcome(&self, success: bool) {
+        if self.config.breaker_signals.includes_operations() {
+            self.record_outcome(success);
         }
     }
-    
-    #[test]
-    fn test_dynamic_pool() {
-        let pool = DynamicObjectPool::new(|| 42, PoolConfiguration::default());
-        
-        {
-            let obj = pool.get_object().unwrap();
-            assert_eq!(*obj, 42);
+
+    /// Feed one success or failure into the circuit breaker, if enabled.
+    fn record_outcome(&self, success: bool) {
+        if let Some(ref cb) = self.circuit_breaker {
+            if success {
+                cb.record_success();
+            } else {
+                cb.record_failure();
+            }
         }
     }
-    
-    #[tokio::test]
-    async fn test_async_get() {
-        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
-        
-        {
-            let obj = pool.get_object_async().await.unwrap();
-            assert!(vec![1, 2, 3].contains(&*obj));
-        }
+
+    /// Run `op` on a pooled object, letting its result drive the circuit breaker
+    ///
+    /// The object is acquired as with [`get_object`](Self::get_object) and
+    /// returned to the pool afterwards. Unlike plain acquisition, whether an
+    /// object happened to be idle is not reported to the breaker; instead
+    /// `Ok` from `op` counts as a success and `Err` as a failure, so the
+    /// breaker opens when the backend behind the objects is failing rather
+    /// than when the pool is merely busy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{GuardedError, ObjectPool, PoolConfiguration};
+    /// use std::time::Duration;
+    ///
+    /// let pool = ObjectPool::new(
+    ///     vec![0u32],
+    ///     PoolConfiguration::new().with_circuit_breaker(2, Duration::from_secs(60)),
+    /// );
+    ///
+    /// let ok: Result<u32, GuardedError<&str>> = pool.run_guarded(|n| {
+    ///     *n += 1;
+    ///     Ok(*n)
+    /// });
+    /// assert_eq!(ok.unwrap(), 1);
+    ///
+    /// for _ in 0..2 {
+    ///     let _ = pool.run_guarded(|_| Err::<(), _>("backend down"));
+    /// }
+    /// assert!(matches!(
+    ///     pool.run_guarded(|_| Ok::<_, &str>(())),
+    ///     Err(GuardedError::Pool(_))
+    /// ));
+    /// ```
+    pub fn run_guarded<R, E, F>(&self, op: F) -> Result<R, GuardedError<E>>
+    where
+        F: FnOnce(&mut T) -> Result<R, E>,
+    {
+        let mut obj = self.wait_for(is_pool_empty, || self.acquire_idle(false, None))?;
+        let result = op(&mut obj);
+        self.report_outcome(result.is_ok());
+        result.map_err(GuardedError::Operation)
     }
-    
-    #[test]
-    fn test_pool_empty_error() {
-        let pool = ObjectPool::new(vec![1], PoolConfiguration::default());
-        
-        let _obj1 = pool.get_object().unwrap();
-        let result = pool.get_object();
-        
-        assert!(result.is_err());
-        if let Err(e) = result {
-            assert!(matches!(e, PoolError::PoolEmpty));
-        }
+
+    /// Async counterpart of [`run_guarded`](Self::run_guarded)
+    ///
+    /// `op` receives the guard itself, so the object can be used across
+    /// `.await` points; it goes back to the pool when the guard is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let pool = ObjectPool::new(vec![String::from("conn")], PoolConfiguration::default());
+    ///
+    /// let len = pool
+    ///     .run_guarded_async(|conn| async move { Ok::<_, std::io::Error>(conn.len()) })
+    ///     .await
+    ///     .unwrap();
+    /// assert_eq!(len, 4);
+    /// # }
+    /// ```
+    pub async fn run_guarded_async<R, E, F, Fut>(&self, op: F) -> Result<R, GuardedError<E>>
+    where
+        F: FnOnce(PooledObject<T>) -> Fut,
+        Fut: Future<Output = Result<R, E>>,
+    {
+        let obj = self.wait_for_async(is_pool_empty, || self.acquire_idle(false, None)).await?;
+        let result = op(obj).await;
+        self.report_outcome(result.is_ok());
+        result.map_err(GuardedError::Operation)
     }
-    
-    #[test]
-    fn test_try_methods() {
-        let pool = ObjectPool::new(vec![42], PoolConfiguration::default());
-        
-        let obj1 = pool.try_get_object().unwrap();
-        assert!(obj1.is_some());
-        if let Some(ref obj) = obj1 {
-            assert_eq!(**obj, 42);
-        }
-        
-        let obj2 = pool.try_get_object().unwrap();
-        assert!(obj2.is_none());
-        
-        drop(obj1);
-        
-        let obj3 = pool.try_get_object().unwrap();
-        assert!(obj3.is_some());
+
+    /// Run `op` like [`run_guarded`](Self::run_guarded), retrying on another
+    /// object when it fails because its object is broken
+    ///
+    /// Errors that `policy` classifies as broken cause the object to be
+    /// [discarded](PooledObject::discard) and `op` to run again on another
+    /// one, up to the policy's attempt limit; the last error is returned
+    /// once the limit is reached. Other errors are returned at once. Every
+    /// attempt counts towards the circuit breaker, as with `run_guarded`.
+    ///
+    /// Discarded objects leave a fixed pool for good; in a
+    /// [`DynamicObjectPool`] they are replaced on demand.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration, RetryPolicy};
+    ///
+    /// // The first connection is stale; the second works.
+    /// let pool = ObjectPool::new(vec![false, true], PoolConfiguration::default());
+    /// let policy = RetryPolicy::new(3, |err: &&str| *err == "connection reset");
+    ///
+    /// let reply = pool.execute_with_retry(&policy, |healthy| {
+    ///     if *healthy { Ok("pong") } else { Err("connection reset") }
+    /// });
+    /// assert_eq!(reply.unwrap(), "pong");
+    /// assert_eq!(pool.get_metrics().discarded_objects, 1);
+    /// ```
+    pub fn execute_with_retry<R, E, F>(&self, policy: &RetryPolicy<E>, op: F) -> Result<R, GuardedError<E>>
+    where
+        F: FnMut(&mut T) -> Result<R, E>,
+    {
+        retry::execute(
+            policy,
+            || self.wait_for(is_pool_empty, || self.acquire_idle(false, None)),
+            |success| self.report_outcome(success),
+            op,
+        )
     }
-    
+
+    /// Return a group of guards in one pass.
+    ///
+    /// Equivalent to dropping each guard, but the active count and metrics are
+    /// updated once for the whole group instead of once per object. Guards
+    /// that belong to a different pool are dropped normally, which returns
+    /// them to their own pool.
+    ///
+    /// Returns the number of objects put back into the available queue.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    ///
+    /// let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+    /// let guards = vec![pool.get_object().unwrap(), pool.get_object().unwrap()];
+    ///
+    /// assert_eq!(pool.return_many(guards), 2);
+    /// assert_eq!(pool.available_count(), 3);
+    /// ```
+    pub fn return_many<I>(&self, objects: I) -> usize
+    where
+        I: IntoIterator<Item = PooledObject<T>>,
+    {
+        let path = self.return_path();
+        let mut reclaimed = 0;
+        let mut to_push = Vec::new();
+
+        for mut guard in objects {
+            if !Arc::ptr_eq(&guard.return_fn, &self.return_fn) {
+                // Not ours: let its own return path handle it.
+                drop(guard);
+                continue;
+            }
+            let Some(obj) = guard.take_for_return() else {
+                continue;
+            };
+            let id = guard.object_id;
+            reclaimed += 1;
+            if let Ok(Some(obj)) = path.settle(obj, id, self.config.validate_on_return, guard.checked_out) {
+                to_push.push((obj, id));
+            }
+        }
+
+        if reclaimed == 0 {
+            return 0;
+        }
+        self.active_count.release(reclaimed);
+
+        let mut returned = 0;
+        for (obj, id) in to_push {
+            if path.push(obj, id).is_ok() {
+                returned += 1;
+            }
+        }
+        if returned > 0 {
+            self.metrics.total_returned.fetch_add(returned, Ordering::Relaxed);
+        }
+        path.finish();
+        if let Some(ref watchdog) = self.watchdog {
+            watchdog.record_release();
+        }
+        self.released.notify_waiters();
+        returned
+    }
+
+    /// Acquire `count` objects at once as a [`PooledBatch`].
+    ///
+    /// Acquisition is all-or-nothing: if any object cannot be obtained, the
+    /// ones already acquired are returned and the error is propagated. The
+    /// whole batch is returned via [`return_many`](Self::return_many) when it
+    /// is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    ///
+    /// let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+    /// {
+    ///     let batch = pool.get_batch(2).unwrap();
+    ///     assert_eq!(batch.len(), 2);
+    ///     assert_eq!(pool.available_count(), 1);
+    /// }
+    /// assert_eq!(pool.available_count(), 3);
+    /// ```
+    #[must_use = "the batch must be used or explicitly dropped"]
+    #[track_caller]
+    pub fn get_batch(&self, count: usize) -> PoolResult<PooledBatch<'_, T>> {
+        let mut objects = Vec::with_capacity(count);
+        for _ in 0..count {
+            match self.get_object() {
+                Ok(obj) => objects.push(obj),
+                Err(err) => {
+                    self.return_many(objects);
+                    return Err(err);
+                }
+            }
+        }
+        Ok(PooledBatch::new(self, objects))
+    }
+
+    /// [`wait_for`](Self::wait_for) without stalling an async runtime.
+    fn wait_in_place<R>(&self, retryable: fn(&PoolError) -> bool, attempt: impl FnMut() -> PoolResult<R>) -> PoolResult<R> {
+        let _waiter = self.watchdog.as_ref().map(|watchdog| watchdog.enter());
+        let started = Instant::now();
+        let policy = self.config.blocking_wait_policy();
+        let result = wait_in_place(
+            policy,
+            self.config.retry_backoff(),
+            retryable,
+            self.circuit_breaker.as_deref(),
+            self.watched(policy, retryable, attempt),
+        );
+        self.record_wait(started, &result);
+        result
+    }
+
+    /// Retry `attempt` under the pool's blocking wait policy.
+    fn wait_for<R>(&self, retryable: fn(&PoolError) -> bool, attempt: impl FnMut() -> PoolResult<R>) -> PoolResult<R> {
+        self.wait_with(self.config.blocking_wait_policy(), retryable, attempt)
+    }
+
+    /// Retry `attempt` under `policy`.
+    fn wait_with<R>(
+        &self,
+        policy: WaitPolicy,
+        retryable: fn(&PoolError) -> bool,
+        attempt: impl FnMut() -> PoolResult<R>,
+    ) -> PoolResult<R> {
+        let _waiter = self.watchdog.as_ref().map(|watchdog| watchdog.enter());
+        let started = Instant::now();
+        let result = wait_blocking(
+            policy,
+            self.config.retry_backoff(),
+            retryable,
+            self.circuit_breaker.as_deref(),
+            self.watched(policy, retryable, attempt),
+        );
+        self.record_wait(started, &result);
+        result
+    }
+
+    /// Retry `attempt` under the pool's async wait policy, waking early
+    /// whenever an object is released.
+    async fn wait_for_async<R>(
+        &self,
+        retryable: fn(&PoolError) -> bool,
+        attempt: impl FnMut() -> PoolResult<R>,
+    ) -> PoolResult<R> {
+        self.wait_with_async(self.config.async_wait_policy(), retryable, attempt)
+            .await
+    }
+
+    /// Async counterpart of [`wait_with`](Self::wait_with).
+    async fn wait_with_async<R>(
+        &self,
+        policy: WaitPolicy,
+        retryable: fn(&PoolError) -> bool,
+        attempt: impl FnMut() -> PoolResult<R>,
+    ) -> PoolResult<R> {
+        let _waiter = self.watchdog.as_ref().map(|watchdog| watchdog.enter());
+        let started = Instant::now();
+        let result = wait_async(
+            policy,
+            self.config.retry_backoff(),
+            &self.released,
+            retryable,
+            self.circuit_breaker.as_deref(),
+            self.watched(policy, retryable, attempt),
+        )
+        .await;
+        self.record_wait(started, &result);
+        result
+    }
+
+    /// Record how long a successful acquisition took, for tuning advice,
+    /// count it and timeouts against the SLO targets, and log a failed one
+    /// in the activity log.
+    fn record_wait<R>(&self, started: Instant, result: &PoolResult<R>) {
+        if let (Some(activity), Err(err)) = (&self.activity, result) {
+            activity.record(ActivityRecord {
+                wait: Some(started.elapsed()),
+                ..ActivityRecord::new(ActivityOutcome::AcquireFailed(err.clone()), None)
+            });
+        }
+        if result.is_ok() && self.metrics.sample_wait() {
+            self.metrics.wait_times.record(started.elapsed());
+        }
+        if let Some(ref slo) = self.slo {
+            let timed_out = matches!(result, Err(PoolError::Timeout(..)));
+            if result.is_ok() || timed_out {
+                slo.record(started.elapsed(), timed_out, |objective| {
+                    self.events.report(PoolEvent::SloBudgetExhausted { objective });
+                });
+            }
+        }
+    }
+
+    /// `attempt`, followed on failure by a starvation check when the
+    /// watchdog is enabled and by recording a [`FailureDiagnosis`]. The
+    /// acquisition counts as waiting from its first failed attempt until the
+    /// returned closure is dropped, which happens when the wait ends or its
+    /// future is dropped. An acquisition that would wait under `policy`
+    /// while `max_waiters` others are fails with `PoolError::QueueFull`
+    /// instead.
+    fn watched<'a, R>(
+        &'a self,
+        policy: WaitPolicy,
+        retryable: fn(&PoolError) -> bool,
+        mut attempt: impl FnMut() -> PoolResult<R> + 'a,
+    ) -> impl FnMut() -> PoolResult<R> + 'a {
+        let mut waiting = None;
+        let started = (self.hooks.times_acquisitions() || self.activity.is_some()).then(Instant::now);
+        move || {
+            let mut result = match started {
+                Some(started) => attempt_started_at(started, &mut attempt),
+                None => attempt(),
+            };
+            if waiting.is_none()
+                && let Err(ref err) = result
+            {
+                let limit = (policy != WaitPolicy::FailFast && retryable(err))
+                    .then_some(self.config.max_waiters)
+                    .flatten();
+                waiting = self.waiters.try_enter(limit);
+                if waiting.is_none() {
+                    self.metrics.waiter_rejections.fetch_add(1, Ordering::Relaxed);
+                    result = Err(PoolError::QueueFull);
+                }
+            }
+            if let Err(ref err) = result {
+                *self.last_failure.lock().unwrap_or_else(PoisonError::into_inner) = Some(self.diagnose(err));
+            }
+            if result.is_err()
+                && let Some(ref watchdog) = self.watchdog
+                && let Some(warning) =
+                    watchdog.check(self.available.len() == 0 && self.active_count.load() > 0)
+            {
+                self.warnings.report(warning);
+            }
+            result
+        }
+    }
+
+    /// The gates behind `err` and the pool's current counts.
+    fn diagnose(&self, err: &PoolError) -> FailureDiagnosis {
+        let mut gates = match err {
+            PoolError::PoolClosed => vec![Gate::Closed],
+            PoolError::CircuitBreakerOpen => vec![Gate::CircuitBreakerOpen],
+            PoolError::MaxActiveObjectsReached => vec![Gate::MaxActiveObjects],
+            PoolError::GroupLimitReached(name) => vec![Gate::LimitGroup(name.clone())],
+            PoolError::PoolEmpty => vec![Gate::Empty],
+            PoolError::PoolFull => vec![Gate::Empty, Gate::AtCapacity],
+            PoolError::CreationThrottled => vec![Gate::Empty, Gate::CreationThrottled],
+            PoolError::CreationPending => vec![Gate::Empty, Gate::CreationPending],
+            PoolError::CreationFailed(_) => vec![Gate::Empty, Gate::CreationFailed],
+            PoolError::ValidationFailed => vec![Gate::ValidationFailed],
+            PoolError::NoMatchFound => vec![Gate::NoMatch],
+            PoolError::QueueFull => vec![Gate::WaitQueueFull],
+            PoolError::Paused => vec![Gate::Paused],
+            _ => Vec::new(),
+        };
+        let expired = self.expired_streak.load(Ordering::Relaxed);
+        if expired > 0 && gates.contains(&Gate::Empty) {
+            gates.push(Gate::EvictionChurn);
+        }
+        FailureDiagnosis {
+            error: err.clone(),
+            gates,
+            available: self.available.len(),
+            active: self.active_count.load(),
+            max_active: self.config.max_active_objects,
+            population: self.population.load(Ordering::Acquire),
+            capacity: self.capacity,
+            waiting: self.waiters.count(),
+            pending_creations: self.pending_creations.in_flight(),
+            breaker: self.circuit_breaker.as_ref().map(|cb| cb.state()),
+            expired_since_last_acquisition: expired,
+            at: Instant::now(),
+        }
+    }
+
+    /// Register a freshly created object with the pool and hand it out.
+    /// `epoch` is the pool's epoch when the factory was picked.
+    ///
+    /// The caller must already hold an active slot.
+    fn adopt_created(&self, obj: T, epoch: u64, ctx: Option<&Arc<AcquireContext>>) -> PoolResult<PooledObject<T>> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        self.eviction.track_object_from(id, epoch);
+        self.eviction.checked_out(id);
+        self.metrics.total_retrieved.fetch_add(1, Ordering::Relaxed);
+        self.expired_streak.store(0, Ordering::Relaxed);
+        self.wrap(obj, id, ctx)
+    }
+
+    pub(crate) fn attach_lease(
+        &self,
+        mut obj: PooledObject<T>,
+        max_hold: Duration,
+        on_expire: Option<Box<dyn FnOnce() + Send>>,
+    ) -> PooledObject<T> {
+        let warnings = Arc::clone(&self.warnings);
+        let warning = PoolWarning::LeaseExpired {
+            object_id: obj.id(),
+            max_hold,
+            label: obj.acquire_context().and_then(AcquireContext::label).map(str::to_owned),
+            location: obj.acquired_at,
+        };
+        obj.lease = Some(Lease::new(
+            max_hold,
+            on_expire,
+            Some(Box::new(move || warnings.report(warning))),
+            Arc::clone(&self.metrics.expired_leases),
+        ));
+        obj
+    }
+
+    /// Run the borrow hooks on a checked-out object and put it in a guard.
+    /// An object whose hook panicked is discarded and its active slot
+    /// released.
+    fn wrap(&self, obj: T, id: usize, ctx: Option<&Arc<AcquireContext>>) -> PoolResult<PooledObject<T>> {
+        let mut guard = self.guard(obj, id, ctx)?;
+        if let Some(ref activity) = self.activity {
+            activity.record(ActivityRecord {
+                label: ctx.and_then(|ctx| ctx.label()).map(str::to_owned),
+                wait: Some(acquisition_wait().unwrap_or(Duration::ZERO)),
+                ..ActivityRecord::new(ActivityOutcome::Acquired, Some(id))
+            });
+        }
+        guard.context = self.hooks.context();
+        guard.holder = self
+            .holders
+            .as_ref()
+            .map(|holders| holders.register(id, ctx.and_then(|ctx| ctx.label())));
+        Ok(guard)
+    }
+
+    /// The part of [`wrap`](Self::wrap) that neither locks nor allocates
+    /// unless a hook panics.
+    fn guard(&self, mut obj: T, id: usize, ctx: Option<&Arc<AcquireContext>>) -> PoolResult<PooledObject<T>> {
+        if !self.hooks.prepare(&mut obj, ctx.map(Arc::as_ref)) {
+            self.metrics.validation_failures.fetch_add(1, Ordering::Relaxed);
+            self.eviction.remove_object(id);
+            self.destroy(obj);
+            self.active_count.release(1);
+            self.released.notify_waiters();
+            return Err(PoolError::ValidationFailed);
+        }
+        if let Some(memory) = self.eviction.memory() {
+            memory.active(id, &obj);
+        }
+        let mut guard = PooledObject::new(
+            obj,
+            id,
+            Arc::clone(&self.return_fn),
+            Arc::clone(&self.detach_fn),
+        );
+        guard.external_id = self.external_ids.external(id).cloned();
+        guard.acquire_context = ctx.cloned();
+        guard.checked_out = self.hooks.times_holds().then(Instant::now);
+        guard.eviction = Some(Arc::clone(&self.eviction));
+        guard.hold_sample = self
+            .metrics
+            .sample_hold()
+            .then(|| HoldSample::start(Arc::clone(&self.metrics.hold_times)));
+        self.metrics.peak_active.fetch_max(self.active_count.load(), Ordering::Relaxed);
+        Ok(guard)
+    }
+
+    /// Fails once the pool is shut down, and while it is paused.
+    fn check_open(&self) -> PoolResult<()> {
+        if self.closed.load(Ordering::Acquire) {
+            return Err(PoolError::PoolClosed);
+        }
+        if self.paused.load(Ordering::Acquire) {
+            return Err(PoolError::Paused);
+        }
+        Ok(())
+    }
+
+    fn check_circuit_breaker(&self) -> PoolResult<()> {
+        if let Some(ref cb) = self.circuit_breaker
+            && !cb.allow_request()
+        {
+            return Err(PoolError::CircuitBreakerOpen);
+        }
+        Ok(())
+    }
+
+    /// Atomically reserve an active slot, enforcing `max_active_objects`
+    /// and the pool's limit group without a TOCTOU race.
+    fn try_acquire_active_slot(&self) -> PoolResult<()> {
+        self.active_count.try_acquire().inspect_err(|err| {
+            if matches!(err, PoolError::GroupLimitReached(_)) {
+                self.metrics.group_limit_rejections.fetch_add(1, Ordering::Relaxed);
+            }
+        })
+    }
+    
+    fn make_return_fn(&self) -> ReturnFn<T> {
+        let available = Arc::clone(&self.available);
+        let active_count = Arc::clone(&self.active_count);
+        let metrics = Arc::clone(&self.metrics);
+        let eviction = Arc::clone(&self.eviction);
+        let config = Arc::clone(&self.config);
+        let return_errors = Arc::clone(&self.return_errors);
+        let released = Arc::clone(&self.released);
+        let population = Arc::clone(&self.population);
+        let watchdog = self.watchdog.clone();
+        let hooks = Arc::clone(&self.hooks);
+        let closed = Arc::clone(&self.closed);
+        let retiring = Arc::clone(&self.retiring);
+        let circuit_breaker = self.circuit_breaker.clone();
+        let activity = self.activity.clone();
+        let events = Arc::clone(&self.events);
+        
+        Arc::new(move |obj, id, disposal, checked_out| {
+            if let Some(ref watchdog) = watchdog {
+                watchdog.record_release();
+            }
+            let path = ReturnPath {
+                available: &available,
+                eviction: &eviction,
+                hooks: &hooks,
+                metrics: &metrics,
+                return_errors: &return_errors,
+                population: &population,
+                closed: &closed,
+                retiring: &retiring,
+                activity: activity.as_deref(),
+                events: &events,
+            };
+            if let (Disposal::Completed { success }, Some(cb)) = (disposal, &circuit_breaker)
+                && config.breaker_signals.includes_operations()
+            {
+                if success {
+                    cb.record_success();
+                } else {
+                    cb.record_failure();
+                }
+            }
+            let (discard, validate) = match disposal {
+                Disposal::Return | Disposal::Completed { success: true } => (false, config.validate_on_return),
+                Disposal::Completed { success: false } => match config.failure_policy {
+                    FailurePolicy::Return => (false, config.validate_on_return),
+                    FailurePolicy::Validate => (false, true),
+                    FailurePolicy::Discard => (true, false),
+                },
+                Disposal::Discard => (true, false),
+            };
+            let settled = if discard {
+                metrics.discarded_objects.fetch_add(1, Ordering::Relaxed);
+                path.destroy(obj, id, ActivityOutcome::Discarded);
+                Ok(None)
+            } else {
+                path.settle(obj, id, validate, checked_out)
+            };
+            active_count.release(1);
+            let result = match settled {
+                Ok(Some(obj)) => path.push(obj, id).map(|()| {
+                    metrics.total_returned.fetch_add(1, Ordering::Relaxed);
+                }),
+                Ok(None) => Ok(()),
+                Err(err) => Err(err),
+            };
+            // Closed while this object was on its way back in.
+            path.finish();
+            released.notify_waiters();
+            result
+        })
+    }
+
+    fn make_detach_fn(&self) -> DetachFn {
+        let active_count = Arc::clone(&self.active_count);
+        let eviction = Arc::clone(&self.eviction);
+        let metrics = Arc::clone(&self.metrics);
+        let released = Arc::clone(&self.released);
+        let population = Arc::clone(&self.population);
+        let watchdog = self.watchdog.clone();
+
+        Arc::new(move |id| {
+            if let Some(ref watchdog) = watchdog {
+                watchdog.record_release();
+            }
+            active_count.release(1);
+            population.fetch_sub(1, Ordering::AcqRel);
+            eviction.remove_object(id);
+            metrics.total_detached.fetch_add(1, Ordering::Relaxed);
+            released.notify_waiters();
+        })
+    }
+
+    /// Put a returned object back in the idle queue, first moving its
+    /// estimated size from the active to the idle total.
+    fn push_returned(
+        available: &IdleQueue<T>,
+        eviction: &EvictionTracker<T>,
+        item: (T, usize),
+    ) -> Result<(), (T, usize)> {
+        if let Some(memory) = eviction.memory() {
+            memory.idle(item.1, &item.0);
+        }
+        Self::push_available_with_retry(available, item)
+    }
+
+    fn push_available_with_retry(
+        available: &IdleQueue<T>,
+        mut item: (T, usize),
+    ) -> Result<(), (T, usize)> {
+        for _ in 0..Self::PUSH_RETRY_LIMIT {
+            match available.push(item) {
+                Ok(()) => return Ok(()),
+                Err(returned_item) => {
+                    item = returned_item;
+                    std::thread::yield_now();
+                }
+            }
+        }
+
+        Err(item)
+    }
+}
+
+/// Queryable object pool - find objects matching a predicate
+///
+/// Objects are only moved, never cloned, so `T` does not need to implement
+/// `Clone`. The pool dereferences to the underlying [`ObjectPool`], which
+/// makes the rest of its API (leases, batches, streams, ...) available for
+/// unqueried acquisition.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{QueryableObjectPool, PoolConfiguration};
+///
+/// struct Connection { id: u32 }
+///
+/// let pool = QueryableObjectPool::new(
+///     vec![Connection { id: 1 }, Connection { id: 2 }],
+///     PoolConfiguration::default()
+/// );
+///
+/// let conn = pool.get_object(|c| c.id == 2).unwrap();
+/// assert_eq!(conn.id, 2);
+/// ```
+pub struct QueryableObjectPool<T: Send> {
+    inner: ObjectPool<T>,
+}
+
+impl<T: Send + Sync + 'static> QueryableObjectPool<T> {
+    /// Create a new queryable pool
+    pub fn new(objects: Vec<T>, config: PoolConfiguration<T>) -> Self {
+        Self {
+            inner: ObjectPool::new(objects, config),
+        }
+    }
+
+    /// Create a queryable pool whose initial objects carry caller-supplied
+    /// ids, which [`get_by_id`](Self::get_by_id) can look up. See
+    /// [`ObjectPool::new_with_ids`].
+    pub fn new_with_ids<I: Into<String>>(objects: Vec<(I, T)>, config: PoolConfiguration<T>) -> Self {
+        Self {
+            inner: ObjectPool::new_with_ids(objects, config),
+        }
+    }
+
+    /// Get the object seeded with external id `id`
+    ///
+    /// Fails with `PoolError::NoMatchFound` when no object was seeded with
+    /// `id` or it has since left the pool. While it is checked out, the
+    /// pool's [`WaitPolicy`](crate::WaitPolicy) decides whether to wait for
+    /// its return.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{PoolConfiguration, PoolError, QueryableObjectPool};
+    ///
+    /// let pool = QueryableObjectPool::new_with_ids(
+    ///     vec![("c-41", 41), ("c-42", 42)],
+    ///     PoolConfiguration::default(),
+    /// );
+    ///
+    /// let conn = pool.get_by_id("c-42").unwrap();
+    /// assert_eq!(*conn, 42);
+    /// assert!(matches!(pool.get_by_id("c-42"), Err(PoolError::NoMatchFound)));
+    /// assert!(matches!(pool.get_by_id("c-99"), Err(PoolError::NoMatchFound)));
+    /// ```
+    #[must_use = "the pool object must be used or explicitly dropped"]
+    pub fn get_by_id(&self, id: &str) -> PoolResult<PooledObject<T>> {
+        let Some(target) = self.inner.external_ids.object(id) else {
+            return Err(PoolError::NoMatchFound);
+        };
+        self.inner
+            .wait_for(is_no_match, || self.scan(|_, object_id| object_id == target, None))
+    }
+    
+    /// Get an object matching `query`
+    ///
+    /// Fails with `PoolError::NoMatchFound` when nothing matches, unless the
+    /// pool's [`WaitPolicy`](crate::WaitPolicy) says to wait for one.
+    #[must_use = "the pool object must be used or explicitly dropped"]
+    #[track_caller]
+    pub fn get_object<F>(&self, query: F) -> PoolResult<PooledObject<T>>
+    where
+        F: Fn(&T) -> bool,
+    {
+        located(self.inner.wait_for(is_no_match, || self.find_now(&query, None)))
+    }
+
+    /// Get an object matching `query`, scanning only partition `hint`
+    ///
+    /// For pools configured with
+    /// [`with_partitions`](PoolConfiguration::with_partitions), only idle
+    /// objects for which the partition function returned `hint` (or a value
+    /// sharing its bucket) are examined, so `query` must only match objects of
+    /// that partition. Without partitioning this is the same as
+    /// [`get_object`](Self::get_object).
+    #[must_use = "the pool object must be used or explicitly dropped"]
+    #[track_caller]
+    pub fn get_object_with_hint<F>(&self, query: F, hint: u64) -> PoolResult<PooledObject<T>>
+    where
+        F: Fn(&T) -> bool,
+    {
+        located(self.inner.wait_for(is_no_match, || self.find_now(&query, Some(hint))))
+    }
+
+    /// Single, non-waiting search of the available objects, limited to one
+    /// partition when `hint` is given.
+    fn find_now<F>(&self, query: F, hint: Option<u64>) -> PoolResult<PooledObject<T>>
+    where
+        F: Fn(&T) -> bool,
+    {
+        self.scan(|obj, _| query(obj), hint)
+    }
+
+    /// [`find_now`](Self::find_now) with a query that also sees each
+    /// object's internal id.
+    fn scan<F>(&self, query: F, hint: Option<u64>) -> PoolResult<PooledObject<T>>
+    where
+        F: Fn(&T, usize) -> bool,
+    {
+        self.inner.check_open()?;
+        self.inner.check_circuit_breaker()?;
+        self.inner.try_acquire_active_slot()?;
+
+        // Rotate through the idle objects one at a time, putting each
+        // non-match straight back. At most one object is out of the queue at
+        // any moment, so concurrent acquisitions never see a pool emptied by
+        // the scan. Each bucket is visited for as many steps as it held
+        // objects when the scan reached it.
+        let available = self.inner.available.as_ref();
+        let buckets = match hint {
+            Some(hint) => {
+                let bucket = available.bucket_of(hint);
+                bucket..bucket + 1
+            }
+            None => 0..available.bucket_count(),
+        };
+        let mut found = None;
+        let mut scanned = 0;
+
+        'scan: for bucket in buckets {
+            for _ in 0..available.bucket_len(bucket) {
+                let Some((obj, id)) = available.pop_bucket(bucket) else {
+                    break;
+                };
+                scanned += 1;
+                if self.inner.eviction.is_expired(id) {
+                    self.inner.evict(obj, id);
+                    continue;
+                }
+
+                if query(&obj, id) {
+                    found = Some((obj, id));
+                    break 'scan;
+                }
+
+                if let Err((obj, failed_id)) =
+                    ObjectPool::<T>::push_available_with_retry(available, (obj, id))
+                {
+                    // Concurrent returns filled the queue while this object
+                    // was out for inspection.
+                    self.inner.discard_overflow(obj, failed_id);
+                }
+            }
+        }
+        self.inner.metrics.queries.record(scanned, found.is_some());
+        
+        if let Some((obj, id)) = found {
+            self.inner.eviction.checked_out(id);
+            self.inner.metrics.total_retrieved.fetch_add(1, Ordering::Relaxed);
+
+            if self.inner.feeds_acquisition() {
+                self.inner.record_outcome(true);
+            }
+            
+            self.inner.wrap(obj, id, None)
+        } else {
+            // Release the slot we reserved — no match was found.
+            self.inner.active_count.release(1);
+            if self.inner.feeds_acquisition() {
+                self.inner.record_outcome(false);
+            }
+            Err(PoolError::NoMatchFound)
+        }
+    }
+    
+    /// Try to get an object matching query
+    #[track_caller]
+    pub fn try_get_object<F>(&self, query: F) -> PoolResult<Option<PooledObject<T>>>
+    where
+        F: Fn(&T) -> bool,
+    {
+        match located(self.find_now(query, None)) {
+            Ok(obj) => Ok(Some(obj)),
+            Err(PoolError::NoMatchFound) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+    
+    /// Get an object matching query asynchronously
+    #[track_caller]
+    pub fn get_object_async<F>(&self, query: F) -> Acquire<'_, T>
+    where
+        F: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        Acquire::new(async move { self.inner.wait_for_async(is_no_match, || self.find_now(&query, None)).await })
+    }
+
+    /// How the pool's searches went: objects scanned per search, match
+    /// rate and searches that found nothing. See [`QueryMetrics`].
+    #[must_use]
+    pub fn query_metrics(&self) -> QueryMetrics {
+        self.inner.get_metrics().queries
+    }
+    
+    // Delegate methods to inner pool
+    #[must_use]
+    pub fn get_health_status(&self) -> HealthStatus {
+        self.inner.get_health_status()
+    }
+
+    #[must_use]
+    pub fn available_count(&self) -> usize {
+        self.inner.available_count()
+    }
+
+    #[must_use]
+    pub fn active_count(&self) -> usize {
+        self.inner.active_count()
+    }
+
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    /// Proactively remove expired objects. See [`ObjectPool::evict_expired`].
+    #[must_use = "returns the count of evicted objects"]
+    pub fn evict_expired(&self) -> usize {
+        self.inner.evict_expired()
+    }
+
+    /// Drain all available objects. See [`ObjectPool::drain`].
+    #[must_use = "returns the drained objects"]
+    pub fn drain(&self) -> Vec<T> {
+        self.inner.drain()
+    }
+
+    /// Return a group of guards in one pass. See [`ObjectPool::return_many`].
+    pub fn return_many<I>(&self, objects: I) -> usize
+    where
+        I: IntoIterator<Item = PooledObject<T>>,
+    {
+        self.inner.return_many(objects)
+    }
+
+    /// Subscribe to return-path error reports. See [`ObjectPool::return_errors`].
+    #[must_use]
+    pub fn return_errors(&self) -> tokio::sync::broadcast::Receiver<ReturnError> {
+        self.inner.return_errors()
+    }
+
+    /// Subscribe to warnings about the pool. See [`ObjectPool::warnings`].
+    #[must_use]
+    pub fn warnings(&self) -> tokio::sync::broadcast::Receiver<PoolWarning> {
+        self.inner.warnings()
+    }
+
+    /// Subscribe to pool events. See [`ObjectPool::events`].
+    #[must_use]
+    pub fn events(&self) -> tokio::sync::broadcast::Receiver<PoolEvent> {
+        self.inner.events()
+    }
+
+    /// Have `observer` receive the pool's events, metrics and health
+    /// changes. See [`ObjectPool::observe`].
+    pub fn observe(self: &Arc<Self>, observer: impl PoolObserver, every: Duration) -> ObserverHandle {
+        ObserverHandle::spawn(Arc::downgrade(self) as Weak<dyn Watched>, observer, every)
+    }
+
+    /// Log pool events through `tracing`. See [`ObjectPool::log_events`].
+    #[cfg(feature = "tracing")]
+    pub fn log_events(&self, logger: EventLogger) {
+        self.inner.log_events(logger);
+    }
+
+    #[must_use]
+    pub fn get_metrics(&self) -> PoolMetrics {
+        self.inner.get_metrics()
+    }
+
+    #[must_use]
+    pub fn export_metrics(&self) -> HashMap<String, String> {
+        self.inner.export_metrics()
+    }
+
+    #[must_use]
+    pub fn export_metrics_prometheus(
+        &self,
+        pool_name: &str,
+        tags: Option<&HashMap<String, String>>,
+    ) -> String {
+        self.inner.export_metrics_prometheus(pool_name, tags)
+    }
+}
+
+impl<T: Send> Deref for QueryableObjectPool<T> {
+    type Target = ObjectPool<T>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+/// Room reserved for an object being created asynchronously: a share of
+/// the population, an active slot and a pending creation. Given back on
+/// drop unless filled.
+struct CreationRoom<'a, T: Send + Sync + 'static> {
+    pool: &'a ObjectPool<T>,
+    epoch: u64,
+    pending: Option<PendingCreation<'a>>,
+    filled: bool,
+}
+
+impl<T: Send + Sync + 'static> CreationRoom<'_, T> {
+    /// Hand out the created object, or account for the failure to create it.
+    fn fill(mut self, created: Result<T, String>) -> PoolResult<PooledObject<T>> {
+        let pool = self.pool;
+        let created = pool.hooks.create(|| created);
+        self.end_pending();
+        pool.creation_throttle.record(created.is_ok());
+        match created {
+            Ok(obj) => {
+                self.filled = true;
+                pool.adopt_created(obj, self.epoch, None)
+            }
+            Err(reason) => {
+                pool.metrics.creation_failures.fetch_add(1, Ordering::Relaxed);
+                Err(PoolError::CreationFailed(reason))
+            }
+        }
+    }
+
+    fn end_pending(&mut self) {
+        if self.pending.take().is_some() {
+            // Callers that coalesced onto this creation may start their own.
+            self.pool.released.notify_waiters();
+        }
+    }
+}
+
+/// What an opening acquisition got hold of: an idle object, or room for
+/// a new one.
+enum Opening<'a, T: Send + Sync + 'static> {
+    Reused(PooledObject<T>),
+    Room(CreationRoom<'a, T>),
+}
+
+impl<T: Send + Sync + 'static> Drop for CreationRoom<'_, T> {
+    fn drop(&mut self) {
+        self.end_pending();
+        if !self.filled {
+            self.pool.active_count.release(1);
+            self.pool.release_population();
+        }
+    }
+}
+
+/// Dynamic object pool - creates objects on demand
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{DynamicObjectPool, PoolConfiguration};
+///
+/// let pool = DynamicObjectPool::new(
+///     || 42,
+///     PoolConfiguration::new().with_max_pool_size(10)
+/// );
+///
+/// let obj = pool.get_object().unwrap();
+/// assert_eq!(*obj, 42);
+/// ```
+pub struct DynamicObjectPool<T: Send> {
+    inner: ObjectPool<T>,
+    /// Replaced by [`DynamicObjectPool::update_factory`], which also starts
+    /// a new epoch while holding the write lock.
+    factory: RwLock<Factory<T>>,
+    /// Serves degraded objects while the circuit breaker is open, when
+    /// configured.
+    degraded: Option<Arc<DegradedTier<T>>>,
+}
+
+impl<T: Send + Sync + 'static> DynamicObjectPool<T> {
+    /// Create a new dynamic pool with factory function
+    pub fn new<F>(factory: F, config: PoolConfiguration<T>) -> Self
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+    {
+        Self::from_factory(Arc::new(move || Ok(factory())), Vec::new(), config)
+    }
+
+    /// Create a dynamic pool whose factory can fail
+    ///
+    /// A failed creation is returned to the caller as
+    /// `PoolError::CreationFailed` carrying the factory's error message, and
+    /// counted in [`PoolMetrics::creation_failures`]. Combine with
+    /// [`with_creation_backoff`](PoolConfiguration::with_creation_backoff)
+    /// to stop a failing backend from being hammered with retries.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{DynamicObjectPool, PoolConfiguration, PoolError};
+    ///
+    /// let pool = DynamicObjectPool::try_new(
+    ///     || Err::<u32, _>("connection refused"),
+    ///     PoolConfiguration::default(),
+    /// );
+    ///
+    /// match pool.get_object() {
+    ///     Err(PoolError::CreationFailed(reason)) => assert_eq!(reason, "connection refused"),
+    ///     other => panic!("unexpected {other:?}"),
+    /// }
+    /// assert_eq!(pool.get_metrics().creation_failures, 1);
+    /// ```
+    pub fn try_new<F, E>(factory: F, config: PoolConfiguration<T>) -> Self
+    where
+        F: Fn() -> Result<T, E> + Send + Sync + 'static,
+        E: std::fmt::Display,
+    {
+        Self::from_factory(
+            Arc::new(move || factory().map_err(|err| err.to_string())),
+            Vec::new(),
+            config,
+        )
+    }
+
+    /// Create a dynamic pool with initial objects and factory
+    pub fn with_initial<F>(factory: F, initial_objects: Vec<T>, config: PoolConfiguration<T>) -> Self
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+    {
+        Self::from_factory(Arc::new(move || Ok(factory())), initial_objects, config)
+    }
+
+    /// Create a dynamic pool from an already boxed factory and initial
+    /// objects
+    ///
+    /// Suits factories chosen at runtime, and pools of trait objects: the
+    /// factory's type fixes `T`, so initial objects written as
+    /// `Box::new(..)` coerce to it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{DynamicObjectPool, PoolConfiguration};
+    ///
+    /// trait Transport {
+    ///     fn scheme(&self) -> &'static str;
+    /// }
+    ///
+    /// struct Tcp;
+    /// struct Unix;
+    ///
+    /// impl Transport for Tcp {
+    ///     fn scheme(&self) -> &'static str { "tcp" }
+    /// }
+    /// impl Transport for Unix {
+    ///     fn scheme(&self) -> &'static str { "unix" }
+    /// }
+    ///
+    /// type BoxedTransport = Box<dyn Transport + Send + Sync>;
+    ///
+    /// let local = true;
+    /// let factory: Box<dyn Fn() -> BoxedTransport + Send + Sync> = if local {
+    ///     Box::new(|| Box::new(Unix))
+    /// } else {
+    ///     Box::new(|| Box::new(Tcp))
+    /// };
+    ///
+    /// let pool = DynamicObjectPool::from_boxed_factory(factory, vec![Box::new(Tcp)], PoolConfiguration::default());
+    /// let first = pool.get_object().unwrap();
+    /// let second = pool.get_object().unwrap();
+    /// assert_eq!((first.scheme(), second.scheme()), ("tcp", "unix"));
+    /// ```
+    pub fn from_boxed_factory(
+        factory: Box<dyn Fn() -> T + Send + Sync>,
+        initial_objects: Vec<T>,
+        config: PoolConfiguration<T>,
+    ) -> Self {
+        Self::from_factory(Arc::new(move || Ok(factory())), initial_objects, config)
+    }
+
+    pub(crate) fn from_factory(factory: Factory<T>, initial_objects: Vec<T>, config: PoolConfiguration<T>) -> Self {
+        let degraded = config.degraded_factory.clone();
+        let inner = ObjectPool::new(initial_objects, config).replacing_lost_objects();
+        let degraded = degraded
+            .filter(|_| inner.circuit_breaker.is_some())
+            .map(|factory| Arc::new(DegradedTier::new(factory, inner.capacity)));
+        Self {
+            inner,
+            factory: RwLock::new(factory),
+            degraded,
+        }
+    }
+    
+    /// Point the pool at a new factory and retire every object made by
+    /// the old one
+    ///
+    /// For endpoint or credential changes without downtime: acquisitions
+    /// that need a new object use `factory` from now on, while objects
+    /// built by the previous factory are drained out as in
+    /// [`invalidate_all`](Self::invalidate_all) — idle ones are destroyed
+    /// when next touched, checked-out ones when returned. A creation already
+    /// running with the old factory when this is called produces an object
+    /// of the old epoch, which is retired the same way.
+    ///
+    /// Returns the new epoch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{DynamicObjectPool, PoolConfiguration};
+    ///
+    /// let pool = DynamicObjectPool::new(|| "db-a:5432", PoolConfiguration::default());
+    /// let old = pool.get_object().unwrap();
+    ///
+    /// assert_eq!(pool.update_factory(|| "db-b:5432"), 1);
+    /// assert_eq!(*pool.get_object().unwrap(), "db-b:5432");
+    ///
+    /// // Still usable by its holder, but not reused once returned.
+    /// assert_eq!(*old, "db-a:5432");
+    /// drop(old);
+    /// assert_eq!(*pool.get_object().unwrap(), "db-b:5432");
+    /// ```
+    pub fn update_factory<F>(&self, factory: F) -> u64
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+    {
+        self.swap_factory(Arc::new(move || Ok(factory())), true)
+    }
+
+    /// [`update_factory`](Self::update_factory) for a factory that can fail,
+    /// as accepted by [`try_new`](Self::try_new)
+    pub fn try_update_factory<F, E>(&self, factory: F) -> u64
+    where
+        F: Fn() -> Result<T, E> + Send + Sync + 'static,
+        E: std::fmt::Display,
+    {
+        self.swap_factory(Arc::new(move || factory().map_err(|err| err.to_string())), true)
+    }
+
+    /// Use a new factory for objects created from now on, keeping the
+    /// objects the pool already owns
+    ///
+    /// For changes that existing objects can live with, such as a new
+    /// default applied only to fresh connections. To retire the existing
+    /// objects as well, use [`update_factory`](Self::update_factory).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{DynamicObjectPool, PoolConfiguration};
+    ///
+    /// let pool = DynamicObjectPool::new(|| vec![0u8; 1024], PoolConfiguration::default());
+    /// drop(pool.get_object().unwrap());
+    ///
+    /// pool.replace_factory(|| vec![0u8; 4096]);
+    /// assert_eq!(pool.get_object().unwrap().len(), 1024, "idle buffer reused");
+    /// assert_eq!(pool.epoch(), 0);
+    /// ```
+    pub fn replace_factory<F>(&self, factory: F)
+    where
+        F: Fn() -> T + Send + Sync + 'static,
+    {
+        self.swap_factory(Arc::new(move || Ok(factory())), false);
+    }
+
+    /// Install `factory`, starting a new epoch if `retire` is set; returns
+    /// the epoch new objects belong to.
+    fn swap_factory(&self, factory: Factory<T>, retire: bool) -> u64 {
+        let mut current = self.factory.write().unwrap_or_else(PoisonError::into_inner);
+        *current = factory;
+        if retire {
+            self.inner.invalidate_all()
+        } else {
+            self.inner.epoch()
+        }
+    }
+
+    /// The current factory and the epoch objects it creates belong to.
+    fn factory(&self) -> (Factory<T>, u64) {
+        let factory = self.factory.read().unwrap_or_else(PoisonError::into_inner);
+        (Arc::clone(&factory), self.inner.epoch())
+    }
+
+    /// Get an object, creating one via the factory if the pool is empty.
+    ///
+    /// Dynamic creation only proceeds when the pool is empty **and** the
+    /// number of objects it owns (checked out or idle, including warmed-up
+    /// ones) is below `max_pool_size`. `CircuitBreakerOpen` and
+    /// `MaxActiveObjectsReached` are propagated immediately — the factory is
+    /// **never** called in those cases.
+    ///
+    /// Room for the new object is reserved atomically before the factory
+    /// runs, so concurrent callers can never create more objects than the
+    /// configured capacity.
+    ///
+    /// When the pool is at capacity, the pool's
+    /// [`WaitPolicy`](crate::WaitPolicy) decides whether to fail with
+    /// `PoolError::PoolFull` or wait for an object to be returned. The same
+    /// holds for `PoolError::CreationThrottled` while creation is
+    /// [rate limited](PoolConfiguration::with_creation_rate_limit) or
+    /// [backing off](PoolConfiguration::with_creation_backoff). A factory
+    /// error is returned as `PoolError::CreationFailed` without waiting.
+    ///
+    /// Whether an empty pool creates at once or first waits for a returned
+    /// object is set by the pool's
+    /// [`CreationPolicy`](crate::CreationPolicy).
+    #[must_use = "the pool object must be used or explicitly dropped"]
+    #[track_caller]
+    pub fn get_object(&self) -> PoolResult<PooledObject<T>> {
+        located(self.reuse_or(None, false, || {
+            self.inner.wait_for(is_creation_blocked, || self.acquire_now())
+        }))
+    }
+
+    /// Single, non-waiting acquisition attempt.
+    fn acquire_now(&self) -> PoolResult<PooledObject<T>> {
+        self.acquire_or_create(self.inner.feeds_acquisition(), None)
+    }
+
+    /// How long the [`CreationPolicy`](crate::CreationPolicy) has an acquisition wait for a
+    /// returned object before creating one, if at all.
+    fn reuse_patience(&self, ctx: Option<&Arc<AcquireContext>>) -> Option<Duration> {
+        // With nothing checked out, nothing can come back.
+        if self.inner.active_count.load() == 0 {
+            return None;
+        }
+        let population = self.inner.population.load(Ordering::Acquire);
+        let patience = self.inner.config.creation_policy.patience(population)?;
+        match ctx.map_or(WaitPolicy::Wait(patience), |ctx| ctx.limit_wait(WaitPolicy::Wait(patience))) {
+            WaitPolicy::Wait(patience) => Some(patience),
+            _ => None,
+        }
+    }
+
+    /// Wait out the creation policy's patience for an idle object, then
+    /// fall back to `acquire`.
+    fn reuse_or(
+        &self,
+        ctx: Option<&Arc<AcquireContext>>,
+        in_place: bool,
+        acquire: impl FnOnce() -> PoolResult<PooledObject<T>>,
+    ) -> PoolResult<PooledObject<T>> {
+        if let Some(patience) = self.reuse_patience(ctx) {
+            let policy = WaitPolicy::Wait(patience);
+            let reuse = || self.inner.acquire_idle(false, ctx);
+            let reused = if in_place {
+                wait_in_place(
+                    policy,
+                    self.inner.config.retry_backoff(),
+                    is_pool_empty,
+                    self.inner.circuit_breaker.as_deref(),
+                    reuse,
+                )
+            } else {
+                self.inner.wait_with(policy, is_pool_empty, reuse)
+            };
+            if !matches!(reused, Err(PoolError::Timeout(..))) {
+                return reused;
+            }
+        }
+        acquire()
+    }
+
+    /// Async counterpart of [`reuse_or`](Self::reuse_or).
+    async fn reuse_or_async(
+        &self,
+        ctx: Option<&Arc<AcquireContext>>,
+        acquire: impl Future<Output = PoolResult<PooledObject<T>>>,
+    ) -> PoolResult<PooledObject<T>> {
+        if let Some(patience) = self.reuse_patience(ctx) {
+            let reused = self
+                .inner
+                .wait_with_async(WaitPolicy::Wait(patience), is_pool_empty, || {
+                    self.inner.acquire_idle(false, ctx)
+                })
+                .await;
+            if !matches!(reused, Err(PoolError::Timeout(..))) {
+                return reused;
+            }
+        }
+        acquire.await
+    }
+
+    /// Blocking acquisition that does not stall an async runtime, creating
+    /// an object if needed. See [`ObjectPool::get_object_blocking_in_place`].
+    #[must_use = "the pool object must be used or explicitly dropped"]
+    #[track_caller]
+    pub fn get_object_blocking_in_place(&self) -> PoolResult<PooledObject<T>> {
+        located(self.reuse_or(None, true, || {
+            self.inner.wait_in_place(is_creation_blocked, || self.acquire_now())
+        }))
+    }
+
+    /// Get an object on behalf of the caller described by `ctx`, creating
+    /// one if needed. See [`ObjectPool::get_object_with`].
+    #[must_use = "the pool object must be used or explicitly dropped"]
+    #[track_caller]
+    pub fn get_object_with(&self, ctx: AcquireContext) -> PoolResult<PooledObject<T>> {
+        let ctx = Arc::new(ctx);
+        let result = self.reuse_or(Some(&ctx), false, || {
+            let policy = ctx.limit_wait(self.inner.config.blocking_wait_policy());
+            self.inner.wait_with(policy, is_creation_blocked, || {
+                self.acquire_or_create(self.inner.feeds_acquisition(), Some(&ctx))
+            })
+        });
+        located(self.inner.track_caller(&ctx, result))
+    }
+
+    /// Async counterpart of [`get_object_with`](Self::get_object_with)
+    #[track_caller]
+    pub fn get_object_with_async(&self, ctx: AcquireContext) -> Acquire<'_, T> {
+        Acquire::new(async move {
+            let ctx = Arc::new(ctx);
+            let create = async {
+                let policy = ctx.limit_wait(self.inner.config.async_wait_policy());
+                self.inner
+                    .wait_with_async(policy, is_creation_blocked, || {
+                        self.acquire_or_create(self.inner.feeds_acquisition(), Some(&ctx))
+                    })
+                    .await
+            };
+            let result = self.reuse_or_async(Some(&ctx), create).await;
+            self.inner.track_caller(&ctx, result)
+        })
+    }
+
+    /// See [`ObjectPool::acquire_idle`] for `feed_breaker`.
+    fn acquire_or_create(&self, feed_breaker: bool, ctx: Option<&Arc<AcquireContext>>) -> PoolResult<PooledObject<T>> {
+        self.acquire_or_create_from(feed_breaker, ctx, None)
+    }
+
+    /// [`acquire_or_create`](Self::acquire_or_create), making a new object
+    /// with `create` instead of the pool's factory if given.
+    fn acquire_or_create_from(
+        &self,
+        feed_breaker: bool,
+        ctx: Option<&Arc<AcquireContext>>,
+        create: Option<Creator<'_, T>>,
+    ) -> PoolResult<PooledObject<T>> {
+        match self.inner.acquire_idle(feed_breaker, ctx) {
+            Ok(obj) => Ok(obj),
+            Err(PoolError::PoolEmpty) => {
+                if !self.inner.reserve_population() {
+                    return Err(PoolError::PoolFull);
+                }
+
+                // Also enforce max_active_objects in the dynamic creation path.
+                // Use the same CAS semaphore to remain race-free.
+                if let Err(err) = self.inner.try_acquire_active_slot() {
+                    self.inner.release_population();
+                    return Err(err);
+                }
+
+                let (factory, epoch) = self.factory();
+                let obj = match create {
+                    Some(create) => self.inner.create_with(|| Ok(create())),
+                    None => self.inner.create_with(|| factory()),
+                }?;
+
+                // The inner `get_object()` recorded a CB failure for the empty
+                // queue. Since we successfully served the request, offset it with
+                // a success so routine dynamic creation doesn't trip the breaker.
+                if feed_breaker {
+                    self.inner.record_circuit_breaker_success();
+                }
+
+                self.inner.adopt_created(obj, epoch, ctx)
+            }
+            Err(PoolError::CircuitBreakerOpen) if self.degraded.is_some() => self.serve_degraded(),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Hand out an object from the degraded-tier factory, which is dropped
+    /// rather than pooled when given back. `CircuitBreakerOpen` when the
+    /// tier is at capacity or its factory fails.
+    fn serve_degraded(&self) -> PoolResult<PooledObject<T>> {
+        let Some(tier) = self.degraded.as_ref() else {
+            return Err(PoolError::CircuitBreakerOpen);
+        };
+        let obj = tier.create().ok_or(PoolError::CircuitBreakerOpen)?;
+        self.inner.metrics.degraded_objects.fetch_add(1, Ordering::Relaxed);
+        let (returned, detached) = (Arc::clone(tier), Arc::clone(tier));
+        let mut guard = PooledObject::new(
+            obj,
+            self.inner.next_id.fetch_add(1, Ordering::Relaxed),
+            Arc::new(move |obj, _, _, _| {
+                drop(obj);
+                returned.release();
+                Ok(())
+            }),
+            Arc::new(move |_| detached.release()),
+        );
+        guard.origin = ObjectOrigin::Degraded;
+        Ok(guard)
+    }
+    
+    /// Get an object on behalf of a bulkhead `category`, creating one if
+    /// needed. See [`ObjectPool::get_object_in`].
+    #[must_use = "the pool object must be used or explicitly dropped"]
+    #[track_caller]
+    pub fn get_object_in(&self, category: &str) -> PoolResult<PooledObject<T>> {
+        self.inner.bulkheads.check(category)?;
+        let mut obj = self.get_object()?;
+        // Dropping `obj` hands it back if the category filled up meanwhile.
+        obj.permit = Some(self.inner.bulkheads.enter(category)?);
+        Ok(obj)
+    }
+
+    /// Async counterpart of [`get_object_in`](Self::get_object_in)
+    #[track_caller]
+    pub fn get_object_in_async<'a>(&'a self, category: &'a str) -> Acquire<'a, T> {
+        let acquire = self.get_object_async();
+        Acquire::new(async move {
+            self.inner.bulkheads.check(category)?;
+            let mut obj = acquire.await?;
+            obj.permit = Some(self.inner.bulkheads.enter(category)?);
+            Ok(obj)
+        })
+    }
+
+    /// Objects checked out under bulkhead `category`. See
+    /// [`ObjectPool::bulkhead_active`].
+    #[must_use]
+    pub fn bulkhead_active(&self, category: &str) -> Option<usize> {
+        self.inner.bulkhead_active(category)
+    }
+
+    /// Checked-out objects and their holders. See
+    /// [`ObjectPool::current_holders`].
+    #[must_use]
+    pub fn current_holders(&self) -> Vec<Holder> {
+        self.inner.current_holders()
+    }
+
+    /// Recent acquisitions, returns and evictions. See
+    /// [`ObjectPool::recent_activity`].
+    #[must_use]
+    pub fn recent_activity(&self) -> Vec<ActivityRecord> {
+        self.inner.recent_activity()
+    }
+
+    /// Report a successful operation. See [`ObjectPool::report_success`].
+    pub fn report_success(&self) {
+        self.inner.report_success();
+    }
+
+    /// Report a failed operation. See [`ObjectPool::report_failure`].
+    pub fn report_failure(&self) {
+        self.inner.report_failure();
+    }
+
+    /// Run `op` on a pooled object, creating one if needed, with `op`'s
+    /// result driving the circuit breaker. See [`ObjectPool::run_guarded`].
+    pub fn run_guarded<R, E, F>(&self, op: F) -> Result<R, GuardedError<E>>
+    where
+        F: FnOnce(&mut T) -> Result<R, E>,
+    {
+        let mut obj = self.reuse_or(None, false, || {
+            self.inner.wait_for(is_creation_blocked, || self.acquire_or_create(false, None))
+        })?;
+        let result = op(&mut obj);
+        self.inner.report_outcome(result.is_ok());
+        result.map_err(GuardedError::Operation)
+    }
+
+    /// Async counterpart of [`run_guarded`](Self::run_guarded). See
+    /// [`ObjectPool::run_guarded_async`].
+    pub async fn run_guarded_async<R, E, F, Fut>(&self, op: F) -> Result<R, GuardedError<E>>
+    where
+        F: FnOnce(PooledObject<T>) -> Fut,
+        Fut: Future<Output = Result<R, E>>,
+    {
+        let create = self
+            .inner
+            .wait_for_async(is_creation_blocked, || self.acquire_or_create(false, None));
+        let obj = self.reuse_or_async(None, create).await?;
+        let result = op(obj).await;
+        self.inner.report_outcome(result.is_ok());
+        result.map_err(GuardedError::Operation)
+    }
+
+    /// Run `op` on a pooled object, replacing the object and retrying when
+    /// `policy` classifies the error as a broken object. See
+    /// [`ObjectPool::execute_with_retry`].
+    pub fn execute_with_retry<R, E, F>(&self, policy: &RetryPolicy<E>, op: F) -> Result<R, GuardedError<E>>
+    where
+        F: FnMut(&mut T) -> Result<R, E>,
+    {
+        retry::execute(
+            policy,
+            || {
+                self.reuse_or(None, false, || {
+                    self.inner.wait_for(is_creation_blocked, || self.acquire_or_create(false, None))
+                })
+            },
+            |success| self.inner.report_outcome(success),
+            op,
+        )
+    }
+
+    /// Get an object with a lease of `max_hold`, creating one if needed.
+    /// See [`ObjectPool::get_object_leased`].
+    #[must_use = "the pool object must be used or explicitly dropped"]
+    #[track_caller]
+    pub fn get_object_leased(&self, max_hold: Duration) -> PoolResult<PooledObject<T>> {
+        let obj = self.get_object()?;
+        Ok(self.inner.attach_lease(obj, max_hold, None))
+    }
+
+    /// Get an object with a lease and an expiry callback, creating one if
+    /// needed. See [`ObjectPool::get_object_leased_with`].
+    #[must_use = "the pool object must be used or explicitly dropped"]
+    #[track_caller]
+    pub fn get_object_leased_with<F>(&self, max_hold: Duration, on_expire: F) -> PoolResult<PooledObject<T>>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let obj = self.get_object()?;
+        Ok(self.inner.attach_lease(obj, max_hold, Some(Box::new(on_expire))))
+    }
+
+    /// Try to get an object
+    #[track_caller]
+    pub fn try_get_object(&self) -> PoolResult<Option<PooledObject<T>>> {
+        match located(self.acquire_now()) {
+            Ok(obj) => Ok(Some(obj)),
+            Err(PoolError::PoolFull | PoolError::CreationPending) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Take an idle object without locking, allocating or waiting. Never
+    /// calls the factory, so an empty pool yields `None` even below
+    /// capacity. See [`ObjectPool::try_get_object_fast`].
+    #[must_use = "the pool object must be used or explicitly dropped"]
+    #[track_caller]
+    pub fn try_get_object_fast(&self) -> Option<PooledObject<T>> {
+        self.inner.try_get_object_fast()
+    }
+
+    /// Whether [`try_get_object_fast`](Self::try_get_object_fast) can hand
+    /// out objects. See [`ObjectPool::supports_fast_path`].
+    #[must_use]
+    pub fn supports_fast_path(&self) -> bool {
+        self.inner.supports_fast_path()
+    }
+    
+    /// Get an object asynchronously, creating one if needed
+    ///
+    /// Cancel safe; see [`ObjectPool::get_object_async`].
+    #[track_caller]
+    pub fn get_object_async(&self) -> Acquire<'_, T> {
+        Acquire::new(async move {
+            let create = self.inner.wait_for_async(is_creation_blocked, || self.acquire_now());
+            self.reuse_or_async(None, create).await
+        })
+    }
+
+    /// [`get_object`](Self::get_object), making a new object with `create`
+    /// instead of the pool's factory.
+    #[track_caller]
+    pub(crate) fn get_object_creating(&self, create: Creator<'_, T>) -> PoolResult<PooledObject<T>> {
+        located(self.reuse_or(None, false, || {
+            self.inner.wait_for(is_creation_blocked, || {
+                self.acquire_or_create_from(self.inner.feeds_acquisition(), None, Some(create))
+            })
+        }))
+    }
+
+    /// Async counterpart of [`get_object_creating`](Self::get_object_creating)
+    pub(crate) async fn get_object_creating_async(
+        &self,
+        create: &(dyn Fn() -> T + Sync),
+    ) -> PoolResult<PooledObject<T>> {
+        let acquire = self.inner.wait_for_async(is_creation_blocked, || {
+            self.acquire_or_create_from(self.inner.feeds_acquisition(), None, Some(create))
+        });
+        self.reuse_or_async(None, acquire).await
+    }
+
+    /// Async acquisition making a new object by awaiting `open` instead of
+    /// calling the factory
+    ///
+    /// Room for the object is reserved before `open` runs, under the
+    /// creation rate limit and the pending-creation cap, and an error from
+    /// `open` counts as a creation failure. Unless `fresh`, an idle object
+    /// is reused first; with it, one is reused only while the pool is full.
+    pub(crate) async fn get_object_opening_async<F>(
+        &self,
+        fresh: bool,
+        open: impl FnOnce() -> F,
+    ) -> PoolResult<PooledObject<T>>
+    where
+        F: Future<Output = Result<T, String>>,
+    {
+        let idle = || match self.inner.acquire_idle(false, None) {
+            Err(PoolError::PoolEmpty) => None,
+            taken => Some(taken),
+        };
+        let claimed = self
+            .inner
+            .wait_for_async(is_creation_blocked, || {
+                if !fresh && let Some(taken) = idle() {
+                    return taken.map(Opening::Reused);
+                }
+                match self.inner.reserve_creation() {
+                    Err(PoolError::PoolFull) if fresh => {
+                        idle().unwrap_or(Err(PoolError::PoolFull)).map(Opening::Reused)
+                    }
+                    reserved => reserved.map(Opening::Room),
+                }
+            })
+            .await?;
+        match claimed {
+            Opening::Reused(obj) => Ok(obj),
+            Opening::Room(room) => room.fill(open().await),
+        }
+    }
+
+    /// Factory calls currently in progress
+    ///
+    /// Bounded by
+    /// [`with_max_pending_creations`](PoolConfiguration::with_max_pending_creations)
+    /// when set.
+    #[must_use]
+    pub fn pending_creations(&self) -> usize {
+        self.inner.pending_creations.in_flight()
+    }
+
+    /// Number of acquisitions currently waiting for an object. See
+    /// [`ObjectPool::waiting_count`].
+    #[must_use]
+    pub fn waiting_count(&self) -> usize {
+        self.inner.waiting_count()
+    }
+
+    /// Recommended settings from observed behaviour. See
+    /// [`ObjectPool::tuning_advice`].
+    #[must_use]
+    pub fn tuning_advice(&self) -> Option<PoolTuningAdvice> {
+        self.inner.advise(self.inner.capacity)
+    }
+
+    /// Error budgets of the configured objectives. See
+    /// [`ObjectPool::slo_status`].
+    #[must_use]
+    pub fn slo_status(&self) -> Option<SloStatus> {
+        self.inner.slo_status()
+    }
+
+    /// Why the most recent failed acquisition attempt failed. See
+    /// [`ObjectPool::explain_last_failure`].
+    #[must_use]
+    pub fn explain_last_failure(&self) -> Option<FailureDiagnosis> {
+        self.inner.explain_last_failure()
+    }
+
+    /// Whether [`get_object`](Self::get_object) would succeed right now
+    /// without waiting: an object is idle or one may be created. See
+    /// [`ObjectPool::is_ready`].
+    #[must_use]
+    pub fn is_ready(&self) -> bool {
+        matches!(self.readiness(), Readiness::Ready)
+    }
+
+    /// How saturated the pool is, measured against its capacity rather than
+    /// the objects created so far. See [`ObjectPool::load`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{DynamicObjectPool, PoolConfiguration};
+    ///
+    /// let pool = DynamicObjectPool::new(|| 0, PoolConfiguration::new().with_max_pool_size(4));
+    /// let _held = pool.get_object().unwrap();
+    /// assert_eq!(pool.load(), 0.25);
+    /// ```
+    #[must_use]
+    pub fn load(&self) -> f64 {
+        self.inner.load_over(self.inner.capacity)
+    }
+
+    pub(crate) fn readiness(&self) -> Readiness {
+        let can_create = self.inner.population.load(Ordering::Acquire) < self.inner.capacity;
+        self.inner.readiness(can_create)
+    }
+
+    pub(crate) fn released(&self) -> Arc<Notify> {
+        self.inner.released()
+    }
+
+    /// Acquire objects as a [`Stream`](futures_core::Stream), creating them via
+    /// the factory while below capacity. See [`ObjectPool::acquire_stream`].
+    #[track_caller]
+    pub fn acquire_stream(&self) -> AcquireStream<'_, T> {
+        AcquireStream::new(move || self.get_object_async())
+    }
+
+    /// Acquire `count` objects at once, creating them as needed. See
+    /// [`ObjectPool::get_batch`].
+    #[must_use = "the batch must be used or explicitly dropped"]
+    #[track_caller]
+    pub fn get_batch(&self, count: usize) -> PoolResult<PooledBatch<'_, T>> {
+        let mut objects = Vec::with_capacity(count);
+        for _ in 0..count {
+            match self.get_object() {
+                Ok(obj) => objects.push(obj),
+                Err(err) => {
+                    self.inner.return_many(objects);
+                    return Err(err);
+                }
+            }
+        }
+        Ok(PooledBatch::new(&self.inner, objects))
+    }
+
+    /// Return a group of guards in one pass. See [`ObjectPool::return_many`].
+    pub fn return_many<I>(&self, objects: I) -> usize
+    where
+        I: IntoIterator<Item = PooledObject<T>>,
+    {
+        self.inner.return_many(objects)
+    }
+
+    /// Subscribe to return-path error reports. See [`ObjectPool::return_errors`].
+    #[must_use]
+    pub fn return_errors(&self) -> tokio::sync::broadcast::Receiver<ReturnError> {
+        self.inner.return_errors()
+    }
+
+    /// Subscribe to warnings about the pool. See [`ObjectPool::warnings`].
+    #[must_use]
+    pub fn warnings(&self) -> tokio::sync::broadcast::Receiver<PoolWarning> {
+        self.inner.warnings()
+    }
+
+    /// Subscribe to pool events. See [`ObjectPool::events`].
+    #[must_use]
+    pub fn events(&self) -> tokio::sync::broadcast::Receiver<PoolEvent> {
+        self.inner.events()
+    }
+
+    /// Have `observer` receive the pool's events, metrics and health
+    /// changes. See [`ObjectPool::observe`].
+    pub fn observe(self: &Arc<Self>, observer: impl PoolObserver, every: Duration) -> ObserverHandle {
+        ObserverHandle::spawn(Arc::downgrade(self) as Weak<dyn Watched>, observer, every)
+    }
+
+    /// Log pool events through `tracing`. See [`ObjectPool::log_events`].
+    #[cfg(feature = "tracing")]
+    pub fn log_events(&self, logger: EventLogger) {
+        self.inner.log_events(logger);
+    }
+    
+    /// Warm up the pool by pre-creating objects
+    ///
+    /// Pre-populates the pool to avoid cold-start latency. Warmup is not
+    /// subject to the creation rate limit. A factory error stops it and is
+    /// returned as `PoolError::CreationFailed`; objects created before the
+    /// error stay in the pool.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{DynamicObjectPool, PoolConfiguration};
+    ///
+    /// let pool = DynamicObjectPool::new(
+    ///     || 42,
+    ///     PoolConfiguration::default()
+    /// );
+    ///
+    /// pool.warmup(5).unwrap();
+    /// 
+    /// let health = pool.get_health_status();
+    /// assert_eq!(health.available_objects, 5);
+    /// ```
+    pub fn warmup(&self, count: usize) -> PoolResult<()> {
+        self.inner.check_open()?;
+        for _ in 0..count {
+            if !self.inner.reserve_population() {
+                break;
+            }
+            let (factory, epoch) = self.factory();
+            let obj = self
+                .inner
+                .hooks
+                .create(|| factory())
+                .map_err(|reason| self.inner.creation_failed(reason))?;
+            let id = self.inner.next_id.fetch_add(1, Ordering::Relaxed);
+            self.inner.eviction.track_object_from(id, epoch);
+            if let Some(memory) = self.inner.eviction.memory() {
+                memory.idle(id, &obj);
+            }
+            
+            if let Err((obj, id)) = self.inner.available.push((obj, id)) {
+                // Unreachable while the population stays within capacity.
+                self.inner.discard_overflow(obj, id);
+                break;
+            }
+        }
+        Ok(())
+    }
+    
+    /// Self-test: examine every idle object once, then create one object
+    /// with the factory, validate it and destroy it
+    ///
+    /// The factory check is skipped when the pool is at capacity. See
+    /// [`ObjectPool::verify`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{DynamicObjectPool, FactoryCheck, PoolConfiguration};
+    ///
+    /// let pool = DynamicObjectPool::new(
+    ///     || -1,
+    ///     PoolConfiguration::new().with_validation(|x: &i32| *x > 0),
+    /// );
+    ///
+    /// let report = pool.verify();
+    /// assert_eq!(report.factory, Some(FactoryCheck::FailedValidation));
+    /// assert!(!report.is_ok());
+    /// ```
+    #[must_use = "the report says whether the pool is healthy"]
+    pub fn verify(&self) -> VerifyReport {
+        let mut report = self.inner.verify();
+        report.factory = Some(self.verify_factory());
+        report
+    }
+
+    /// Async counterpart of [`verify`](Self::verify)
+    #[must_use = "the report says whether the pool is healthy"]
+    pub async fn verify_async(&self) -> VerifyReport {
+        let mut report = self.inner.verify_async().await;
+        report.factory = Some(self.verify_factory());
+        report
+    }
+
+    /// Check that the pool's accounting adds up. See
+    /// [`ObjectPool::check_consistency`].
+    #[must_use = "the report says whether the accounting is consistent"]
+    pub fn check_consistency(&self) -> ConsistencyReport {
+        self.inner.check_consistency()
+    }
+
+    fn verify_factory(&self) -> FactoryCheck {
+        if !self.inner.reserve_population() {
+            return FactoryCheck::SkippedAtCapacity;
+        }
+        let obj = match self.factory().0() {
+            Ok(obj) => obj,
+            Err(reason) => {
+                self.inner.release_population();
+                return FactoryCheck::CreationFailed(reason);
+            }
+        };
+        let valid = self.inner.hooks.is_valid(&obj);
+        self.inner.destroy(obj);
+        if valid {
+            FactoryCheck::Passed
+        } else {
+            FactoryCheck::FailedValidation
+        }
+    }
+
+    /// Warm up asynchronously
+    pub async fn warmup_async(&self, count: usize) -> PoolResult<()> {
+        self.inner.check_open()?;
+        let (factory, epoch) = self.factory();
+        let available = Arc::clone(&self.inner.available);
+        let next_id = Arc::clone(&self.inner.next_id);
+        let eviction = Arc::clone(&self.inner.eviction);
+        let hooks = Arc::clone(&self.inner.hooks);
+        let metrics = Arc::clone(&self.inner.metrics);
+        let return_errors = Arc::clone(&self.inner.return_errors);
+        let population = Arc::clone(&self.inner.population);
+        let throttle = Arc::clone(&self.inner.creation_throttle);
+        let capacity = self.inner.capacity;
+        
+        run_blocking(move || {
+            for _ in 0..count {
+                let reserved = population
+                    .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| {
+                        (n < capacity).then_some(n + 1)
+                    })
+                    .is_ok();
+                if !reserved {
+                    break;
+                }
+                let obj = match hooks.create(|| factory()) {
+                    Ok(obj) => obj,
+                    Err(reason) => {
+                        population.fetch_sub(1, Ordering::AcqRel);
+                        throttle.record(false);
+                        metrics.creation_failures.fetch_add(1, Ordering::Relaxed);
+                        return Err(PoolError::CreationFailed(reason));
+                    }
+                };
+                let id = next_id.fetch_add(1, Ordering::Relaxed);
+                eviction.track_object_from(id, epoch);
+                if let Some(memory) = eviction.memory() {
+                    memory.idle(id, &obj);
+                }
+                
+                if let Err((obj, id)) = available.push((obj, id)) {
+                    ObjectPool::discard_overflow_with(
+                        &hooks,
+                        &metrics,
+                        &eviction,
+                        &return_errors,
+                        &population,
+                        obj,
+                        id,
+                    );
+                    break;
+                }
+            }
+            Ok(())
+        })
+        .await?
+    }
+    
+    // Delegate methods
+    #[must_use]
+    pub fn get_health_status(&self) -> HealthStatus {
+        let mut health = self.inner.get_health_status();
+        if let Some(ref tier) = self.degraded {
+            health.warn_on_degraded(tier.in_use());
+        }
+        health
+    }
+
+    #[must_use]
+    pub fn available_count(&self) -> usize {
+        self.inner.available_count()
+    }
+
+    #[must_use]
+    pub fn active_count(&self) -> usize {
+        self.inner.active_count()
+    }
+
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    /// The eviction policy. See [`ObjectPool::eviction_policy`].
+    #[must_use]
+    pub fn eviction_policy(&self) -> &EvictionPolicy {
+        self.inner.eviction_policy()
+    }
+
+    /// Proactively remove expired objects. See [`ObjectPool::evict_expired`].
+    #[must_use = "returns the count of evicted objects"]
+    pub fn evict_expired(&self) -> usize {
+        self.inner.evict_expired()
+    }
+
+    /// Drain all available objects. See [`ObjectPool::drain`].
+    #[must_use = "returns the drained objects"]
+    pub fn drain(&self) -> Vec<T> {
+        self.inner.drain()
+    }
+
+    /// Close the pool for good. See [`ObjectPool::shutdown`].
+    pub fn shutdown(&self) -> usize {
+        self.inner.shutdown()
+    }
+
+    /// Whether the pool has been shut down
+    #[must_use]
+    pub fn is_closed(&self) -> bool {
+        self.inner.is_closed()
+    }
+
+    /// Stop handing out and creating objects. See [`ObjectPool::pause`].
+    pub fn pause(&self) {
+        self.inner.pause()
+    }
+
+    /// Hand out objects again. See [`ObjectPool::resume`].
+    pub fn resume(&self) {
+        self.inner.resume()
+    }
+
+    /// Whether the pool is paused
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.inner.is_paused()
+    }
+
+    /// Take `n` objects out of circulation as they become idle. See
+    /// [`ObjectPool::retire`].
+    pub fn retire(&self, n: usize) -> usize {
+        self.inner.retire(n)
+    }
+
+    /// Objects still to be retired as they are returned
+    #[must_use]
+    pub fn pending_retirements(&self) -> usize {
+        self.inner.pending_retirements()
+    }
+
+    /// Retire every object the pool currently owns. See
+    /// [`ObjectPool::invalidate_all`].
+    pub fn invalidate_all(&self) -> u64 {
+        self.inner.invalidate_all()
+    }
+
+    /// How many times [`invalidate_all`](Self::invalidate_all) has been called
+    #[must_use]
+    pub fn epoch(&self) -> u64 {
+        self.inner.epoch()
+    }
+
+    #[must_use]
+    pub fn get_metrics(&self) -> PoolMetrics {
+        self.inner.get_metrics()
+    }
+
+    #[must_use]
+    pub fn export_metrics(&self) -> HashMap<String, String> {
+        self.inner.export_metrics()
+    }
+
+    #[must_use]
+    pub fn export_metrics_prometheus(
+        &self,
+        pool_name: &str,
+        tags: Option<&HashMap<String, String>>,
+    ) -> String {
+        self.inner.export_metrics_prometheus(pool_name, tags)
+    }
+}
+
+/// Queryable pool that creates matching objects on demand
+///
+/// Objects are looked up by key: `matches(obj, key)` decides whether an idle
+/// object serves a request for `key`. If none does, `factory(key)` creates
+/// one — the usual shape of a "connection to shard X" pool. When the pool
+/// is at capacity but holds idle objects for other keys, one of them is
+/// destroyed to make room for the new object.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{DynamicQueryablePool, PoolConfiguration};
+///
+/// struct Connection { shard: u32 }
+///
+/// let pool = DynamicQueryablePool::new(
+///     |shard: &u32| Connection { shard: *shard },
+///     |conn: &Connection, shard: &u32| conn.shard == *shard,
+///     PoolConfiguration::new().with_max_pool_size(4),
+/// );
+///
+/// let conn = pool.get_object(&7).unwrap();
+/// assert_eq!(conn.shard, 7);
+/// drop(conn);
+///
+/// // The idle connection is reused rather than created again.
+/// let _conn = pool.get_object(&7).unwrap();
+/// assert_eq!(pool.get_metrics().total_retrieved, 2);
+/// assert_eq!(pool.available_count(), 0);
+/// ```
+pub struct DynamicQueryablePool<K, T: Send> {
+    inner: QueryableObjectPool<T>,
+    factory: RwLock<KeyedFactory<K, T>>,
+    matches: KeyMatcher<K, T>,
+}
+
+impl<K, T: Send + Sync + 'static> DynamicQueryablePool<K, T> {
+    /// Create a new keyed pool from a factory and a matching predicate
+    pub fn new<F, M>(factory: F, matches: M, config: PoolConfiguration<T>) -> Self
+    where
+        F: Fn(&K) -> T + Send + Sync + 'static,
+        M: Fn(&T, &K) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            inner: QueryableObjectPool {
+                inner: ObjectPool::new(Vec::new(), config).replacing_lost_objects(),
+            },
+            factory: RwLock::new(Arc::new(factory)),
+            matches: Arc::new(matches),
+        }
+    }
+
+    /// Point the pool at a new factory and retire every object made by
+    /// the old one. See [`DynamicObjectPool::update_factory`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{DynamicQueryablePool, PoolConfiguration};
+    ///
+    /// let pool = DynamicQueryablePool::new(
+    ///     |shard: &u32| format!("shard-{shard}.old"),
+    ///     |conn: &String, shard: &u32| conn.starts_with(&format!("shard-{shard}.")),
+    ///     PoolConfiguration::default(),
+    /// );
+    /// drop(pool.get_object(&1).unwrap());
+    ///
+    /// pool.update_factory(|shard: &u32| format!("shard-{shard}.new"));
+    /// assert_eq!(*pool.get_object(&1).unwrap(), "shard-1.new");
+    /// ```
+    pub fn update_factory<F>(&self, factory: F) -> u64
+    where
+        F: Fn(&K) -> T + Send + Sync + 'static,
+    {
+        let mut current = self.factory.write().unwrap_or_else(PoisonError::into_inner);
+        *current = Arc::new(factory);
+        self.inner.invalidate_all()
+    }
+
+    /// Get an object for `key`, creating one if no idle object matches
+    ///
+    /// At capacity with nothing to replace, the pool's
+    /// [`WaitPolicy`](crate::WaitPolicy) decides whether to fail with
+    /// `PoolError::PoolFull` or wait.
+    #[must_use = "the pool object must be used or explicitly dropped"]
+    #[track_caller]
+    pub fn get_object(&self, key: &K) -> PoolResult<PooledObject<T>> {
+        located(self.inner.inner.wait_for(is_creation_blocked, || self.acquire_now(key)))
+    }
+
+    /// Try to get an object for `key` without waiting
+    #[track_caller]
+    pub fn try_get_object(&self, key: &K) -> PoolResult<Option<PooledObject<T>>> {
+        match located(self.acquire_now(key)) {
+            Ok(obj) => Ok(Some(obj)),
+            Err(PoolError::PoolFull | PoolError::CreationPending) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Get an object for `key` asynchronously
+    #[track_caller]
+    pub fn get_object_async<'a>(&'a self, key: &'a K) -> Acquire<'a, T>
+    where
+        K: Sync,
+    {
+        Acquire::new(self.inner.inner.wait_for_async(is_creation_blocked, || self.acquire_now(key)))
+    }
+
+    /// Single, non-waiting acquisition attempt.
+    fn acquire_now(&self, key: &K) -> PoolResult<PooledObject<T>> {
+        match self.inner.find_now(|obj| (self.matches)(obj, key), None) {
+            Ok(obj) => Ok(obj),
+            Err(PoolError::NoMatchFound) => self.create_for(key),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn create_for(&self, key: &K) -> PoolResult<PooledObject<T>> {
+        let pool = &self.inner.inner;
+
+        while !pool.reserve_population() {
+            // Make room by retiring an idle object held for another key.
+            match pool.available.pop() {
+                Some((obj, id)) => {
+                    pool.eviction.remove_object(id);
+                    pool.destroy(obj);
+                }
+                None => return Err(PoolError::PoolFull),
+            }
+        }
+
+        if let Err(err) = pool.try_acquire_active_slot() {
+            pool.release_population();
+            return Err(err);
+        }
+        let (factory, epoch) = {
+            let factory = self.factory.read().unwrap_or_else(PoisonError::into_inner);
+            (Arc::clone(&factory), pool.epoch())
+        };
+        let obj = pool.create_with(|| Ok(factory(key)))?;
+
+        // The failed search recorded a CB failure; offset it as the dynamic
+        // pool does so routine creation doesn't trip the breaker.
+        if pool.feeds_acquisition() {
+            pool.record_circuit_breaker_success();
+        }
+
+        pool.adopt_created(obj, epoch, None)
+    }
+
+    #[must_use]
+    pub fn get_health_status(&self) -> HealthStatus {
+        self.inner.get_health_status()
+    }
+
+    /// Checked-out objects and their holders. See
+    /// [`ObjectPool::current_holders`].
+    #[must_use]
+    pub fn current_holders(&self) -> Vec<Holder> {
+        self.inner.current_holders()
+    }
+
+    /// Recent acquisitions, returns and evictions. See
+    /// [`ObjectPool::recent_activity`].
+    #[must_use]
+    pub fn recent_activity(&self) -> Vec<ActivityRecord> {
+        self.inner.recent_activity()
+    }
+
+    #[must_use]
+    pub fn available_count(&self) -> usize {
+        self.inner.available_count()
+    }
+
+    #[must_use]
+    pub fn active_count(&self) -> usize {
+        self.inner.active_count()
+    }
+
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        self.inner.capacity()
+    }
+
+    /// The eviction policy. See [`ObjectPool::eviction_policy`].
+    #[must_use]
+    pub fn eviction_policy(&self) -> &EvictionPolicy {
+        self.inner.eviction_policy()
+    }
+
+    /// Proactively remove expired objects. See [`ObjectPool::evict_expired`].
+    #[must_use = "returns the count of evicted objects"]
+    pub fn evict_expired(&self) -> usize {
+        self.inner.evict_expired()
+    }
+
+    /// Self-test of the idle objects. No factory check is made, as creating
+    /// an object needs a key. See [`ObjectPool::verify`].
+    #[must_use = "the report says whether the pool is healthy"]
+    pub fn verify(&self) -> VerifyReport {
+        self.inner.verify()
+    }
+
+    /// Check that the pool's accounting adds up. See
+    /// [`ObjectPool::check_consistency`].
+    #[must_use = "the report says whether the accounting is consistent"]
+    pub fn check_consistency(&self) -> ConsistencyReport {
+        self.inner.check_consistency()
+    }
+
+    /// Drain all available objects. See [`ObjectPool::drain`].
+    #[must_use = "returns the drained objects"]
+    pub fn drain(&self) -> Vec<T> {
+        self.inner.drain()
+    }
+
+    /// Close the pool for good. See [`ObjectPool::shutdown`].
+    pub fn shutdown(&self) -> usize {
+        self.inner.shutdown()
+    }
+
+    /// Whether the pool has been shut down
+    #[must_use]
+    pub fn is_closed(&self) -> bool {
+        self.inner.is_closed()
+    }
+
+    /// Stop handing out and creating objects. See [`ObjectPool::pause`].
+    pub fn pause(&self) {
+        self.inner.pause()
+    }
+
+    /// Hand out objects again. See [`ObjectPool::resume`].
+    pub fn resume(&self) {
+        self.inner.resume()
+    }
+
+    /// Whether the pool is paused
+    #[must_use]
+    pub fn is_paused(&self) -> bool {
+        self.inner.is_paused()
+    }
+
+    /// Take `n` objects out of circulation as they become idle. See
+    /// [`ObjectPool::retire`].
+    pub fn retire(&self, n: usize) -> usize {
+        self.inner.retire(n)
+    }
+
+    /// Objects still to be retired as they are returned
+    #[must_use]
+    pub fn pending_retirements(&self) -> usize {
+        self.inner.pending_retirements()
+    }
+
+    /// Retire every object the pool currently owns. See
+    /// [`ObjectPool::invalidate_all`].
+    pub fn invalidate_all(&self) -> u64 {
+        self.inner.invalidate_all()
+    }
+
+    /// How many times [`invalidate_all`](Self::invalidate_all) has been called
+    #[must_use]
+    pub fn epoch(&self) -> u64 {
+        self.inner.epoch()
+    }
+
+    /// Subscribe to return-path error reports. See [`ObjectPool::return_errors`].
+    #[must_use]
+    pub fn return_errors(&self) -> tokio::sync::broadcast::Receiver<ReturnError> {
+        self.inner.return_errors()
+    }
+
+    /// Subscribe to warnings about the pool. See [`ObjectPool::warnings`].
+    #[must_use]
+    pub fn warnings(&self) -> tokio::sync::broadcast::Receiver<PoolWarning> {
+        self.inner.warnings()
+    }
+
+    /// Subscribe to pool events. See [`ObjectPool::events`].
+    #[must_use]
+    pub fn events(&self) -> tokio::sync::broadcast::Receiver<PoolEvent> {
+        self.inner.events()
+    }
+
+    /// Have `observer` receive the pool's events, metrics and health
+    /// changes. See [`ObjectPool::observe`].
+    pub fn observe(self: &Arc<Self>, observer: impl PoolObserver, every: Duration) -> ObserverHandle
+    where
+        K: Send + Sync + 'static,
+    {
+        ObserverHandle::spawn(Arc::downgrade(self) as Weak<dyn Watched>, observer, every)
+    }
+
+    /// Log pool events through `tracing`. See [`ObjectPool::log_events`].
+    #[cfg(feature = "tracing")]
+    pub fn log_events(&self, logger: EventLogger) {
+        self.inner.log_events(logger);
+    }
+
+    #[must_use]
+    pub fn get_metrics(&self) -> PoolMetrics {
+        self.inner.get_metrics()
+    }
+
+    /// How the pool's searches for idle objects went. See
+    /// [`QueryableObjectPool::query_metrics`].
+    #[must_use]
+    pub fn query_metrics(&self) -> QueryMetrics {
+        self.inner.query_metrics()
+    }
+
+    #[must_use]
+    pub fn export_metrics(&self) -> HashMap<String, String> {
+        self.inner.export_metrics()
+    }
+
+    #[must_use]
+    pub fn export_metrics_prometheus(
+        &self,
+        pool_name: &str,
+        tags: Option<&HashMap<String, String>>,
+    ) -> String {
+        self.inner.export_metrics_prometheus(pool_name, tags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eviction::{ExpiredReturn, TtlRefresh};
+    use crate::hooks::HookTiming;
+    use crate::slo::{SloObjective, SloTargets};
+    
+    #[test]
+    fn test_object_pool_basic() {
+        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+        
+        {
+            let obj = pool.get_object().unwrap();
+            assert!([1, 2, 3].contains(&*obj));
+        }
+        
+        assert_eq!(pool.available_count(), 3);
+    }
+    
+    #[test]
+    fn test_queryable_pool() {
+        let pool = QueryableObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+        
+        {
+            let obj = pool.get_object(|x| *x == 2).unwrap();
+            assert_eq!(*obj, 2);
+        }
+    }
+    
+    #[test]
+    fn test_queryable_pool_with_non_clone_type() {
+        struct Handle {
+            id: u32,
+            _file: Box<dyn Send + Sync>,
+        }
+
+        let pool = QueryableObjectPool::new(
+            vec![
+                Handle { id: 1, _file: Box::new(()) },
+                Handle { id: 2, _file: Box::new(()) },
+            ],
+            PoolConfiguration::default(),
+        );
+
+        let handle = pool.get_object(|h| h.id == 2).unwrap();
+        assert_eq!(handle.id, 2);
+        assert_eq!(pool.active_count(), 1);
+        assert_eq!(pool.available_count(), 1);
+        drop(handle);
+        assert_eq!(pool.available_count(), 2);
+    }
+
+    #[test]
+    fn test_queryable_pool_derefs_to_object_pool() {
+        let pool = QueryableObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+
+        // Unqueried acquisition through the underlying pool.
+        let batch = pool.get_batch(2).unwrap();
+        assert_eq!(batch.len(), 2);
+        assert_eq!(pool.active_count(), 2);
+        drop(batch);
+
+        let inner: &ObjectPool<i32> = &pool;
+        assert_eq!(inner.available_count(), 3);
+    }
+
+    #[test]
+    fn test_dynamic_pool() {
+        let pool = DynamicObjectPool::new(|| 42, PoolConfiguration::default());
+        
+        {
+            let obj = pool.get_object().unwrap();
+            assert_eq!(*obj, 42);
+        }
+    }
+    
+    #[tokio::test]
+    async fn test_async_get() {
+        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+        
+        {
+            let obj = pool.get_object_async().await.unwrap();
+            assert!([1, 2, 3].contains(&*obj));
+        }
+    }
+    
+    #[test]
+    fn test_pool_empty_error() {
+        let pool = ObjectPool::new(vec![1], PoolConfiguration::default());
+        
+        let _obj1 = pool.get_object().unwrap();
+        let result = pool.get_object();
+        
+        assert!(result.is_err());
+        if let Err(e) = result {
+            assert!(matches!(e, PoolError::PoolEmpty));
+        }
+    }
+    
+    #[test]
+    fn test_try_methods() {
+        let pool = ObjectPool::new(vec![42], PoolConfiguration::default());
+        
+        let obj1 = pool.try_get_object().unwrap();
+        assert!(obj1.is_some());
+        if let Some(ref obj) = obj1 {
+            assert_eq!(**obj, 42);
+        }
+        
+        let obj2 = pool.try_get_object().unwrap();
+        assert!(obj2.is_none());
+        
+        drop(obj1);
+        
+        let obj3 = pool.try_get_object().unwrap();
+        assert!(obj3.is_some());
+    }
+    
+    #[test]
+    fn test_max_active_objects() {
+        let config = PoolConfiguration::new()
+            .with_max_active_objects(2);
+        
+        let pool = ObjectPool::new(vec![1, 2, 3, 4, 5], config);
+        
+        let _obj1 = pool.get_object().unwrap();
+        let _obj2 = pool.get_object().unwrap();
+        
+        let result = pool.get_object();
+        assert!(result.is_err());
+        if let Err(e) = result {
+            assert!(matches!(e, PoolError::MaxActiveObjectsReached));
+        }
+    }
+    
+    #[test]
+    fn test_validation_on_return() {
+        let config = PoolConfiguration::new()
+            .with_validation(|x| *x > 0);
+        
+        let pool = ObjectPool::new(vec![1, 2, 3], config);
+        
+        {
+            let obj = pool.get_object().unwrap();
+            assert_eq!(*obj, 1);
+        }
+        
+        assert_eq!(pool.available_count(), 3);
+    }
+    
+    #[test]
+    fn test_metrics_tracking() {
+        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+        
+        {
+            let _obj1 = pool.get_object().unwrap();
+            let _obj2 = pool.get_object().unwrap();
+            
+            let metrics = pool.get_metrics();
+            assert_eq!(metrics.total_retrieved, 2);
+            assert_eq!(metrics.active_objects, 2);
+            assert_eq!(metrics.available_objects, 1);
+        }
+        
+        let metrics = pool.get_metrics();
+        assert_eq!(metrics.total_returned, 2);
+        assert_eq!(metrics.active_objects, 0);
+        assert_eq!(metrics.available_objects, 3);
+    }
+    
+    #[test]
+    fn test_health_status() {
+        let config = PoolConfiguration::new()
+            .with_max_pool_size(5);
+        
+        let pool = ObjectPool::new(vec![1, 2, 3, 4, 5], config);
+        
+        {
+            let _obj1 = pool.get_object().unwrap();
+            let _obj2 = pool.get_object().unwrap();
+            
+            let health = pool.get_health_status();
+            assert!(health.is_healthy);
+            assert_eq!(health.active_objects, 2);
+            assert_eq!(health.available_objects, 3);
+            assert_eq!(health.total_capacity, 5);
+        }
+    }
+    
+    #[test]
+    fn test_prometheus_export() {
+        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+        
+        {
+            let _obj = pool.get_object().unwrap();
+            
+            let prometheus = pool.export_metrics_prometheus("test_pool", None);
+            
+            assert!(prometheus.contains("objectpool_objects_active"));
+            assert!(prometheus.contains("objectpool_objects_available"));
+            assert!(prometheus.contains("objectpool_utilization"));
+            assert!(prometheus.contains("test_pool"));
+        }
+    }
+    
+    #[test]
+    fn test_prometheus_with_tags() {
+        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+        
+        let mut tags = HashMap::new();
+        tags.insert("env".to_string(), "test".to_string());
+        tags.insert("service".to_string(), "api".to_string());
+        
+        let prometheus = pool.export_metrics_prometheus("test_pool", Some(&tags));
+        
+        assert!(prometheus.contains("env=\"test\""));
+        assert!(prometheus.contains("service=\"api\""));
+    }
+    
+    #[test]
+    fn test_prometheus_labels_are_sorted() {
+        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+        let keys = ["zone", "env", "service", "region", "app", "tier", "team", "host"];
+        let forward: HashMap<String, String> =
+            keys.iter().map(|k| (k.to_string(), format!("{k}-v"))).collect();
+        let mut backward = HashMap::new();
+        for k in keys.iter().rev() {
+            backward.insert(k.to_string(), format!("{k}-v"));
+        }
+
+        let first = pool.export_metrics_prometheus("p", Some(&forward));
+        assert_eq!(first, pool.export_metrics_prometheus("p", Some(&forward)));
+        assert_eq!(first, pool.export_metrics_prometheus("p", Some(&backward)));
+        assert!(first.contains(
+            "objectpool_objects_active{pool=\"p\",app=\"app-v\",env=\"env-v\",host=\"host-v\",\
+             region=\"region-v\",service=\"service-v\",team=\"team-v\",tier=\"tier-v\",zone=\"zone-v\"} 0"
+        ));
+    }
+
+    #[test]
+    fn test_prometheus_series_order_is_stable() {
+        let pool = ObjectPool::new(vec![1, 2], PoolConfiguration::new().with_caller_metrics());
+        for label in ["web", "batch", "api"] {
+            drop(pool.get_object_with(AcquireContext::new().with_label(label)).unwrap());
+        }
+        let tags = HashMap::from([("env".to_string(), "prod".to_string())]);
+        let output = pool.export_metrics_prometheus("p", Some(&tags));
+
+        let families: Vec<&str> = output
+            .lines()
+            .filter_map(|line| line.strip_prefix("# TYPE "))
+            .map(|line| line.split(' ').next().unwrap())
+            .collect();
+        assert_eq!(
+            families,
+            [
+                "objectpool_objects_active",
+                "objectpool_objects_available",
+                "objectpool_utilization",
+                "objectpool_objects_total",
+                "objectpool_capacity_lost",
+                "objectpool_memory_idle_bytes",
+                "objectpool_memory_active_bytes",
+                "objectpool_paused",
+                "objectpool_objects_retrieved_total",
+                "objectpool_objects_returned_total",
+                "objectpool_events_empty_total",
+                "objectpool_validation_failures_total",
+                "objectpool_queue_push_failures_total",
+                "objectpool_objects_detached_total",
+                "objectpool_leases_expired_total",
+                "objectpool_group_limit_rejections_total",
+                "objectpool_creation_failures_total",
+                "objectpool_creations_throttled_total",
+                "objectpool_hook_panics_total",
+                "objectpool_objects_discarded_total",
+                "objectpool_objects_retired_total",
+                "objectpool_objects_degraded_total",
+                "objectpool_waiter_rejections_total",
+                "objectpool_caller_acquisitions_total",
+                "objectpool_caller_timeouts_total",
+                "objectpool_caller_releases_total",
+                "objectpool_caller_hold_seconds_total",
+            ]
+        );
+        let acquisitions: Vec<&str> = output
+            .lines()
+            .filter(|line| line.starts_with("objectpool_caller_acquisitions_total{"))
+            .collect();
+        assert_eq!(
+            acquisitions,
+            [
+                "objectpool_caller_acquisitions_total{pool=\"p\",env=\"prod\",caller=\"api\"} 1",
+                "objectpool_caller_acquisitions_total{pool=\"p\",env=\"prod\",caller=\"batch\"} 1",
+                "objectpool_caller_acquisitions_total{pool=\"p\",env=\"prod\",caller=\"web\"} 1",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_queryable_no_match() {
+        let pool = QueryableObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+        
+        let result = pool.get_object(|x| *x == 99);
+        
+        assert!(result.is_err());
+        if let Err(e) = result {
+            assert!(matches!(e, PoolError::NoMatchFound));
+        }
+    }
+    
+    #[test]
+    fn test_queryable_multiple_matches() {
+        let pool = QueryableObjectPool::new(vec![1, 2, 3, 2, 4], PoolConfiguration::default());
+        
+        let obj = pool.get_object(|x| *x == 2).unwrap();
+        assert_eq!(*obj, 2);
+        
+        // Should still find the other 2
+        let obj2 = pool.get_object(|x| *x == 2).unwrap();
+        assert_eq!(*obj2, 2);
+    }
+    
+    #[test]
+    fn test_dynamic_pool_creation() {
+        let call_count = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let count_clone = call_count.clone();
+        
+        let pool = DynamicObjectPool::new(
+            move || {
+                count_clone.fetch_add(1, Ordering::Relaxed);
+                42
+            },
+            PoolConfiguration::new().with_max_pool_size(5),
+        );
+        
+        let _obj1 = pool.get_object().unwrap();
+        let _obj2 = pool.get_object().unwrap();
+        
+        assert_eq!(call_count.load(Ordering::Relaxed), 2);
+    }
+    
+    #[test]
+    fn test_dynamic_pool_warmup() {
+        let pool = DynamicObjectPool::new(
+            || 42,
+            PoolConfiguration::new().with_max_pool_size(10),
+        );
+        
+        pool.warmup(5).unwrap();
+        
+        let health = pool.get_health_status();
+        assert_eq!(health.available_objects, 5);
+    }
+    
+    #[test]
+    fn test_dynamic_pool_max_capacity() {
+        let pool = DynamicObjectPool::new(
+            || 42,
+            PoolConfiguration::new().with_max_pool_size(2),
+        );
+        
+        let _obj1 = pool.get_object().unwrap();
+        let _obj2 = pool.get_object().unwrap();
+        let result = pool.get_object();
+        
+        assert!(result.is_err());
+        if let Err(e) = result {
+            assert!(matches!(e, PoolError::PoolFull));
+        }
+    }
+
+    #[test]
+    fn test_dynamic_pool_propagates_max_active_without_creating() {
+        let created = Arc::new(AtomicUsize::new(0));
+        let created_clone = Arc::clone(&created);
+
+        let pool = DynamicObjectPool::with_initial(
+            move || {
+                created_clone.fetch_add(1, Ordering::Relaxed);
+                42
+            },
+            vec![1, 2],
+            PoolConfiguration::new().with_max_active_objects(1),
+        );
+
+        let _obj = pool.get_object().unwrap();
+        let result = pool.get_object();
+
+        assert!(matches!(result, Err(PoolError::MaxActiveObjectsReached)));
+        assert_eq!(created.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_dynamic_pool_propagates_circuit_breaker_without_extra_creation() {
+        let created = Arc::new(AtomicUsize::new(0));
+        let created_clone = Arc::clone(&created);
+
+        let pool = DynamicObjectPool::new(
+            move || {
+                created_clone.fetch_add(1, Ordering::Relaxed);
+                42
+            },
+            PoolConfiguration::new()
+                .with_max_pool_size(2)
+                .with_circuit_breaker(1, Duration::from_secs(60)),
+        );
+
+        // First call creates because the inner pool is empty.
+        let _obj = pool.get_object().unwrap();
+        assert_eq!(created.load(Ordering::Relaxed), 1);
+
+        // The first empty inner read opened the breaker, so this must fail fast.
+        let result = pool.get_object();
+        assert!(matches!(result, Err(PoolError::CircuitBreakerOpen)));
+        assert_eq!(created.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_eviction_ttl() {
+        use std::thread;
+        
+        let config = PoolConfiguration::new()
+            .with_ttl(Duration::from_millis(100));
+        
+        let pool = ObjectPool::new(vec![1, 2, 3], config);
+        
+        thread::sleep(Duration::from_millis(150));
+        
+        // Objects should be expired and filtered out
+        let obj = pool.try_get_object().unwrap();
+        assert!(obj.is_none() || pool.available_count() < 3);
+    }
+    
+    #[test]
+    fn test_circuit_breaker_opens() {
+        let config = PoolConfiguration::new()
+            .with_circuit_breaker(3, Duration::from_secs(60));
+        
+        let pool = ObjectPool::new(vec![1], config);
+        
+        let _obj = pool.get_object().unwrap();
+        
+        // Cause failures
+        for _ in 0..3 {
+            let _ = pool.try_get_object();
+        }
+        
+        // Circuit breaker should be open now
+        let result = pool.get_object();
+        assert!(result.is_err());
+    }
+    
+    #[tokio::test]
+    async fn test_async_timeout() {
+        let config = PoolConfiguration::new()
+            .with_timeout(Duration::from_millis(50));
+        
+        let pool = ObjectPool::new(vec![1], config);
+        
+        let _obj = pool.get_object().unwrap();
+        
+        let result = pool.get_object_async().await;
+        
+        assert!(result.is_err());
+        if let Err(e) = result {
+            assert!(matches!(e, PoolError::Timeout(..)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_async_get_fails_fast_on_max_active() {
+        use std::time::Instant;
+
+        let config = PoolConfiguration::new()
+            .with_timeout(Duration::from_secs(2))
+            .with_max_active_objects(1);
+
+        let pool = ObjectPool::new(vec![1, 2], config);
+        let _obj = pool.get_object().unwrap();
+
+        let start = Instant::now();
+        let result = pool.get_object_async().await;
+        let elapsed = start.elapsed();
+
+        assert!(matches!(result, Err(PoolError::MaxActiveObjectsReached)));
+        assert!(elapsed < Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn test_async_get_fails_fast_on_circuit_breaker_open() {
+        use std::time::Instant;
+
+        let config = PoolConfiguration::new()
+            .with_timeout(Duration::from_secs(2))
+            .with_circuit_breaker(1, Duration::from_secs(60));
+
+        let pool = ObjectPool::new(vec![1], config);
+        let _obj = pool.get_object().unwrap();
+
+        // First empty attempt records failure and opens the breaker.
+        assert!(matches!(pool.try_get_object(), Ok(None)));
+
+        let start = Instant::now();
+        let result = pool.get_object_async().await;
+        let elapsed = start.elapsed();
+
+        assert!(matches!(result, Err(PoolError::CircuitBreakerOpen)));
+        assert!(elapsed < Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_access() {
+        use std::sync::Arc;
+        
+        let pool = Arc::new(ObjectPool::new(
+            vec![1, 2, 3, 4, 5],
+            PoolConfiguration::default(),
+        ));
+        
+        let mut handles = vec![];
+        
+        for _ in 0..10 {
+            let pool_clone = Arc::clone(&pool);
+            let handle = tokio::spawn(async move {
+                if let Ok(Some(obj)) = pool_clone.try_get_object() {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                    drop(obj);
+                    true
+                } else {
+                    false
+                }
+            });
+            handles.push(handle);
+        }
+        
+        let mut success_count = 0;
+        for handle in handles {
+            if handle.await.unwrap() {
+                success_count += 1;
+            }
+        }
+        
+        // At least 5 should succeed (pool size)
+        assert!(success_count >= 5);
+        assert_eq!(pool.available_count(), 5);
+    }
+    
+    #[tokio::test]
+    async fn test_queryable_async() {
+        let pool = QueryableObjectPool::new(vec![1, 2, 3, 4, 5], PoolConfiguration::default());
+        
+        let obj = pool.get_object_async(|x| *x > 3).await.unwrap();
+        assert!(*obj > 3);
+    }
+    
+    #[tokio::test]
+    async fn test_dynamic_warmup_async() {
+        let pool = DynamicObjectPool::new(
+            || 42,
+            PoolConfiguration::new().with_max_pool_size(10),
+        );
+        
+        pool.warmup_async(7).await.unwrap();
+        
+        assert_eq!(pool.get_health_status().available_objects, 7);
+    }
+    
+    #[test]
+    fn test_pool_reuse_after_drop() {
+        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+        
+        for _ in 0..100 {
+            {
+                let obj = pool.get_object().unwrap();
+                assert!([1, 2, 3].contains(&*obj));
+            }
+            assert_eq!(pool.available_count(), 3);
+        }
+    }
+    
+    #[test]
+    fn test_multiple_pools() {
+        let pool1 = ObjectPool::new(vec![1, 2], PoolConfiguration::default());
+        let pool2 = ObjectPool::new(vec![3, 4], PoolConfiguration::default());
+        
+        let obj1 = pool1.get_object().unwrap();
+        let obj2 = pool2.get_object().unwrap();
+        
+        assert!([1, 2].contains(&*obj1));
+        assert!([3, 4].contains(&*obj2));
+    }
+    
+    #[test]
+    fn test_export_metrics_map() {
+        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+        
+        {
+            let _obj = pool.get_object().unwrap();
+        }
+        
+        let metrics_map = pool.export_metrics();
+        
+        assert!(metrics_map.contains_key("total_retrieved"));
+        assert!(metrics_map.contains_key("total_returned"));
+        assert!(metrics_map.contains_key("active_objects"));
+        assert!(metrics_map.contains_key("queue_push_failures"));
+        assert_eq!(metrics_map.get("total_retrieved").unwrap(), "1");
+        assert_eq!(metrics_map.get("total_returned").unwrap(), "1");
+    }
+
+    #[test]
+    fn test_return_push_failure_is_tracked() {
+        let config = PoolConfiguration::new().with_max_pool_size(1);
+        let pool = ObjectPool::new(vec![1], config);
+
+        let obj = pool.get_object().unwrap();
+
+        // Fill the queue while the checked-out object is still active.
+        pool.available.push((2, 999)).unwrap();
+
+        drop(obj);
+
+        let metrics = pool.get_metrics();
+        assert_eq!(metrics.queue_push_failures, 1);
+        assert_eq!(metrics.total_returned, 0);
+        assert_eq!(pool.active_count(), 0);
+    }
+
+    #[test]
+    fn test_health_warnings() {
+        let config = PoolConfiguration::new()
+            .with_max_pool_size(2);
+        
+        let pool = ObjectPool::new(vec![1, 2], config);
+        
+        // Use both objects to get high utilization
+        let _obj1 = pool.get_object().unwrap();
+        let _obj2 = pool.get_object().unwrap();
+        
+        let health = pool.get_health_status();
+        assert_eq!(health.utilization, 1.0); // 100% utilization
+    }
+    
+    #[test]
+    fn test_configuration_builder() {
+        let config = PoolConfiguration::<i32>::new()
+            .with_max_pool_size(50)
+            .with_max_active_objects(25)
+            .with_timeout(Duration::from_secs(10))
+            .with_ttl(Duration::from_secs(300))
+            .with_idle_timeout(Duration::from_secs(60))
+            .with_warmup(10)
+            .with_circuit_breaker(5, Duration::from_secs(30));
+        
+        assert_eq!(config.max_pool_size, 50);
+        assert_eq!(config.max_active_objects, Some(25));
+        assert_eq!(config.warmup_size, Some(10));
+        assert!(config.enable_circuit_breaker);
+    }
+
+    #[test]
+    fn test_into_detached_cleans_active_state() {
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new().with_max_active_objects(1),
+        );
+
+        let obj = pool.get_object().unwrap();
+        assert_eq!(pool.active_count(), 1);
+
+        let value = obj.into_detached();
+        assert_eq!(value, 1);
+
+        assert_eq!(pool.active_count(), 0);
+        assert_eq!(pool.available_count(), 0);
+
+        // Active slot is released, so next error should be pool-empty, not max-active.
+        let result = pool.get_object();
+        assert!(matches!(result, Err(PoolError::PoolEmpty)));
+    }
+
+    #[test]
+    fn test_into_detached_does_not_increment_returned_metrics() {
+        let pool = ObjectPool::new(vec![1], PoolConfiguration::default());
+
+        let obj = pool.get_object().unwrap();
+        let _value = obj.into_detached();
+
+        let metrics = pool.get_metrics();
+        assert_eq!(metrics.total_retrieved, 1);
+        assert_eq!(metrics.total_returned, 0);
+        assert_eq!(metrics.total_detached, 1);
+        assert_eq!(metrics.active_objects, 0);
+        assert_eq!(metrics.available_objects, 0);
+    }
+
+    #[test]
+    fn test_get_borrows_without_removing_from_pool() {
+        let pool = ObjectPool::new(vec![42], PoolConfiguration::default());
+        let obj = pool.get_object().unwrap();
+
+        assert_eq!(*obj.get(), 42);
+        assert_eq!(pool.active_count(), 1);
+
+        drop(obj);
+
+        // Object must be returned normally.
+        assert_eq!(pool.available_count(), 1);
+        let metrics = pool.get_metrics();
+        assert_eq!(metrics.total_returned, 1);
+        assert_eq!(metrics.total_detached, 0);
+    }
+
+    #[test]
+    fn test_get_mut_mutates_without_removing_from_pool() {
+        let pool = ObjectPool::new(vec![0], PoolConfiguration::default());
+        let mut obj = pool.get_object().unwrap();
+
+        *obj.get_mut() = 99;
+        assert_eq!(*obj.get(), 99);
+
+        drop(obj);
+
+        // Object must be returned normally.
+        assert_eq!(pool.available_count(), 1);
+        let metrics = pool.get_metrics();
+        assert_eq!(metrics.total_returned, 1);
+        assert_eq!(metrics.total_detached, 0);
+    }
+
+    #[test]
+    fn test_try_get_object_propagates_max_active_error() {
+        let pool = ObjectPool::new(
+            vec![1, 2],
+            PoolConfiguration::new().with_max_active_objects(1),
+        );
+
+        let _obj = pool.get_object().unwrap();
+
+        let result = pool.try_get_object();
+        assert!(matches!(result, Err(PoolError::MaxActiveObjectsReached)));
+    }
+
+    #[test]
+    fn test_try_get_object_propagates_circuit_breaker_error() {
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new().with_circuit_breaker(1, Duration::from_secs(60)),
+        );
+
+        let _obj = pool.get_object().unwrap();
+
+        // First empty attempt records a failure and opens the breaker.
+        let first = pool.try_get_object();
+        assert!(matches!(first, Ok(None)));
+
+        let second = pool.try_get_object();
+        assert!(matches!(second, Err(PoolError::CircuitBreakerOpen)));
+    }
+
+    // ── DynamicObjectPool: circuit breaker does not trip on successful dynamic creation ──
+
+    #[test]
+    fn test_dynamic_pool_cb_does_not_trip_on_routine_creation() {
+        // Threshold = 3: the CB must NOT open when every empty-pool event is
+        // immediately followed by a successful dynamic creation.
+        let pool = DynamicObjectPool::new(
+            || 42,
+            PoolConfiguration::new()
+                .with_max_pool_size(10)
+                .with_circuit_breaker(3, Duration::from_secs(60)),
+        );
+
+        // Create 4 objects (all via dynamic creation); the CB should remain closed.
+        let _a = pool.get_object().unwrap();
+        let _b = pool.get_object().unwrap();
+        let _c = pool.get_object().unwrap();
+        let _d = pool.get_object().unwrap();
+
+        let health = pool.get_health_status();
+        assert!(!health.circuit_breaker_open, "CB must stay closed during normal dynamic creation");
+    }
+
+    // ── evict_expired ──────────────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_evict_expired_removes_stale_objects() {
+        use std::thread;
+
+        let config = PoolConfiguration::new()
+            .with_ttl(Duration::from_millis(50));
+
+        let pool = ObjectPool::new(vec![1, 2, 3], config);
+
+        thread::sleep(Duration::from_millis(80));
+
+        let evicted = pool.evict_expired();
+        assert_eq!(evicted, 3, "all three objects should have been evicted");
+        assert_eq!(pool.available_count(), 0);
+    }
+
+    #[test]
+    fn test_evict_expired_keeps_fresh_objects() {
+        let config = PoolConfiguration::new()
+            .with_ttl(Duration::from_secs(300));
+
+        let pool = ObjectPool::new(vec![1, 2, 3], config);
+
+        let evicted = pool.evict_expired();
+        assert_eq!(evicted, 0);
+        assert_eq!(pool.available_count(), 3);
+    }
+
+    // ── drain ─────────────────────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_drain_returns_available_objects() {
+        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+
+        let drained = pool.drain();
+        assert_eq!(drained.len(), 3);
+        assert_eq!(pool.available_count(), 0);
+    }
+
+    #[test]
+    fn test_drain_does_not_affect_active_objects() {
+        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+
+        let _obj = pool.get_object().unwrap(); // 1 active
+        let drained = pool.drain();
+        assert_eq!(drained.len(), 2); // only the 2 available ones
+        assert_eq!(pool.active_count(), 1);
+    }
+
+    #[test]
+    fn test_into_inner_hands_back_objects_without_destroying_them() {
+        let destroyed = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&destroyed);
+        let pool = ObjectPool::new(
+            vec![1, 2, 3],
+            PoolConfiguration::new().with_on_destroy(move |_: i32| {
+                counter.fetch_add(1, Ordering::Relaxed);
+            }),
+        );
+        let held = pool.get_object().unwrap();
+
+        let err = pool.into_inner().unwrap_err();
+        assert_eq!((err.active(), err.available()), (1, 2));
+        assert_eq!(err.to_string(), "Pool still has 1 checked-out objects (2 idle)");
+        let pool = err.into_pool();
+        assert_eq!(pool.available_count(), 2, "a refused into_inner leaves the pool intact");
+
+        drop(held);
+        let mut objects = pool.into_inner().unwrap();
+        objects.sort_unstable();
+        assert_eq!(objects, [1, 2, 3]);
+        assert_eq!(destroyed.load(Ordering::Relaxed), 0);
+    }
+
+    // ── HealthStatus includes circuit-breaker state ───────────────────────────────────
+
+    #[test]
+    fn test_health_status_includes_cb_state() {
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new().with_circuit_breaker(1, Duration::from_secs(60)),
+        );
+
+        // Before the breaker opens the pool should be healthy.
+        assert!(!pool.get_health_status().circuit_breaker_open);
+
+        let _obj = pool.get_object().unwrap();
+        let _ = pool.try_get_object(); // opens the breaker (threshold = 1)
+
+        let health = pool.get_health_status();
+        assert!(health.circuit_breaker_open);
+        assert!(!health.is_healthy);
+        assert!(health.warnings.iter().any(|w| w.contains("Circuit breaker")));
+    }
+
+    // ── DynamicObjectPool: observable counts and eviction ────────────────────────────
+
+    #[test]
+    fn test_dynamic_pool_available_and_active_counts() {
+        let pool = DynamicObjectPool::new(
+            || 0u8,
+            PoolConfiguration::new().with_max_pool_size(5),
+        );
+
+        let _a = pool.get_object().unwrap();
+        let _b = pool.get_object().unwrap();
+
+        assert_eq!(pool.active_count(), 2);
+
+        drop(_a);
+        assert_eq!(pool.available_count(), 1);
+    }
+
+    // ── Validation on return ──────────────────────────────────────────────────
+
+    #[test]
+    fn test_validation_failure_removes_object_and_increments_metric() {
+        // Validation rejects any value ≤ 0; we mutate the object to -1 before
+        // returning it so the validator fires.
+        let config = PoolConfiguration::new().with_validation(|x: &i32| *x > 0);
+        let pool = ObjectPool::new(vec![1, 2, 3], config);
+
+        {
+            let mut obj = pool.get_object().unwrap();
+            *obj.get_mut() = -1; // will fail validation on drop
+        }
+
+        // One object was rejected, so only 2 remain.
+        assert_eq!(pool.available_count(), 2);
+
+        let metrics = pool.get_metrics();
+        assert_eq!(metrics.validation_failures, 1);
+        assert_eq!(metrics.total_returned, 0); // failed validation ≠ returned
+    }
+
+    #[test]
+    fn test_validation_success_returns_object() {
+        let config = PoolConfiguration::new().with_validation(|x: &i32| *x > 0);
+        let pool = ObjectPool::new(vec![1, 2, 3], config);
+
+        {
+            let _obj = pool.get_object().unwrap(); // value is > 0, passes
+        }
+
+        assert_eq!(pool.available_count(), 3);
+        let metrics = pool.get_metrics();
+        assert_eq!(metrics.validation_failures, 0);
+        assert_eq!(metrics.total_returned, 1);
+    }
+
+    // ── PooledObject Debug impl ───────────────────────────────────────────────
+
+    #[test]
+    fn test_pooled_object_debug_does_not_panic() {
+        let pool = ObjectPool::new(vec![42i32], PoolConfiguration::default());
+        let obj = pool.get_object().unwrap();
+        let dbg = format!("{:?}", obj);
+        assert!(dbg.contains("PooledObject"));
+        assert!(dbg.contains("42"));
+    }
+
+    // ── DynamicObjectPool::with_initial returns objects to pool ──────────────
+
+    #[test]
+    fn test_dynamic_with_initial_returns_on_drop() {
+        let pool = DynamicObjectPool::with_initial(
+            || 99,
+            vec![1, 2, 3],
+            PoolConfiguration::new().with_max_pool_size(5),
+        );
+
+        assert_eq!(pool.available_count(), 3);
+        {
+            let _obj = pool.get_object().unwrap();
+            assert_eq!(pool.active_count(), 1);
+        }
+        assert_eq!(pool.available_count(), 3);
+    }
+
+    // ── QueryableObjectPool::try_get_object error propagation ────────────────
+
+    #[test]
+    fn test_queryable_try_get_propagates_max_active_error() {
+        let pool = QueryableObjectPool::new(
+            vec![1, 2, 3],
+            PoolConfiguration::new().with_max_active_objects(1),
+        );
+
+        let _obj = pool.get_object(|_| true).unwrap();
+        let result = pool.try_get_object(|_| true);
+        assert!(matches!(result, Err(PoolError::MaxActiveObjectsReached)));
+    }
+
+    #[test]
+    fn test_queryable_try_get_returns_none_on_no_match() {
+        let pool = QueryableObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+        let result = pool.try_get_object(|x| *x == 99);
+        assert!(matches!(result, Ok(None)));
+    }
+
+    // ── DynamicObjectPool::try_get_object full → Ok(None) ────────────────────
+
+    #[test]
+    fn test_dynamic_try_get_returns_none_when_full() {
+        let pool = DynamicObjectPool::new(
+            || 0,
+            PoolConfiguration::new().with_max_pool_size(1),
+        );
+
+        let _obj = pool.get_object().unwrap(); // fills capacity
+        let result = pool.try_get_object();
+        assert!(matches!(result, Ok(None)));
+    }
+
+    // ── Idle-timeout eviction ─────────────────────────────────────────────────
+
+    #[test]
+    fn test_idle_timeout_eviction() {
+        use std::thread;
+
+        let config = PoolConfiguration::new()
+            .with_idle_timeout(Duration::from_millis(50));
+
+        let pool = ObjectPool::new(vec![1, 2, 3], config);
+        thread::sleep(Duration::from_millis(80));
+
+        // On checkout the expired objects are skipped.
+        let result = pool.try_get_object().unwrap();
+        assert!(result.is_none() || pool.available_count() < 3);
+    }
+
+    // ── Combined TTL + idle-timeout eviction ──────────────────────────────────
+
+    #[test]
+    fn test_combined_ttl_idle_eviction_via_evict_expired() {
+        use std::thread;
+
+        let config = PoolConfiguration::new()
+            .with_ttl(Duration::from_millis(50))
+            .with_idle_timeout(Duration::from_secs(300)); // idle won't fire
+
+        let pool = ObjectPool::new(vec![1, 2, 3], config);
+        thread::sleep(Duration::from_millis(80));
+
+        let evicted = pool.evict_expired();
+        assert_eq!(evicted, 3);
+        assert_eq!(pool.available_count(), 0);
+    }
+
+    // ── evict_expired / drain on delegating pool types ────────────────────────
+
+    #[test]
+    fn test_queryable_evict_expired_and_drain() {
+        use std::thread;
+
+        let config = PoolConfiguration::new()
+            .with_ttl(Duration::from_millis(50));
+        let pool = QueryableObjectPool::new(vec![1, 2, 3], config);
+        thread::sleep(Duration::from_millis(80));
+
+        let evicted = pool.evict_expired();
+        assert_eq!(evicted, 3);
+        assert_eq!(pool.available_count(), 0);
+
+        // Drain on empty pool returns empty vec without panic.
+        let drained = pool.drain();
+        assert!(drained.is_empty());
+    }
+
+    #[test]
+    fn test_dynamic_evict_and_drain() {
+        use std::thread;
+
+        let config = PoolConfiguration::new()
+            .with_ttl(Duration::from_millis(50))
+            .with_max_pool_size(5);
+        let pool = DynamicObjectPool::with_initial(|| 0, vec![1, 2, 3], config);
+        thread::sleep(Duration::from_millis(80));
+
+        let evicted = pool.evict_expired();
+        assert_eq!(evicted, 3);
+
+        let drained = pool.drain();
+        assert!(drained.is_empty());
+        let report = pool.check_consistency();
+        assert!(report.is_consistent(), "{report}");
+    }
+
+    // ── metrics export on delegating pool types ───────────────────────────────
+
+    #[test]
+    fn test_queryable_export_metrics() {
+        let pool = QueryableObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+        let _ = pool.get_object(|_| true).unwrap();
+
+        let map = pool.export_metrics();
+        assert!(map.contains_key("total_retrieved"));
+        assert_eq!(map.get("total_retrieved").unwrap(), "1");
+    }
+
+    #[test]
+    fn test_dynamic_export_metrics() {
+        let pool = DynamicObjectPool::new(|| 1, PoolConfiguration::new().with_max_pool_size(5));
+        let _ = pool.get_object().unwrap();
+
+        let map = pool.export_metrics();
+        assert!(map.contains_key("total_retrieved"));
+        assert_eq!(map.get("total_retrieved").unwrap(), "1");
+    }
+
+    // ── total_detached in metrics map and Prometheus ──────────────────────────
+
+    #[test]
+    fn test_export_metrics_includes_total_detached() {
+        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+
+        let obj = pool.get_object().unwrap();
+        let _ = obj.into_detached();
+
+        let map = pool.export_metrics();
+        assert!(map.contains_key("total_detached"));
+        assert_eq!(map.get("total_detached").unwrap(), "1");
+    }
+
+    #[test]
+    fn test_prometheus_export_includes_detached_counter() {
+        let pool = ObjectPool::new(vec![1], PoolConfiguration::default());
+        let obj = pool.get_object().unwrap();
+        let _ = obj.into_detached();
+
+        let prom = pool.export_metrics_prometheus("detach_pool", None);
+        assert!(prom.contains("objectpool_objects_detached_total"));
+        assert!(prom.contains("detach_pool"));
+    }
+
+    // ── warmup capped at capacity ─────────────────────────────────────────────
+
+    #[test]
+    fn test_warmup_capped_at_capacity() {
+        let pool = DynamicObjectPool::new(
+            || 0,
+            PoolConfiguration::new().with_max_pool_size(3),
+        );
+
+        // Request more than capacity; only 3 should be created.
+        pool.warmup(100).unwrap();
+        assert_eq!(pool.available_count(), 3);
+    }
+
+    // ── concurrent DynamicObjectPool creation stays within capacity ──────────
+
+    #[tokio::test]
+    async fn test_dynamic_pool_concurrent_creation_does_not_exceed_capacity() {
+        use std::sync::Arc;
+
+        let pool = Arc::new(DynamicObjectPool::new(
+            || 0u32,
+            PoolConfiguration::new().with_max_pool_size(5),
+        ));
+
+        let mut handles = vec![];
+        for _ in 0..20 {
+            let p = Arc::clone(&pool);
+            handles.push(tokio::spawn(async move { p.get_object() }));
+        }
+
+        let mut objects = vec![];
+        for h in handles {
+            if let Ok(Ok(obj)) = h.await {
+                objects.push(obj);
+            }
+        }
+
+        // Total live objects (active + available) must never exceed capacity=5.
+        assert!(pool.active_count() + pool.available_count() <= 5);
+        // All successfully obtained objects came from within capacity.
+        assert!(objects.len() <= 5);
+    }
+
+    // ── QueryableObjectPool::get_object_async fails fast on errors ────────────
+
+    #[tokio::test]
+    async fn test_queryable_async_fails_fast_on_max_active() {
+        use std::time::Instant;
+
+        let pool = QueryableObjectPool::new(
+            vec![1, 2],
+            PoolConfiguration::new()
+                .with_timeout(Duration::from_secs(2))
+                .with_max_active_objects(1),
+        );
+        let _obj = pool.get_object(|_| true).unwrap();
+
+        let start = Instant::now();
+        let result = pool.get_object_async(|_| true).await;
+        assert!(start.elapsed() < Duration::from_millis(200));
+        assert!(matches!(result, Err(PoolError::MaxActiveObjectsReached)));
+    }
+
+    // ── DynamicObjectPool::get_object_async timeout ───────────────────────────
+
+    #[tokio::test]
+    async fn test_dynamic_async_timeout_when_full() {
+        let pool = DynamicObjectPool::new(
+            || 0,
+            PoolConfiguration::new()
+                .with_max_pool_size(1)
+                .with_timeout(Duration::from_millis(60)),
+        );
+
+        let _obj = pool.get_object().unwrap(); // fills capacity
+
+        let result = pool.get_object_async().await;
+        assert!(matches!(result, Err(PoolError::Timeout(..))));
+    }
+
+    // ── New regression / feature tests ───────────────────────────────────────
+
+    #[test]
+    fn test_capacity_method() {
+        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::new().with_max_pool_size(10));
+        assert_eq!(pool.capacity(), 10);
+
+        let q = QueryableObjectPool::new(vec![1, 2], PoolConfiguration::default());
+        assert_eq!(q.capacity(), 100); // default max_pool_size
+
+        let d = DynamicObjectPool::new(|| 0, PoolConfiguration::new().with_max_pool_size(7));
+        assert_eq!(d.capacity(), 7);
+    }
+
+    #[test]
+    fn test_get_metrics_on_delegate_pools() {
+        let q = QueryableObjectPool::new(vec![1, 2], PoolConfiguration::default());
+        let _ = q.get_object(|_| true).unwrap();
+        assert_eq!(q.get_metrics().total_retrieved, 1);
+
+        let d = DynamicObjectPool::new(|| 0, PoolConfiguration::new().with_max_pool_size(5));
+        let _ = d.get_object().unwrap();
+        assert_eq!(d.get_metrics().total_retrieved, 1);
+    }
+
+    #[test]
+    fn test_as_ref_as_mut_for_pooled_object() {
+        let pool = ObjectPool::new(vec![42i32], PoolConfiguration::default());
+        let mut obj = pool.get_object().unwrap();
+
+        // AsRef
+        let r: &i32 = obj.as_ref();
+        assert_eq!(*r, 42);
+
+        // AsMut
+        *obj.as_mut() = 99;
+        assert_eq!(*obj.as_ref(), 99);
+    }
+
+    // ── on_borrow hook ────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_on_borrow_runs_on_every_acquisition_path() {
+        let config = || PoolConfiguration::new().with_on_borrow(|x: &mut i32| *x += 100);
+
+        let pool = ObjectPool::new(vec![1], config());
+        assert_eq!(*pool.get_object().unwrap(), 101);
+
+        let queryable = QueryableObjectPool::new(vec![1, 2], config());
+        assert_eq!(*queryable.get_object(|x| *x == 2).unwrap(), 102);
+
+        let dynamic = DynamicObjectPool::new(|| 0, config());
+        assert_eq!(*dynamic.get_object().unwrap(), 100);
+    }
+
+    #[test]
+    fn test_on_borrow_does_not_affect_query_matching() {
+        // The hook runs after selection, so the query sees the stored value.
+        let pool = QueryableObjectPool::new(
+            vec![1, 2],
+            PoolConfiguration::new().with_on_borrow(|x: &mut i32| *x = 0),
+        );
+        {
+            let obj = pool.get_object(|x| *x == 2).unwrap();
+            assert_eq!(*obj, 0);
+        }
+        assert!(matches!(pool.get_object(|x| *x == 2), Err(PoolError::NoMatchFound)));
+    }
+
+    // ── Partitioned queryable pool ────────────────────────────────────────────
+
+    #[test]
+    fn test_hinted_query_scans_only_its_partition() {
+        let scanned = Arc::new(AtomicUsize::new(0));
+        let pool = QueryableObjectPool::new(
+            (0..40u64).collect(),
+            PoolConfiguration::new().with_partitions(4, |x: &u64| *x % 4),
+        );
+
+        let counter = Arc::clone(&scanned);
+        let obj = pool
+            .get_object_with_hint(
+                move |x| {
+                    counter.fetch_add(1, Ordering::Relaxed);
+                    *x == 13
+                },
+                13 % 4,
+            )
+            .unwrap();
+
+        assert_eq!(*obj, 13);
+        assert!(scanned.load(Ordering::Relaxed) <= 10);
+        assert_eq!(pool.available_count(), 39);
+    }
+
+    #[test]
+    fn test_partitioned_pool_returns_objects_to_their_bucket() {
+        let pool = QueryableObjectPool::new(
+            vec![1u64, 2, 3, 4],
+            PoolConfiguration::new().with_partitions(2, |x: &u64| *x % 2),
+        );
+
+        drop(pool.get_object(|x| *x == 3).unwrap());
+        assert_eq!(*pool.get_object_with_hint(|x| *x == 3, 1).unwrap(), 3);
+        assert!(matches!(
+            pool.get_object_with_hint(|x| *x == 3, 0),
+            Err(PoolError::NoMatchFound)
+        ));
+        // Unqueried acquisition still sees every bucket.
+        assert_eq!(pool.get_batch(4).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_query_scan_keeps_other_objects_available() {
+        let pool = QueryableObjectPool::new((0..16).collect(), PoolConfiguration::default());
+        let min_seen = AtomicUsize::new(usize::MAX);
+
+        let obj = pool
+            .get_object(|x| {
+                min_seen.fetch_min(pool.available_count(), Ordering::Relaxed);
+                *x == 15
+            })
+            .unwrap();
+
+        assert_eq!(*obj, 15);
+        // Only the object under inspection is ever out of the queue.
+        assert_eq!(min_seen.load(Ordering::Relaxed), 15);
+        assert_eq!(pool.get_metrics().pool_empty_events, 0);
+    }
+
+    #[test]
+    fn test_concurrent_plain_gets_during_query_scans() {
+        let pool = Arc::new(QueryableObjectPool::new((0..64).collect(), PoolConfiguration::default()));
+        let stop = Arc::new(std::sync::atomic::AtomicBool::new(false));
+
+        let scanner = {
+            let pool = Arc::clone(&pool);
+            let stop = Arc::clone(&stop);
+            std::thread::spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let _ = pool.try_get_object(|x| *x < 0);
+                }
+            })
+        };
+
+        for _ in 0..2_000 {
+            drop(pool.get_batch(4).unwrap());
+        }
+        stop.store(true, Ordering::Relaxed);
+        scanner.join().unwrap();
+
+        assert_eq!(pool.get_metrics().pool_empty_events, 0);
+        assert_eq!(pool.available_count(), 64);
+    }
+
+    #[tokio::test]
+    async fn test_queryable_async_wakes_on_return_not_polling() {
+        let pool = Arc::new(QueryableObjectPool::new(
+            (0..8).collect(),
+            PoolConfiguration::new()
+                .with_timeout(Duration::from_secs(5))
+                .with_retry_backoff(Duration::from_secs(10), Duration::from_secs(10)),
+        ));
+        let held = pool.get_object(|x| *x == 3).unwrap();
+        let scans = Arc::new(AtomicUsize::new(0));
+
+        let waiter = {
+            let pool = Arc::clone(&pool);
+            let scans = Arc::clone(&scans);
+            tokio::spawn(async move {
+                pool.get_object_async(move |x| {
+                    scans.fetch_add(1, Ordering::Relaxed);
+                    *x == 3
+                })
+                .await
+                .map(|obj| *obj)
+            })
+        };
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        // Only the initial scan so far: no timer-driven rescans.
+        assert_eq!(scans.load(Ordering::Relaxed), 7);
+        drop(held);
+
+        let found = tokio::time::timeout(Duration::from_secs(1), waiter).await.unwrap().unwrap();
+        assert_eq!(found.unwrap(), 3);
+    }
+
+    // ── Keyed dynamic queryable pool ──────────────────────────────────────────
+
+    fn shard_pool(max: usize) -> DynamicQueryablePool<u32, (u32, usize)> {
+        let created = AtomicUsize::new(0);
+        DynamicQueryablePool::new(
+            move |shard: &u32| (*shard, created.fetch_add(1, Ordering::Relaxed)),
+            |conn: &(u32, usize), shard: &u32| conn.0 == *shard,
+            PoolConfiguration::new().with_max_pool_size(max),
+        )
+    }
+
+    #[test]
+    fn test_keyed_pool_reuses_matching_objects() {
+        let pool = shard_pool(4);
+
+        let a = pool.get_object(&1).unwrap();
+        let serial = a.1;
+        drop(a);
+        let b = pool.get_object(&2).unwrap();
+        let a = pool.get_object(&1).unwrap();
+
+        assert_eq!(a.1, serial);
+        assert_eq!(b.0, 2);
+        assert_eq!(pool.active_count(), 2);
+    }
+
+    #[test]
+    fn test_keyed_pool_replaces_idle_object_at_capacity() {
+        let destroyed = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&destroyed);
+        let pool = DynamicQueryablePool::new(
+            |shard: &u32| *shard,
+            |conn: &u32, shard: &u32| conn == shard,
+            PoolConfiguration::new()
+                .with_max_pool_size(1)
+                .with_on_destroy(move |_| {
+                    counter.fetch_add(1, Ordering::Relaxed);
+                }),
+        );
+
+        drop(pool.get_object(&1).unwrap());
+        let obj = pool.get_object(&2).unwrap();
+
+        assert_eq!(*obj, 2);
+        assert_eq!(destroyed.load(Ordering::Relaxed), 1);
+        assert_eq!(pool.available_count(), 0);
+    }
+
+    #[test]
+    fn test_keyed_pool_full_when_all_objects_in_use() {
+        let pool = shard_pool(1);
+        let _held = pool.get_object(&1).unwrap();
+
+        assert!(matches!(pool.get_object(&2), Err(PoolError::PoolFull)));
+        assert!(pool.try_get_object(&2).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_keyed_pool_async() {
+        let pool = shard_pool(2);
+        let obj = pool.get_object_async(&9).await.unwrap();
+        assert_eq!(obj.0, 9);
+    }
+
+    // ── Guarded execution ─────────────────────────────────────────────────────
+
+    #[test]
+    fn test_run_guarded_operation_failures_open_breaker() {
+        let pool = ObjectPool::new(
+            vec![1, 2],
+            PoolConfiguration::new().with_circuit_breaker(3, Duration::from_secs(60)),
+        );
+
+        for _ in 0..3 {
+            let result = pool.run_guarded(|_| Err::<(), _>("boom"));
+            assert!(matches!(result, Err(GuardedError::Operation("boom"))));
+        }
+
+        assert_eq!(pool.available_count(), 2);
+        assert!(matches!(pool.get_object(), Err(PoolError::CircuitBreakerOpen)));
+    }
+
+    #[test]
+    fn test_run_guarded_ignores_pool_emptiness_for_breaker() {
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new().with_circuit_breaker(1, Duration::from_secs(60)),
+        );
+        let held = pool.get_object().unwrap();
+
+        let result = pool.run_guarded(|_| Ok::<_, ()>(()));
+        assert!(matches!(result, Err(GuardedError::Pool(PoolError::PoolEmpty))));
+        drop(held);
+
+        assert_eq!(pool.run_guarded(|x| Ok::<_, ()>(*x)).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_run_guarded_success_resets_failure_streak() {
+        let pool = DynamicObjectPool::new(
+            || 0,
+            PoolConfiguration::new().with_circuit_breaker(2, Duration::from_secs(60)),
+        );
+
+        let _ = pool.run_guarded(|_| Err::<(), _>(()));
+        pool.run_guarded(|_| Ok::<_, ()>(())).unwrap();
+        let _ = pool.run_guarded(|_| Err::<(), _>(()));
+
+        assert!(pool.get_object().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_run_guarded_async_returns_object_and_counts_failure() {
+        let pool = DynamicObjectPool::new(
+            || String::from("conn"),
+            PoolConfiguration::new().with_circuit_breaker(1, Duration::from_secs(60)),
+        );
+
+        let result = pool
+            .run_guarded_async(|conn| async move {
+                tokio::task::yield_now().await;
+                Err::<(), _>(conn.len())
+            })
+            .await;
+
+        assert!(matches!(result, Err(GuardedError::Operation(4))));
+        assert_eq!(pool.active_count(), 0);
+        assert!(matches!(pool.get_object(), Err(PoolError::CircuitBreakerOpen)));
+    }
+
+    // ── Breaker signal selection ──────────────────────────────────────────────
+
+    #[test]
+    fn test_operations_only_breaker_ignores_empty_pool() {
+        use crate::BreakerSignals;
+
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new()
+                .with_circuit_breaker(1, Duration::from_secs(60))
+                .with_breaker_signals(BreakerSignals::Operations),
+        );
+        let held = pool.get_object().unwrap();
+        for _ in 0..5 {
+            assert!(matches!(pool.get_object(), Err(PoolError::PoolEmpty)));
+        }
+        drop(held);
+
+        let _obj = pool.get_object().unwrap();
+        pool.report_failure();
+        assert!(matches!(pool.get_object(), Err(PoolError::CircuitBreakerOpen)));
+    }
+
+    #[test]
+    fn test_acquisition_only_breaker_ignores_reports() {
+        use crate::BreakerSignals;
+
+        let pool = DynamicObjectPool::new(
+            || 0,
+            PoolConfiguration::new()
+                .with_circuit_breaker(1, Duration::from_secs(60))
+                .with_breaker_signals(BreakerSignals::Acquisition),
+        );
+        pool.report_failure();
+        let _ = pool.run_guarded(|_| Err::<(), _>(()));
+
+        assert!(pool.get_object().is_ok());
+    }
+
+    #[test]
+    fn test_reported_success_breaks_failure_streak() {
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new().with_circuit_breaker(2, Duration::from_secs(60)),
+        );
+        pool.report_failure();
+        pool.report_success();
+        pool.report_failure();
+
+        assert!(pool.get_object().is_ok());
+    }
+
+    // ── Runtime-aware blocking acquisition ────────────────────────────────────
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_blocking_in_place_lets_runtime_return_objects() {
+        use crate::WaitPolicy;
+        let pool = Arc::new(ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new().with_wait_on_empty(WaitPolicy::Wait(Duration::from_secs(5))),
+        ));
+        let held = pool.get_object().unwrap();
+        let returner = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(held);
+        });
+
+        let obj = pool.get_object_blocking_in_place().unwrap();
+        assert_eq!(*obj, 1);
+        returner.await.unwrap();
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_blocking_in_place_does_not_hang_current_thread_runtime() {
+        use crate::WaitPolicy;
+        let pool = DynamicObjectPool::new(
+            || 0,
+            PoolConfiguration::new()
+                .with_max_pool_size(1)
+                .with_wait_on_empty(WaitPolicy::WaitForever),
+        );
+        let _held = pool.get_object_blocking_in_place().unwrap();
+        assert!(matches!(pool.get_object_blocking_in_place(), Err(PoolError::PoolFull)));
+    }
+
+    // ── Guards across threads ─────────────────────────────────────────────────
+
+    #[test]
+    fn test_guards_move_between_scoped_threads() {
+        let pool = ObjectPool::new(vec![0u32; 4], PoolConfiguration::default());
+        let guards: Vec<_> = (0..4).map(|_| pool.get_object().unwrap()).collect();
+
+        std::thread::scope(|s| {
+            for mut guard in guards {
+                s.spawn(move || *guard += 1);
+            }
+        });
+
+        assert_eq!(pool.available_count(), 4);
+        let total: u32 = pool.get_batch(4).unwrap().iter().map(|obj| **obj).sum();
+        assert_eq!(total, 4);
+    }
+
+    #[test]
+    fn test_send_but_not_sync_objects_can_be_handed_to_threads() {
+        use std::cell::Cell;
+
+        // `Cell` is Send but not Sync: the guard can move, not be shared.
+        fn requires_send<X: Send>(_: &X) {}
+        let (sender, receiver) = std::sync::mpsc::channel::<Cell<u8>>();
+        let guard = PooledObject::new(Cell::new(7), 0, Arc::new(move |v, _, _, _| {
+            sender.send(v).unwrap();
+            Ok(())
+        }), Arc::new(|_| {}));
+        requires_send(&guard);
+
+        std::thread::spawn(move || guard.set(8)).join().unwrap();
+        assert_eq!(receiver.recv().unwrap().get(), 8);
+    }
+
+    #[tokio::test]
+    async fn test_guard_held_across_await_in_spawned_task() {
+        let pool = Arc::new(ObjectPool::new(vec![5], PoolConfiguration::default()));
+        let p = Arc::clone(&pool);
+        let value = tokio::spawn(async move {
+            let obj = p.get_object_async().await.unwrap();
+            tokio::task::yield_now().await;
+            *obj
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(value, 5);
+        assert_eq!(pool.available_count(), 1);
+    }
+
+    // ── External ids ──────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_external_id_survives_round_trips() {
+        let pool = QueryableObjectPool::new_with_ids(
+            vec![("a".to_string(), 1), ("b".to_string(), 2)],
+            PoolConfiguration::default(),
+        );
+
+        for _ in 0..3 {
+            let obj = pool.get_by_id("b").unwrap();
+            assert_eq!(*obj, 2);
+            assert_eq!(obj.external_id(), Some("b"));
+        }
+        let other = pool.get_object(|x| *x == 1).unwrap();
+        assert_eq!(other.external_id(), Some("a"));
+    }
+
+    #[test]
+    fn test_get_by_id_misses_detached_object() {
+        let pool = QueryableObjectPool::new_with_ids(vec![("a", 1)], PoolConfiguration::default());
+        let _value = pool.get_by_id("a").unwrap().into_detached();
+        assert!(matches!(pool.get_by_id("a"), Err(PoolError::NoMatchFound)));
+        assert_eq!(pool.active_count(), 0);
+    }
+
+    #[test]
+    fn test_created_objects_have_no_external_id() {
+        let pool = DynamicObjectPool::new(|| 0, PoolConfiguration::new().with_max_pool_size(1));
+        let obj = pool.get_object().unwrap();
+        assert_eq!(obj.external_id(), None);
+        assert_eq!(pool.inner.external_id(obj.id()), None);
+    }
+
+    // ── Acquisition context ───────────────────────────────────────────────────
+
+    #[test]
+    fn test_on_acquire_sees_caller_context_or_empty_one() {
+        use crate::AcquireContext;
+
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = Arc::clone(&seen);
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new().with_on_acquire(move |_: &mut i32, ctx: &AcquireContext| {
+                log.lock().unwrap().push((ctx.label().map(str::to_string), ctx.priority()));
+            }),
+        );
+
+        drop(pool.get_object().unwrap());
+        let obj = pool
+            .get_object_with(AcquireContext::new().with_label("batch").with_priority(-1))
+            .unwrap();
+        assert_eq!(obj.acquire_context().unwrap().priority(), -1);
+        drop(obj);
+        assert!(pool.get_object().unwrap().acquire_context().is_none());
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen[0], (None, 0));
+        assert_eq!(seen[1], (Some("batch".to_string()), -1));
+    }
+
+    #[tokio::test]
+    async fn test_dynamic_get_object_with_async_honours_deadline() {
+        use crate::{AcquireContext, WaitPolicy};
+
+        let pool = DynamicObjectPool::new(
+            || 0,
+            PoolConfiguration::new()
+                .with_max_pool_size(1)
+                .with_wait_on_empty(WaitPolicy::WaitForever),
+        );
+        let created = pool
+            .get_object_with_async(AcquireContext::new().with_label("a"))
+            .await
+            .unwrap();
+        assert_eq!(created.acquire_context().unwrap().label(), Some("a"));
+
+        let start = std::time::Instant::now();
+        let result = pool
+            .get_object_with_async(AcquireContext::new().with_timeout(Duration::from_millis(30)))
+            .await;
+        assert!(matches!(result, Err(PoolError::Timeout(..))));
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
+    // ── Per-caller metrics ────────────────────────────────────────────────────
+
+    #[test]
+    fn test_caller_metrics_count_acquisitions_timeouts_and_holds() {
+        use crate::{AcquireContext, WaitPolicy};
+
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new()
+                .with_caller_metrics()
+                .with_wait_on_empty(WaitPolicy::Wait(Duration::from_millis(10))),
+        );
+        let label = |l: &str| AcquireContext::new().with_label(l);
+
+        let held = pool.get_object_with(label("ingest")).unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(matches!(pool.get_object_with(label("api")), Err(PoolError::Timeout(..))));
+        drop(held);
+        drop(pool.get_object_with(AcquireContext::new()).unwrap());
+
+        let by_caller = pool.get_metrics().by_caller;
+        assert_eq!(by_caller.len(), 2, "unlabelled acquisitions are not tracked");
+        assert_eq!(by_caller["ingest"].acquisitions, 1);
+        assert_eq!(by_caller["ingest"].releases, 1);
+        assert!(by_caller["ingest"].total_hold_time >= Duration::from_millis(5));
+        assert_eq!(by_caller["api"].timeouts, 1);
+        assert_eq!(by_caller["api"].acquisitions, 0);
+
+        let prometheus = pool.export_metrics_prometheus("p", None);
+        assert!(prometheus.contains("objectpool_caller_acquisitions_total{pool=\"p\",caller=\"ingest\"} 1"));
+        assert!(prometheus.contains("objectpool_caller_timeouts_total{pool=\"p\",caller=\"api\"} 1"));
+        assert_eq!(pool.export_metrics()["caller.api.timeouts"], "1");
+    }
+
+    #[test]
+    fn test_caller_metrics_are_off_by_default() {
+        use crate::AcquireContext;
+
+        let pool = DynamicObjectPool::new(|| 0, PoolConfiguration::default());
+        drop(pool.get_object_with(AcquireContext::new().with_label("x")).unwrap());
+        assert!(pool.get_metrics().by_caller.is_empty());
+        assert!(!pool.export_metrics_prometheus("p", None).contains("caller="));
+    }
+
+    // ── Self-test ─────────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_verify_removes_invalid_and_expired_objects() {
+        let destroyed = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&destroyed);
+        let pool = ObjectPool::new(
+            vec![1, -1, 2],
+            PoolConfiguration::new()
+                .with_validation(|x: &i32| *x > 0)
+                .with_on_destroy(move |_| {
+                    counter.fetch_add(1, Ordering::Relaxed);
+                }),
+        );
+        let held = pool.get_object().unwrap();
+
+        let report = pool.verify();
+        assert_eq!(report.checked, 2, "checked-out objects are not examined");
+        assert_eq!(report.passed + report.failed_validation.len(), 2);
+        assert_eq!(destroyed.load(Ordering::Relaxed), report.failed_validation.len());
+        drop(held);
+
+        let ttl = ObjectPool::new(vec![1], PoolConfiguration::new().with_ttl(Duration::from_millis(1)));
+        std::thread::sleep(Duration::from_millis(5));
+        let report = ttl.verify();
+        assert_eq!(report.expired.len(), 1);
+        assert!(report.is_ok());
+        assert_eq!(ttl.available_count(), 0);
+    }
+
+    #[test]
+    fn test_verify_visits_each_object_once_across_partitions() {
+        let pool = QueryableObjectPool::new(
+            (0..10u64).collect(),
+            PoolConfiguration::new().with_partitions(3, |x: &u64| *x),
+        );
+        let report = pool.verify();
+        assert_eq!(report.checked, 10);
+        assert_eq!(report.passed, 10);
+        assert_eq!(pool.available_count(), 10);
+    }
+
+    #[tokio::test]
+    async fn test_dynamic_verify_async_checks_factory() {
+        let pool = DynamicObjectPool::new(|| 7, PoolConfiguration::new().with_max_pool_size(1));
+        let report = pool.verify_async().await;
+        assert_eq!(report.factory, Some(FactoryCheck::Passed));
+        assert!(report.is_ok());
+        assert_eq!(pool.available_count(), 0, "the probe object is not kept");
+
+        let _held = pool.get_object().unwrap();
+        assert_eq!(pool.verify().factory, Some(FactoryCheck::SkippedAtCapacity));
+    }
+
+    // ── Creation throttling ───────────────────────────────────────────────────
+
+    /// Factory failing its first `failures` calls, counting every call.
+    fn flaky_factory(failures: usize) -> (Arc<AtomicUsize>, impl Fn() -> Result<i32, String>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let factory = move || {
+            let n = counter.fetch_add(1, Ordering::Relaxed);
+            if n < failures { Err(format!("attempt {n} refused")) } else { Ok(n as i32) }
+        };
+        (calls, factory)
+    }
+
+    #[test]
+    fn test_failed_creation_releases_capacity() {
+        let (calls, factory) = flaky_factory(1);
+        let pool = DynamicObjectPool::try_new(
+            factory,
+            PoolConfiguration::new().with_max_pool_size(1).with_max_active_objects(1),
+        );
+
+        assert!(matches!(pool.get_object(), Err(PoolError::CreationFailed(r)) if r == "attempt 0 refused"));
+        let obj = pool.get_object().expect("population and active slot were given back");
+        assert_eq!(*obj, 1);
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+        assert_eq!(pool.get_metrics().creation_failures, 1);
+    }
+
+    #[test]
+    fn test_creation_backoff_skips_factory_until_elapsed() {
+        let (calls, factory) = flaky_factory(usize::MAX);
+        let pool = DynamicObjectPool::try_new(
+            factory,
+            PoolConfiguration::new()
+                .with_creation_backoff(Duration::from_millis(30), Duration::from_secs(1)),
+        );
+
+        assert!(matches!(pool.get_object(), Err(PoolError::CreationFailed(_))));
+        assert!(matches!(pool.get_object(), Err(PoolError::CreationThrottled)));
+        assert_eq!(calls.load(Ordering::Relaxed), 1, "factory not called while backing off");
+
+        std::thread::sleep(Duration::from_millis(50));
+        assert!(matches!(pool.get_object(), Err(PoolError::CreationFailed(_))));
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+
+        let metrics = pool.get_metrics();
+        assert_eq!(metrics.creation_failures, 2);
+        assert_eq!(metrics.creations_throttled, 1);
+    }
+
+    #[test]
+    fn test_backoff_still_serves_idle_objects() {
+        let pool = DynamicObjectPool::with_initial(
+            || 0,
+            vec![1],
+            PoolConfiguration::new()
+                .with_creation_backoff(Duration::from_secs(60), Duration::from_secs(60)),
+        );
+        pool.inner.creation_throttle.record(false);
+
+        assert_eq!(*pool.get_object().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_rate_limited_creation_waits_under_wait_policy() {
+        use crate::WaitPolicy;
+
+        let pool = DynamicObjectPool::new(
+            || 0,
+            PoolConfiguration::new()
+                .with_creation_rate_limit(20)
+                .with_wait_on_empty(WaitPolicy::Wait(Duration::from_secs(2))),
+        );
+
+        let start = std::time::Instant::now();
+        let held: Vec<_> = (0..3).map(|_| pool.get_object().unwrap()).collect();
+        assert_eq!(held.len(), 3);
+        assert!(start.elapsed() >= Duration::from_millis(90), "creations spaced 50ms apart");
+        assert!(pool.get_metrics().creations_throttled > 0);
+    }
+
+    #[tokio::test]
+    async fn test_warmup_stops_at_factory_error() {
+        let (_, factory) = flaky_factory(0);
+        let failing_third = move || factory().and_then(|n| if n == 2 { Err("full".into()) } else { Ok(n) });
+        let pool = DynamicObjectPool::try_new(failing_third, PoolConfiguration::new().with_max_pool_size(3));
+
+        assert!(matches!(pool.warmup(5), Err(PoolError::CreationFailed(_))));
+        assert_eq!(pool.available_count(), 2);
+        assert!(pool.warmup_async(1).await.is_ok(), "the failed reservation was released");
+        assert_eq!(pool.available_count(), 3);
+    }
+
+    #[test]
+    fn test_verify_reports_factory_error() {
+        let (_, factory) = flaky_factory(usize::MAX);
+        let pool = DynamicObjectPool::try_new(factory, PoolConfiguration::new().with_max_pool_size(1));
+
+        let report = pool.verify();
+        assert_eq!(report.factory, Some(FactoryCheck::CreationFailed("attempt 0 refused".into())));
+        assert!(!report.is_ok());
+        assert_eq!(pool.inner.population.load(Ordering::Relaxed), 0);
+    }
+
+    // ── Pending-creation cap ──────────────────────────────────────────────────
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_burst_on_empty_pool_coalesces_factory_calls() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let (c, r, p) = (Arc::clone(&calls), Arc::clone(&running), Arc::clone(&peak));
+        let pool = Arc::new(DynamicObjectPool::new(
+            move || {
+                c.fetch_add(1, Ordering::Relaxed);
+                let now = r.fetch_add(1, Ordering::AcqRel) + 1;
+                p.fetch_max(now, Ordering::AcqRel);
+                std::thread::sleep(Duration::from_millis(30));
+                r.fetch_sub(1, Ordering::AcqRel);
+                0
+            },
+            PoolConfiguration::new().with_max_pending_creations(2),
+        ));
+
+        let tasks: Vec<_> = (0..50)
+            .map(|_| {
+                let pool = Arc::clone(&pool);
+                tokio::spawn(async move {
+                    let obj = pool.get_object_async().await.unwrap();
+                    tokio::task::yield_now().await;
+                    drop(obj);
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        assert!(peak.load(Ordering::Relaxed) <= 2);
+        assert!(calls.load(Ordering::Relaxed) < 50, "returned objects served most callers");
+        assert_eq!(pool.pending_creations(), 0);
+    }
+
+    #[test]
+    fn test_fail_fast_caller_does_not_join_pending_creation() {
+        let (started_tx, started_rx) = std::sync::mpsc::channel();
+        let (finish_tx, finish_rx) = std::sync::mpsc::channel::<()>();
+        let finish_rx = std::sync::Mutex::new(finish_rx);
+        let pool = Arc::new(DynamicObjectPool::new(
+            move || {
+                started_tx.send(()).unwrap();
+                finish_rx.lock().unwrap().recv().unwrap();
+                0
+            },
+            PoolConfiguration::new().with_max_pending_creations(1),
+        ));
+
+        let creator = {
+            let pool = Arc::clone(&pool);
+            std::thread::spawn(move || pool.get_object().map(|obj| *obj))
+        };
+        started_rx.recv().unwrap();
+        assert_eq!(pool.pending_creations(), 1);
+        assert!(matches!(pool.get_object(), Err(PoolError::CreationPending)));
+        assert!(pool.try_get_object().unwrap().is_none());
+
+        finish_tx.send(()).unwrap();
+        assert_eq!(creator.join().unwrap().unwrap(), 0);
+        assert_eq!(pool.pending_creations(), 0);
+    }
+
+    #[test]
+    fn test_waiting_caller_starts_creation_once_one_lands() {
+        use crate::WaitPolicy;
+
+        let pool = Arc::new(DynamicObjectPool::new(
+            || {
+                std::thread::sleep(Duration::from_millis(20));
+                0
+            },
+            PoolConfiguration::new()
+                .with_max_pending_creations(1)
+                .with_wait_on_empty(WaitPolicy::Wait(Duration::from_secs(2))),
+        ));
+
+        let held: Vec<_> = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..3).map(|_| scope.spawn(|| pool.get_object().unwrap())).collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        assert_eq!(held.len(), 3);
+        assert_eq!(pool.get_metrics().active_objects, 3);
+    }
+
+    // ── Creation policy ───────────────────────────────────────────────────────
+
+    fn counting_factory() -> (Arc<AtomicUsize>, impl Fn() -> usize + Send + Sync + 'static) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        (calls, move || counter.fetch_add(1, Ordering::Relaxed))
+    }
+
+    #[test]
+    fn test_reuse_first_takes_returned_object() {
+        use crate::CreationPolicy;
+
+        let (calls, factory) = counting_factory();
+        let pool = DynamicObjectPool::new(
+            factory,
+            PoolConfiguration::new().with_creation_policy(CreationPolicy::ReuseFirst(Duration::from_secs(2))),
+        );
+        let held = pool.get_object().unwrap();
+
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                std::thread::sleep(Duration::from_millis(20));
+                drop(held);
+            });
+            assert_eq!(*pool.get_object().unwrap(), 0);
+        });
+        assert_eq!(calls.load(Ordering::Relaxed), 1, "no second object was created");
+    }
+
+    #[test]
+    fn test_reuse_first_creates_once_patience_runs_out() {
+        use crate::CreationPolicy;
+
+        let patience = Duration::from_millis(30);
+        let (calls, factory) = counting_factory();
+        let pool = DynamicObjectPool::new(
+            factory,
+            PoolConfiguration::new().with_creation_policy(CreationPolicy::ReuseFirst(patience)),
+        );
+
+        let start = std::time::Instant::now();
+        let _first = pool.get_object().unwrap();
+        assert!(start.elapsed() < patience, "nothing checked out, so no waiting");
+
+        let _second = pool.get_object().unwrap();
+        assert!(start.elapsed() >= patience);
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_hybrid_waits_only_from_threshold() {
+        use crate::CreationPolicy;
+
+        let patience = Duration::from_millis(30);
+        let (calls, factory) = counting_factory();
+        let pool = DynamicObjectPool::new(
+            factory,
+            PoolConfiguration::new().with_creation_policy(CreationPolicy::Hybrid { threshold: 2, patience }),
+        );
+
+        let start = std::time::Instant::now();
+        let held: Vec<_> = (0..2).map(|_| pool.get_object().unwrap()).collect();
+        assert!(start.elapsed() < patience);
+
+        let start = std::time::Instant::now();
+        let _third = pool.get_object().unwrap();
+        assert!(start.elapsed() >= patience);
+        assert_eq!(calls.load(Ordering::Relaxed), 3);
+        drop(held);
+    }
+
+    #[tokio::test]
+    async fn test_reuse_first_async_wakes_on_return() {
+        use crate::CreationPolicy;
+
+        let (calls, factory) = counting_factory();
+        let pool = Arc::new(DynamicObjectPool::new(
+            factory,
+            PoolConfiguration::new()
+                .with_creation_policy(CreationPolicy::ReuseFirst(Duration::from_secs(5)))
+                .with_retry_backoff(Duration::from_secs(1), Duration::from_secs(1)),
+        ));
+        let held = pool.get_object_async().await.unwrap();
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(held);
+        });
+        let start = std::time::Instant::now();
+        let obj = pool.get_object_async().await.unwrap();
+        assert_eq!(*obj, 0);
+        assert!(start.elapsed() < Duration::from_secs(1), "woken by the return, not the backoff");
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+    }
+
+    // ── Holder report ─────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_current_holders_follow_checkouts() {
+        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::new().with_holder_tracking());
+        let first = pool.get_object_with(AcquireContext::new().with_label("slow")).unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+        let second = pool.get_object().unwrap();
+
+        let holders = pool.current_holders();
+        assert_eq!(holders.len(), 2);
+        assert_eq!(holders[0].object_id, first.id());
+        assert_eq!(holders[0].label.as_deref(), Some("slow"));
+        assert!(holders[0].held_for >= Duration::from_millis(5));
+        assert_eq!(holders[1].object_id, second.id());
+
+        drop(first);
+        assert_eq!(pool.current_holders().len(), 1);
+        let _detached = second.into_detached();
+        assert!(pool.current_holders().is_empty());
+    }
+
+    #[test]
+    fn test_current_holders_empty_without_tracking() {
+        let pool = ObjectPool::new(vec![1], PoolConfiguration::default());
+        let _obj = pool.get_object().unwrap();
+        assert!(pool.current_holders().is_empty());
+    }
+
+    #[test]
+    fn test_dynamic_and_keyed_pools_report_holders() {
+        let dynamic = DynamicObjectPool::new(|| 0, PoolConfiguration::new().with_holder_tracking());
+        let _obj = dynamic.get_object().unwrap();
+        assert_eq!(dynamic.current_holders().len(), 1);
+
+        let keyed = DynamicQueryablePool::new(
+            |k: &u32| *k,
+            |obj: &u32, k: &u32| obj == k,
+            PoolConfiguration::new().with_holder_tracking(),
+        );
+        let _one = keyed.get_object(&1).unwrap();
+        assert_eq!(keyed.current_holders().len(), 1);
+    }
+
+    // ── Starvation detection ──────────────────────────────────────────────────
+
+    #[test]
+    fn test_starvation_warning_for_hold_while_acquiring() {
+        use crate::WaitPolicy;
+
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new()
+                .with_wait_on_empty(WaitPolicy::Wait(Duration::from_millis(80)))
+                .with_starvation_detection(Duration::from_millis(20)),
+        );
+        let mut warnings = pool.warnings();
+
+        let _outer = pool.get_object().unwrap();
+        assert!(matches!(pool.get_object(), Err(PoolError::Timeout(..))));
+
+        match warnings.try_recv() {
+            Ok(PoolWarning::Starvation { waiters, longest_wait, since_last_return }) => {
+                assert_eq!(waiters, 1);
+                assert!(longest_wait >= Duration::from_millis(20));
+                assert!(since_last_return >= Duration::from_millis(20));
+            }
+            other => panic!("expected a starvation warning, got {other:?}"),
+        }
+        assert!(warnings.try_recv().is_err(), "one warning per stall");
+    }
+
+    #[tokio::test]
+    async fn test_no_starvation_warning_while_objects_come_back() {
+        let pool = Arc::new(DynamicObjectPool::new(
+            || 0,
+            PoolConfiguration::new()
+                .with_max_pool_size(1)
+                .with_starvation_detection(Duration::from_millis(30)),
+        ));
+        let mut warnings = pool.warnings();
+
+        let held = pool.get_object_async().await.unwrap();
+        let releaser = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            drop(held);
+        });
+        let _obj = pool.get_object_async().await.unwrap();
+        releaser.await.unwrap();
+        assert!(warnings.try_recv().is_err());
+    }
+
+    // ── Hook panics ───────────────────────────────────────────────────────────
+
+    fn panics_on_negative(x: &i32) -> bool {
+        assert!(*x >= 0, "negative object");
+        true
+    }
+
+    #[test]
+    fn test_panicking_validation_on_return_discards_object() {
+        let pool = ObjectPool::new(
+            vec![1, 2],
+            PoolConfiguration::new().with_validation(panics_on_negative),
+        );
+        let mut warnings = pool.warnings();
+
+        let mut obj = pool.get_object().unwrap();
+        *obj = -1;
+        drop(obj);
+
+        assert_eq!(pool.available_count(), 1);
+        assert_eq!(pool.active_count(), 0);
+        let metrics = pool.get_metrics();
+        assert_eq!(metrics.hook_panics, 1);
+        assert_eq!(metrics.validation_failures, 1);
+        assert!(matches!(
+            warnings.try_recv(),
+            Ok(PoolWarning::HookPanicked { hook: "validation", ref message }) if message == "negative object"
+        ));
+    }
+
+    #[test]
+    fn test_panicking_borrow_hook_fails_acquisition_and_frees_slot() {
+        let pool = DynamicObjectPool::new(
+            || 0,
+            PoolConfiguration::new()
+                .with_max_pool_size(1)
+                .with_on_borrow(|_: &mut i32| panic!("borrow hook")),
+        );
+
+        assert!(matches!(pool.get_object(), Err(PoolError::ValidationFailed)));
+        assert_eq!(pool.get_metrics().active_objects, 0);
+        assert_eq!(pool.get_metrics().hook_panics, 1);
+        // The slot was released, so the pool is not stuck at its limit.
+        assert!(matches!(pool.get_object(), Err(PoolError::ValidationFailed)));
+    }
+
+    #[test]
+    fn test_panicking_destroy_hook_is_contained() {
+        let pool = DynamicObjectPool::new(
+            || 0,
+            PoolConfiguration::new()
+                .with_max_pool_size(4)
+                .with_validation(|x: &i32| *x >= 0)
+                .with_on_destroy(|_: i32| panic!("destroy hook")),
+        );
+
+        let mut obj = pool.get_object().unwrap();
+        *obj = -1;
+        drop(obj);
+
+        assert_eq!(pool.get_metrics().hook_panics, 1);
+        assert_eq!(*pool.get_object().unwrap(), 0);
+    }
+
+    // ── Sync and async hooks ──────────────────────────────────────────────────
+
+    #[test]
+    fn test_sync_hooks_run_inline() {
+        use crate::SyncHooks;
+
+        struct Stamp;
+        impl SyncHooks<String> for Stamp {
+            fn on_acquire(&self, s: &mut String, ctx: &AcquireContext) {
+                *s = ctx.label().unwrap_or("anonymous").to_owned();
+            }
+        }
+
+        let pool = ObjectPool::new(vec![String::new()], PoolConfiguration::new().with_sync_hooks(Stamp));
+        assert_eq!(*pool.get_object().unwrap(), "anonymous");
+        let obj = pool.get_object_with(AcquireContext::new().with_label("job")).unwrap();
+        assert_eq!(*obj, "job");
+    }
+
+    #[test]
+    fn test_slow_async_destroy_does_not_block_return() {
+        use crate::AsyncHooks;
+        use std::sync::Mutex;
+        use std::sync::mpsc::{self, Receiver};
+
+        struct Gated(Mutex<Receiver<()>>);
+        impl AsyncHooks<i32> for Gated {
+            fn on_destroy(&self, _: i32) {
+                self.0.lock().unwrap().recv().unwrap();
+            }
+        }
+
+        let (open, gate) = mpsc::channel();
+        let pool = DynamicObjectPool::new(
+            || 1,
+            PoolConfiguration::new()
+                .with_validation(|x: &i32| *x > 0)
+                .with_async_hooks(Gated(Mutex::new(gate))),
+        );
+
+        for _ in 0..3 {
+            let mut obj = pool.get_object().unwrap();
+            *obj = 0;
+            // Returns at once although the destroy hook is blocked.
+            drop(obj);
+        }
+        assert_eq!(pool.get_metrics().validation_failures, 3);
+        assert_eq!(*pool.get_object().unwrap(), 1);
+        for _ in 0..3 {
+            open.send(()).unwrap();
+        }
+    }
+
+    // ── Multiple runtimes ─────────────────────────────────────────────────────
+
+    fn current_thread_runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap()
+    }
+
+    fn waiting_pool() -> Arc<ObjectPool<i32>> {
+        use crate::WaitPolicy;
+
+        Arc::new(ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new().with_wait_on_empty(WaitPolicy::Wait(Duration::from_secs(2))),
+        ))
+    }
+
+    /// Hold `pool`'s only object on another thread for `hold`.
+    fn hold_elsewhere(pool: &Arc<ObjectPool<i32>>, hold: Duration) -> std::thread::JoinHandle<()> {
+        let obj = pool.get_object().unwrap();
+        std::thread::spawn(move || {
+            std::thread::sleep(hold);
+            drop(obj);
+        })
+    }
+
+    #[test]
+    fn test_pool_outlives_the_runtime_it_was_created_on() {
+        let pool = current_thread_runtime().block_on(async {
+            let pool = waiting_pool();
+            drop(pool.get_object_async().await.unwrap());
+            pool
+        });
+
+        let holder = hold_elsewhere(&pool, Duration::from_millis(20));
+        let obj = current_thread_runtime().block_on(pool.get_object_async()).unwrap();
+        assert_eq!(*obj, 1);
+        assert!(current_thread_runtime().block_on(obj.release_async()).is_ok());
+        holder.join().unwrap();
+    }
+
+    #[test]
+    fn test_async_acquisition_outside_any_runtime() {
+        let pool = waiting_pool();
+        let holder = hold_elsewhere(&pool, Duration::from_millis(20));
+
+        let obj = futures::executor::block_on(pool.get_object_async()).unwrap();
+        assert!(futures::executor::block_on(obj.release_async()).is_ok());
+        holder.join().unwrap();
+        assert_eq!(pool.available_count(), 1);
+    }
+
+    #[test]
+    fn test_blocking_acquisition_on_another_runtimes_blocking_pool() {
+        let pool = waiting_pool();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let holder = hold_elsewhere(&pool, Duration::from_millis(20));
+
+        let p = Arc::clone(&pool);
+        let value = runtime
+            .block_on(async { tokio::task::spawn_blocking(move || *p.get_object().unwrap()).await })
+            .unwrap();
+        assert_eq!(value, 1);
+        holder.join().unwrap();
+    }
+
+    #[test]
+    fn test_lease_callback_fires_after_its_runtime_shuts_down() {
+        let pool = ObjectPool::new(vec![1], PoolConfiguration::default());
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        let obj = current_thread_runtime().block_on(async {
+            pool.get_object_leased_with(Duration::from_millis(20), move || tx.send(()).unwrap())
+                .unwrap()
+        });
+        // The runtime that armed the timer is gone; the callback still fires.
+        rx.recv_timeout(Duration::from_secs(2)).unwrap();
+        assert!(obj.lease().unwrap().expired());
+    }
+
+    // ── Closed pools ──────────────────────────────────────────────────────────
+
+    fn is_closed_err<T>(result: PoolResult<T>) -> bool {
+        matches!(result, Err(PoolError::PoolClosed))
+    }
+
+    #[tokio::test]
+    async fn test_every_acquisition_path_fails_after_shutdown() {
+        let pool = ObjectPool::new(vec![1, 2], PoolConfiguration::default());
+        assert_eq!(pool.shutdown(), 2);
+        assert!(is_closed_err(pool.get_object()));
+        assert!(is_closed_err(pool.try_get_object()));
+        assert!(is_closed_err(pool.get_object_async().await));
+        assert!(is_closed_err(pool.get_object_with(AcquireContext::new())));
+        assert!(is_closed_err(pool.get_object_blocking_in_place()));
+        assert!(is_closed_err(pool.get_batch(1)));
+
+        let queryable = QueryableObjectPool::new(vec![1], PoolConfiguration::default());
+        queryable.shutdown();
+        assert!(is_closed_err(queryable.get_object(|_| true)));
+        assert!(is_closed_err(queryable.try_get_object(|_| true)));
+        assert!(is_closed_err(queryable.get_object_async(|_| true).await));
+
+        let dynamic = DynamicObjectPool::new(|| 0, PoolConfiguration::default());
+        dynamic.shutdown();
+        assert!(is_closed_err(dynamic.get_object()));
+        assert!(is_closed_err(dynamic.try_get_object()));
+        assert!(is_closed_err(dynamic.get_object_async().await));
+        assert!(is_closed_err(dynamic.warmup(1)));
+        assert!(is_closed_err(dynamic.warmup_async(1).await));
+        assert_eq!(dynamic.get_metrics().active_objects, 0, "nothing was created");
+
+        let keyed = DynamicQueryablePool::new(|k: &u32| *k, |o: &u32, k: &u32| o == k, PoolConfiguration::default());
+        keyed.shutdown();
+        assert!(is_closed_err(keyed.get_object(&1)));
+        assert!(keyed.is_closed());
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_wakes_waiting_acquisitions() {
+        use crate::WaitPolicy;
+
+        let pool = Arc::new(ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new().with_wait_on_empty(WaitPolicy::WaitForever),
+        ));
+        let _held = pool.get_object().unwrap();
+
+        let p = Arc::clone(&pool);
+        let waiter = tokio::spawn(async move { p.get_object_async().await });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        pool.shutdown();
+
+        let result = tokio::time::timeout(Duration::from_secs(1), waiter).await.unwrap().unwrap();
+        assert!(is_closed_err(result));
+    }
+
+    #[test]
+    fn test_returns_after_shutdown_are_accepted_and_destroyed() {
+        let destroyed = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&destroyed);
+        let pool = ObjectPool::new(
+            vec![1, 2, 3, 4],
+            PoolConfiguration::new().with_on_destroy(move |_: i32| {
+                counter.fetch_add(1, Ordering::Relaxed);
+            }),
+        );
+        let single = pool.get_object().unwrap();
+        let many = vec![pool.get_object().unwrap(), pool.get_object().unwrap()];
+
+        assert_eq!(pool.shutdown(), 1);
+        assert_eq!(pool.shutdown(), 0, "second shutdown is a no-op");
+        assert!(single.release().is_ok());
+        assert_eq!(pool.return_many(many), 0);
+
+        assert_eq!(destroyed.load(Ordering::Relaxed), 4);
+        assert_eq!(pool.available_count(), 0);
+        assert_eq!(pool.active_count(), 0);
+        let report = pool.check_consistency();
+        assert!(report.is_consistent(), "{report}");
+    }
+
+    // ── Waiting on the circuit breaker ────────────────────────────────────────
+
+    #[tokio::test]
+    async fn test_async_get_waits_out_a_breaker_that_reopens_in_time() {
+        use crate::WaitPolicy;
+        use std::time::Instant;
+
+        let pool = Arc::new(ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new()
+                .with_wait_on_empty(WaitPolicy::Wait(Duration::from_secs(2)))
+                .with_circuit_breaker(1, Duration::from_millis(50)),
+        ));
+        let held = pool.get_object().unwrap();
+        // The empty attempt opens the breaker.
+        assert!(matches!(pool.try_get_object(), Ok(None)));
+        drop(held);
+
+        let start = Instant::now();
+        let obj = pool.get_object_async().await.unwrap();
+        assert_eq!(*obj, 1);
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+
+    // ── Invalidation ──────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_invalidated_idle_objects_are_destroyed_on_next_touch() {
+        let destroyed = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&destroyed);
+        let pool = ObjectPool::new(
+            vec![1, 2],
+            PoolConfiguration::new().with_on_destroy(move |_| {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }),
+        );
+
+        assert_eq!(pool.invalidate_all(), 1);
+        assert_eq!(pool.available_count(), 2, "nothing destroyed until touched");
+        assert!(matches!(pool.get_object(), Err(PoolError::PoolEmpty)));
+        assert_eq!(destroyed.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_invalidated_active_objects_are_destroyed_on_return() {
+        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+        let a = pool.get_object().unwrap();
+        let b = pool.get_object().unwrap();
+        pool.invalidate_all();
+
+        assert!(a.release().is_ok());
+        assert_eq!(pool.return_many([b]), 0, "destroyed, not put back");
+        assert_eq!(pool.available_count(), 1, "only the idle object remains, still stale");
+        assert_eq!(pool.active_count(), 0);
+        assert_eq!(pool.get_metrics().total_returned, 0);
+    }
+
+    #[test]
+    fn test_dynamic_pool_refills_after_invalidation() {
+        let created = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&created);
+        let pool = DynamicObjectPool::new(
+            move || counter.fetch_add(1, Ordering::SeqCst),
+            PoolConfiguration::new().with_max_pool_size(2),
+        );
+        pool.warmup(2).unwrap();
+        pool.invalidate_all();
+
+        let fresh = pool.get_object().unwrap();
+        assert_eq!(*fresh, 2, "stale objects skipped, a new one created");
+        drop(fresh);
+        assert_eq!(*pool.get_object().unwrap(), 2, "objects of the new epoch are reused");
+        assert_eq!(pool.epoch(), 1);
+        let report = pool.check_consistency();
+        assert!(report.is_consistent(), "{report}");
+    }
+
+    #[test]
+    fn test_evict_expired_and_queries_skip_invalidated_objects() {
+        let pool = ObjectPool::new(vec![1, 2], PoolConfiguration::default());
+        pool.invalidate_all();
+        assert_eq!(pool.evict_expired(), 2);
+
+        let queryable = QueryableObjectPool::new(vec![1, 2], PoolConfiguration::default());
+        queryable.invalidate_all();
+        assert!(queryable.get_object(|_| true).is_err());
+        assert_eq!(queryable.available_count(), 0);
+    }
+
+    #[test]
+    fn test_update_factory_drains_objects_of_the_old_factory() {
+        let pool = DynamicObjectPool::new(|| "old", PoolConfiguration::new().with_max_pool_size(2));
+        pool.warmup(1).unwrap();
+        let held = pool.get_object().unwrap();
+
+        assert_eq!(pool.update_factory(|| "new"), 1);
+        drop(held);
+        assert_eq!(pool.available_count(), 0);
+        assert_eq!(*pool.get_object().unwrap(), "new");
+        assert_eq!(pool.get_metrics().active_objects, 0);
+    }
+
+    #[test]
+    fn test_creation_racing_update_factory_is_retired() {
+        use std::sync::mpsc;
+        use std::sync::Mutex;
+
+        let (started_tx, started_rx) = mpsc::channel();
+        let (finish_tx, finish_rx) = mpsc::channel::<()>();
+        let finish_rx = Mutex::new(finish_rx);
+        let pool = Arc::new(DynamicObjectPool::new(
+            move || {
+                started_tx.send(()).unwrap();
+                finish_rx.lock().unwrap().recv().unwrap();
+                "old"
+            },
+            PoolConfiguration::default(),
+        ));
+
+        let p = Arc::clone(&pool);
+        let creating = std::thread::spawn(move || p.get_object().map(drop));
+        started_rx.recv().unwrap();
+        pool.update_factory(|| "new");
+        finish_tx.send(()).unwrap();
+
+        creating.join().unwrap().unwrap();
+        assert_eq!(pool.available_count(), 0, "the old factory's object was not kept");
+    }
+
+    #[test]
+    fn test_try_update_factory_reports_the_new_factorys_errors() {
+        let pool = DynamicObjectPool::new(|| 1, PoolConfiguration::default());
+        drop(pool.get_object().unwrap());
+
+        pool.try_update_factory(|| Err::<i32, _>("endpoint unreachable"));
+        match pool.get_object() {
+            Err(PoolError::CreationFailed(reason)) => assert_eq!(reason, "endpoint unreachable"),
+            other => panic!("unexpected {other:?}"),
+        }
+        assert_eq!(pool.available_count(), 0, "the old object was retired");
+    }
+
+    #[test]
+    fn test_replace_factory_keeps_existing_objects() {
+        let pool = DynamicObjectPool::new(|| 1, PoolConfiguration::new().with_max_pool_size(2));
+        let held = pool.get_object().unwrap();
+        pool.replace_factory(|| 2);
+
+        assert_eq!(*pool.get_object().unwrap(), 2);
+        drop(held);
+        assert_eq!(pool.available_count(), 2);
+    }
+
+    #[test]
+    fn test_keyed_update_factory_retires_objects_for_every_key() {
+        let pool = DynamicQueryablePool::new(
+            |k: &u32| (*k, "old"),
+            |o: &(u32, &str), k: &u32| o.0 == *k,
+            PoolConfiguration::default(),
+        );
+        let held = pool.get_object(&1).unwrap();
+        drop(pool.get_object(&2).unwrap());
+
+        assert_eq!(pool.update_factory(|k: &u32| (*k, "new")), 1);
+        drop(held);
+        assert_eq!(pool.get_object(&1).unwrap().1, "new");
+        assert_eq!(pool.get_object(&2).unwrap().1, "new");
+    }
+
+    // ── on_return hook ────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_on_return_runs_on_both_return_paths() {
+        let pool = ObjectPool::new(vec![1, 2], PoolConfiguration::new().with_on_return(|x: &mut i32| *x = 0));
+        drop(pool.get_object().unwrap());
+        pool.return_many([pool.get_object().unwrap(), pool.get_object().unwrap()]);
+
+        assert_eq!(*pool.get_object().unwrap(), 0);
+        assert_eq!(*pool.get_object().unwrap(), 0);
+    }
+
+    #[test]
+    fn test_on_return_panic_discards_the_object() {
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new().with_on_return(|_: &mut i32| panic!("reset failed")),
+        );
+        let mut warnings = pool.warnings();
+
+        assert!(matches!(pool.get_object().unwrap().release(), Err(PoolError::ValidationFailed)));
+        assert_eq!(pool.available_count(), 0);
+        assert_eq!(pool.get_metrics().hook_panics, 1);
+        assert!(matches!(warnings.try_recv(), Ok(PoolWarning::HookPanicked { hook: "on_return", .. })));
+    }
+
+    // ── Retry with object replacement ─────────────────────────────────────────
+
+    #[test]
+    fn test_discard_destroys_instead_of_returning() {
+        let destroyed = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&destroyed);
+        let pool = ObjectPool::new(
+            vec![1, 2],
+            PoolConfiguration::new().with_on_destroy(move |_: i32| {
+                counter.fetch_add(1, Ordering::SeqCst);
+            }),
+        );
+
+        pool.get_object().unwrap().discard();
+        assert_eq!(destroyed.load(Ordering::SeqCst), 1);
+        assert_eq!(pool.active_count(), 0);
+        assert_eq!(pool.available_count(), 1);
+        let metrics = pool.get_metrics();
+        assert_eq!((metrics.discarded_objects, metrics.total_returned), (1, 0));
+    }
+
+    #[test]
+    fn test_retry_replaces_broken_objects_in_dynamic_pool() {
+        let created = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&created);
+        let pool = DynamicObjectPool::new(
+            move || counter.fetch_add(1, Ordering::SeqCst),
+            PoolConfiguration::new().with_max_pool_size(1),
+        );
+        let policy = RetryPolicy::new(5, |err: &&str| *err == "broken");
+
+        // Objects 0 and 1 are broken; 2 works.
+        let result = pool.execute_with_retry(&policy, |id| if *id < 2 { Err("broken") } else { Ok(*id) });
+        assert_eq!(result.unwrap(), 2);
+        assert_eq!(created.load(Ordering::SeqCst), 3);
+        assert_eq!(pool.get_metrics().discarded_objects, 2);
+        assert_eq!(pool.available_count(), 1);
+    }
+
+    #[test]
+    fn test_retry_gives_up_after_max_attempts() {
+        let pool = DynamicObjectPool::new(|| 0, PoolConfiguration::default());
+        let policy = RetryPolicy::new(3, |_: &&str| true);
+        let mut attempts = 0;
+
+        let result: Result<(), _> = pool.execute_with_retry(&policy, |_| {
+            attempts += 1;
+            Err("broken")
+        });
+        assert!(matches!(result, Err(GuardedError::Operation("broken"))));
+        assert_eq!(attempts, 3);
+        assert_eq!(pool.get_metrics().discarded_objects, 3);
+    }
+
+    #[test]
+    fn test_retry_keeps_object_on_other_errors() {
+        let pool = ObjectPool::new(vec![1], PoolConfiguration::default());
+        let policy = RetryPolicy::new(3, |err: &&str| *err == "broken");
+        let mut attempts = 0;
+
+        let result: Result<(), _> = pool.execute_with_retry(&policy, |_| {
+            attempts += 1;
+            Err("bad request")
+        });
+        assert!(matches!(result, Err(GuardedError::Operation("bad request"))));
+        assert_eq!(attempts, 1);
+        assert_eq!(pool.available_count(), 1);
+    }
+
+    #[test]
+    fn test_retry_fails_when_fixed_pool_runs_out() {
+        let pool = ObjectPool::new(
+            vec![1, 2],
+            PoolConfiguration::new().with_wait_on_empty(WaitPolicy::FailFast),
+        );
+        let policy = RetryPolicy::new(5, |_: &&str| true);
+
+        let result: Result<(), _> = pool.execute_with_retry(&policy, |_| Err("broken"));
+        assert!(matches!(result, Err(GuardedError::Pool(PoolError::PoolEmpty))));
+        assert_eq!(pool.get_metrics().discarded_objects, 2);
+    }
+
+    // ── Completion outcomes ───────────────────────────────────────────────────
+
+    #[test]
+    fn test_complete_passes_result_through_and_returns_object() {
+        let pool = ObjectPool::new(vec![1], PoolConfiguration::default());
+        let obj = pool.get_object().unwrap();
+
+        assert_eq!(obj.complete(Ok::<_, ()>(5)), Ok(5));
+        assert_eq!(pool.available_count(), 1);
+        assert_eq!(pool.get_metrics().total_returned, 1);
+    }
+
+    #[test]
+    fn test_complete_err_validates_by_default() {
+        let pool = ObjectPool::new(
+            vec![-1, 1],
+            PoolConfiguration::new()
+                .with_validation(|x: &i32| *x > 0)
+                .with_wait_on_empty(WaitPolicy::FailFast),
+        );
+        let a = pool.get_object().unwrap();
+        let b = pool.get_object().unwrap();
+        let _ = a.complete(Err::<(), _>("failed"));
+        let _ = b.complete(Err::<(), _>("failed"));
+
+        assert_eq!(pool.available_count(), 1);
+        assert_eq!(pool.get_metrics().validation_failures, 1);
+    }
+
+    #[test]
+    fn test_complete_err_validates_even_without_validate_on_return() {
+        let mut config = PoolConfiguration::new().with_validation(|x: &i32| *x > 0);
+        config.validate_on_return = false;
+        let pool = ObjectPool::new(vec![1], config);
+
+        let mut obj = pool.get_object().unwrap();
+        *obj = -1;
+        drop(obj);
+        assert_eq!(pool.available_count(), 1, "plain returns are not validated");
+
+        let mut obj = pool.get_object().unwrap();
+        *obj = -1;
+        let _ = obj.complete(Err::<(), _>("failed"));
+        assert_eq!(pool.available_count(), 0);
+        assert_eq!(pool.get_metrics().validation_failures, 1);
+    }
+
+    #[test]
+    fn test_complete_err_with_return_policy_keeps_object() {
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new()
+                .with_validation(|_: &i32| false)
+                .with_failure_policy(FailurePolicy::Return),
+        );
+        let _ = pool.get_object().unwrap().complete(Err::<(), _>("failed"));
+        assert_eq!(pool.available_count(), 0, "validate_on_return still applies");
+
+        let pool = DynamicObjectPool::new(|| 1, PoolConfiguration::new().with_failure_policy(FailurePolicy::Return));
+        let _ = pool.get_object().unwrap().complete(Err::<(), _>("failed"));
+        assert_eq!(pool.available_count(), 1);
+    }
+
+    #[test]
+    fn test_complete_err_with_discard_policy_destroys_object() {
+        let pool = DynamicObjectPool::new(|| 1, PoolConfiguration::new().with_failure_policy(FailurePolicy::Discard));
+        let _ = pool.get_object().unwrap().complete(Err::<(), _>("failed"));
+        assert_eq!(pool.available_count(), 0);
+        assert_eq!(pool.get_metrics().discarded_objects, 1);
+
+        let _ = pool.get_object().unwrap().complete(Ok::<_, ()>(()));
+        assert_eq!(pool.available_count(), 1);
+    }
+
+    #[test]
+    fn test_complete_feeds_the_breaker_by_signal_setting() {
+        let config = || PoolConfiguration::new().with_circuit_breaker(2, Duration::from_secs(60));
+        let pool = ObjectPool::new(
+            vec![1],
+            config().with_breaker_signals(crate::BreakerSignals::Operations),
+        );
+        for _ in 0..2 {
+            let _ = pool.get_object().unwrap().complete(Err::<(), _>("failed"));
+        }
+        assert!(matches!(pool.get_object(), Err(PoolError::CircuitBreakerOpen)));
+
+        let pool = ObjectPool::new(
+            vec![1],
+            config().with_breaker_signals(crate::BreakerSignals::Acquisition),
+        );
+        for _ in 0..2 {
+            let _ = pool.get_object().unwrap().complete(Err::<(), _>("failed"));
+        }
+        assert!(pool.get_object().is_ok());
+    }
+
+    // ── Cancellation ──────────────────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn test_select_losing_acquisition_leaves_no_waiter() {
+        use futures::FutureExt;
+
+        let pool = ObjectPool::new(vec![1], PoolConfiguration::default());
+        let held = pool.get_object().unwrap();
+        {
+            let acquire = pool.get_object_async().fuse();
+            let timer = tokio::time::sleep(Duration::from_millis(30)).fuse();
+            futures::pin_mut!(acquire, timer);
+            futures::select! {
+                _ = acquire => panic!("no object should be available"),
+                () = timer => {}
+            }
+            assert_eq!(pool.waiting_count(), 1, "still pending until dropped");
+        }
+        assert_eq!(pool.waiting_count(), 0);
+
+        drop(held);
+        assert_eq!(pool.available_count(), 1);
+        assert_eq!(pool.active_count(), 0);
+        assert!(pool.get_object_async().await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_aborted_waiters_do_not_leak_objects() {
+        let pool = Arc::new(DynamicObjectPool::new(
+            || 0,
+            PoolConfiguration::new()
+                .with_max_pool_size(2)
+                .with_wait_on_empty(WaitPolicy::WaitForever),
+        ));
+        let held: Vec<_> = (0..2).map(|_| pool.get_object().unwrap()).collect();
+
+        let waiters: Vec<_> = (0..8)
+            .map(|_| {
+                let pool = Arc::clone(&pool);
+                tokio::spawn(async move { pool.get_object_async().await.map(drop) })
+            })
+            .collect();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(pool.waiting_count(), 8);
+
+        for waiter in &waiters {
+            waiter.abort();
+        }
+        drop(held);
+        for waiter in waiters {
+            assert!(waiter.await.unwrap_err().is_cancelled());
+        }
+
+        assert_eq!(pool.waiting_count(), 0);
+        assert_eq!(pool.active_count(), 0);
+        assert_eq!(pool.available_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_waiter_does_not_swallow_a_release() {
+        let pool = Arc::new(ObjectPool::new(vec![1], PoolConfiguration::default()));
+        let held = pool.get_object().unwrap();
+
+        let cancelled = tokio::spawn({
+            let pool = Arc::clone(&pool);
+            async move { pool.get_object_async().await.map(drop) }
+        });
+        let survivor = tokio::spawn({
+            let pool = Arc::clone(&pool);
+            async move { pool.get_object_async().await.map(|obj| *obj) }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        cancelled.abort();
+        drop(held);
+        let got = tokio::time::timeout(Duration::from_secs(1), survivor).await.unwrap().unwrap();
+        assert_eq!(got.unwrap(), 1);
+    }
+
+    #[test]
+    fn test_blocking_waiters_are_counted() {
+        let pool = Arc::new(ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new().with_wait_on_empty(WaitPolicy::Wait(Duration::from_millis(200))),
+        ));
+        let held = pool.get_object().unwrap();
+        let waiter = std::thread::spawn({
+            let pool = Arc::clone(&pool);
+            move || pool.get_object().map(drop)
+        });
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(pool.waiting_count(), 1);
+
+        drop(held);
+        waiter.join().unwrap().unwrap();
+        assert_eq!(pool.waiting_count(), 0);
+    }
+
+    // ── Failure diagnosis ─────────────────────────────────────────────────────
+
+    #[test]
+    fn test_explain_last_failure_empty_pool() {
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new()
+                .with_max_pool_size(1)
+                .with_wait_on_empty(WaitPolicy::FailFast),
+        );
+        assert!(pool.explain_last_failure().is_none());
+
+        let _held = pool.get_object().unwrap();
+        assert!(pool.get_object().is_err());
+
+        let diagnosis = pool.explain_last_failure().unwrap();
+        assert!(matches!(diagnosis.error, PoolError::PoolEmpty));
+        assert_eq!(diagnosis.gates, [Gate::Empty]);
+        assert_eq!((diagnosis.available, diagnosis.active), (0, 1));
+        assert_eq!((diagnosis.population, diagnosis.capacity), (1, 1));
+        assert!(diagnosis.breaker.is_none());
+    }
+
+    #[test]
+    fn test_explain_last_failure_dynamic_pool_at_capacity() {
+        let pool = DynamicObjectPool::new(
+            || 0,
+            PoolConfiguration::new()
+                .with_max_pool_size(2)
+                .with_wait_on_empty(WaitPolicy::FailFast),
+        );
+        let _a = pool.get_object().unwrap();
+        let _b = pool.get_object().unwrap();
+        assert!(matches!(pool.get_object(), Err(PoolError::PoolFull)));
+
+        let diagnosis = pool.explain_last_failure().unwrap();
+        assert_eq!(diagnosis.gates, [Gate::Empty, Gate::AtCapacity]);
+        assert_eq!((diagnosis.population, diagnosis.capacity), (2, 2));
+        assert!(diagnosis.to_string().contains("at capacity"));
+    }
+
+    #[test]
+    fn test_explain_last_failure_reports_eviction_churn() {
+        let pool = ObjectPool::new(
+            vec![1, 2],
+            PoolConfiguration::new()
+                .with_ttl(Duration::from_millis(1))
+                .with_wait_on_empty(WaitPolicy::FailFast),
+        );
+        std::thread::sleep(Duration::from_millis(10));
+        assert!(pool.get_object().is_err());
+
+        let diagnosis = pool.explain_last_failure().unwrap();
+        assert_eq!(diagnosis.gates, [Gate::Empty, Gate::EvictionChurn]);
+        assert_eq!(diagnosis.expired_since_last_acquisition, 2);
+        assert_eq!(diagnosis.population, 0);
+    }
+
+    #[test]
+    fn test_explain_last_failure_after_timeout_names_the_gate() {
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new()
+                .with_wait_on_empty(WaitPolicy::Wait(Duration::from_millis(20))),
+        );
+        let _held = pool.get_object().unwrap();
+        assert!(matches!(pool.get_object(), Err(PoolError::Timeout(..))));
+
+        let diagnosis = pool.explain_last_failure().unwrap();
+        assert!(matches!(diagnosis.error, PoolError::PoolEmpty));
+        assert_eq!(diagnosis.gates, [Gate::Empty]);
+        assert_eq!(diagnosis.waiting, 1, "counted while it was waiting");
+    }
+
+    #[test]
+    fn test_explain_last_failure_includes_breaker_state() {
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new()
+                .with_circuit_breaker(1, Duration::from_secs(60))
+                .with_wait_on_empty(WaitPolicy::FailFast),
+        );
+        let _held = pool.get_object().unwrap();
+        assert!(pool.get_object().is_err());
+        assert!(matches!(pool.get_object(), Err(PoolError::CircuitBreakerOpen)));
+
+        let diagnosis = pool.explain_last_failure().unwrap();
+        assert_eq!(diagnosis.gates, [Gate::CircuitBreakerOpen]);
+        assert_eq!(diagnosis.breaker, Some(CircuitBreakerState::Open));
+    }
+
+    // ── Object metadata ───────────────────────────────────────────────────────
+
+    #[test]
+    fn test_meta_follows_the_object_between_checkouts() {
+        let pool = ObjectPool::new(vec![1, 2], PoolConfiguration::default());
+        let a = pool.get_object().unwrap();
+        let b = pool.get_object().unwrap();
+        a.set_meta(String::from("tenant-a"));
+        a.set_meta(7u32);
+        let a_id = a.object_id;
+        drop((a, b));
+
+        let first = pool.get_object().unwrap();
+        let second = pool.get_object().unwrap();
+        let (a, b) = if first.object_id == a_id { (first, second) } else { (second, first) };
+        assert_eq!(a.meta::<String>().as_deref(), Some("tenant-a"));
+        assert_eq!(a.meta::<u32>(), Some(7));
+        assert_eq!(b.meta::<String>(), None);
+
+        assert_eq!(a.take_meta::<u32>(), Some(7));
+        assert_eq!(a.meta::<u32>(), None);
+    }
+
+    #[test]
+    fn test_meta_is_dropped_with_the_object() {
+        let pool = DynamicObjectPool::new(|| 0, PoolConfiguration::default());
+        let discarded = pool.get_object().unwrap();
+        let detached = pool.get_object().unwrap();
+        let kept = pool.get_object().unwrap();
+        for obj in [&discarded, &detached, &kept] {
+            obj.set_meta(1u8);
+        }
+        assert_eq!(pool.inner.eviction.user_meta().len(), 3);
+
+        discarded.discard();
+        let _ = detached.into_detached();
+        drop(kept);
+        assert_eq!(pool.inner.eviction.user_meta().len(), 1);
+
+        pool.inner.shutdown();
+        assert_eq!(pool.inner.eviction.user_meta().len(), 0);
+    }
+
+    // ── Eviction introspection ────────────────────────────────────────────────
+
+    #[test]
+    fn test_eviction_policy_reflects_configuration() {
+        let none = ObjectPool::new(vec![1], PoolConfiguration::default());
+        assert!(matches!(none.eviction_policy(), EvictionPolicy::None));
+
+        let combined = DynamicObjectPool::new(
+            || 0,
+            PoolConfiguration::new()
+                .with_ttl(Duration::from_secs(60))
+                .with_idle_timeout(Duration::from_secs(5)),
+        );
+        assert!(matches!(
+            combined.eviction_policy(),
+            EvictionPolicy::Combined { ttl, idle_timeout }
+                if *ttl == Duration::from_secs(60) && *idle_timeout == Duration::from_secs(5)
+        ));
+    }
+
+    #[test]
+    fn test_remaining_ttl_and_idle_count_down() {
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new()
+                .with_ttl(Duration::from_secs(60))
+                .with_idle_timeout(Duration::from_secs(10)),
+        );
+        let obj = pool.get_object().unwrap();
+        std::thread::sleep(Duration::from_millis(50));
+        let ttl = obj.remaining_ttl().unwrap();
+        let idle = obj.idle_remaining().unwrap();
+        assert!(ttl < Duration::from_secs(60) && ttl > Duration::from_secs(59));
+        assert!(idle <= Duration::from_millis(9950) && idle > Duration::from_secs(9));
+
+        drop(obj);
+        let obj = pool.get_object().unwrap();
+        assert!(obj.idle_remaining().unwrap() > idle, "restarted by the return");
+        assert!(obj.remaining_ttl().unwrap() < ttl);
+    }
+
+    #[test]
+    fn test_remaining_ttl_without_eviction_and_after_invalidation() {
+        let plain = ObjectPool::new(vec![1], PoolConfiguration::default());
+        let obj = plain.get_object().unwrap();
+        assert_eq!((obj.remaining_ttl(), obj.idle_remaining()), (None, None));
+
+        let pool = ObjectPool::new(vec![1], PoolConfiguration::new().with_ttl(Duration::from_secs(60)));
+        let obj = pool.get_object().unwrap();
+        pool.invalidate_all();
+        assert_eq!(obj.remaining_ttl(), Some(Duration::ZERO));
+        assert_eq!(obj.idle_remaining(), None);
+    }
+
+    // ── TTL refresh ───────────────────────────────────────────────────────────
+
+    fn refreshing_pool(refresh: TtlRefresh) -> ObjectPool<i32> {
+        ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new()
+                .with_ttl(Duration::from_millis(150))
+                .with_validation(|x: &i32| *x > 0)
+                .with_ttl_refresh(refresh),
+        )
+    }
+
+    #[test]
+    fn test_validated_return_refreshes_ttl() {
+        let pool = refreshing_pool(TtlRefresh::Reset);
+        for _ in 0..4 {
+            let obj = pool.get_object().unwrap();
+            std::thread::sleep(Duration::from_millis(50));
+            drop(obj);
+        }
+        let obj = pool.get_object().unwrap();
+        assert!(obj.remaining_ttl().unwrap() > Duration::from_millis(50));
+        assert_eq!(pool.get_metrics().total_retrieved, 5);
+    }
+
+    #[test]
+    fn test_failed_validation_still_expires() {
+        let pool = refreshing_pool(TtlRefresh::Reset);
+        let mut obj = pool.get_object().unwrap();
+        *obj = -1;
+        drop(obj);
+        assert_eq!(pool.available_count(), 0);
+
+        let pool = refreshing_pool(TtlRefresh::Never);
+        let obj = pool.get_object().unwrap();
+        std::thread::sleep(Duration::from_millis(100));
+        drop(obj);
+        std::thread::sleep(Duration::from_millis(60));
+        assert_eq!(pool.verify().expired.len(), 1, "no refresh without the option");
+    }
+
+    #[test]
+    fn test_verify_refreshes_only_with_a_validation_function() {
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new()
+                .with_ttl(Duration::from_millis(100))
+                .with_ttl_refresh(TtlRefresh::Reset),
+        );
+        std::thread::sleep(Duration::from_millis(60));
+        assert_eq!(pool.verify().passed, 1);
+        std::thread::sleep(Duration::from_millis(60));
+        assert_eq!(pool.verify().expired.len(), 1);
+    }
+
+    // ── Custom eviction ───────────────────────────────────────────────────────
+
+    #[test]
+    fn test_custom_eviction_counts_uses() {
+        let created = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&created);
+        let pool = DynamicObjectPool::new(
+            move || counter.fetch_add(1, Ordering::Relaxed),
+            PoolConfiguration::new()
+                .with_custom_eviction(|stats| stats.uses >= 3)
+                .with_ttl(Duration::from_nanos(1)),
+        );
+        assert!(matches!(pool.eviction_policy(), EvictionPolicy::Custom(_)));
+
+        for _ in 0..3 {
+            drop(pool.get_object().unwrap());
+        }
+        assert_eq!(created.load(Ordering::Relaxed), 1, "the TTL is replaced");
+        assert_eq!(pool.available_count(), 0, "evicted as it was returned");
+        assert_eq!(pool.evict_expired(), 0);
+
+        drop(pool.get_object().unwrap());
+        assert_eq!(created.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn test_custom_eviction_in_queryable_pool() {
+        let pool = QueryableObjectPool::new(
+            vec![1, 2],
+            PoolConfiguration::new().with_custom_eviction(|stats| stats.uses >= 1),
+        );
+        drop(pool.get_object(|x| *x == 1).unwrap());
+        assert_eq!(pool.evict_expired(), 0, "evicted as it was returned");
+        assert!(pool.get_object(|x| *x == 1).is_err());
+        assert!(pool.get_object(|x| *x == 2).is_ok());
+    }
+
+    // ── Expired returns ───────────────────────────────────────────────────────
+
+    /// Returns objects that expired while checked out, while another thread
+    /// sweeps the pool. The test has no clock to inject, so objects age
+    /// past a short time to live.
+    fn return_expired_during_sweep(expired_return: ExpiredReturn) -> (ObjectPool<usize>, usize) {
+        let pool = Arc::new(ObjectPool::new(
+            (0..8).collect(),
+            PoolConfiguration::new()
+                .with_max_pool_size(8)
+                .with_ttl(Duration::from_millis(200))
+                .with_expired_return(expired_return),
+        ));
+        let held: Vec<_> = (0..8).map(|_| pool.get_object().unwrap()).collect();
+        std::thread::sleep(Duration::from_millis(250));
+
+        let done = Arc::new(AtomicBool::new(false));
+        let sweeper = {
+            let (pool, done) = (Arc::clone(&pool), Arc::clone(&done));
+            std::thread::spawn(move || {
+                let mut evicted = 0;
+                while !done.load(Ordering::Acquire) {
+                    evicted += pool.evict_expired();
+                    std::thread::yield_now();
+                }
+                evicted
+            })
+        };
+        for obj in held {
+            drop(obj);
+            std::thread::yield_now();
+        }
+        done.store(true, Ordering::Release);
+        let swept = sweeper.join().unwrap();
+        let pool = Arc::try_unwrap(pool).unwrap_or_else(|_| panic!("sweeper still holds the pool"));
+        (pool, swept)
+    }
+
+    #[test]
+    fn test_expired_return_is_evicted_regardless_of_sweeps() {
+        let (pool, swept) = return_expired_during_sweep(ExpiredReturn::Evict);
+        assert_eq!(swept, 0, "never pooled for the sweeper to find");
+        assert_eq!(pool.available_count(), 0);
+        assert_eq!(pool.get_metrics().total_returned, 0);
+        let report = pool.check_consistency();
+        assert!(report.is_consistent(), "{report}");
+        assert_eq!(report.population, 0);
+    }
+
+    #[test]
+    fn test_expired_return_refreshes_regardless_of_sweeps() {
+        let (pool, swept) = return_expired_during_sweep(ExpiredReturn::Refresh);
+        assert_eq!(swept, 0, "returned objects start a fresh lifetime");
+        assert_eq!(pool.available_count(), 8);
+        assert_eq!(pool.get_metrics().total_returned, 8);
+        let obj = pool.get_object().unwrap();
+        assert!(obj.remaining_ttl().unwrap() > Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_expired_return_refresh_restarts_uses() {
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new()
+                .with_custom_eviction(|stats| stats.uses >= 2)
+                .with_expired_return(ExpiredReturn::Refresh),
+        );
+        for _ in 0..5 {
+            drop(pool.get_object().unwrap());
+        }
+        assert_eq!(pool.evict_expired(), 0);
+        assert_eq!(pool.get_metrics().total_retrieved, 5);
+    }
+
+    #[test]
+    fn test_expired_return_is_reported_as_an_eviction() {
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new()
+                .with_custom_eviction(|stats| stats.uses >= 1)
+                .with_activity_log(4),
+        );
+        let mut events = pool.events();
+        drop(pool.get_object().unwrap());
+        assert!(matches!(events.try_recv(), Ok(PoolEvent::Evicted { .. })));
+        assert!(matches!(pool.recent_activity().last().unwrap().outcome, ActivityOutcome::Evicted));
+    }
+
+    // ── Tuning advice ─────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_tuning_advice_grows_a_pool_that_runs_dry() {
+        let pool = ObjectPool::new(
+            vec![1, 2],
+            PoolConfiguration::new().with_wait_on_empty(WaitPolicy::FailFast),
+        );
+        for _ in 0..25 {
+            let _a = pool.get_object().unwrap();
+            let _b = pool.get_object().unwrap();
+            assert!(pool.get_object().is_err());
+        }
+
+        let advice = pool.tuning_advice().unwrap();
+        assert_eq!(advice.samples, 50);
+        assert_eq!((advice.peak_active, advice.peak_utilization), (2, 1.0));
+        assert!((advice.empty_ratio - 0.5).abs() < f64::EPSILON);
+        assert_eq!(advice.suggested_max_pool_size, 3);
+        assert_eq!(advice.suggested_min_idle, 1);
+        assert!(advice.hold_p50 > Duration::ZERO);
+    }
+
+    #[test]
+    fn test_tuning_advice_for_dynamic_pool() {
+        let pool = DynamicObjectPool::new(|| 0, PoolConfiguration::new().with_max_pool_size(40));
+        for _ in 0..30 {
+            drop(pool.get_object().unwrap());
+        }
+        let advice = pool.tuning_advice().unwrap();
+        assert_eq!(advice.peak_active, 1);
+        assert_eq!(advice.suggested_max_pool_size, 2);
+    }
+
+    // ── Pool events ───────────────────────────────────────────────────────────
+
+    fn drain(events: &mut tokio::sync::broadcast::Receiver<PoolEvent>) -> Vec<PoolEvent> {
+        std::iter::from_fn(|| events.try_recv().ok()).collect()
+    }
+
+    #[test]
+    fn test_events_report_evictions_and_empty_pool() {
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new().with_ttl(Duration::from_millis(10)),
+        );
+        let mut events = pool.events();
+        drop(pool.get_object().unwrap());
+
+        std::thread::sleep(Duration::from_millis(30));
+        assert!(pool.try_get_object().unwrap().is_none());
+
+        let events = drain(&mut events);
+        assert_eq!(events.len(), 2);
+        assert!(matches!(events[0], PoolEvent::Evicted { .. }));
+        assert_eq!(events[1], PoolEvent::Empty);
+    }
+
+    #[test]
+    fn test_events_report_breaker_transitions() {
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new().with_circuit_breaker(1, Duration::from_millis(20)),
+        );
+        let mut events = pool.events();
+        let held = pool.get_object().unwrap();
+        assert!(pool.try_get_object().unwrap().is_none());
+        assert!(matches!(pool.try_get_object(), Err(PoolError::CircuitBreakerOpen)));
+        drop(held);
+
+        std::thread::sleep(Duration::from_millis(40));
+        for _ in 0..3 {
+            drop(pool.get_object().unwrap());
+        }
+
+        assert_eq!(
+            drain(&mut events),
+            [
+                PoolEvent::Empty,
+                PoolEvent::CircuitOpened,
+                PoolEvent::CircuitHalfOpen,
+                PoolEvent::CircuitClosed,
+            ]
+        );
+    }
+
+    // ── Sampling ──────────────────────────────────────────────────────────────
+
     #[test]
-    fn test_max_active_objects() {
-        let config = PoolConfiguration::new()
-            .with_max_active_objects(2);
-        
-        let pool = ObjectPool::new(vec![1, 2, 3, 4, 5], config);
-        
-        let _obj1 = pool.get_object().unwrap();
-        let _obj2 = pool.get_object().unwrap();
-        
-        let result = pool.get_object();
-        assert!(result.is_err());
-        if let Err(e) = result {
-            assert!(matches!(e, PoolError::MaxActiveObjectsReached));
+    fn test_sampling_thins_histograms_but_keeps_counters_exact() {
+        let pool = ObjectPool::new(vec![1, 2], PoolConfiguration::new().with_sampling(4));
+        for _ in 0..20 {
+            drop(pool.get_object().unwrap());
         }
+
+        assert_eq!(pool.metrics.wait_times.count(), 5);
+        assert_eq!(pool.metrics.hold_times.count(), 5);
+        let metrics = pool.get_metrics();
+        assert_eq!((metrics.total_retrieved, metrics.total_returned), (20, 20));
+        assert!(pool.tuning_advice().is_none(), "5 samples are too few to advise on");
     }
-    
+
     #[test]
-    fn test_validation_on_return() {
-        let config = PoolConfiguration::new()
-            .with_validation(|x| *x > 0);
-        
-        let pool = ObjectPool::new(vec![1, 2, 3], config);
-        
-        {
-            let obj = pool.get_object().unwrap();
-            assert_eq!(*obj, 1);
+    fn test_sampling_thins_evictions_and_empty_events() {
+        let pool = ObjectPool::new(
+            vec![1, 2, 3, 4],
+            PoolConfiguration::new()
+                .with_ttl(Duration::from_millis(10))
+                .with_sampling(3),
+        );
+        let mut events = pool.events();
+        std::thread::sleep(Duration::from_millis(30));
+        for _ in 0..5 {
+            assert!(pool.try_get_object().unwrap().is_none());
         }
-        
-        assert_eq!(pool.available_count(), 3);
+
+        let events = drain(&mut events);
+        let evicted = events.iter().filter(|e| matches!(e, PoolEvent::Evicted { .. })).count();
+        assert_eq!((evicted, events.len()), (2, 3));
+        assert_eq!(pool.get_metrics().pool_empty_events, 5);
     }
-    
+
     #[test]
-    fn test_metrics_tracking() {
-        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
-        
-        {
-            let _obj1 = pool.get_object().unwrap();
-            let _obj2 = pool.get_object().unwrap();
-            
-            let metrics = pool.get_metrics();
-            assert_eq!(metrics.total_retrieved, 2);
-            assert_eq!(metrics.active_objects, 2);
-            assert_eq!(metrics.available_objects, 1);
+    fn test_sampling_of_zero_keeps_everything() {
+        let pool = ObjectPool::new(vec![1], PoolConfiguration::new().with_sampling(0));
+        for _ in 0..3 {
+            drop(pool.get_object().unwrap());
         }
-        
+        assert_eq!(pool.metrics.wait_times.count(), 3);
+    }
+
+    // ── Capacity loss ─────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_fixed_pool_reports_capacity_lost() {
+        let pool = ObjectPool::new(
+            vec![1, 2, 3, 4],
+            PoolConfiguration::new().with_validation(|x: &i32| *x > 0),
+        );
+        let mut obj = pool.get_object().unwrap();
+        *obj = -1;
+        drop(obj);
+        pool.get_object().unwrap().discard();
+
         let metrics = pool.get_metrics();
-        assert_eq!(metrics.total_returned, 2);
-        assert_eq!(metrics.active_objects, 0);
-        assert_eq!(metrics.available_objects, 3);
+        assert_eq!((metrics.total_objects, metrics.capacity_lost), (2, 2));
+        assert!(pool.get_health_status().warnings.iter().all(|w| !w.contains("Capacity")));
+
+        let _ = pool.get_object().unwrap().into_detached();
+        let health = pool.get_health_status();
+        assert!(health.warnings.contains(&"Capacity lost: 1 of 4 objects left".to_string()));
+        assert!(health.is_healthy);
+        let prometheus = pool.export_metrics_prometheus("p", None);
+        assert!(prometheus.contains("objectpool_objects_total{pool=\"p\"} 1"));
+        assert!(prometheus.contains("objectpool_capacity_lost{pool=\"p\"} 3"));
     }
-    
+
     #[test]
-    fn test_health_status() {
-        let config = PoolConfiguration::new()
-            .with_max_pool_size(5);
-        
-        let pool = ObjectPool::new(vec![1, 2, 3, 4, 5], config);
-        
-        {
-            let _obj1 = pool.get_object().unwrap();
-            let _obj2 = pool.get_object().unwrap();
-            
-            let health = pool.get_health_status();
-            assert!(health.is_healthy);
-            assert_eq!(health.active_objects, 2);
-            assert_eq!(health.available_objects, 3);
-            assert_eq!(health.total_capacity, 5);
+    fn test_capacity_loss_warning_can_be_disabled() {
+        let pool = ObjectPool::new(vec![1, 2], PoolConfiguration::new().with_capacity_loss_warning(0.0));
+        pool.get_object().unwrap().discard();
+        pool.get_object().unwrap().discard();
+
+        assert_eq!(pool.get_metrics().capacity_lost, 2);
+        assert!(pool.get_health_status().warnings.iter().all(|w| !w.contains("Capacity")));
+    }
+
+    #[test]
+    fn test_dynamic_pool_never_reports_capacity_lost() {
+        let pool = DynamicObjectPool::with_initial(|| 0, vec![1, 2, 3, 4], PoolConfiguration::new());
+        for _ in 0..4 {
+            pool.get_object().unwrap().discard();
         }
+
+        let metrics = pool.get_metrics();
+        assert_eq!((metrics.total_objects, metrics.capacity_lost), (0, 0));
+        assert!(pool.get_health_status().warnings.iter().all(|w| !w.contains("Capacity")));
     }
-    
+
+    // ── Query metrics ─────────────────────────────────────────────────────────
+
     #[test]
-    fn test_prometheus_export() {
-        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
-        
-        {
-            let _obj = pool.get_object().unwrap();
-            
-            let prometheus = pool.export_metrics_prometheus("test_pool", None);
-            
-            assert!(prometheus.contains("objectpool_objects_active"));
-            assert!(prometheus.contains("objectpool_objects_available"));
-            assert!(prometheus.contains("objectpool_utilization"));
-            assert!(prometheus.contains("test_pool"));
+    fn test_query_metrics_track_scans_and_matches() {
+        let pool = QueryableObjectPool::new((0..8).collect(), PoolConfiguration::default());
+        drop(pool.get_object(|x| *x == 0).unwrap());
+        drop(pool.get_object(|x| *x == 7).unwrap());
+        assert!(pool.try_get_object(|x| *x == 99).unwrap().is_none());
+
+        let queries = pool.query_metrics();
+        assert_eq!((queries.queries, queries.matches, queries.no_match_events), (3, 2, 1));
+        assert!((queries.match_rate - 2.0 / 3.0).abs() < 1e-9);
+        // 0 is found first; returned, it goes to the back of the queue, so
+        // finding 7 takes 7 steps and the miss examines all 8.
+        assert_eq!(queries.objects_scanned, 1 + 7 + 8);
+        assert_eq!(queries.scan_length_p50, 7);
+        assert_eq!(queries.scan_length_p99, 15);
+        assert_eq!(queries.scan_length_buckets, [(0, 0), (1, 1), (3, 1), (7, 2), (15, 3)]);
+        assert_eq!(queries.average_scan_length(), Some(16.0 / 3.0));
+    }
+
+    #[test]
+    fn test_query_metrics_are_exported() {
+        let pool = QueryableObjectPool::new(vec![1, 2], PoolConfiguration::default());
+        assert!(!pool.export_metrics_prometheus("q", None).contains("objectpool_queries_total"));
+
+        drop(pool.get_object(|x| *x == 2).unwrap());
+        let prometheus = pool.export_metrics_prometheus("q", None);
+        assert!(prometheus.contains("objectpool_queries_total{pool=\"q\"} 1"));
+        assert!(prometheus.contains("objectpool_query_no_match_total{pool=\"q\"} 0"));
+        assert!(prometheus.contains("# TYPE objectpool_query_scan_length histogram"));
+        assert!(prometheus.contains("objectpool_query_scan_length_bucket{pool=\"q\",le=\"3\"} 1"));
+        assert!(prometheus.contains("objectpool_query_scan_length_bucket{pool=\"q\",le=\"+Inf\"} 1"));
+        assert!(prometheus.contains("objectpool_query_scan_length_sum{pool=\"q\"} 2"));
+        assert_eq!(pool.export_metrics()["query_match_rate"], "1.00");
+    }
+
+    #[test]
+    fn test_keyed_pool_counts_misses_before_creating() {
+        let pool = DynamicQueryablePool::new(|k: &u32| *k, |obj, k| obj == k, PoolConfiguration::default());
+        drop(pool.get_object(&1).unwrap());
+        drop(pool.get_object(&1).unwrap());
+
+        let queries = pool.query_metrics();
+        assert_eq!((queries.queries, queries.matches), (2, 1));
+        assert_eq!(queries.objects_scanned, 1);
+    }
+
+    // ── Wait queue limit ──────────────────────────────────────────────────────
+
+    #[test]
+    fn test_full_wait_queue_rejects_immediately() {
+        let pool = Arc::new(ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new()
+                .with_wait_on_empty(WaitPolicy::Wait(Duration::from_secs(5)))
+                .with_max_waiters(1),
+        ));
+        let held = pool.get_object().unwrap();
+        let waiter = {
+            let pool = Arc::clone(&pool);
+            std::thread::spawn(move || pool.get_object().map(|obj| *obj))
+        };
+        while pool.waiting_count() == 0 {
+            std::thread::yield_now();
         }
+
+        let started = Instant::now();
+        assert!(matches!(pool.get_object(), Err(PoolError::QueueFull)));
+        assert!(started.elapsed() < Duration::from_secs(1));
+        assert_eq!(pool.explain_last_failure().unwrap().gates, [Gate::WaitQueueFull]);
+
+        drop(held);
+        assert_eq!(waiter.join().unwrap().unwrap(), 1);
+        assert_eq!(pool.get_metrics().waiter_rejections, 1);
     }
-    
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_full_wait_queue_rejects_async_acquisitions() {
+        let pool = Arc::new(ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new()
+                .with_wait_on_empty(WaitPolicy::Wait(Duration::from_secs(5)))
+                .with_max_waiters(1),
+        ));
+        let held = pool.get_object_async().await.unwrap();
+        let waiter = {
+            let pool = Arc::clone(&pool);
+            tokio::spawn(async move { pool.get_object_async().await.map(|obj| *obj) })
+        };
+        while pool.waiting_count() == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        assert!(matches!(pool.get_object_async().await, Err(PoolError::QueueFull)));
+        drop(held);
+        assert_eq!(waiter.await.unwrap().unwrap(), 1);
+    }
+
     #[test]
-    fn test_prometheus_with_tags() {
-        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
-        
-        let mut tags = HashMap::new();
-        tags.insert("env".to_string(), "test".to_string());
-        tags.insert("service".to_string(), "api".to_string());
-        
-        let prometheus = pool.export_metrics_prometheus("test_pool", Some(&tags));
-        
-        assert!(prometheus.contains("env=\"test\""));
-        assert!(prometheus.contains("service=\"api\""));
+    fn test_wait_queue_limit_ignores_acquisitions_that_do_not_wait() {
+        let pool = ObjectPool::new(
+            vec![1, 2],
+            PoolConfiguration::new()
+                .with_max_active_objects(1)
+                .with_max_waiters(0),
+        );
+        let _held = pool.get_object().unwrap();
+        assert!(matches!(pool.get_object(), Err(PoolError::MaxActiveObjectsReached)));
+
+        let fail_fast = ObjectPool::new(vec![1], PoolConfiguration::new().with_max_waiters(0));
+        let _held = fail_fast.get_object().unwrap();
+        assert!(matches!(fail_fast.get_object(), Err(PoolError::PoolEmpty)));
+        assert_eq!(fail_fast.get_metrics().waiter_rejections, 0);
+    }
+
+    // ── Timeout jitter ────────────────────────────────────────────────────────
+
+    #[tokio::test]
+    async fn test_timeout_jitter_varies_budget_per_acquisition() {
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new()
+                .with_timeout(Duration::from_millis(40))
+                .with_timeout_jitter(0.5),
+        );
+        let _held = pool.get_object().unwrap();
+
+        let results = futures::future::join_all((0..8).map(|_| pool.get_object_async())).await;
+        let budgets: Vec<Duration> = results
+            .into_iter()
+            .map(|result| match result {
+                Err(PoolError::Timeout(budget, _)) => budget,
+                other => panic!("expected a timeout, got {other:?}"),
+            })
+            .collect();
+
+        assert!(budgets.iter().all(|b| (20..=60).contains(&b.as_millis())));
+        assert!(budgets.iter().any(|b| *b != budgets[0]), "budgets should differ: {budgets:?}");
+    }
+
+    // ── Pause ─────────────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_paused_pool_holds_waiters_until_resumed() {
+        let pool = Arc::new(ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new().with_wait_on_empty(WaitPolicy::Wait(Duration::from_secs(5))),
+        ));
+        pool.pause();
+        let waiter = {
+            let pool = Arc::clone(&pool);
+            std::thread::spawn(move || pool.get_object().map(|obj| *obj))
+        };
+        while pool.waiting_count() == 0 {
+            std::thread::yield_now();
+        }
+        assert_eq!(pool.explain_last_failure().unwrap().gates, [Gate::Paused]);
+
+        pool.resume();
+        assert_eq!(waiter.join().unwrap().unwrap(), 1);
+        assert!(!pool.is_paused());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_resume_wakes_async_waiters() {
+        let pool = Arc::new(ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new().with_wait_on_empty(WaitPolicy::WaitForever),
+        ));
+        pool.pause();
+        let waiter = {
+            let pool = Arc::clone(&pool);
+            tokio::spawn(async move { pool.get_object_async().await.map(|obj| *obj) })
+        };
+        while pool.waiting_count() == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        pool.resume();
+        let result = tokio::time::timeout(Duration::from_secs(5), waiter).await;
+        assert_eq!(result.unwrap().unwrap().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_paused_pool_fails_fast_and_times_out() {
+        let pool = ObjectPool::new(vec![1, 2], PoolConfiguration::new());
+        pool.pause();
+        assert!(matches!(pool.get_object(), Err(PoolError::Paused)));
+        assert!(matches!(pool.try_get_object(), Err(PoolError::Paused)));
+        assert_eq!(pool.get_metrics().pool_empty_events, 0);
+
+        let waiting = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new().with_wait_on_empty(WaitPolicy::Wait(Duration::from_millis(20))),
+        );
+        waiting.pause();
+        assert!(matches!(waiting.get_object(), Err(PoolError::Timeout(..))));
+
+        let queryable = QueryableObjectPool::new(vec![1, 2], PoolConfiguration::new());
+        queryable.pause();
+        assert!(matches!(queryable.try_get_object(|_| true), Err(PoolError::Paused)));
+        queryable.resume();
+        assert!(queryable.try_get_object(|_| true).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_paused_pool_accepts_returns() {
+        let pool = ObjectPool::new(vec![1, 2], PoolConfiguration::new());
+        let held = pool.get_object().unwrap();
+        pool.pause();
+        assert!(held.release().is_ok());
+        assert_eq!(pool.available_count(), 2);
+        assert_eq!(pool.get_metrics().total_returned, 1);
+    }
+
+    #[test]
+    fn test_paused_dynamic_pool_creates_nothing() {
+        let pool = DynamicObjectPool::new(|| 7, PoolConfiguration::new().with_max_pool_size(2));
+        pool.pause();
+        assert!(pool.is_paused());
+        assert!(matches!(pool.get_object(), Err(PoolError::Paused)));
+        assert_eq!(pool.get_metrics().total_objects, 0);
+
+        pool.resume();
+        assert_eq!(*pool.get_object().unwrap(), 7);
+    }
+
+    #[test]
+    fn test_pause_is_reported_in_health_and_metrics() {
+        let pool = ObjectPool::new(vec![1], PoolConfiguration::new());
+        assert!(!pool.get_metrics().paused);
+        assert!(!pool.get_health_status().paused);
+
+        pool.pause();
+        let health = pool.get_health_status();
+        assert!(health.paused);
+        assert!(!health.is_healthy());
+        assert!(health.warnings.contains(&"Pool is paused".to_string()));
+        assert!(pool.get_metrics().paused);
+        assert_eq!(pool.export_metrics()["paused"], "true");
+        assert!(pool.export_metrics_prometheus("p", None).contains("objectpool_paused{pool=\"p\"} 1"));
+        assert!(matches!(pool.readiness(false), Readiness::Busy));
+
+        pool.resume();
+        assert!(pool.get_health_status().is_healthy());
+        assert!(matches!(pool.readiness(false), Readiness::Ready));
+    }
+
+    // ── Retirement ────────────────────────────────────────────────────────────
+
+    #[test]
+    fn test_retire_destroys_idle_objects_then_returned_ones() {
+        let destroyed = Arc::new(Mutex::new(Vec::new()));
+        let pool = ObjectPool::new(
+            vec![1, 2, 3, 4],
+            PoolConfiguration::new().with_on_destroy({
+                let destroyed = Arc::clone(&destroyed);
+                move |obj| destroyed.lock().unwrap().push(obj)
+            }),
+        );
+        let first = pool.get_object().unwrap();
+        let second = pool.get_object().unwrap();
+
+        assert_eq!(pool.retire(3), 2);
+        assert_eq!(*destroyed.lock().unwrap(), [3, 4]);
+        assert_eq!(pool.pending_retirements(), 1);
+
+        drop(first);
+        drop(second);
+        assert_eq!(*destroyed.lock().unwrap(), [3, 4, 1]);
+        assert_eq!(pool.pending_retirements(), 0);
+        assert_eq!(pool.available_count(), 1);
+        assert_eq!(*pool.get_object().unwrap(), 2);
+        assert_eq!(pool.get_metrics().retired_objects, 3);
+        let report = pool.check_consistency();
+        assert!(report.is_consistent(), "{report}");
+    }
+
+    #[test]
+    fn test_retire_takes_objects_returned_in_a_batch() {
+        let pool = ObjectPool::new(vec![1, 2, 3, 4], PoolConfiguration::new());
+        let batch = pool.get_batch(3).unwrap();
+
+        assert_eq!(pool.retire(3), 1);
+        assert_eq!(pool.pending_retirements(), 2);
+        drop(batch);
+
+        assert_eq!(pool.pending_retirements(), 0);
+        assert_eq!(pool.available_count(), 1);
+        assert_eq!(pool.active_count(), 0);
+        assert_eq!(pool.get_metrics().retired_objects, 3);
+        let report = pool.check_consistency();
+        assert!(report.is_consistent(), "{report}");
+    }
+
+    #[test]
+    fn test_retire_is_capped_at_the_pool_population() {
+        let pool = ObjectPool::new(vec![1, 2], PoolConfiguration::new());
+        let held = pool.get_object().unwrap();
+        assert_eq!(pool.retire(10), 1);
+        assert_eq!(pool.pending_retirements(), 1);
+        drop(held);
+        assert_eq!(pool.pending_retirements(), 0);
+        assert_eq!(pool.get_metrics().total_objects, 0);
+    }
+
+    #[test]
+    fn test_retired_objects_are_not_capacity_lost() {
+        let pool = ObjectPool::new(vec![1, 2, 3, 4], PoolConfiguration::new());
+        assert_eq!(pool.retire(3), 3);
+
+        let metrics = pool.get_metrics();
+        assert_eq!(metrics.capacity_lost, 0);
+        assert_eq!(metrics.total_objects, 1);
+        assert!(pool.get_health_status().warnings.iter().all(|w| !w.contains("Capacity lost")));
+
+        pool.get_object().unwrap().discard();
+        assert_eq!(pool.get_metrics().capacity_lost, 1);
+    }
+
+    #[test]
+    fn test_dynamic_pool_creates_after_retirement_on_demand() {
+        let pool = DynamicObjectPool::new(|| 7, PoolConfiguration::new().with_max_pool_size(2));
+        let a = pool.get_object().unwrap();
+        let b = pool.get_object().unwrap();
+        drop(a);
+        assert_eq!(pool.retire(2), 1);
+        drop(b);
+        assert_eq!(pool.get_metrics().total_objects, 0);
+        assert_eq!(pool.get_metrics().retired_objects, 2);
+
+        assert_eq!(*pool.get_object().unwrap(), 7);
+    }
+
+    // ── Capacity limits ───────────────────────────────────────────────────────
+
+    #[test]
+    fn test_active_limit_below_pool_size_fails_before_creation() {
+        let pool = DynamicObjectPool::new(
+            || 0,
+            PoolConfiguration::new()
+                .with_capacity_limits(CapacityLimits::new(4).with_max_active_objects(2))
+                .with_wait_on_empty(WaitPolicy::Wait(Duration::from_secs(5))),
+        );
+        let _a = pool.get_object().unwrap();
+        let _b = pool.get_object().unwrap();
+
+        let started = Instant::now();
+        assert!(matches!(pool.get_object(), Err(PoolError::MaxActiveObjectsReached)));
+        assert!(started.elapsed() < Duration::from_secs(1), "never waited on");
+        assert_eq!(pool.get_metrics().total_objects, 2);
+    }
+
+    #[test]
+    fn test_active_limit_at_pool_size_is_ignored() {
+        let pool = Arc::new(DynamicObjectPool::new(
+            || 0,
+            PoolConfiguration::new()
+                .with_capacity_limits(CapacityLimits::new(2).with_max_active_objects(2))
+                .with_wait_on_empty(WaitPolicy::FailFast),
+        ));
+        let a = pool.get_object().unwrap();
+        let _b = pool.get_object().unwrap();
+        assert!(matches!(pool.get_object(), Err(PoolError::PoolFull)));
+
+        let waiting = Arc::new(DynamicObjectPool::new(
+            || 0,
+            PoolConfiguration::new()
+                .with_capacity_limits(CapacityLimits::new(1).with_max_active_objects(3))
+                .with_wait_on_empty(WaitPolicy::Wait(Duration::from_secs(5))),
+        ));
+        let held = waiting.get_object().unwrap();
+        let waiter = {
+            let waiting = Arc::clone(&waiting);
+            std::thread::spawn(move || waiting.get_object().map(|obj| *obj))
+        };
+        while waiting.waiting_count() == 0 {
+            std::thread::yield_now();
+        }
+        drop(held);
+        assert_eq!(waiter.join().unwrap().unwrap(), 0);
+        drop(a);
     }
-    
+
     #[test]
-    fn test_queryable_no_match() {
-        let pool = QueryableObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
-        
-        let result = pool.get_object(|x| *x == 99);
-        
-        assert!(result.is_err());
-        if let Err(e) = result {
-            assert!(matches!(e, PoolError::NoMatchFound));
+    fn test_fixed_pool_size_covers_its_initial_objects() {
+        let pool = ObjectPool::new(
+            vec![1, 2, 3],
+            PoolConfiguration::new().with_capacity_limits(CapacityLimits::new(1).with_max_active_objects(3)),
+        );
+        assert_eq!(pool.capacity(), 3);
+        let held: Vec<_> = (0..3).map(|_| pool.get_object().unwrap()).collect();
+        assert!(matches!(pool.get_object(), Err(PoolError::PoolEmpty)));
+        drop(held);
+    }
+
+    // ── Fast path ─────────────────────────────────────────────────────────────
+
+    /// Counts heap allocations made by the current thread.
+    struct CountingAlloc;
+
+    thread_local! {
+        static ALLOCATIONS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    unsafe impl std::alloc::GlobalAlloc for CountingAlloc {
+        unsafe fn alloc(&self, layout: std::alloc::Layout) -> *mut u8 {
+            let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+            unsafe { std::alloc::System.alloc(layout) }
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: std::alloc::Layout) {
+            unsafe { std::alloc::System.dealloc(ptr, layout) }
         }
     }
-    
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAlloc = CountingAlloc;
+
     #[test]
-    fn test_queryable_multiple_matches() {
-        let pool = QueryableObjectPool::new(vec![1, 2, 3, 2, 4], PoolConfiguration::default());
-        
-        let obj = pool.get_object(|x| *x == 2).unwrap();
-        assert_eq!(*obj, 2);
-        
-        // Should still find the other 2
-        let obj2 = pool.get_object(|x| *x == 2).unwrap();
-        assert_eq!(*obj2, 2);
+    fn test_fast_path_does_not_allocate() {
+        let pool = ObjectPool::new(
+            vec![1, 2],
+            PoolConfiguration::new()
+                .with_max_active_objects(1)
+                .with_on_borrow(|x: &mut i32| *x += 0),
+        );
+
+        let before = ALLOCATIONS.with(|count| count.get());
+        let obj = pool.try_get_object_fast();
+        let refused = pool.try_get_object_fast();
+        let after = ALLOCATIONS.with(|count| count.get());
+
+        assert!(obj.is_some());
+        assert!(refused.is_none());
+        assert_eq!(after, before);
+
+        drop(std::hint::black_box(Box::new(0u64)));
+        assert!(ALLOCATIONS.with(|count| count.get()) > after, "the counter must see allocations");
     }
-    
+
     #[test]
-    fn test_dynamic_pool_creation() {
-        let call_count = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
-        let count_clone = call_count.clone();
-        
-        let pool = DynamicObjectPool::new(
-            move || {
-                count_clone.fetch_add(1, Ordering::Relaxed);
-                42
-            },
-            PoolConfiguration::new().with_max_pool_size(5),
-        );
-        
-        let _obj1 = pool.get_object().unwrap();
-        let _obj2 = pool.get_object().unwrap();
-        
-        assert_eq!(call_count.load(Ordering::Relaxed), 2);
+    fn test_fast_path_takes_idle_objects_and_counts_them() {
+        let pool = ObjectPool::new(vec![1, 2], PoolConfiguration::default());
+        let mut events = pool.events();
+
+        let a = pool.try_get_object_fast().unwrap();
+        let b = pool.try_get_object_fast().unwrap();
+        assert!(pool.try_get_object_fast().is_none());
+        assert_eq!(pool.active_count(), 2);
+
+        let metrics = pool.get_metrics();
+        assert_eq!(metrics.total_retrieved, 2);
+        assert_eq!(metrics.pool_empty_events, 1);
+        assert!(events.try_recv().is_err(), "the fast path publishes no events");
+
+        drop((a, b));
+        assert_eq!(pool.available_count(), 2);
+        assert_eq!(pool.active_count(), 0);
     }
-    
+
     #[test]
-    fn test_dynamic_pool_warmup() {
-        let pool = DynamicObjectPool::new(
-            || 42,
-            PoolConfiguration::new().with_max_pool_size(10),
-        );
-        
-        pool.warmup(5).unwrap();
-        
-        let health = pool.get_health_status();
-        assert_eq!(health.available_objects, 5);
+    fn test_fast_path_refuses_closed_paused_and_capped_pools() {
+        use crate::LimitGroup;
+
+        let group = LimitGroup::new("rt", 1);
+        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::new().with_limit_group(group.clone()));
+
+        pool.pause();
+        assert!(pool.try_get_object_fast().is_none());
+        pool.resume();
+
+        let _held = pool.try_get_object_fast().unwrap();
+        assert!(pool.try_get_object_fast().is_none());
+        assert_eq!(pool.get_metrics().group_limit_rejections, 1);
+        assert_eq!(group.active(), 1);
+
+        let _ = pool.shutdown();
+        assert!(pool.try_get_object_fast().is_none());
     }
-    
+
     #[test]
-    fn test_dynamic_pool_max_capacity() {
-        let pool = DynamicObjectPool::new(
-            || 42,
-            PoolConfiguration::new().with_max_pool_size(2),
-        );
-        
-        let _obj1 = pool.get_object().unwrap();
-        let _obj2 = pool.get_object().unwrap();
-        let result = pool.get_object();
-        
-        assert!(result.is_err());
-        if let Err(e) = result {
-            assert!(matches!(e, PoolError::PoolFull));
+    fn test_fast_path_unsupported_with_locking_features() {
+        let plain = ObjectPool::new(vec![1], PoolConfiguration::default());
+        assert!(plain.supports_fast_path());
+
+        let configs = [
+            PoolConfiguration::new().with_circuit_breaker(3, Duration::from_secs(1)),
+            PoolConfiguration::new().with_ttl(Duration::from_secs(60)),
+            PoolConfiguration::new().with_holder_tracking(),
+        ];
+        for config in configs {
+            let pool = ObjectPool::new(vec![1], config);
+            assert!(!pool.supports_fast_path());
+            assert!(pool.try_get_object_fast().is_none());
+            assert_eq!(pool.available_count(), 1);
         }
+
+        let _ = plain.invalidate_all();
+        assert!(!plain.supports_fast_path());
+        assert!(plain.try_get_object_fast().is_none());
     }
 
     #[test]
-    fn test_dynamic_pool_propagates_max_active_without_creating() {
+    fn test_dynamic_fast_path_never_creates() {
         let created = Arc::new(AtomicUsize::new(0));
-        let created_clone = Arc::clone(&created);
-
+        let counter = Arc::clone(&created);
         let pool = DynamicObjectPool::with_initial(
             move || {
-                created_clone.fetch_add(1, Ordering::Relaxed);
-                42
+                counter.fetch_add(1, Ordering::Relaxed);
+                9
             },
-            vec![1, 2],
-            PoolConfiguration::new().with_max_active_objects(1),
+            vec![1],
+            PoolConfiguration::new().with_max_pool_size(4),
         );
 
-        let _obj = pool.get_object().unwrap();
-        let result = pool.get_object();
-
-        assert!(matches!(result, Err(PoolError::MaxActiveObjectsReached)));
+        assert_eq!(*pool.try_get_object_fast().unwrap(), 1);
+        let _held = pool.try_get_object_fast().unwrap();
+        assert!(pool.try_get_object_fast().is_none());
         assert_eq!(created.load(Ordering::Relaxed), 0);
     }
 
+    // ── Minimal build ─────────────────────────────────────────────────────────
+
+    #[cfg(feature = "minimal")]
     #[test]
-    fn test_dynamic_pool_propagates_circuit_breaker_without_extra_creation() {
-        let created = Arc::new(AtomicUsize::new(0));
-        let created_clone = Arc::clone(&created);
+    fn test_minimal_strips_counters_but_keeps_gauges() {
+        use crate::metrics::Counter;
 
-        let pool = DynamicObjectPool::new(
-            move || {
-                created_clone.fetch_add(1, Ordering::Relaxed);
-                42
-            },
+        assert_eq!(std::mem::size_of::<Counter>(), 0);
+        let pool = ObjectPool::new(vec![1, 2], PoolConfiguration::default());
+        let _held = pool.get_object().unwrap();
+        drop(pool.get_object().unwrap());
+
+        let metrics = pool.get_metrics();
+        assert_eq!(metrics.total_retrieved, 0);
+        assert_eq!(metrics.total_returned, 0);
+        assert_eq!(metrics.active_objects, 1);
+        assert_eq!(metrics.available_objects, 1);
+        assert!(pool.get_health_status().is_healthy);
+    }
+
+    #[cfg(feature = "minimal")]
+    #[test]
+    fn test_minimal_ignores_eviction_policy() {
+        let pool = ObjectPool::new(vec![1], PoolConfiguration::new().with_ttl(Duration::from_millis(1)));
+        std::thread::sleep(Duration::from_millis(5));
+
+        let obj = pool.get_object().unwrap();
+        assert_eq!(*obj, 1);
+        assert_eq!(obj.remaining_ttl(), None);
+        assert!(pool.supports_fast_path());
+    }
+
+    #[cfg(feature = "minimal")]
+    #[test]
+    fn test_minimal_ignores_health_tracking() {
+        use crate::slo::SloTargets;
+
+        let pool = ObjectPool::new(
+            vec![1],
             PoolConfiguration::new()
-                .with_max_pool_size(2)
-                .with_circuit_breaker(1, Duration::from_secs(60)),
+                .with_slo(SloTargets::new().with_timeout_rate(0.1))
+                .with_churn_warning(1),
         );
+        drop(pool.get_object().unwrap());
+        assert!(pool.slo_status().is_none());
+        assert!(pool.get_health_status().warnings.is_empty());
+    }
 
-        // First call creates because the inner pool is empty.
-        let _obj = pool.get_object().unwrap();
-        assert_eq!(created.load(Ordering::Relaxed), 1);
+    // ── Acquisition sites ─────────────────────────────────────────────────────
 
-        // The first empty inner read opened the breaker, so this must fail fast.
-        let result = pool.get_object();
-        assert!(matches!(result, Err(PoolError::CircuitBreakerOpen)));
-        assert_eq!(created.load(Ordering::Relaxed), 1);
+    #[test]
+    fn test_guards_record_the_calling_line() {
+        let fixed = ObjectPool::new(vec![1, 2], PoolConfiguration::default());
+        let queryable = QueryableObjectPool::new(vec![1], PoolConfiguration::default());
+        let dynamic = DynamicObjectPool::new(|| 0, PoolConfiguration::new().with_max_pool_size(2));
+
+        let (a, line_a) = (fixed.get_object().unwrap(), line!());
+        let (b, line_b) = (fixed.try_get_object().unwrap().unwrap(), line!());
+        let (c, line_c) = (queryable.get_object(|x| *x == 1).unwrap(), line!());
+        let (d, line_d) = (dynamic.get_object_leased(Duration::from_secs(60)).unwrap(), line!());
+
+        for (obj, line) in [(&a, line_a), (&b, line_b), (&c, line_c), (&d, line_d)] {
+            let location = obj.acquired_at().unwrap();
+            assert_eq!((location.file(), location.line()), (file!(), line));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_async_guards_record_the_calling_line() {
+        let pool = DynamicObjectPool::new(|| 0, PoolConfiguration::new().with_max_pool_size(2));
+        let (obj, line) = (pool.get_object_async(), line!());
+        assert_eq!(obj.await.unwrap().acquired_at().unwrap().line(), line);
+
+        let with = AcquireContext::new().with_label("job");
+        let (obj, line) = (pool.get_object_with_async(with), line!());
+        assert_eq!(obj.await.unwrap().acquired_at().unwrap().line(), line);
+
+        let (mut stream, line) = (pool.acquire_stream(), line!());
+        let obj = futures::StreamExt::next(&mut stream).await.unwrap();
+        assert_eq!(obj.acquired_at().unwrap().line(), line);
     }
 
     #[test]
-    fn test_eviction_ttl() {
-        use std::thread;
-        
-        let config = PoolConfiguration::new()
-            .with_ttl(Duration::from_millis(100));
-        
-        let pool = ObjectPool::new(vec![1, 2, 3], config);
-        
-        thread::sleep(Duration::from_millis(150));
-        
-        // Objects should be expired and filtered out
-        let obj = pool.try_get_object().unwrap();
-        assert!(obj.is_none() || pool.available_count() < 3);
+    fn test_holders_report_acquisition_sites() {
+        let pool = ObjectPool::new(vec![1], PoolConfiguration::new().with_holder_tracking());
+        let (_held, line) = (pool.get_object().unwrap(), line!());
+
+        let holders = pool.current_holders();
+        let location = holders[0].location.unwrap();
+        assert_eq!((location.file(), location.line()), (file!(), line));
     }
-    
+
     #[test]
-    fn test_circuit_breaker_opens() {
-        let config = PoolConfiguration::new()
-            .with_circuit_breaker(3, Duration::from_secs(60));
-        
-        let pool = ObjectPool::new(vec![1], config);
-        
-        let _obj = pool.get_object().unwrap();
-        
-        // Cause failures
-        for _ in 0..3 {
-            let _ = pool.try_get_object();
+    fn test_expired_lease_warns_with_acquisition_site() {
+        let pool = ObjectPool::new(vec![1], PoolConfiguration::default());
+        let mut warnings = pool.warnings();
+
+        let ctx = AcquireContext::new().with_label("import");
+        let obj = pool.get_object_with(ctx).unwrap();
+        let site = obj.acquired_at();
+        let obj = pool.attach_lease(obj, Duration::from_millis(1), None);
+        std::thread::sleep(Duration::from_millis(5));
+        drop(obj);
+
+        match warnings.try_recv() {
+            Ok(PoolWarning::LeaseExpired { object_id, label, location, .. }) => {
+                assert_eq!(object_id.raw(), 0);
+                assert_eq!(label.as_deref(), Some("import"));
+                assert_eq!(location, site);
+            }
+            other => panic!("expected a lease warning, got {other:?}"),
         }
-        
-        // Circuit breaker should be open now
-        let result = pool.get_object();
-        assert!(result.is_err());
+        assert!(warnings.try_recv().is_err(), "one warning per lease");
     }
-    
+
+    // ── Sink and stream passthrough ───────────────────────────────────────────
+
+    #[cfg(feature = "futures")]
     #[tokio::test]
-    async fn test_async_timeout() {
-        let config = PoolConfiguration::new()
-            .with_timeout(Duration::from_millis(50));
-        
-        let pool = ObjectPool::new(vec![1], config);
-        
-        let _obj = pool.get_object().unwrap();
-        
-        let result = pool.get_object_async().await;
-        
-        assert!(result.is_err());
-        if let Err(e) = result {
-            assert!(matches!(e, PoolError::Timeout(_)));
+    async fn pooled_streams_work_in_combinators() {
+        use futures::StreamExt;
+
+        let pool = ObjectPool::new(vec![futures::stream::iter(1..=4)], PoolConfiguration::default());
+        let numbers = pool.get_object().unwrap();
+        assert_eq!(numbers.size_hint(), (4, Some(4)));
+
+        let doubled: Vec<i32> = numbers.map(|n| n * 2).take(3).collect().await;
+        assert_eq!(doubled, [2, 4, 6]);
+        assert_eq!(pool.available_count(), 1, "the combinator drop returns the stream");
+
+        let mut rest = pool.get_object().unwrap();
+        assert_eq!(rest.next().await, Some(4));
+        assert_eq!(rest.next().await, None);
+    }
+
+    #[cfg(feature = "futures")]
+    #[tokio::test]
+    async fn pooled_sinks_forward_and_flush() {
+        use futures::channel::mpsc;
+        use futures::{SinkExt, StreamExt};
+
+        let (tx, rx) = mpsc::unbounded::<u32>();
+        let pool = ObjectPool::new(vec![tx], PoolConfiguration::default());
+
+        let mut sender = pool.get_object().unwrap();
+        sender.send_all(&mut futures::stream::iter([1, 2, 3]).map(Ok)).await.unwrap();
+        sender.feed(4).await.unwrap();
+        sender.flush().await.unwrap();
+        drop(sender);
+        assert_eq!(pool.available_count(), 1);
+
+        let mut sender = pool.get_object().unwrap();
+        sender.close().await.unwrap();
+        assert!(sender.send(5).await.is_err());
+        drop(sender);
+
+        assert_eq!(rx.collect::<Vec<_>>().await, [1, 2, 3, 4]);
+    }
+
+    // ── Trait objects ─────────────────────────────────────────────────────────
+
+    trait Backend {
+        fn name(&self) -> &str;
+        fn query(&mut self) -> usize;
+    }
+
+    struct Primary {
+        queries: usize,
+    }
+
+    struct Replica;
+
+    impl Backend for Primary {
+        fn name(&self) -> &str {
+            "primary"
+        }
+
+        fn query(&mut self) -> usize {
+            self.queries += 1;
+            self.queries
+        }
+    }
+
+    impl Backend for Replica {
+        fn name(&self) -> &str {
+            "replica"
+        }
+
+        fn query(&mut self) -> usize {
+            0
+        }
+    }
+
+    type DynBackend = Box<dyn Backend + Send + Sync>;
+
+    #[test]
+    fn trait_objects_pool_with_validation_and_queries() {
+        let pool: QueryableObjectPool<DynBackend> = QueryableObjectPool::new(
+            vec![Box::new(Primary { queries: 0 }), Box::new(Replica)],
+            PoolConfiguration::new().with_validation(|backend| backend.name() != "broken"),
+        );
+
+        let mut primary = pool.get_object(|backend| backend.name() == "primary").unwrap();
+        assert_eq!(primary.query(), 1);
+        drop(primary);
+
+        let mut primary = pool.get_object(|backend| backend.name() == "primary").unwrap();
+        assert_eq!(primary.query(), 2, "the same boxed object came back");
+        let replica = ObjectPool::get_object(&pool).unwrap();
+        assert_eq!(replica.name(), "replica");
+    }
+
+    #[tokio::test]
+    async fn boxed_factory_creates_trait_objects() {
+        let factory: Box<dyn Fn() -> DynBackend + Send + Sync> = Box::new(|| Box::new(Replica));
+        let pool = DynamicObjectPool::from_boxed_factory(
+            factory,
+            vec![Box::new(Primary { queries: 0 })],
+            PoolConfiguration::new().with_max_pool_size(2),
+        );
+
+        let first = pool.get_object_async().await.unwrap();
+        let second = pool.get_object_async().await.unwrap();
+        assert_eq!((first.name(), second.name()), ("primary", "replica"));
+        assert_eq!(pool.get_metrics().total_retrieved, 2);
+        drop((first, second));
+        assert_eq!(pool.available_count(), 2);
+    }
+
+    // ── Health-weighted selection ─────────────────────────────────────────────
+
+    #[test]
+    fn healthiest_idle_object_is_handed_out_first() {
+        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::new().with_health_selection(3));
+        let objects: Vec<_> = (0..3).map(|_| pool.get_object().unwrap()).collect();
+        for obj in &objects {
+            obj.set_health(f64::from(**obj) / 10.0);
         }
+        drop(objects);
+
+        let best = pool.get_object().unwrap();
+        assert_eq!((*best, best.health()), (3, 0.3));
+        let next = pool.get_object().unwrap();
+        assert_eq!(*next, 2);
+        assert_eq!(pool.available_count(), 1, "losing candidates go back");
+    }
+
+    #[test]
+    fn selection_window_bounds_the_comparison() {
+        let pool = ObjectPool::new(
+            vec![0u32, 1, 2, 3],
+            PoolConfiguration::new().with_selection(2, |obj: &u32, _| f64::from(*obj)),
+        );
+        assert_eq!(*pool.get_object().unwrap(), 1, "only the first two were compared");
+        assert_eq!(pool.get_metrics().total_retrieved, 1);
+        assert_eq!(pool.available_count(), 4);
+    }
+
+    #[test]
+    fn selection_skips_expired_candidates() {
+        let pool = ObjectPool::new(
+            vec![1, 2],
+            PoolConfiguration::new()
+                .with_ttl(Duration::from_millis(20))
+                .with_selection(2, |obj: &i32, _| f64::from(*obj)),
+        );
+        std::thread::sleep(Duration::from_millis(40));
+        assert!(matches!(pool.get_object(), Err(PoolError::PoolEmpty)));
+        assert_eq!(pool.get_metrics().total_objects, 0);
     }
 
-    #[tokio::test]
-    async fn test_async_get_fails_fast_on_max_active() {
-        use std::time::Instant;
+    #[test]
+    fn health_survives_checkouts_and_defaults_without_selection() {
+        let pool = DynamicObjectPool::new(|| 0, PoolConfiguration::new().with_max_pool_size(1));
+        let obj = pool.get_object().unwrap();
+        assert_eq!(obj.health(), 1.0);
+        obj.set_health(0.5);
+        drop(obj);
+        assert_eq!(pool.get_object().unwrap().health(), 0.5);
+    }
 
-        let config = PoolConfiguration::new()
-            .with_timeout(Duration::from_secs(2))
-            .with_max_active_objects(1);
+    // ── SLO tracking ──────────────────────────────────────────────────────────
 
-        let pool = ObjectPool::new(vec![1, 2], config);
-        let _obj = pool.get_object().unwrap();
+    #[test]
+    fn timeouts_exhaust_the_budget_and_publish_an_event_once() {
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new()
+                .with_wait_on_empty(WaitPolicy::Wait(Duration::from_millis(1)))
+                .with_slo(SloTargets::new().with_timeout_rate(0.1)),
+        );
+        let mut events = pool.events();
+        for _ in 0..15 {
+            drop(pool.get_object().unwrap());
+        }
+        let held = pool.get_object().unwrap();
+        for _ in 0..5 {
+            assert!(matches!(pool.get_object(), Err(PoolError::Timeout(..))));
+        }
+        assert!(matches!(pool.try_get_object(), Ok(None)), "try_* is not counted");
+        drop(held);
 
-        let start = Instant::now();
-        let result = pool.get_object_async().await;
-        let elapsed = start.elapsed();
+        let status = pool.slo_status().unwrap();
+        let timeouts = status.timeouts.unwrap();
+        assert_eq!(status.acquisitions, 21);
+        assert!(timeouts.exhausted && timeouts.burn_rate > 2.0);
+        assert_eq!(timeouts.remaining, 0.0);
+        assert!(status.latency.is_none());
 
-        assert!(matches!(result, Err(PoolError::MaxActiveObjectsReached)));
-        assert!(elapsed < Duration::from_millis(200));
+        let published: Vec<_> = std::iter::from_fn(|| events.try_recv().ok())
+            .filter(|event| matches!(event, PoolEvent::SloBudgetExhausted { .. }))
+            .collect();
+        assert_eq!(published, [PoolEvent::SloBudgetExhausted { objective: SloObjective::Timeouts }]);
+        assert_eq!(published[0].to_string(), "timeout error budget exhausted");
     }
 
     #[tokio::test]
-    async fn test_async_get_fails_fast_on_circuit_breaker_open() {
-        use std::time::Instant;
-
-        let config = PoolConfiguration::new()
-            .with_timeout(Duration::from_secs(2))
-            .with_circuit_breaker(1, Duration::from_secs(60));
-
-        let pool = ObjectPool::new(vec![1], config);
-        let _obj = pool.get_object().unwrap();
-
-        // First empty attempt records failure and opens the breaker.
-        assert!(matches!(pool.try_get_object(), Ok(None)));
+    async fn slow_async_acquisitions_burn_the_latency_budget() {
+        let pool = DynamicObjectPool::new(
+            || 0,
+            PoolConfiguration::new()
+                .with_max_pool_size(1)
+                .with_slo(SloTargets::new().with_latency(Duration::from_millis(5), 0.5)),
+        );
+        assert!(pool.slo_status().unwrap().latency.unwrap().burn_rate == 0.0);
 
-        let start = Instant::now();
-        let result = pool.get_object_async().await;
-        let elapsed = start.elapsed();
+        let held = pool.get_object_async().await.unwrap();
+        let waiter = pool.get_object_async();
+        let release = async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(held);
+        };
+        let (obj, ()) = tokio::join!(waiter, release);
+        drop(obj.unwrap());
 
-        assert!(matches!(result, Err(PoolError::CircuitBreakerOpen)));
-        assert!(elapsed < Duration::from_millis(200));
+        let latency = pool.slo_status().unwrap().latency.unwrap();
+        assert_eq!(latency.observed, 0.5);
+        assert_eq!(latency.burn_rate, 1.0);
+        assert!(!latency.exhausted);
+        assert!(ObjectPool::new(vec![1], PoolConfiguration::default()).slo_status().is_none());
     }
 
-    #[tokio::test]
-    async fn test_concurrent_access() {
-        use std::sync::Arc;
-        
+    // ── Timed hooks ───────────────────────────────────────────────────────────
+
+    #[test]
+    fn timed_acquire_hook_measures_the_wait() {
+        let waits = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&waits);
         let pool = Arc::new(ObjectPool::new(
-            vec![1, 2, 3, 4, 5],
-            PoolConfiguration::default(),
+            vec![1],
+            PoolConfiguration::new()
+                .with_wait_on_empty(WaitPolicy::Wait(Duration::from_secs(5)))
+                .with_on_acquire_timed(move |_: &mut i32, timing: &HookTiming| {
+                    recorded.lock().unwrap().push(timing.wait.unwrap());
+                }),
         ));
-        
-        let mut handles = vec![];
-        
-        for _ in 0..10 {
-            let pool_clone = Arc::clone(&pool);
-            let handle = tokio::spawn(async move {
-                if let Ok(Some(obj)) = pool_clone.try_get_object() {
-                    tokio::time::sleep(Duration::from_millis(10)).await;
-                    drop(obj);
-                    true
-                } else {
-                    false
-                }
-            });
-            handles.push(handle);
-        }
-        
-        let mut success_count = 0;
-        for handle in handles {
-            if handle.await.unwrap() {
-                success_count += 1;
-            }
-        }
-        
-        // At least 5 should succeed (pool size)
-        assert!(success_count >= 5);
-        assert_eq!(pool.available_count(), 5);
-    }
-    
-    #[tokio::test]
-    async fn test_queryable_async() {
-        let pool = QueryableObjectPool::new(vec![1, 2, 3, 4, 5], PoolConfiguration::default());
-        
-        let obj = pool.get_object_async(|x| *x > 3).await.unwrap();
-        assert!(*obj > 3);
+
+        let held = pool.get_object().unwrap();
+        let returner = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(30));
+            drop(held);
+        });
+        drop(pool.get_object().unwrap());
+        returner.join().unwrap();
+
+        let waits = waits.lock().unwrap();
+        assert!(waits[0] < Duration::from_millis(30), "first object was idle");
+        assert!(waits[1] >= Duration::from_millis(25), "second waited for the return");
     }
-    
+
     #[tokio::test]
-    async fn test_dynamic_warmup_async() {
+    async fn timed_hooks_cover_async_release_and_creation() {
+        let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let (returned, created) = (Arc::clone(&seen), Arc::clone(&seen));
         let pool = DynamicObjectPool::new(
-            || 42,
-            PoolConfiguration::new().with_max_pool_size(10),
+            || {
+                std::thread::sleep(Duration::from_millis(10));
+                0
+            },
+            PoolConfiguration::new()
+                .with_on_return_timed(move |_: &mut i32, timing: &HookTiming| returned.lock().unwrap().push(*timing))
+                .with_on_create(move |_: &mut i32, timing: &HookTiming| created.lock().unwrap().push(*timing)),
         );
-        
-        pool.warmup_async(7).await.unwrap();
-        
-        assert_eq!(pool.get_health_status().available_objects, 7);
+
+        let obj = pool.get_object_async().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        obj.release_async().await.unwrap();
+
+        let seen = seen.lock().unwrap();
+        assert!(seen[0].creation.unwrap() >= Duration::from_millis(10));
+        assert!(seen[1].hold.unwrap() >= Duration::from_millis(10));
+        assert_eq!(seen.len(), 2);
     }
-    
+
+    // ── Churn warning ─────────────────────────────────────────────────────────
+
     #[test]
-    fn test_pool_reuse_after_drop() {
-        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
-        
-        for _ in 0..100 {
-            {
-                let obj = pool.get_object().unwrap();
-                assert!(vec![1, 2, 3].contains(&*obj));
-            }
-            assert_eq!(pool.available_count(), 3);
+    fn failing_validation_raises_a_churn_warning() {
+        let pool = DynamicObjectPool::new(
+            || -1,
+            PoolConfiguration::new()
+                .with_max_pool_size(2)
+                .with_validation(|n: &i32| *n >= 0)
+                .with_churn_warning(3),
+        );
+        let mut warnings = pool.warnings();
+
+        for _ in 0..3 {
+            drop(pool.get_object().unwrap());
         }
+        assert!(warnings.try_recv().is_err(), "three replacements are within the threshold");
+        assert!(!pool.get_health_status().warnings.iter().any(|w| w.contains("churn")));
+
+        drop(pool.get_object().unwrap());
+        assert_eq!(
+            warnings.try_recv(),
+            Ok(PoolWarning::HighChurn { created: 4, destroyed: 4, threshold: 3 })
+        );
+        drop(pool.get_object().unwrap());
+        assert!(warnings.try_recv().is_err(), "reported once per episode");
+        let health = pool.get_health_status();
+        assert!(health.warnings.iter().any(|w| w.starts_with("High churn: 5 objects replaced")));
     }
-    
-    #[test]
-    fn test_multiple_pools() {
-        let pool1 = ObjectPool::new(vec![1, 2], PoolConfiguration::default());
-        let pool2 = ObjectPool::new(vec![3, 4], PoolConfiguration::default());
-        
-        let obj1 = pool1.get_object().unwrap();
-        let obj2 = pool2.get_object().unwrap();
-        
-        assert!(vec![1, 2].contains(&*obj1));
-        assert!(vec![3, 4].contains(&*obj2));
-    }
-    
+
     #[test]
-    fn test_export_metrics_map() {
-        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
-        
-        {
-            let _obj = pool.get_object().unwrap();
-        }
-        
-        let metrics_map = pool.export_metrics();
-        
-        assert!(metrics_map.contains_key("total_retrieved"));
-        assert!(metrics_map.contains_key("total_returned"));
-        assert!(metrics_map.contains_key("active_objects"));
-        assert!(metrics_map.contains_key("queue_push_failures"));
-        assert_eq!(metrics_map.get("total_retrieved").unwrap(), "1");
-        assert_eq!(metrics_map.get("total_returned").unwrap(), "1");
+    fn warming_up_is_not_churn() {
+        let pool = DynamicObjectPool::new(
+            || 0,
+            PoolConfiguration::new().with_max_pool_size(20).with_churn_warning(1),
+        );
+        let mut warnings = pool.warnings();
+        pool.warmup(20).unwrap();
+
+        assert!(warnings.try_recv().is_err());
+        assert!(!pool.get_health_status().warnings.iter().any(|w| w.contains("churn")));
     }
 
-    #[test]
-    fn test_return_push_failure_is_tracked() {
-        let config = PoolConfiguration::new().with_max_pool_size(1);
-        let pool = ObjectPool::new(vec![1], config);
+    // ── Activity log ──────────────────────────────────────────────────────────
 
-        let obj = pool.get_object().unwrap();
+    #[test]
+    fn activity_log_records_waits_failures_and_discards() {
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new()
+                .with_activity_log(8)
+                .with_wait_on_empty(WaitPolicy::Wait(Duration::from_millis(20))),
+        );
+        let held = pool.get_object().unwrap();
+        assert!(matches!(pool.get_object(), Err(PoolError::Timeout(..))));
+        held.discard();
 
-        // Fill the queue while the checked-out object is still active.
-        pool.available.push((2, 999)).unwrap();
+        let activity = pool.recent_activity();
+        assert_eq!(activity.len(), 3);
+        assert!(matches!(activity[0].outcome, ActivityOutcome::Acquired));
+        assert!(activity[0].wait.unwrap() < Duration::from_millis(20));
 
-        drop(obj);
+        assert!(matches!(activity[1].outcome, ActivityOutcome::AcquireFailed(PoolError::Timeout(..))));
+        assert_eq!(activity[1].object_id, None);
+        assert!(activity[1].wait.unwrap() >= Duration::from_millis(20));
 
-        let metrics = pool.get_metrics();
-        assert_eq!(metrics.queue_push_failures, 1);
-        assert_eq!(metrics.total_returned, 0);
-        assert_eq!(pool.active_count(), 0);
+        assert!(matches!(activity[2].outcome, ActivityOutcome::Discarded));
+        assert_eq!(activity[2].object_id, activity[0].object_id);
+        assert!(activity.windows(2).all(|pair| pair[0].at <= pair[1].at));
     }
 
     #[test]
-    fn test_health_warnings() {
-        let config = PoolConfiguration::new()
-            .with_max_pool_size(2);
-        
-        let pool = ObjectPool::new(vec![1, 2], config);
-        
-        // Use both objects to get high utilization
-        let _obj1 = pool.get_object().unwrap();
-        let _obj2 = pool.get_object().unwrap();
-        
-        let health = pool.get_health_status();
-        assert_eq!(health.utilization, 1.0); // 100% utilization
-    }
-    
-    #[test]
-    fn test_configuration_builder() {
-        let config = PoolConfiguration::<i32>::new()
-            .with_max_pool_size(50)
-            .with_max_active_objects(25)
-            .with_timeout(Duration::from_secs(10))
-            .with_ttl(Duration::from_secs(300))
-            .with_idle_timeout(Duration::from_secs(60))
-            .with_warmup(10)
-            .with_circuit_breaker(5, Duration::from_secs(30));
-        
-        assert_eq!(config.max_pool_size, 50);
-        assert_eq!(config.max_active_objects, Some(25));
-        assert_eq!(config.warmup_size, Some(10));
-        assert!(config.enable_circuit_breaker);
+    fn activity_log_records_evictions_and_disables_the_fast_path() {
+        let pool = ObjectPool::new(
+            vec![1, 2],
+            PoolConfiguration::new()
+                .with_activity_log(1)
+                .with_idle_timeout(Duration::from_millis(5)),
+        );
+        std::thread::sleep(Duration::from_millis(10));
+        assert_eq!(pool.evict_expired(), 2);
+
+        let activity = pool.recent_activity();
+        assert_eq!(activity.len(), 1, "only the newest record is kept");
+        assert!(matches!(activity[0].outcome, ActivityOutcome::Evicted));
+
+        let plain = ObjectPool::new(vec![1], PoolConfiguration::new());
+        assert!(plain.recent_activity().is_empty());
+        assert!(plain.supports_fast_path());
+        assert!(!ObjectPool::new(vec![1], PoolConfiguration::new().with_activity_log(4)).supports_fast_path());
     }
 
     #[test]
-    fn test_into_detached_cleans_active_state() {
+    fn activity_log_records_objects_returned_in_a_batch() {
         let pool = ObjectPool::new(
-            vec![1],
-            PoolConfiguration::new().with_max_active_objects(1),
+            vec![1, 2, 3],
+            PoolConfiguration::new()
+                .with_activity_log(8)
+                .with_validation(|x: &i32| *x > 0),
         );
+        let mut batch = pool.get_batch(2).unwrap();
+        *batch[0] = -1;
+        let (invalid, valid) = (batch[0].id(), batch[1].id());
+        drop(batch);
 
-        let obj = pool.get_object().unwrap();
-        assert_eq!(pool.active_count(), 1);
+        let returns: Vec<_> = pool.recent_activity().into_iter().skip(2).collect();
+        assert_eq!(returns.len(), 2);
+        assert!(matches!(returns[0].outcome, ActivityOutcome::Discarded));
+        assert_eq!(returns[0].object_id, Some(invalid));
+        assert!(matches!(returns[1].outcome, ActivityOutcome::Returned));
+        assert_eq!(returns[1].object_id, Some(valid));
+    }
 
-        let value = obj.into_detached();
-        assert_eq!(value, 1);
+    // ── Memory accounting ─────────────────────────────────────────────────────
 
-        assert_eq!(pool.active_count(), 0);
-        assert_eq!(pool.available_count(), 0);
+    #[test]
+    fn memory_accounting_follows_objects_in_and_out_of_the_pool() {
+        let pool = DynamicObjectPool::new(
+            || Vec::<u8>::with_capacity(100),
+            PoolConfiguration::new()
+                .with_max_pool_size(4)
+                .with_size_estimator(Vec::capacity)
+                .with_idle_timeout(Duration::from_millis(5)),
+        );
+        pool.warmup(2).unwrap();
+        assert_eq!(pool.get_metrics().idle_bytes, 200);
 
-        // Active slot is released, so next error should be pool-empty, not max-active.
-        let result = pool.get_object();
-        assert!(matches!(result, Err(PoolError::PoolEmpty)));
+        let mut grown = pool.get_object().unwrap();
+        let kept = pool.get_object().unwrap();
+        let detached = pool.get_object().unwrap();
+        let metrics = pool.get_metrics();
+        assert_eq!((metrics.idle_bytes, metrics.active_bytes), (0, 300));
+
+        grown.reserve_exact(1000);
+        drop(grown);
+        detached.into_detached();
+        let metrics = pool.get_metrics();
+        assert!(metrics.idle_bytes >= 1000, "measured again on return");
+        assert_eq!(metrics.active_bytes, 100);
+        assert_eq!(metrics.export()["active_bytes"], "100");
+
+        kept.discard();
+        std::thread::sleep(Duration::from_millis(10));
+        assert_eq!(pool.evict_expired(), 1);
+        let metrics = pool.get_metrics();
+        assert_eq!((metrics.idle_bytes, metrics.active_bytes), (0, 0));
     }
 
     #[test]
-    fn test_into_detached_does_not_increment_returned_metrics() {
-        let pool = ObjectPool::new(vec![1], PoolConfiguration::default());
-
-        let obj = pool.get_object().unwrap();
-        let _value = obj.into_detached();
+    fn memory_accounting_follows_objects_returned_in_a_batch() {
+        let pool = ObjectPool::new(
+            vec![Vec::<u8>::with_capacity(100), Vec::with_capacity(100), Vec::with_capacity(48)],
+            PoolConfiguration::new().with_size_estimator(Vec::capacity),
+        );
+        let batch = pool.get_batch(3).unwrap();
+        let metrics = pool.get_metrics();
+        assert_eq!((metrics.idle_bytes, metrics.active_bytes), (0, 248));
 
+        drop(batch);
         let metrics = pool.get_metrics();
-        assert_eq!(metrics.total_retrieved, 1);
-        assert_eq!(metrics.total_returned, 0);
-        assert_eq!(metrics.total_detached, 1);
-        assert_eq!(metrics.active_objects, 0);
-        assert_eq!(metrics.available_objects, 0);
+        assert_eq!((metrics.idle_bytes, metrics.active_bytes), (248, 0));
     }
 
     #[test]
-    fn test_get_borrows_without_removing_from_pool() {
-        let pool = ObjectPool::new(vec![42], PoolConfiguration::default());
-        let obj = pool.get_object().unwrap();
+    fn retained_memory_cap_warns_and_disables_the_fast_path() {
+        let pool = ObjectPool::new(
+            vec![String::with_capacity(2048), String::new()],
+            PoolConfiguration::new()
+                .with_memory_accounting()
+                .with_retained_memory_cap(1024),
+        );
+        assert!(!pool.supports_fast_path());
+        assert!(pool.get_health_status().warnings.iter().any(|w| w.contains("Retained memory")));
 
-        assert_eq!(*obj.get(), 42);
-        assert_eq!(pool.active_count(), 1);
+        let big = pool.get_object().unwrap();
+        let health = pool.get_health_status();
+        assert!(!health.warnings.iter().any(|w| w.contains("Retained memory")));
+        assert!(health.is_healthy);
+        drop(big);
 
-        drop(obj);
+        let prometheus = pool.export_metrics_prometheus("p", None);
+        assert!(prometheus.lines().any(|line| line.starts_with("objectpool_memory_idle_bytes{pool=\"p\"} ")));
+        assert!(prometheus.contains("objectpool_memory_active_bytes{pool=\"p\"} 0"));
 
-        // Object must be returned normally.
-        assert_eq!(pool.available_count(), 1);
+        let plain = ObjectPool::new(vec![String::with_capacity(2048)], PoolConfiguration::new());
+        assert_eq!(plain.get_metrics().idle_bytes, 0);
+    }
+
+    // ── Degraded tier ─────────────────────────────────────────────────────────
+
+    #[test]
+    fn degraded_tier_serves_while_the_breaker_is_open() {
+        let primary_up = Arc::new(AtomicBool::new(false));
+        let up = Arc::clone(&primary_up);
+        let pool = DynamicObjectPool::try_new(
+            move || if up.load(Ordering::Relaxed) { Ok("primary") } else { Err("down") },
+            PoolConfiguration::new()
+                .with_max_pool_size(4)
+                .with_circuit_breaker(1, Duration::from_millis(50))
+                .with_degraded_factory(|| Ok::<_, String>("replica")),
+        );
+        assert!(matches!(pool.get_object(), Err(PoolError::CreationFailed(_))));
+
+        let degraded = pool.get_object().unwrap();
+        assert_eq!((*degraded, degraded.origin()), ("replica", ObjectOrigin::Degraded));
+        assert!(pool.get_health_status().warnings.iter().any(|w| w.contains("1 fallback objects")));
+        drop(degraded);
+        assert_eq!(pool.available_count(), 0, "degraded objects are not pooled");
+        assert_eq!(pool.try_get_object().unwrap().unwrap().origin(), ObjectOrigin::Degraded);
         let metrics = pool.get_metrics();
-        assert_eq!(metrics.total_returned, 1);
-        assert_eq!(metrics.total_detached, 0);
+        assert_eq!((metrics.degraded_objects, metrics.total_objects), (2, 0));
+        assert_eq!(metrics.export()["degraded_objects"], "2");
+        assert!(!pool.get_health_status().warnings.iter().any(|w| w.contains("fallback")));
+
+        primary_up.store(true, Ordering::Relaxed);
+        std::thread::sleep(Duration::from_millis(60));
+        let primary = pool.get_object().unwrap();
+        assert_eq!((*primary, primary.origin()), ("primary", ObjectOrigin::Primary));
     }
 
     #[test]
-    fn test_get_mut_mutates_without_removing_from_pool() {
-        let pool = ObjectPool::new(vec![0], PoolConfiguration::default());
-        let mut obj = pool.get_object().unwrap();
+    fn degraded_tier_is_bounded_and_needs_a_breaker() {
+        let pool = DynamicObjectPool::try_new(
+            || Err::<u32, _>("down"),
+            PoolConfiguration::new()
+                .with_max_pool_size(1)
+                .with_circuit_breaker(1, Duration::from_secs(60))
+                .with_degraded_factory(|| Ok::<_, String>(7)),
+        );
+        assert!(pool.get_object().is_err());
+        let held = pool.get_object().unwrap();
+        assert!(matches!(pool.get_object(), Err(PoolError::CircuitBreakerOpen)));
+        let detached = held.into_detached();
+        assert_eq!(detached, 7);
+        assert!(pool.get_object().is_ok(), "detaching frees the slot");
+
+        let failing = DynamicObjectPool::try_new(
+            || Err::<u32, _>("down"),
+            PoolConfiguration::new()
+                .with_circuit_breaker(1, Duration::from_secs(60))
+                .with_degraded_factory(|| Err::<u32, _>("replica down too")),
+        );
+        assert!(failing.get_object().is_err());
+        assert!(matches!(failing.get_object(), Err(PoolError::CircuitBreakerOpen)));
 
-        *obj.get_mut() = 99;
-        assert_eq!(*obj.get(), 99);
+        let unguarded = DynamicObjectPool::try_new(
+            || Err::<u32, _>("down"),
+            PoolConfiguration::new().with_degraded_factory(|| Ok::<_, String>(7)),
+        );
+        assert!(matches!(unguarded.get_object(), Err(PoolError::CreationFailed(_))));
+    }
 
-        drop(obj);
+    // ── Consistency check ─────────────────────────────────────────────────────
 
-        // Object must be returned normally.
-        assert_eq!(pool.available_count(), 1);
-        let metrics = pool.get_metrics();
-        assert_eq!(metrics.total_returned, 1);
-        assert_eq!(metrics.total_detached, 0);
+    #[test]
+    fn test_consistency_holds_across_invalidation_retirement_and_drain() {
+        let pool = DynamicObjectPool::new(
+            || String::with_capacity(16),
+            PoolConfiguration::new()
+                .with_max_pool_size(6)
+                .with_idle_timeout(Duration::from_secs(60))
+                .with_memory_accounting()
+                .with_holder_tracking(),
+        );
+        pool.warmup(4).unwrap();
+        let held: Vec<_> = (0..3).map(|_| pool.get_object().unwrap()).collect();
+        held[0].set_meta(7u32);
+        let report = pool.check_consistency();
+        assert!(report.is_consistent(), "{report}");
+        assert_eq!((report.population, report.available, report.active), (4, 1, 3));
+
+        pool.invalidate_all();
+        let mut held = held.into_iter();
+        held.next().unwrap().discard();
+        let _detached = held.next().unwrap().into_detached();
+        assert_eq!(pool.retire(1), 1);
+        let _fresh = pool.get_object().unwrap();
+        drop(held);
+        let _drained = pool.drain();
+
+        let report = pool.check_consistency();
+        assert!(report.is_consistent(), "{report}");
+        assert_eq!((report.population, report.available, report.active), (1, 0, 1));
     }
 
     #[test]
-    fn test_try_get_object_propagates_max_active_error() {
+    fn test_consistency_check_reports_mismatches() {
         let pool = ObjectPool::new(
             vec![1, 2],
-            PoolConfiguration::new().with_max_active_objects(1),
+            PoolConfiguration::new().with_max_pool_size(2).with_ttl(Duration::from_secs(60)),
+        );
+        pool.eviction.track_object(99);
+        assert_eq!(pool.check_consistency().issues, ["3 objects tracked for eviction, population 2"]);
+
+        pool.eviction.remove_object(99);
+        pool.population.fetch_add(1, Ordering::AcqRel);
+        let report = pool.check_consistency();
+        assert!(!report.is_consistent());
+        assert_eq!(
+            report.issues,
+            [
+                "population 3 != 2 available + 0 active + 0 pending",
+                "population 3 exceeds capacity 2",
+                "2 objects tracked for eviction, population 3",
+            ]
         );
-
-        let _obj = pool.get_object().unwrap();
-
-        let result = pool.try_get_object();
-        assert!(matches!(result, Err(PoolError::MaxActiveObjectsReached)));
     }
 
     #[test]
-    fn test_try_get_object_propagates_circuit_breaker_error() {
+    fn test_consistency_check_compares_memory_table_with_counts() {
         let pool = ObjectPool::new(
-            vec![1],
-            PoolConfiguration::new().with_circuit_breaker(1, Duration::from_secs(60)),
+            vec![String::from("a"), String::from("b")],
+            PoolConfiguration::new().with_memory_accounting(),
         );
+        let held = pool.get_object().unwrap();
+        assert!(pool.check_consistency().is_consistent());
+
+        // What a return path that skipped the memory table would leave.
+        let memory = pool.eviction.memory().unwrap();
+        let idle_id = 1 - held.object_id;
+        memory.active(idle_id, &String::from("b"));
+        assert_eq!(
+            pool.check_consistency().issues,
+            [
+                "0 objects measured as idle, 1 available",
+                "2 objects measured as checked out, 1 active",
+            ]
+        );
+    }
 
-        let _obj = pool.get_object().unwrap();
+    // ── Limit groups ──────────────────────────────────────────────────────────
 
-        // First empty attempt records a failure and opens the breaker.
-        let first = pool.try_get_object();
-        assert!(matches!(first, Ok(None)));
+    #[test]
+    fn test_limit_group_spans_pool_types() {
+        use crate::LimitGroup;
 
-        let second = pool.try_get_object();
-        assert!(matches!(second, Err(PoolError::CircuitBreakerOpen)));
-    }
+        let created = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&created);
+        let group = LimitGroup::new("db", 2);
+        let fixed = ObjectPool::new(vec![1, 2], PoolConfiguration::new().with_limit_group(group.clone()));
+        let dynamic = DynamicObjectPool::new(
+            move || counter.fetch_add(1, Ordering::Relaxed),
+            PoolConfiguration::new().with_max_pool_size(4).with_limit_group(group.clone()),
+        );
 
-    // ── DynamicObjectPool: circuit breaker does not trip on successful dynamic creation ──
+        let _a = fixed.get_object().unwrap();
+        let _b = dynamic.get_object().unwrap();
+        assert!(matches!(dynamic.get_object(), Err(PoolError::GroupLimitReached(_))));
+        assert_eq!(created.load(Ordering::Relaxed), 1, "factory must not run when the group is full");
+        assert_eq!(dynamic.active_count(), 1);
+        assert_eq!(dynamic.get_metrics().group_limit_rejections, 1);
+        assert_eq!(fixed.get_metrics().group_limit_rejections, 0);
+    }
 
     #[test]
-    fn test_dynamic_pool_cb_does_not_trip_on_routine_creation() {
-        // Threshold = 3: the CB must NOT open when every empty-pool event is
-        // immediately followed by a successful dynamic creation.
-        let pool = DynamicObjectPool::new(
-            || 42,
+    fn test_limit_group_slots_freed_on_every_exit_path() {
+        use crate::LimitGroup;
+
+        let group = LimitGroup::new("g", 1);
+        let pool = QueryableObjectPool::new(
+            vec![1, 2, 3],
             PoolConfiguration::new()
-                .with_max_pool_size(10)
-                .with_circuit_breaker(3, Duration::from_secs(60)),
+                .with_validation(|x: &i32| *x > 0)
+                .with_limit_group(group.clone()),
         );
 
-        // Create 4 objects (all via dynamic creation); the CB should remain closed.
-        let _a = pool.get_object().unwrap();
-        let _b = pool.get_object().unwrap();
-        let _c = pool.get_object().unwrap();
-        let _d = pool.get_object().unwrap();
+        drop(pool.get_object(|x| *x == 1).unwrap());
+        assert_eq!(group.active(), 0);
 
-        let health = pool.get_health_status();
-        assert!(!health.circuit_breaker_open, "CB must stay closed during normal dynamic creation");
-    }
+        let mut bad = pool.get_object(|_| true).unwrap();
+        *bad = -1;
+        drop(bad);
+        assert_eq!(group.active(), 0);
 
-    // ── evict_expired ──────────────────────────────────────────────────────────────────
+        let _value = pool.get_object(|_| true).unwrap().into_detached();
+        assert_eq!(group.active(), 0);
 
-    #[test]
-    fn test_evict_expired_removes_stale_objects() {
-        use std::thread;
+        assert!(pool.get_object(|x| *x == 99).is_err());
+        assert_eq!(group.active(), 0);
 
-        let config = PoolConfiguration::new()
-            .with_ttl(Duration::from_millis(50));
+        let batch: Vec<_> = vec![pool.get_object(|_| true).unwrap()];
+        pool.return_many(batch);
+        assert_eq!(group.active(), 0);
+    }
 
-        let pool = ObjectPool::new(vec![1, 2, 3], config);
+    // ── Bulkheads ─────────────────────────────────────────────────────────────
 
-        thread::sleep(Duration::from_millis(80));
+    #[test]
+    fn test_bulkhead_limits_category_not_pool() {
+        let pool = ObjectPool::new(
+            vec![1, 2, 3, 4],
+            PoolConfiguration::new().with_bulkhead("batch", 2),
+        );
 
-        let evicted = pool.evict_expired();
-        assert_eq!(evicted, 3, "all three objects should have been evicted");
-        assert_eq!(pool.available_count(), 0);
+        let _a = pool.get_object_in("batch").unwrap();
+        let _b = pool.get_object_in("batch").unwrap();
+        assert!(matches!(pool.get_object_in("batch"), Err(PoolError::BulkheadFull(c)) if c == "batch"));
+
+        // Uncategorised acquisitions still see the rest of the pool.
+        let _c = pool.get_object().unwrap();
+        assert_eq!(pool.active_count(), 3);
+        assert_eq!(pool.bulkhead_active("batch"), Some(2));
     }
 
     #[test]
-    fn test_evict_expired_keeps_fresh_objects() {
-        let config = PoolConfiguration::new()
-            .with_ttl(Duration::from_secs(300));
+    fn test_bulkhead_slot_freed_on_return_and_detach() {
+        let pool = ObjectPool::new(vec![1, 2], PoolConfiguration::new().with_bulkhead("w", 1));
 
-        let pool = ObjectPool::new(vec![1, 2, 3], config);
+        pool.get_object_in("w").unwrap().release().unwrap();
+        assert_eq!(pool.bulkhead_active("w"), Some(0));
 
-        let evicted = pool.evict_expired();
-        assert_eq!(evicted, 0);
-        assert_eq!(pool.available_count(), 3);
+        let _value = pool.get_object_in("w").unwrap().into_detached();
+        assert_eq!(pool.bulkhead_active("w"), Some(0));
+        assert!(pool.get_object_in("w").is_ok());
     }
 
-    // ── drain ─────────────────────────────────────────────────────────────────────────
-
     #[test]
-    fn test_drain_returns_available_objects() {
-        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+    fn test_bulkhead_slot_not_held_when_pool_empty() {
+        let pool = ObjectPool::new(vec![1], PoolConfiguration::new().with_bulkhead("w", 1));
+        let _held = pool.get_object().unwrap();
 
-        let drained = pool.drain();
-        assert_eq!(drained.len(), 3);
-        assert_eq!(pool.available_count(), 0);
+        assert!(matches!(pool.get_object_in("w"), Err(PoolError::PoolEmpty)));
+        assert_eq!(pool.bulkhead_active("w"), Some(0));
     }
 
     #[test]
-    fn test_drain_does_not_affect_active_objects() {
-        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
-
-        let _obj = pool.get_object().unwrap(); // 1 active
-        let drained = pool.drain();
-        assert_eq!(drained.len(), 2); // only the 2 available ones
-        assert_eq!(pool.active_count(), 1);
+    fn test_unknown_bulkhead_is_an_error() {
+        let pool = ObjectPool::new(vec![1], PoolConfiguration::default());
+        assert!(matches!(pool.get_object_in("nope"), Err(PoolError::UnknownBulkhead(_))));
+        assert_eq!(pool.bulkhead_active("nope"), None);
+        assert_eq!(pool.available_count(), 1);
     }
 
-    // ── HealthStatus includes circuit-breaker state ───────────────────────────────────
+    #[tokio::test]
+    async fn test_bulkhead_slot_not_held_while_waiting() {
+        let pool = ObjectPool::new(vec![1], PoolConfiguration::new().with_bulkhead("w", 1));
+        let held = pool.get_object().unwrap();
 
-    #[test]
-    fn test_health_status_includes_cb_state() {
-        let pool = ObjectPool::new(
-            vec![1],
-            PoolConfiguration::new().with_circuit_breaker(1, Duration::from_secs(60)),
-        );
+        let (waited, ()) = tokio::join!(pool.get_object_in_async("w"), async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            assert_eq!(pool.bulkhead_active("w"), Some(0));
+            drop(held);
+        });
 
-        // Before the breaker opens the pool should be healthy.
-        assert!(!pool.get_health_status().circuit_breaker_open);
+        let _obj = waited.unwrap();
+        assert_eq!(pool.bulkhead_active("w"), Some(1));
+    }
 
-        let _obj = pool.get_object().unwrap();
-        let _ = pool.try_get_object(); // opens the breaker (threshold = 1)
+    #[tokio::test]
+    async fn test_dynamic_pool_bulkhead_async() {
+        let pool = DynamicObjectPool::new(
+            || 0,
+            PoolConfiguration::new().with_max_pool_size(4).with_bulkhead("slow", 1),
+        );
 
-        let health = pool.get_health_status();
-        assert!(health.circuit_breaker_open);
-        assert!(!health.is_healthy);
-        assert!(health.warnings.iter().any(|w| w.contains("Circuit breaker")));
+        let _slow = pool.get_object_in_async("slow").await.unwrap();
+        assert!(matches!(
+            pool.get_object_in_async("slow").await,
+            Err(PoolError::BulkheadFull(_))
+        ));
+        assert!(pool.get_object_async().await.is_ok());
+        assert_eq!(pool.bulkhead_active("slow"), Some(1));
     }
 
-    // ── DynamicObjectPool: observable counts and eviction ────────────────────────────
+    // ── Dynamic pool population accounting ───────────────────────────────────
 
     #[test]
-    fn test_dynamic_pool_available_and_active_counts() {
+    fn test_warmup_counts_checked_out_objects_toward_capacity() {
+        let created = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&created);
         let pool = DynamicObjectPool::new(
-            || 0u8,
-            PoolConfiguration::new().with_max_pool_size(5),
+            move || counter.fetch_add(1, Ordering::Relaxed),
+            PoolConfiguration::new().with_max_pool_size(3),
         );
 
         let _a = pool.get_object().unwrap();
         let _b = pool.get_object().unwrap();
+        pool.warmup(3).unwrap();
 
-        assert_eq!(pool.active_count(), 2);
-
-        drop(_a);
-        assert_eq!(pool.available_count(), 1);
+        assert_eq!(created.load(Ordering::Relaxed), 3);
+        assert_eq!(pool.available_count() + pool.active_count(), 3);
+        let _c = pool.get_object().unwrap();
+        assert!(matches!(pool.get_object(), Err(PoolError::PoolFull)));
     }
 
-    // ── Validation on return ──────────────────────────────────────────────────
-
     #[test]
-    fn test_validation_failure_removes_object_and_increments_metric() {
-        // Validation rejects any value ≤ 0; we mutate the object to -1 before
-        // returning it so the validator fires.
-        let config = PoolConfiguration::new().with_validation(|x: &i32| *x > 0);
-        let pool = ObjectPool::new(vec![1, 2, 3], config);
-
-        {
-            let mut obj = pool.get_object().unwrap();
-            *obj.get_mut() = -1; // will fail validation on drop
-        }
+    fn test_detached_and_rejected_objects_free_capacity() {
+        let pool = DynamicObjectPool::new(
+            || 1,
+            PoolConfiguration::new()
+                .with_max_pool_size(1)
+                .with_validation(|x: &i32| *x > 0),
+        );
 
-        // One object was rejected, so only 2 remain.
-        assert_eq!(pool.available_count(), 2);
+        let _value = pool.get_object().unwrap().into_detached();
+        let mut obj = pool.get_object().unwrap();
+        *obj = -1;
+        drop(obj);
+        let _obj = pool.get_object().unwrap();
 
-        let metrics = pool.get_metrics();
-        assert_eq!(metrics.validation_failures, 1);
-        assert_eq!(metrics.total_returned, 0); // failed validation ≠ returned
+        assert_eq!(pool.get_metrics().validation_failures, 1);
     }
 
     #[test]
-    fn test_validation_success_returns_object() {
-        let config = PoolConfiguration::new().with_validation(|x: &i32| *x > 0);
-        let pool = ObjectPool::new(vec![1, 2, 3], config);
+    fn test_warmup_concurrent_create_and_return_stay_within_capacity() {
+        const CAPACITY: usize = 8;
+        let created = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&created);
+        let pool = Arc::new(DynamicObjectPool::new(
+            move || counter.fetch_add(1, Ordering::Relaxed),
+            PoolConfiguration::new().with_max_pool_size(CAPACITY),
+        ));
 
-        {
-            let _obj = pool.get_object().unwrap(); // value is > 0, passes
+        let workers: Vec<_> = (0..8)
+            .map(|i| {
+                let pool = Arc::clone(&pool);
+                std::thread::spawn(move || {
+                    for _ in 0..200 {
+                        if i % 4 == 0 {
+                            pool.warmup(2).unwrap();
+                        } else if let Ok(obj) = pool.get_object() {
+                            assert!(pool.available_count() + pool.active_count() <= CAPACITY);
+                            drop(obj);
+                        }
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
         }
 
+        assert!(created.load(Ordering::Relaxed) <= CAPACITY);
+        assert_eq!(pool.active_count(), 0);
+        assert_eq!(pool.available_count(), created.load(Ordering::Relaxed));
+        assert_eq!(pool.get_metrics().queue_push_failures, 0);
+        let report = pool.check_consistency();
+        assert!(report.is_consistent(), "{report}");
+    }
+
+    #[tokio::test]
+    async fn test_warmup_async_respects_population() {
+        let pool = DynamicObjectPool::new(|| 0, PoolConfiguration::new().with_max_pool_size(4));
+        let _held = pool.get_object().unwrap();
+
+        pool.warmup_async(10).await.unwrap();
         assert_eq!(pool.available_count(), 3);
-        let metrics = pool.get_metrics();
-        assert_eq!(metrics.validation_failures, 0);
-        assert_eq!(metrics.total_returned, 1);
     }
 
-    // ── PooledObject Debug impl ───────────────────────────────────────────────
+    // ── Wait policy ───────────────────────────────────────────────────────────
 
     #[test]
-    fn test_pooled_object_debug_does_not_panic() {
-        let pool = ObjectPool::new(vec![42i32], PoolConfiguration::default());
+    fn test_wait_policy_blocks_sync_get_until_return() {
+        use crate::WaitPolicy;
+
+        let pool = Arc::new(ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new().with_wait_on_empty(WaitPolicy::Wait(Duration::from_secs(2))),
+        ));
+        let held = pool.get_object().unwrap();
+
+        let releaser = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(30));
+            drop(held);
+        });
         let obj = pool.get_object().unwrap();
-        let dbg = format!("{:?}", obj);
-        assert!(dbg.contains("PooledObject"));
-        assert!(dbg.contains("42"));
+        assert_eq!(*obj, 1);
+        releaser.join().unwrap();
     }
 
-    // ── DynamicObjectPool::with_initial returns objects to pool ──────────────
-
     #[test]
-    fn test_dynamic_with_initial_returns_on_drop() {
-        let pool = DynamicObjectPool::with_initial(
-            || 99,
-            vec![1, 2, 3],
-            PoolConfiguration::new().with_max_pool_size(5),
+    fn test_try_get_never_waits() {
+        use crate::WaitPolicy;
+
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new().with_wait_on_empty(WaitPolicy::WaitForever),
         );
+        let _held = pool.get_object().unwrap();
+        assert!(pool.try_get_object().unwrap().is_none());
+    }
 
-        assert_eq!(pool.available_count(), 3);
-        {
-            let _obj = pool.get_object().unwrap();
-            assert_eq!(pool.active_count(), 1);
-        }
-        assert_eq!(pool.available_count(), 3);
+    #[tokio::test]
+    async fn test_fail_fast_policy_applies_to_async_get() {
+        use crate::WaitPolicy;
+
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new().with_wait_on_empty(WaitPolicy::FailFast),
+        );
+        let _held = pool.get_object().unwrap();
+        assert!(matches!(pool.get_object_async().await, Err(PoolError::PoolEmpty)));
     }
 
-    // ── QueryableObjectPool::try_get_object error propagation ────────────────
+    #[test]
+    fn test_dynamic_pool_waits_at_capacity() {
+        use crate::WaitPolicy;
+
+        let pool = DynamicObjectPool::new(
+            || 0,
+            PoolConfiguration::new()
+                .with_max_pool_size(1)
+                .with_wait_on_empty(WaitPolicy::Wait(Duration::from_millis(20))),
+        );
+        let _held = pool.get_object().unwrap();
+        assert!(matches!(pool.get_object(), Err(PoolError::Timeout(..))));
+    }
 
     #[test]
-    fn test_queryable_try_get_propagates_max_active_error() {
+    fn test_queryable_pool_honours_wait_policy() {
+        use crate::WaitPolicy;
+
         let pool = QueryableObjectPool::new(
-            vec![1, 2, 3],
-            PoolConfiguration::new().with_max_active_objects(1),
+            vec![1, 2],
+            PoolConfiguration::new().with_wait_on_empty(WaitPolicy::Wait(Duration::from_millis(20))),
         );
+        assert!(matches!(pool.get_object(|x| *x == 3), Err(PoolError::Timeout(..))));
+        assert!(pool.try_get_object(|x| *x == 3).unwrap().is_none());
+    }
 
-        let _obj = pool.get_object(|_| true).unwrap();
-        let result = pool.try_get_object(|_| true);
-        assert!(matches!(result, Err(PoolError::MaxActiveObjectsReached)));
+    // ── Destroy hook and overflow handling ────────────────────────────────────
+
+    fn counting_destroy_config(counter: &Arc<AtomicUsize>) -> PoolConfiguration<i32> {
+        let counter = Arc::clone(counter);
+        PoolConfiguration::new().with_on_destroy(move |_| {
+            counter.fetch_add(1, Ordering::Relaxed);
+        })
+    }
+
+    #[test]
+    fn test_overflow_on_return_goes_through_destroy_hook() {
+        let destroyed = Arc::new(AtomicUsize::new(0));
+        let pool = ObjectPool::new(vec![1], counting_destroy_config(&destroyed).with_max_pool_size(1));
+        let mut errors = pool.return_errors();
+
+        let obj = pool.get_object().unwrap();
+        pool.available.push((2, 999)).unwrap();
+        drop(obj);
+
+        assert_eq!(destroyed.load(Ordering::Relaxed), 1);
+        assert_eq!(pool.get_metrics().queue_push_failures, 1);
+        assert!(matches!(errors.try_recv(), Ok(ReturnError::QueueFull { .. })));
     }
 
     #[test]
-    fn test_queryable_try_get_returns_none_on_no_match() {
-        let pool = QueryableObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
-        let result = pool.try_get_object(|x| *x == 99);
-        assert!(matches!(result, Ok(None)));
-    }
+    fn test_evicted_objects_go_through_destroy_hook() {
+        let destroyed = Arc::new(AtomicUsize::new(0));
+        let pool = ObjectPool::new(
+            vec![1, 2, 3],
+            counting_destroy_config(&destroyed).with_ttl(Duration::from_millis(20)),
+        );
+        std::thread::sleep(Duration::from_millis(40));
 
-    // ── DynamicObjectPool::try_get_object full → Ok(None) ────────────────────
+        assert_eq!(pool.evict_expired(), 3);
+        assert_eq!(destroyed.load(Ordering::Relaxed), 3);
+    }
 
     #[test]
-    fn test_dynamic_try_get_returns_none_when_full() {
+    fn test_warmup_overflow_is_accounted_not_silent() {
+        let destroyed = Arc::new(AtomicUsize::new(0));
+        let created = Arc::new(AtomicUsize::new(0));
+        let created_clone = Arc::clone(&created);
         let pool = DynamicObjectPool::new(
-            || 0,
-            PoolConfiguration::new().with_max_pool_size(1),
+            move || {
+                created_clone.fetch_add(1, Ordering::Relaxed);
+                0
+            },
+            counting_destroy_config(&destroyed).with_max_pool_size(2),
         );
 
-        let _obj = pool.get_object().unwrap(); // fills capacity
-        let result = pool.try_get_object();
-        assert!(matches!(result, Ok(None)));
+        pool.warmup(2).unwrap();
+        // The queue is already full, so no further objects are built.
+        pool.warmup(2).unwrap();
+
+        assert_eq!(created.load(Ordering::Relaxed), 2);
+        assert_eq!(destroyed.load(Ordering::Relaxed), 0);
+        assert_eq!(pool.available_count(), 2);
     }
 
-    // ── Idle-timeout eviction ─────────────────────────────────────────────────
+    // ── Return error reporting ────────────────────────────────────────────────
 
     #[test]
-    fn test_idle_timeout_eviction() {
-        use std::thread;
-
-        let config = PoolConfiguration::new()
-            .with_idle_timeout(Duration::from_millis(50));
+    fn test_return_errors_reports_validation_failure() {
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new().with_validation(|x: &i32| *x > 0),
+        );
+        let mut errors = pool.return_errors();
 
-        let pool = ObjectPool::new(vec![1, 2, 3], config);
-        thread::sleep(Duration::from_millis(80));
+        let mut obj = pool.get_object().unwrap();
+        *obj = 0;
+        let id = obj.id();
+        drop(obj);
 
-        // On checkout the expired objects are skipped.
-        let result = pool.try_get_object().unwrap();
-        assert!(result.is_none() || pool.available_count() < 3);
+        assert_eq!(errors.try_recv().unwrap(), ReturnError::ValidationFailed { object_id: id });
+        assert!(errors.try_recv().is_err());
     }
 
-    // ── Combined TTL + idle-timeout eviction ──────────────────────────────────
-
     #[test]
-    fn test_combined_ttl_idle_eviction_via_evict_expired() {
-        use std::thread;
+    fn test_return_errors_reports_queue_full() {
+        let pool = ObjectPool::new(vec![1], PoolConfiguration::new().with_max_pool_size(1));
+        let mut errors = pool.return_errors();
 
-        let config = PoolConfiguration::new()
-            .with_ttl(Duration::from_millis(50))
-            .with_idle_timeout(Duration::from_secs(300)); // idle won't fire
+        let obj = pool.get_object().unwrap();
+        let id = obj.id();
+        pool.available.push((2, 999)).unwrap();
+        drop(obj);
 
-        let pool = ObjectPool::new(vec![1, 2, 3], config);
-        thread::sleep(Duration::from_millis(80));
+        assert_eq!(errors.try_recv().unwrap(), ReturnError::QueueFull { object_id: id });
+    }
 
-        let evicted = pool.evict_expired();
-        assert_eq!(evicted, 3);
-        assert_eq!(pool.available_count(), 0);
+    #[test]
+    fn test_return_errors_on_delegating_pools() {
+        let pool = DynamicObjectPool::new(
+            || 1,
+            PoolConfiguration::new().with_validation(|x: &i32| *x > 0),
+        );
+        let mut errors = pool.return_errors();
+        let mut obj = pool.get_object().unwrap();
+        *obj = -1;
+        drop(obj);
+        assert!(matches!(errors.try_recv(), Ok(ReturnError::ValidationFailed { .. })));
+
+        let pool = QueryableObjectPool::new(
+            vec![1],
+            PoolConfiguration::new().with_validation(|x: &i32| *x > 0),
+        );
+        let mut errors = pool.return_errors();
+        let obj = pool.get_object(|_| true).unwrap();
+        let mut batch = vec![obj];
+        *batch[0] = -1;
+        assert_eq!(pool.return_many(batch), 0);
+        assert!(matches!(errors.try_recv(), Ok(ReturnError::ValidationFailed { .. })));
     }
 
-    // ── evict_expired / drain on delegating pool types ────────────────────────
+    // ── Explicit release ──────────────────────────────────────────────────────
 
     #[test]
-    fn test_queryable_evict_expired_and_drain() {
-        use std::thread;
+    fn test_release_returns_object_and_reports_ok() {
+        let pool = ObjectPool::new(vec![1], PoolConfiguration::default());
+        let obj = pool.get_object().unwrap();
 
-        let config = PoolConfiguration::new()
-            .with_ttl(Duration::from_millis(50));
-        let pool = QueryableObjectPool::new(vec![1, 2, 3], config);
-        thread::sleep(Duration::from_millis(80));
+        assert!(obj.release().is_ok());
+        assert_eq!(pool.available_count(), 1);
+        assert_eq!(pool.active_count(), 0);
+        assert_eq!(pool.get_metrics().total_returned, 1);
+    }
 
-        let evicted = pool.evict_expired();
-        assert_eq!(evicted, 3);
-        assert_eq!(pool.available_count(), 0);
+    #[test]
+    fn test_release_surfaces_validation_failure() {
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new().with_validation(|x: &i32| *x > 0),
+        );
+        let mut obj = pool.get_object().unwrap();
+        *obj = 0;
 
-        // Drain on empty pool returns empty vec without panic.
-        let drained = pool.drain();
-        assert!(drained.is_empty());
+        assert!(matches!(obj.release(), Err(PoolError::ValidationFailed)));
+        assert_eq!(pool.available_count(), 0);
+        assert_eq!(pool.active_count(), 0);
+        assert_eq!(pool.get_metrics().validation_failures, 1);
     }
 
     #[test]
-    fn test_dynamic_evict_and_drain() {
-        use std::thread;
+    fn test_release_surfaces_queue_full() {
+        let pool = ObjectPool::new(vec![1], PoolConfiguration::new().with_max_pool_size(1));
+        let obj = pool.get_object().unwrap();
+        pool.available.push((2, 999)).unwrap();
 
-        let config = PoolConfiguration::new()
-            .with_ttl(Duration::from_millis(50))
-            .with_max_pool_size(5);
-        let pool = DynamicObjectPool::with_initial(|| 0, vec![1, 2, 3], config);
-        thread::sleep(Duration::from_millis(80));
+        assert!(matches!(obj.release(), Err(PoolError::PoolFull)));
+        assert_eq!(pool.get_metrics().queue_push_failures, 1);
+    }
 
-        let evicted = pool.evict_expired();
-        assert_eq!(evicted, 3);
+    #[tokio::test]
+    async fn test_release_async_reports_outcome() {
+        let pool = ObjectPool::new(
+            vec![1, 2],
+            PoolConfiguration::new().with_validation(|x: &i32| *x > 0),
+        );
+        let good = pool.get_object().unwrap();
+        let mut bad = pool.get_object().unwrap();
+        *bad = -5;
 
-        let drained = pool.drain();
-        assert!(drained.is_empty());
+        assert!(good.release_async().await.is_ok());
+        assert!(matches!(bad.release_async().await, Err(PoolError::ValidationFailed)));
+        assert_eq!(pool.available_count(), 1);
+        assert_eq!(pool.active_count(), 0);
     }
 
-    // ── metrics export on delegating pool types ───────────────────────────────
+    // ── Context hook ──────────────────────────────────────────────────────────
 
     #[test]
-    fn test_queryable_export_metrics() {
-        let pool = QueryableObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
-        let _ = pool.get_object(|_| true).unwrap();
+    fn test_context_hook_attaches_correlation_id() {
+        let counter = Arc::new(AtomicUsize::new(0));
+        let hook_counter = Arc::clone(&counter);
+        let config = PoolConfiguration::new().with_context_hook(move || {
+            Some(format!("corr-{}", hook_counter.fetch_add(1, Ordering::Relaxed)))
+        });
+        let pool = DynamicObjectPool::new(|| 0, config);
 
-        let map = pool.export_metrics();
-        assert!(map.contains_key("total_retrieved"));
-        assert_eq!(map.get("total_retrieved").unwrap(), "1");
+        let a = pool.get_object().unwrap();
+        let b = pool.get_object().unwrap();
+        assert_eq!(a.context(), Some("corr-0"));
+        assert_eq!(b.context(), Some("corr-1"));
+        assert!(format!("{a:?}").contains("corr-0"));
     }
 
     #[test]
-    fn test_dynamic_export_metrics() {
-        let pool = DynamicObjectPool::new(|| 1, PoolConfiguration::new().with_max_pool_size(5));
-        let _ = pool.get_object().unwrap();
-
-        let map = pool.export_metrics();
-        assert!(map.contains_key("total_retrieved"));
-        assert_eq!(map.get("total_retrieved").unwrap(), "1");
+    fn test_no_context_without_hook() {
+        let pool = ObjectPool::new(vec![1], PoolConfiguration::default());
+        assert_eq!(pool.get_object().unwrap().context(), None);
     }
 
-    // ── total_detached in metrics map and Prometheus ──────────────────────────
+    // ── Leases ────────────────────────────────────────────────────────────────
 
     #[test]
-    fn test_export_metrics_includes_total_detached() {
-        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
-
-        let obj = pool.get_object().unwrap();
-        let _ = obj.into_detached();
-
-        let map = pool.export_metrics();
-        assert!(map.contains_key("total_detached"));
-        assert_eq!(map.get("total_detached").unwrap(), "1");
+    fn test_unleased_guard_has_no_lease() {
+        let pool = ObjectPool::new(vec![1], PoolConfiguration::default());
+        assert!(pool.get_object().unwrap().lease().is_none());
     }
 
     #[test]
-    fn test_prometheus_export_includes_detached_counter() {
+    fn test_lease_returned_in_time_is_not_counted() {
         let pool = ObjectPool::new(vec![1], PoolConfiguration::default());
-        let obj = pool.get_object().unwrap();
-        let _ = obj.into_detached();
+        let obj = pool.get_object_leased(Duration::from_secs(60)).unwrap();
+        assert!(!obj.lease().unwrap().expired());
+        drop(obj);
 
-        let prom = pool.export_metrics_prometheus("detach_pool", None);
-        assert!(prom.contains("objectpool_objects_detached_total"));
-        assert!(prom.contains("detach_pool"));
+        assert_eq!(pool.get_metrics().expired_leases, 0);
+        assert_eq!(pool.available_count(), 1);
     }
 
-    // ── warmup capped at capacity ─────────────────────────────────────────────
-
     #[test]
-    fn test_warmup_capped_at_capacity() {
-        let pool = DynamicObjectPool::new(
-            || 0,
-            PoolConfiguration::new().with_max_pool_size(3),
-        );
+    fn test_overrun_lease_counted_on_return_and_exported() {
+        let pool = ObjectPool::new(vec![1], PoolConfiguration::default());
+        let obj = pool.get_object_leased(Duration::from_millis(5)).unwrap();
+        std::thread::sleep(Duration::from_millis(15));
+        drop(obj);
 
-        // Request more than capacity; only 3 should be created.
-        pool.warmup(100).unwrap();
-        assert_eq!(pool.available_count(), 3);
+        assert_eq!(pool.get_metrics().expired_leases, 1);
+        assert_eq!(pool.export_metrics().get("expired_leases").unwrap(), "1");
+        assert!(pool
+            .export_metrics_prometheus("lease_pool", None)
+            .contains("objectpool_leases_expired_total{pool=\"lease_pool\"} 1"));
     }
 
-    // ── concurrent DynamicObjectPool creation stays within capacity ──────────
-
     #[tokio::test]
-    async fn test_dynamic_pool_concurrent_creation_does_not_exceed_capacity() {
-        use std::sync::Arc;
-
-        let pool = Arc::new(DynamicObjectPool::new(
-            || 0u32,
-            PoolConfiguration::new().with_max_pool_size(5),
-        ));
-
-        let mut handles = vec![];
-        for _ in 0..20 {
-            let p = Arc::clone(&pool);
-            handles.push(tokio::spawn(async move { p.get_object() }));
-        }
-
-        let mut objects = vec![];
-        for h in handles {
-            if let Ok(Ok(obj)) = h.await {
-                objects.push(obj);
-            }
-        }
+    async fn test_lease_callback_fires_while_held() {
+        let pool = DynamicObjectPool::new(|| 0, PoolConfiguration::new().with_max_pool_size(1));
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        let obj = pool
+            .get_object_leased_with(Duration::from_millis(10), move || {
+                let _ = tx.send(());
+            })
+            .unwrap();
 
-        // Total live objects (active + available) must never exceed capacity=5.
-        assert!(pool.active_count() + pool.available_count() <= 5);
-        // All successfully obtained objects came from within capacity.
-        assert!(objects.len() <= 5);
+        tokio::time::timeout(Duration::from_secs(1), rx).await.unwrap().unwrap();
+        assert!(obj.lease().unwrap().expired());
+        assert_eq!(pool.get_metrics().expired_leases, 1);
     }
 
-    // ── QueryableObjectPool::get_object_async fails fast on errors ────────────
+    // ── Bulk return ───────────────────────────────────────────────────────────
 
-    #[tokio::test]
-    async fn test_queryable_async_fails_fast_on_max_active() {
-        use std::time::Instant;
+    #[test]
+    fn test_return_many_updates_metrics_once() {
+        let pool = ObjectPool::new(vec![1, 2, 3, 4], PoolConfiguration::default());
+        let guards: Vec<_> = (0..4).map(|_| pool.get_object().unwrap()).collect();
+        assert_eq!(pool.active_count(), 4);
 
-        let pool = QueryableObjectPool::new(
-            vec![1, 2],
-            PoolConfiguration::new()
-                .with_timeout(Duration::from_secs(2))
-                .with_max_active_objects(1),
-        );
-        let _obj = pool.get_object(|_| true).unwrap();
+        assert_eq!(pool.return_many(guards), 4);
 
-        let start = Instant::now();
-        let result = pool.get_object_async(|_| true).await;
-        assert!(start.elapsed() < Duration::from_millis(200));
-        assert!(matches!(result, Err(PoolError::MaxActiveObjectsReached)));
+        assert_eq!(pool.active_count(), 0);
+        assert_eq!(pool.available_count(), 4);
+        assert_eq!(pool.get_metrics().total_returned, 4);
     }
 
-    // ── DynamicObjectPool::get_object_async timeout ───────────────────────────
-
-    #[tokio::test]
-    async fn test_dynamic_async_timeout_when_full() {
-        let pool = DynamicObjectPool::new(
-            || 0,
-            PoolConfiguration::new()
-                .with_max_pool_size(1)
-                .with_timeout(Duration::from_millis(60)),
+    #[test]
+    fn test_return_many_applies_validation() {
+        let pool = ObjectPool::new(
+            vec![1, 2, 3],
+            PoolConfiguration::new().with_validation(|x: &i32| *x > 0),
         );
+        let mut bad = pool.get_object().unwrap();
+        *bad = -1;
+        let good = pool.get_object().unwrap();
 
-        let _obj = pool.get_object().unwrap(); // fills capacity
+        assert_eq!(pool.return_many(vec![bad, good]), 1);
 
-        let result = pool.get_object_async().await;
-        assert!(matches!(result, Err(PoolError::Timeout(_))));
+        let metrics = pool.get_metrics();
+        assert_eq!(metrics.validation_failures, 1);
+        assert_eq!(metrics.total_returned, 1);
+        assert_eq!(pool.active_count(), 0);
+        assert_eq!(pool.available_count(), 2);
     }
 
-    // ── New regression / feature tests ───────────────────────────────────────
-
     #[test]
-    fn test_capacity_method() {
-        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::new().with_max_pool_size(10));
-        assert_eq!(pool.capacity(), 10);
-
-        let q = QueryableObjectPool::new(vec![1, 2], PoolConfiguration::default());
-        assert_eq!(q.capacity(), 100); // default max_pool_size
+    fn test_return_many_ends_leases_before_recycling() {
+        let pool = ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new().with_on_return(|_: &mut i32| std::thread::sleep(Duration::from_millis(40))),
+        );
+        let expired = Arc::new(AtomicBool::new(false));
+        let flag = Arc::clone(&expired);
+        let obj = pool
+            .get_object_leased_with(Duration::from_millis(10), move || flag.store(true, Ordering::Relaxed))
+            .unwrap();
 
-        let d = DynamicObjectPool::new(|| 0, PoolConfiguration::new().with_max_pool_size(7));
-        assert_eq!(d.capacity(), 7);
+        assert_eq!(pool.return_many(vec![obj]), 1);
+        assert!(!expired.load(Ordering::Relaxed), "the lease ended when the object was handed back");
+        assert_eq!(pool.get_metrics().expired_leases, 0);
     }
 
     #[test]
-    fn test_get_metrics_on_delegate_pools() {
-        let q = QueryableObjectPool::new(vec![1, 2], PoolConfiguration::default());
-        let _ = q.get_object(|_| true).unwrap();
-        assert_eq!(q.get_metrics().total_retrieved, 1);
+    fn test_return_many_hands_foreign_guards_back_to_their_pool() {
+        let pool_a = ObjectPool::new(vec![1], PoolConfiguration::default());
+        let pool_b = ObjectPool::new(vec![2], PoolConfiguration::default());
+        let a = pool_a.get_object().unwrap();
+        let b = pool_b.get_object().unwrap();
 
-        let d = DynamicObjectPool::new(|| 0, PoolConfiguration::new().with_max_pool_size(5));
-        let _ = d.get_object().unwrap();
-        assert_eq!(d.get_metrics().total_retrieved, 1);
+        assert_eq!(pool_a.return_many(vec![a, b]), 1);
+
+        assert_eq!(pool_a.available_count(), 1);
+        assert_eq!(pool_b.available_count(), 1);
+        assert_eq!(pool_b.active_count(), 0);
     }
 
     #[test]
-    fn test_as_ref_as_mut_for_pooled_object() {
-        let pool = ObjectPool::new(vec![42i32], PoolConfiguration::default());
-        let mut obj = pool.get_object().unwrap();
+    fn test_get_batch_is_all_or_nothing() {
+        let pool = ObjectPool::new(vec![1, 2], PoolConfiguration::default());
 
-        // AsRef
-        let r: &i32 = obj.as_ref();
-        assert_eq!(*r, 42);
+        let result = pool.get_batch(3);
+        assert!(matches!(result, Err(PoolError::PoolEmpty)));
+        assert_eq!(pool.available_count(), 2);
+        assert_eq!(pool.active_count(), 0);
+    }
 
-        // AsMut
-        *obj.as_mut() = 99;
-        assert_eq!(*obj.as_ref(), 99);
+    #[test]
+    fn test_dynamic_get_batch_creates_objects() {
+        let pool = DynamicObjectPool::new(|| 5, PoolConfiguration::new().with_max_pool_size(4));
+        {
+            let batch = pool.get_batch(3).unwrap();
+            assert_eq!(batch.len(), 3);
+        }
+        assert_eq!(pool.available_count(), 3);
+        assert_eq!(pool.active_count(), 0);
     }
 
     #[test]
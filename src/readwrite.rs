@@ -0,0 +1,336 @@
+//! Read/write split pool pairs
+
+use crate::errors::PoolResult;
+use crate::metrics::{merge_families, PoolMetrics};
+use crate::observable::ObservablePool;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A pool of read-only connections paired with a pool of writable ones, the
+/// usual replica/primary database topology
+///
+/// [`acquire_read`](Self::acquire_read) draws from the read pool and
+/// [`acquire_write`](Self::acquire_write) from the write pool. With
+/// [`with_read_fallback`](Self::with_read_fallback), a read that the read
+/// pool cannot serve (exhausted, breaker open, closed, ...) is retried on the
+/// write pool instead of failing; each such read is counted in
+/// [`ReadWriteMetrics::read_fallbacks`].
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{ObjectPool, PoolConfiguration, ReadWritePool};
+///
+/// let config = || PoolConfiguration::new().with_max_pool_size(1);
+/// let db = ReadWritePool::new(
+///     ObjectPool::new(vec!["replica"], config()),
+///     ObjectPool::new(vec!["primary"], config()),
+/// )
+/// .with_read_fallback();
+///
+/// let read = db.acquire_read(|pool| pool.get_object()).unwrap();
+/// assert_eq!(*read, "replica");
+///
+/// // The replica is checked out, so the next read goes to the primary.
+/// let fallback = db.acquire_read(|pool| pool.get_object()).unwrap();
+/// assert_eq!(*fallback, "primary");
+/// assert_eq!(db.get_metrics().read_fallbacks, 1);
+/// ```
+pub struct ReadWritePool<P> {
+    read: P,
+    write: P,
+    read_fallback: bool,
+    read_fallbacks: AtomicUsize,
+}
+
+impl<P: ObservablePool> ReadWritePool<P> {
+    /// Pair `read` with `write`; reads never touch the write pool
+    pub fn new(read: P, write: P) -> Self {
+        Self {
+            read,
+            write,
+            read_fallback: false,
+            read_fallbacks: AtomicUsize::new(0),
+        }
+    }
+
+    /// Serve reads from the write pool when the read pool fails them
+    #[must_use]
+    pub fn with_read_fallback(mut self) -> Self {
+        self.read_fallback = true;
+        self
+    }
+
+    /// Run `acquire` against the read pool, then against the write pool if
+    /// that failed and read fallback is enabled
+    ///
+    /// The error returned when both fail is the write pool's.
+    pub fn acquire_read<R>(&self, acquire: impl Fn(&P) -> PoolResult<R>) -> PoolResult<R> {
+        match acquire(&self.read) {
+            Err(_) if self.read_fallback => {
+                self.read_fallbacks.fetch_add(1, Ordering::Relaxed);
+                acquire(&self.write)
+            }
+            result => result,
+        }
+    }
+
+    /// Run `acquire` against the read pool, falling back as
+    /// [`acquire_read`](Self::acquire_read) does
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration, ReadWritePool};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let db = ReadWritePool::new(
+    ///     ObjectPool::new(vec![1], PoolConfiguration::default()),
+    ///     ObjectPool::new(vec![2], PoolConfiguration::default()),
+    /// );
+    /// let obj = db.acquire_read_async(|pool| pool.get_object_async()).await.unwrap();
+    /// assert_eq!(*obj, 1);
+    /// # }
+    /// ```
+    pub async fn acquire_read_async<'a, R, F>(&'a self, acquire: impl Fn(&'a P) -> F) -> PoolResult<R>
+    where
+        F: Future<Output = PoolResult<R>>,
+    {
+        match acquire(&self.read).await {
+            Err(_) if self.read_fallback => {
+                self.read_fallbacks.fetch_add(1, Ordering::Relaxed);
+                acquire(&self.write).await
+            }
+            result => result,
+        }
+    }
+
+    /// Run `acquire` against the write pool
+    pub fn acquire_write<R>(&self, acquire: impl FnOnce(&P) -> PoolResult<R>) -> PoolResult<R> {
+        acquire(&self.write)
+    }
+
+    /// Run `acquire` against the write pool
+    pub async fn acquire_write_async<'a, R, F>(&'a self, acquire: impl FnOnce(&'a P) -> F) -> PoolResult<R>
+    where
+        F: Future<Output = PoolResult<R>>,
+    {
+        acquire(&self.write).await
+    }
+
+    /// The read pool
+    #[must_use]
+    pub fn read(&self) -> &P {
+        &self.read
+    }
+
+    /// The write pool
+    #[must_use]
+    pub fn write(&self) -> &P {
+        &self.write
+    }
+
+    /// Whether reads fall back to the write pool
+    #[must_use]
+    pub fn has_read_fallback(&self) -> bool {
+        self.read_fallback
+    }
+
+    /// Metrics of both pools and the number of reads served by the write pool
+    #[must_use]
+    pub fn get_metrics(&self) -> ReadWriteMetrics {
+        ReadWriteMetrics {
+            read: self.read.get_metrics(),
+            write: self.write.get_metrics(),
+            read_fallbacks: self.read_fallbacks.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Both pools' metrics as one key/value map, keyed `read.<metric>` and
+    /// `write.<metric>`, plus `read_fallbacks`
+    #[must_use]
+    pub fn export_metrics(&self) -> HashMap<String, String> {
+        let mut map = HashMap::new();
+        for (role, pool) in [("read", &self.read), ("write", &self.write)] {
+            for (key, value) in pool.export_metrics() {
+                map.insert(format!("{role}.{key}"), value);
+            }
+        }
+        map.insert(
+            "read_fallbacks".to_string(),
+            self.read_fallbacks.load(Ordering::Relaxed).to_string(),
+        );
+        map
+    }
+
+    /// Both pools' metrics in Prometheus text exposition format
+    ///
+    /// Each series carries a `role="read"` or `role="write"` label next to
+    /// `pool` and `tags`, and `objectpool_read_fallbacks_total` counts reads
+    /// served by the write pool.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration, ReadWritePool};
+    ///
+    /// let db = ReadWritePool::new(
+    ///     ObjectPool::new(vec![1], PoolConfiguration::default()),
+    ///     ObjectPool::new(vec![2], PoolConfiguration::default()),
+    /// );
+    /// let output = db.export_metrics_prometheus("db", None);
+    ///
+    /// assert!(output.contains("objectpool_objects_available{pool=\"db\",role=\"read\"} 1"));
+    /// assert!(output.contains("objectpool_objects_available{pool=\"db\",role=\"write\"} 1"));
+    /// assert!(output.contains("objectpool_read_fallbacks_total{pool=\"db\"} 0"));
+    /// ```
+    #[must_use]
+    pub fn export_metrics_prometheus(
+        &self,
+        pool_name: &str,
+        tags: Option<&HashMap<String, String>>,
+    ) -> String {
+        let role_tags = |role: &str| {
+            let mut tags = tags.cloned().unwrap_or_default();
+            tags.insert("role".to_string(), role.to_string());
+            tags
+        };
+        let read = self.read.export_metrics_prometheus(pool_name, Some(&role_tags("read")));
+        let write = self.write.export_metrics_prometheus(pool_name, Some(&role_tags("write")));
+
+        let mut labels = vec![format!("pool=\"{pool_name}\"")];
+        labels.extend(tags.into_iter().flatten().map(|(key, value)| format!("{key}=\"{value}\"")));
+        let fallbacks = format!(
+            "# HELP objectpool_read_fallbacks_total Reads served by the write pool\n\
+             # TYPE objectpool_read_fallbacks_total counter\n\
+             objectpool_read_fallbacks_total{{{}}} {}\n",
+            labels.join(","),
+            self.read_fallbacks.load(Ordering::Relaxed),
+        );
+        merge_families(&[&read, &write, &fallbacks])
+    }
+}
+
+/// Metrics of a [`ReadWritePool`]
+#[derive(Debug, Clone)]
+pub struct ReadWriteMetrics {
+    /// The read pool's metrics
+    pub read: PoolMetrics,
+
+    /// The write pool's metrics
+    pub write: PoolMetrics,
+
+    /// Reads the read pool failed and the write pool served or attempted
+    pub read_fallbacks: usize,
+}
+
+impl ReadWriteMetrics {
+    /// Objects checked out across both pools
+    #[must_use]
+    pub fn active_objects(&self) -> usize {
+        self.read.active_objects + self.write.active_objects
+    }
+
+    /// Objects idle across both pools
+    #[must_use]
+    pub fn available_objects(&self) -> usize {
+        self.read.available_objects + self.write.available_objects
+    }
+
+    /// Objects retrieved across both pools
+    #[must_use]
+    pub fn total_retrieved(&self) -> usize {
+        self.read.total_retrieved + self.write.total_retrieved
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::PoolError;
+    use crate::{ObjectPool, PoolConfiguration};
+
+    fn pair() -> ReadWritePool<ObjectPool<&'static str>> {
+        let config = || PoolConfiguration::new().with_max_pool_size(1);
+        ReadWritePool::new(
+            ObjectPool::new(vec!["replica"], config()),
+            ObjectPool::new(vec!["primary"], config()),
+        )
+    }
+
+    #[test]
+    fn reads_do_not_fall_back_by_default() {
+        let db = pair();
+        let _held = db.acquire_read(|pool| pool.get_object()).unwrap();
+
+        assert!(matches!(
+            db.acquire_read(|pool| pool.get_object()),
+            Err(PoolError::PoolEmpty)
+        ));
+        assert_eq!(db.write().available_count(), 1);
+        assert_eq!(db.get_metrics().read_fallbacks, 0);
+    }
+
+    #[test]
+    fn writes_never_use_the_read_pool() {
+        let db = pair().with_read_fallback();
+        let _write = db.acquire_write(|pool| pool.get_object()).unwrap();
+
+        assert!(db.acquire_write(|pool| pool.get_object()).is_err());
+        assert_eq!(db.read().available_count(), 1);
+    }
+
+    #[test]
+    fn fallback_reports_the_write_pools_error() {
+        let db = pair().with_read_fallback();
+        let _read = db.acquire_read(|pool| pool.get_object()).unwrap();
+        let _write = db.acquire_write(|pool| pool.get_object()).unwrap();
+
+        assert!(matches!(
+            db.acquire_read(|pool| pool.get_object()),
+            Err(PoolError::PoolEmpty)
+        ));
+        assert_eq!(db.get_metrics().read_fallbacks, 1);
+    }
+
+    #[tokio::test]
+    async fn async_reads_fall_back() {
+        let db = pair().with_read_fallback();
+        let _read = db.acquire_read_async(|pool| pool.get_object_async()).await.unwrap();
+        db.read().shutdown();
+
+        let obj = db.acquire_read_async(|pool| pool.get_object_async()).await.unwrap();
+        assert_eq!(*obj, "primary");
+    }
+
+    #[test]
+    fn metrics_cover_both_pools() {
+        let db = pair().with_read_fallback();
+        let _read = db.acquire_read(|pool| pool.get_object()).unwrap();
+        let _fallback = db.acquire_read(|pool| pool.get_object()).unwrap();
+
+        let metrics = db.get_metrics();
+        assert_eq!(metrics.active_objects(), 2);
+        assert_eq!(metrics.available_objects(), 0);
+        assert_eq!(metrics.total_retrieved(), 2);
+
+        let map = db.export_metrics();
+        assert_eq!(map["read.total_retrieved"], "1");
+        assert_eq!(map["write.total_retrieved"], "1");
+        assert_eq!(map["read_fallbacks"], "1");
+    }
+
+    #[test]
+    fn prometheus_export_groups_each_family() {
+        let db = pair();
+        let output = db.export_metrics_prometheus("db", None);
+
+        let help = "# HELP objectpool_objects_active Current active objects";
+        assert_eq!(output.matches(help).count(), 1);
+        let read = output.find("objectpool_objects_active{pool=\"db\",role=\"read\"}").unwrap();
+        let write = output.find("objectpool_objects_active{pool=\"db\",role=\"write\"}").unwrap();
+        assert_eq!(write, output[read..].find('\n').unwrap() + read + 1);
+    }
+}
@@ -0,0 +1,352 @@
+//! Connection pools that replace broken connections and retry
+
+use crate::config::PoolConfiguration;
+use crate::errors::{GuardedError, PoolError};
+use crate::health::HealthStatus;
+use crate::metrics::PoolMetrics;
+use crate::observable::ObservablePool;
+use crate::pool::{DynamicObjectPool, PooledObject};
+use crate::wait::Backoff;
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Opens a new connection.
+type Connect<T, E> = Arc<dyn Fn() -> Pin<Box<dyn Future<Output = Result<T, E>> + Send>> + Send + Sync>;
+/// Decides whether an error means the connection it came from is broken.
+type IsBroken<E> = Arc<dyn Fn(&E) -> bool + Send + Sync>;
+
+/// Dynamic pool of connections opened by an async factory, replacing a
+/// connection that breaks and retrying the work once on a new one
+///
+/// [`run`](Self::run) hands `op` a pooled connection, reusing an idle one
+/// or opening one with `connect`. When `op` fails with an error that
+/// `is_broken` classifies as a broken connection, the connection is
+/// [discarded](PooledObject::discard), a new one is opened and `op` runs
+/// once more on it. The retry's result is returned whatever it is, and a
+/// connection broken again is discarded as well. Other outcomes give the
+/// connection back through [`complete`](PooledObject::complete), so the
+/// pool's [`FailurePolicy`](crate::FailurePolicy) and circuit breaker see
+/// them as usual; a broken connection counts as a failure too.
+///
+/// A connection is opened only once the pool has room for it, under the
+/// configuration's creation rate limit and pending-creation cap, so a full
+/// pool waits for an idle connection or for room like any other creation.
+/// `connect` is then retried with exponential backoff, 3 attempts from
+/// 50 ms up to 1 s by default (see
+/// [`with_connect_attempts`](Self::with_connect_attempts) and
+/// [`with_backoff`](Self::with_backoff)). The last connection error fails
+/// the call as `PoolError::CreationFailed` and counts as a creation
+/// failure, including for the creation backoff. The pool itself can be
+/// watched through [`ObservablePool`].
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{PoolConfiguration, ReconnectingPool};
+/// use std::sync::atomic::{AtomicBool, Ordering};
+/// use std::sync::Arc;
+///
+/// struct Conn {
+///     alive: Arc<AtomicBool>,
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// // The backend drops the first connection it hands out.
+/// let first = Arc::new(AtomicBool::new(true));
+/// let pool = ReconnectingPool::new(
+///     move || {
+///         let alive = !first.swap(false, Ordering::Relaxed);
+///         async move { Ok::<_, String>(Conn { alive: Arc::new(AtomicBool::new(alive)) }) }
+///     },
+///     |err: &String| err == "connection reset",
+///     PoolConfiguration::new().with_max_pool_size(4),
+/// );
+///
+/// let reply = pool
+///     .run(|conn| {
+///         Box::pin(async move {
+///             if conn.alive.load(Ordering::Relaxed) { Ok("pong") } else { Err("connection reset".to_string()) }
+///         })
+///     })
+///     .await;
+/// assert_eq!(reply.unwrap(), "pong");
+/// assert_eq!(pool.reconnects(), 1);
+/// assert_eq!(pool.get_metrics().discarded_objects, 1);
+/// # }
+/// ```
+pub struct ReconnectingPool<T: Send, E> {
+    pool: DynamicObjectPool<T>,
+    connect: Connect<T, E>,
+    is_broken: IsBroken<E>,
+    connect_attempts: usize,
+    backoff: Backoff,
+    reconnects: AtomicU64,
+}
+
+impl<T: Send + Sync + 'static, E: std::fmt::Display> ReconnectingPool<T, E> {
+    /// Create a pool opening connections with `connect`, treating errors
+    /// for which `is_broken` returns `true` as broken connections
+    pub fn new<C, Fut, B>(connect: C, is_broken: B, config: PoolConfiguration<T>) -> Self
+    where
+        C: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<T, E>> + Send + 'static,
+        B: Fn(&E) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            pool: DynamicObjectPool::try_new(
+                || Err::<T, _>("connections of a ReconnectingPool are opened asynchronously"),
+                config,
+            ),
+            connect: Arc::new(move || Box::pin(connect())),
+            is_broken: Arc::new(is_broken),
+            connect_attempts: 3,
+            backoff: Backoff {
+                initial: Duration::from_millis(50),
+                max: Duration::from_secs(1),
+            },
+            reconnects: AtomicU64::new(0),
+        }
+    }
+
+    /// Try `connect` at most `attempts` times per connection (default: 3)
+    ///
+    /// # Panics
+    ///
+    /// Panics if `attempts` is 0.
+    pub fn with_connect_attempts(mut self, attempts: usize) -> Self {
+        assert!(attempts > 0, "ReconnectingPool needs at least one connect attempt");
+        self.connect_attempts = attempts;
+        self
+    }
+
+    /// Wait `initial` before the first connect retry, doubling up to `max`
+    /// (default: 50 ms up to 1 s)
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.backoff = Backoff { initial, max };
+        self
+    }
+
+    /// Run `op` on a pooled connection, reconnecting and retrying once if
+    /// the connection turns out to be broken
+    ///
+    /// # Errors
+    ///
+    /// `GuardedError::Pool` when no connection could be acquired or
+    /// opened, `GuardedError::Operation` with the error of the last run of
+    /// `op`.
+    pub async fn run<R, F>(&self, mut op: F) -> Result<R, GuardedError<E>>
+    where
+        F: for<'c> FnMut(&'c mut T) -> Pin<Box<dyn Future<Output = Result<R, E>> + Send + 'c>>,
+    {
+        let mut conn = self.acquire(false).await?;
+        let result = op(&mut conn).await;
+        match self.give_back(conn, result) {
+            Err(err) if (self.is_broken)(&err) => {
+                self.reconnects.fetch_add(1, Ordering::Relaxed);
+                let mut conn = self.acquire(true).await?;
+                let result = op(&mut conn).await;
+                self.give_back(conn, result).map_err(GuardedError::Operation)
+            }
+            result => result.map_err(GuardedError::Operation),
+        }
+    }
+
+    /// Get a connection, reusing an idle one or opening one
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`DynamicObjectPool::get_object_async`], or with
+    /// `PoolError::CreationFailed` once every connect attempt failed.
+    pub async fn get_object_async(&self) -> Result<PooledObject<T>, PoolError> {
+        self.acquire(false).await
+    }
+
+    /// Broken connections replaced by [`run`](Self::run)
+    #[must_use]
+    pub fn reconnects(&self) -> u64 {
+        self.reconnects.load(Ordering::Relaxed)
+    }
+
+    /// Metrics of the underlying pool
+    #[must_use]
+    pub fn get_metrics(&self) -> PoolMetrics {
+        self.pool.get_metrics()
+    }
+
+    /// Discard `conn` if `result` says it is broken, otherwise complete it
+    /// with `result`.
+    fn give_back<R>(&self, conn: PooledObject<T>, result: Result<R, E>) -> Result<R, E> {
+        match result {
+            Err(err) if (self.is_broken)(&err) => {
+                conn.discard();
+                self.pool.report_failure();
+                Err(err)
+            }
+            result => conn.complete(result),
+        }
+    }
+
+    /// An idle connection, unless `fresh`, or else a newly opened one.
+    async fn acquire(&self, fresh: bool) -> Result<PooledObject<T>, PoolError> {
+        self.pool.get_object_opening_async(fresh, || self.open()).await
+    }
+
+    /// Call `connect` until it succeeds or runs out of attempts.
+    async fn open(&self) -> Result<T, String> {
+        let mut attempt = 1;
+        loop {
+            match (self.connect)().await {
+                Ok(conn) => return Ok(conn),
+                Err(err) if attempt == self.connect_attempts => return Err(err.to_string()),
+                Err(_) => {
+                    tokio::time::sleep(self.backoff.delay(attempt as u64 - 1)).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+}
+
+impl<T: Send + Sync + 'static, E> ObservablePool for ReconnectingPool<T, E> {
+    fn available_count(&self) -> usize {
+        self.pool.available_count()
+    }
+
+    fn active_count(&self) -> usize {
+        self.pool.active_count()
+    }
+
+    fn capacity(&self) -> usize {
+        self.pool.capacity()
+    }
+
+    fn get_metrics(&self) -> PoolMetrics {
+        self.pool.get_metrics()
+    }
+
+    fn get_health_status(&self) -> HealthStatus {
+        self.pool.get_health_status()
+    }
+
+    fn export_metrics(&self) -> HashMap<String, String> {
+        self.pool.export_metrics()
+    }
+
+    fn export_metrics_prometheus(
+        &self,
+        pool_name: &str,
+        tags: Option<&HashMap<String, String>>,
+    ) -> String {
+        self.pool.export_metrics_prometheus(pool_name, tags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WaitPolicy;
+    use std::sync::atomic::AtomicUsize;
+
+    /// Connections numbered from 1; the first `failing` connect calls fail.
+    fn numbered_pool(failing: usize) -> (ReconnectingPool<usize, String>, Arc<AtomicUsize>) {
+        numbered_pool_with(failing, PoolConfiguration::new().with_max_pool_size(2))
+    }
+
+    fn numbered_pool_with(
+        failing: usize,
+        config: PoolConfiguration<usize>,
+    ) -> (ReconnectingPool<usize, String>, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let pool = ReconnectingPool::new(
+            move || {
+                let call = counter.fetch_add(1, Ordering::Relaxed) + 1;
+                async move {
+                    if call <= failing { Err(format!("refused {call}")) } else { Ok(call - failing) }
+                }
+            },
+            |err: &String| err.starts_with("broken"),
+            config,
+        )
+        .with_backoff(Duration::from_millis(1), Duration::from_millis(2));
+        (pool, calls)
+    }
+
+    #[tokio::test]
+    async fn broken_connection_is_replaced_and_the_work_retried_once() {
+        let (pool, _) = numbered_pool(0);
+        let result: Result<(), _> = pool
+            .run(|conn| Box::pin(async move { Err(format!("broken {conn}")) }))
+            .await;
+        assert!(matches!(result, Err(GuardedError::Operation(err)) if err == "broken 2"));
+        assert_eq!(pool.reconnects(), 1);
+        assert_eq!(pool.get_metrics().discarded_objects, 2);
+
+        let seen = pool.run(|conn| Box::pin(async move { Ok::<_, String>(*conn) })).await;
+        assert_eq!(seen.unwrap(), 3, "the retry's broken connection was not reused");
+    }
+
+    #[tokio::test]
+    async fn other_errors_keep_the_connection() {
+        let (pool, calls) = numbered_pool(0);
+        let result: Result<(), _> = pool
+            .run(|_| Box::pin(async { Err("no such table".to_string()) }))
+            .await;
+        assert!(matches!(result, Err(GuardedError::Operation(_))));
+        assert_eq!(pool.reconnects(), 0);
+        assert_eq!(pool.available_count(), 1);
+
+        assert_eq!(*pool.get_object_async().await.unwrap(), 1);
+        assert_eq!(calls.load(Ordering::Relaxed), 1, "the idle connection was reused");
+    }
+
+    #[tokio::test]
+    async fn connect_is_retried_with_backoff_until_attempts_run_out() {
+        let (pool, calls) = numbered_pool(2);
+        assert_eq!(*pool.get_object_async().await.unwrap(), 1);
+        assert_eq!(calls.load(Ordering::Relaxed), 3);
+
+        let (pool, calls) = numbered_pool(5);
+        let pool = pool.with_connect_attempts(2);
+        assert!(matches!(
+            pool.get_object_async().await,
+            Err(PoolError::CreationFailed(reason)) if reason == "refused 2"
+        ));
+        assert_eq!(calls.load(Ordering::Relaxed), 2);
+        assert_eq!(pool.active_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn full_pool_does_not_connect() {
+        let (pool, calls) = numbered_pool_with(
+            0,
+            PoolConfiguration::new()
+                .with_max_pool_size(1)
+                .with_wait_on_empty(WaitPolicy::FailFast),
+        );
+        let _held = pool.get_object_async().await.unwrap();
+        assert!(matches!(pool.get_object_async().await, Err(PoolError::PoolFull)));
+        assert_eq!(calls.load(Ordering::Relaxed), 1, "no connection is opened without room for it");
+    }
+
+    #[tokio::test]
+    async fn connect_failures_count_as_creation_failures() {
+        let config = PoolConfiguration::new()
+            .with_creation_backoff(Duration::from_secs(60), Duration::from_secs(60))
+            .with_wait_on_empty(WaitPolicy::FailFast);
+        let (pool, calls) = numbered_pool_with(5, config);
+        let pool = pool.with_connect_attempts(2);
+        assert!(matches!(pool.get_object_async().await, Err(PoolError::CreationFailed(_))));
+        assert_eq!(pool.get_metrics().creation_failures, 1);
+
+        assert!(matches!(pool.get_object_async().await, Err(PoolError::CreationThrottled)));
+        assert_eq!(calls.load(Ordering::Relaxed), 2, "the creation backoff holds off connecting");
+        assert_eq!(pool.active_count(), 0);
+    }
+}
@@ -0,0 +1,393 @@
+//! Periodic metric snapshots written to CSV files
+
+use crate::metrics::PoolMetrics;
+use crate::observable::ObservablePool;
+
+use crossbeam::channel::{self, RecvTimeoutError, Sender};
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+type Column = (&'static str, fn(&PoolMetrics) -> String);
+
+/// Metric columns after `timestamp_ms` and `pool`, in file order.
+const COLUMNS: &[Column] = &[
+    ("total_retrieved", |m| m.total_retrieved.to_string()),
+    ("total_returned", |m| m.total_returned.to_string()),
+    ("active_objects", |m| m.active_objects.to_string()),
+    ("available_objects", |m| m.available_objects.to_string()),
+    ("total_objects", |m| m.total_objects.to_string()),
+    ("max_capacity", |m| m.max_capacity.to_string()),
+    ("utilization", |m| format!("{:.4}", m.utilization)),
+    ("pool_empty_events", |m| m.pool_empty_events.to_string()),
+    ("validation_failures", |m| m.validation_failures.to_string()),
+    ("creation_failures", |m| m.creation_failures.to_string()),
+    ("discarded_objects", |m| m.discarded_objects.to_string()),
+    ("expired_leases", |m| m.expired_leases.to_string()),
+    ("waiter_rejections", |m| m.waiter_rejections.to_string()),
+    ("capacity_lost", |m| m.capacity_lost.to_string()),
+    ("paused", |m| m.paused.to_string()),
+];
+
+type Watched = (String, Arc<dyn ObservablePool + Send + Sync>);
+
+/// Appends periodic snapshots of pool metrics to a CSV file, for capacity
+/// planning and other offline analysis
+///
+/// Each snapshot writes one row per watched pool: a Unix timestamp in
+/// milliseconds, the pool's name, and its counters and gauges from
+/// [`PoolMetrics`]. A header row starts every file. Snapshots are taken on
+/// a background thread when recording starts, every interval (10 seconds
+/// by default) and once more when it stops.
+///
+/// An existing file is appended to. With
+/// [`with_max_file_size`](Self::with_max_file_size) or
+/// [`with_max_file_age`](Self::with_max_file_age), a full or old file is
+/// renamed before the next snapshot, `metrics.csv` becoming
+/// `metrics.1.csv`, then `metrics.2.csv` and so on, and a fresh file is
+/// started.
+///
+/// Only CSV is written. Parquet output is out of scope: it would pull an
+/// Arrow stack into the crate, and the CSV files load directly into
+/// pandas, polars or DuckDB, which convert them to Parquet when needed.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{MetricsRecorder, ObjectPool, PoolConfiguration};
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// let dir = std::env::temp_dir().join(format!("objectpool-recorder-doc-{}", std::process::id()));
+/// std::fs::create_dir_all(&dir).unwrap();
+/// let path = dir.join("metrics.csv");
+///
+/// let pool = Arc::new(ObjectPool::new(vec![1, 2], PoolConfiguration::default()));
+/// let recording = MetricsRecorder::csv(&path)
+///     .with_interval(Duration::from_secs(60))
+///     .with_pool("db", Arc::clone(&pool))
+///     .start()
+///     .unwrap();
+///
+/// let _obj = pool.get_object().unwrap();
+/// recording.stop().unwrap();
+///
+/// let csv = std::fs::read_to_string(&path).unwrap();
+/// let rows: Vec<_> = csv.lines().collect();
+/// assert!(rows[0].starts_with("timestamp_ms,pool,total_retrieved,"));
+/// assert_eq!(rows.len(), 3, "header, start and stop");
+/// assert!(rows[2].contains(",db,1,"));
+/// # std::fs::remove_dir_all(&dir).unwrap();
+/// ```
+pub struct MetricsRecorder {
+    path: PathBuf,
+    interval: Duration,
+    max_file_size: Option<u64>,
+    max_file_age: Option<Duration>,
+    pools: Vec<Watched>,
+}
+
+impl MetricsRecorder {
+    /// Record to the CSV file at `path`
+    pub fn csv(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            interval: Duration::from_secs(10),
+            max_file_size: None,
+            max_file_age: None,
+            pools: Vec::new(),
+        }
+    }
+
+    /// Take a snapshot every `interval` (at least 1 ms)
+    #[must_use]
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval.max(Duration::from_millis(1));
+        self
+    }
+
+    /// Record `pool` in rows named `name`
+    #[must_use]
+    pub fn with_pool(
+        mut self,
+        name: impl Into<String>,
+        pool: Arc<impl ObservablePool + Send + Sync + 'static>,
+    ) -> Self {
+        self.pools.push((name.into(), pool));
+        self
+    }
+
+    /// Start a new file once the current one has reached `bytes`
+    #[must_use]
+    pub fn with_max_file_size(mut self, bytes: u64) -> Self {
+        self.max_file_size = Some(bytes);
+        self
+    }
+
+    /// Start a new file once the current one has been written to for `age`
+    #[must_use]
+    pub fn with_max_file_age(mut self, age: Duration) -> Self {
+        self.max_file_age = Some(age);
+        self
+    }
+
+    /// Open the file and start taking snapshots on a background thread
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of opening or writing to the file.
+    pub fn start(self) -> io::Result<Recording> {
+        let mut file = CsvFile::open(self.path.clone())?;
+        file.write_snapshot(&self.pools)?;
+
+        let (stop, stopped) = channel::bounded::<()>(0);
+        let worker = thread::Builder::new()
+            .name("objectpool-recorder".into())
+            .spawn(move || -> io::Result<()> {
+                loop {
+                    let stopping = !matches!(stopped.recv_timeout(self.interval), Err(RecvTimeoutError::Timeout));
+                    if self.max_file_size.is_some_and(|max| file.written >= max)
+                        || self.max_file_age.is_some_and(|max| file.opened.elapsed() >= max)
+                    {
+                        file = file.rotate()?;
+                    }
+                    file.write_snapshot(&self.pools)?;
+                    if stopping {
+                        return Ok(());
+                    }
+                }
+            })?;
+        Ok(Recording {
+            stop: Some(stop),
+            worker: Some(worker),
+        })
+    }
+}
+
+/// A running [`MetricsRecorder`]; stops recording when dropped
+pub struct Recording {
+    stop: Option<Sender<()>>,
+    worker: Option<JoinHandle<io::Result<()>>>,
+}
+
+impl Recording {
+    /// Take a last snapshot and stop recording
+    ///
+    /// # Errors
+    ///
+    /// Returns the write or rotation error that ended recording early, if
+    /// any.
+    pub fn stop(mut self) -> io::Result<()> {
+        self.stop.take();
+        match self.worker.take().map(JoinHandle::join) {
+            Some(Ok(result)) => result,
+            Some(Err(panic)) => std::panic::resume_unwind(panic),
+            None => Ok(()),
+        }
+    }
+
+    /// Whether recording ended early on an error, returned by
+    /// [`stop`](Self::stop)
+    #[must_use]
+    pub fn has_failed(&self) -> bool {
+        self.worker.as_ref().is_some_and(JoinHandle::is_finished)
+    }
+}
+
+impl Drop for Recording {
+    fn drop(&mut self) {
+        // Disconnecting wakes the worker for its final snapshot.
+        self.stop.take();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl std::fmt::Debug for MetricsRecorder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MetricsRecorder")
+            .field("path", &self.path)
+            .field("interval", &self.interval)
+            .field("max_file_size", &self.max_file_size)
+            .field("max_file_age", &self.max_file_age)
+            .field("pools", &self.pools.iter().map(|(name, _)| name).collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl std::fmt::Debug for Recording {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Recording").field("failed", &self.has_failed()).finish()
+    }
+}
+
+/// The file currently recorded to.
+struct CsvFile {
+    path: PathBuf,
+    out: BufWriter<File>,
+    written: u64,
+    opened: Instant,
+}
+
+impl CsvFile {
+    /// Open `path` for appending, writing the header if it is empty.
+    fn open(path: PathBuf) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let written = file.metadata()?.len();
+        let mut file = Self {
+            path,
+            out: BufWriter::new(file),
+            written,
+            opened: Instant::now(),
+        };
+        if written == 0 {
+            let header: Vec<_> = ["timestamp_ms", "pool"]
+                .into_iter()
+                .chain(COLUMNS.iter().map(|(name, _)| *name))
+                .collect();
+            file.write_line(&header.join(","))?;
+        }
+        Ok(file)
+    }
+
+    fn write_snapshot(&mut self, pools: &[Watched]) -> io::Result<()> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        for (name, pool) in pools {
+            let metrics = pool.get_metrics();
+            let mut row = format!("{timestamp},{}", escape(name));
+            for (_, value) in COLUMNS {
+                row.push(',');
+                row.push_str(&value(&metrics));
+            }
+            self.write_line(&row)?;
+        }
+        self.out.flush()
+    }
+
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        writeln!(self.out, "{line}")?;
+        self.written += line.len() as u64 + 1;
+        Ok(())
+    }
+
+    /// Move the file aside under the first free numbered name and start
+    /// a new one.
+    fn rotate(mut self) -> io::Result<Self> {
+        self.out.flush()?;
+        let stem = self.path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+        let extension = self.path.extension().map(|ext| format!(".{}", ext.to_string_lossy()));
+        let rotated = (1..)
+            .map(|n| self.path.with_file_name(format!("{stem}.{n}{}", extension.as_deref().unwrap_or(""))))
+            .find(|candidate| !candidate.exists())
+            .expect("some numbered file name is free");
+        fs::rename(&self.path, rotated)?;
+        Self::open(self.path)
+    }
+}
+
+/// Quote `field` if it contains a CSV delimiter, quote or line break.
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DynamicObjectPool, ObjectPool, PoolConfiguration};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("objectpool-recorder-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn rows_per_pool_and_snapshot_under_one_header() {
+        let dir = temp_dir("rows");
+        let path = dir.join("metrics.csv");
+        let fixed = Arc::new(ObjectPool::new(vec![1], PoolConfiguration::default()));
+        let dynamic = Arc::new(DynamicObjectPool::new(|| 0, PoolConfiguration::default()));
+        let recorder = || {
+            MetricsRecorder::csv(&path)
+                .with_interval(Duration::from_secs(3600))
+                .with_pool("fixed", Arc::clone(&fixed))
+                .with_pool("a \"quoted\", name", Arc::clone(&dynamic))
+        };
+
+        recorder().start().unwrap().stop().unwrap();
+        drop(recorder().start().unwrap());
+
+        let csv = fs::read_to_string(&path).unwrap();
+        let rows: Vec<_> = csv.lines().collect();
+        assert_eq!(rows.len(), 1 + 2 * 2 * 2, "one header, appended to");
+        assert_eq!(rows[0].split(',').count(), 2 + COLUMNS.len());
+        assert!(rows[1].contains(",fixed,0,0,0,1,"));
+        assert!(rows[2].contains(",\"a \"\"quoted\"\", name\",0,"));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn periodic_snapshots_rotate_by_size() {
+        let dir = temp_dir("rotate");
+        let path = dir.join("metrics.csv");
+        let pool = Arc::new(ObjectPool::new(vec![1], PoolConfiguration::default()));
+        let recording = MetricsRecorder::csv(&path)
+            .with_interval(Duration::from_millis(5))
+            .with_max_file_size(1)
+            .with_pool("p", pool)
+            .start()
+            .unwrap();
+
+        while !dir.join("metrics.2.csv").exists() {
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert!(!recording.has_failed());
+        recording.stop().unwrap();
+
+        for file in ["metrics.1.csv", "metrics.2.csv", "metrics.csv"] {
+            let csv = fs::read_to_string(dir.join(file)).unwrap();
+            assert!(csv.starts_with("timestamp_ms,pool,"), "{file} has a header");
+            assert_eq!(csv.lines().count(), 2, "{file} has one snapshot");
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn rotates_by_age() {
+        let dir = temp_dir("age");
+        let path = dir.join("metrics");
+        let pool = Arc::new(ObjectPool::new(vec![1], PoolConfiguration::default()));
+        let recording = MetricsRecorder::csv(&path)
+            .with_interval(Duration::from_secs(3600))
+            .with_max_file_age(Duration::ZERO)
+            .with_pool("p", pool)
+            .start()
+            .unwrap();
+        recording.stop().unwrap();
+
+        assert_eq!(fs::read_to_string(dir.join("metrics.1")).unwrap().lines().count(), 2);
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 2);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn start_fails_on_an_unwritable_path() {
+        let dir = temp_dir("missing");
+        let recorder = MetricsRecorder::csv(dir.join("no-such-dir").join("metrics.csv"));
+        assert!(recorder.start().is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
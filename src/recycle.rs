@@ -0,0 +1,264 @@
+//! Clear-on-return support for collection types
+
+use crate::config::PoolConfiguration;
+use crate::pool::DynamicObjectPool;
+
+use std::collections::VecDeque;
+
+/// Types that can be emptied for reuse without giving up their allocation
+///
+/// Implemented for `Vec<T>`, `String` and `VecDeque<T>`. A pool configured
+/// with [`with_recycling`](PoolConfiguration::with_recycling) recycles every
+/// object given back to it. With the `derive` feature, `#[derive(Recycle)]`
+/// implements it for structs from `#[pool(...)]` field attributes.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::Recycle;
+///
+/// let mut line = String::with_capacity(1024);
+/// line.push_str("GET / HTTP/1.1");
+/// line.recycle();
+/// assert!(line.is_empty());
+/// assert!(line.capacity() >= 1024);
+///
+/// line.cap_capacity(16);
+/// assert!(line.capacity() < 1024);
+/// ```
+pub trait Recycle {
+    /// Empty the value for its next user, keeping its allocation
+    fn recycle(&mut self);
+
+    /// Give back memory beyond `max_capacity` elements, if any is held
+    fn cap_capacity(&mut self, max_capacity: usize);
+
+    /// Whether a returned value may go back into the pool; `true` unless
+    /// overridden
+    ///
+    /// Not called by the pool itself: pass `MyType::is_reusable` to
+    /// [`with_validation`](PoolConfiguration::with_validation) to destroy
+    /// values that fail it.
+    fn is_reusable(&self) -> bool {
+        true
+    }
+}
+
+impl<T> Recycle for Vec<T> {
+    fn recycle(&mut self) {
+        self.clear();
+    }
+
+    fn cap_capacity(&mut self, max_capacity: usize) {
+        if self.capacity() > max_capacity {
+            self.shrink_to(max_capacity);
+        }
+    }
+}
+
+impl Recycle for String {
+    fn recycle(&mut self) {
+        self.clear();
+    }
+
+    fn cap_capacity(&mut self, max_capacity: usize) {
+        if self.capacity() > max_capacity {
+            self.shrink_to(max_capacity);
+        }
+    }
+}
+
+impl<T> Recycle for VecDeque<T> {
+    fn recycle(&mut self) {
+        self.clear();
+    }
+
+    fn cap_capacity(&mut self, max_capacity: usize) {
+        if self.capacity() > max_capacity {
+            self.shrink_to(max_capacity);
+        }
+    }
+}
+
+impl<T: Recycle + 'static> PoolConfiguration<T> {
+    /// Recycle every object given back to the pool, and shrink any that
+    /// grew past `max_capacity` elements
+    ///
+    /// The cap keeps one oversized use from pinning its memory in the pool
+    /// for good; pass `usize::MAX` to never shrink. Installs an
+    /// [`on_return`](Self::with_on_return) hook, replacing any set before.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration};
+    ///
+    /// let pool = ObjectPool::new(
+    ///     vec![Vec::<u32>::with_capacity(16)],
+    ///     PoolConfiguration::new().with_recycling(64),
+    /// );
+    ///
+    /// pool.get_object().unwrap().extend(0..10_000);
+    ///
+    /// let ids = pool.get_object().unwrap();
+    /// assert!(ids.is_empty());
+    /// assert!(ids.capacity() <= 64);
+    /// ```
+    pub fn with_recycling(self, max_capacity: usize) -> Self {
+        self.with_on_return(move |obj: &mut T| {
+            obj.recycle();
+            obj.cap_capacity(max_capacity);
+        })
+    }
+}
+
+/// A pool of reusable strings
+pub type StringPool = DynamicObjectPool<String>;
+
+/// A pool of reusable vectors
+pub type VecPool<T> = DynamicObjectPool<Vec<T>>;
+
+/// A pool of reusable byte vectors
+pub type BytesPool = VecPool<u8>;
+
+impl<T: Recycle + Default + Send + Sync + 'static> DynamicObjectPool<T> {
+    /// Create a pool of empty collections that recycles returned ones,
+    /// shrinking any that grew past `max_capacity`
+    ///
+    /// New objects start as `T::default()`. See
+    /// [`PoolConfiguration::with_recycling`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{PoolConfiguration, StringPool};
+    /// use std::fmt::Write;
+    ///
+    /// let strings = StringPool::recycling(4096, PoolConfiguration::default());
+    ///
+    /// let mut line = strings.get_object().unwrap();
+    /// write!(line, "{} {}", "GET", "/index.html").unwrap();
+    /// assert_eq!(*line, "GET /index.html");
+    /// drop(line);
+    ///
+    /// assert!(strings.get_object().unwrap().is_empty());
+    /// ```
+    pub fn recycling(max_capacity: usize, config: PoolConfiguration<T>) -> Self {
+        Self::new(T::default, config.with_recycling(max_capacity))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BytesPool, VecPool};
+    #[cfg(feature = "derive")]
+    use crate::{ObjectPool, PoolError, Recycle};
+
+    #[test]
+    fn recycled_vectors_keep_capacity_under_the_cap() {
+        let pool: VecPool<u64> = VecPool::recycling(1024, PoolConfiguration::default());
+        let mut values = pool.get_object().unwrap();
+        values.extend(0..100);
+        let capacity = values.capacity();
+        drop(values);
+
+        let values = pool.get_object().unwrap();
+        assert!(values.is_empty());
+        assert_eq!(values.capacity(), capacity);
+    }
+
+    #[test]
+    fn oversized_buffers_are_shrunk_on_return() {
+        let pool = BytesPool::recycling(256, PoolConfiguration::default());
+        pool.get_object().unwrap().resize(1 << 20, 0);
+
+        assert!(pool.get_object().unwrap().capacity() <= 256);
+    }
+
+    #[test]
+    fn deques_recycle() {
+        let mut queue: VecDeque<i32> = (0..50).collect();
+        queue.recycle();
+        assert!(queue.is_empty());
+        assert!(queue.capacity() >= 50);
+
+        queue.cap_capacity(4);
+        assert!(queue.capacity() < 50);
+    }
+
+    #[cfg(feature = "derive")]
+    #[derive(Default, Recycle)]
+    #[pool(validate = Frame::intact)]
+    struct Frame {
+        #[pool(clear)]
+        payload: Vec<u8>,
+        #[pool(clear)]
+        tags: Vec<String>,
+        #[pool(reset)]
+        sequence: u64,
+        #[pool(reset = 1)]
+        version: u8,
+        connection: u32,
+        broken: bool,
+    }
+
+    #[cfg(feature = "derive")]
+    impl Frame {
+        fn intact(&self) -> bool {
+            !self.broken
+        }
+    }
+
+    #[cfg(feature = "derive")]
+    #[derive(Recycle)]
+    struct Pair(#[pool(clear)] String, #[pool(reset)] usize, u8);
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derived_recycle_resets_attributed_fields_only() {
+        let mut frame = Frame {
+            payload: Vec::with_capacity(256),
+            sequence: 9,
+            version: 3,
+            connection: 42,
+            ..Frame::default()
+        };
+        frame.payload.extend_from_slice(b"data");
+        frame.tags.push("hot".to_string());
+
+        frame.recycle();
+        assert!(frame.payload.is_empty() && frame.tags.is_empty());
+        assert!(frame.payload.capacity() >= 256);
+        assert_eq!((frame.sequence, frame.version, frame.connection), (0, 1, 42));
+
+        frame.cap_capacity(8);
+        assert!(frame.payload.capacity() <= 8);
+
+        let mut pair = Pair("abc".to_string(), 5, 7);
+        pair.recycle();
+        assert_eq!((pair.0.as_str(), pair.1, pair.2), ("", 0, 7));
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn derived_validation_destroys_unusable_objects() {
+        let pool = ObjectPool::new(
+            vec![Frame::default()],
+            PoolConfiguration::new()
+                .with_recycling(64)
+                .with_validation(Frame::is_reusable),
+        );
+        let mut frame = pool.get_object().unwrap();
+        frame.payload.extend_from_slice(b"data");
+        frame.sequence = 3;
+        frame.release().unwrap();
+        assert!(pool.get_object().unwrap().payload.is_empty());
+
+        let mut frame = pool.get_object().unwrap();
+        frame.broken = true;
+        assert!(matches!(frame.release(), Err(PoolError::ValidationFailed)));
+        assert_eq!(pool.available_count(), 0);
+        assert!(Pair(String::new(), 0, 0).is_reusable(), "validation defaults to true");
+    }
+}
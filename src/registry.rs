@@ -0,0 +1,523 @@
+//! Named pools with fleet-wide health and metrics roll-ups
+
+use crate::health::HealthStatus;
+use crate::observable::ObservablePool;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, PoisonError, RwLock};
+
+type Registered = Arc<dyn ObservablePool + Send + Sync>;
+
+/// Health of one pool, or of a fleet of pools, for roll-ups
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PoolState {
+    /// Healthy with no warnings
+    Healthy,
+
+    /// Healthy, but with warnings such as an empty pool or lost capacity
+    Degraded,
+
+    /// Not healthy: over 90% utilized, circuit breaker open or paused
+    Unhealthy,
+}
+
+impl PoolState {
+    /// The state a pool's health status rolls up to
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{ObjectPool, PoolConfiguration, PoolState};
+    ///
+    /// let pool = ObjectPool::new(vec![1, 2], PoolConfiguration::default());
+    /// assert_eq!(PoolState::of(&pool.get_health_status()), PoolState::Healthy);
+    ///
+    /// pool.pause();
+    /// assert_eq!(PoolState::of(&pool.get_health_status()), PoolState::Unhealthy);
+    /// ```
+    #[must_use]
+    pub fn of(health: &HealthStatus) -> Self {
+        if !health.is_healthy {
+            Self::Unhealthy
+        } else if !health.warnings.is_empty() {
+            Self::Degraded
+        } else {
+            Self::Healthy
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Healthy => "healthy",
+            Self::Degraded => "degraded",
+            Self::Unhealthy => "unhealthy",
+        }
+    }
+}
+
+impl fmt::Display for PoolState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One pool's part of a [`FleetHealth`]
+#[derive(Debug, Clone)]
+pub struct PoolHealth {
+    /// Name the pool was registered under
+    pub name: String,
+
+    /// What its health rolls up to
+    pub state: PoolState,
+
+    /// Its health status
+    pub health: HealthStatus,
+}
+
+/// Health of every pool in a [`PoolRegistry`], rolled up into one state
+///
+/// Returned by [`PoolRegistry::aggregate_health`].
+#[derive(Debug, Clone)]
+pub struct FleetHealth {
+    /// The fleet's state: `Unhealthy` if any pool is, `Degraded` if more
+    /// pools are degraded than the registry's threshold, `Healthy` otherwise
+    pub state: PoolState,
+
+    /// Every pool, in registration order
+    pub pools: Vec<PoolHealth>,
+}
+
+impl FleetHealth {
+    /// Number of pools in `state`
+    #[must_use]
+    pub fn count(&self, state: PoolState) -> usize {
+        self.pools.iter().filter(|pool| pool.state == state).count()
+    }
+}
+
+/// Metrics of every pool in a [`PoolRegistry`], summed
+///
+/// Returned by [`PoolRegistry::aggregate_metrics`]. Counters are totals
+/// over the pools; utilization is their average.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FleetMetrics {
+    /// Number of pools
+    pub pools: usize,
+
+    /// Pools currently paused
+    pub paused_pools: usize,
+
+    /// Objects retrieved from any pool
+    pub total_retrieved: usize,
+
+    /// Objects returned to any pool
+    pub total_returned: usize,
+
+    /// Objects currently checked out
+    pub active_objects: usize,
+
+    /// Objects currently idle
+    pub available_objects: usize,
+
+    /// Objects the pools own
+    pub total_objects: usize,
+
+    /// Combined capacity
+    pub max_capacity: usize,
+
+    /// Acquisitions that found a pool empty
+    pub pool_empty_events: usize,
+
+    /// Objects that failed validation
+    pub validation_failures: usize,
+
+    /// Factory calls that failed
+    pub creation_failures: usize,
+
+    /// Objects discarded instead of being returned
+    pub discarded_objects: usize,
+
+    /// Leases that expired
+    pub expired_leases: usize,
+
+    /// Hooks that panicked
+    pub hook_panics: usize,
+
+    /// Acquisitions turned away by a full wait queue
+    pub waiter_rejections: usize,
+
+    /// Objects fixed pools have lost for good
+    pub capacity_lost: usize,
+
+    /// Estimated bytes held by idle objects, in pools with memory accounting
+    pub idle_bytes: usize,
+
+    /// Estimated bytes held by checked-out objects, in pools with memory
+    /// accounting
+    pub active_bytes: usize,
+
+    /// Mean utilization of the pools (0.0 to 1.0); `0.0` without pools
+    pub average_utilization: f64,
+}
+
+impl FleetMetrics {
+    /// Export the metrics as a key-value map, as
+    /// [`PoolMetrics::export`](crate::PoolMetrics::export) does for one pool
+    #[must_use]
+    pub fn export(&self) -> HashMap<String, String> {
+        self.fields()
+            .into_iter()
+            .map(|(key, value)| (key.to_owned(), value.to_string()))
+            .chain([("average_utilization".to_owned(), format!("{:.4}", self.average_utilization))])
+            .collect()
+    }
+
+    /// The counters by name.
+    fn fields(&self) -> [(&'static str, usize); 18] {
+        [
+            ("pools", self.pools),
+            ("paused_pools", self.paused_pools),
+            ("total_retrieved", self.total_retrieved),
+            ("total_returned", self.total_returned),
+            ("active_objects", self.active_objects),
+            ("available_objects", self.available_objects),
+            ("total_objects", self.total_objects),
+            ("max_capacity", self.max_capacity),
+            ("pool_empty_events", self.pool_empty_events),
+            ("validation_failures", self.validation_failures),
+            ("creation_failures", self.creation_failures),
+            ("discarded_objects", self.discarded_objects),
+            ("expired_leases", self.expired_leases),
+            ("hook_panics", self.hook_panics),
+            ("waiter_rejections", self.waiter_rejections),
+            ("capacity_lost", self.capacity_lost),
+            ("idle_bytes", self.idle_bytes),
+            ("active_bytes", self.active_bytes),
+        ]
+    }
+}
+
+/// A set of named pools, for fleet-wide health and metrics
+///
+/// Pools of any type can be registered, since only their
+/// [`ObservablePool`] side is used. [`aggregate_health`](Self::aggregate_health)
+/// rolls their health up into one [`PoolState`] and
+/// [`aggregate_metrics`](Self::aggregate_metrics) sums their metrics, so a
+/// single status endpoint can report on the whole process; with the `serde`
+/// feature both results are serializable.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{DynamicObjectPool, ObjectPool, PoolConfiguration, PoolRegistry, PoolState};
+/// use std::sync::Arc;
+///
+/// let registry = PoolRegistry::new().with_degraded_threshold(1);
+/// let numbers = Arc::new(ObjectPool::new(vec![1, 2], PoolConfiguration::new().with_max_pool_size(2)));
+/// let buffers = Arc::new(DynamicObjectPool::new(Vec::<u8>::new, PoolConfiguration::new().with_max_pool_size(8)));
+/// registry.register("numbers", Arc::clone(&numbers));
+/// registry.register("buffers", buffers);
+///
+/// let _held = numbers.get_object().unwrap();
+/// let metrics = registry.aggregate_metrics();
+/// assert_eq!((metrics.pools, metrics.active_objects, metrics.max_capacity), (2, 1, 10));
+///
+/// // An empty dynamic pool has no idle objects, which is worth a warning.
+/// let health = registry.aggregate_health();
+/// assert_eq!(health.count(PoolState::Degraded), 1);
+/// assert_eq!(health.state, PoolState::Healthy, "one degraded pool is tolerated");
+/// ```
+pub struct PoolRegistry {
+    pools: RwLock<Vec<(String, Registered)>>,
+    degraded_threshold: usize,
+}
+
+impl PoolRegistry {
+    /// An empty registry that reports the fleet as degraded as soon as one
+    /// pool is
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            pools: RwLock::new(Vec::new()),
+            degraded_threshold: 0,
+        }
+    }
+
+    /// Tolerate up to `pools` degraded pools before reporting the fleet as
+    /// degraded (default 0)
+    #[must_use]
+    pub fn with_degraded_threshold(mut self, pools: usize) -> Self {
+        self.degraded_threshold = pools;
+        self
+    }
+
+    /// Add `pool` under `name`, replacing any pool registered under it
+    pub fn register<P: ObservablePool + Send + Sync + 'static>(&self, name: impl Into<String>, pool: Arc<P>) {
+        let name = name.into();
+        let mut pools = self.pools.write().unwrap_or_else(PoisonError::into_inner);
+        match pools.iter_mut().find(|(existing, _)| *existing == name) {
+            Some(entry) => entry.1 = pool,
+            None => pools.push((name, pool)),
+        }
+    }
+
+    /// Remove the pool registered under `name`; `false` if there was none
+    pub fn unregister(&self, name: &str) -> bool {
+        let mut pools = self.pools.write().unwrap_or_else(PoisonError::into_inner);
+        let before = pools.len();
+        pools.retain(|(existing, _)| existing != name);
+        pools.len() < before
+    }
+
+    /// The pool registered under `name`
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<Arc<dyn ObservablePool + Send + Sync>> {
+        self.pools
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .find(|(existing, _)| existing == name)
+            .map(|(_, pool)| Arc::clone(pool))
+    }
+
+    /// Names of the registered pools, in registration order
+    #[must_use]
+    pub fn names(&self) -> Vec<String> {
+        self.snapshot().into_iter().map(|(name, _)| name).collect()
+    }
+
+    /// Number of registered pools
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.pools.read().unwrap_or_else(PoisonError::into_inner).len()
+    }
+
+    /// Whether no pool is registered
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Every pool's health, rolled up: `Unhealthy` if any pool is
+    /// unhealthy, `Degraded` if more pools are degraded than the threshold
+    /// set with [`with_degraded_threshold`](Self::with_degraded_threshold),
+    /// `Healthy` otherwise, including when no pool is registered
+    #[must_use]
+    pub fn aggregate_health(&self) -> FleetHealth {
+        let pools: Vec<PoolHealth> = self
+            .snapshot()
+            .into_iter()
+            .map(|(name, pool)| {
+                let health = pool.get_health_status();
+                PoolHealth { name, state: PoolState::of(&health), health }
+            })
+            .collect();
+        let mut fleet = FleetHealth { state: PoolState::Healthy, pools };
+        if fleet.count(PoolState::Unhealthy) > 0 {
+            fleet.state = PoolState::Unhealthy;
+        } else if fleet.count(PoolState::Degraded) > self.degraded_threshold {
+            fleet.state = PoolState::Degraded;
+        }
+        fleet
+    }
+
+    /// Every pool's counters summed, with their average utilization
+    #[must_use]
+    pub fn aggregate_metrics(&self) -> FleetMetrics {
+        let mut fleet = FleetMetrics::default();
+        let mut utilization = 0.0;
+        for (_, pool) in self.snapshot() {
+            let metrics = pool.get_metrics();
+            fleet.pools += 1;
+            fleet.paused_pools += usize::from(metrics.paused);
+            fleet.total_retrieved += metrics.total_retrieved;
+            fleet.total_returned += metrics.total_returned;
+            fleet.active_objects += metrics.active_objects;
+            fleet.available_objects += metrics.available_objects;
+            fleet.total_objects += metrics.total_objects;
+            fleet.max_capacity += metrics.max_capacity;
+            fleet.pool_empty_events += metrics.pool_empty_events;
+            fleet.validation_failures += metrics.validation_failures;
+            fleet.creation_failures += metrics.creation_failures;
+            fleet.discarded_objects += metrics.discarded_objects;
+            fleet.expired_leases += metrics.expired_leases;
+            fleet.hook_panics += metrics.hook_panics;
+            fleet.waiter_rejections += metrics.waiter_rejections;
+            fleet.capacity_lost += metrics.capacity_lost;
+            fleet.idle_bytes += metrics.idle_bytes;
+            fleet.active_bytes += metrics.active_bytes;
+            utilization += metrics.utilization;
+        }
+        if fleet.pools > 0 {
+            fleet.average_utilization = utilization / fleet.pools as f64;
+        }
+        fleet
+    }
+
+    /// The registered pools, so none is queried while the lock is held.
+    fn snapshot(&self) -> Vec<(String, Registered)> {
+        self.pools.read().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+}
+
+impl Default for PoolRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for PoolRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PoolRegistry")
+            .field("pools", &self.names())
+            .field("degraded_threshold", &self.degraded_threshold)
+            .finish()
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serialize {
+    use super::{FleetHealth, FleetMetrics, PoolHealth, PoolState};
+
+    use serde_core::ser::{SerializeStruct, Serializer};
+    use serde_core::Serialize;
+
+    impl Serialize for PoolState {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(self.as_str())
+        }
+    }
+
+    impl Serialize for PoolHealth {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut pool = serializer.serialize_struct("PoolHealth", 7)?;
+            pool.serialize_field("name", &self.name)?;
+            pool.serialize_field("state", &self.state)?;
+            pool.serialize_field("utilization", &self.health.utilization)?;
+            pool.serialize_field("active_objects", &self.health.active_objects)?;
+            pool.serialize_field("available_objects", &self.health.available_objects)?;
+            pool.serialize_field("total_capacity", &self.health.total_capacity)?;
+            pool.serialize_field("warnings", &self.health.warnings)?;
+            pool.end()
+        }
+    }
+
+    impl Serialize for FleetHealth {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut fleet = serializer.serialize_struct("FleetHealth", 2)?;
+            fleet.serialize_field("state", &self.state)?;
+            fleet.serialize_field("pools", &self.pools)?;
+            fleet.end()
+        }
+    }
+
+    impl Serialize for FleetMetrics {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let fields = self.fields();
+            let mut fleet = serializer.serialize_struct("FleetMetrics", fields.len() + 1)?;
+            for (key, value) in fields {
+                fleet.serialize_field(key, &value)?;
+            }
+            fleet.serialize_field("average_utilization", &self.average_utilization)?;
+            fleet.end()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ObjectPool, PoolConfiguration};
+
+    fn pool(objects: usize) -> Arc<ObjectPool<usize>> {
+        Arc::new(ObjectPool::new(
+            (0..objects).collect(),
+            PoolConfiguration::new().with_max_pool_size(objects),
+        ))
+    }
+
+    #[test]
+    fn any_unhealthy_pool_makes_the_fleet_unhealthy() {
+        let registry = PoolRegistry::new().with_degraded_threshold(5);
+        let (a, b) = (pool(2), pool(2));
+        registry.register("a", Arc::clone(&a));
+        registry.register("b", Arc::clone(&b));
+        assert_eq!(registry.aggregate_health().state, PoolState::Healthy);
+
+        b.pause();
+        let health = registry.aggregate_health();
+        assert_eq!(health.state, PoolState::Unhealthy);
+        assert_eq!(health.pools[1].name, "b");
+        assert_eq!(health.pools[1].state, PoolState::Unhealthy);
+        assert_eq!(health.count(PoolState::Healthy), 1);
+    }
+
+    #[test]
+    fn degraded_pools_over_the_threshold_degrade_the_fleet() {
+        let registry = PoolRegistry::new().with_degraded_threshold(1);
+        let pools: Vec<_> = (0..3).map(|_| pool(20)).collect();
+        for (i, pool) in pools.iter().enumerate() {
+            registry.register(format!("p{i}"), Arc::clone(pool));
+        }
+        // Discarding half of a fixed pool's objects warns of lost capacity.
+        let lose_half = |pool: &ObjectPool<usize>| {
+            for _ in 0..11 {
+                pool.get_object().unwrap().discard();
+            }
+        };
+
+        lose_half(&pools[0]);
+        assert_eq!(registry.aggregate_health().state, PoolState::Healthy);
+        lose_half(&pools[1]);
+        let health = registry.aggregate_health();
+        assert_eq!(health.count(PoolState::Degraded), 2);
+        assert_eq!(health.state, PoolState::Degraded);
+    }
+
+    #[test]
+    fn metrics_are_summed_and_utilization_averaged() {
+        let registry = PoolRegistry::new();
+        assert_eq!(registry.aggregate_metrics(), FleetMetrics::default());
+
+        let (a, b) = (pool(4), pool(2));
+        registry.register("a", Arc::clone(&a));
+        registry.register("b", Arc::clone(&b));
+        let _held = (a.get_object().unwrap(), b.get_object().unwrap());
+        drop(b.get_object().unwrap());
+        b.pause();
+
+        let metrics = registry.aggregate_metrics();
+        assert_eq!(metrics.pools, 2);
+        assert_eq!(metrics.paused_pools, 1);
+        assert_eq!(metrics.total_retrieved, 3);
+        assert_eq!(metrics.active_objects, 2);
+        assert_eq!(metrics.max_capacity, 6);
+        assert!((metrics.average_utilization - (0.25 + 0.5) / 2.0).abs() < 1e-9);
+        assert_eq!(metrics.export()["total_retrieved"], "3");
+        assert_eq!(metrics.export()["average_utilization"], "0.3750");
+    }
+
+    #[test]
+    fn registering_a_name_again_replaces_the_pool() {
+        let registry = PoolRegistry::new();
+        registry.register("a", pool(1));
+        registry.register("b", pool(1));
+        registry.register("a", pool(3));
+        assert_eq!(registry.names(), ["a", "b"]);
+        assert_eq!(registry.get("a").unwrap().capacity(), 3);
+
+        assert!(registry.unregister("a"));
+        assert!(!registry.unregister("a"));
+        assert!(registry.get("a").is_none());
+        assert_eq!(registry.len(), 1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn roll_ups_are_serializable() {
+        fn assert_serialize<S: serde_core::Serialize>() {}
+        assert_serialize::<FleetHealth>();
+        assert_serialize::<FleetMetrics>();
+    }
+}
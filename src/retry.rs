@@ -0,0 +1,132 @@
+//! Retrying operations on a fresh object when the current one is broken
+
+use crate::errors::{GuardedError, PoolResult};
+use crate::pool::PooledObject;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+/// When [`execute_with_retry`](crate::ObjectPool::execute_with_retry)
+/// gives up on an object and tries again with another
+///
+/// An error for which `is_broken` returns `true` means the object itself is
+/// unusable, such as a connection reset by its peer: the object is
+/// [discarded](PooledObject::discard) and the operation retried on another
+/// one, up to `max_attempts` attempts in total. Any other error is returned
+/// at once and the object goes back to the pool as usual.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::RetryPolicy;
+/// use std::io::{Error, ErrorKind};
+/// use std::time::Duration;
+///
+/// let policy = RetryPolicy::new(3, |err: &Error| {
+///     matches!(err.kind(), ErrorKind::ConnectionReset | ErrorKind::BrokenPipe)
+/// })
+/// .with_delay(Duration::from_millis(10));
+///
+/// assert!(policy.is_broken(&Error::from(ErrorKind::BrokenPipe)));
+/// assert!(!policy.is_broken(&Error::from(ErrorKind::NotFound)));
+/// assert_eq!(policy.max_attempts(), 3);
+/// ```
+pub struct RetryPolicy<E> {
+    max_attempts: usize,
+    delay: Duration,
+    is_broken: Arc<dyn Fn(&E) -> bool + Send + Sync>,
+}
+
+impl<E> RetryPolicy<E> {
+    /// Try at most `max_attempts` times, retrying after errors that
+    /// `is_broken` classifies as a broken object
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_attempts` is 0.
+    pub fn new<F>(max_attempts: usize, is_broken: F) -> Self
+    where
+        F: Fn(&E) -> bool + Send + Sync + 'static,
+    {
+        assert!(max_attempts > 0, "RetryPolicy needs at least one attempt");
+        Self {
+            max_attempts,
+            delay: Duration::ZERO,
+            is_broken: Arc::new(is_broken),
+        }
+    }
+
+    /// Pause for `delay` before each retry (default: none)
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Maximum number of attempts, the first included
+    #[must_use]
+    pub fn max_attempts(&self) -> usize {
+        self.max_attempts
+    }
+
+    /// Pause before each retry
+    #[must_use]
+    pub fn delay(&self) -> Duration {
+        self.delay
+    }
+
+    /// Whether `err` means the object it came from is broken
+    pub fn is_broken(&self, err: &E) -> bool {
+        (self.is_broken)(err)
+    }
+}
+
+impl<E> Clone for RetryPolicy<E> {
+    fn clone(&self) -> Self {
+        Self {
+            max_attempts: self.max_attempts,
+            delay: self.delay,
+            is_broken: Arc::clone(&self.is_broken),
+        }
+    }
+}
+
+impl<E> std::fmt::Debug for RetryPolicy<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("delay", &self.delay)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Run `op` on objects from `acquire` until it succeeds, fails with an
+/// error that is not a broken object, or runs out of attempts. `report`
+/// receives the outcome of every attempt.
+pub(crate) fn execute<T, R, E>(
+    policy: &RetryPolicy<E>,
+    mut acquire: impl FnMut() -> PoolResult<PooledObject<T>>,
+    mut report: impl FnMut(bool),
+    mut op: impl FnMut(&mut T) -> Result<R, E>,
+) -> Result<R, GuardedError<E>> {
+    let mut attempt = 1;
+    loop {
+        let mut obj = acquire()?;
+        let result = op(&mut obj);
+        report(result.is_ok());
+        let err = match result {
+            Ok(value) => return Ok(value),
+            Err(err) => err,
+        };
+        if !policy.is_broken(&err) {
+            return Err(GuardedError::Operation(err));
+        }
+        obj.discard();
+        if attempt == policy.max_attempts {
+            return Err(GuardedError::Operation(err));
+        }
+        attempt += 1;
+        if !policy.delay.is_zero() {
+            std::thread::sleep(policy.delay);
+        }
+    }
+}
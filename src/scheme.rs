@@ -0,0 +1,294 @@
+//! Pools built from connection-string-style descriptors
+
+use crate::config::PoolConfiguration;
+use crate::duration::parse_duration;
+use crate::errors::{PoolError, PoolResult};
+use crate::pool::{DynamicObjectPool, Factory};
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, LazyLock, PoisonError, RwLock};
+
+/// Turns a descriptor into a factory for a pool's objects.
+type Handler<T> = Arc<dyn Fn(&PoolUri) -> PoolResult<Factory<T>> + Send + Sync>;
+
+/// Handlers by lowercased scheme and object type, each a boxed
+/// [`Handler`] of that type.
+type Registry = HashMap<(String, TypeId), Box<dyn Any + Send + Sync>>;
+
+static SCHEMES: LazyLock<RwLock<Registry>> = LazyLock::new(Default::default);
+
+/// A parsed pool descriptor such as
+/// `redis://cache.internal:6379/0?max_size=16&idle_timeout=5m`
+///
+/// Made of a scheme, the target after `://` and `&`-separated `key=value`
+/// parameters after `?`. Values are taken verbatim, without percent-decoding.
+/// [`DynamicObjectPool::from_uri`] hands it to the handler registered for
+/// its scheme.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::PoolUri;
+///
+/// let uri = PoolUri::parse("Redis://cache:6379/0?max_size=16&tls").unwrap();
+/// assert_eq!(uri.scheme(), "redis");
+/// assert_eq!(uri.target(), "cache:6379/0");
+/// assert_eq!(uri.param("max_size"), Some("16"));
+/// assert_eq!(uri.param("tls"), Some(""));
+/// assert_eq!(uri.param("password"), None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolUri {
+    uri: String,
+    scheme: String,
+    target: String,
+    params: Vec<(String, String)>,
+}
+
+impl PoolUri {
+    /// Parse `scheme://target?key=value&...`
+    ///
+    /// Fails with `PoolError::InvalidConfiguration` if the `://` is missing
+    /// or the scheme is empty or not made of letters, digits, `+`, `-` and
+    /// `.` starting with a letter.
+    pub fn parse(uri: &str) -> PoolResult<Self> {
+        let invalid = |reason: &str| PoolError::InvalidConfiguration(format!("invalid pool URI {uri:?}: {reason}"));
+        let (scheme, rest) = uri.split_once("://").ok_or_else(|| invalid("expected scheme://"))?;
+        let valid_scheme = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+        if !valid_scheme {
+            return Err(invalid("bad scheme"));
+        }
+        let (target, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let params = query
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                (key.to_owned(), value.to_owned())
+            })
+            .collect();
+        Ok(Self {
+            uri: uri.to_owned(),
+            scheme: scheme.to_ascii_lowercase(),
+            target: target.to_owned(),
+            params,
+        })
+    }
+
+    /// The scheme, lowercased
+    #[must_use]
+    pub fn scheme(&self) -> &str {
+        &self.scheme
+    }
+
+    /// Everything between `://` and `?`, such as `host:port/path`
+    #[must_use]
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    /// Value of the first parameter named `key`; `""` for a bare `key`
+    #[must_use]
+    pub fn param(&self, key: &str) -> Option<&str> {
+        self.params.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+
+    /// Every parameter in order
+    pub fn params(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.params.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// `config` with the pool settings among the parameters applied.
+    fn configure<T>(&self, mut config: PoolConfiguration<T>) -> PoolResult<PoolConfiguration<T>> {
+        let count = |key: &str, value: &str| {
+            value
+                .parse::<usize>()
+                .map_err(|_| PoolError::InvalidConfiguration(format!("{key} must be a count, got {value:?}")))
+        };
+        for (key, value) in self.params() {
+            config = match key {
+                "max_size" => config.with_max_pool_size(count(key, value)?),
+                "max_active" => config.with_max_active_objects(count(key, value)?),
+                "warmup" => config.with_warmup(count(key, value)?),
+                "timeout" => config.with_timeout(parse_duration(value)?),
+                "ttl" => config.with_ttl(parse_duration(value)?),
+                "idle_timeout" => config.with_idle_timeout(parse_duration(value)?),
+                _ => config,
+            };
+        }
+        Ok(config)
+    }
+}
+
+impl fmt::Display for PoolUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.uri)
+    }
+}
+
+/// Register how to create objects of type `T` for pool URIs with `scheme`
+///
+/// [`DynamicObjectPool::from_uri`] calls `handler` once per pool with the
+/// parsed descriptor; it returns the pool's factory, or an error message
+/// reported as `PoolError::InvalidConfiguration`. Handlers are
+/// process-wide and keyed by scheme (case-insensitively) and object type,
+/// so the same scheme can serve pools of different types. Registering a
+/// scheme again for the same type replaces its handler; pools already
+/// built keep their factory.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{register_scheme, DynamicObjectPool, PoolConfiguration};
+///
+/// struct Conn {
+///     addr: String,
+///     db: u32,
+/// }
+///
+/// register_scheme("kv", |uri| {
+///     let addr = uri.target().to_owned();
+///     let db = uri.param("db").unwrap_or("0").parse().map_err(|_| "db must be a number")?;
+///     Ok(move || Ok::<_, String>(Conn { addr: addr.clone(), db }))
+/// });
+///
+/// let pool: DynamicObjectPool<Conn> =
+///     DynamicObjectPool::from_uri("kv://cache:6379?db=2&max_size=8", PoolConfiguration::default()).unwrap();
+/// assert_eq!(pool.capacity(), 8);
+///
+/// let conn = pool.get_object().unwrap();
+/// assert_eq!((conn.addr.as_str(), conn.db), ("cache:6379", 2));
+/// ```
+pub fn register_scheme<T, H, F, E>(scheme: &str, handler: H)
+where
+    T: Send + Sync + 'static,
+    H: Fn(&PoolUri) -> Result<F, String> + Send + Sync + 'static,
+    F: Fn() -> Result<T, E> + Send + Sync + 'static,
+    E: fmt::Display,
+{
+    let handler: Handler<T> = Arc::new(move |uri: &PoolUri| {
+        let factory = handler(uri)
+            .map_err(|err| PoolError::InvalidConfiguration(format!("{}: {err}", uri.scheme())))?;
+        Ok(Arc::new(move || factory().map_err(|err| err.to_string())) as Factory<T>)
+    });
+    SCHEMES
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert((scheme.to_ascii_lowercase(), TypeId::of::<T>()), Box::new(handler));
+}
+
+fn handler<T: 'static>(scheme: &str) -> Option<Handler<T>> {
+    SCHEMES
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&(scheme.to_owned(), TypeId::of::<T>()))
+        .and_then(|handler| handler.downcast_ref::<Handler<T>>())
+        .cloned()
+}
+
+impl<T: Send + Sync + 'static> DynamicObjectPool<T> {
+    /// Create a dynamic pool from a descriptor such as
+    /// `redis://cache:6379/0?max_size=16&idle_timeout=5m`
+    ///
+    /// The factory comes from the handler registered for the scheme with
+    /// [`register_scheme`]. These parameters, if present, are applied on
+    /// top of `config`; every parameter, these included, is also visible to
+    /// the handler:
+    ///
+    /// | Parameter      | Setting                                                        |
+    /// |----------------|----------------------------------------------------------------|
+    /// | `max_size`     | [`with_max_pool_size`](PoolConfiguration::with_max_pool_size)  |
+    /// | `max_active`   | [`with_max_active_objects`](PoolConfiguration::with_max_active_objects) |
+    /// | `warmup`       | [`with_warmup`](PoolConfiguration::with_warmup)                |
+    /// | `timeout`      | [`with_timeout`](PoolConfiguration::with_timeout)              |
+    /// | `ttl`          | [`with_ttl`](PoolConfiguration::with_ttl)                      |
+    /// | `idle_timeout` | [`with_idle_timeout`](PoolConfiguration::with_idle_timeout)    |
+    ///
+    /// Durations use the format of
+    /// [`with_timeout_str`](PoolConfiguration::with_timeout_str). Fails with
+    /// `PoolError::InvalidConfiguration` if the descriptor does not parse,
+    /// no handler for its scheme and `T` is registered, a parameter above
+    /// has a bad value, or the handler refuses the descriptor. See
+    /// [`register_scheme`] for an example.
+    pub fn from_uri(uri: &str, config: PoolConfiguration<T>) -> PoolResult<Self> {
+        let uri = PoolUri::parse(uri)?;
+        let handler = handler::<T>(uri.scheme()).ok_or_else(|| {
+            PoolError::InvalidConfiguration(format!(
+                "no handler registered for scheme {:?} and {}",
+                uri.scheme(),
+                std::any::type_name::<T>(),
+            ))
+        })?;
+        let config = uri.configure(config)?;
+        let factory = handler(&uri)?;
+        Ok(Self::from_factory(factory, Vec::new(), config))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn parses_scheme_target_and_params() {
+        let uri = PoolUri::parse("pg+tls://db.local:5432/app?ttl=1h&&flag&max_size=4&max_size=9").unwrap();
+        assert_eq!(uri.scheme(), "pg+tls");
+        assert_eq!(uri.target(), "db.local:5432/app");
+        assert_eq!(uri.param("max_size"), Some("4"), "first occurrence wins");
+        assert_eq!(uri.params().count(), 4);
+        assert_eq!(uri.to_string(), "pg+tls://db.local:5432/app?ttl=1h&&flag&max_size=4&max_size=9");
+
+        for bad in ["cache:6379", "://host", "1db://host", "my db://host"] {
+            assert!(matches!(PoolUri::parse(bad), Err(PoolError::InvalidConfiguration(_))), "{bad}");
+        }
+    }
+
+    #[test]
+    fn pool_settings_come_from_the_params() {
+        register_scheme("scheme-test-settings", |_| Ok(|| Ok::<u8, String>(0)));
+        let pool = DynamicObjectPool::<u8>::from_uri(
+            "scheme-test-settings://x?max_size=6&max_active=3&ttl=90s&timeout=250ms&other=1",
+            PoolConfiguration::default(),
+        )
+        .unwrap();
+        assert_eq!(pool.capacity(), 6);
+        let _held: Vec<_> = (0..3).map(|_| pool.get_object().unwrap()).collect();
+        assert!(matches!(pool.get_object(), Err(PoolError::MaxActiveObjectsReached)));
+
+        let uri = PoolUri::parse("scheme-test-settings://x?max_active=3&ttl=90s&timeout=250ms&other=1").unwrap();
+        let config = uri.configure(PoolConfiguration::<u8>::default()).unwrap();
+        assert_eq!(config.max_active_objects, Some(3));
+        assert_eq!(config.time_to_live, Some(Duration::from_secs(90)));
+        assert_eq!(config.operation_timeout, Some(Duration::from_millis(250)));
+
+        let bad = DynamicObjectPool::<u8>::from_uri("scheme-test-settings://x?max_size=lots", PoolConfiguration::default());
+        assert!(matches!(bad, Err(PoolError::InvalidConfiguration(msg)) if msg.contains("max_size")));
+    }
+
+    #[test]
+    fn handlers_are_per_scheme_and_type() {
+        register_scheme("SCHEME-TEST-TYPED", |uri| {
+            let name = uri.target().to_owned();
+            Ok(move || Ok::<String, String>(name.clone()))
+        });
+        register_scheme("scheme-test-typed", |uri| {
+            if uri.param("port").is_none() {
+                return Err("port is required".into());
+            }
+            Ok(|| Ok::<u16, String>(7))
+        });
+
+        let strings = DynamicObjectPool::<String>::from_uri("scheme-test-typed://a", PoolConfiguration::default()).unwrap();
+        assert_eq!(*strings.get_object().unwrap(), "a");
+
+        let refused = DynamicObjectPool::<u16>::from_uri("scheme-test-typed://a", PoolConfiguration::default());
+        assert!(matches!(refused, Err(PoolError::InvalidConfiguration(msg)) if msg == "scheme-test-typed: port is required"));
+
+        let unknown = DynamicObjectPool::<u32>::from_uri("scheme-test-typed://a", PoolConfiguration::default());
+        assert!(matches!(unknown, Err(PoolError::InvalidConfiguration(msg)) if msg.contains("no handler")));
+    }
+}
@@ -0,0 +1,376 @@
+//! Acquiring from whichever of several pools has an object first
+
+use crate::errors::{PoolError, PoolResult, WaitBreakdown};
+use crate::pool::{DynamicObjectPool, ObjectPool, PooledObject, QueryableObjectPool};
+use crate::wait::{wait_blocking, Backoff, WaitPolicy};
+
+use std::future::{poll_fn, Future};
+use std::sync::Arc;
+use std::task::Poll;
+use std::time::{Duration, Instant};
+use tokio::runtime::Handle;
+use tokio::sync::Notify;
+
+/// Delay between rounds over the pools while all of them are empty, as for
+/// a pool with the default retry interval.
+const BACKOFF: Backoff = Backoff {
+    initial: Duration::from_millis(5),
+    max: Duration::from_millis(20),
+};
+
+mod sealed {
+    use std::sync::Arc;
+    use tokio::sync::Notify;
+
+    pub trait Sealed {
+        /// Signalled whenever an object is returned to or leaves the pool.
+        fn released(&self) -> Arc<Notify>;
+    }
+}
+
+/// A pool [`try_acquire_any`] can take objects from
+///
+/// Implemented by [`ObjectPool`], [`QueryableObjectPool`] and
+/// [`DynamicObjectPool`]; not implementable outside this crate.
+pub trait SelectablePool<T>: sealed::Sealed + Sync {
+    /// Take an object if one is available right now, without waiting; a
+    /// dynamic pool below capacity creates one
+    fn try_acquire(&self) -> PoolResult<Option<PooledObject<T>>>;
+}
+
+impl<T: Send + Sync + 'static> sealed::Sealed for ObjectPool<T> {
+    fn released(&self) -> Arc<Notify> {
+        ObjectPool::released(self)
+    }
+}
+
+impl<T: Send + Sync + 'static> SelectablePool<T> for ObjectPool<T> {
+    fn try_acquire(&self) -> PoolResult<Option<PooledObject<T>>> {
+        self.try_get_object()
+    }
+}
+
+impl<T: Send + Sync + 'static> sealed::Sealed for QueryableObjectPool<T> {
+    fn released(&self) -> Arc<Notify> {
+        ObjectPool::released(self)
+    }
+}
+
+impl<T: Send + Sync + 'static> SelectablePool<T> for QueryableObjectPool<T> {
+    fn try_acquire(&self) -> PoolResult<Option<PooledObject<T>>> {
+        ObjectPool::try_get_object(self)
+    }
+}
+
+impl<T: Send + Sync + 'static> sealed::Sealed for DynamicObjectPool<T> {
+    fn released(&self) -> Arc<Notify> {
+        DynamicObjectPool::released(self)
+    }
+}
+
+impl<T: Send + Sync + 'static> SelectablePool<T> for DynamicObjectPool<T> {
+    fn try_acquire(&self) -> PoolResult<Option<PooledObject<T>>> {
+        self.try_get_object()
+    }
+}
+
+/// Whether `err` only means a pool has nothing to hand out right now: it
+/// is empty, at one of its limits, paused or behind an open circuit breaker.
+fn is_unavailable(err: &PoolError) -> bool {
+    matches!(
+        err,
+        PoolError::PoolEmpty
+            | PoolError::PoolFull
+            | PoolError::Paused
+            | PoolError::CircuitBreakerOpen
+            | PoolError::MaxActiveObjectsReached
+            | PoolError::GroupLimitReached(_)
+            | PoolError::CreationThrottled
+            | PoolError::CreationPending
+    )
+}
+
+/// What the rounds of one call over the pools have found so far.
+struct Rounds {
+    /// Pools that failed with something other than being unavailable,
+    /// skipped from then on.
+    failed: Vec<bool>,
+
+    /// The last of those failures.
+    failure: Option<PoolError>,
+}
+
+impl Rounds {
+    fn new(pools: usize) -> Self {
+        Self {
+            failed: vec![false; pools],
+            failure: None,
+        }
+    }
+
+    /// One round over `pools` in order: the first object found with the
+    /// index of its pool. Pools that are merely unavailable are skipped;
+    /// one failing otherwise is remembered and left out of later rounds.
+    /// Fails with `PoolEmpty` while some pool may still serve, otherwise
+    /// with `PoolClosed` if every pool is shut down and with the last
+    /// failure if not.
+    fn attempt<T>(&mut self, pools: &[&dyn SelectablePool<T>]) -> PoolResult<(usize, PooledObject<T>)> {
+        let (mut closed, mut waiting) = (0, false);
+        for (index, pool) in pools.iter().enumerate() {
+            if self.failed[index] {
+                continue;
+            }
+            match pool.try_acquire() {
+                Ok(Some(obj)) => return Ok((index, obj)),
+                Ok(None) => waiting = true,
+                Err(PoolError::PoolClosed) => closed += 1,
+                Err(err) if is_unavailable(&err) => waiting = true,
+                Err(err) => {
+                    self.failed[index] = true;
+                    self.failure = Some(err);
+                }
+            }
+        }
+        match self.failure {
+            _ if waiting => Err(PoolError::PoolEmpty),
+            Some(ref err) if closed < pools.len() => Err(err.clone()),
+            _ => Err(PoolError::PoolClosed),
+        }
+    }
+}
+
+/// The error to report for a wait that ended in `err`: a timeout gives
+/// way to the last real failure seen on the way.
+fn reported(err: PoolError, failure: Option<PoolError>) -> PoolError {
+    match (err, failure) {
+        (PoolError::Timeout(..), Some(failure)) => failure,
+        (err, _) => err,
+    }
+}
+
+/// Get an object from whichever of `pools` has one first, waiting up to
+/// `timeout`
+///
+/// Returns the object with the index in `pools` of the pool that served
+/// it. The pools are tried in order, so list preferred ones first — for
+/// example local resources before remote ones. A pool that is empty, at
+/// one of its limits, paused or behind an open circuit breaker is skipped
+/// and retried until `timeout` elapses, each pool's own [`WaitPolicy`]
+/// notwithstanding. A pool that fails otherwise, such as a dynamic pool
+/// whose factory fails, is not tried again, and its error is returned at
+/// once if no other pool may still serve.
+///
+/// Fails with `PoolError::PoolClosed` at once if every pool is shut down,
+/// and otherwise with the last such error, or `PoolError::Timeout` if
+/// there was none, when no pool had an object in time.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{try_acquire_any, DynamicObjectPool, ObjectPool, PoolConfiguration};
+/// use std::time::Duration;
+///
+/// let local = ObjectPool::new(vec!["local"], PoolConfiguration::default());
+/// let remote = DynamicObjectPool::new(|| "remote", PoolConfiguration::new().with_max_pool_size(4));
+/// let timeout = Duration::from_millis(50);
+///
+/// let (index, first) = try_acquire_any(&[&local, &remote], timeout).unwrap();
+/// assert_eq!((index, *first), (0, "local"));
+///
+/// let (index, second) = try_acquire_any(&[&local, &remote], timeout).unwrap();
+/// assert_eq!((index, *second), (1, "remote"), "local pool is exhausted");
+/// ```
+pub fn try_acquire_any<T>(
+    pools: &[&dyn SelectablePool<T>],
+    timeout: Duration,
+) -> PoolResult<(usize, PooledObject<T>)> {
+    let mut rounds = Rounds::new(pools.len());
+    wait_blocking(
+        WaitPolicy::Wait(timeout),
+        BACKOFF,
+        |err| matches!(err, PoolError::PoolEmpty),
+        None,
+        || rounds.attempt(pools),
+    )
+    .map_err(|err| reported(err, rounds.failure.take()))
+}
+
+/// Async counterpart of [`try_acquire_any`]
+///
+/// Rather than polling, an empty round is retried as soon as an object is
+/// returned to any of the pools. Outside a tokio runtime the wait blocks
+/// the polling thread, as [`try_acquire_any`] does.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{try_acquire_any_async, ObjectPool, PoolConfiguration};
+/// use std::time::Duration;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let fast = ObjectPool::new(vec![1], PoolConfiguration::default());
+/// let slow = ObjectPool::new(vec![2], PoolConfiguration::default());
+/// let _held = fast.get_object().unwrap();
+///
+/// let (index, obj) = try_acquire_any_async(&[&fast, &slow], Duration::from_secs(1)).await.unwrap();
+/// assert_eq!((index, *obj), (1, 2));
+/// # }
+/// ```
+pub async fn try_acquire_any_async<T>(
+    pools: &[&dyn SelectablePool<T>],
+    timeout: Duration,
+) -> PoolResult<(usize, PooledObject<T>)> {
+    if Handle::try_current().is_err() {
+        return try_acquire_any(pools, timeout);
+    }
+    let released: Vec<Arc<Notify>> = pools.iter().map(|pool| pool.released()).collect();
+    let start = Instant::now();
+    let mut rounds = Rounds::new(pools.len());
+
+    let retry = async {
+        let mut n: u64 = 0;
+        loop {
+            // Register before attempting so a release that races with the
+            // attempt is not missed.
+            let mut notified: Vec<_> = released.iter().map(|notify| Box::pin(notify.notified())).collect();
+            for notified in &mut notified {
+                notified.as_mut().enable();
+            }
+
+            match rounds.attempt(pools) {
+                Err(PoolError::PoolEmpty) => {
+                    let any_released = poll_fn(|cx| {
+                        if notified.iter_mut().any(|notified| notified.as_mut().poll(cx).is_ready()) {
+                            Poll::Ready(())
+                        } else {
+                            Poll::Pending
+                        }
+                    });
+                    tokio::select! {
+                        _ = any_released => {}
+                        _ = tokio::time::sleep(BACKOFF.delay(n)) => {}
+                    }
+                    n = n.wrapping_add(1);
+                }
+                result => return result,
+            }
+        }
+    };
+
+    let result = tokio::time::timeout(timeout, retry).await.unwrap_or_else(|_| {
+        Err(PoolError::Timeout(
+            timeout,
+            WaitBreakdown {
+                empty: start.elapsed(),
+                ..WaitBreakdown::default()
+            },
+        ))
+    });
+    result.map_err(|err| reported(err, rounds.failure))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::PoolConfiguration;
+
+    #[test]
+    fn waits_for_a_return_to_any_pool() {
+        let a = Arc::new(ObjectPool::new(vec![1], PoolConfiguration::default()));
+        let b = ObjectPool::new(vec![2], PoolConfiguration::default());
+        let held_a = a.get_object().unwrap();
+        let _held_b = b.get_object().unwrap();
+
+        let returner = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(20));
+            drop(held_a);
+        });
+        let (index, obj) = try_acquire_any(&[&*a, &b], Duration::from_secs(5)).unwrap();
+        assert_eq!((index, *obj), (0, 1));
+        returner.join().unwrap();
+
+        drop(obj);
+        let _again = a.get_object().unwrap();
+        assert!(matches!(
+            try_acquire_any(&[&*a, &b], Duration::from_millis(10)),
+            Err(PoolError::Timeout(..))
+        ));
+    }
+
+    #[test]
+    fn skips_refusing_pools_and_fails_fast_when_all_are_closed() {
+        let paused = ObjectPool::new(vec![1], PoolConfiguration::default());
+        let open = ObjectPool::new(vec![2], PoolConfiguration::default());
+        paused.pause();
+        let (index, _obj) = try_acquire_any(&[&paused, &open], Duration::from_millis(10)).unwrap();
+        assert_eq!(index, 1);
+
+        paused.shutdown();
+        open.shutdown();
+        let started = Instant::now();
+        assert!(matches!(
+            try_acquire_any(&[&paused, &open], Duration::from_secs(5)),
+            Err(PoolError::PoolClosed)
+        ));
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn failing_factory_is_reported_instead_of_a_timeout() {
+        let failing = DynamicObjectPool::try_new(|| Err::<i32, _>("refused"), PoolConfiguration::default());
+        let started = Instant::now();
+        assert!(matches!(
+            try_acquire_any(&[&failing], Duration::from_secs(5)),
+            Err(PoolError::CreationFailed(reason)) if reason == "refused"
+        ));
+        assert!(started.elapsed() < Duration::from_secs(1), "nothing else to wait for");
+        assert_eq!(failing.get_metrics().creation_failures, 1);
+
+        let empty = ObjectPool::new(vec![1], PoolConfiguration::default());
+        let _held = empty.get_object().unwrap();
+        assert!(matches!(
+            try_acquire_any(&[&failing, &empty], Duration::from_millis(20)),
+            Err(PoolError::CreationFailed(_))
+        ));
+        assert_eq!(failing.get_metrics().creation_failures, 2, "not retried while waiting");
+    }
+
+    #[tokio::test]
+    async fn async_failing_factory_is_reported_instead_of_a_timeout() {
+        let failing = DynamicObjectPool::try_new(|| Err::<i32, _>("refused"), PoolConfiguration::default());
+        let empty = ObjectPool::new(vec![1], PoolConfiguration::default());
+        let _held = empty.get_object().unwrap();
+        assert!(matches!(
+            try_acquire_any_async(&[&failing], Duration::from_secs(5)).await,
+            Err(PoolError::CreationFailed(_))
+        ));
+        assert!(matches!(
+            try_acquire_any_async(&[&empty, &failing], Duration::from_millis(20)).await,
+            Err(PoolError::CreationFailed(_))
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn async_wait_wakes_on_release() {
+        let a = ObjectPool::new(vec![1], PoolConfiguration::default());
+        let b = Arc::new(ObjectPool::new(vec![2], PoolConfiguration::default()));
+        let _held_a = a.get_object().unwrap();
+        let held_b = b.get_object().unwrap();
+
+        let returner = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(held_b);
+        });
+        let (index, obj) = try_acquire_any_async(&[&a, &*b], Duration::from_secs(5)).await.unwrap();
+        assert_eq!((index, *obj), (1, 2));
+        returner.await.unwrap();
+
+        drop(obj);
+        let _held_b = b.get_object().unwrap();
+        assert!(matches!(
+            try_acquire_any_async(&[&a, &*b], Duration::from_millis(10)).await,
+            Err(PoolError::Timeout(..))
+        ));
+    }
+}
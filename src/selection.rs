@@ -0,0 +1,57 @@
+//! Choosing among idle objects by score
+
+/// Health score of an object, stored with its application metadata.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Health(pub f64);
+
+impl Health {
+    /// Score of an object nobody has reported on.
+    pub const DEFAULT: f64 = 1.0;
+}
+
+/// Best-scoring candidate seen so far.
+pub(crate) struct Best<E> {
+    best: Option<(E, f64)>,
+}
+
+impl<E> Best<E> {
+    pub fn new() -> Self {
+        Self { best: None }
+    }
+
+    /// Offer `entry`, returning whichever of it and the current best loses.
+    /// Ties keep the current best, which was idle longer.
+    pub fn offer(&mut self, entry: E, score: f64) -> Option<E> {
+        match &self.best {
+            Some((_, best)) if *best >= score || score.is_nan() => Some(entry),
+            _ => self.best.replace((entry, score)).map(|(loser, _)| loser),
+        }
+    }
+
+    pub fn into_inner(self) -> Option<E> {
+        self.best.map(|(entry, _)| entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_highest_score_and_first_of_ties() {
+        let mut best = Best::new();
+        assert_eq!(best.offer('a', 0.5), None);
+        assert_eq!(best.offer('b', 0.5), Some('b'));
+        assert_eq!(best.offer('c', 0.9), Some('a'));
+        assert_eq!(best.offer('d', f64::NAN), Some('d'));
+        assert_eq!(best.into_inner(), Some('c'));
+    }
+
+    #[test]
+    fn nan_first_candidate_is_replaced() {
+        let mut best = Best::new();
+        assert_eq!(best.offer('a', f64::NAN), None);
+        assert_eq!(best.offer('b', 0.0), Some('a'));
+        assert_eq!(best.into_inner(), Some('b'));
+    }
+}
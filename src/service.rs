@@ -0,0 +1,244 @@
+//! Tower integration: pools as readiness-aware services
+
+use crate::errors::{PoolError, PoolResult};
+use crate::pool::{DynamicObjectPool, ObjectPool, PooledObject, Readiness};
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::sync::Notify;
+use tower::load::Load;
+use tower::Service;
+
+type AcquireFuture<T> = Pin<Box<dyn Future<Output = PoolResult<PooledObject<T>>> + Send>>;
+type Wakeup = Pin<Box<dyn Future<Output = ()> + Send + Sync>>;
+
+/// What a [`PoolService`] needs from the pool behind it.
+trait Backend<T>: Send + Sync {
+    fn readiness(&self) -> Readiness;
+    fn load(&self) -> f64;
+    fn released(&self) -> Arc<Notify>;
+    fn acquire(self: Arc<Self>) -> AcquireFuture<T>;
+}
+
+impl<T: Send + Sync + 'static> Backend<T> for ObjectPool<T> {
+    fn readiness(&self) -> Readiness {
+        ObjectPool::readiness(self, false)
+    }
+
+    fn load(&self) -> f64 {
+        ObjectPool::load(self)
+    }
+
+    fn released(&self) -> Arc<Notify> {
+        ObjectPool::released(self)
+    }
+
+    fn acquire(self: Arc<Self>) -> AcquireFuture<T> {
+        Box::pin(async move { self.get_object_async().await })
+    }
+}
+
+impl<T: Send + Sync + 'static> Backend<T> for DynamicObjectPool<T> {
+    fn readiness(&self) -> Readiness {
+        DynamicObjectPool::readiness(self)
+    }
+
+    fn load(&self) -> f64 {
+        DynamicObjectPool::load(self)
+    }
+
+    fn released(&self) -> Arc<Notify> {
+        DynamicObjectPool::released(self)
+    }
+
+    fn acquire(self: Arc<Self>) -> AcquireFuture<T> {
+        Box::pin(async move { self.get_object_async().await })
+    }
+}
+
+/// A pool as a [`tower::Service`] handing out objects
+///
+/// `poll_ready` reports the pool's saturation instead of leaving callers to
+/// find out at acquisition time: it is pending while every object is
+/// checked out, an active-object limit is reached or the circuit breaker is
+/// open, and fails with `PoolError::PoolClosed` once the pool is shut down.
+/// The [`Load`] implementation reports [`ObjectPool::load`] (or
+/// [`DynamicObjectPool::load`]), so several pools can sit behind a
+/// load-balancing layer that routes to the least saturated one.
+///
+/// Readiness is a snapshot: a concurrent caller may take the object that
+/// made the service ready, in which case `call` waits or fails according to
+/// the pool's [`WaitPolicy`](crate::WaitPolicy).
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{ObjectPool, PoolConfiguration, PoolService};
+/// use std::sync::Arc;
+/// use tower::load::Load;
+/// use tower::{Service, ServiceExt};
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let pool = Arc::new(ObjectPool::new(vec![1, 2], PoolConfiguration::default()));
+/// let mut service = PoolService::from(Arc::clone(&pool));
+///
+/// let obj = service.ready().await.unwrap().call(()).await.unwrap();
+/// assert_eq!(service.load(), 0.5);
+/// drop(obj);
+/// assert_eq!(service.load(), 0.0);
+/// # }
+/// ```
+pub struct PoolService<T> {
+    pool: Arc<dyn Backend<T>>,
+    /// Wakes the task that last saw the pool not ready.
+    wakeup: Option<Wakeup>,
+}
+
+impl<T> Clone for PoolService<T> {
+    fn clone(&self) -> Self {
+        Self {
+            pool: Arc::clone(&self.pool),
+            wakeup: None,
+        }
+    }
+}
+
+impl<T> PoolService<T> {
+    /// Acquire an object, as `call` does.
+    pub(crate) fn acquire(&self) -> AcquireFuture<T> {
+        Arc::clone(&self.pool).acquire()
+    }
+}
+
+impl<T: Send + Sync + 'static> From<Arc<ObjectPool<T>>> for PoolService<T> {
+    fn from(pool: Arc<ObjectPool<T>>) -> Self {
+        Self { pool, wakeup: None }
+    }
+}
+
+impl<T: Send + Sync + 'static> From<Arc<DynamicObjectPool<T>>> for PoolService<T> {
+    fn from(pool: Arc<DynamicObjectPool<T>>) -> Self {
+        Self { pool, wakeup: None }
+    }
+}
+
+impl<T: Send + Sync + 'static> Service<()> for PoolService<T> {
+    type Response = PooledObject<T>;
+    type Error = PoolError;
+    type Future = AcquireFuture<T>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<PoolResult<()>> {
+        loop {
+            if let Some(wakeup) = self.wakeup.as_mut() {
+                if wakeup.as_mut().poll(cx).is_pending() {
+                    return Poll::Pending;
+                }
+                self.wakeup = None;
+            }
+            match self.pool.readiness() {
+                Readiness::Ready => return Poll::Ready(Ok(())),
+                Readiness::Closed => return Poll::Ready(Err(PoolError::PoolClosed)),
+                Readiness::BreakerOpen(wait) => {
+                    self.wakeup = Some(Box::pin(tokio::time::sleep(wait)));
+                }
+                Readiness::Busy => {
+                    let mut released = Box::pin(self.pool.released().notified_owned());
+                    // Register before checking again so a return in between
+                    // is not missed.
+                    released.as_mut().enable();
+                    if matches!(self.pool.readiness(), Readiness::Busy) {
+                        self.wakeup = Some(released);
+                    }
+                }
+            }
+        }
+    }
+
+    fn call(&mut self, _: ()) -> Self::Future {
+        self.acquire()
+    }
+}
+
+impl<T> Load for PoolService<T> {
+    type Metric = f64;
+
+    fn load(&self) -> f64 {
+        self.pool.load()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PoolConfiguration;
+    use std::time::Duration;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn ready_once_an_object_is_returned() {
+        let pool = Arc::new(ObjectPool::new(vec![1], PoolConfiguration::default()));
+        let mut service = PoolService::from(Arc::clone(&pool));
+        let held = service.ready().await.unwrap().call(()).await.unwrap();
+
+        let waiting = tokio::spawn(async move { service.ready().await.map(|_| ()) });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiting.is_finished());
+
+        drop(held);
+        let ready = tokio::time::timeout(Duration::from_secs(1), waiting).await.unwrap().unwrap();
+        assert!(ready.is_ok());
+    }
+
+    #[tokio::test]
+    async fn dynamic_pool_is_ready_below_capacity() {
+        let pool = Arc::new(DynamicObjectPool::new(|| 0, PoolConfiguration::new().with_max_pool_size(1)));
+        let mut service = PoolService::from(Arc::clone(&pool));
+        assert!(pool.is_ready());
+
+        let _held = service.ready().await.unwrap().call(()).await.unwrap();
+        assert!(!pool.is_ready());
+        assert_eq!(service.load(), 1.0);
+    }
+
+    #[tokio::test]
+    async fn shutdown_fails_readiness() {
+        let pool = Arc::new(ObjectPool::new(vec![1], PoolConfiguration::default()));
+        let _held = pool.get_object().unwrap();
+        let mut service = PoolService::from(Arc::clone(&pool));
+
+        let waiting = tokio::spawn(async move { service.ready().await.map(|_| ()) });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        pool.shutdown();
+
+        let ready = tokio::time::timeout(Duration::from_secs(1), waiting).await.unwrap().unwrap();
+        assert!(matches!(ready, Err(PoolError::PoolClosed)));
+    }
+
+    #[tokio::test]
+    async fn open_breaker_is_waited_out() {
+        let pool = Arc::new(ObjectPool::new(
+            vec![1],
+            PoolConfiguration::new().with_circuit_breaker(1, Duration::from_millis(50)),
+        ));
+        pool.report_failure();
+        let mut service = PoolService::from(Arc::clone(&pool));
+        assert_eq!(service.load(), f64::INFINITY);
+
+        let ready = tokio::time::timeout(Duration::from_secs(1), service.ready()).await.unwrap();
+        assert!(ready.is_ok());
+    }
+
+    #[test]
+    fn max_active_objects_limits_readiness_and_load() {
+        let pool = ObjectPool::new(vec![1, 2, 3, 4], PoolConfiguration::new().with_max_active_objects(2));
+        let _a = pool.get_object().unwrap();
+        assert_eq!(pool.load(), 0.5);
+
+        let _b = pool.get_object().unwrap();
+        assert!(!pool.is_ready(), "idle objects remain but the limit is reached");
+        assert_eq!(pool.load(), 1.0);
+    }
+}
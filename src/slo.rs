@@ -0,0 +1,361 @@
+//! Acquisition service level objectives tracked as error budgets
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Slots a window is divided into; the newest one is the short window.
+const SLOTS: usize = 12;
+
+/// Acquisitions a window must hold before a budget can count as exhausted.
+const MIN_ACQUISITIONS: u64 = 20;
+
+/// One of the objectives in [`SloTargets`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum SloObjective {
+    /// Share of acquisitions completing within the latency threshold
+    Latency,
+    /// Share of acquisitions timing out
+    Timeouts,
+}
+
+impl std::fmt::Display for SloObjective {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Latency => "latency",
+            Self::Timeouts => "timeout",
+        })
+    }
+}
+
+/// Targets a pool tracks its acquisitions against
+///
+/// Set with [`PoolConfiguration::with_slo`](crate::PoolConfiguration::with_slo).
+/// Every acquisition that may wait for an object — `get_object`,
+/// `get_object_async` and their variants, but not the `try_*` methods —
+/// counts towards the objectives: as slow if it took longer than the
+/// latency threshold, and as a timeout if it failed with
+/// `PoolError::Timeout`. Acquisitions failing for any other reason are
+/// not counted.
+///
+/// Each objective allows a share of bad acquisitions, its error budget.
+/// Over a rolling window (five minutes unless set) the pool compares the
+/// share it saw with the share allowed: the burn rate. A burn rate of 1
+/// spends exactly the budget; above 1 the budget is exhausted, which
+/// publishes [`PoolEvent::SloBudgetExhausted`](crate::PoolEvent::SloBudgetExhausted)
+/// once, until the burn rate drops below 1 again. A window holding fewer
+/// than 20 acquisitions never counts as exhausted.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{ObjectPool, PoolConfiguration, SloTargets};
+/// use std::time::Duration;
+///
+/// // 99% of acquisitions within 5 ms, fewer than 0.1% timing out.
+/// let pool = ObjectPool::new(
+///     vec![1, 2],
+///     PoolConfiguration::new().with_slo(
+///         SloTargets::new()
+///             .with_latency(Duration::from_millis(5), 0.99)
+///             .with_timeout_rate(0.001),
+///     ),
+/// );
+///
+/// for _ in 0..50 {
+///     drop(pool.get_object().unwrap());
+/// }
+///
+/// let status = pool.slo_status().unwrap();
+/// assert_eq!(status.acquisitions, 50);
+/// assert!(!status.is_exhausted());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SloTargets {
+    latency: Option<(Duration, f64)>,
+    timeout_rate: Option<f64>,
+    window: Duration,
+}
+
+impl SloTargets {
+    /// No objectives, over a five minute window
+    pub fn new() -> Self {
+        Self {
+            latency: None,
+            timeout_rate: None,
+            window: Duration::from_secs(300),
+        }
+    }
+
+    /// Aim for at least `target` (0.0 to 1.0) of acquisitions to complete
+    /// within `threshold`
+    pub fn with_latency(mut self, threshold: Duration, target: f64) -> Self {
+        self.latency = Some((threshold, target.clamp(0.0, 1.0)));
+        self
+    }
+
+    /// Aim for at most `max_rate` (0.0 to 1.0) of acquisitions to time out
+    pub fn with_timeout_rate(mut self, max_rate: f64) -> Self {
+        self.timeout_rate = Some(max_rate.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Compute burn rates over the last `window` instead of five minutes
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window.max(Duration::from_millis(SLOTS as u64));
+        self
+    }
+
+    /// Rolling window burn rates are computed over
+    #[must_use]
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    fn allowed(&self, objective: SloObjective) -> Option<f64> {
+        match objective {
+            SloObjective::Latency => self.latency.map(|(_, target)| 1.0 - target),
+            SloObjective::Timeouts => self.timeout_rate,
+        }
+    }
+}
+
+impl Default for SloTargets {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Where a pool stands against its [`SloTargets`], from
+/// [`ObjectPool::slo_status`](crate::ObjectPool::slo_status)
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct SloStatus {
+    /// The rolling window the figures cover
+    pub window: Duration,
+    /// Acquisitions counted in the window
+    pub acquisitions: u64,
+    /// The latency objective, if one is set
+    pub latency: Option<BudgetStatus>,
+    /// The timeout objective, if one is set
+    pub timeouts: Option<BudgetStatus>,
+}
+
+impl SloStatus {
+    /// Whether any objective has exhausted its error budget
+    #[must_use]
+    pub fn is_exhausted(&self) -> bool {
+        [self.latency, self.timeouts].iter().flatten().any(|budget| budget.exhausted)
+    }
+}
+
+/// One objective's error budget over the rolling window
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub struct BudgetStatus {
+    /// Share of acquisitions allowed to be bad
+    pub allowed: f64,
+    /// Share of acquisitions in the window that were bad
+    pub observed: f64,
+    /// `observed / allowed` over the whole window; above 1.0 the budget
+    /// is being spent faster than it accrues
+    pub burn_rate: f64,
+    /// The burn rate over the most recent twelfth of the window, which
+    /// reacts sooner to a change
+    pub short_burn_rate: f64,
+    /// Share of the window's budget left, from 1.0 (untouched) to 0.0
+    pub remaining: f64,
+    /// Whether the budget is spent, with enough acquisitions to tell
+    pub exhausted: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Slot {
+    index: u64,
+    total: u64,
+    slow: u64,
+    timeouts: u64,
+}
+
+impl Slot {
+    fn bad(&self, objective: SloObjective) -> u64 {
+        match objective {
+            SloObjective::Latency => self.slow,
+            SloObjective::Timeouts => self.timeouts,
+        }
+    }
+}
+
+/// Counts acquisitions in rolling slots and tracks whether each budget is
+/// exhausted.
+pub(crate) struct SloTracker {
+    targets: SloTargets,
+    started: Instant,
+    slots: Mutex<[Slot; SLOTS]>,
+    /// Per objective, whether exhaustion was already reported.
+    exhausted: [AtomicBool; 2],
+}
+
+impl SloTracker {
+    pub fn new(targets: SloTargets) -> Self {
+        Self {
+            targets,
+            started: Instant::now(),
+            slots: Mutex::new([Slot::default(); SLOTS]),
+            exhausted: [AtomicBool::new(false), AtomicBool::new(false)],
+        }
+    }
+
+    fn current_index(&self) -> u64 {
+        let slot = self.targets.window.as_nanos() / SLOTS as u128;
+        (self.started.elapsed().as_nanos() / slot.max(1)) as u64
+    }
+
+    /// Count an acquisition that took `elapsed`, calling `exhausted` with
+    /// each objective whose budget it exhausted.
+    pub fn record(&self, elapsed: Duration, timed_out: bool, mut exhausted: impl FnMut(SloObjective)) {
+        let index = self.current_index();
+        let slow = self.targets.latency.is_some_and(|(threshold, _)| elapsed > threshold);
+        let status = {
+            let mut slots = self.slots.lock().unwrap_or_else(PoisonError::into_inner);
+            let slot = &mut slots[(index % SLOTS as u64) as usize];
+            if slot.index != index {
+                *slot = Slot { index, ..Slot::default() };
+            }
+            slot.total += 1;
+            slot.slow += u64::from(slow);
+            slot.timeouts += u64::from(timed_out);
+            self.summarize(&slots, index)
+        };
+
+        for (objective, budget) in [(SloObjective::Latency, status.latency), (SloObjective::Timeouts, status.timeouts)] {
+            let Some(budget) = budget else { continue };
+            let latch = &self.exhausted[objective as usize];
+            if budget.exhausted {
+                if !latch.swap(true, Ordering::AcqRel) {
+                    exhausted(objective);
+                }
+            } else if budget.burn_rate < 1.0 {
+                latch.store(false, Ordering::Release);
+            }
+        }
+    }
+
+    pub fn status(&self) -> SloStatus {
+        let index = self.current_index();
+        let slots = self.slots.lock().unwrap_or_else(PoisonError::into_inner);
+        self.summarize(&slots, index)
+    }
+
+    fn summarize(&self, slots: &[Slot; SLOTS], index: u64) -> SloStatus {
+        let window = || {
+            slots
+                .iter()
+                .filter(move |slot| slot.total > 0 && index.saturating_sub(slot.index) < SLOTS as u64)
+        };
+        let newest = || window().filter(move |slot| slot.index == index);
+        let acquisitions = window().map(|slot| slot.total).sum();
+        let recent = newest().map(|slot| slot.total).sum();
+
+        let budget = |objective| {
+            let allowed = self.targets.allowed(objective)?;
+            let bad = window().map(|slot| slot.bad(objective)).sum();
+            let recent_bad = newest().map(|slot| slot.bad(objective)).sum();
+            let burn_rate = burn(ratio(bad, acquisitions), allowed);
+            Some(BudgetStatus {
+                allowed,
+                observed: ratio(bad, acquisitions),
+                burn_rate,
+                short_burn_rate: burn(ratio(recent_bad, recent), allowed),
+                remaining: (1.0 - burn_rate).max(0.0),
+                exhausted: burn_rate > 1.0 && acquisitions >= MIN_ACQUISITIONS,
+            })
+        };
+
+        SloStatus {
+            window: self.targets.window,
+            acquisitions,
+            latency: budget(SloObjective::Latency),
+            timeouts: budget(SloObjective::Timeouts),
+        }
+    }
+}
+
+fn ratio(part: u64, total: u64) -> f64 {
+    if total == 0 { 0.0 } else { part as f64 / total as f64 }
+}
+
+fn burn(observed: f64, allowed: f64) -> f64 {
+    if observed == 0.0 {
+        0.0
+    } else if allowed == 0.0 {
+        f64::INFINITY
+    } else {
+        observed / allowed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracker() -> SloTracker {
+        SloTracker::new(
+            SloTargets::new()
+                .with_latency(Duration::from_millis(5), 0.9)
+                .with_timeout_rate(0.05),
+        )
+    }
+
+    #[test]
+    fn burn_rate_compares_observed_with_allowed() {
+        let slo = tracker();
+        for i in 0..20 {
+            slo.record(Duration::from_millis(if i < 1 { 10 } else { 1 }), false, |_| {});
+        }
+        let status = slo.status();
+        let latency = status.latency.unwrap();
+        assert_eq!(status.acquisitions, 20);
+        assert!((latency.allowed - 0.1).abs() < 1e-9);
+        assert!((latency.burn_rate - 0.5).abs() < 1e-9);
+        assert!((latency.remaining - 0.5).abs() < 1e-9);
+        assert_eq!(status.timeouts.unwrap().burn_rate, 0.0);
+        assert!(!status.is_exhausted());
+    }
+
+    #[test]
+    fn exhaustion_is_reported_once_until_recovered() {
+        let slo = tracker();
+        let mut reported = Vec::new();
+        for _ in 0..19 {
+            slo.record(Duration::from_millis(1), true, |objective| reported.push(objective));
+        }
+        assert!(reported.is_empty(), "too few acquisitions to judge");
+        slo.record(Duration::from_millis(1), true, |objective| reported.push(objective));
+        slo.record(Duration::from_millis(1), true, |objective| reported.push(objective));
+        assert_eq!(reported, [SloObjective::Timeouts]);
+        assert!(slo.status().timeouts.unwrap().exhausted);
+        assert!(!slo.status().latency.unwrap().exhausted);
+    }
+
+    #[test]
+    fn old_slots_leave_the_window() {
+        let slo = SloTracker::new(SloTargets::new().with_timeout_rate(0.5).with_window(Duration::from_millis(120)));
+        for _ in 0..30 {
+            slo.record(Duration::ZERO, true, |_| {});
+        }
+        assert!(slo.status().is_exhausted());
+        std::thread::sleep(Duration::from_millis(150));
+        let status = slo.status();
+        assert_eq!(status.acquisitions, 0);
+        assert!(!status.is_exhausted());
+    }
+
+    #[test]
+    fn zero_allowance_burns_infinitely_on_any_failure() {
+        assert_eq!(burn(0.0, 0.0), 0.0);
+        assert_eq!(burn(0.1, 0.0), f64::INFINITY);
+        assert_eq!(burn(0.1, 0.2), 0.5);
+    }
+}
@@ -0,0 +1,164 @@
+//! Stream-based acquisition for object pools
+
+use crate::acquire::Acquire;
+use crate::errors::PoolError;
+use crate::pool::PooledObject;
+
+use futures_core::Stream;
+use std::future::Future;
+use std::panic::Location;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A [`Stream`] of pooled objects, created by
+/// [`ObjectPool::acquire_stream`](crate::ObjectPool::acquire_stream) and
+/// [`DynamicObjectPool::acquire_stream`](crate::DynamicObjectPool::acquire_stream).
+///
+/// Each item is acquired with the pool's async acquisition path, so
+/// `max_active_objects`, the circuit breaker and eviction all apply exactly as
+/// they do for `get_object_async`. A timeout while waiting is not treated as
+/// the end of the stream: the stream simply keeps waiting for the next object.
+/// Any other acquisition error ends the stream.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{ObjectPool, PoolConfiguration};
+/// use futures::StreamExt;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+///
+/// let batch: Vec<_> = pool.acquire_stream().take(3).collect().await;
+/// assert_eq!(batch.len(), 3);
+/// assert_eq!(pool.active_count(), 3);
+/// # }
+/// ```
+pub struct AcquireStream<'a, T> {
+    acquire: Box<dyn Fn() -> Acquire<'a, T> + Send + Sync + 'a>,
+    pending: Option<Acquire<'a, T>>,
+    done: bool,
+    /// Where the stream was created, recorded as every item's acquisition
+    /// site.
+    location: &'static Location<'static>,
+}
+
+impl<'a, T> AcquireStream<'a, T> {
+    #[track_caller]
+    pub(crate) fn new<F>(acquire: F) -> Self
+    where
+        F: Fn() -> Acquire<'a, T> + Send + Sync + 'a,
+    {
+        Self {
+            acquire: Box::new(acquire),
+            pending: None,
+            done: false,
+            location: Location::caller(),
+        }
+    }
+}
+
+impl<T> Stream for AcquireStream<'_, T> {
+    type Item = PooledObject<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+
+        loop {
+            if self.pending.is_none() {
+                let fut = (self.acquire)();
+                self.pending = Some(fut);
+            }
+
+            let fut = self.pending.as_mut().expect("pending future was just set");
+            match Pin::new(fut).poll(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(result) => {
+                    self.pending = None;
+                    match result {
+                        Ok(obj) => return Poll::Ready(Some(obj.located(self.location))),
+                        // Nothing became available within the operation
+                        // timeout; keep waiting for the next object.
+                        Err(PoolError::Timeout(..)) => continue,
+                        Err(_) => {
+                            self.done = true;
+                            return Poll::Ready(None);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DynamicObjectPool, ObjectPool, PoolConfiguration, WaitPolicy};
+    use futures::StreamExt;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn stream_yields_available_objects() {
+        let pool = ObjectPool::new(vec![1, 2, 3], PoolConfiguration::default());
+
+        let mut items: Vec<i32> = pool
+            .acquire_stream()
+            .take(3)
+            .map(|obj| *obj)
+            .collect()
+            .await;
+        items.sort();
+
+        assert_eq!(items, vec![1, 2, 3]);
+        assert_eq!(pool.available_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn stream_waits_for_returned_objects_across_timeouts() {
+        let pool = ObjectPool::new(
+            vec![7],
+            PoolConfiguration::new().with_timeout(Duration::from_millis(20)),
+        );
+        let held = pool.get_object().unwrap();
+
+        let mut stream = pool.acquire_stream();
+        let release = async {
+            // Outlive at least one operation timeout before releasing.
+            tokio::time::sleep(Duration::from_millis(60)).await;
+            drop(held);
+        };
+
+        let (obj, ()) = tokio::join!(stream.next(), release);
+        assert_eq!(*obj.unwrap(), 7);
+    }
+
+    #[tokio::test]
+    async fn stream_ends_on_max_active() {
+        let pool = ObjectPool::new(
+            vec![1, 2, 3],
+            PoolConfiguration::new().with_max_active_objects(2),
+        );
+
+        let items: Vec<_> = pool.acquire_stream().collect().await;
+        assert_eq!(items.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn dynamic_stream_creates_up_to_capacity() {
+        let pool = DynamicObjectPool::new(
+            || 42,
+            PoolConfiguration::new()
+                .with_max_pool_size(2)
+                .with_max_active_objects(2)
+                .with_wait_on_empty(WaitPolicy::FailFast),
+        );
+
+        // The redundant active limit is ignored; `PoolFull` ends the stream.
+        let items: Vec<_> = pool.acquire_stream().collect().await;
+        assert_eq!(items.len(), 2);
+        assert!(items.iter().all(|obj| **obj == 42));
+    }
+}
@@ -0,0 +1,280 @@
+//! Throttling of object creation in dynamic pools
+
+use crate::errors::{PoolError, PoolResult};
+use crate::wait::Backoff;
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// Whether an empty dynamic pool creates an object or waits for one to be
+/// returned
+///
+/// Creating at once minimises latency and suits cheap objects such as
+/// buffers. For expensive objects such as connections it can be better to
+/// give a checked-out object a moment to come back before paying for a new
+/// one. The patience applies only while objects are checked out — with none
+/// out, nothing can be returned — and is capped by an
+/// [`AcquireContext`](crate::AcquireContext) deadline. Once it runs out, the
+/// acquisition proceeds as under `CreateFirst`, including waiting under the
+/// pool's [`WaitPolicy`](crate::WaitPolicy) if the pool is full.
+/// `try_get_object` never waits and always creates.
+///
+/// Set with
+/// [`PoolConfiguration::with_creation_policy`](crate::PoolConfiguration::with_creation_policy).
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{CreationPolicy, DynamicObjectPool, PoolConfiguration};
+/// use std::time::Duration;
+///
+/// let pool = DynamicObjectPool::new(
+///     || String::from("connection"),
+///     PoolConfiguration::new().with_creation_policy(CreationPolicy::Hybrid {
+///         threshold: 4,
+///         patience: Duration::from_millis(50),
+///     }),
+/// );
+///
+/// // Below the threshold, objects are created immediately.
+/// let first = pool.get_object().unwrap();
+/// let second = pool.get_object().unwrap();
+/// assert_eq!(pool.get_metrics().active_objects, 2);
+/// # drop((first, second));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CreationPolicy {
+    /// Create a new object as soon as the pool is empty
+    #[default]
+    CreateFirst,
+
+    /// Wait up to the given duration for a returned object before creating
+    ReuseFirst(Duration),
+
+    /// Create freely while the pool owns fewer than `threshold` objects,
+    /// then wait up to `patience` for a returned object before creating
+    Hybrid {
+        /// Population below which objects are created at once
+        threshold: usize,
+        /// How long to wait for a return once the threshold is reached
+        patience: Duration,
+    },
+}
+
+impl CreationPolicy {
+    /// How long to wait for a return when the pool owns `population`
+    /// objects, or `None` to create at once.
+    pub(crate) fn patience(&self, population: usize) -> Option<Duration> {
+        match *self {
+            Self::CreateFirst => None,
+            Self::ReuseFirst(patience) => Some(patience),
+            Self::Hybrid { threshold, patience } => (population >= threshold).then_some(patience),
+        }
+    }
+}
+
+/// Limits how often a pool's factory runs: at a steady maximum rate, and
+/// not at all for a growing period after it fails.
+pub(crate) struct CreationThrottle {
+    /// Minimum spacing between creations.
+    interval: Option<Duration>,
+    /// Pause after consecutive failures.
+    backoff: Option<Backoff>,
+    state: Mutex<ThrottleState>,
+}
+
+#[derive(Default)]
+struct ThrottleState {
+    next_slot: Option<Instant>,
+    failures: u64,
+    blocked_until: Option<Instant>,
+}
+
+impl CreationThrottle {
+    pub fn new(per_second: Option<u32>, backoff: Option<Backoff>) -> Self {
+        Self {
+            interval: per_second
+                .filter(|&n| n > 0)
+                .map(|n| Duration::from_secs(1) / n),
+            backoff,
+            state: Mutex::new(ThrottleState::default()),
+        }
+    }
+
+    fn is_active(&self) -> bool {
+        self.interval.is_some() || self.backoff.is_some()
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, ThrottleState> {
+        self.state.lock().unwrap_or_else(|p| p.into_inner())
+    }
+
+    /// Claim permission to run the factory once.
+    pub fn try_begin(&self) -> PoolResult<()> {
+        if !self.is_active() {
+            return Ok(());
+        }
+        let mut state = self.state();
+        let now = Instant::now();
+        if state.blocked_until.is_some_and(|until| until > now)
+            || state.next_slot.is_some_and(|slot| slot > now)
+        {
+            return Err(PoolError::CreationThrottled);
+        }
+        if let Some(interval) = self.interval {
+            state.next_slot = Some(now + interval);
+        }
+        Ok(())
+    }
+
+    /// Report how the factory run allowed by `try_begin` went.
+    pub fn record(&self, success: bool) {
+        let Some(backoff) = self.backoff else {
+            return;
+        };
+        let mut state = self.state();
+        if success {
+            state.failures = 0;
+            state.blocked_until = None;
+        } else {
+            state.blocked_until = Some(Instant::now() + backoff.base(state.failures));
+            state.failures = state.failures.saturating_add(1);
+        }
+    }
+}
+
+/// Factory calls in progress, optionally capped so that a burst of demand
+/// on an empty pool coalesces onto a few creations.
+pub(crate) struct PendingCreations {
+    in_flight: AtomicUsize,
+    max: Option<usize>,
+}
+
+/// One factory call in progress; ends on drop.
+pub(crate) struct PendingCreation<'a> {
+    in_flight: &'a AtomicUsize,
+}
+
+impl PendingCreations {
+    pub fn new(max: Option<usize>) -> Self {
+        Self {
+            in_flight: AtomicUsize::new(0),
+            max,
+        }
+    }
+
+    /// Register a factory call, unless the cap is reached.
+    pub fn try_begin(&self) -> PoolResult<PendingCreation<'_>> {
+        match self.max {
+            Some(max) => {
+                self.in_flight
+                    .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| (n < max).then_some(n + 1))
+                    .map_err(|_| PoolError::CreationPending)?;
+            }
+            None => {
+                self.in_flight.fetch_add(1, Ordering::AcqRel);
+            }
+        }
+        Ok(PendingCreation { in_flight: &self.in_flight })
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for PendingCreation<'_> {
+    fn drop(&mut self) {
+        self.in_flight.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_throttle_never_refuses() {
+        let throttle = CreationThrottle::new(None, None);
+        for _ in 0..100 {
+            throttle.try_begin().unwrap();
+            throttle.record(false);
+        }
+    }
+
+    #[test]
+    fn rate_limit_spaces_creations() {
+        let throttle = CreationThrottle::new(Some(50), None);
+        throttle.try_begin().unwrap();
+        assert!(matches!(throttle.try_begin(), Err(PoolError::CreationThrottled)));
+        std::thread::sleep(Duration::from_millis(25));
+        assert!(throttle.try_begin().is_ok());
+    }
+
+    #[test]
+    fn failures_block_until_backoff_elapses_and_success_resets() {
+        let backoff = Backoff {
+            initial: Duration::from_millis(20),
+            max: Duration::from_secs(1),
+        };
+        let throttle = CreationThrottle::new(None, Some(backoff));
+
+        throttle.try_begin().unwrap();
+        throttle.record(false);
+        assert!(matches!(throttle.try_begin(), Err(PoolError::CreationThrottled)));
+
+        std::thread::sleep(Duration::from_millis(40));
+        throttle.try_begin().unwrap();
+        throttle.record(true);
+        assert!(throttle.try_begin().is_ok());
+    }
+
+    #[test]
+    fn consecutive_failures_back_off_longer() {
+        let backoff = Backoff {
+            initial: Duration::from_millis(10),
+            max: Duration::from_secs(10),
+        };
+        let throttle = CreationThrottle::new(None, Some(backoff));
+        for _ in 0..4 {
+            throttle.record(false);
+        }
+        // Fourth failure: at least 10ms * 2^3.
+        std::thread::sleep(Duration::from_millis(40));
+        assert!(throttle.try_begin().is_err());
+    }
+
+    #[test]
+    fn creation_policy_patience() {
+        let wait = Duration::from_millis(5);
+        assert_eq!(CreationPolicy::CreateFirst.patience(100), None);
+        assert_eq!(CreationPolicy::ReuseFirst(wait).patience(0), Some(wait));
+
+        let hybrid = CreationPolicy::Hybrid { threshold: 3, patience: wait };
+        assert_eq!(hybrid.patience(2), None);
+        assert_eq!(hybrid.patience(3), Some(wait));
+    }
+
+    #[test]
+    fn pending_creations_are_capped_and_released_on_drop() {
+        let pending = PendingCreations::new(Some(2));
+        let a = pending.try_begin().unwrap();
+        let _b = pending.try_begin().unwrap();
+        assert!(matches!(pending.try_begin(), Err(PoolError::CreationPending)));
+        assert_eq!(pending.in_flight(), 2);
+
+        drop(a);
+        assert_eq!(pending.in_flight(), 1);
+        assert!(pending.try_begin().is_ok());
+    }
+
+    #[test]
+    fn uncapped_pending_creations_are_only_counted() {
+        let pending = PendingCreations::new(None);
+        let held: Vec<_> = (0..10).map(|_| pending.try_begin().unwrap()).collect();
+        assert_eq!(pending.in_flight(), 10);
+        drop(held);
+        assert_eq!(pending.in_flight(), 0);
+    }
+}
@@ -0,0 +1,331 @@
+//! Sizing and timeout recommendations from observed pool behaviour
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Acquisitions needed before [`tuning_advice`](crate::ObjectPool::tuning_advice)
+/// says anything.
+pub(crate) const MIN_SAMPLES: usize = 20;
+
+/// Share of acquisitions finding the pool empty above which a saturated pool
+/// is considered too small.
+const EMPTY_RATIO_LIMIT: f64 = 0.05;
+
+/// p99 wait above which a saturated pool is considered too small.
+const WAIT_LIMIT: Duration = Duration::from_millis(1);
+
+pub(crate) const BUCKETS: usize = 40;
+
+/// Lock-free histogram of counts in power-of-two buckets: bucket `b` holds
+/// the values needing `b` bits, so its largest value is `2^b - 1`.
+pub(crate) struct Log2Histogram {
+    buckets: [AtomicU64; BUCKETS],
+}
+
+impl Default for Log2Histogram {
+    fn default() -> Self {
+        Self {
+            buckets: std::array::from_fn(|_| AtomicU64::new(0)),
+        }
+    }
+}
+
+impl Log2Histogram {
+    pub fn record(&self, value: u64) {
+        let bucket = (u64::BITS - value.leading_zeros()) as usize;
+        self.buckets[bucket.min(BUCKETS - 1)].fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn count(&self) -> usize {
+        self.counts().iter().sum::<u64>() as usize
+    }
+
+    pub fn counts(&self) -> [u64; BUCKETS] {
+        std::array::from_fn(|bucket| self.buckets[bucket].load(Ordering::Relaxed))
+    }
+
+    /// The bucket holding the `quantile` (0.0 to 1.0) of the recorded
+    /// values, or `None` if nothing was recorded.
+    pub fn percentile_bucket(&self, quantile: f64) -> Option<usize> {
+        let counts = self.counts();
+        let total: u64 = counts.iter().sum();
+        if total == 0 {
+            return None;
+        }
+        let target = ((total as f64 * quantile).ceil() as u64).clamp(1, total);
+        let mut seen = 0;
+        counts.iter().position(|count| {
+            seen += count;
+            seen >= target
+        })
+    }
+}
+
+/// Histogram of durations in power-of-two microsecond buckets.
+/// Percentiles are reported as the upper bound of their bucket, so they are
+/// at most twice the true value.
+#[derive(Default)]
+pub(crate) struct LatencyHistogram {
+    micros: Log2Histogram,
+}
+
+impl LatencyHistogram {
+    pub fn record(&self, elapsed: Duration) {
+        self.micros.record(u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX));
+    }
+
+    pub fn count(&self) -> usize {
+        self.micros.count()
+    }
+
+    /// The `quantile` (0.0 to 1.0) of the recorded durations, or `None` if
+    /// nothing was recorded.
+    pub fn percentile(&self, quantile: f64) -> Option<Duration> {
+        self.micros
+            .percentile_bucket(quantile)
+            .map(|bucket| Duration::from_micros(1 << bucket))
+    }
+}
+
+/// Records how long a guard is held into the pool's hold-time histogram
+/// when dropped.
+pub(crate) struct HoldSample {
+    histogram: Arc<LatencyHistogram>,
+    started: Instant,
+}
+
+impl HoldSample {
+    pub fn start(histogram: Arc<LatencyHistogram>) -> Self {
+        Self {
+            histogram,
+            started: Instant::now(),
+        }
+    }
+}
+
+impl Drop for HoldSample {
+    fn drop(&mut self) {
+        self.histogram.record(self.started.elapsed());
+    }
+}
+
+/// What the pool observed, as input to [`advise`].
+pub(crate) struct Observed<'a> {
+    /// Objects the pool can hand out: its objects for a fixed pool, its
+    /// maximum size for a dynamic one.
+    pub size: usize,
+    pub peak_active: usize,
+    pub acquisitions: usize,
+    pub empty_events: usize,
+    pub warmup: usize,
+    pub wait_times: &'a LatencyHistogram,
+    pub hold_times: &'a LatencyHistogram,
+}
+
+/// Recommended settings derived from a pool's metrics
+///
+/// Returned by [`ObjectPool::tuning_advice`](crate::ObjectPool::tuning_advice)
+/// once the pool has served enough acquisitions to judge. The suggestions
+/// follow simple rules, each explained in [`reasons`](Self::reasons):
+///
+/// - A pool that was fully checked out while callers found it empty or
+///   waited noticeably is too small; grow it by half.
+/// - A pool whose peak use stayed at or below half its size is too large;
+///   shrink it to the peak plus a quarter.
+/// - Pools that ran empty should keep half their peak ready from the start
+///   ([`with_warmup`](crate::PoolConfiguration::with_warmup)).
+/// - The timeout should cover twice the p99 wait and at least one p99 hold.
+///
+/// Percentiles come from power-of-two buckets and are rounded up.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{ObjectPool, PoolConfiguration};
+///
+/// let pool = ObjectPool::new(vec![0; 10], PoolConfiguration::new());
+/// assert!(pool.tuning_advice().is_none(), "nothing observed yet");
+///
+/// for _ in 0..50 {
+///     let _a = pool.get_object().unwrap();
+///     let _b = pool.get_object().unwrap();
+/// }
+///
+/// let advice = pool.tuning_advice().unwrap();
+/// assert_eq!(advice.peak_active, 2);
+/// assert!(advice.suggested_max_pool_size < 10);
+/// for reason in &advice.reasons {
+///     println!("{reason}");
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoolTuningAdvice {
+    /// Recommended pool size: the number of objects for a fixed pool, the
+    /// maximum size for a dynamic one
+    pub suggested_max_pool_size: usize,
+
+    /// Recommended number of objects to keep ready, for example through
+    /// warm-up
+    pub suggested_min_idle: usize,
+
+    /// Recommended acquisition timeout
+    pub suggested_timeout: Duration,
+
+    /// Why each suggestion was made
+    pub reasons: Vec<String>,
+
+    /// 99th percentile of the time acquisitions took
+    pub wait_p99: Duration,
+
+    /// Median time objects were held
+    pub hold_p50: Duration,
+
+    /// 99th percentile of the time objects were held
+    pub hold_p99: Duration,
+
+    /// Most objects checked out at once
+    pub peak_active: usize,
+
+    /// Peak use as a share of the pool size (0.0 to 1.0)
+    pub peak_utilization: f64,
+
+    /// Share of acquisitions that found no idle object (0.0 to 1.0)
+    pub empty_ratio: f64,
+
+    /// Acquisitions the advice is based on; with
+    /// [`with_sampling`](crate::PoolConfiguration::with_sampling), only the
+    /// sampled ones
+    pub samples: usize,
+}
+
+/// Advice for a pool that observed `observed`, or `None` before
+/// [`MIN_SAMPLES`] acquisitions.
+pub(crate) fn advise(observed: &Observed<'_>) -> Option<PoolTuningAdvice> {
+    let samples = observed.wait_times.count();
+    if samples < MIN_SAMPLES {
+        return None;
+    }
+    let wait_p99 = observed.wait_times.percentile(0.99).unwrap_or_default();
+    let hold_p50 = observed.hold_times.percentile(0.5).unwrap_or_default();
+    let hold_p99 = observed.hold_times.percentile(0.99).unwrap_or_default();
+    let capacity = observed.size.max(1);
+    let peak = observed.peak_active;
+    let peak_utilization = peak as f64 / capacity as f64;
+    let empty_ratio = observed.empty_events as f64 / observed.acquisitions.max(1) as f64;
+    let mut reasons = Vec::new();
+
+    let saturated = peak >= capacity && (empty_ratio > EMPTY_RATIO_LIMIT || wait_p99 > WAIT_LIMIT);
+    let suggested_max_pool_size = if saturated {
+        let grown = capacity + capacity.div_ceil(2);
+        reasons.push(format!(
+            "all {capacity} objects were in use, {:.0}% of acquisitions found the pool empty and p99 wait was {wait_p99:?}: grow to {grown}",
+            empty_ratio * 100.0
+        ));
+        grown
+    } else if peak * 2 <= capacity {
+        let shrunk = (peak + peak.div_ceil(4)).max(1);
+        reasons.push(format!(
+            "at most {peak} of {capacity} objects were in use at once: shrink to {shrunk}"
+        ));
+        shrunk
+    } else {
+        reasons.push(format!("peak use of {peak} of {capacity} objects fits the pool size"));
+        capacity
+    };
+
+    let suggested_min_idle = if empty_ratio > EMPTY_RATIO_LIMIT {
+        let ready = peak.div_ceil(2).min(suggested_max_pool_size);
+        reasons.push(format!(
+            "{:.0}% of acquisitions found the pool empty: keep {ready} objects ready",
+            empty_ratio * 100.0
+        ));
+        ready
+    } else {
+        observed.warmup.min(suggested_max_pool_size)
+    };
+
+    let suggested_timeout = (wait_p99 * 2).max(hold_p99).max(Duration::from_millis(1));
+    reasons.push(format!(
+        "p99 wait {wait_p99:?} and p99 hold {hold_p99:?}: time out after {suggested_timeout:?}"
+    ));
+
+    Some(PoolTuningAdvice {
+        suggested_max_pool_size,
+        suggested_min_idle,
+        suggested_timeout,
+        reasons,
+        wait_p99,
+        hold_p50,
+        hold_p99,
+        peak_active: peak,
+        peak_utilization,
+        empty_ratio,
+        samples,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn histogram(samples: &[(Duration, usize)]) -> LatencyHistogram {
+        let histogram = LatencyHistogram::default();
+        for &(elapsed, count) in samples {
+            for _ in 0..count {
+                histogram.record(elapsed);
+            }
+        }
+        histogram
+    }
+
+    fn observed<'a>(waits: &'a LatencyHistogram, holds: &'a LatencyHistogram) -> Observed<'a> {
+        Observed {
+            size: 10,
+            peak_active: 10,
+            acquisitions: 100,
+            empty_events: 0,
+            warmup: 0,
+            wait_times: waits,
+            hold_times: holds,
+        }
+    }
+
+    #[test]
+    fn percentiles_round_up_to_bucket_bounds() {
+        let mixed = histogram(&[(Duration::from_micros(3), 90), (Duration::from_millis(5), 10)]);
+        assert_eq!(mixed.count(), 100);
+        assert_eq!(mixed.percentile(0.5), Some(Duration::from_micros(4)));
+        assert_eq!(mixed.percentile(0.99), Some(Duration::from_micros(8192)));
+        assert_eq!(LatencyHistogram::default().percentile(0.5), None);
+        assert_eq!(histogram(&[(Duration::ZERO, 1)]).percentile(1.0), Some(Duration::from_micros(1)));
+    }
+
+    #[test]
+    fn saturated_pool_is_told_to_grow() {
+        let waits = histogram(&[(Duration::from_millis(20), 100)]);
+        let holds = histogram(&[(Duration::from_millis(50), 100)]);
+        let advice = advise(&Observed { empty_events: 30, ..observed(&waits, &holds) }).unwrap();
+        assert_eq!(advice.suggested_max_pool_size, 15);
+        assert_eq!(advice.suggested_min_idle, 5);
+        assert_eq!(advice.suggested_timeout, Duration::from_micros(65536));
+        assert_eq!(advice.reasons.len(), 3);
+    }
+
+    #[test]
+    fn oversized_pool_is_told_to_shrink() {
+        let waits = histogram(&[(Duration::from_micros(2), 100)]);
+        let holds = histogram(&[(Duration::from_micros(2), 100)]);
+        let advice = advise(&Observed { peak_active: 4, warmup: 8, ..observed(&waits, &holds) }).unwrap();
+        assert_eq!(advice.suggested_max_pool_size, 5);
+        assert_eq!(advice.suggested_min_idle, 5);
+        assert_eq!(advice.suggested_timeout, Duration::from_millis(1));
+    }
+
+    #[test]
+    fn too_few_samples_give_no_advice() {
+        let waits = histogram(&[(Duration::from_micros(2), MIN_SAMPLES - 1)]);
+        let holds = LatencyHistogram::default();
+        assert!(advise(&observed(&waits, &holds)).is_none());
+    }
+}
@@ -0,0 +1,119 @@
+//! Startup self-test results
+
+/// Outcome of the factory check performed by
+/// [`DynamicObjectPool::verify`](crate::DynamicObjectPool::verify)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FactoryCheck {
+    /// The factory produced an object that passed validation
+    Passed,
+
+    /// The factory produced an object the validator rejected
+    FailedValidation,
+
+    /// The pool was at capacity, so no object could be created
+    SkippedAtCapacity,
+
+    /// The factory returned an error
+    CreationFailed(String),
+}
+
+/// What [`ObjectPool::verify`](crate::ObjectPool::verify) found
+///
+/// Every object that was idle when the check started is examined once.
+/// Objects failing validation are removed from the pool (and handed to the
+/// destroy hook, if any), as are objects that turned out to be expired.
+/// Objects checked out during the check are not examined.
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{ObjectPool, PoolConfiguration};
+///
+/// let pool = ObjectPool::new(
+///     vec![1, -2, 3],
+///     PoolConfiguration::new().with_validation(|x: &i32| *x > 0),
+/// );
+///
+/// let report = pool.verify();
+/// assert!(!report.is_ok());
+/// assert_eq!(report.checked, 3);
+/// assert_eq!(report.passed, 2);
+/// assert_eq!(report.failed_validation, vec![1]);
+/// assert_eq!(pool.available_count(), 2);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerifyReport {
+    /// Idle objects examined
+    pub checked: usize,
+
+    /// Idle objects that passed and were put back
+    pub passed: usize,
+
+    /// Ids of objects that failed validation and were removed
+    pub failed_validation: Vec<usize>,
+
+    /// Ids of objects that had expired and were evicted
+    pub expired: Vec<usize>,
+
+    /// Ids of objects that passed but could not be put back because
+    /// concurrent returns filled the queue
+    pub overflowed: Vec<usize>,
+
+    /// Result of creating one object with the factory (dynamic pools only)
+    pub factory: Option<FactoryCheck>,
+}
+
+impl VerifyReport {
+    /// Whether nothing was rejected: every examined object passed
+    /// validation and the factory, if checked, produced a valid object.
+    ///
+    /// Expired objects are routine eviction, and a factory check skipped
+    /// because the pool is full says nothing about the factory, so neither
+    /// counts as a failure.
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.failed_validation.is_empty()
+            && !matches!(
+                self.factory,
+                Some(FactoryCheck::FailedValidation | FactoryCheck::CreationFailed(_))
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_report_is_ok() {
+        assert!(VerifyReport::default().is_ok());
+    }
+
+    #[test]
+    fn expiry_and_skipped_factory_are_not_failures() {
+        let report = VerifyReport {
+            expired: vec![3],
+            factory: Some(FactoryCheck::SkippedAtCapacity),
+            ..VerifyReport::default()
+        };
+        assert!(report.is_ok());
+    }
+
+    #[test]
+    fn rejected_factory_object_is_a_failure() {
+        let report = VerifyReport {
+            factory: Some(FactoryCheck::FailedValidation),
+            ..VerifyReport::default()
+        };
+        assert!(!report.is_ok());
+    }
+
+    #[test]
+    fn factory_error_is_a_failure() {
+        let report = VerifyReport {
+            factory: Some(FactoryCheck::CreationFailed("refused".into())),
+            ..VerifyReport::default()
+        };
+        assert!(!report.is_ok());
+    }
+}
@@ -0,0 +1,593 @@
+//! Waiting for objects when a pool has none to hand out
+
+use crate::circuit_breaker::CircuitBreaker;
+use crate::errors::{PoolError, PoolResult, WaitBreakdown};
+
+use std::cell::Cell;
+use std::hash::{BuildHasher, RandomState};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use tokio::runtime::{Handle, RuntimeFlavor};
+use tokio::sync::Notify;
+
+/// What acquisition does when no object is available
+///
+/// Set per pool with
+/// [`PoolConfiguration::with_wait_on_empty`](crate::PoolConfiguration::with_wait_on_empty).
+/// The policy applies to both the blocking (`get_object`) and the async
+/// (`get_object_async`) acquisition paths. The `try_*` methods never wait.
+///
+/// Only running out of objects and a [paused](crate::ObjectPool::pause)
+/// pool are waited on. An open circuit breaker is
+/// waited out only if it will let a probe through before the wait budget
+/// runs out; otherwise `PoolError::CircuitBreakerOpen` is returned at once.
+/// Other failures, such as `max_active_objects` being reached, are returned
+/// immediately under every policy. A timeout reports how the wait was split
+/// between the two in its [`WaitBreakdown`].
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{ObjectPool, PoolConfiguration, PoolError, WaitPolicy};
+/// use std::time::Duration;
+///
+/// let pool = ObjectPool::new(
+///     vec![1],
+///     PoolConfiguration::new().with_wait_on_empty(WaitPolicy::Wait(Duration::from_millis(20))),
+/// );
+/// let _held = pool.get_object().unwrap();
+///
+/// assert!(matches!(pool.get_object(), Err(PoolError::Timeout(..))));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitPolicy {
+    /// Fail immediately with the pool's "empty" error
+    FailFast,
+
+    /// Keep retrying for up to the given duration, then fail with
+    /// [`PoolError::Timeout`]
+    Wait(Duration),
+
+    /// Keep retrying until an object becomes available
+    WaitForever,
+}
+
+impl WaitPolicy {
+    /// This policy with a `Wait` budget moved by a random amount of up to
+    /// `fraction` of itself either way.
+    pub(crate) fn jittered(self, fraction: f64) -> Self {
+        match self {
+            WaitPolicy::Wait(budget) if fraction > 0.0 => {
+                WaitPolicy::Wait(budget.mul_f64(1.0 + fraction * random_signed_unit()))
+            }
+            policy => policy,
+        }
+    }
+}
+
+/// Uniformly distributed value in `[-1.0, 1.0)` from a per-thread
+/// xorshift generator seeded from the standard library's hash keys.
+fn random_signed_unit() -> f64 {
+    thread_local! {
+        static STATE: Cell<u64> = Cell::new(RandomState::new().hash_one(0u8) | 1);
+    }
+    STATE.with(|state| {
+        let mut x = state.get();
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        state.set(x);
+        (x >> 11) as f64 / (1u64 << 53) as f64 * 2.0 - 1.0
+    })
+}
+
+/// Exponential delay between acquisition retries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Backoff {
+    pub initial: Duration,
+    pub max: Duration,
+}
+
+impl Backoff {
+    /// Share of the delay that jitter may add or take away.
+    const JITTER: f64 = 0.25;
+
+    /// Pause after `attempt` earlier failures: doubles from `initial` up to
+    /// `max`.
+    pub(crate) fn base(&self, attempt: u64) -> Duration {
+        self.initial
+            .saturating_mul(1u32 << attempt.min(16))
+            .min(self.max)
+    }
+
+    /// Delay before retry number `attempt`: [`base`](Self::base) moved by
+    /// a random amount of up to 25% either way, so waiters that started
+    /// together do not wake in lockstep, and never beyond `max`.
+    pub(crate) fn delay(&self, attempt: u64) -> Duration {
+        self.base(attempt)
+            .mul_f64(1.0 + Self::JITTER * random_signed_unit())
+            .min(self.max)
+    }
+}
+
+/// How long to wait out an open `breaker` that failed an attempt with
+/// `err`, or `None` if it will not let a probe through within `remaining`
+/// (`None`: no limit).
+fn breaker_pause(
+    err: &PoolError,
+    breaker: Option<&CircuitBreaker>,
+    remaining: Option<Duration>,
+) -> Option<Duration> {
+    if !matches!(err, PoolError::CircuitBreakerOpen) {
+        return None;
+    }
+    let pause = breaker?.retry_after()?;
+    remaining.is_none_or(|remaining| pause <= remaining).then_some(pause)
+}
+
+fn timed_out(budget: Duration, elapsed: Duration, breaker: Duration) -> PoolError {
+    PoolError::Timeout(
+        budget,
+        WaitBreakdown {
+            empty: elapsed.saturating_sub(breaker),
+            breaker,
+        },
+    )
+}
+
+/// Run `attempt` until it succeeds or fails with an error other than
+/// `retryable`, blocking the current thread between attempts. An open
+/// `breaker` is waited out if it reopens within the budget.
+pub(crate) fn wait_blocking<R>(
+    policy: WaitPolicy,
+    backoff: Backoff,
+    retryable: fn(&PoolError) -> bool,
+    breaker: Option<&CircuitBreaker>,
+    mut attempt: impl FnMut() -> PoolResult<R>,
+) -> PoolResult<R> {
+    let budget = match policy {
+        WaitPolicy::FailFast => return attempt(),
+        WaitPolicy::Wait(budget) => Some(budget),
+        WaitPolicy::WaitForever => None,
+    };
+    let start = Instant::now();
+    let mut breaker_time = Duration::ZERO;
+
+    let mut n: u64 = 0;
+    loop {
+        let err = match attempt() {
+            Err(err) => err,
+            ok => return ok,
+        };
+        let remaining = budget.map(|budget| budget.saturating_sub(start.elapsed()));
+        if retryable(&err) {
+            if let (Some(budget), Some(Duration::ZERO)) = (budget, remaining) {
+                return Err(timed_out(budget, start.elapsed(), breaker_time));
+            }
+            let delay = backoff.delay(n);
+            std::thread::sleep(remaining.map_or(delay, |remaining| delay.min(remaining)));
+            n = n.wrapping_add(1);
+        } else if let Some(pause) = breaker_pause(&err, breaker, remaining) {
+            std::thread::sleep(pause);
+            breaker_time += pause;
+        } else {
+            return Err(err);
+        }
+    }
+}
+
+/// [`wait_blocking`] that is safe to call from inside a tokio runtime.
+///
+/// On a multi-threaded runtime the wait runs under
+/// [`block_in_place`](tokio::task::block_in_place), so the worker's other
+/// tasks are moved elsewhere instead of stalling. A current-thread runtime
+/// has no other worker to hand them to, and whoever would return an object
+/// may be queued behind this call, so only a single attempt is made there.
+/// Outside a runtime this is plain [`wait_blocking`].
+pub(crate) fn wait_in_place<R>(
+    policy: WaitPolicy,
+    backoff: Backoff,
+    retryable: fn(&PoolError) -> bool,
+    breaker: Option<&CircuitBreaker>,
+    attempt: impl FnMut() -> PoolResult<R>,
+) -> PoolResult<R> {
+    match Handle::try_current().map(|handle| handle.runtime_flavor()) {
+        Ok(RuntimeFlavor::MultiThread) => {
+            tokio::task::block_in_place(|| wait_blocking(policy, backoff, retryable, breaker, attempt))
+        }
+        Ok(_) => wait_blocking(WaitPolicy::FailFast, backoff, retryable, breaker, attempt),
+        Err(_) => wait_blocking(policy, backoff, retryable, breaker, attempt),
+    }
+}
+
+/// Async counterpart of [`wait_blocking`].
+///
+/// Rather than polling on a fixed interval, a failed attempt is retried as
+/// soon as `released` fires (an object came back to the pool or left it),
+/// with the backoff delay only as a fallback for changes that are not
+/// signalled, such as a circuit breaker closing.
+///
+/// Timers come from whichever tokio runtime polls the future, not the one
+/// the pool was created on. Polled outside any tokio runtime (by another
+/// executor), there are no timers, so the wait blocks the polling thread as
+/// [`wait_blocking`] does.
+pub(crate) async fn wait_async<R>(
+    policy: WaitPolicy,
+    backoff: Backoff,
+    released: &Notify,
+    retryable: fn(&PoolError) -> bool,
+    breaker: Option<&CircuitBreaker>,
+    mut attempt: impl FnMut() -> PoolResult<R>,
+) -> PoolResult<R> {
+    let budget = match policy {
+        WaitPolicy::FailFast => return attempt(),
+        WaitPolicy::Wait(budget) => Some(budget),
+        WaitPolicy::WaitForever => None,
+    };
+    if Handle::try_current().is_err() {
+        return wait_blocking(policy, backoff, retryable, breaker, attempt);
+    }
+    let start = Instant::now();
+    let mut breaker_time = Duration::ZERO;
+
+    let retry = async {
+        let mut n: u64 = 0;
+        loop {
+            // Register before attempting so a release that races with the
+            // attempt is not missed.
+            let notified = released.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            match attempt() {
+                Err(ref err) if retryable(err) => {
+                    tokio::select! {
+                        _ = notified => {}
+                        _ = tokio::time::sleep(backoff.delay(n)) => {}
+                    }
+                    n = n.wrapping_add(1);
+                }
+                Err(err) => {
+                    let remaining = budget.map(|budget| budget.saturating_sub(start.elapsed()));
+                    let Some(pause) = breaker_pause(&err, breaker, remaining) else {
+                        return Err(err);
+                    };
+                    breaker_time += pause;
+                    tokio::time::sleep(pause).await;
+                }
+                ok => return ok,
+            }
+        }
+    };
+
+    match budget {
+        Some(budget) => {
+            let result = tokio::time::timeout(budget, retry).await;
+            result.unwrap_or_else(|_| Err(timed_out(budget, start.elapsed(), breaker_time)))
+        }
+        None => retry.await,
+    }
+}
+
+/// Acquisitions of a pool that found no object and are waiting for one.
+#[derive(Default)]
+pub(crate) struct Waiters {
+    count: AtomicUsize,
+}
+
+/// One waiting acquisition; stops being counted on drop, including when
+/// its future is dropped mid-wait.
+pub(crate) struct WaitTicket<'a> {
+    count: &'a AtomicUsize,
+}
+
+impl Waiters {
+    pub fn enter(&self) -> WaitTicket<'_> {
+        self.count.fetch_add(1, Ordering::AcqRel);
+        WaitTicket { count: &self.count }
+    }
+
+    /// [`enter`](Self::enter) unless `limit` acquisitions already wait.
+    pub fn try_enter(&self, limit: Option<usize>) -> Option<WaitTicket<'_>> {
+        let Some(limit) = limit else {
+            return Some(self.enter());
+        };
+        self.count
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| (n < limit).then_some(n + 1))
+            .ok()
+            .map(|_| WaitTicket { count: &self.count })
+    }
+
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::Acquire)
+    }
+}
+
+impl Drop for WaitTicket<'_> {
+    fn drop(&mut self) {
+        self.count.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Run `f` on the current runtime's blocking thread pool, or inline when
+/// polled outside a tokio runtime.
+pub(crate) async fn run_blocking<R: Send + 'static>(
+    f: impl FnOnce() -> R + Send + 'static,
+) -> PoolResult<R> {
+    match Handle::try_current() {
+        Ok(handle) => handle.spawn_blocking(f).await.map_err(|_| PoolError::Cancelled),
+        Err(_) => Ok(f()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jitter_spreads_wait_budgets_within_bounds() {
+        let budget = Duration::from_millis(100);
+        let budgets: Vec<Duration> = (0..200)
+            .map(|_| match WaitPolicy::Wait(budget).jittered(0.2) {
+                WaitPolicy::Wait(budget) => budget,
+                other => panic!("unexpected {other:?}"),
+            })
+            .collect();
+
+        assert!(budgets.iter().all(|b| (80..=120).contains(&b.as_millis())));
+        assert!(budgets.iter().any(|b| *b < budget) && budgets.iter().any(|b| *b > budget));
+        assert_eq!(WaitPolicy::Wait(budget).jittered(0.0), WaitPolicy::Wait(budget));
+        assert_eq!(WaitPolicy::FailFast.jittered(0.5), WaitPolicy::FailFast);
+        assert_eq!(WaitPolicy::WaitForever.jittered(0.5), WaitPolicy::WaitForever);
+    }
+
+    const BACKOFF: Backoff = Backoff {
+        initial: Duration::from_millis(5),
+        max: Duration::from_millis(20),
+    };
+
+    fn is_empty(err: &PoolError) -> bool {
+        matches!(err, PoolError::PoolEmpty)
+    }
+
+    #[test]
+    fn fail_fast_makes_a_single_attempt() {
+        let mut calls = 0;
+        let result: PoolResult<()> = wait_blocking(WaitPolicy::FailFast, BACKOFF, is_empty, None, || {
+            calls += 1;
+            Err(PoolError::PoolEmpty)
+        });
+        assert!(matches!(result, Err(PoolError::PoolEmpty)));
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn wait_retries_until_success() {
+        let mut calls = 0;
+        let result = wait_blocking(WaitPolicy::WaitForever, BACKOFF, is_empty, None, || {
+            calls += 1;
+            if calls < 3 { Err(PoolError::PoolEmpty) } else { Ok(calls) }
+        });
+        assert_eq!(result.unwrap(), 3);
+    }
+
+    #[test]
+    fn wait_times_out_with_budget() {
+        let budget = Duration::from_millis(20);
+        let start = Instant::now();
+        let result: PoolResult<()> =
+            wait_blocking(WaitPolicy::Wait(budget), BACKOFF, is_empty, None, || Err(PoolError::PoolEmpty));
+        assert!(matches!(result, Err(PoolError::Timeout(d, _)) if d == budget));
+        assert!(start.elapsed() >= budget);
+    }
+
+    #[test]
+    fn other_errors_are_not_retried() {
+        let mut calls = 0;
+        let result: PoolResult<()> = wait_blocking(WaitPolicy::WaitForever, BACKOFF, is_empty, None, || {
+            calls += 1;
+            Err(PoolError::CircuitBreakerOpen)
+        });
+        assert!(matches!(result, Err(PoolError::CircuitBreakerOpen)));
+        assert_eq!(calls, 1);
+    }
+
+    /// An attempt that fails while `breaker` is open, then with `then`.
+    fn behind(breaker: &CircuitBreaker, then: PoolResult<()>) -> impl FnMut() -> PoolResult<()> + '_ {
+        move || {
+            if breaker.allow_request() {
+                then.clone()
+            } else {
+                Err(PoolError::CircuitBreakerOpen)
+            }
+        }
+    }
+
+    #[test]
+    fn open_breaker_is_waited_out_within_budget() {
+        let breaker = CircuitBreaker::new(1, Duration::from_millis(30));
+        breaker.record_failure();
+        let start = Instant::now();
+        let result = wait_blocking(
+            WaitPolicy::Wait(Duration::from_secs(1)),
+            BACKOFF,
+            is_empty,
+            Some(&breaker),
+            behind(&breaker, Ok(())),
+        );
+        assert!(result.is_ok());
+        assert!(start.elapsed() >= Duration::from_millis(30));
+    }
+
+    #[test]
+    fn breaker_reopening_after_the_budget_fails_fast() {
+        let breaker = CircuitBreaker::new(1, Duration::from_secs(60));
+        breaker.record_failure();
+        let start = Instant::now();
+        let result = wait_blocking(
+            WaitPolicy::Wait(Duration::from_secs(1)),
+            BACKOFF,
+            is_empty,
+            Some(&breaker),
+            behind(&breaker, Ok(())),
+        );
+        assert!(matches!(result, Err(PoolError::CircuitBreakerOpen)));
+        assert!(start.elapsed() < Duration::from_millis(100));
+    }
+
+    #[test]
+    fn timeout_splits_breaker_and_empty_wait() {
+        let breaker = CircuitBreaker::new(1, Duration::from_millis(20));
+        breaker.record_failure();
+        let budget = Duration::from_millis(80);
+        let result = wait_blocking(
+            WaitPolicy::Wait(budget),
+            BACKOFF,
+            is_empty,
+            Some(&breaker),
+            behind(&breaker, Err(PoolError::PoolEmpty)),
+        );
+        let Err(PoolError::Timeout(d, waited)) = result else {
+            panic!("expected a timeout, got {result:?}");
+        };
+        assert_eq!(d, budget);
+        assert!(waited.breaker >= Duration::from_millis(20));
+        assert!(waited.empty > Duration::ZERO);
+        assert!(waited.empty + waited.breaker >= budget);
+    }
+
+    #[tokio::test]
+    async fn async_timeout_splits_breaker_and_empty_wait() {
+        let breaker = CircuitBreaker::new(1, Duration::from_millis(20));
+        breaker.record_failure();
+        let released = Notify::new();
+        let result = wait_async(
+            WaitPolicy::Wait(Duration::from_millis(80)),
+            BACKOFF,
+            &released,
+            is_empty,
+            Some(&breaker),
+            behind(&breaker, Err(PoolError::PoolEmpty)),
+        )
+        .await;
+        let Err(PoolError::Timeout(_, waited)) = result else {
+            panic!("expected a timeout, got {result:?}");
+        };
+        assert!(waited.breaker >= Duration::from_millis(20));
+        assert!(waited.empty > Duration::ZERO);
+    }
+
+    #[test]
+    fn backoff_grows_exponentially_up_to_max() {
+        let backoff = Backoff {
+            initial: Duration::from_millis(10),
+            max: Duration::from_millis(100),
+        };
+        assert_eq!(backoff.base(0), Duration::from_millis(10));
+        assert_eq!(backoff.base(2), Duration::from_millis(40));
+        assert_eq!(backoff.base(60), Duration::from_millis(100));
+        for _ in 0..100 {
+            let delay = backoff.delay(2);
+            assert!((Duration::from_millis(30)..=Duration::from_millis(50)).contains(&delay), "{delay:?}");
+            let capped = backoff.delay(60);
+            assert!((Duration::from_millis(75)..=Duration::from_millis(100)).contains(&capped), "{capped:?}");
+        }
+    }
+
+    #[test]
+    fn backoff_jitter_spreads_waiters_of_the_same_attempt() {
+        let backoff = Backoff {
+            initial: Duration::from_millis(10),
+            max: Duration::from_secs(1),
+        };
+        let delays: std::collections::HashSet<_> = (0..32).map(|_| backoff.delay(3)).collect();
+        assert!(delays.len() > 1, "every waiter got {delays:?}");
+    }
+
+    #[test]
+    fn in_place_waits_outside_a_runtime() {
+        let mut calls = 0;
+        let result = wait_in_place(WaitPolicy::WaitForever, BACKOFF, is_empty, None, || {
+            calls += 1;
+            if calls < 3 { Err(PoolError::PoolEmpty) } else { Ok(calls) }
+        });
+        assert_eq!(result.unwrap(), 3);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn in_place_waits_on_multi_thread_runtime() {
+        let mut calls = 0;
+        let result = wait_in_place(WaitPolicy::WaitForever, BACKOFF, is_empty, None, || {
+            calls += 1;
+            if calls < 3 { Err(PoolError::PoolEmpty) } else { Ok(calls) }
+        });
+        assert_eq!(result.unwrap(), 3);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn in_place_makes_single_attempt_on_current_thread_runtime() {
+        let mut calls = 0;
+        let result: PoolResult<()> = wait_in_place(WaitPolicy::WaitForever, BACKOFF, is_empty, None, || {
+            calls += 1;
+            Err(PoolError::PoolEmpty)
+        });
+        assert!(matches!(result, Err(PoolError::PoolEmpty)));
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn async_wait_blocks_outside_a_runtime() {
+        let budget = Duration::from_millis(20);
+        let released = Notify::new();
+        let result: PoolResult<()> = futures::executor::block_on(wait_async(
+            WaitPolicy::Wait(budget),
+            BACKOFF,
+            &released,
+            is_empty,
+            None,
+            || Err(PoolError::PoolEmpty),
+        ));
+        assert!(matches!(result, Err(PoolError::Timeout(d, _)) if d == budget));
+    }
+
+    #[test]
+    fn run_blocking_runs_inline_outside_a_runtime() {
+        let result = futures::executor::block_on(run_blocking(|| std::thread::current().id()));
+        assert_eq!(result.unwrap(), std::thread::current().id());
+    }
+
+    #[tokio::test]
+    async fn async_wait_times_out_with_budget() {
+        let budget = Duration::from_millis(20);
+        let released = Notify::new();
+        let result: PoolResult<()> = wait_async(WaitPolicy::Wait(budget), BACKOFF, &released, is_empty, None, || {
+            Err(PoolError::PoolEmpty)
+        })
+        .await;
+        assert!(matches!(result, Err(PoolError::Timeout(d, _)) if d == budget));
+    }
+
+    #[tokio::test]
+    async fn async_wait_retries_on_release_not_on_timer() {
+        let slow = Backoff {
+            initial: Duration::from_secs(10),
+            max: Duration::from_secs(10),
+        };
+        let released = Notify::new();
+        let mut calls = 0;
+
+        let waiter = wait_async(WaitPolicy::Wait(Duration::from_secs(5)), slow, &released, is_empty, None, || {
+            calls += 1;
+            if calls < 2 { Err(PoolError::PoolEmpty) } else { Ok(calls) }
+        });
+        let release = async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            released.notify_waiters();
+        };
+
+        let start = Instant::now();
+        let (result, ()) = tokio::join!(waiter, release);
+        assert_eq!(result.unwrap(), 2);
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+}
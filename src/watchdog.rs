@@ -0,0 +1,116 @@
+//! Detection of acquisitions starved by objects that are never returned
+
+use crate::concurrent::Map;
+use crate::events::PoolWarning;
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Watches a pool's waiting acquisitions for a stall: waiters blocked past
+/// a threshold while every object is checked out and none comes back.
+pub(crate) struct StarvationWatchdog {
+    threshold: Duration,
+    waiters: Map<u64, Instant>,
+    next_token: AtomicU64,
+    last_release: Mutex<Instant>,
+    /// Set once a stall has been reported; cleared by the next release so
+    /// each stall is reported once.
+    reported: AtomicBool,
+}
+
+/// One waiting acquisition; stops being watched on drop.
+pub(crate) struct Waiter<'a> {
+    watchdog: &'a StarvationWatchdog,
+    token: u64,
+}
+
+impl StarvationWatchdog {
+    pub fn new(threshold: Duration) -> Self {
+        Self {
+            threshold,
+            waiters: Map::new(),
+            next_token: AtomicU64::new(0),
+            last_release: Mutex::new(Instant::now()),
+            reported: AtomicBool::new(false),
+        }
+    }
+
+    pub fn enter(&self) -> Waiter<'_> {
+        let token = self.next_token.fetch_add(1, Ordering::Relaxed);
+        self.waiters.insert(token, Instant::now());
+        Waiter { watchdog: self, token }
+    }
+
+    /// An object came back to (or left) the pool.
+    pub fn record_release(&self) {
+        *self.last_release.lock().unwrap_or_else(|p| p.into_inner()) = Instant::now();
+        self.reported.store(false, Ordering::Relaxed);
+    }
+
+    /// The warning to publish, if the pool is `saturated` (every object
+    /// checked out) and has been stalled for longer than the threshold.
+    pub fn check(&self, saturated: bool) -> Option<PoolWarning> {
+        if !saturated || self.reported.load(Ordering::Relaxed) {
+            return None;
+        }
+        let now = Instant::now();
+        let since_last_return =
+            now.saturating_duration_since(*self.last_release.lock().unwrap_or_else(|p| p.into_inner()));
+        if since_last_return < self.threshold {
+            return None;
+        }
+        let oldest = self.waiters.collect(|_, since| *since).into_iter().min()?;
+        let longest_wait = now.saturating_duration_since(oldest);
+        if longest_wait < self.threshold || self.reported.swap(true, Ordering::Relaxed) {
+            return None;
+        }
+        Some(PoolWarning::Starvation {
+            waiters: self.waiters.len(),
+            longest_wait,
+            since_last_return,
+        })
+    }
+}
+
+impl Drop for Waiter<'_> {
+    fn drop(&mut self) {
+        self.watchdog.waiters.remove(&self.token);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THRESHOLD: Duration = Duration::from_millis(10);
+
+    #[test]
+    fn stall_is_reported_once_until_a_release() {
+        let watchdog = StarvationWatchdog::new(THRESHOLD);
+        let _waiter = watchdog.enter();
+        assert!(watchdog.check(true).is_none(), "threshold not reached yet");
+
+        std::thread::sleep(THRESHOLD * 2);
+        assert!(matches!(watchdog.check(true), Some(PoolWarning::Starvation { waiters: 1, .. })));
+        assert!(watchdog.check(true).is_none(), "already reported");
+
+        watchdog.record_release();
+        assert!(watchdog.check(true).is_none(), "a return just happened");
+        std::thread::sleep(THRESHOLD * 2);
+        assert!(watchdog.check(true).is_some());
+    }
+
+    #[test]
+    fn no_report_without_saturation_or_waiters() {
+        let watchdog = StarvationWatchdog::new(THRESHOLD);
+        std::thread::sleep(THRESHOLD * 2);
+        assert!(watchdog.check(true).is_none(), "nobody waiting");
+
+        let waiter = watchdog.enter();
+        std::thread::sleep(THRESHOLD * 2);
+        assert!(watchdog.check(false).is_none(), "idle objects exist");
+        drop(waiter);
+        assert_eq!(watchdog.waiters.len(), 0);
+    }
+}
@@ -0,0 +1,311 @@
+//! Pooled worker threads running submitted jobs
+
+use crate::config::PoolConfiguration;
+use crate::errors::PoolResult;
+use crate::metrics::PoolMetrics;
+use crate::pool::{DynamicObjectPool, PooledObject};
+
+use crossbeam::channel::{self, Sender};
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::thread;
+use tokio::sync::oneshot;
+
+/// Runs a job and returns what hands its result over, which the worker
+/// calls only after going back to the pool.
+type Task = Box<dyn FnOnce() -> Delivery + Send>;
+type Delivery = Box<dyn FnOnce() + Send>;
+
+/// A long-lived worker thread pooled by a [`WorkerPool`]
+///
+/// Opaque; it appears only as the object type of the pool's
+/// [`PoolConfiguration`]. The thread exits once the worker is destroyed.
+pub struct Worker {
+    /// Each job travels with the guard of the worker running it, so the
+    /// worker goes back to the pool as soon as the job is done.
+    jobs: Sender<(Task, PooledObject<Worker>)>,
+}
+
+impl Worker {
+    fn spawn() -> std::io::Result<Self> {
+        let (jobs, queue) = channel::unbounded::<(Task, PooledObject<Worker>)>();
+        thread::Builder::new()
+            .name("objectpool-worker".into())
+            .spawn(move || {
+                for (task, worker) in queue {
+                    let deliver = task();
+                    drop(worker);
+                    deliver();
+                }
+            })?;
+        Ok(Self { jobs })
+    }
+}
+
+/// A pool of worker threads for CPU-bound jobs
+///
+/// Each [`submit`](Self::submit) acquires an idle worker and hands it the
+/// job; the worker returns to the pool when the job finishes. `min` workers
+/// are started up front, more are started on demand up to the
+/// configuration's `max_pool_size`, and once all are busy the
+/// configuration's [`WaitPolicy`](crate::WaitPolicy) decides whether
+/// `submit` waits or fails with `PoolError::PoolFull`. With an
+/// [idle timeout](PoolConfiguration::with_idle_timeout),
+/// [`trim`](Self::trim) retires workers that sat idle too long while
+/// keeping `min` alive.
+///
+/// A panicking job does not take its worker down; the panic is handed to
+/// whoever waits on the job's [`JobHandle`].
+///
+/// # Examples
+///
+/// ```
+/// use esox_objectpool::{PoolConfiguration, WorkerPool};
+///
+/// let workers = WorkerPool::new(2, PoolConfiguration::new().with_max_pool_size(4));
+///
+/// let sum = workers.submit(|| (1..=100u64).sum::<u64>()).unwrap();
+/// assert_eq!(sum.join().unwrap(), 5050);
+/// assert_eq!(workers.worker_count(), 2);
+/// ```
+pub struct WorkerPool {
+    pool: DynamicObjectPool<Worker>,
+    min: usize,
+}
+
+impl WorkerPool {
+    /// Start `min` workers; `config` sets the maximum, waiting and the rest
+    ///
+    /// # Panics
+    ///
+    /// Panics if the initial workers cannot be started.
+    pub fn new(min: usize, config: PoolConfiguration<Worker>) -> Self {
+        let pool = DynamicObjectPool::try_new(Worker::spawn, config);
+        pool.warmup(min).expect("failed to start the initial workers");
+        Self { pool, min }
+    }
+
+    /// Run `job` on a worker, starting one if none is idle
+    ///
+    /// Fails like [`DynamicObjectPool::get_object`] when no worker can be
+    /// had, for example with `PoolError::CreationFailed` if a thread could
+    /// not be spawned.
+    pub fn submit<F, R>(&self, job: F) -> PoolResult<JobHandle<R>>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        Ok(dispatch(self.pool.get_object()?, job))
+    }
+
+    /// Run `job` on a worker, waiting asynchronously for one to be free
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use esox_objectpool::{PoolConfiguration, WorkerPool};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let workers = WorkerPool::new(1, PoolConfiguration::default());
+    /// let job = workers.submit_async(|| "done").await.unwrap();
+    /// assert_eq!(job.await.unwrap(), "done");
+    /// # }
+    /// ```
+    pub async fn submit_async<F, R>(&self, job: F) -> PoolResult<JobHandle<R>>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        Ok(dispatch(self.pool.get_object_async().await?, job))
+    }
+
+    /// Retire workers idle past the idle timeout, then start workers again
+    /// until at least `min` exist; returns how many were retired
+    pub fn trim(&self) -> usize {
+        let retired = self.pool.evict_expired();
+        let missing = self.min.saturating_sub(self.worker_count());
+        // A failed start leaves the pool below `min` until the next trim.
+        let _ = self.pool.warmup(missing);
+        retired
+    }
+
+    /// Workers currently started, busy or idle
+    #[must_use]
+    pub fn worker_count(&self) -> usize {
+        self.pool.available_count() + self.pool.active_count()
+    }
+
+    /// Workers currently running a job
+    #[must_use]
+    pub fn busy_count(&self) -> usize {
+        self.pool.active_count()
+    }
+
+    /// Stop taking jobs; idle workers exit now, busy ones after their job.
+    /// See [`ObjectPool::shutdown`](crate::ObjectPool::shutdown).
+    pub fn shutdown(&self) -> usize {
+        self.pool.shutdown()
+    }
+
+    /// Metrics of the underlying pool
+    #[must_use]
+    pub fn get_metrics(&self) -> PoolMetrics {
+        self.pool.get_metrics()
+    }
+
+    /// The pool holding the workers
+    #[must_use]
+    pub fn pool(&self) -> &DynamicObjectPool<Worker> {
+        &self.pool
+    }
+}
+
+fn dispatch<F, R>(worker: PooledObject<Worker>, job: F) -> JobHandle<R>
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    let (done, result) = oneshot::channel();
+    let task: Task = Box::new(move || {
+        let outcome = panic::catch_unwind(AssertUnwindSafe(job));
+        Box::new(move || {
+            // Nobody waiting for the result is fine.
+            let _ = done.send(outcome);
+        })
+    });
+    let jobs = worker.jobs.clone();
+    // The worker's thread only stops once the worker is destroyed, so the
+    // send cannot fail while we hold it.
+    let _ = jobs.send((task, worker));
+    JobHandle { result }
+}
+
+/// The result of a job submitted to a [`WorkerPool`]
+///
+/// Await it, or [`join`](Self::join) it from synchronous code. Either way
+/// the result is `Err` with the panic payload if the job panicked, as with
+/// [`std::thread::JoinHandle::join`].
+pub struct JobHandle<R> {
+    result: oneshot::Receiver<thread::Result<R>>,
+}
+
+impl<R> JobHandle<R> {
+    /// Block until the job has run
+    ///
+    /// # Panics
+    ///
+    /// Panics when called from within an async runtime; await the handle
+    /// there instead.
+    pub fn join(self) -> thread::Result<R> {
+        self.result.blocking_recv().unwrap_or_else(|_| Err(Box::new("worker stopped")))
+    }
+
+    /// Whether the job has finished
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        !self.result.is_empty() || self.result.is_terminated()
+    }
+}
+
+impl<R> Future for JobHandle<R> {
+    type Output = thread::Result<R>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.result)
+            .poll(cx)
+            .map(|result| result.unwrap_or_else(|_| Err(Box::new("worker stopped"))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::PoolError;
+    use crate::WaitPolicy;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    fn workers(min: usize, max: usize) -> WorkerPool {
+        WorkerPool::new(
+            min,
+            PoolConfiguration::new()
+                .with_max_pool_size(max)
+                .with_wait_on_empty(WaitPolicy::FailFast),
+        )
+    }
+
+    #[test]
+    fn scales_up_to_max_then_refuses() {
+        let workers = workers(0, 2);
+        let (release, gate) = mpsc::channel::<()>();
+        let gate = std::sync::Arc::new(std::sync::Mutex::new(gate));
+
+        let held: Vec<_> = (0..2)
+            .map(|_| {
+                let gate = std::sync::Arc::clone(&gate);
+                workers.submit(move || gate.lock().unwrap().recv().unwrap()).unwrap()
+            })
+            .collect();
+        assert_eq!(workers.busy_count(), 2);
+        assert!(matches!(workers.submit(|| ()), Err(PoolError::PoolFull)));
+
+        for _ in 0..2 {
+            release.send(()).unwrap();
+        }
+        for job in held {
+            job.join().unwrap();
+        }
+        assert_eq!(workers.worker_count(), 2);
+    }
+
+    #[test]
+    fn workers_are_reused() {
+        let workers = workers(1, 4);
+        let first = workers.submit(|| thread::current().id()).unwrap().join().unwrap();
+        let second = workers.submit(|| thread::current().id()).unwrap().join().unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(workers.worker_count(), 1);
+    }
+
+    #[test]
+    fn panicking_job_keeps_its_worker() {
+        let workers = workers(1, 1);
+        let job = workers.submit(|| panic!("bad input")).unwrap();
+        assert_eq!(*job.join().unwrap_err().downcast::<&str>().unwrap(), "bad input");
+
+        assert_eq!(workers.submit(|| 7).unwrap().join().unwrap(), 7);
+        assert_eq!(workers.worker_count(), 1);
+    }
+
+    #[test]
+    fn trim_keeps_the_minimum() {
+        let workers = WorkerPool::new(
+            1,
+            PoolConfiguration::new()
+                .with_max_pool_size(3)
+                .with_idle_timeout(Duration::from_millis(20)),
+        );
+        workers.pool().warmup(3).unwrap();
+        thread::sleep(Duration::from_millis(40));
+
+        assert_eq!(workers.trim(), 3);
+        assert_eq!(workers.worker_count(), 1);
+    }
+
+    #[test]
+    fn shutdown_lets_running_jobs_finish() {
+        let workers = workers(1, 1);
+        let (release, gate) = mpsc::channel::<()>();
+        let job = workers.submit(move || gate.recv().unwrap()).unwrap();
+
+        assert_eq!(workers.shutdown(), 0);
+        assert!(matches!(workers.submit(|| ()), Err(PoolError::PoolClosed)));
+        release.send(()).unwrap();
+        job.join().unwrap();
+        assert_eq!(workers.worker_count(), 0);
+    }
+}